@@ -0,0 +1,106 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::serde_bytes::Bytes;
+use bincode_core::{deserialize, serialize, BufferWriter, ByteArray, DefaultOptions};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize)]
+struct Sample {
+    timestamp: u64,
+    channel: u8,
+    value: i32,
+    valid: bool,
+}
+
+fn encode<T: Serialize + ?Sized, O: Options>(value: &T, options: O) -> Vec<u8> {
+    let mut buffer = vec![0u8; 1 << 20];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, options).unwrap();
+    let len = writer.written_len();
+    buffer.truncate(len);
+    buffer
+}
+
+/// Bytes read one `u8` at a time, compared to a single read of the whole range.
+fn bytes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytes");
+    let data: Vec<u8> = (0..4096u32).map(|i| i as u8).collect();
+    group.throughput(Throughput::Bytes(data.len() as u64));
+
+    let scalar = encode(&data, DefaultOptions::new());
+    group.bench_function("scalar", |b| {
+        b.iter(|| deserialize::<Vec<u8>, _, _>(black_box(&scalar[..]), DefaultOptions::new()))
+    });
+
+    let bulk = encode(Bytes::new(&data), DefaultOptions::new());
+    group.bench_function("bulk", |b| {
+        b.iter(|| deserialize::<&[u8], _, _>(black_box(&bulk[..]), DefaultOptions::new()))
+    });
+
+    let mut array = ByteArray([0u8; 4096]);
+    array.copy_from_slice(&data);
+    let fixed = encode(&array, DefaultOptions::new());
+    group.bench_function("byte_array", |b| {
+        b.iter(|| {
+            deserialize::<ByteArray<4096>, _, _>(black_box(&fixed[..]), DefaultOptions::new())
+        })
+    });
+    group.finish();
+}
+
+fn strings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("str");
+    for len in [16usize, 256, 4096] {
+        let text: String = "telemetry ".chars().cycle().take(len).collect();
+        let bytes = encode(text.as_str(), DefaultOptions::new());
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &bytes, |b, bytes| {
+            b.iter(|| deserialize::<&str, _, _>(black_box(&bytes[..]), DefaultOptions::new()))
+        });
+    }
+    group.finish();
+}
+
+fn integers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("u32");
+    let values: Vec<u32> = (0..1024u32)
+        .map(|i| i.wrapping_mul(2_654_435_761))
+        .collect();
+    group.throughput(Throughput::Elements(values.len() as u64));
+
+    let varint = encode(&values, DefaultOptions::new());
+    group.bench_function("varint", |b| {
+        b.iter(|| deserialize::<Vec<u32>, _, _>(black_box(&varint[..]), DefaultOptions::new()))
+    });
+
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let fixint = encode(&values, options);
+    group.bench_function("fixint", |b| {
+        b.iter(|| deserialize::<Vec<u32>, _, _>(black_box(&fixint[..]), options))
+    });
+    group.finish();
+}
+
+fn structs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("struct");
+    let samples: Vec<Sample> = (0..256u64)
+        .map(|i| Sample {
+            timestamp: 1_700_000_000_000 + i * 10,
+            channel: (i % 8) as u8,
+            value: (i as i32 - 128) * 1000,
+            valid: i % 3 != 0,
+        })
+        .collect();
+    group.throughput(Throughput::Elements(samples.len() as u64));
+    let bytes = encode(&samples, DefaultOptions::new());
+    group.bench_function("samples", |b| {
+        b.iter(|| deserialize::<Vec<Sample>, _, _>(black_box(&bytes[..]), DefaultOptions::new()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bytes, strings, integers, structs);
+criterion_main!(benches);
@@ -0,0 +1 @@
+//! Benchmarks for bincode_core, see `benches/`.
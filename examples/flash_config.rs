@@ -0,0 +1,79 @@
+//! Storing the device configuration in a flash page, and reading it in place at boot.
+//!
+//! Every time the configuration changes, a new record is appended to the page, at the next offset
+//! that the flash controller can program (8 bytes here, like the double words of an STM32L4).
+//! At boot, the last record before the erased part of the page is the current configuration. The
+//! strings in it point into the flash, so nothing is copied into RAM.
+//!
+//! On a device, the page would be e.g. `&'static [u8]` from a `#[link_section]` static, and
+//! `program` would go through the flash controller. Here the page is leaked from a `Vec`, so the
+//! example runs anywhere:
+//!
+//! ```text
+//! cargo run --example flash_config
+//! ```
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{serialize, BufferWriter, DefaultOptions, Deserializer, FlashSlice};
+use serde::Deserialize;
+
+const PAGE_SIZE: usize = 256;
+const PROGRAM_SIZE: usize = 8;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Config<'a> {
+    revision: u8,
+    name: &'a str,
+    sample_interval_ms: u32,
+    thresholds: [i16; 3],
+}
+
+/// Appends `config` to the page at the first free offset, like programming the flash.
+fn program(page: &mut [u8], config: &Config) -> usize {
+    let offset = latest(page).map_or(0, |(_, end)| FlashSlice::align_up(end, PROGRAM_SIZE));
+    let mut writer = BufferWriter::new(&mut page[offset..]);
+    serialize(config, &mut writer, DefaultOptions::new()).unwrap();
+    offset
+}
+
+/// The last record in the page, and the offset where it ends.
+fn latest(page: &[u8]) -> Option<(Config<'_>, usize)> {
+    let mut offset = 0;
+    let mut latest = None;
+    while let Some(flash) = FlashSlice::at(page, offset) {
+        if flash.is_erased() {
+            break;
+        }
+        let mut deserializer = Deserializer::new(flash, DefaultOptions::new());
+        let config = Config::deserialize(&mut deserializer).ok()?;
+        let end = deserializer.reader().position();
+        latest = Some((config, end));
+        offset = FlashSlice::align_up(end, PROGRAM_SIZE);
+    }
+    latest
+}
+
+fn main() {
+    let mut page = vec![FlashSlice::ERASED_BYTE; PAGE_SIZE];
+    for (revision, interval) in [(1, 1000), (2, 500), (3, 250)] {
+        let config = Config {
+            revision,
+            name: "pump-station",
+            sample_interval_ms: interval,
+            thresholds: [-40, 85, 105],
+        };
+        let offset = program(&mut page, &config);
+        println!("Programmed revision {} at offset {}", revision, offset);
+    }
+
+    // After a reset, the page is read in place
+    let flash: &'static [u8] = Box::leak(page.into_boxed_slice());
+    let (config, end) = latest(flash).expect("no configuration in flash");
+    println!("Booting with {:?}", config);
+    assert_eq!(config.revision, 3);
+    assert_eq!(config.sample_interval_ms, 250);
+    assert!(flash.as_ptr_range().contains(&config.name.as_ptr()));
+    println!("{} of {} bytes used", end, PAGE_SIZE);
+}
@@ -0,0 +1,102 @@
+//! Checks that serializing and deserializing a typical message has no panic path.
+//!
+//! Every codec call is wrapped in a guard whose destructor references a symbol that does not
+//! exist. The destructor only runs while unwinding, so if the optimizer can prove that the call
+//! never panics, the reference is removed. Otherwise linking fails, with the name of the symbol in
+//! the error:
+//!
+//! ```text
+//! cargo build --example no_panic --features panic-check --profile panic-check
+//! ```
+//!
+//! In builds with debug assertions the guard is disabled, so the example can be built and run by
+//! `cargo test`.
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+    SerializeError,
+};
+use core::hint::black_box;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Command<'a> {
+    sequence: u32,
+    setpoint: i16,
+    mode: Mode,
+    armed: bool,
+    limit: Option<u16>,
+    key: [u8; 4],
+    label: &'a str,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Mode {
+    Idle,
+    Hover { altitude: u16 },
+    Land,
+}
+
+struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        #[cfg(not(debug_assertions))]
+        {
+            extern "C" {
+                #[link_name = "\n\nERROR: a bincode_core codec call in examples/no_panic.rs can panic\n\n"]
+                fn codec_can_panic() -> !;
+            }
+            unsafe { codec_can_panic() }
+        }
+    }
+}
+
+/// Calls `f`, and fails to link if `f` can panic.
+fn no_panic<T>(f: impl FnOnce() -> T) -> T {
+    let guard = Guard;
+    let result = f();
+    core::mem::forget(guard);
+    result
+}
+
+#[inline(never)]
+fn encode(command: &Command, buffer: &mut [u8]) -> Result<usize, SerializeError<()>> {
+    no_panic(|| {
+        let options = DefaultOptions::new();
+        let len = serialize_size(command, options)?;
+        let mut writer = BufferWriter::new(buffer);
+        serialize(command, &mut writer, options).map_err(|_| SerializeError::Write(()))?;
+        let fixint = DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_big_endian();
+        serialize(command, BufferWriter::new(&mut [0u8; 64]), fixint)
+            .map_err(|_| SerializeError::Write(()))?;
+        Ok(len)
+    })
+}
+
+#[inline(never)]
+fn decode<'a>(bytes: &'a [u8]) -> Result<Command<'a>, DeserializeError<'a, &'a [u8]>> {
+    no_panic(|| deserialize(bytes, DefaultOptions::new().with_hardened_decoding()))
+}
+
+fn main() {
+    let command = Command {
+        sequence: black_box(70_000),
+        setpoint: black_box(-300),
+        mode: Mode::Hover { altitude: 120 },
+        armed: black_box(true),
+        limit: black_box(Some(1000)),
+        key: black_box(*b"\x01\x02\x03\x04"),
+        label: black_box("hover"),
+    };
+    let mut buffer = [0u8; 64];
+    let len = encode(&command, &mut buffer).unwrap();
+    let decoded = decode(black_box(&buffer[..len])).unwrap();
+    assert_eq!(decoded, command);
+    println!("{:?} round-trips in {} bytes", decoded, len);
+}
@@ -0,0 +1,128 @@
+//! A small MCU telemetry protocol over smoltcp.
+//!
+//! A "device" sends fixed-size telemetry frames over TCP, and a "host" decodes them straight from
+//! the socket receive buffer. Both ends run on a loopback interface so the example runs anywhere:
+//!
+//! ```text
+//! cargo run --example smoltcp_telemetry --features smoltcp
+//! ```
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, DefaultOptions};
+use smoltcp::iface::{Config, Interface, SocketSet};
+use smoltcp::phy::{Loopback, Medium};
+use smoltcp::socket::tcp;
+use smoltcp::time::{Duration, Instant};
+use smoltcp::wire::{HardwareAddress, IpAddress, IpCidr};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct Telemetry {
+    uptime_ms: u32,
+    temperature: i16,
+    battery_mv: u16,
+    status: Status,
+    fault_code: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+enum Status {
+    Ok,
+    LowBattery,
+    SensorFault,
+}
+
+const PORT: u16 = 4000;
+
+/// Fixed-width integers and u8 discriminants give every frame the same size, so the host knows
+/// when a complete frame is in the receive buffer.
+fn options() -> impl Options {
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_u8_discriminants()
+}
+
+fn main() {
+    let frames = [
+        Telemetry {
+            uptime_ms: 1_000,
+            temperature: 215,
+            battery_mv: 3_900,
+            status: Status::Ok,
+            fault_code: 0,
+        },
+        Telemetry {
+            uptime_ms: 2_000,
+            temperature: 217,
+            battery_mv: 3_300,
+            status: Status::LowBattery,
+            fault_code: 0,
+        },
+        Telemetry {
+            uptime_ms: 3_000,
+            temperature: -40,
+            battery_mv: 3_250,
+            status: Status::SensorFault,
+            fault_code: 2,
+        },
+    ];
+    let frame_len = serialize_size(&frames[0], options()).unwrap();
+
+    let mut device = Loopback::new(Medium::Ip);
+    let mut now = Instant::from_millis(0);
+    let mut iface = Interface::new(Config::new(HardwareAddress::Ip), &mut device, now);
+    iface.update_ip_addrs(|addrs| {
+        addrs
+            .push(IpCidr::new(IpAddress::v4(127, 0, 0, 1), 8))
+            .unwrap();
+    });
+
+    let host = tcp::Socket::new(
+        tcp::SocketBuffer::new(vec![0; 256]),
+        tcp::SocketBuffer::new(vec![0; 256]),
+    );
+    let mcu = tcp::Socket::new(
+        tcp::SocketBuffer::new(vec![0; 256]),
+        tcp::SocketBuffer::new(vec![0; 256]),
+    );
+    let mut sockets = SocketSet::new(vec![]);
+    let host = sockets.add(host);
+    let mcu = sockets.add(mcu);
+
+    sockets.get_mut::<tcp::Socket>(host).listen(PORT).unwrap();
+    sockets
+        .get_mut::<tcp::Socket>(mcu)
+        .connect(iface.context(), (IpAddress::v4(127, 0, 0, 1), PORT), 50_000)
+        .unwrap();
+
+    let mut sent = 0;
+    let mut received = 0;
+    while received < frames.len() && now < Instant::from_millis(10_000) {
+        iface.poll(now, &mut device, &mut sockets);
+
+        // Device side: serialize straight into the socket's send buffer
+        let socket = sockets.get_mut::<tcp::Socket>(mcu);
+        if sent < frames.len() && socket.can_send() {
+            serialize(&frames[sent], socket, options()).unwrap();
+            println!("device: sent {:?}", frames[sent]);
+            sent += 1;
+        }
+
+        // Host side: deserialize straight from the socket's receive buffer
+        let socket = sockets.get_mut::<tcp::Socket>(host);
+        while socket.recv_queue() >= frame_len {
+            let frame: Telemetry = deserialize(&mut *socket, options()).unwrap();
+            println!("host: received {:?}", frame);
+            assert_eq!(frames[received], frame);
+            received += 1;
+        }
+
+        now += iface
+            .poll_delay(now, &sockets)
+            .unwrap_or(Duration::from_millis(1))
+            .max(Duration::from_millis(1));
+    }
+    assert_eq!(received, frames.len());
+}
@@ -0,0 +1,106 @@
+//! Sending telemetry to the host over a USB CDC-ACM serial port.
+//!
+//! On a device, `port` would be a `usbd_serial::SerialPort`, which implements
+//! `embedded_hal::serial::Write<u8>`, and `FakeUsb::poll` would be `usb_dev.poll(&mut [port])`.
+//! The fake port here accepts one 64 byte packet between polls, like a full speed bulk endpoint,
+//! so the example runs anywhere:
+//!
+//! ```text
+//! cargo run --example usb_serial --features embedded-hal
+//! ```
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{deserialize_iter, serialize, serialize_size, DefaultOptions, SerialWriter};
+use embedded_hal::serial;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct Telemetry {
+    tick: u32,
+    temperature: i16,
+    battery_mv: u16,
+}
+
+/// Stands in for a `usbd_serial::SerialPort` and the USB device that polls it.
+struct FakeUsb {
+    endpoint: Vec<u8>,
+    host: Vec<u8>,
+}
+
+impl FakeUsb {
+    /// The host reads the packet in the endpoint buffer.
+    fn poll(&mut self) {
+        self.host.append(&mut self.endpoint);
+    }
+}
+
+impl serial::Write<u8> for FakeUsb {
+    type Error = ();
+
+    fn write(&mut self, word: u8) -> nb::Result<(), ()> {
+        if self.endpoint.len() == 64 {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.endpoint.push(word);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> nb::Result<(), ()> {
+        if self.endpoint.is_empty() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+fn main() {
+    let port = FakeUsb {
+        endpoint: Vec::new(),
+        host: Vec::new(),
+    };
+    let mut ring = [0u8; 256];
+    let mut writer = SerialWriter::new(port, &mut ring);
+    let mut sent = Vec::new();
+    let mut skipped = 0;
+
+    for tick in 0..100 {
+        // Poll the USB device, then keep sending what is left in the ring
+        writer.get_mut().poll();
+        let _ = writer.poll();
+
+        // Burst of samples, more than one packet can carry
+        for sample in 0..16 {
+            let telemetry = Telemetry {
+                tick,
+                temperature: 200 + sample,
+                battery_mv: 3_900 - tick as u16,
+            };
+            // Never start a value that does not fit, so the host never sees half a value
+            if serialize_size(&telemetry, DefaultOptions::new()).unwrap() > writer.available() {
+                skipped += 1;
+                continue;
+            }
+            serialize(&telemetry, &mut writer, DefaultOptions::new()).unwrap();
+            sent.push(telemetry);
+        }
+    }
+
+    // Wait until everything is sent
+    while writer.poll().is_err() {
+        writer.get_mut().poll();
+    }
+    let (port, _) = writer.release();
+
+    let received: Vec<Telemetry> = deserialize_iter(&port.host[..], DefaultOptions::new())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(received, sent);
+    println!(
+        "host received {} values in {} bytes, {} samples were skipped while the ring was full",
+        received.len(),
+        port.host.len(),
+        skipped
+    );
+}
@@ -0,0 +1,14 @@
+//! Deserializes arbitrary bytes into a set of representative types.
+#![no_main]
+
+use bincode_core::testing::fuzz_decode;
+use bincode_core_fuzz::{Command, Empty, Id, Settings, Telemetry};
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    fuzz_decode::<Command>(data);
+    fuzz_decode::<Settings>(data);
+    fuzz_decode::<Telemetry>(data);
+    fuzz_decode::<(Empty, Id)>(data);
+    fuzz_decode::<(i8, i64, i128, f32, f64)>(data);
+    fuzz_decode::<Vec<Option<String>>>(data);
+});
@@ -0,0 +1,12 @@
+//! Serializes and deserializes arbitrary values of a set of representative types.
+#![no_main]
+
+use bincode_core::testing::fuzz_roundtrip;
+use bincode_core_fuzz::{Command, Empty, Id, Telemetry};
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    fuzz_roundtrip::<Command>(data);
+    fuzz_roundtrip::<Telemetry>(data);
+    fuzz_roundtrip::<(Empty, Id)>(data);
+    fuzz_roundtrip::<(i8, i64, i128, char, Vec<Option<String>>)>(data);
+});
@@ -0,0 +1,36 @@
+//! Message types for the fuzz targets, with the shapes that an embedded protocol typically has.
+
+use arbitrary::Arbitrary;
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Arbitrary, Serialize, Deserialize, Debug, PartialEq)]
+pub enum Command {
+    Ping,
+    SetLed { index: u8, on: bool },
+    Write(u32, Vec<u8>),
+    Rename(String),
+    Configure(Settings),
+}
+
+#[derive(Arbitrary, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Settings {
+    pub interval_ms: u32,
+    pub threshold: i16,
+    pub channels: [bool; 4],
+    pub name: Option<String>,
+}
+
+#[derive(Arbitrary, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Telemetry {
+    pub sequence: u64,
+    pub uptime: (u32, u16),
+    pub readings: Vec<i32>,
+    pub errors: Vec<(u8, char)>,
+    pub last_command: Option<Command>,
+}
+
+#[derive(Arbitrary, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Empty;
+
+#[derive(Arbitrary, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Id(pub u128);
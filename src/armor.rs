@@ -0,0 +1,452 @@
+use crate::traits::{CoreRead, CoreWrite};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_PADDING: u8 = b'=';
+
+/// The amount of input bytes that the writers encode into a stack buffer before writing it.
+const CHUNK_LEN: usize = 24;
+
+/// An implementation of [CoreWrite] that writes every byte as two lowercase hex digits, so binary
+/// messages can be sent over channels that only carry text, like the AT commands of a modem or a
+/// log line.
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions, HexWriter};
+/// let mut buffer = [0u8; 8];
+/// let mut writer = HexWriter::new(BufferWriter::new(&mut buffer));
+/// serialize(&(1u8, 0xABCDu16), &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.into_inner().written_buffer(), b"01fbcdab");
+/// ```
+pub struct HexWriter<W: CoreWrite> {
+    writer: W,
+}
+
+impl<W: CoreWrite> HexWriter<W> {
+    /// Create a new writer that writes the hex digits of all bytes to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn hex_digits(val: u8) -> [u8; 2] {
+    [
+        HEX_DIGITS[(val >> 4) as usize],
+        HEX_DIGITS[(val & 0xF) as usize],
+    ]
+}
+
+impl<W: CoreWrite> CoreWrite for HexWriter<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.writer.write_all(&hex_digits(val))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        let mut buffer = [0u8; CHUNK_LEN * 2];
+        for chunk in val.chunks(CHUNK_LEN) {
+            for (digits, byte) in buffer.chunks_exact_mut(2).zip(chunk) {
+                digits.copy_from_slice(&hex_digits(*byte));
+            }
+            self.writer.write_all(&buffer[..chunk.len() * 2])?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for &mut HexWriter<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// An implementation of [CoreWrite] that writes all bytes as standard base64 (RFC 4648, with
+/// `+`, `/` and `=` padding), which is a third smaller than hex.
+///
+/// Base64 encodes groups of 3 bytes, so the last 1 or 2 bytes are kept until more bytes are
+/// written, or the writer is flushed. A flush writes them out as a padded group.
+/// [serialize](crate::serialize) flushes the writer when it is done, so every message ends with a
+/// complete group and can be decoded as soon as it is received. The padding in between messages
+/// is understood by [Base64Reader], but not by every base64 decoder.
+///
+/// ```
+/// # use bincode_core::{serialize, Base64Writer, BufferWriter, DefaultOptions};
+/// let mut buffer = [0u8; 8];
+/// let mut writer = Base64Writer::new(BufferWriter::new(&mut buffer));
+/// serialize(&(1u8, 0xABCDu16), &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.into_inner().written_buffer(), b"AfvNqw==");
+/// ```
+pub struct Base64Writer<W: CoreWrite> {
+    writer: W,
+    pending: [u8; 2],
+    pending_len: usize,
+}
+
+impl<W: CoreWrite> Base64Writer<W> {
+    /// Create a new writer that writes all bytes as base64 to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            pending: [0; 2],
+            pending_len: 0,
+        }
+    }
+
+    /// Return the inner writer. The last 1 or 2 bytes are discarded if they are not written yet,
+    /// call [CoreWrite::flush] first to write them out.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Encodes `group`, which is 1 to 3 bytes, as 4 base64 characters.
+fn base64_group(group: &[u8]) -> [u8; 4] {
+    let byte = |i: usize| group.get(i).copied().unwrap_or(0) as u32;
+    let bits = (byte(0) << 16) | (byte(1) << 8) | byte(2);
+    let mut chars = [BASE64_PADDING; 4];
+    for (i, char) in chars.iter_mut().enumerate().take(group.len() + 1) {
+        *char = BASE64_ALPHABET[((bits >> (18 - 6 * i)) & 0x3F) as usize];
+    }
+    chars
+}
+
+impl<W: CoreWrite> CoreWrite for Base64Writer<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        if self.pending_len > 0 {
+            let chars = base64_group(&self.pending[..self.pending_len]);
+            self.pending_len = 0;
+            self.writer.write_all(&chars)?;
+        }
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, mut val: &[u8]) -> Result<(), Self::Error> {
+        if self.pending_len + val.len() < 3 {
+            self.pending[self.pending_len..self.pending_len + val.len()].copy_from_slice(val);
+            self.pending_len += val.len();
+            return Ok(());
+        }
+        let mut buffer = [0u8; CHUNK_LEN / 3 * 4];
+        let mut buffer_len = 0;
+        if self.pending_len > 0 {
+            let (rest, remaining) = val.split_at(3 - self.pending_len);
+            let mut group = [0u8; 3];
+            group[..self.pending_len].copy_from_slice(&self.pending[..self.pending_len]);
+            group[self.pending_len..].copy_from_slice(rest);
+            buffer[..4].copy_from_slice(&base64_group(&group));
+            buffer_len = 4;
+            self.pending_len = 0;
+            val = remaining;
+        }
+        let mut groups = val.chunks_exact(3);
+        for group in &mut groups {
+            if buffer_len == buffer.len() {
+                self.writer.write_all(&buffer)?;
+                buffer_len = 0;
+            }
+            buffer[buffer_len..buffer_len + 4].copy_from_slice(&base64_group(group));
+            buffer_len += 4;
+        }
+        let rest = groups.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+        self.writer.write_all(&buffer[..buffer_len])
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for &mut Base64Writer<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// An error that is thrown when reading from a [HexReader] or [Base64Reader].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ArmorReadError<E> {
+    /// The inner reader returned an error.
+    Read(E),
+    /// Tried to deserialize a `&str` or `&[u8]`. The decoded bytes are not stored in the inner
+    /// reader, wrap the reader in a [BufferedReader](crate::BufferedReader) to deserialize
+    /// borrowed data.
+    CannotBorrow,
+    /// The input contains a character that is not a hex digit or base64 character, or padding in
+    /// the wrong place.
+    InvalidCharacter(u8),
+}
+
+/// An implementation of [CoreRead] that decodes the output of a [HexWriter]. Both lowercase and
+/// uppercase hex digits are accepted.
+///
+/// Borrowed `&str` and `&[u8]` values cannot be read from the decoded stream. To deserialize
+/// those, wrap this reader in a [BufferedReader](crate::BufferedReader).
+///
+/// ```
+/// # use bincode_core::{deserialize, DefaultOptions, HexReader};
+/// let reader = HexReader::new(&b"01FBCDAB"[..]);
+/// let value: (u8, u16) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, 0xABCD));
+/// ```
+pub struct HexReader<R> {
+    reader: R,
+}
+
+impl<R> HexReader<R> {
+    /// Create a new reader that decodes the hex digits of `reader`.
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+fn hex_value<E>(digit: u8) -> Result<u8, ArmorReadError<E>> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(ArmorReadError::InvalidCharacter(digit)),
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for HexReader<R> {
+    type Error = ArmorReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let mut digits = [0u8; CHUNK_LEN * 2];
+        for chunk in buffer.chunks_mut(CHUNK_LEN) {
+            let digits = &mut digits[..chunk.len() * 2];
+            self.reader.fill(digits).map_err(ArmorReadError::Read)?;
+            for (byte, pair) in chunk.iter_mut().zip(digits.chunks_exact(2)) {
+                *byte = (hex_value(pair[0])? << 4) | hex_value(pair[1])?;
+            }
+        }
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(ArmorReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(ArmorReadError::CannotBorrow)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        self.reader.remaining_len().map(|len| len / 2)
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for &mut HexReader<R> {
+    type Error = ArmorReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+}
+
+/// An implementation of [CoreRead] that decodes the output of a [Base64Writer].
+///
+/// The input is read in groups of 4 characters. A padded group may appear anywhere in the input,
+/// so the output of a `Base64Writer` that was flushed after every message can be read as one
+/// stream.
+///
+/// Borrowed `&str` and `&[u8]` values cannot be read from the decoded stream. To deserialize
+/// those, wrap this reader in a [BufferedReader](crate::BufferedReader).
+///
+/// ```
+/// # use bincode_core::{deserialize, Base64Reader, DefaultOptions};
+/// let reader = Base64Reader::new(&b"AfvNqw=="[..]);
+/// let value: (u8, u16) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, 0xABCD));
+/// ```
+pub struct Base64Reader<R> {
+    reader: R,
+    /// The decoded bytes of the last group that are not read yet, at the end of the array.
+    pending: [u8; 3],
+    pending_len: usize,
+}
+
+impl<R> Base64Reader<R> {
+    /// Create a new reader that decodes the base64 of `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: [0; 3],
+            pending_len: 0,
+        }
+    }
+
+    /// Return the inner reader. The decoded bytes of the last group that are not read yet are
+    /// discarded.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+fn base64_value<E>(char: u8) -> Result<u32, ArmorReadError<E>> {
+    let value = match char {
+        b'A'..=b'Z' => char - b'A',
+        b'a'..=b'z' => char - b'a' + 26,
+        b'0'..=b'9' => char - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => return Err(ArmorReadError::InvalidCharacter(char)),
+    };
+    Ok(value as u32)
+}
+
+impl<'a, R: CoreRead<'a>> Base64Reader<R> {
+    /// Reads and decodes the next group into `pending`.
+    fn read_group(&mut self) -> Result<(), ArmorReadError<R::Error>> {
+        let mut chars = [0u8; 4];
+        self.reader.fill(&mut chars).map_err(ArmorReadError::Read)?;
+        let len = match chars {
+            [_, _, BASE64_PADDING, BASE64_PADDING] => 1,
+            [_, _, _, BASE64_PADDING] => 2,
+            _ => 3,
+        };
+        let mut bits = 0;
+        for char in &chars[..len + 1] {
+            bits = (bits << 6) | base64_value(*char)?;
+        }
+        bits <<= 6 * (3 - len);
+        let bytes = bits.to_be_bytes();
+        self.pending[3 - len..].copy_from_slice(&bytes[1..len + 1]);
+        self.pending_len = len;
+        Ok(())
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for Base64Reader<R> {
+    type Error = ArmorReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buffer {
+            if self.pending_len == 0 {
+                self.read_group()?;
+            }
+            *byte = self.pending[3 - self.pending_len];
+            self.pending_len -= 1;
+        }
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(ArmorReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(ArmorReadError::CannotBorrow)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        // Every group decodes to 3 bytes minus its padding. Only the padding of the last group is
+        // counted, which needs the inner reader to peek at the end of the input
+        let len = self.reader.remaining_len()?;
+        let padding = match len.checked_sub(4) {
+            None => 0,
+            Some(start) => {
+                let last = self.reader.peek(len)?.get(start..)?;
+                last.iter().filter(|&&char| char == BASE64_PADDING).count()
+            }
+        };
+        Some(self.pending_len + (len / 4 * 3).saturating_sub(padding))
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for &mut Base64Reader<R> {
+    type Error = ArmorReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+}
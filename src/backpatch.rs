@@ -0,0 +1,108 @@
+use crate::config::{Options, UsizeEncoding};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreWrite, CoreWriteSeek};
+use core::convert::Infallible;
+use serde::ser::Serializer as _;
+
+/// The encoded length in front of a sequence or frame: at most a type tag and a `u64` length.
+#[derive(Default)]
+pub(crate) struct Prefix {
+    bytes: [u8; 16],
+    len: usize,
+    overflow: bool,
+}
+
+impl Prefix {
+    /// Encodes the type tag and the length of a sequence of `len` items.
+    pub(crate) fn seq<E, O: Options>(options: O, len: usize) -> Result<Self, SerializeError<E>> {
+        Self::encode(options, len, |serializer| {
+            serializer.serialize_seq(Some(len)).map(drop)
+        })
+    }
+
+    /// Encodes a length of `len` bytes.
+    pub(crate) fn len<E, O: Options>(options: O, len: usize) -> Result<Self, SerializeError<E>> {
+        Self::encode(options, len, |serializer| {
+            O::Usize::serialize_len(serializer, len)
+        })
+    }
+
+    fn encode<E, O: Options>(
+        options: O,
+        len: usize,
+        f: impl FnOnce(&mut Serializer<Prefix, O>) -> Result<(), SerializeError<Infallible>>,
+    ) -> Result<Self, SerializeError<E>> {
+        let mut serializer = Serializer::new(Prefix::default(), options);
+        f(&mut serializer).map_err(|e| e.map_write(|e| match e {}))?;
+        let prefix = serializer.into_writer();
+        if prefix.overflow {
+            return Err(SerializeError::LengthTooLarge(len));
+        }
+        Ok(prefix)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+impl CoreWrite for Prefix {
+    type Error = Infallible;
+
+    fn write(&mut self, val: u8) -> Result<(), Infallible> {
+        match self.bytes.get_mut(self.len) {
+            Some(byte) => {
+                *byte = val;
+                self.len += 1;
+            }
+            None => self.overflow = true,
+        }
+        Ok(())
+    }
+}
+
+/// A prefix that was written with a placeholder length, to be overwritten when the length is
+/// known.
+pub(crate) struct Reserved {
+    start: usize,
+    len: usize,
+}
+
+impl Reserved {
+    /// Writes `placeholder` at the current position of `writer`.
+    pub(crate) fn write<W: CoreWriteSeek + ?Sized>(
+        writer: &mut W,
+        placeholder: &Prefix,
+    ) -> Result<Self, SerializeError<W::Error>> {
+        let start = writer.position();
+        writer
+            .write_all(placeholder.as_bytes())
+            .map_err(SerializeError::Write)?;
+        Ok(Reserved {
+            start,
+            len: placeholder.len,
+        })
+    }
+
+    /// The position of the first byte after the prefix.
+    pub(crate) fn end(&self) -> usize {
+        self.start + self.len
+    }
+
+    /// Overwrites the placeholder with `prefix`. If it needs a different amount of bytes, the bytes
+    /// after the placeholder are moved.
+    pub(crate) fn patch<W: CoreWriteSeek + ?Sized>(
+        self,
+        writer: &mut W,
+        prefix: &Prefix,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if prefix.len != self.len {
+            writer
+                .move_tail(self.end(), self.start + prefix.len)
+                .map_err(SerializeError::Write)?;
+        }
+        writer
+            .write_at(self.start, prefix.as_bytes())
+            .map_err(SerializeError::Write)
+    }
+}
@@ -1,4 +1,9 @@
-use crate::traits::CoreWrite;
+use crate::traits::{CoreWrite, CoreWriteSeek};
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
 
 /// An implementation of [CoreWrite]. This buffer writer will write data to a backing `&mut [u8]`.
 pub struct BufferWriter<'a> {
@@ -33,36 +38,308 @@ impl<'a> BufferWriter<'a> {
     pub fn written_buffer(&self) -> &[u8] {
         &self.buffer[..self.index]
     }
+
+    /// Consume the writer and return the backing buffer, together with the bytes count that was
+    /// written to it. Unlike [written_buffer](Self::written_buffer), the returned buffer lives for
+    /// the full lifetime `'a`, so it can be handed to e.g. a DMA transfer.
+    /// ```
+    /// # use bincode_core::{serialize, BufferWriter, DefaultOptions};
+    /// fn encode(buffer: &mut [u8]) -> &mut [u8] {
+    ///     let mut writer = BufferWriter::new(buffer);
+    ///     serialize(&1234u16, &mut writer, DefaultOptions::new()).unwrap();
+    ///     let (buffer, len) = writer.into_written();
+    ///     &mut buffer[..len]
+    /// }
+    ///
+    /// let mut buffer = [0u8; 16];
+    /// assert_eq!(encode(&mut buffer), &[251, 210, 4]);
+    /// ```
+    pub fn into_written(self) -> (&'a mut [u8], usize) {
+        (self.buffer, self.index)
+    }
+
+    /// Split the backing buffer into the bytes that were written, and the part that has not been
+    /// written yet. The second slice can be used as the backing buffer of a new [BufferWriter] for
+    /// the next message, while the first one is being sent.
+    /// ```
+    /// # use bincode_core::{serialize, BufferWriter, DefaultOptions};
+    /// let mut buffer = [0u8; 16];
+    /// let mut writer = BufferWriter::new(&mut buffer);
+    /// serialize(&1u8, &mut writer, DefaultOptions::new()).unwrap();
+    ///
+    /// let (first, rest) = writer.split_written();
+    /// let mut next = BufferWriter::new(rest);
+    /// serialize(&2u8, &mut next, DefaultOptions::new()).unwrap();
+    ///
+    /// assert_eq!(first, &[1]);
+    /// assert_eq!(next.written_buffer(), &[2]);
+    /// ```
+    pub fn split_written(&mut self) -> (&[u8], &mut [u8]) {
+        let (written, rest) = self.buffer.split_at_mut(self.index);
+        (written, rest)
+    }
+
+    fn write_slice(&mut self, val: &[u8]) -> Result<(), BufferWriterError> {
+        let target = self
+            .buffer
+            .get_mut(self.index..)
+            .and_then(|rest| rest.get_mut(..val.len()))
+            .ok_or(BufferWriterError::BufferTooSmall)?;
+        target.copy_from_slice(val);
+        self.index += val.len();
+        Ok(())
+    }
+
+    fn write_byte(&mut self, val: u8) -> Result<(), BufferWriterError> {
+        let target = self
+            .buffer
+            .get_mut(self.index)
+            .ok_or(BufferWriterError::BufferTooSmall)?;
+        *target = val;
+        self.index += 1;
+        Ok(())
+    }
+
+    fn write_slice_at(&mut self, position: usize, val: &[u8]) -> Result<(), BufferWriterError> {
+        let target = self
+            .buffer
+            .get_mut(position..self.index)
+            .and_then(|written| written.get_mut(..val.len()))
+            .ok_or(BufferWriterError::BufferTooSmall)?;
+        target.copy_from_slice(val);
+        Ok(())
+    }
+
+    fn move_slice(&mut self, from: usize, to: usize) -> Result<(), BufferWriterError> {
+        let len = self
+            .index
+            .checked_sub(from)
+            .ok_or(BufferWriterError::BufferTooSmall)?;
+        if to
+            .checked_add(len)
+            .is_none_or(|end| end > self.buffer.len())
+        {
+            return Err(BufferWriterError::BufferTooSmall);
+        }
+        self.buffer.copy_within(from..self.index, to);
+        self.index = to + len;
+        Ok(())
+    }
 }
 
-/// Errors that can be returned from writing to a [BufferWriter].
+/// Errors that can be returned from writing to a [BufferWriter], [FrameBufferWriter] or
+/// [ChainedWriter](crate::ChainedWriter).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BufferWriterError {
-    /// The backing buffer of the [BufferWriter] is too small.
+    /// The backing buffer of the writer is too small.
     BufferTooSmall,
+    /// A [FrameBufferWriter] has no room to record another frame.
+    TooManyFrames,
 }
 
+impl core::fmt::Display for BufferWriterError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            BufferWriterError::BufferTooSmall => write!(fmt, "The buffer is too small"),
+            BufferWriterError::TooManyFrames => write!(fmt, "No room to record another frame"),
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for BufferWriterError {}
+
 impl CoreWrite for &'_ mut BufferWriter<'_> {
     type Error = BufferWriterError;
 
     fn write(&mut self, val: u8) -> Result<(), Self::Error> {
-        if self.index >= self.buffer.len() {
-            return Err(BufferWriterError::BufferTooSmall);
-        }
-        self.buffer[self.index] = val;
-        self.index += 1;
-        Ok(())
+        self.write_byte(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice(val)
+    }
+
+    fn as_seek(&mut self) -> Option<&mut dyn CoreWriteSeek<Error = Self::Error>> {
+        Some(self)
     }
 }
 
 impl CoreWrite for BufferWriter<'_> {
     type Error = BufferWriterError;
     fn write(&mut self, val: u8) -> Result<(), Self::Error> {
-        if self.buffer.is_empty() {
-            return Err(BufferWriterError::BufferTooSmall);
+        self.write_byte(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice(val)
+    }
+
+    fn as_seek(&mut self) -> Option<&mut dyn CoreWriteSeek<Error = Self::Error>> {
+        Some(self)
+    }
+}
+
+impl CoreWriteSeek for &'_ mut BufferWriter<'_> {
+    fn position(&self) -> usize {
+        self.index
+    }
+
+    fn write_at(&mut self, position: usize, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice_at(position, val)
+    }
+
+    fn move_tail(&mut self, from: usize, to: usize) -> Result<(), Self::Error> {
+        self.move_slice(from, to)
+    }
+}
+
+impl CoreWriteSeek for BufferWriter<'_> {
+    fn position(&self) -> usize {
+        self.index
+    }
+
+    fn write_at(&mut self, position: usize, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice_at(position, val)
+    }
+
+    fn move_tail(&mut self, from: usize, to: usize) -> Result<(), Self::Error> {
+        self.move_slice(from, to)
+    }
+}
+
+/// A [BufferWriter] that records where each serialized message ends, so several messages can be
+/// batched into one buffer and sent as separate packets later.
+///
+/// The end offset of every frame is stored in a caller-provided `&mut [usize]`, which limits the
+/// amount of frames that can be recorded.
+///
+/// ```
+/// # use bincode_core::{serialize, DefaultOptions, FrameBufferWriter};
+/// let mut buffer = [0u8; 16];
+/// let mut ends = [0usize; 4];
+/// let mut writer = FrameBufferWriter::new(&mut buffer, &mut ends);
+/// serialize(&1u8, &mut writer, DefaultOptions::new()).unwrap();
+/// writer.mark_frame().unwrap();
+/// serialize("ab", &mut writer, DefaultOptions::new()).unwrap();
+/// writer.mark_frame().unwrap();
+///
+/// let mut frames = writer.frames();
+/// assert_eq!(frames.next(), Some(&[1][..]));
+/// assert_eq!(frames.next(), Some(&[2, b'a', b'b'][..]));
+/// assert_eq!(frames.next(), None);
+/// ```
+pub struct FrameBufferWriter<'a, 'f> {
+    writer: BufferWriter<'a>,
+    ends: &'f mut [usize],
+    frames: usize,
+}
+
+impl<'a, 'f> FrameBufferWriter<'a, 'f> {
+    /// Create a new writer with a backing buffer, that can record up to `ends.len()` frames.
+    pub fn new(buffer: &'a mut [u8], ends: &'f mut [usize]) -> Self {
+        Self {
+            writer: BufferWriter::new(buffer),
+            ends,
+            frames: 0,
         }
-        self.buffer[self.index] = val;
-        self.index += 1;
+    }
+
+    /// End the current frame at the bytes that were written so far. Marking an empty frame is
+    /// allowed, and yields an empty slice from [frames](Self::frames).
+    pub fn mark_frame(&mut self) -> Result<(), BufferWriterError> {
+        let end = self
+            .ends
+            .get_mut(self.frames)
+            .ok_or(BufferWriterError::TooManyFrames)?;
+        *end = self.writer.written_len();
+        self.frames += 1;
         Ok(())
     }
+
+    /// The amount of frames that were marked.
+    pub fn frame_count(&self) -> usize {
+        self.frames
+    }
+
+    /// The bytes count written to the backing buffer, including bytes after the last frame.
+    pub fn written_len(&self) -> usize {
+        self.writer.written_len()
+    }
+
+    /// The bytes that were written after the last marked frame.
+    pub fn pending(&self) -> &[u8] {
+        &self.writer.written_buffer()[self.frames_len()..]
+    }
+
+    /// An iterator over the bytes of every marked frame, in the order they were written.
+    pub fn frames(&self) -> FrameIterator<'_> {
+        FrameIterator {
+            buffer: self.writer.written_buffer(),
+            ends: self.ends[..self.frames].iter(),
+            start: 0,
+        }
+    }
+
+    /// Discard all frames and written bytes, so the buffer can be used for the next batch.
+    pub fn clear(&mut self) {
+        self.writer.index = 0;
+        self.frames = 0;
+    }
+
+    fn frames_len(&self) -> usize {
+        match self.frames {
+            0 => 0,
+            n => self.ends[n - 1],
+        }
+    }
+}
+
+/// Iterator over the frames of a [FrameBufferWriter]. See [FrameBufferWriter::frames].
+pub struct FrameIterator<'b> {
+    buffer: &'b [u8],
+    ends: core::slice::Iter<'b, usize>,
+    start: usize,
+}
+
+impl<'b> Iterator for FrameIterator<'b> {
+    type Item = &'b [u8];
+
+    fn next(&mut self) -> Option<&'b [u8]> {
+        let end = *self.ends.next()?;
+        let frame = &self.buffer[self.start..end];
+        self.start = end;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ends.size_hint()
+    }
+}
+
+impl ExactSizeIterator for FrameIterator<'_> {}
+
+impl CoreWrite for &'_ mut FrameBufferWriter<'_, '_> {
+    type Error = BufferWriterError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.writer.write_byte(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_slice(val)
+    }
+}
+
+impl CoreWrite for FrameBufferWriter<'_, '_> {
+    type Error = BufferWriterError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.writer.write_byte(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_slice(val)
+    }
 }
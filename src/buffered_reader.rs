@@ -0,0 +1,106 @@
+use crate::traits::CoreRead;
+use core::str;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// An implementation of [CoreRead] that allows deserializing `&str` and `&[u8]` from a stream.
+///
+/// Readers like a serial port or an SPI bus can only fill a buffer, they have no persistent
+/// storage to borrow a `&str` or `&[u8]` from. This adapter reads those slices into a
+/// caller-provided scratch buffer instead, and hands out references into that buffer. All other
+/// values are read straight from the inner reader.
+///
+/// The scratch buffer is not reused during a single deserialization, so it has to be large enough
+/// to hold all borrowed slices in the deserialized value.
+///
+/// The reader is consumed by [deserialize](crate::deserialize), so to keep using a stream afterwards,
+/// pass a `&mut` reference to it as the inner reader.
+pub struct BufferedReader<'buf, R> {
+    reader: R,
+    scratch: &'buf mut [u8],
+}
+
+impl<'buf, R> BufferedReader<'buf, R> {
+    /// Create a new reader that reads from `reader`, and stores borrowed slices in `scratch`.
+    pub fn new(reader: R, scratch: &'buf mut [u8]) -> Self {
+        Self { reader, scratch }
+    }
+
+    fn read_scratch(&mut self, len: usize) -> Result<&'buf [u8], BufferedReadError<R::Error>>
+    where
+        R: CoreRead<'buf>,
+    {
+        if len > self.scratch.len() {
+            return Err(BufferedReadError::ScratchTooSmall);
+        }
+        let (result, rest) = core::mem::take(&mut self.scratch).split_at_mut(len);
+        self.scratch = rest;
+        self.reader.fill(result).map_err(BufferedReadError::Read)?;
+        Ok(result)
+    }
+}
+
+/// An error that is thrown when reading from a [BufferedReader].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BufferedReadError<E> {
+    /// The inner reader returned an error.
+    Read(E),
+    /// The scratch buffer does not have enough space left for a `&str` or `&[u8]`.
+    ScratchTooSmall,
+    /// The bytes read for a `&str` are not valid UTF8.
+    InvalidUtf8,
+    /// The visitor rejected the borrowed `&str` or `&[u8]`.
+    Custom,
+}
+
+impl<E: core::fmt::Debug> serde::de::Error for BufferedReadError<E> {
+    fn custom<T: core::fmt::Display>(_cause: T) -> Self {
+        BufferedReadError::Custom
+    }
+}
+
+impl<E: core::fmt::Debug> core::fmt::Display for BufferedReadError<E> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: core::fmt::Debug> StdError for BufferedReadError<E> {}
+
+impl<'buf, R: CoreRead<'buf>> CoreRead<'buf> for BufferedReader<'buf, R> {
+    type Error = BufferedReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.reader.fill(buffer).map_err(BufferedReadError::Read)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        self.reader.remaining_len()
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.reader.peek(len)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        let bytes = self.read_scratch(len)?;
+        let string = str::from_utf8(bytes).map_err(|_| BufferedReadError::InvalidUtf8)?;
+        visitor.visit_borrowed_str(string)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        let bytes = self.read_scratch(len)?;
+        visitor.visit_borrowed_bytes(bytes)
+    }
+}
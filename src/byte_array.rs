@@ -0,0 +1,102 @@
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use serde::de::{Error, SeqAccess, Visitor};
+use serde::ser::SerializeTupleStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The tuple struct name that the deserializer recognizes to read a [ByteArray] in one go.
+pub(crate) const BYTE_ARRAY_NAME: &str = "$bincode_core::ByteArray";
+
+/// A fixed-size byte array that is deserialized with a single read.
+///
+/// Serde deserializes `[u8; N]` one element at a time, which is slow for large arrays. This
+/// wrapper has the same encoding as `[u8; N]` (the raw bytes without a length), but is read from
+/// the [CoreRead](crate::CoreRead) with a single `forward_bytes` call.
+///
+/// Because the bytes are forwarded, readers that cannot borrow (like a serial port) have to be
+/// wrapped in a [BufferedReader](crate::BufferedReader).
+///
+/// Other serde formats see this type as a tuple struct of `N` bytes.
+///
+/// Unlike `serde_bytes::ByteArray`, no length is written. Enable the `serde-bytes` feature for
+/// byte arrays that should be written with a single [CoreWrite::write_all](crate::CoreWrite::write_all)
+/// call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ByteArray<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> Default for ByteArray<N> {
+    fn default() -> Self {
+        ByteArray([0; N])
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ByteArray<N> {
+    fn from(bytes: [u8; N]) -> Self {
+        ByteArray(bytes)
+    }
+}
+
+impl<const N: usize> From<ByteArray<N>> for [u8; N] {
+    fn from(bytes: ByteArray<N>) -> Self {
+        bytes.0
+    }
+}
+
+impl<const N: usize> Deref for ByteArray<N> {
+    type Target = [u8; N];
+
+    fn deref(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for ByteArray<N> {
+    fn deref_mut(&mut self) -> &mut [u8; N] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> Serialize for ByteArray<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple_struct(BYTE_ARRAY_NAME, N)?;
+        for byte in &self.0 {
+            tuple.serialize_field(byte)?;
+        }
+        tuple.end()
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for ByteArray<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple_struct(BYTE_ARRAY_NAME, N, ByteArrayVisitor)
+    }
+}
+
+struct ByteArrayVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for ByteArrayVisitor<N> {
+    type Value = ByteArray<N>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "an array of {} bytes", N)
+    }
+
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        if v.len() != N {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(v);
+        Ok(ByteArray(bytes))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; N];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+        }
+        Ok(ByteArray(bytes))
+    }
+}
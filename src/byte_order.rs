@@ -0,0 +1,98 @@
+use core::ops::{Deref, DerefMut};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A number that is always encoded as big-endian, regardless of the endian in the options.
+///
+/// The value is written as its raw bytes, like a `[u8; N]`, so it also ignores the integer
+/// encoding: a `Be<u32>` is always 4 bytes, even with varint encoding. This is useful for protocols
+/// and register maps that have a single big-endian field in an otherwise little-endian message.
+///
+/// ```
+/// # extern crate serde_derive;
+/// # use serde_derive::{Deserialize, Serialize};
+/// # use bincode_core::{deserialize, serialize, Be, BufferWriter, DefaultOptions};
+/// # use bincode_core::config::Options;
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Header {
+///     version: u16,
+///     length: Be<u16>,
+/// }
+///
+/// let header = Header { version: 1, length: Be(0x0102) };
+/// let mut buffer = [0u8; 4];
+/// let options = DefaultOptions::new().with_fixint_encoding();
+/// serialize(&header, BufferWriter::new(&mut buffer), options).unwrap();
+/// assert_eq!(buffer, [0x01, 0x00, 0x01, 0x02]);
+///
+/// let decoded: Header = deserialize(&buffer[..], options).unwrap();
+/// assert_eq!(decoded, header);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Be<T>(pub T);
+
+/// A number that is always encoded as little-endian, regardless of the endian in the options.
+///
+/// See [Be] for more information.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Le<T>(pub T);
+
+macro_rules! impl_wrapper {
+    ($wrapper:ident) => {
+        impl<T> From<T> for $wrapper<T> {
+            fn from(value: T) -> Self {
+                $wrapper(value)
+            }
+        }
+
+        impl<T> Deref for $wrapper<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $wrapper<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+    };
+}
+
+impl_wrapper!(Be);
+impl_wrapper!(Le);
+
+macro_rules! impl_serde {
+    ($($ty:ty),*) => {
+        $(
+            impl Serialize for Be<$ty> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.0.to_be_bytes().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for Be<$ty> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let bytes = Deserialize::deserialize(deserializer)?;
+                    Ok(Be(<$ty>::from_be_bytes(bytes)))
+                }
+            }
+
+            impl Serialize for Le<$ty> {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.0.to_le_bytes().serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for Le<$ty> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let bytes = Deserialize::deserialize(deserializer)?;
+                    Ok(Le(<$ty>::from_le_bytes(bytes)))
+                }
+            }
+        )*
+    };
+}
+
+impl_serde!(u16, u32, u64, u128, i16, i32, i64, i128, f32, f64);
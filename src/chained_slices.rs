@@ -0,0 +1,163 @@
+use crate::traits::{CoreRead, SliceReadError};
+use core::str;
+
+/// An implementation of [CoreRead] that reads from multiple slices in order.
+///
+/// When a slice is fully read, reading continues in the next slice. This allows deserializing
+/// directly from fragmented (scatter-gather) input, like the RX descriptors of an Ethernet MAC,
+/// without copying it into a single buffer first.
+///
+/// A `&str` or `&[u8]` that lies within a single slice is borrowed directly. One that is split over
+/// multiple slices is copied into a caller-provided scratch buffer, and borrowed from there. The
+/// scratch buffer is not reused during a single deserialization, so it has to be large enough to
+/// hold all split slices in the deserialized value. If the value has no borrowed data, the scratch
+/// buffer can be empty.
+///
+/// ```
+/// # use bincode_core::{deserialize, ChainedSlices, DefaultOptions};
+/// let first = [3, b'a'];
+/// let second = [b'b', b'c', 5];
+/// let segments = [&first[..], &second[..]];
+/// let mut scratch = [0u8; 8];
+/// let reader = ChainedSlices::new(&segments, &mut scratch);
+/// let value: (&str, u8) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, ("abc", 5));
+/// ```
+pub struct ChainedSlices<'a, 'b> {
+    /// The slices after `current`.
+    segments: &'b [&'a [u8]],
+    /// The part of the current slice that is not read yet.
+    current: &'a [u8],
+    scratch: &'a mut [u8],
+}
+
+impl<'a, 'b> ChainedSlices<'a, 'b> {
+    /// Create a new reader for the given slices, that stores split `&str` and `&[u8]` values in
+    /// `scratch`.
+    pub fn new(segments: &'b [&'a [u8]], scratch: &'a mut [u8]) -> Self {
+        Self {
+            segments,
+            current: &[],
+            scratch,
+        }
+    }
+
+    /// Moves on to the next non-empty slice, if the current slice is fully read.
+    fn next_segment(&mut self) {
+        while self.current.is_empty() {
+            match self.segments.split_first() {
+                Some((first, rest)) => {
+                    self.current = first;
+                    self.segments = rest;
+                }
+                None => break,
+            }
+        }
+    }
+
+    fn remaining(&self) -> usize {
+        self.current.len() + self.segments.iter().map(|s| s.len()).sum::<usize>()
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], SliceReadError> {
+        if len > self.remaining() {
+            return Err(SliceReadError::EndOfSlice);
+        }
+        self.next_segment();
+        if len <= self.current.len() {
+            let (result, rest) = self.current.split_at(len);
+            self.current = rest;
+            return Ok(result);
+        }
+        if len > self.scratch.len() {
+            return Err(SliceReadError::ScratchTooSmall);
+        }
+        let (result, rest) = core::mem::take(&mut self.scratch).split_at_mut(len);
+        self.scratch = rest;
+        self.fill(result)?;
+        Ok(result)
+    }
+}
+
+impl<'a> CoreRead<'a> for ChainedSlices<'a, '_> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, mut buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if buffer.len() > self.remaining() {
+            return Err(SliceReadError::EndOfSlice);
+        }
+        while !buffer.is_empty() {
+            self.next_segment();
+            let len = self.current.len().min(buffer.len());
+            let (target, rest) = buffer.split_at_mut(len);
+            target.copy_from_slice(&self.current[..len]);
+            self.current = &self.current[len..];
+            buffer = rest;
+        }
+        Ok(())
+    }
+
+    fn read_range(&mut self, len: usize) -> Result<&'a [u8], Self::Error> {
+        self.read_slice(len)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        let bytes = self.read_slice(len)?;
+        let string = str::from_utf8(bytes).map_err(|_| SliceReadError::InvalidUtf8)?;
+        visitor.visit_borrowed_str(string)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        let bytes = self.read_slice(len)?;
+        visitor.visit_borrowed_bytes(bytes)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.remaining())
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        // Only bytes in the current slice can be returned without copying
+        let current = match self.current {
+            [] => self.segments.iter().find(|s| !s.is_empty())?,
+            current => current,
+        };
+        current.get(..len)
+    }
+}
+
+impl<'a> CoreRead<'a> for &mut ChainedSlices<'a, '_> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        (**self).peek(len)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::buffer_writer::BufferWriterError;
+use crate::traits::CoreWrite;
+
+/// An implementation of [CoreWrite] that writes to multiple backing buffers in order.
+///
+/// When a buffer is full, writing continues in the next buffer. This allows serializing directly
+/// into fragmented (scatter-gather) buffers, like the TX buffers of a network stack, without
+/// copying through an intermediate array.
+///
+/// ```
+/// # use bincode_core::{serialize, ChainedWriter, DefaultOptions};
+/// let mut first = [0u8; 2];
+/// let mut second = [0u8; 4];
+/// let mut segments = [&mut first[..], &mut second[..]];
+/// let mut writer = ChainedWriter::new(&mut segments);
+/// serialize("abc", &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.written_len(), 4);
+///
+/// assert_eq!(first, [3, b'a']);
+/// assert_eq!(second, [b'b', b'c', 0, 0]);
+/// ```
+pub struct ChainedWriter<'a, 'b> {
+    segments: &'b mut [&'a mut [u8]],
+    segment: usize,
+    index: usize,
+    written: usize,
+}
+
+impl<'a, 'b> ChainedWriter<'a, 'b> {
+    /// Create a new writer with the given backing buffers.
+    pub fn new(segments: &'b mut [&'a mut [u8]]) -> Self {
+        Self {
+            segments,
+            segment: 0,
+            index: 0,
+            written: 0,
+        }
+    }
+
+    /// The total amount of bytes written to all backing buffers.
+    pub fn written_len(&self) -> usize {
+        self.written
+    }
+
+    fn remaining_len(&self) -> usize {
+        let total: usize = self.segments.iter().map(|s| s.len()).sum();
+        total - self.written
+    }
+
+    fn write_slice(&mut self, mut val: &[u8]) -> Result<(), BufferWriterError> {
+        if val.len() > self.remaining_len() {
+            return Err(BufferWriterError::BufferTooSmall);
+        }
+        while !val.is_empty() {
+            let segment = &mut self.segments[self.segment][self.index..];
+            let len = segment.len().min(val.len());
+            segment[..len].copy_from_slice(&val[..len]);
+            val = &val[len..];
+            self.index += len;
+            self.written += len;
+            if self.index == self.segments[self.segment].len() {
+                self.segment += 1;
+                self.index = 0;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CoreWrite for ChainedWriter<'_, '_> {
+    type Error = BufferWriterError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_slice(&[val])
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice(val)
+    }
+}
+
+impl CoreWrite for &mut ChainedWriter<'_, '_> {
+    type Error = BufferWriterError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_slice(&[val])
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.write_slice(val)
+    }
+}
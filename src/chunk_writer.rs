@@ -0,0 +1,89 @@
+use crate::traits::CoreWrite;
+
+/// An implementation of [CoreWrite] that combines small writes into larger chunks.
+///
+/// The serializer writes every integer with its own call to [CoreWrite::write_all], so a sequence
+/// of integers turns into many tiny writes. This writer collects those writes in a caller-provided
+/// buffer, and passes the buffer on to the inner writer with a single `write_all` call whenever it
+/// is full, or when the writer is flushed. Writes that are larger than the buffer are passed on
+/// directly.
+///
+/// [serialize](crate::serialize) flushes the writer when it is done. When writing to a
+/// `ChunkWriter` manually, [CoreWrite::flush] or [Serializer::finish](crate::Serializer::finish)
+/// has to be called to write out the last chunk.
+pub struct ChunkWriter<'buf, W: CoreWrite> {
+    writer: W,
+    buffer: &'buf mut [u8],
+    len: usize,
+}
+
+impl<'buf, W: CoreWrite> ChunkWriter<'buf, W> {
+    /// Create a new writer that writes to `writer` in chunks of up to `buffer.len()` bytes.
+    pub fn new(writer: W, buffer: &'buf mut [u8]) -> Self {
+        Self {
+            writer,
+            buffer,
+            len: 0,
+        }
+    }
+
+    /// The amount of bytes that are buffered, and not written to the inner writer yet.
+    pub fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    /// Return the inner writer. Any bytes that are still buffered are discarded, call
+    /// [CoreWrite::flush] first to write them out.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_buffer(&mut self) -> Result<(), W::Error> {
+        if self.len > 0 {
+            self.writer.write_all(&self.buffer[..self.len])?;
+            self.len = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for ChunkWriter<'_, W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.write_buffer()?;
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        if self.len + val.len() > self.buffer.len() {
+            self.write_buffer()?;
+        }
+        if val.len() >= self.buffer.len() {
+            return self.writer.write_all(val);
+        }
+        self.buffer[self.len..self.len + val.len()].copy_from_slice(val);
+        self.len += val.len();
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for &mut ChunkWriter<'_, W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
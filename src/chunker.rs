@@ -0,0 +1,293 @@
+//! Splitting messages into fragments that fit in a single packet.
+//!
+//! Links like BLE and LoRa have a small maximum packet size (MTU), often smaller than a single
+//! message. [serialize_chunked] splits a serialized value into packets of at most the MTU, each
+//! starting with a [FragmentHeader]. On the receiving side, a [Reassembler] collects the fragments
+//! in a fixed-size buffer until the message is complete.
+//!
+//! ```
+//! # extern crate serde_derive;
+//! # use serde_derive::{Deserialize, Serialize};
+//! # use bincode_core::chunker::{serialize_chunked, Reassembler};
+//! # use bincode_core::{deserialize, DefaultOptions};
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Reading {
+//!     sensor: [u8; 12],
+//!     value: u32,
+//! }
+//!
+//! let reading = Reading { sensor: *b"thermocouple", value: 1234 };
+//! let mut buffer = [0u8; 32];
+//! let mut receiver = Reassembler::new(&mut buffer);
+//! let mut received = None;
+//!
+//! // An MTU of 8 bytes leaves 5 bytes for every fragment
+//! let mut packet = [0u8; 8];
+//! serialize_chunked(&reading, 1, &mut packet, DefaultOptions::new(), |packet| {
+//!     if let Some(message) = receiver.push(packet).unwrap() {
+//!         received = Some(deserialize::<Reading, _, _>(message, DefaultOptions::new()).unwrap());
+//!     }
+//!     Ok::<(), ()>(())
+//! })
+//! .unwrap();
+//! assert_eq!(received, Some(reading));
+//! ```
+
+use crate::config::{Bounded, Infinite, Options, SizeLimit, WithOtherLimit};
+use crate::serialize::{serialize, serialize_size, SerializeError};
+use crate::traits::CoreWrite;
+use serde::Serialize;
+
+/// The amount of bytes that the [FragmentHeader] takes at the start of every packet.
+pub const HEADER_LEN: usize = 3;
+
+/// The header in front of every fragment.
+///
+/// The header is encoded as the message id, the index and the total amount of fragments, one byte
+/// each.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FragmentHeader {
+    /// The id of the message that the fragment belongs to.
+    pub message_id: u8,
+    /// The index of the fragment in the message, starting at 0.
+    pub index: u8,
+    /// The amount of fragments in the message.
+    pub total: u8,
+}
+
+impl FragmentHeader {
+    /// Split a packet into its header and payload. Returns `None` if the packet is shorter than
+    /// [HEADER_LEN], or the index is not smaller than the total.
+    pub fn parse(packet: &[u8]) -> Option<(FragmentHeader, &[u8])> {
+        match packet {
+            [message_id, index, total, payload @ ..] if index < total => Some((
+                FragmentHeader {
+                    message_id: *message_id,
+                    index: *index,
+                    total: *total,
+                },
+                payload,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// An error that is thrown by [serialize_chunked].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ChunkError<E> {
+    /// The callback returned an error while sending a packet.
+    Send(E),
+    /// The value could not be serialized.
+    Serialize(SerializeError<()>),
+    /// The packet buffer does not have room for a header and at least one byte of payload.
+    PacketTooSmall,
+    /// The value needs more than 255 fragments.
+    TooManyFragments,
+}
+
+/// Serialize `value` and pass it to `send` in packets of at most `packet.len()` bytes.
+///
+/// The length of `packet` is the MTU of the link. Every packet starts with a [FragmentHeader] with
+/// the given `message_id`, followed by up to `packet.len() - HEADER_LEN` bytes of the value. A
+/// value that serializes to zero bytes is still sent as a single empty fragment. The last packet is
+/// sent once the whole value is written, also when the options use
+/// [with_manual_flush](Options::with_manual_flush).
+///
+/// The size of the value is measured first, so the total amount of fragments is known when the
+/// first packet is sent.
+pub fn serialize_chunked<T, O, F, E>(
+    value: &T,
+    message_id: u8,
+    packet: &mut [u8],
+    mut options: O,
+    send: F,
+) -> Result<(), ChunkError<E>>
+where
+    T: Serialize + ?Sized,
+    O: Options,
+    F: FnMut(&[u8]) -> Result<(), E>,
+    E: core::fmt::Debug,
+{
+    if packet.len() <= HEADER_LEN {
+        return Err(ChunkError::PacketTooSmall);
+    }
+    let size = match options.limit().limit() {
+        Some(limit) => serialize_size(value, WithOtherLimit::new(&mut options, Bounded(limit))),
+        None => serialize_size(value, WithOtherLimit::new(&mut options, Infinite)),
+    }
+    .map_err(ChunkError::Serialize)?;
+
+    let payload_len = packet.len() - HEADER_LEN;
+    let total = size.div_ceil(payload_len);
+    if total > u8::MAX as usize {
+        return Err(ChunkError::TooManyFragments);
+    }
+    let mut writer = FragmentWriter {
+        packet,
+        len: HEADER_LEN,
+        header: FragmentHeader {
+            message_id,
+            index: 0,
+            total: total.max(1) as u8,
+        },
+        send,
+    };
+    // The last packet is sent below, whatever the flush policy of the options is
+    serialize(value, &mut writer, options.with_manual_flush()).map_err(|e| match e {
+        SerializeError::Write(e) => ChunkError::Send(e),
+        e => ChunkError::Serialize(e.map_write(|_| ())),
+    })?;
+    writer.send_last().map_err(ChunkError::Send)
+}
+
+/// Writes the serialized value into `packet` after the header, and sends the packet when it is
+/// full.
+struct FragmentWriter<'p, F> {
+    packet: &'p mut [u8],
+    len: usize,
+    header: FragmentHeader,
+    send: F,
+}
+
+impl<F, E> FragmentWriter<'_, F>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+{
+    fn send_packet(&mut self) -> Result<(), E> {
+        let header = &mut self.header;
+        self.packet[..HEADER_LEN].copy_from_slice(&[header.message_id, header.index, header.total]);
+        header.index = header.index.wrapping_add(1);
+        let len = core::mem::replace(&mut self.len, HEADER_LEN);
+        (self.send)(&self.packet[..len])
+    }
+
+    /// Sends the partially filled last packet.
+    fn send_last(&mut self) -> Result<(), E> {
+        // A value without any bytes is still sent as one fragment
+        if self.len > HEADER_LEN || self.header.index == 0 {
+            self.send_packet()?;
+        }
+        Ok(())
+    }
+}
+
+impl<F, E> CoreWrite for FragmentWriter<'_, F>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn write_all(&mut self, mut val: &[u8]) -> Result<(), Self::Error> {
+        while !val.is_empty() {
+            if self.len == self.packet.len() {
+                self.send_packet()?;
+            }
+            let len = val.len().min(self.packet.len() - self.len);
+            self.packet[self.len..self.len + len].copy_from_slice(&val[..len]);
+            self.len += len;
+            val = &val[len..];
+        }
+        Ok(())
+    }
+}
+
+impl<F, E> CoreWrite for &mut FragmentWriter<'_, F>
+where
+    F: FnMut(&[u8]) -> Result<(), E>,
+    E: core::fmt::Debug,
+{
+    type Error = E;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// An error that is thrown by [Reassembler::push].
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ReassembleError {
+    /// The packet is shorter than [HEADER_LEN], or the index in the header is not smaller than the
+    /// total.
+    InvalidHeader,
+    /// The fragment is not the next fragment of the message that is being reassembled. The partial
+    /// message is discarded.
+    UnexpectedFragment {
+        /// The header of the packet that was received.
+        header: FragmentHeader,
+    },
+    /// The message does not fit in the buffer of the [Reassembler]. The partial message is
+    /// discarded.
+    MessageTooLarge,
+}
+
+/// Collects the fragments that were sent by [serialize_chunked] into a complete message.
+///
+/// Fragments have to arrive in order. A fragment with index 0 always starts a new message, and
+/// discards any partial message. If a fragment is lost, the rest of that message is rejected with
+/// [ReassembleError::UnexpectedFragment], and the next message is received as usual.
+pub struct Reassembler<'buf> {
+    buffer: &'buf mut [u8],
+    len: usize,
+    /// The header of the next fragment that is expected, if a message is being reassembled.
+    next: Option<FragmentHeader>,
+}
+
+impl<'buf> Reassembler<'buf> {
+    /// Create a new reassembler that can receive messages of up to `buffer.len()` bytes.
+    pub fn new(buffer: &'buf mut [u8]) -> Self {
+        Self {
+            buffer,
+            len: 0,
+            next: None,
+        }
+    }
+
+    /// Returns `true` if some, but not all, fragments of a message were received.
+    pub fn is_partial(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// Add a received packet. Returns the complete message when this was its last fragment, which
+    /// can be passed to [deserialize](crate::deserialize).
+    pub fn push(&mut self, packet: &[u8]) -> Result<Option<&[u8]>, ReassembleError> {
+        let (header, payload) =
+            FragmentHeader::parse(packet).ok_or(ReassembleError::InvalidHeader)?;
+        if header.index == 0 {
+            self.len = 0;
+        } else if self.next != Some(header) {
+            self.next = None;
+            return Err(ReassembleError::UnexpectedFragment { header });
+        }
+
+        self.next = None;
+        let end = self.len + payload.len();
+        if end > self.buffer.len() {
+            return Err(ReassembleError::MessageTooLarge);
+        }
+        self.buffer[self.len..end].copy_from_slice(payload);
+        self.len = end;
+
+        if header.index + 1 == header.total {
+            Ok(Some(&self.buffer[..self.len]))
+        } else {
+            self.next = Some(FragmentHeader {
+                index: header.index + 1,
+                ..header
+            });
+            Ok(None)
+        }
+    }
+}
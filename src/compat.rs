@@ -0,0 +1,47 @@
+//! Compatibility with the upstream [bincode](https://docs.rs/bincode) crate.
+//!
+//! With matching options, bincode_core produces the exact same bytes as the serde integration of
+//! upstream bincode, and can decode everything that upstream bincode produces (as long as the types
+//! are supported in `no_std`). This is checked by the test suite that runs with the `compat-tests`
+//! feature.
+//!
+//! | Upstream                                            | bincode_core                       |
+//! |-----------------------------------------------------|------------------------------------|
+//! | 1.x `bincode::serialize` / `bincode::deserialize`   | [Legacy], [config::legacy]         |
+//! | 1.x `bincode::DefaultOptions::new()`                | [DefaultOptions]                   |
+//! | 2.x `bincode::config::legacy()`                     | [Legacy], [config::legacy]         |
+//! | 2.x `bincode::config::standard()`                   | [Standard], [config::standard]     |
+//!
+//! The builder methods of [Options](crate::config::Options) have the same names as the builder
+//! methods of bincode 1.x, so e.g. `DefaultOptions::new().with_big_endian()` matches on both
+//! sides. bincode 2.x `with_fixed_int_encoding()` is
+//! [with_fixint_encoding](crate::config::Options::with_fixint_encoding) here.
+//!
+//! [config::legacy]: crate::config::legacy
+//! [config::standard]: crate::config::standard
+
+use crate::config::{AllowTrailing, FixintEncoding, WithOtherIntEncoding, WithOtherTrailing};
+
+pub use crate::config::DefaultOptions;
+
+/// The options of bincode 1.x `bincode::serialize`, and of bincode 2.x `bincode::config::legacy()`.
+pub type Legacy =
+    WithOtherTrailing<WithOtherIntEncoding<DefaultOptions, FixintEncoding>, AllowTrailing>;
+
+/// The options of bincode 2.x `bincode::config::standard()`.
+pub type Standard = WithOtherTrailing<DefaultOptions, AllowTrailing>;
+
+/// The largest value that the varint encoding writes as a single byte.
+pub const SINGLE_BYTE_MAX: u8 = 250;
+
+/// The varint marker byte that is followed by a `u16`.
+pub const U16_BYTE: u8 = 251;
+
+/// The varint marker byte that is followed by a `u32`.
+pub const U32_BYTE: u8 = 252;
+
+/// The varint marker byte that is followed by a `u64`.
+pub const U64_BYTE: u8 = 253;
+
+/// The varint marker byte that is followed by a `u128`.
+pub const U128_BYTE: u8 = 254;
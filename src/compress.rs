@@ -0,0 +1,386 @@
+use crate::traits::{CoreRead, CoreWrite};
+
+// The stream is a sequence of tokens, most significant bit first, like heatshrink:
+// - `1` followed by 8 bits is a literal byte.
+// - `0` followed by `window_bits` bits of `distance - 1` and `COUNT_BITS` bits of
+//   `len - min_match` copies `len` bytes that were written `distance` bytes ago.
+// - `0` followed by `window_bits` one bits marks a flush, the rest of the byte is padding.
+
+/// The amount of bits that encode the length of a back-reference.
+const COUNT_BITS: u32 = 4;
+
+/// The longest match that is searched for, for the largest window.
+const MAX_LOOKAHEAD: usize = 18;
+
+const MIN_WINDOW_BITS: u32 = 4;
+const MAX_WINDOW_BITS: u32 = 15;
+
+/// The parameters that follow from the size of the window.
+#[derive(Clone, Copy)]
+struct Window {
+    bits: u32,
+    /// The shortest back-reference that is smaller than the same bytes as literals.
+    min_match: usize,
+}
+
+impl Window {
+    fn new(len: usize) -> Self {
+        let bits = len.trailing_zeros();
+        assert!(
+            len.is_power_of_two() && (MIN_WINDOW_BITS..=MAX_WINDOW_BITS).contains(&bits),
+            "the window must be a power of two between 16 and 32768 bytes"
+        );
+        Window {
+            bits,
+            min_match: (1 + bits + COUNT_BITS) as usize / 9 + 1,
+        }
+    }
+
+    fn max_match(&self) -> usize {
+        self.min_match + (1 << COUNT_BITS) - 1
+    }
+
+    /// The distance that marks a flush. Back-references never use it.
+    fn flush_marker(&self) -> u32 {
+        (1 << self.bits) - 1
+    }
+}
+
+/// An implementation of [CoreWrite] that compresses all bytes before they are written to the
+/// inner writer.
+///
+/// The compression is LZSS in the style of [heatshrink](https://github.com/atomicobject/heatshrink):
+/// repeated byte sequences are replaced by a reference to an earlier occurrence in the last
+/// `window.len()` bytes. The window is a caller-provided buffer, which has to be a power of two
+/// between 16 and 32768 bytes, and has to have the same size as the window of the
+/// [CompressedReader] on the other side. A larger window finds more repetitions, but searching it
+/// takes longer. Telemetry with repeated strings, like field names or device ids, typically
+/// compresses 3-4x with a window of 256 bytes.
+///
+/// The last few bytes are kept until more bytes are written, or the writer is flushed. A flush
+/// writes them out, padded to a full byte. [serialize](crate::serialize) flushes the writer when
+/// it is done, so every message can be decoded as soon as it is received.
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, CompressedWriter, DefaultOptions};
+/// let mut buffer = [0u8; 64];
+/// let mut window = [0u8; 64];
+/// let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+/// let value = ("sensor-1", "sensor-2", "sensor-3");
+/// serialize(&value, &mut writer, DefaultOptions::new()).unwrap();
+/// assert!(writer.into_inner().written_len() < 18);
+/// ```
+pub struct CompressedWriter<'buf, W: CoreWrite> {
+    writer: W,
+    window: &'buf mut [u8],
+    params: Window,
+    /// The position in `window` where the next byte is stored.
+    head: usize,
+    /// The amount of bytes in `window`.
+    history_len: usize,
+    lookahead: [u8; MAX_LOOKAHEAD],
+    lookahead_len: usize,
+    bits: u32,
+    bit_count: u32,
+}
+
+impl<'buf, W: CoreWrite> CompressedWriter<'buf, W> {
+    /// Create a new writer that compresses bytes with the given window, and writes them to
+    /// `writer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `window` is not a power of two between 16 and 32768.
+    pub fn new(writer: W, window: &'buf mut [u8]) -> Self {
+        let params = Window::new(window.len());
+        Self {
+            writer,
+            window,
+            params,
+            head: 0,
+            history_len: 0,
+            lookahead: [0; MAX_LOOKAHEAD],
+            lookahead_len: 0,
+            bits: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Return a reference to the inner writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// Return the inner writer. Any bytes that are not written yet are discarded, call
+    /// [CoreWrite::flush] first to write them out.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_bits(&mut self, value: u32, count: u32) -> Result<(), W::Error> {
+        self.bits = (self.bits << count) | (value & ((1 << count) - 1));
+        self.bit_count += count;
+        while self.bit_count >= 8 {
+            self.bit_count -= 8;
+            self.writer.write((self.bits >> self.bit_count) as u8)?;
+        }
+        self.bits &= (1 << self.bit_count) - 1;
+        Ok(())
+    }
+
+    /// The byte at `index` of the history, followed by the lookahead, counted from `distance`
+    /// bytes back.
+    fn byte_at(&self, distance: usize, index: usize) -> u8 {
+        if index < distance {
+            let len = self.window.len();
+            self.window[(self.head + len - distance + index) % len]
+        } else {
+            self.lookahead[index - distance]
+        }
+    }
+
+    /// Returns the distance and length of the longest match for the start of the lookahead.
+    fn find_match(&self) -> (usize, usize) {
+        let max_len = self.lookahead_len.min(self.params.max_match());
+        // The largest distance is reserved for the flush marker
+        let max_distance = self.history_len.min(self.window.len() - 1);
+        let mut best = (0, 0);
+        for distance in 1..=max_distance {
+            let len = (0..max_len)
+                .take_while(|&i| self.byte_at(distance, i) == self.lookahead[i])
+                .count();
+            if len > best.1 {
+                best = (distance, len);
+                if len == max_len {
+                    break;
+                }
+            }
+        }
+        best
+    }
+
+    /// Writes one token for the start of the lookahead.
+    fn write_token(&mut self) -> Result<(), W::Error> {
+        let (distance, len) = self.find_match();
+        let len = if len >= self.params.min_match {
+            self.write_bits(0, 1)?;
+            self.write_bits(distance as u32 - 1, self.params.bits)?;
+            self.write_bits((len - self.params.min_match) as u32, COUNT_BITS)?;
+            len
+        } else {
+            self.write_bits(1, 1)?;
+            self.write_bits(self.lookahead[0] as u32, 8)?;
+            1
+        };
+        for i in 0..len {
+            self.window[self.head] = self.lookahead[i];
+            self.head = (self.head + 1) % self.window.len();
+        }
+        self.history_len = (self.history_len + len).min(self.window.len());
+        self.lookahead.copy_within(len..self.lookahead_len, 0);
+        self.lookahead_len -= len;
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for CompressedWriter<'_, W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.lookahead[self.lookahead_len] = val;
+        self.lookahead_len += 1;
+        if self.lookahead_len == self.params.max_match() {
+            self.write_token()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        while self.lookahead_len > 0 {
+            self.write_token()?;
+        }
+        if self.bit_count > 0 {
+            self.write_bits(0, 1)?;
+            self.write_bits(self.params.flush_marker(), self.params.bits)?;
+            if self.bit_count > 0 {
+                self.write_bits(0, 8 - self.bit_count)?;
+            }
+        }
+        self.writer.flush()
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for &mut CompressedWriter<'_, W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+}
+
+/// An error that is thrown when reading from a [CompressedReader].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CompressedReadError<E> {
+    /// The inner reader returned an error.
+    Read(E),
+    /// Tried to deserialize a `&str` or `&[u8]`. The decompressed bytes are not stored in the inner
+    /// reader, wrap the `CompressedReader` in a [BufferedReader](crate::BufferedReader) to
+    /// deserialize borrowed data.
+    CannotBorrow,
+    /// The input refers to bytes before the start of the stream. The input is corrupt, or the
+    /// window of the writer was larger than the window of the reader.
+    InvalidReference,
+}
+
+/// An implementation of [CoreRead] that decompresses the output of a [CompressedWriter].
+///
+/// The window has to have the same size as the window of the writer.
+///
+/// Borrowed `&str` and `&[u8]` values cannot be read from the decompressed stream. To deserialize
+/// those, wrap this reader in a [BufferedReader](crate::BufferedReader).
+///
+/// ```
+/// # use bincode_core::{deserialize, serialize, BufferWriter, CompressedReader, CompressedWriter, DefaultOptions};
+/// let mut buffer = [0u8; 64];
+/// let mut window = [0u8; 64];
+/// let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+/// serialize(&[7u8; 32], &mut writer, DefaultOptions::new()).unwrap();
+/// let len = writer.into_inner().written_len();
+///
+/// let mut window = [0u8; 64];
+/// let reader = CompressedReader::new(&buffer[..len], &mut window);
+/// let value: [u8; 32] = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, [7; 32]);
+/// ```
+pub struct CompressedReader<'buf, R> {
+    reader: R,
+    window: &'buf mut [u8],
+    params: Window,
+    head: usize,
+    history_len: usize,
+    /// The distance and remaining length of the back-reference that is being copied.
+    copy: (usize, usize),
+    bits: u32,
+    bit_count: u32,
+}
+
+impl<'buf, R> CompressedReader<'buf, R> {
+    /// Create a new reader that decompresses the bytes of `reader` with the given window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `window` is not a power of two between 16 and 32768.
+    pub fn new(reader: R, window: &'buf mut [u8]) -> Self {
+        let params = Window::new(window.len());
+        Self {
+            reader,
+            window,
+            params,
+            head: 0,
+            history_len: 0,
+            copy: (0, 0),
+            bits: 0,
+            bit_count: 0,
+        }
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'a, 'buf, R: CoreRead<'a>> CompressedReader<'buf, R> {
+    fn read_bits(&mut self, count: u32) -> Result<u32, CompressedReadError<R::Error>> {
+        while self.bit_count < count {
+            let byte = self.reader.read().map_err(CompressedReadError::Read)?;
+            self.bits = (self.bits << 8) | byte as u32;
+            self.bit_count += 8;
+        }
+        self.bit_count -= count;
+        let value = self.bits >> self.bit_count;
+        self.bits &= (1 << self.bit_count) - 1;
+        Ok(value)
+    }
+
+    fn read_byte(&mut self) -> Result<u8, CompressedReadError<R::Error>> {
+        loop {
+            let (distance, remaining) = self.copy;
+            if remaining > 0 {
+                let len = self.window.len();
+                self.copy.1 -= 1;
+                return Ok(self.window[(self.head + len - distance) % len]);
+            }
+            if self.read_bits(1)? == 1 {
+                return Ok(self.read_bits(8)? as u8);
+            }
+            let index = self.read_bits(self.params.bits)?;
+            if index == self.params.flush_marker() {
+                self.bits = 0;
+                self.bit_count = 0;
+                continue;
+            }
+            let distance = index as usize + 1;
+            if distance > self.history_len {
+                return Err(CompressedReadError::InvalidReference);
+            }
+            let len = self.read_bits(COUNT_BITS)? as usize + self.params.min_match;
+            self.copy = (distance, len);
+        }
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for CompressedReader<'_, R> {
+    type Error = CompressedReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buffer {
+            *byte = self.read_byte()?;
+            self.window[self.head] = *byte;
+            self.head = (self.head + 1) % self.window.len();
+            self.history_len = (self.history_len + 1).min(self.window.len());
+        }
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(CompressedReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(CompressedReadError::CannotBorrow)
+    }
+}
+
+impl<'a, R: CoreRead<'a>> CoreRead<'a> for &mut CompressedReader<'_, R> {
+    type Error = CompressedReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
@@ -0,0 +1,33 @@
+/// A trait for encoding `bool` values.
+pub trait BoolEncoding {
+    /// Whether consecutive `bool` values are packed into one byte.
+    const PACKED: bool;
+}
+
+/// Every `bool` is encoded as a byte of `0` or `1`.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct ByteBools;
+
+/// Consecutive `bool` values are packed into a byte, 8 per byte. The first value is bit 0, and the
+/// unused bits of the last byte are zero.
+///
+/// Values are consecutive if nothing else is written in between, so the bool fields of a struct,
+/// the items of a `[bool; N]` or `Vec<bool>`, and bool fields that follow each other across
+/// nested structs are all packed together. Any other value starts a new byte.
+///
+/// The last byte is written once a different value follows, at the end of
+/// [serialize](crate::serialize), after every item of [serialize_all](crate::serialize_all), or by
+/// [Serializer::finish](crate::Serializer::finish). Bytes that are written directly to
+/// [Serializer::writer_mut](crate::Serializer::writer_mut) can end up before pending bools.
+#[derive(Copy, Clone)]
+pub struct PackedBools;
+
+impl BoolEncoding for ByteBools {
+    const PACKED: bool = false;
+}
+
+impl BoolEncoding for PackedBools {
+    const PACKED: bool = true;
+}
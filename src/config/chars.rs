@@ -0,0 +1,44 @@
+/// A trait for encoding `char` values.
+pub trait CharEncoding {
+    /// Whether a `char` is encoded as its code point, a `u32` with the configured int encoding.
+    const U32: bool;
+}
+
+/// Every `char` is encoded as its 1 to 4 UTF-8 bytes, like the serde integration of upstream
+/// bincode 1.x and 2.x.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct Utf8Char;
+
+/// Every `char` is encoded as its code point, a `u32` with the configured int encoding.
+///
+/// This matches formats that store characters as a `u32`. With
+/// [VarintEncoding](super::VarintEncoding), ASCII takes a single byte either way, but most other
+/// characters take 3 or 5 bytes instead of 2 to 4. Deserializing a code point that is not a valid
+/// `char`, like a surrogate, fails with
+/// [DeserializeError::InvalidCharEncoding](crate::DeserializeError::InvalidCharEncoding).
+///
+/// ```
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+/// let options = DefaultOptions::new().with_fixint_encoding().with_u32_chars();
+///
+/// let mut buffer = [0u8; 4];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&'é', &mut writer, options).unwrap();
+/// assert_eq!(writer.written_buffer(), &[0xE9, 0, 0, 0]);
+///
+/// let decoded: char = deserialize(writer.written_buffer(), options).unwrap();
+/// assert_eq!(decoded, 'é');
+/// ```
+#[derive(Copy, Clone)]
+pub struct U32Char;
+
+impl CharEncoding for Utf8Char {
+    const U32: bool = false;
+}
+
+impl CharEncoding for U32Char {
+    const U32: bool = true;
+}
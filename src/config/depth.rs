@@ -0,0 +1,29 @@
+/// A trait for limiting how deep sequences, maps, options and enums may be nested.
+pub trait DepthLimit {
+    /// Returns the maximum nesting depth (if one exists)
+    fn max_depth(&self) -> Option<usize>;
+}
+
+/// A DepthLimit that rejects input where sequences, maps, options and enums are nested deeper
+/// than the given value.
+#[derive(Copy, Clone)]
+pub struct BoundedDepth(pub usize);
+
+/// A DepthLimit that allows any nesting depth.
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct UnboundedDepth;
+
+impl DepthLimit for BoundedDepth {
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        Some(self.0)
+    }
+}
+
+impl DepthLimit for UnboundedDepth {
+    #[inline(always)]
+    fn max_depth(&self) -> Option<usize> {
+        None
+    }
+}
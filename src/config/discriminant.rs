@@ -0,0 +1,113 @@
+use super::{IntEncoding, Options};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+
+/// A trait for encoding the variant index of an enum.
+pub trait DiscriminantEncoding {
+    /// Gets the size (in bytes) that a variant index would be serialized to.
+    fn discriminant_size<O: Options>(idx: u32) -> usize;
+
+    /// Serializes a variant index.
+    fn serialize_discriminant<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        idx: u32,
+    ) -> Result<(), SerializeError<W::Error>>;
+
+    /// Deserializes a variant index.
+    fn deserialize_discriminant<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>>;
+}
+
+/// Enum discriminants are encoded as a u32 with the configured integer encoding.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct IntDiscriminant;
+
+/// Enum discriminants are encoded as a single byte, regardless of the integer encoding.
+///
+/// Serializing an enum with more than 256 variants will fail with
+/// [SerializeError::InvalidDiscriminant].
+#[derive(Copy, Clone)]
+pub struct U8Discriminant;
+
+/// Enum discriminants are encoded as a u16 with the configured integer encoding.
+///
+/// Serializing an enum with more than 65536 variants will fail with
+/// [SerializeError::InvalidDiscriminant].
+#[derive(Copy, Clone)]
+pub struct U16Discriminant;
+
+impl DiscriminantEncoding for IntDiscriminant {
+    #[inline(always)]
+    fn discriminant_size<O: Options>(idx: u32) -> usize {
+        O::IntEncoding::u32_size(idx)
+    }
+
+    #[inline(always)]
+    fn serialize_discriminant<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        idx: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        O::IntEncoding::serialize_u32(ser, idx)
+    }
+
+    #[inline(always)]
+    fn deserialize_discriminant<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        O::IntEncoding::deserialize_u32(de)
+    }
+}
+
+impl DiscriminantEncoding for U8Discriminant {
+    #[inline(always)]
+    fn discriminant_size<O: Options>(_: u32) -> usize {
+        1
+    }
+
+    #[inline(always)]
+    fn serialize_discriminant<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        idx: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if idx > u8::MAX as u32 {
+            return Err(SerializeError::InvalidDiscriminant(idx));
+        }
+        ser.serialize_byte(idx as u8)
+    }
+
+    #[inline(always)]
+    fn deserialize_discriminant<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        Ok(de.deserialize_byte()? as u32)
+    }
+}
+
+impl DiscriminantEncoding for U16Discriminant {
+    #[inline(always)]
+    fn discriminant_size<O: Options>(idx: u32) -> usize {
+        O::IntEncoding::u16_size(idx as u16)
+    }
+
+    #[inline(always)]
+    fn serialize_discriminant<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        idx: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if idx > u16::MAX as u32 {
+            return Err(SerializeError::InvalidDiscriminant(idx));
+        }
+        O::IntEncoding::serialize_u16(ser, idx as u16)
+    }
+
+    #[inline(always)]
+    fn deserialize_discriminant<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        Ok(O::IntEncoding::deserialize_u16(de)? as u32)
+    }
+}
@@ -0,0 +1,29 @@
+/// A trait for rejecting single elements whose declared length is too large.
+pub trait ElementLimit {
+    /// Returns the maximum length of a single `&str`, `&[u8]`, sequence or map (if one exists)
+    fn max_element_len(&self) -> Option<usize>;
+}
+
+/// An ElementLimit that rejects any `&str`, `&[u8]`, sequence or map that declares a length
+/// larger than the given value.
+#[derive(Copy, Clone)]
+pub struct BoundedElements(pub usize);
+
+/// An ElementLimit that accepts elements of any declared length.
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct UnboundedElements;
+
+impl ElementLimit for BoundedElements {
+    #[inline(always)]
+    fn max_element_len(&self) -> Option<usize> {
+        Some(self.0)
+    }
+}
+
+impl ElementLimit for UnboundedElements {
+    #[inline(always)]
+    fn max_element_len(&self) -> Option<usize> {
+        None
+    }
+}
@@ -12,6 +12,9 @@ pub struct LittleEndian;
 #[derive(Copy, Clone)]
 pub struct BigEndian;
 
+/// Network byte ordering, which is big-endian.
+pub type NetworkEndian = BigEndian;
+
 /// The native byte ordering of the current system.
 #[derive(Copy, Clone)]
 pub struct NativeEndian;
@@ -0,0 +1,136 @@
+use super::{IntEncoding, Options};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+use core::mem::size_of;
+
+/// A trait for encoding `f32` and `f64` values.
+pub trait FloatEncoding {
+    /// Gets the size (in bytes) that a value would be serialized to.
+    fn f32_size<O: Options>(v: f32) -> usize;
+    /// Gets the size (in bytes) that a value would be serialized to.
+    fn f64_size<O: Options>(v: f64) -> usize;
+
+    /// Serializes an `f32`.
+    fn serialize_f32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f32,
+    ) -> Result<(), SerializeError<W::Error>>;
+
+    /// Serializes an `f64`.
+    fn serialize_f64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f64,
+    ) -> Result<(), SerializeError<W::Error>>;
+
+    /// Deserializes an `f32`.
+    fn deserialize_f32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f32, DeserializeError<'de, R>>;
+
+    /// Deserializes an `f64`.
+    fn deserialize_f64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f64, DeserializeError<'de, R>>;
+}
+
+/// Floats are encoded as 4 or 8 bytes in the configured endianness, regardless of the int
+/// encoding.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct IeeeFloat;
+
+/// Floats are encoded as their raw bit pattern, as a `u32` or `u64` with the configured int
+/// encoding.
+///
+/// The bits are copied with `to_bits` and `from_bits`, so no floating point instructions are used
+/// and every NaN payload, including signaling NaNs, round-trips exactly. With a varint int
+/// encoding, floats with few significant bits (such as small integers in big endian order) may
+/// be encoded in fewer bytes, but most floats will take more bytes than with [IeeeFloat].
+#[derive(Copy, Clone)]
+pub struct FloatAsBits;
+
+impl FloatEncoding for IeeeFloat {
+    #[inline(always)]
+    fn f32_size<O: Options>(_: f32) -> usize {
+        size_of::<f32>()
+    }
+
+    #[inline(always)]
+    fn f64_size<O: Options>(_: f64) -> usize {
+        size_of::<f64>()
+    }
+
+    #[inline(always)]
+    fn serialize_f32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        ser.serialize_literal_u32(v.to_bits())
+    }
+
+    #[inline(always)]
+    fn serialize_f64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        ser.serialize_literal_u64(v.to_bits())
+    }
+
+    #[inline(always)]
+    fn deserialize_f32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f32, DeserializeError<'de, R>> {
+        de.deserialize_literal_u32().map(f32::from_bits)
+    }
+
+    #[inline(always)]
+    fn deserialize_f64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f64, DeserializeError<'de, R>> {
+        de.deserialize_literal_u64().map(f64::from_bits)
+    }
+}
+
+impl FloatEncoding for FloatAsBits {
+    #[inline(always)]
+    fn f32_size<O: Options>(v: f32) -> usize {
+        O::IntEncoding::u32_size(v.to_bits())
+    }
+
+    #[inline(always)]
+    fn f64_size<O: Options>(v: f64) -> usize {
+        O::IntEncoding::u64_size(v.to_bits())
+    }
+
+    #[inline(always)]
+    fn serialize_f32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        O::IntEncoding::serialize_u32(ser, v.to_bits())
+    }
+
+    #[inline(always)]
+    fn serialize_f64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        v: f64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        O::IntEncoding::serialize_u64(ser, v.to_bits())
+    }
+
+    #[inline(always)]
+    fn deserialize_f32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f32, DeserializeError<'de, R>> {
+        O::IntEncoding::deserialize_u32(de).map(f32::from_bits)
+    }
+
+    #[inline(always)]
+    fn deserialize_f64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<f64, DeserializeError<'de, R>> {
+        O::IntEncoding::deserialize_u64(de).map(f64::from_bits)
+    }
+}
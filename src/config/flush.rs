@@ -0,0 +1,28 @@
+/// A trait for when the writer is flushed by [serialize](crate::serialize).
+pub trait FlushPolicy {
+    /// Whether the writer is flushed after a value is written successfully.
+    const AUTO_FLUSH: bool;
+}
+
+/// The writer is flushed after every successful [serialize](crate::serialize) and
+/// [serialize_all](crate::serialize_all), so buffered writers like a UART FIFO or a
+/// [ChunkWriter](crate::ChunkWriter) send out the tail of the message right away.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct AutoFlush;
+
+/// The writer is never flushed by [serialize](crate::serialize) and
+/// [serialize_all](crate::serialize_all). This allows several messages to be batched in a
+/// buffered writer, which then has to be flushed with [CoreWrite::flush](crate::CoreWrite::flush)
+/// or [Serializer::finish](crate::Serializer::finish).
+#[derive(Copy, Clone)]
+pub struct ManualFlush;
+
+impl FlushPolicy for AutoFlush {
+    const AUTO_FLUSH: bool = true;
+}
+
+impl FlushPolicy for ManualFlush {
+    const AUTO_FLUSH: bool = false;
+}
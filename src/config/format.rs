@@ -0,0 +1,130 @@
+/// A trait for the format that values are serialized in.
+pub trait Format {
+    /// Whether values are written in a self-describing format instead of bincode.
+    const SELF_DESCRIBING: bool;
+    /// Whether the self-describing format is CBOR instead of MessagePack.
+    const CBOR: bool;
+}
+
+/// Values are serialized and deserialized as bincode, as configured by the other options.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct Bincode;
+
+/// Values are serialized as [MessagePack](https://msgpack.org), so they can be read on a host
+/// with any MessagePack library, e.g. `msgpack` in Python. The same writers, size limit and flush
+/// policy are used as for bincode, and nothing is allocated.
+///
+/// MessagePack is self-describing, so the other encoding options (endianness, int encoding,
+/// discriminants, type tags, option, bool, char and struct encodings) have no effect. Values are
+/// written as follows:
+///
+/// | Value | MessagePack |
+/// |-------|-------------|
+/// | `bool` | bool |
+/// | `u8` - `u64`, `i8` - `i64` | the smallest int that fits the value |
+/// | `u128`, `i128` | bin of the 16 big endian bytes |
+/// | `f32`, `f64` | float 32, float 64 |
+/// | `char`, `&str` | str |
+/// | `&[u8]` (with `serde_bytes`) | bin |
+/// | `None`, `()`, unit struct | nil |
+/// | `Some(value)`, newtype struct | the value |
+/// | sequence, tuple, tuple struct | array |
+/// | map | map |
+/// | struct | map from the field names to the values |
+/// | unit variant | the name of the variant as a str |
+/// | other variants | map with a single entry, from the name of the variant to its content |
+///
+/// Structs and enums use the same representation as `serde_json`, so the decoded values look
+/// like the JSON of the same type. Sequences and maps need to know their length up front, the
+/// same as for bincode.
+///
+/// This is an output format only. Deserializing with these options fails with
+/// [DeserializeError::Unsupported](crate::DeserializeError::Unsupported).
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions};
+/// #[derive(Serialize)]
+/// struct Reading {
+///     id: u8,
+///     temp: i16,
+/// }
+///
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// let options = DefaultOptions::new().with_msgpack_format();
+/// serialize(&Reading { id: 7, temp: -300 }, &mut writer, options).unwrap();
+/// assert_eq!(
+///     writer.written_buffer(),
+///     &[0x82, 0xA2, b'i', b'd', 0x07, 0xA4, b't', b'e', b'm', b'p', 0xD1, 0xFE, 0xD4]
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct MsgPack;
+
+/// Values are serialized as [CBOR](https://www.rfc-editor.org/rfc/rfc8949.html), as required by
+/// e.g. CoAP, LwM2M and COSE. The same writers, size limit and flush policy are used as for
+/// bincode, and nothing is allocated.
+///
+/// Values are written like [MsgPack] does, with these CBOR items instead:
+///
+/// | Value | CBOR |
+/// |-------|------|
+/// | `bool` | `true`, `false` |
+/// | `u8` - `u64`, `i8` - `i64` | unsigned or negative int |
+/// | `u128`, `i128` | unsigned or negative int, or a bignum (tag 2 or 3) if it does not fit |
+/// | `f32`, `f64` | the shortest of float 16, 32 and 64 that has exactly the same value |
+/// | `char`, `&str` | text string |
+/// | `&[u8]` (with `serde_bytes`) | byte string |
+/// | `None`, `()`, unit struct | `null` |
+/// | sequence, tuple, tuple struct | array |
+/// | map, struct, variant with content | map |
+///
+/// Ints, lengths and floats are written in their shortest form, and all lengths are definite, as
+/// in the deterministic encoding of RFC 8949. Every NaN is written as the same `f16` NaN. The keys
+/// of a map are written in the order that the value serializes them and are not sorted, so a map
+/// is only in the deterministic key order if its keys already are, e.g. the fields of a struct
+/// that are declared in that order.
+///
+/// This is an output format only. Deserializing with these options fails with
+/// [DeserializeError::Unsupported](crate::DeserializeError::Unsupported).
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions};
+/// #[derive(Serialize)]
+/// struct Reading {
+///     id: u8,
+///     temp: i16,
+/// }
+///
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// let options = DefaultOptions::new().with_cbor_format();
+/// serialize(&Reading { id: 7, temp: -300 }, &mut writer, options).unwrap();
+/// assert_eq!(
+///     writer.written_buffer(),
+///     &[0xA2, 0x62, b'i', b'd', 0x07, 0x64, b't', b'e', b'm', b'p', 0x39, 0x01, 0x2B]
+/// );
+/// ```
+#[derive(Copy, Clone)]
+pub struct Cbor;
+
+impl Format for Bincode {
+    const SELF_DESCRIBING: bool = false;
+    const CBOR: bool = false;
+}
+
+impl Format for MsgPack {
+    const SELF_DESCRIBING: bool = true;
+    const CBOR: bool = false;
+}
+
+impl Format for Cbor {
+    const SELF_DESCRIBING: bool = true;
+    const CBOR: bool = true;
+}
@@ -0,0 +1,39 @@
+/// A trait for the extra validation that is done while decoding untrusted input.
+pub trait DecodeHardening {
+    /// The maximum nesting depth of sequences, maps, options and enums, or `None` for no limit.
+    const MAX_DEPTH: Option<usize>;
+
+    /// Whether a declared length is checked against the amount of bytes left in the reader.
+    const CHECK_LENGTHS: bool;
+}
+
+/// Input is decoded without extra validation.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct StandardDecoding;
+
+/// Input is validated for use with untrusted data, like packets received over a radio.
+///
+/// - Sequences, maps, options and enums may be nested at most 32 levels deep. Deeper input fails
+///   with [DeserializeError::DepthLimitExceeded](crate::DeserializeError::DepthLimitExceeded),
+///   before the stack of a recursive type can overflow. A lower limit can be set with
+///   [with_max_depth](super::Options::with_max_depth).
+/// - When the reader knows how many bytes it has left (see
+///   [CoreRead::remaining_len](crate::CoreRead::remaining_len)), a `&str`, `&[u8]`, sequence or
+///   map that declares more elements than there are bytes left fails with
+///   [DeserializeError::LengthExceedsInput](crate::DeserializeError::LengthExceedsInput), before
+///   anything is allocated. Note that this also rejects long sequences of zero-sized values, like
+///   `()`.
+#[derive(Copy, Clone)]
+pub struct HardenedDecoding;
+
+impl DecodeHardening for StandardDecoding {
+    const MAX_DEPTH: Option<usize> = None;
+    const CHECK_LENGTHS: bool = false;
+}
+
+impl DecodeHardening for HardenedDecoding {
+    const MAX_DEPTH: Option<usize> = Some(32);
+    const CHECK_LENGTHS: bool = true;
+}
@@ -0,0 +1,109 @@
+/// Callbacks that are made while a value is serialized, to measure the bytes and the time spent
+/// per message type or field in the field. Set with
+/// [with_instrument](super::Options::with_instrument).
+///
+/// Every method does nothing by default. The callbacks are only made for values that are written
+/// by [serialize](crate::serialize) or a [Serializer](crate::Serializer), not when measuring the
+/// size of a value. Without the `instrument` feature, the hooks do not exist at all.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::{Instrument, Options};
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions};
+/// #[derive(Serialize)]
+/// struct Reading {
+///     sensor: u8,
+///     label: &'static str,
+/// }
+///
+/// #[derive(Default)]
+/// struct FieldBytes {
+///     current: &'static str,
+///     label_bytes: usize,
+/// }
+///
+/// impl Instrument for FieldBytes {
+///     fn on_field_start(&mut self, name: &'static str) {
+///         self.current = name;
+///     }
+///
+///     fn on_bytes_written(&mut self, n: usize) {
+///         if self.current == "label" {
+///             self.label_bytes += n;
+///         }
+///     }
+/// }
+///
+/// let mut stats = FieldBytes::default();
+/// let options = DefaultOptions::new().with_instrument(&mut stats);
+/// let reading = Reading { sensor: 1, label: "outdoor" };
+/// let mut buffer = [0u8; 16];
+/// serialize(&reading, BufferWriter::new(&mut buffer), options).unwrap();
+/// assert_eq!(stats.label_bytes, 8);
+/// ```
+pub trait Instrument {
+    /// A struct, or a struct variant of an enum, is about to be serialized. `name` is the name of
+    /// the struct or variant.
+    #[inline(always)]
+    fn on_struct_start(&mut self, name: &'static str) {
+        let _ = name;
+    }
+
+    /// The struct of the last [on_struct_start](Instrument::on_struct_start) that has not ended
+    /// yet was serialized.
+    #[inline(always)]
+    fn on_struct_end(&mut self) {}
+
+    /// A field of a struct is about to be serialized.
+    #[inline(always)]
+    fn on_field_start(&mut self, name: &'static str) {
+        let _ = name;
+    }
+
+    /// A field of a struct was serialized.
+    #[inline(always)]
+    fn on_field_end(&mut self, name: &'static str) {
+        let _ = name;
+    }
+
+    /// `n` bytes were passed to the writer.
+    #[inline(always)]
+    fn on_bytes_written(&mut self, n: usize) {
+        let _ = n;
+    }
+}
+
+impl<I: Instrument + ?Sized> Instrument for &mut I {
+    #[inline(always)]
+    fn on_struct_start(&mut self, name: &'static str) {
+        (**self).on_struct_start(name)
+    }
+
+    #[inline(always)]
+    fn on_struct_end(&mut self) {
+        (**self).on_struct_end()
+    }
+
+    #[inline(always)]
+    fn on_field_start(&mut self, name: &'static str) {
+        (**self).on_field_start(name)
+    }
+
+    #[inline(always)]
+    fn on_field_end(&mut self, name: &'static str) {
+        (**self).on_field_end(name)
+    }
+
+    #[inline(always)]
+    fn on_bytes_written(&mut self, n: usize) {
+        (**self).on_bytes_written(n)
+    }
+}
+
+/// An [Instrument] that ignores every callback.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct NoInstrument;
+
+impl Instrument for NoInstrument {}
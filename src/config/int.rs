@@ -1,9 +1,9 @@
 use super::Options;
+use crate::compat::{SINGLE_BYTE_MAX, U128_BYTE, U16_BYTE, U32_BYTE, U64_BYTE};
 use crate::deserialize::{DeserializeError, Deserializer};
 use crate::serialize::{SerializeError, Serializer};
 use crate::traits::{CoreRead, CoreWrite};
 use core::mem::size_of;
-use serde::serde_if_integer128;
 
 pub trait IntEncoding {
     /// Gets the size (in bytes) that a value would be serialized to.
@@ -20,57 +20,35 @@ pub trait IntEncoding {
     /// Gets the size (in bytes) that a value would be serialized to.
     fn i64_size(n: i64) -> usize;
 
-    #[inline(always)]
-    fn len_size(len: usize) -> usize {
-        Self::u64_size(len as u64)
-    }
-
-    /// Serializes a sequence length.
-    #[inline(always)]
-    fn serialize_len<W: CoreWrite, O: Options>(
-        ser: &mut Serializer<W, O>,
-        len: usize,
-    ) -> Result<(), SerializeError<W>> {
-        Self::serialize_u64(ser, len as u64)
-    }
-
     fn serialize_u16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u16,
-    ) -> Result<(), SerializeError<W>>;
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn serialize_u32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u32,
-    ) -> Result<(), SerializeError<W>>;
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn serialize_u64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u64,
-    ) -> Result<(), SerializeError<W>>;
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn serialize_i16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i16,
-    ) -> Result<(), SerializeError<W>>;
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn serialize_i32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i32,
-    ) -> Result<(), SerializeError<W>>;
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn serialize_i64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i64,
-    ) -> Result<(), SerializeError<W>>;
-
-    /// Deserializes a sequence length.
-    #[inline(always)]
-    fn deserialize_len<'de, R: CoreRead<'de>, O: Options>(
-        de: &mut Deserializer<'de, R, O>,
-    ) -> Result<usize, DeserializeError<'de, R>> {
-        Self::deserialize_u64(de).and_then(cast_u64_to_usize)
-    }
+    ) -> Result<(), SerializeError<W::Error>>;
 
     fn deserialize_u16<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
@@ -96,31 +74,30 @@ pub trait IntEncoding {
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<i64, DeserializeError<'de, R>>;
 
-    serde_if_integer128! {
-        fn u128_size(v: u128) -> usize;
-        fn i128_size(v: i128) -> usize;
-        fn serialize_u128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: u128,
-        ) -> Result<(), SerializeError<W>>;
-        fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<u128, DeserializeError<'de, R>>;
-        fn serialize_i128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: i128,
-        ) -> Result<(), SerializeError<W>>;
-        fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<i128, DeserializeError<'de, R>>;
-    }
+    fn u128_size(v: u128) -> usize;
+    fn i128_size(v: i128) -> usize;
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>>;
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>>;
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>>;
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>>;
 }
 
 /// Fixed-size integer encoding.
 ///
 /// * Fixed size integers are encoded directly
 /// * Enum discriminants are encoded as u32
-/// * Lengths and usize are encoded as u64
+/// * Lengths are encoded as u64, unless configured otherwise (see [U32Usize](super::U32Usize))
+/// * usize is encoded as u64
 #[derive(Copy, Clone)]
 pub struct FixintEncoding;
 
@@ -133,7 +110,7 @@ pub struct FixintEncoding;
 /// 3. If `2**16 <= u < 2**32`, encode it as a literal byte 252, followed by a u32 with value `u`.
 /// 4. If `2**32 <= u < 2**64`, encode it as a literal byte 253, followed by a u64 with value `u`.
 /// 5. If `2**64 <= u < 2**128`, encode it as a literal byte 254, followed by a
-///   u128 with value `u`.
+///    u128 with value `u`.
 ///
 /// Then, for signed integers, we first convert to unsigned using the zigzag algorithm,
 /// and then encode them as we do for unsigned integers generally. The reason we use this
@@ -161,7 +138,7 @@ pub struct FixintEncoding;
 /// assert_eq!(zigzag(1), 2);
 /// assert_eq!(zigzag(-2), 3);
 /// assert_eq!(zigzag(2), 4);
-/// assert_eq!(zigzag(i64::min_value()), u64::max_value());
+/// assert_eq!(zigzag(i64::MIN), u64::MAX);
 /// ```
 ///
 /// Note that u256 and the like are unsupported by this format; if and when they are added to the
@@ -169,19 +146,26 @@ pub struct FixintEncoding;
 #[derive(Copy, Clone)]
 pub struct VarintEncoding;
 
-const SINGLE_BYTE_MAX: u8 = 250;
-const U16_BYTE: u8 = 251;
-const U32_BYTE: u8 = 252;
-const U64_BYTE: u8 = 253;
-const U128_BYTE: u8 = 254;
+/// [VarintEncoding] that only accepts the shortest encoding of every value.
+///
+/// Serializing produces the same bytes as [VarintEncoding]. Deserializing returns
+/// [DeserializeError::NonCanonical] for a varint that uses a longer form than necessary, e.g. `5`
+/// encoded as `[251, 5, 0]` instead of `[5]`. Every value then has exactly one encoding, which is
+/// needed when the encoded bytes are hashed or signed.
+///
+/// Other parts of the format can still have more than one encoding: trailing bytes are only
+/// rejected with [reject_trailing_bytes](super::Options::reject_trailing_bytes), and maps are
+/// written in the iteration order of the map type.
+#[derive(Copy, Clone)]
+pub struct CanonicalVarintEncoding;
 
 impl VarintEncoding {
     fn varint_size(n: u64) -> usize {
         if n <= SINGLE_BYTE_MAX as u64 {
             1
-        } else if n <= u16::max_value() as u64 {
+        } else if n <= u16::MAX as u64 {
             1 + size_of::<u16>()
-        } else if n <= u32::max_value() as u64 {
+        } else if n <= u32::MAX as u64 {
             1 + size_of::<u32>()
         } else {
             1 + size_of::<u64>()
@@ -189,9 +173,9 @@ impl VarintEncoding {
     }
 
     #[inline(always)]
-    fn zigzag_encode(n: i64) -> u64 {
+    pub(super) fn zigzag_encode(n: i64) -> u64 {
         if n < 0 {
-            // let's avoid the edge case of i64::min_value()
+            // let's avoid the edge case of i64::MIN
             // !n is equal to `-n - 1`, so this is:
             // !n * 2 + 1 = 2(-n - 1) + 1 = -2n - 2 + 1 = -2n - 1
             !(n as u64) * 2 + 1
@@ -201,8 +185,8 @@ impl VarintEncoding {
     }
 
     #[inline(always)]
-    fn zigzag_decode(n: u64) -> i64 {
-        if n % 2 == 0 {
+    pub(super) fn zigzag_decode(n: u64) -> i64 {
+        if n & 1 == 0 {
             // positive number
             (n / 2) as i64
         } else {
@@ -219,103 +203,129 @@ impl VarintEncoding {
     fn serialize_varint<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         n: u64,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         if n <= SINGLE_BYTE_MAX as u64 {
             ser.serialize_byte(n as u8)
-        } else if n <= u16::max_value() as u64 {
+        } else if n <= u16::MAX as u64 {
             ser.serialize_byte(U16_BYTE)?;
             ser.serialize_literal_u16(n as u16)
-        } else if n <= u32::max_value() as u64 {
+        } else if n <= u32::MAX as u64 {
             ser.serialize_byte(U32_BYTE)?;
             ser.serialize_literal_u32(n as u32)
         } else {
             ser.serialize_byte(U64_BYTE)?;
-            ser.serialize_literal_u64(n as u64)
+            ser.serialize_literal_u64(n)
         }
     }
 
+    /// Deserializes a varint. If `canonical` is set, values that are encoded with more bytes than
+    /// necessary are rejected.
     fn deserialize_varint<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
+        canonical: bool,
     ) -> Result<u64, DeserializeError<'de, R>> {
+        let (marker, peeked) = de.deserialize_varint_marker(U64_BYTE)?;
         #[allow(ellipsis_inclusive_range_patterns)]
-        match de.deserialize_byte()? {
-            byte @ 0...SINGLE_BYTE_MAX => Ok(byte as u64),
-            U16_BYTE => Ok(de.deserialize_literal_u16()? as u64),
-            U32_BYTE => Ok(de.deserialize_literal_u32()? as u64),
-            U64_BYTE => de.deserialize_literal_u64(),
-            U128_BYTE => Err(DeserializeError::InvalidValueRange),
-            _ => Err(DeserializeError::ExtensionPoint),
+        let (n, min) = match marker {
+            byte @ 0...SINGLE_BYTE_MAX => return Ok(byte as u64),
+            U16_BYTE => (
+                de.deserialize_varint_u16(peeked)? as u64,
+                SINGLE_BYTE_MAX as u64 + 1,
+            ),
+            U32_BYTE => (
+                de.deserialize_varint_u32(peeked)? as u64,
+                u16::MAX as u64 + 1,
+            ),
+            U64_BYTE => (de.deserialize_varint_u64(peeked)?, u32::MAX as u64 + 1),
+            U128_BYTE => return Err(DeserializeError::InvalidValueRange),
+            _ => return Err(DeserializeError::ExtensionPoint),
+        };
+        if canonical && n < min {
+            return Err(DeserializeError::NonCanonical);
         }
+        Ok(n)
     }
 
-    serde_if_integer128! {
-        // see zigzag_encode and zigzag_decode for implementation comments
-        #[inline(always)]
-        fn zigzag128_encode(n: i128) -> u128 {
-            if n < 0 {
-                !(n as u128) * 2 + 1
-            } else {
-                (n as u128) * 2
-            }
+    // see zigzag_encode and zigzag_decode for implementation comments
+    #[inline(always)]
+    pub(super) fn zigzag128_encode(n: i128) -> u128 {
+        if n < 0 {
+            !(n as u128) * 2 + 1
+        } else {
+            (n as u128) * 2
         }
-        #[inline(always)]
-        fn zigzag128_decode(n: u128) -> i128 {
-            if n % 2 == 0 {
-                (n / 2) as i128
-            } else {
-
-                !(n / 2) as i128
-            }
+    }
+    #[inline(always)]
+    pub(super) fn zigzag128_decode(n: u128) -> i128 {
+        if n & 1 == 0 {
+            (n / 2) as i128
+        } else {
+            !(n / 2) as i128
         }
+    }
 
-        fn varint128_size(n: u128) -> usize {
-            if n <= SINGLE_BYTE_MAX as u128 {
-                1
-            } else if n <= u16::max_value() as u128 {
-                1 + size_of::<u16>()
-            } else if n <= u32::max_value() as u128 {
-                1 + size_of::<u32>()
-            } else if n <= u64::max_value() as u128 {
-                1 + size_of::<u64>()
-            } else {
-                1 + size_of::<u128>()
-            }
+    fn varint128_size(n: u128) -> usize {
+        if n <= SINGLE_BYTE_MAX as u128 {
+            1
+        } else if n <= u16::MAX as u128 {
+            1 + size_of::<u16>()
+        } else if n <= u32::MAX as u128 {
+            1 + size_of::<u32>()
+        } else if n <= u64::MAX as u128 {
+            1 + size_of::<u64>()
+        } else {
+            1 + size_of::<u128>()
         }
+    }
 
-        fn serialize_varint128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            n: u128,
-        ) -> Result<(), SerializeError<W>> {
-            if n <= SINGLE_BYTE_MAX as u128 {
-                ser.serialize_byte(n as u8)
-            } else if n <= u16::max_value() as u128 {
-                ser.serialize_byte(U16_BYTE)?;
-                ser.serialize_literal_u16(n as u16)
-            } else if n <= u32::max_value() as u128 {
-                ser.serialize_byte(U32_BYTE)?;
-                ser.serialize_literal_u32(n as u32)
-            } else if n <= u64::max_value() as u128 {
-                ser.serialize_byte(U64_BYTE)?;
-                ser.serialize_literal_u64(n as u64)
-            } else {
-                ser.serialize_byte(U128_BYTE)?;
-                ser.serialize_literal_u128(n)
-            }
+    fn serialize_varint128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        n: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if n <= SINGLE_BYTE_MAX as u128 {
+            ser.serialize_byte(n as u8)
+        } else if n <= u16::MAX as u128 {
+            ser.serialize_byte(U16_BYTE)?;
+            ser.serialize_literal_u16(n as u16)
+        } else if n <= u32::MAX as u128 {
+            ser.serialize_byte(U32_BYTE)?;
+            ser.serialize_literal_u32(n as u32)
+        } else if n <= u64::MAX as u128 {
+            ser.serialize_byte(U64_BYTE)?;
+            ser.serialize_literal_u64(n as u64)
+        } else {
+            ser.serialize_byte(U128_BYTE)?;
+            ser.serialize_literal_u128(n)
         }
+    }
 
-        fn deserialize_varint128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<u128, DeserializeError<'de, R>> {
-            #[allow(ellipsis_inclusive_range_patterns)]
-            match de.deserialize_byte()? {
-                byte @ 0...SINGLE_BYTE_MAX => Ok(byte as u128),
-                U16_BYTE => Ok(de.deserialize_literal_u16()? as u128),
-                U32_BYTE => Ok(de.deserialize_literal_u32()? as u128),
-                U64_BYTE => Ok(de.deserialize_literal_u64()? as u128),
-                U128_BYTE => de.deserialize_literal_u128(),
-                _ => Err(DeserializeError::ExtensionPoint),
-            }
+    fn deserialize_varint128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+        canonical: bool,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        let (marker, peeked) = de.deserialize_varint_marker(U128_BYTE)?;
+        #[allow(ellipsis_inclusive_range_patterns)]
+        let (n, min) = match marker {
+            byte @ 0...SINGLE_BYTE_MAX => return Ok(byte as u128),
+            U16_BYTE => (
+                de.deserialize_varint_u16(peeked)? as u128,
+                SINGLE_BYTE_MAX as u128 + 1,
+            ),
+            U32_BYTE => (
+                de.deserialize_varint_u32(peeked)? as u128,
+                u16::MAX as u128 + 1,
+            ),
+            U64_BYTE => (
+                de.deserialize_varint_u64(peeked)? as u128,
+                u32::MAX as u128 + 1,
+            ),
+            U128_BYTE => (de.deserialize_varint_u128(peeked)?, u64::MAX as u128 + 1),
+            _ => return Err(DeserializeError::ExtensionPoint),
+        };
+        if canonical && n < min {
+            return Err(DeserializeError::NonCanonical);
         }
+        Ok(n)
     }
 }
 
@@ -350,21 +360,21 @@ impl IntEncoding for FixintEncoding {
     fn serialize_u16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u16,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u16(val)
     }
     #[inline(always)]
     fn serialize_u32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u32,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u32(val)
     }
     #[inline(always)]
     fn serialize_u64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u64,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u64(val)
     }
 
@@ -372,21 +382,21 @@ impl IntEncoding for FixintEncoding {
     fn serialize_i16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i16,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u16(val as u16)
     }
     #[inline(always)]
     fn serialize_i32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i32,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u32(val as u32)
     }
     #[inline(always)]
     fn serialize_i64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i64,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         ser.serialize_literal_u64(val as u64)
     }
 
@@ -428,42 +438,40 @@ impl IntEncoding for FixintEncoding {
         Ok(de.deserialize_literal_u64()? as i64)
     }
 
-    serde_if_integer128! {
-        #[inline(always)]
-        fn u128_size(_: u128) -> usize {
-            size_of::<u128>()
-        }
-        #[inline(always)]
-        fn i128_size(_: i128) -> usize {
-            size_of::<i128>()
-        }
+    #[inline(always)]
+    fn u128_size(_: u128) -> usize {
+        size_of::<u128>()
+    }
+    #[inline(always)]
+    fn i128_size(_: i128) -> usize {
+        size_of::<i128>()
+    }
 
-        #[inline(always)]
-        fn serialize_u128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: u128,
-        ) -> Result<(), SerializeError<W>> {
-            ser.serialize_literal_u128(val)
-        }
-        #[inline(always)]
-        fn serialize_i128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: i128,
-        ) -> Result<(), SerializeError<W>> {
-            ser.serialize_literal_u128(val as u128)
-        }
-        #[inline(always)]
-        fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<u128, DeserializeError<'de, R>> {
-            de.deserialize_literal_u128()
-        }
-        #[inline(always)]
-        fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<i128, DeserializeError<'de, R>> {
-            Ok(de.deserialize_literal_u128()? as i128)
-        }
+    #[inline(always)]
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        ser.serialize_literal_u128(val)
+    }
+    #[inline(always)]
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        ser.serialize_literal_u128(val as u128)
+    }
+    #[inline(always)]
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        de.deserialize_literal_u128()
+    }
+    #[inline(always)]
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>> {
+        Ok(de.deserialize_literal_u128()? as i128)
     }
 }
 
@@ -498,21 +506,21 @@ impl IntEncoding for VarintEncoding {
     fn serialize_u16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u16,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, val as u64)
     }
     #[inline(always)]
     fn serialize_u32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u32,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, val as u64)
     }
     #[inline(always)]
     fn serialize_u64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: u64,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, val)
     }
 
@@ -520,21 +528,21 @@ impl IntEncoding for VarintEncoding {
     fn serialize_i16<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i16,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, Self::zigzag_encode(val as i64))
     }
     #[inline(always)]
     fn serialize_i32<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i32,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, Self::zigzag_encode(val as i64))
     }
     #[inline(always)]
     fn serialize_i64<W: CoreWrite, O: Options>(
         ser: &mut Serializer<W, O>,
         val: i64,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         Self::serialize_varint(ser, Self::zigzag_encode(val))
     }
 
@@ -542,26 +550,26 @@ impl IntEncoding for VarintEncoding {
     fn deserialize_u16<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<u16, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de).and_then(cast_u64_to_u16)
+        Self::deserialize_varint(de, false).and_then(cast_u64_to_u16)
     }
     #[inline(always)]
     fn deserialize_u32<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<u32, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de).and_then(cast_u64_to_u32)
+        Self::deserialize_varint(de, false).and_then(cast_u64_to_u32)
     }
     #[inline(always)]
     fn deserialize_u64<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<u64, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de)
+        Self::deserialize_varint(de, false)
     }
 
     #[inline(always)]
     fn deserialize_i16<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<i16, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de)
+        Self::deserialize_varint(de, false)
             .map(Self::zigzag_decode)
             .and_then(cast_i64_to_i16)
     }
@@ -569,7 +577,7 @@ impl IntEncoding for VarintEncoding {
     fn deserialize_i32<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<i32, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de)
+        Self::deserialize_varint(de, false)
             .map(Self::zigzag_decode)
             .and_then(cast_i64_to_i32)
     }
@@ -577,61 +585,198 @@ impl IntEncoding for VarintEncoding {
     fn deserialize_i64<'de, R: CoreRead<'de>, O: Options>(
         de: &mut Deserializer<'de, R, O>,
     ) -> Result<i64, DeserializeError<'de, R>> {
-        Self::deserialize_varint(de).map(Self::zigzag_decode)
+        Self::deserialize_varint(de, false).map(Self::zigzag_decode)
     }
 
-    serde_if_integer128! {
-        #[inline(always)]
-        fn u128_size(n: u128) -> usize {
-            Self::varint128_size(n)
-        }
-        #[inline(always)]
-        fn i128_size(n: i128) -> usize {
-            Self::varint128_size(Self::zigzag128_encode(n))
-        }
-        #[inline(always)]
-        fn serialize_u128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: u128,
-        ) -> Result<(), SerializeError<W>> {
-            Self::serialize_varint128(ser, val)
-        }
-        #[inline(always)]
-        fn serialize_i128<W: CoreWrite, O: Options>(
-            ser: &mut Serializer<W, O>,
-            val: i128,
-        ) -> Result<(), SerializeError<W>> {
-            Self::serialize_varint128(ser, Self::zigzag128_encode(val))
-        }
-        #[inline(always)]
-        fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<u128, DeserializeError<'de, R>> {
-            Self::deserialize_varint128(de)
-        }
-        #[inline(always)]
-        fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
-            de: &mut Deserializer<'de, R, O>,
-        ) -> Result<i128, DeserializeError<'de, R>> {
-            Self::deserialize_varint128(de).map(Self::zigzag128_decode)
-        }
+    #[inline(always)]
+    fn u128_size(n: u128) -> usize {
+        Self::varint128_size(n)
+    }
+    #[inline(always)]
+    fn i128_size(n: i128) -> usize {
+        Self::varint128_size(Self::zigzag128_encode(n))
+    }
+    #[inline(always)]
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        Self::serialize_varint128(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        Self::serialize_varint128(ser, Self::zigzag128_encode(val))
+    }
+    #[inline(always)]
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        Self::deserialize_varint128(de, false)
+    }
+    #[inline(always)]
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>> {
+        Self::deserialize_varint128(de, false).map(Self::zigzag128_decode)
     }
 }
 
-fn cast_u64_to_usize<'de, R: CoreRead<'de> + 'de>(
-    n: u64,
-) -> Result<usize, DeserializeError<'de, R>> {
-    if n <= usize::max_value() as u64 {
-        Ok(n as usize)
-    } else {
-        Err(DeserializeError::InvalidCast {
-            from_type: "u64",
-            to_type: "usize",
-        })
+impl IntEncoding for CanonicalVarintEncoding {
+    #[inline(always)]
+    fn u16_size(n: u16) -> usize {
+        VarintEncoding::u16_size(n)
+    }
+    #[inline(always)]
+    fn u32_size(n: u32) -> usize {
+        VarintEncoding::u32_size(n)
+    }
+    #[inline(always)]
+    fn u64_size(n: u64) -> usize {
+        VarintEncoding::u64_size(n)
+    }
+
+    #[inline(always)]
+    fn i16_size(n: i16) -> usize {
+        VarintEncoding::i16_size(n)
+    }
+    #[inline(always)]
+    fn i32_size(n: i32) -> usize {
+        VarintEncoding::i32_size(n)
+    }
+    #[inline(always)]
+    fn i64_size(n: i64) -> usize {
+        VarintEncoding::i64_size(n)
+    }
+
+    #[inline(always)]
+    fn serialize_u16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_u16(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_u32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_u32(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_u64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_u64(ser, val)
+    }
+
+    #[inline(always)]
+    fn serialize_i16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_i16(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_i32(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_i64(ser, val)
+    }
+
+    #[inline(always)]
+    fn deserialize_u16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u16, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true).and_then(cast_u64_to_u16)
+    }
+    #[inline(always)]
+    fn deserialize_u32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true).and_then(cast_u64_to_u32)
+    }
+    #[inline(always)]
+    fn deserialize_u64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u64, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true)
+    }
+
+    #[inline(always)]
+    fn deserialize_i16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i16, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true)
+            .map(VarintEncoding::zigzag_decode)
+            .and_then(cast_i64_to_i16)
+    }
+    #[inline(always)]
+    fn deserialize_i32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i32, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true)
+            .map(VarintEncoding::zigzag_decode)
+            .and_then(cast_i64_to_i32)
+    }
+    #[inline(always)]
+    fn deserialize_i64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i64, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint(de, true).map(VarintEncoding::zigzag_decode)
+    }
+
+    #[inline(always)]
+    fn u128_size(n: u128) -> usize {
+        VarintEncoding::u128_size(n)
+    }
+    #[inline(always)]
+    fn i128_size(n: i128) -> usize {
+        VarintEncoding::i128_size(n)
+    }
+    #[inline(always)]
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_u128(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        VarintEncoding::serialize_i128(ser, val)
+    }
+    #[inline(always)]
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint128(de, true)
+    }
+    #[inline(always)]
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>> {
+        VarintEncoding::deserialize_varint128(de, true).map(VarintEncoding::zigzag128_decode)
     }
 }
-fn cast_u64_to_u32<'de, R: CoreRead<'de> + 'de>(n: u64) -> Result<u32, DeserializeError<'de, R>> {
-    if n <= u32::max_value() as u64 {
+
+pub(super) fn cast_u64_to_u32<'de, R: CoreRead<'de> + 'de>(
+    n: u64,
+) -> Result<u32, DeserializeError<'de, R>> {
+    if n <= u32::MAX as u64 {
         Ok(n as u32)
     } else {
         Err(DeserializeError::InvalidCast {
@@ -640,8 +785,10 @@ fn cast_u64_to_u32<'de, R: CoreRead<'de> + 'de>(n: u64) -> Result<u32, Deseriali
         })
     }
 }
-fn cast_u64_to_u16<'de, R: CoreRead<'de> + 'de>(n: u64) -> Result<u16, DeserializeError<'de, R>> {
-    if n <= u16::max_value() as u64 {
+pub(super) fn cast_u64_to_u16<'de, R: CoreRead<'de> + 'de>(
+    n: u64,
+) -> Result<u16, DeserializeError<'de, R>> {
+    if n <= u16::MAX as u64 {
         Ok(n as u16)
     } else {
         Err(DeserializeError::InvalidCast {
@@ -651,8 +798,10 @@ fn cast_u64_to_u16<'de, R: CoreRead<'de> + 'de>(n: u64) -> Result<u16, Deseriali
     }
 }
 
-fn cast_i64_to_i32<'de, R: CoreRead<'de> + 'de>(n: i64) -> Result<i32, DeserializeError<'de, R>> {
-    if n <= i32::max_value() as i64 && n >= i32::min_value() as i64 {
+pub(super) fn cast_i64_to_i32<'de, R: CoreRead<'de> + 'de>(
+    n: i64,
+) -> Result<i32, DeserializeError<'de, R>> {
+    if n <= i32::MAX as i64 && n >= i32::MIN as i64 {
         Ok(n as i32)
     } else {
         Err(DeserializeError::InvalidCast {
@@ -662,8 +811,10 @@ fn cast_i64_to_i32<'de, R: CoreRead<'de> + 'de>(n: i64) -> Result<i32, Deseriali
     }
 }
 
-fn cast_i64_to_i16<'de, R: CoreRead<'de> + 'de>(n: i64) -> Result<i16, DeserializeError<'de, R>> {
-    if n <= i16::max_value() as i64 && n >= i16::min_value() as i64 {
+pub(super) fn cast_i64_to_i16<'de, R: CoreRead<'de> + 'de>(
+    n: i64,
+) -> Result<i16, DeserializeError<'de, R>> {
+    if n <= i16::MAX as i64 && n >= i16::MIN as i64 {
         Ok(n as i16)
     } else {
         Err(DeserializeError::InvalidCast {
@@ -702,10 +853,10 @@ mod test {
     fn test_zigzag_edge_cases() {
         let (zigzag, zigzagp) = (VarintEncoding::zigzag_encode, VarintEncoding::zigzag_decode);
 
-        assert_eq!(zigzag(i64::max_value()), u64::max_value() - 1);
-        assert_eq!(zigzag(i64::min_value()), u64::max_value());
+        assert_eq!(zigzag(i64::MAX), u64::MAX - 1);
+        assert_eq!(zigzag(i64::MIN), u64::MAX);
 
-        assert_eq!(zigzagp(u64::max_value() - 1), i64::max_value());
-        assert_eq!(zigzagp(u64::max_value()), i64::min_value());
+        assert_eq!(zigzagp(u64::MAX - 1), i64::MAX);
+        assert_eq!(zigzagp(u64::MAX), i64::MIN);
     }
 }
@@ -2,21 +2,92 @@ use super::*;
 
 pub trait InternalOptions {
     type Limit: SizeLimit + 'static;
+    type ReadLimit: SizeLimit + 'static;
     type Endian: BincodeByteOrder + 'static;
     type IntEncoding: IntEncoding + 'static;
     type Trailing: TrailingBytes + 'static;
+    type Discriminant: DiscriminantEncoding + 'static;
+    type ElementLimit: ElementLimit + 'static;
+    type Float: FloatEncoding + 'static;
+    type Hardening: DecodeHardening + 'static;
+    type DepthLimit: DepthLimit + 'static;
+    type Flush: FlushPolicy + 'static;
+    type Presence: OptionEncoding + 'static;
+    type Bools: BoolEncoding + 'static;
+    type Chars: CharEncoding + 'static;
+    type Usize: UsizeEncoding + 'static;
+    type TypeTags: TypeTagEncoding + 'static;
+    type Structs: StructEncoding + 'static;
+    type UnknownVariants: UnknownVariants + 'static;
+    type Format: Format + 'static;
+    #[cfg(feature = "instrument")]
+    type Instrument: Instrument;
 
     fn limit(&mut self) -> &mut Self::Limit;
+
+    fn read_limit(&mut self) -> &mut Self::ReadLimit;
+
+    fn element_limit(&self) -> &Self::ElementLimit;
+
+    fn depth_limit(&self) -> &Self::DepthLimit;
+
+    fn unknown_variants(&self) -> &Self::UnknownVariants;
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut Self::Instrument;
 }
 
-impl<'a, O: InternalOptions> InternalOptions for &'a mut O {
+impl<O: InternalOptions> InternalOptions for &mut O {
     type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
     type Endian = O::Endian;
     type IntEncoding = O::IntEncoding;
     type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
 
     #[inline(always)]
     fn limit(&mut self) -> &mut Self::Limit {
         (*self).limit()
     }
+
+    #[inline(always)]
+    fn read_limit(&mut self) -> &mut Self::ReadLimit {
+        (*self).read_limit()
+    }
+
+    #[inline(always)]
+    fn element_limit(&self) -> &Self::ElementLimit {
+        (**self).element_limit()
+    }
+
+    #[inline(always)]
+    fn depth_limit(&self) -> &Self::DepthLimit {
+        (**self).depth_limit()
+    }
+
+    #[inline(always)]
+    fn unknown_variants(&self) -> &Self::UnknownVariants {
+        (**self).unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    #[inline(always)]
+    fn instrument(&mut self) -> &mut Self::Instrument {
+        (*self).instrument()
+    }
 }
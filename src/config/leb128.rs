@@ -0,0 +1,501 @@
+use super::int::{
+    cast_i64_to_i16, cast_i64_to_i32, cast_u64_to_u16, cast_u64_to_u32, VarintEncoding,
+};
+use super::{IntEncoding, Options};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+
+/// Postcard-compatible variable-size integer encoding (excepting \[ui\]8).
+///
+/// This is the integer encoding used by the [postcard](https://docs.rs/postcard) wire format:
+///
+/// * Unsigned integers are encoded as unsigned LEB128: 7 bits of the value per byte, least
+///   significant group first, with the high bit of each byte set if more bytes follow.
+/// * Signed integers are first converted with the zigzag algorithm (see [VarintEncoding]), and then
+///   encoded as unsigned LEB128.
+/// * Enum discriminants are encoded as a u32, and lengths and usize as a u64.
+///
+/// Combined with little-endian floats this produces the same bytes as postcard for integers, bools,
+/// floats, options, sequences, maps, strings, byte slices and enums. `char` is not compatible:
+/// postcard encodes it as a length-prefixed string, while this crate writes the raw UTF-8 bytes.
+#[derive(Copy, Clone)]
+pub struct PostcardEncoding;
+
+/// Standard LEB128 variable-size integer encoding (excepting \[ui\]8).
+///
+/// This is the encoding used by DWARF, WebAssembly and protobuf-style varints:
+///
+/// * Unsigned integers are encoded as unsigned LEB128, exactly like [PostcardEncoding].
+/// * Signed integers are encoded as signed LEB128: the two's complement value is split in groups of
+///   7 bits, and the last byte is sign-extended. `-1` encodes as `0x7F` and `64` as `[0xC0, 0x00]`.
+/// * Enum discriminants are encoded as a u32, and lengths and usize as a u64.
+#[derive(Copy, Clone)]
+pub struct Leb128Encoding;
+
+macro_rules! impl_uleb128 {
+    ($size:ident, $ser:ident, $de:ident, $ty:ty, $max_len:expr) => {
+        #[inline(always)]
+        pub(super) fn $size(n: $ty) -> usize {
+            let bits = <$ty>::BITS - n.leading_zeros();
+            if bits == 0 {
+                1
+            } else {
+                bits.div_ceil(7) as usize
+            }
+        }
+
+        pub(super) fn $ser<W: CoreWrite, O: Options>(
+            ser: &mut Serializer<W, O>,
+            mut n: $ty,
+        ) -> Result<(), SerializeError<W::Error>> {
+            let mut buf = [0u8; $max_len];
+            let mut len = 0;
+            loop {
+                let byte = (n & 0x7F) as u8;
+                n >>= 7;
+                if n == 0 {
+                    buf[len] = byte;
+                    len += 1;
+                    break;
+                }
+                buf[len] = byte | 0x80;
+                len += 1;
+            }
+            ser.serialize_raw(&buf[..len])
+        }
+
+        pub(super) fn $de<'de, R: CoreRead<'de>, O: Options>(
+            de: &mut Deserializer<'de, R, O>,
+        ) -> Result<$ty, DeserializeError<'de, R>> {
+            let mut result: $ty = 0;
+            let mut shift = 0;
+            loop {
+                let byte = de.deserialize_byte()?;
+                let part = (byte & 0x7F) as $ty;
+                // Reject encodings that are too long for the type, or have bits that would be
+                // shifted out of the result.
+                if shift >= <$ty>::BITS || (part << shift) >> shift != part {
+                    return Err(DeserializeError::InvalidVarint);
+                }
+                result |= part << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(result);
+                }
+                shift += 7;
+            }
+        }
+    };
+}
+
+impl_uleb128!(
+    uleb128_size,
+    serialize_uleb128,
+    deserialize_uleb128,
+    u64,
+    10
+);
+impl_uleb128!(
+    uleb128_128_size,
+    serialize_uleb128_128,
+    deserialize_uleb128_128,
+    u128,
+    19
+);
+
+macro_rules! impl_sleb128 {
+    ($size:ident, $ser:ident, $de:ident, $ty:ty, $max_len:expr) => {
+        #[inline(always)]
+        fn $size(n: $ty) -> usize {
+            let unused = if n < 0 {
+                n.leading_ones()
+            } else {
+                n.leading_zeros()
+            };
+            // One extra bit for the sign
+            (<$ty>::BITS - unused + 1).div_ceil(7) as usize
+        }
+
+        fn $ser<W: CoreWrite, O: Options>(
+            ser: &mut Serializer<W, O>,
+            mut n: $ty,
+        ) -> Result<(), SerializeError<W::Error>> {
+            let mut buf = [0u8; $max_len];
+            let mut len = 0;
+            loop {
+                let byte = (n & 0x7F) as u8;
+                n >>= 7;
+                if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+                    buf[len] = byte;
+                    len += 1;
+                    break;
+                }
+                buf[len] = byte | 0x80;
+                len += 1;
+            }
+            ser.serialize_raw(&buf[..len])
+        }
+
+        fn $de<'de, R: CoreRead<'de>, O: Options>(
+            de: &mut Deserializer<'de, R, O>,
+        ) -> Result<$ty, DeserializeError<'de, R>> {
+            let mut result: $ty = 0;
+            let mut shift = 0;
+            loop {
+                let byte = de.deserialize_byte()?;
+                if byte & 0x80 == 0 {
+                    // Sign-extend the last group of 7 bits, and make sure no bits get lost when
+                    // shifting it into place.
+                    let part = (((byte << 1) as i8) >> 1) as $ty;
+                    if (part << shift) >> shift != part {
+                        return Err(DeserializeError::InvalidVarint);
+                    }
+                    return Ok(result | (part << shift));
+                }
+                result |= ((byte & 0x7F) as $ty) << shift;
+                shift += 7;
+                if shift >= <$ty>::BITS {
+                    return Err(DeserializeError::InvalidVarint);
+                }
+            }
+        }
+    };
+}
+
+impl_sleb128!(
+    sleb128_size,
+    serialize_sleb128,
+    deserialize_sleb128,
+    i64,
+    10
+);
+impl_sleb128!(
+    sleb128_128_size,
+    serialize_sleb128_128,
+    deserialize_sleb128_128,
+    i128,
+    19
+);
+
+impl IntEncoding for PostcardEncoding {
+    #[inline(always)]
+    fn u16_size(n: u16) -> usize {
+        uleb128_size(n as u64)
+    }
+    #[inline(always)]
+    fn u32_size(n: u32) -> usize {
+        uleb128_size(n as u64)
+    }
+    #[inline(always)]
+    fn u64_size(n: u64) -> usize {
+        uleb128_size(n)
+    }
+
+    #[inline(always)]
+    fn i16_size(n: i16) -> usize {
+        uleb128_size(VarintEncoding::zigzag_encode(n as i64))
+    }
+    #[inline(always)]
+    fn i32_size(n: i32) -> usize {
+        uleb128_size(VarintEncoding::zigzag_encode(n as i64))
+    }
+    #[inline(always)]
+    fn i64_size(n: i64) -> usize {
+        uleb128_size(VarintEncoding::zigzag_encode(n))
+    }
+
+    #[inline(always)]
+    fn serialize_u16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val as u64)
+    }
+    #[inline(always)]
+    fn serialize_u32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val as u64)
+    }
+    #[inline(always)]
+    fn serialize_u64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val)
+    }
+
+    #[inline(always)]
+    fn serialize_i16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, VarintEncoding::zigzag_encode(val as i64))
+    }
+    #[inline(always)]
+    fn serialize_i32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, VarintEncoding::zigzag_encode(val as i64))
+    }
+    #[inline(always)]
+    fn serialize_i64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, VarintEncoding::zigzag_encode(val))
+    }
+
+    #[inline(always)]
+    fn deserialize_u16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u16, DeserializeError<'de, R>> {
+        deserialize_uleb128(de).and_then(cast_u64_to_u16)
+    }
+    #[inline(always)]
+    fn deserialize_u32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        deserialize_uleb128(de).and_then(cast_u64_to_u32)
+    }
+    #[inline(always)]
+    fn deserialize_u64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u64, DeserializeError<'de, R>> {
+        deserialize_uleb128(de)
+    }
+
+    #[inline(always)]
+    fn deserialize_i16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i16, DeserializeError<'de, R>> {
+        deserialize_uleb128(de)
+            .map(VarintEncoding::zigzag_decode)
+            .and_then(cast_i64_to_i16)
+    }
+    #[inline(always)]
+    fn deserialize_i32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i32, DeserializeError<'de, R>> {
+        deserialize_uleb128(de)
+            .map(VarintEncoding::zigzag_decode)
+            .and_then(cast_i64_to_i32)
+    }
+    #[inline(always)]
+    fn deserialize_i64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i64, DeserializeError<'de, R>> {
+        deserialize_uleb128(de).map(VarintEncoding::zigzag_decode)
+    }
+
+    #[inline(always)]
+    fn u128_size(n: u128) -> usize {
+        uleb128_128_size(n)
+    }
+    #[inline(always)]
+    fn i128_size(n: i128) -> usize {
+        uleb128_128_size(VarintEncoding::zigzag128_encode(n))
+    }
+    #[inline(always)]
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128_128(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128_128(ser, VarintEncoding::zigzag128_encode(val))
+    }
+    #[inline(always)]
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        deserialize_uleb128_128(de)
+    }
+    #[inline(always)]
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>> {
+        deserialize_uleb128_128(de).map(VarintEncoding::zigzag128_decode)
+    }
+}
+
+impl IntEncoding for Leb128Encoding {
+    #[inline(always)]
+    fn u16_size(n: u16) -> usize {
+        uleb128_size(n as u64)
+    }
+    #[inline(always)]
+    fn u32_size(n: u32) -> usize {
+        uleb128_size(n as u64)
+    }
+    #[inline(always)]
+    fn u64_size(n: u64) -> usize {
+        uleb128_size(n)
+    }
+
+    #[inline(always)]
+    fn i16_size(n: i16) -> usize {
+        sleb128_size(n as i64)
+    }
+    #[inline(always)]
+    fn i32_size(n: i32) -> usize {
+        sleb128_size(n as i64)
+    }
+    #[inline(always)]
+    fn i64_size(n: i64) -> usize {
+        sleb128_size(n)
+    }
+
+    #[inline(always)]
+    fn serialize_u16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val as u64)
+    }
+    #[inline(always)]
+    fn serialize_u32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val as u64)
+    }
+    #[inline(always)]
+    fn serialize_u64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128(ser, val)
+    }
+
+    #[inline(always)]
+    fn serialize_i16<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i16,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_sleb128(ser, val as i64)
+    }
+    #[inline(always)]
+    fn serialize_i32<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i32,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_sleb128(ser, val as i64)
+    }
+    #[inline(always)]
+    fn serialize_i64<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i64,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_sleb128(ser, val)
+    }
+
+    #[inline(always)]
+    fn deserialize_u16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u16, DeserializeError<'de, R>> {
+        deserialize_uleb128(de).and_then(cast_u64_to_u16)
+    }
+    #[inline(always)]
+    fn deserialize_u32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u32, DeserializeError<'de, R>> {
+        deserialize_uleb128(de).and_then(cast_u64_to_u32)
+    }
+    #[inline(always)]
+    fn deserialize_u64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u64, DeserializeError<'de, R>> {
+        deserialize_uleb128(de)
+    }
+
+    #[inline(always)]
+    fn deserialize_i16<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i16, DeserializeError<'de, R>> {
+        deserialize_sleb128(de).and_then(cast_i64_to_i16)
+    }
+    #[inline(always)]
+    fn deserialize_i32<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i32, DeserializeError<'de, R>> {
+        deserialize_sleb128(de).and_then(cast_i64_to_i32)
+    }
+    #[inline(always)]
+    fn deserialize_i64<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i64, DeserializeError<'de, R>> {
+        deserialize_sleb128(de)
+    }
+
+    #[inline(always)]
+    fn u128_size(n: u128) -> usize {
+        uleb128_128_size(n)
+    }
+    #[inline(always)]
+    fn i128_size(n: i128) -> usize {
+        sleb128_128_size(n)
+    }
+    #[inline(always)]
+    fn serialize_u128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: u128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_uleb128_128(ser, val)
+    }
+    #[inline(always)]
+    fn serialize_i128<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        val: i128,
+    ) -> Result<(), SerializeError<W::Error>> {
+        serialize_sleb128_128(ser, val)
+    }
+    #[inline(always)]
+    fn deserialize_u128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<u128, DeserializeError<'de, R>> {
+        deserialize_uleb128_128(de)
+    }
+    #[inline(always)]
+    fn deserialize_i128<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<i128, DeserializeError<'de, R>> {
+        deserialize_sleb128_128(de)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sleb128_128_size, sleb128_size, uleb128_128_size, uleb128_size};
+
+    #[test]
+    fn test_uleb128_size() {
+        assert_eq!(uleb128_size(0), 1);
+        assert_eq!(uleb128_size(0x7F), 1);
+        assert_eq!(uleb128_size(0x80), 2);
+        assert_eq!(uleb128_size(0x3FFF), 2);
+        assert_eq!(uleb128_size(0x4000), 3);
+        assert_eq!(uleb128_size(u64::MAX), 10);
+        assert_eq!(uleb128_128_size(u128::MAX), 19);
+    }
+
+    #[test]
+    fn test_sleb128_size() {
+        assert_eq!(sleb128_size(0), 1);
+        assert_eq!(sleb128_size(-1), 1);
+        assert_eq!(sleb128_size(63), 1);
+        assert_eq!(sleb128_size(-64), 1);
+        assert_eq!(sleb128_size(64), 2);
+        assert_eq!(sleb128_size(-65), 2);
+        assert_eq!(sleb128_size(i64::MAX), 10);
+        assert_eq!(sleb128_size(i64::MIN), 10);
+        assert_eq!(sleb128_128_size(i128::MIN), 19);
+    }
+}
@@ -1,14 +1,91 @@
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
 /// A trait for stopping serialization and deserialization when a certain limit has been reached.
+///
+/// Besides [Bounded] and [Infinite], this can be implemented for limits that change at runtime,
+/// and used with [WithOtherLimit::new](super::WithOtherLimit::new). For example, a limit that
+/// follows the free space in a TX ring buffer that is maintained by a driver:
+///
+/// ```
+/// # use bincode_core::config::{LimitError, SizeLimit, WithOtherLimit};
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions, SerializeError};
+/// use core::sync::atomic::{AtomicUsize, Ordering};
+///
+/// // Updated by the driver
+/// static TX_FREE: AtomicUsize = AtomicUsize::new(3);
+///
+/// struct TxRingLimit {
+///     used: u64,
+/// }
+///
+/// impl TxRingLimit {
+///     fn free(&self) -> u64 {
+///         (TX_FREE.load(Ordering::Relaxed) as u64).saturating_sub(self.used)
+///     }
+/// }
+///
+/// impl SizeLimit for TxRingLimit {
+///     fn add(&mut self, n: u64) -> Result<(), LimitError> {
+///         if n > self.free() {
+///             return Err(LimitError::LimitReached);
+///         }
+///         self.used += n;
+///         Ok(())
+///     }
+///
+///     fn rewind(&mut self, n: u64) {
+///         self.used -= n;
+///     }
+///
+///     fn limit(&self) -> Option<u64> {
+///         Some(self.free())
+///     }
+/// }
+///
+/// let mut buffer = [0u8; 16];
+/// let options = WithOtherLimit::new(DefaultOptions::new(), TxRingLimit { used: 0 });
+/// let result = serialize(&[1u16, 2, 3, 4], BufferWriter::new(&mut buffer), options);
+/// assert!(matches!(result, Err(SerializeError::LimitReached)));
+///
+/// TX_FREE.store(16, Ordering::Relaxed);
+/// let options = WithOtherLimit::new(DefaultOptions::new(), TxRingLimit { used: 0 });
+/// serialize(&[1u16, 2, 3, 4], BufferWriter::new(&mut buffer), options).unwrap();
+/// ```
+///
+/// The trait is object safe, so a `&'static mut dyn SizeLimit` can be used as a limit as well.
 pub trait SizeLimit {
     /// Tells the SizeLimit that a certain number of bytes has been
     /// read or written.  Returns Err if the limit has been exceeded.
     fn add(&mut self, n: u64) -> Result<(), LimitError>;
-    /// Returns the hard limit (if one exists)
+    /// Gives back `n` bytes that were added before, when a reader is rolled back.
+    fn rewind(&mut self, n: u64);
+    /// Returns the amount of bytes that can still be read or written (if there is a limit).
     fn limit(&self) -> Option<u64>;
 }
 
+impl<L: SizeLimit + ?Sized> SizeLimit for &mut L {
+    #[inline(always)]
+    fn add(&mut self, n: u64) -> Result<(), LimitError> {
+        (**self).add(n)
+    }
+
+    #[inline(always)]
+    fn rewind(&mut self, n: u64) {
+        (**self).rewind(n)
+    }
+
+    #[inline(always)]
+    fn limit(&self) -> Option<u64> {
+        (**self).limit()
+    }
+}
+
 /// Reached an error regarding the size limit that was passed to the options.
 #[non_exhaustive]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum LimitError {
     /// Reached the limit of the given size
     LimitReached,
@@ -22,6 +99,15 @@ impl core::fmt::Debug for LimitError {
     }
 }
 
+impl core::fmt::Display for LimitError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for LimitError {}
+
 /// A SizeLimit that restricts serialized or deserialized messages from
 /// exceeding a certain byte length.
 #[derive(Copy, Clone)]
@@ -43,6 +129,11 @@ impl SizeLimit for Bounded {
         }
     }
 
+    #[inline(always)]
+    fn rewind(&mut self, n: u64) {
+        self.0 += n;
+    }
+
     #[inline(always)]
     fn limit(&self) -> Option<u64> {
         Some(self.0)
@@ -55,6 +146,9 @@ impl SizeLimit for Infinite {
         Ok(())
     }
 
+    #[inline(always)]
+    fn rewind(&mut self, _: u64) {}
+
     #[inline(always)]
     fn limit(&self) -> Option<u64> {
         None
@@ -1,26 +1,73 @@
 use core::marker::PhantomData;
 
+pub(crate) use self::bools::BoolEncoding;
+pub(crate) use self::chars::CharEncoding;
+pub(crate) use self::depth::DepthLimit;
+pub(crate) use self::discriminant::DiscriminantEncoding;
+pub(crate) use self::element::ElementLimit;
 pub(crate) use self::endian::BincodeByteOrder;
+pub(crate) use self::float::FloatEncoding;
+pub(crate) use self::flush::FlushPolicy;
+pub(crate) use self::format::Format;
+pub(crate) use self::hardening::DecodeHardening;
 pub(crate) use self::int::IntEncoding;
 pub(crate) use self::internal::InternalOptions;
-pub(crate) use self::limit::SizeLimit;
+pub(crate) use self::presence::{bitmap_len, OptionEncoding, BITMAP_FIELDS};
+pub(crate) use self::structs::StructEncoding;
 pub(crate) use self::trailing::TrailingBytes;
+pub(crate) use self::type_tags::{TypeTag, TypeTagEncoding};
+pub(crate) use self::usize::UsizeEncoding;
+pub(crate) use self::variants::UnknownVariants;
 
-pub use self::endian::{BigEndian, LittleEndian, NativeEndian};
-pub use self::int::{FixintEncoding, VarintEncoding};
-pub use self::limit::{Bounded, Infinite, LimitError};
+pub use self::bools::{ByteBools, PackedBools};
+pub use self::chars::{U32Char, Utf8Char};
+pub use self::depth::{BoundedDepth, UnboundedDepth};
+pub use self::discriminant::{IntDiscriminant, U16Discriminant, U8Discriminant};
+pub use self::element::{BoundedElements, UnboundedElements};
+pub use self::endian::{BigEndian, LittleEndian, NativeEndian, NetworkEndian};
+pub use self::float::{FloatAsBits, IeeeFloat};
+pub use self::flush::{AutoFlush, ManualFlush};
+pub use self::format::{Bincode, Cbor, MsgPack};
+pub use self::hardening::{HardenedDecoding, StandardDecoding};
+#[cfg(feature = "instrument")]
+pub use self::instrument::{Instrument, NoInstrument};
+pub use self::int::{CanonicalVarintEncoding, FixintEncoding, VarintEncoding};
+pub use self::leb128::{Leb128Encoding, PostcardEncoding};
+pub use self::limit::{Bounded, Infinite, LimitError, SizeLimit};
+pub use self::presence::{NicheOptions, PresenceBitmap, TaggedOptions};
+pub use self::structs::{CountedStructs, PlainStructs};
 pub use self::trailing::{AllowTrailing, RejectTrailing};
+pub use self::type_tags::{NoTypeTags, TypeTags};
+pub use self::usize::{NativeUsize, U32Usize, U64Usize};
+pub use self::variants::{RejectUnknownVariants, UnknownVariantAs};
 use crate::{
     deserialize::DeserializeError,
     serialize::SerializeError,
     traits::{CoreRead, CoreWrite},
 };
 
+mod bools;
+mod chars;
+mod depth;
+mod discriminant;
+mod element;
 mod endian;
+mod float;
+mod flush;
+mod format;
+mod hardening;
+#[cfg(feature = "instrument")]
+mod instrument;
 mod int;
 mod internal;
+mod leb128;
 mod limit;
+mod presence;
+mod structs;
 mod trailing;
+mod type_tags;
+mod usize;
+mod variants;
 
 /// The default options for bincode serialization/deserialization.
 ///
@@ -28,18 +75,26 @@ mod trailing;
 /// By default bincode will use little-endian encoding for multi-byte integers, and will not
 /// limit the number of serialized/deserialized bytes.
 #[derive(Copy, Clone)]
-pub struct DefaultOptions(Infinite);
+pub struct DefaultOptions {
+    limit: Infinite,
+    #[cfg(feature = "instrument")]
+    instrument: NoInstrument,
+}
 
 impl DefaultOptions {
     /// Get a default configuration object.
     ///
     /// ### Default Configuration:
     ///
-    /// | Byte limit | Endianness | Int Encoding | Trailing Behavior |
-    /// |------------|------------|--------------|-------------------|
-    /// | Unlimited  | Little     | Varint       | Reject            |
+    /// | Byte limit | Endianness | Int Encoding | Trailing Behavior | Discriminants |
+    /// |------------|------------|--------------|-------------------|---------------|
+    /// | Unlimited  | Little     | Varint       | Reject            | Int Encoding  |
     pub fn new() -> DefaultOptions {
-        DefaultOptions(Infinite)
+        DefaultOptions {
+            limit: Infinite,
+            #[cfg(feature = "instrument")]
+            instrument: NoInstrument,
+        }
     }
 }
 
@@ -51,16 +106,86 @@ impl Default for DefaultOptions {
 
 impl InternalOptions for DefaultOptions {
     type Limit = Infinite;
+    type ReadLimit = Infinite;
     type Endian = LittleEndian;
     type IntEncoding = VarintEncoding;
     type Trailing = RejectTrailing;
+    type Discriminant = IntDiscriminant;
+    type ElementLimit = UnboundedElements;
+    type Float = IeeeFloat;
+    type Hardening = StandardDecoding;
+    type DepthLimit = UnboundedDepth;
+    type Flush = AutoFlush;
+    type Presence = TaggedOptions;
+    type Bools = ByteBools;
+    type Chars = Utf8Char;
+    type Usize = U64Usize;
+    type TypeTags = NoTypeTags;
+    type Structs = PlainStructs;
+    type UnknownVariants = RejectUnknownVariants;
+    type Format = Bincode;
+    #[cfg(feature = "instrument")]
+    type Instrument = NoInstrument;
 
     #[inline(always)]
     fn limit(&mut self) -> &mut Infinite {
-        &mut self.0
+        &mut self.limit
+    }
+
+    #[inline(always)]
+    fn read_limit(&mut self) -> &mut Infinite {
+        &mut self.limit
+    }
+
+    #[inline(always)]
+    fn element_limit(&self) -> &UnboundedElements {
+        &UnboundedElements
+    }
+
+    #[inline(always)]
+    fn depth_limit(&self) -> &UnboundedDepth {
+        &UnboundedDepth
+    }
+
+    #[inline(always)]
+    fn unknown_variants(&self) -> &RejectUnknownVariants {
+        &RejectUnknownVariants
+    }
+
+    #[cfg(feature = "instrument")]
+    #[inline(always)]
+    fn instrument(&mut self) -> &mut NoInstrument {
+        &mut self.instrument
     }
 }
 
+/// Options that match the `bincode::serialize` and `bincode::deserialize` functions of bincode
+/// 1.x, and `bincode::config::legacy()` of bincode 2.x.
+///
+/// | Byte limit | Endianness | Int Encoding | Trailing Behavior | Discriminants |
+/// |------------|------------|--------------|-------------------|---------------|
+/// | Unlimited  | Little     | Fixint       | Allow             | Int Encoding  |
+///
+/// The `bincode::DefaultOptions` of bincode 1.x match [DefaultOptions].
+pub fn legacy() -> crate::compat::Legacy {
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .allow_trailing_bytes()
+}
+
+/// Options that match `bincode::config::standard()` of bincode 2.x.
+///
+/// | Byte limit | Endianness | Int Encoding | Trailing Behavior | Discriminants |
+/// |------------|------------|--------------|-------------------|---------------|
+/// | Unlimited  | Little     | Varint       | Allow             | Int Encoding  |
+///
+/// bincode 2.x does not check for trailing bytes, `decode_from_slice` returns the amount of bytes
+/// that were read instead. Use [deserialize_counted](crate::deserialize_counted) to get the same
+/// information.
+pub fn standard() -> crate::compat::Standard {
+    DefaultOptions::new().allow_trailing_bytes()
+}
+
 /// A configuration builder trait whose options Bincode will use
 /// while serializing and deserializing.
 ///
@@ -73,6 +198,26 @@ impl InternalOptions for DefaultOptions {
 ///
 /// Trailing Behavior: The behavior when there are trailing bytes left over in a slice after deserialization. *default: reject*
 ///
+/// Discriminant Encoding: The encoding used for enum variant indexes. *default: u32 with the int encoding*
+///
+/// Max Element Length: The maximum declared length of a single `&str`, `&[u8]`, sequence or map. *default: unlimited*
+///
+/// Float Encoding: The encoding used for `f32` and `f64`. *default: 4 or 8 bytes in the configured endianness*
+///
+/// Max Depth: The maximum nesting depth of sequences, maps, options and enums. *default: unlimited*
+///
+/// Decode Hardening: Extra validation for decoding untrusted input. *default: none*
+///
+/// Flush Policy: Whether [serialize](crate::serialize) flushes the writer when it is done. *default: flush*
+///
+/// Option Encoding: How the `Option` fields of a struct are encoded. *default: a tag byte per option*
+///
+/// Bool Encoding: How `bool` values are encoded. *default: one byte per bool*
+///
+/// Unknown Variants: How enum variant indexes that are not a variant of the enum are deserialized. *default: reject*
+///
+/// Format: Whether values are serialized as bincode, MessagePack or CBOR. *default: bincode*
+///
 /// ### Byte Limit Details
 /// The purpose of byte-limiting is to prevent Denial-Of-Service attacks whereby malicious attackers get bincode
 /// deserialization to crash your process by allocating too much memory or keeping a connection open for too long.
@@ -88,11 +233,62 @@ pub trait Options: InternalOptions + Sized {
         WithOtherLimit::new(self, Infinite)
     }
 
-    /// Sets the byte limit to `limit`.
+    /// Sets the byte limit to `limit`, for both serialization and deserialization.
     fn with_limit(self, limit: u64) -> WithOtherLimit<Self, Bounded> {
         WithOtherLimit::new(self, Bounded(limit))
     }
 
+    /// Sets the byte limit for deserialization to `limit`, and leaves the limit for serialization
+    /// as it is.
+    ///
+    /// ```
+    /// # use bincode_core::config::Options;
+    /// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+    /// // A small RX buffer, but no limit on the outgoing log stream
+    /// let options = DefaultOptions::new().with_read_limit(4);
+    ///
+    /// let mut buffer = [0u8; 16];
+    /// let mut writer = BufferWriter::new(&mut buffer);
+    /// serialize("a long line", &mut writer, options).unwrap();
+    ///
+    /// assert!(deserialize::<&str, _, _>(writer.written_buffer(), options).is_err());
+    /// assert_eq!(deserialize::<&str, _, _>(&[2, b'o', b'k'][..], options).unwrap(), "ok");
+    /// ```
+    fn with_read_limit(self, limit: u64) -> WithOtherReadLimit<Self, Bounded> {
+        WithOtherReadLimit::new(self, Bounded(limit))
+    }
+
+    /// Sets the byte limit for serialization to `limit`, and leaves the limit for deserialization
+    /// as it is.
+    fn with_write_limit(self, limit: u64) -> WithOtherWriteLimit<Self, Bounded> {
+        WithOtherWriteLimit::new(self, Bounded(limit))
+    }
+
+    /// Allows `&str`, `&[u8]`, sequences and maps of any declared length.
+    /// This is the default.
+    fn with_no_max_element_len(self) -> WithOtherElementLimit<Self, UnboundedElements> {
+        WithOtherElementLimit::new(self, UnboundedElements)
+    }
+
+    /// Rejects any single `&str`, `&[u8]`, sequence or map whose declared length is larger than
+    /// `max`, before any of its content is read.
+    fn with_max_element_len(self, max: usize) -> WithOtherElementLimit<Self, BoundedElements> {
+        WithOtherElementLimit::new(self, BoundedElements(max))
+    }
+
+    /// Allows sequences, maps, options and enums to be nested to any depth.
+    /// This is the default.
+    fn with_no_max_depth(self) -> WithOtherDepthLimit<Self, UnboundedDepth> {
+        WithOtherDepthLimit::new(self, UnboundedDepth)
+    }
+
+    /// Rejects input where sequences, maps, options and enums are nested more than `max` levels
+    /// deep, with [DeserializeError::DepthLimitExceeded]. Each level uses stack space while
+    /// deserializing, so this protects small stacks against deeply nested or recursive types.
+    fn with_max_depth(self, max: usize) -> WithOtherDepthLimit<Self, BoundedDepth> {
+        WithOtherDepthLimit::new(self, BoundedDepth(max))
+    }
+
     /// Sets the endianness to little-endian
     /// This is the default.
     fn with_little_endian(self) -> WithOtherEndian<Self, LittleEndian> {
@@ -104,6 +300,11 @@ pub trait Options: InternalOptions + Sized {
         WithOtherEndian::new(self)
     }
 
+    /// Sets the endianness to network byte order, which is big-endian
+    fn with_network_endian(self) -> WithOtherEndian<Self, NetworkEndian> {
+        WithOtherEndian::new(self)
+    }
+
     /// Sets the endianness to the the machine-native endianness
     fn with_native_endian(self) -> WithOtherEndian<Self, NativeEndian> {
         WithOtherEndian::new(self)
@@ -114,11 +315,27 @@ pub trait Options: InternalOptions + Sized {
         WithOtherIntEncoding::new(self)
     }
 
+    /// Sets the length encoding to varint, and rejects varints that are not encoded in their
+    /// shortest form while deserializing. See [CanonicalVarintEncoding].
+    fn with_canonical_varints(self) -> WithOtherIntEncoding<Self, CanonicalVarintEncoding> {
+        WithOtherIntEncoding::new(self)
+    }
+
     /// Sets the length encoding to be fixed
     fn with_fixint_encoding(self) -> WithOtherIntEncoding<Self, FixintEncoding> {
         WithOtherIntEncoding::new(self)
     }
 
+    /// Sets the length encoding to the LEB128 based varint encoding used by postcard
+    fn with_postcard_encoding(self) -> WithOtherIntEncoding<Self, PostcardEncoding> {
+        WithOtherIntEncoding::new(self)
+    }
+
+    /// Sets the length encoding to standard (signed and unsigned) LEB128
+    fn with_leb128_encoding(self) -> WithOtherIntEncoding<Self, Leb128Encoding> {
+        WithOtherIntEncoding::new(self)
+    }
+
     /// Sets the deserializer to reject trailing bytes
     fn reject_trailing_bytes(self) -> WithOtherTrailing<Self, RejectTrailing> {
         WithOtherTrailing::new(self)
@@ -129,7 +346,179 @@ pub trait Options: InternalOptions + Sized {
         WithOtherTrailing::new(self)
     }
 
+    /// Sets enum discriminants to be encoded as a u32 with the configured int encoding.
+    /// This is the default.
+    fn with_int_discriminants(self) -> WithOtherDiscriminant<Self, IntDiscriminant> {
+        WithOtherDiscriminant::new(self)
+    }
+
+    /// Sets enum discriminants to be encoded as a single byte
+    fn with_u8_discriminants(self) -> WithOtherDiscriminant<Self, U8Discriminant> {
+        WithOtherDiscriminant::new(self)
+    }
+
+    /// Sets enum discriminants to be encoded as a u16 with the configured int encoding
+    fn with_u16_discriminants(self) -> WithOtherDiscriminant<Self, U16Discriminant> {
+        WithOtherDiscriminant::new(self)
+    }
+
+    /// Sets floats to be encoded as 4 or 8 bytes in the configured endianness.
+    /// This is the default.
+    fn with_ieee_floats(self) -> WithOtherFloat<Self, IeeeFloat> {
+        WithOtherFloat::new(self)
+    }
+
+    /// Sets floats to be encoded as their raw bit pattern, as a u32 or u64 with the configured
+    /// int encoding
+    fn with_float_as_bits(self) -> WithOtherFloat<Self, FloatAsBits> {
+        WithOtherFloat::new(self)
+    }
+
+    /// Decodes without extra validation.
+    /// This is the default.
+    fn with_standard_decoding(self) -> WithOtherHardening<Self, StandardDecoding> {
+        WithOtherHardening::new(self)
+    }
+
+    /// Validates the input for use with untrusted data. See [HardenedDecoding].
+    fn with_hardened_decoding(self) -> WithOtherHardening<Self, HardenedDecoding> {
+        WithOtherHardening::new(self)
+    }
+
+    /// Flushes the writer after every successful [serialize](crate::serialize).
+    /// This is the default.
+    fn with_auto_flush(self) -> WithOtherFlush<Self, AutoFlush> {
+        WithOtherFlush::new(self)
+    }
+
+    /// Leaves flushing the writer to the caller. See [ManualFlush].
+    fn with_manual_flush(self) -> WithOtherFlush<Self, ManualFlush> {
+        WithOtherFlush::new(self)
+    }
+
+    /// Encodes every `Option` with a tag byte.
+    /// This is the default.
+    fn with_tagged_options(self) -> WithOtherPresence<Self, TaggedOptions> {
+        WithOtherPresence::new(self)
+    }
+
+    /// Encodes the `Option` fields of structs as a presence bitmap. See [PresenceBitmap].
+    fn with_presence_bitmap(self) -> WithOtherPresence<Self, PresenceBitmap> {
+        WithOtherPresence::new(self)
+    }
+
+    /// Encodes `None` as a zero byte and `Some` without a tag byte. See [NicheOptions].
+    fn with_niche_options(self) -> WithOtherPresence<Self, NicheOptions> {
+        WithOtherPresence::new(self)
+    }
+
+    /// Encodes every `bool` as a byte.
+    /// This is the default.
+    fn with_byte_bools(self) -> WithOtherBools<Self, ByteBools> {
+        WithOtherBools::new(self)
+    }
+
+    /// Packs consecutive `bool` values into a byte, 8 per byte. See [PackedBools].
+    fn with_packed_bools(self) -> WithOtherBools<Self, PackedBools> {
+        WithOtherBools::new(self)
+    }
+
+    /// Encodes every `char` as UTF-8.
+    /// This is the default.
+    fn with_utf8_chars(self) -> WithOtherChars<Self, Utf8Char> {
+        WithOtherChars::new(self)
+    }
+
+    /// Encodes every `char` as its code point, a `u32` with the configured int encoding. See
+    /// [U32Char].
+    fn with_u32_chars(self) -> WithOtherChars<Self, U32Char> {
+        WithOtherChars::new(self)
+    }
+
+    /// Encodes lengths as a u64 on every target.
+    /// This is the default.
+    fn with_u64_usize(self) -> WithOtherUsize<Self, U64Usize> {
+        WithOtherUsize::new(self)
+    }
+
+    /// Encodes lengths as a u32 on every target. See [U32Usize].
+    fn with_u32_usize(self) -> WithOtherUsize<Self, U32Usize> {
+        WithOtherUsize::new(self)
+    }
+
+    /// Encodes lengths with the pointer width of the target. See [NativeUsize].
+    fn with_native_usize(self) -> WithOtherUsize<Self, NativeUsize> {
+        WithOtherUsize::new(self)
+    }
+
+    /// Writes values without type tags.
+    /// This is the default.
+    fn with_no_type_tags(self) -> WithOtherTypeTags<Self, NoTypeTags> {
+        WithOtherTypeTags::new(self)
+    }
+
+    /// Writes a type tag in front of every value, so the format is self-describing. See
+    /// [TypeTags].
+    fn with_type_tags(self) -> WithOtherTypeTags<Self, TypeTags> {
+        WithOtherTypeTags::new(self)
+    }
+
+    /// Writes the fields of a struct one after the other.
+    /// This is the default.
+    fn with_plain_structs(self) -> WithOtherStructs<Self, PlainStructs> {
+        WithOtherStructs::new(self)
+    }
+
+    /// Writes every struct with its number of fields and their byte length, so structs can gain
+    /// new fields. See [CountedStructs].
+    fn with_counted_structs(self) -> WithOtherStructs<Self, CountedStructs> {
+        WithOtherStructs::new(self)
+    }
+
+    /// Fails on enum variant indexes that are not a variant of the enum.
+    /// This is the default.
+    fn reject_unknown_variants(self) -> WithOtherUnknownVariants<Self, RejectUnknownVariants> {
+        WithOtherUnknownVariants::new(self, RejectUnknownVariants)
+    }
+
+    /// Deserializes enum variant indexes that are not a variant of the enum as the variant named
+    /// `name`, so an older receiver accepts new variants. See [UnknownVariantAs].
+    fn with_unknown_variant_as(
+        self,
+        name: &'static str,
+    ) -> WithOtherUnknownVariants<Self, UnknownVariantAs> {
+        WithOtherUnknownVariants::new(self, UnknownVariantAs(name))
+    }
+
+    /// Serializes values as bincode.
+    /// This is the default.
+    fn with_bincode_format(self) -> WithOtherFormat<Self, Bincode> {
+        WithOtherFormat::new(self)
+    }
+
+    /// Serializes values as MessagePack, for hosts that decode them with a MessagePack library.
+    /// See [MsgPack].
+    fn with_msgpack_format(self) -> WithOtherFormat<Self, MsgPack> {
+        WithOtherFormat::new(self)
+    }
+
+    /// Serializes values as CBOR, for protocols like CoAP and LwM2M that require it. See [Cbor].
+    fn with_cbor_format(self) -> WithOtherFormat<Self, Cbor> {
+        WithOtherFormat::new(self)
+    }
+
+    /// Calls `instrument` while values are serialized. See [Instrument].
+    #[cfg(feature = "instrument")]
+    fn with_instrument<I: Instrument>(self, instrument: I) -> WithOtherInstrument<Self, I> {
+        WithOtherInstrument {
+            options: self,
+            new_instrument: instrument,
+        }
+    }
+
     /// Returns the size that an object would be if serialized using Bincode with this configuration
+    ///
+    /// If the size would exceed the size limit, [SerializeError::LimitReached] is returned.
     #[inline(always)]
     fn serialized_size<T: ?Sized + serde::Serialize>(
         self,
@@ -147,7 +536,7 @@ pub trait Options: InternalOptions + Sized {
         self,
         w: W,
         t: &T,
-    ) -> Result<(), SerializeError<W>> {
+    ) -> Result<(), SerializeError<W::Error>> {
         crate::serialize::serialize(t, w, self)
     }
 
@@ -194,10 +583,38 @@ impl<T: InternalOptions> Options for T {}
 /// A configuration struct with a user-specified byte limit
 #[derive(Clone, Copy)]
 pub struct WithOtherLimit<O: Options, L: SizeLimit> {
-    _options: O,
+    options: O,
     pub(crate) new_limit: L,
 }
 
+/// A configuration struct with a user-specified byte limit for deserialization
+#[derive(Clone, Copy)]
+pub struct WithOtherReadLimit<O: Options, L: SizeLimit> {
+    options: O,
+    new_read_limit: L,
+}
+
+/// A configuration struct with a user-specified byte limit for serialization
+#[derive(Clone, Copy)]
+pub struct WithOtherWriteLimit<O: Options, L: SizeLimit> {
+    options: O,
+    new_write_limit: L,
+}
+
+/// A configuration struct with a user-specified maximum element length
+#[derive(Clone, Copy)]
+pub struct WithOtherElementLimit<O: Options, E: ElementLimit> {
+    options: O,
+    pub(crate) new_element_limit: E,
+}
+
+/// A configuration struct with a user-specified maximum nesting depth
+#[derive(Clone, Copy)]
+pub struct WithOtherDepthLimit<O: Options, D: DepthLimit> {
+    options: O,
+    pub(crate) new_depth_limit: D,
+}
+
 /// A configuration struct with a user-specified endian order
 #[derive(Clone, Copy)]
 pub struct WithOtherEndian<O: Options, E: BincodeByteOrder> {
@@ -206,27 +623,167 @@ pub struct WithOtherEndian<O: Options, E: BincodeByteOrder> {
 }
 
 /// A configuration struct with a user-specified length encoding
+#[derive(Clone, Copy)]
 pub struct WithOtherIntEncoding<O: Options, I: IntEncoding> {
     options: O,
     _length: PhantomData<I>,
 }
 
 /// A configuration struct with a user-specified trailing bytes behavior.
+#[derive(Clone, Copy)]
 pub struct WithOtherTrailing<O: Options, T: TrailingBytes> {
     options: O,
     _trailing: PhantomData<T>,
 }
 
+/// A configuration struct with a user-specified enum discriminant encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherDiscriminant<O: Options, D: DiscriminantEncoding> {
+    options: O,
+    _discriminant: PhantomData<D>,
+}
+
+/// A configuration struct with a user-specified float encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherFloat<O: Options, F: FloatEncoding> {
+    options: O,
+    _float: PhantomData<F>,
+}
+
+/// A configuration struct with a user-specified decode hardening.
+#[derive(Clone, Copy)]
+pub struct WithOtherHardening<O: Options, H: DecodeHardening> {
+    options: O,
+    _hardening: PhantomData<H>,
+}
+
+/// A configuration struct with a user-specified flush policy.
+#[derive(Clone, Copy)]
+pub struct WithOtherFlush<O: Options, F: FlushPolicy> {
+    options: O,
+    _flush: PhantomData<F>,
+}
+
+/// A configuration struct with a user-specified option encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherPresence<O: Options, P: OptionEncoding> {
+    options: O,
+    _presence: PhantomData<P>,
+}
+
+/// A configuration struct with a user-specified bool encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherBools<O: Options, B: BoolEncoding> {
+    options: O,
+    _bools: PhantomData<B>,
+}
+
+/// A configuration struct with a user-specified char encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherChars<O: Options, C: CharEncoding> {
+    options: O,
+    _chars: PhantomData<C>,
+}
+
+/// A configuration struct with a user-specified [Instrument].
+#[cfg(feature = "instrument")]
+#[derive(Clone, Copy)]
+pub struct WithOtherInstrument<O: Options, I: Instrument> {
+    options: O,
+    new_instrument: I,
+}
+
+/// A configuration struct with a user-specified length encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherUsize<O: Options, U: UsizeEncoding> {
+    options: O,
+    _usize: PhantomData<U>,
+}
+
+/// A configuration struct with user-specified type tags.
+#[derive(Clone, Copy)]
+pub struct WithOtherTypeTags<O: Options, T: TypeTagEncoding> {
+    options: O,
+    _tags: PhantomData<T>,
+}
+
+/// A configuration struct with a user-specified struct encoding.
+#[derive(Clone, Copy)]
+pub struct WithOtherStructs<O: Options, S: StructEncoding> {
+    options: O,
+    _structs: PhantomData<S>,
+}
+
+/// A configuration struct with a user-specified behavior for unknown enum variants.
+#[derive(Clone, Copy)]
+pub struct WithOtherUnknownVariants<O: Options, U: UnknownVariants> {
+    options: O,
+    pub(crate) new_unknown_variants: U,
+}
+
+/// A configuration struct with a user-specified serialization format.
+#[derive(Clone, Copy)]
+pub struct WithOtherFormat<O: Options, F: Format> {
+    options: O,
+    _format: PhantomData<F>,
+}
+
 impl<O: Options, L: SizeLimit> WithOtherLimit<O, L> {
+    /// Use `limit` as the byte limit of `options`. Unlike [Options::with_limit], this accepts any
+    /// [SizeLimit], including your own implementations.
     #[inline(always)]
-    pub(crate) fn new(options: O, limit: L) -> WithOtherLimit<O, L> {
+    pub fn new(options: O, limit: L) -> WithOtherLimit<O, L> {
         WithOtherLimit {
-            _options: options,
+            options,
             new_limit: limit,
         }
     }
 }
 
+impl<O: Options, L: SizeLimit> WithOtherReadLimit<O, L> {
+    /// Use `limit` as the byte limit for deserialization. Unlike [Options::with_read_limit], this
+    /// accepts any [SizeLimit], including your own implementations.
+    #[inline(always)]
+    pub fn new(options: O, limit: L) -> WithOtherReadLimit<O, L> {
+        WithOtherReadLimit {
+            options,
+            new_read_limit: limit,
+        }
+    }
+}
+
+impl<O: Options, L: SizeLimit> WithOtherWriteLimit<O, L> {
+    /// Use `limit` as the byte limit for serialization. Unlike [Options::with_write_limit], this
+    /// accepts any [SizeLimit], including your own implementations.
+    #[inline(always)]
+    pub fn new(options: O, limit: L) -> WithOtherWriteLimit<O, L> {
+        WithOtherWriteLimit {
+            options,
+            new_write_limit: limit,
+        }
+    }
+}
+
+impl<O: Options, E: ElementLimit> WithOtherElementLimit<O, E> {
+    #[inline(always)]
+    pub(crate) fn new(options: O, element_limit: E) -> WithOtherElementLimit<O, E> {
+        WithOtherElementLimit {
+            options,
+            new_element_limit: element_limit,
+        }
+    }
+}
+
+impl<O: Options, D: DepthLimit> WithOtherDepthLimit<O, D> {
+    #[inline(always)]
+    pub(crate) fn new(options: O, depth_limit: D) -> WithOtherDepthLimit<O, D> {
+        WithOtherDepthLimit {
+            options,
+            new_depth_limit: depth_limit,
+        }
+    }
+}
+
 impl<O: Options, E: BincodeByteOrder> WithOtherEndian<O, E> {
     #[inline(always)]
     pub(crate) fn new(options: O) -> WithOtherEndian<O, E> {
@@ -259,43 +816,1148 @@ impl<O: Options, T: TrailingBytes> WithOtherTrailing<O, T> {
 
 impl<O: Options, E: BincodeByteOrder + 'static> InternalOptions for WithOtherEndian<O, E> {
     type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
     type Endian = E;
     type IntEncoding = O::IntEncoding;
     type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
     #[inline(always)]
     fn limit(&mut self) -> &mut O::Limit {
         self.options.limit()
     }
+
+    #[inline(always)]
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
 }
 
 impl<O: Options, L: SizeLimit + 'static> InternalOptions for WithOtherLimit<O, L> {
     type Limit = L;
+    type ReadLimit = L;
     type Endian = O::Endian;
     type IntEncoding = O::IntEncoding;
     type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
     fn limit(&mut self) -> &mut L {
         &mut self.new_limit
     }
-}
 
-impl<O: Options, I: IntEncoding + 'static> InternalOptions for WithOtherIntEncoding<O, I> {
-    type Limit = O::Limit;
-    type Endian = O::Endian;
-    type IntEncoding = I;
-    type Trailing = O::Trailing;
+    fn read_limit(&mut self) -> &mut L {
+        &mut self.new_limit
+    }
 
-    fn limit(&mut self) -> &mut O::Limit {
-        self.options.limit()
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
     }
-}
 
-impl<O: Options, T: TrailingBytes + 'static> InternalOptions for WithOtherTrailing<O, T> {
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, L: SizeLimit + 'static> InternalOptions for WithOtherReadLimit<O, L> {
+    type Limit = O::Limit;
+    type ReadLimit = L;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut L {
+        &mut self.new_read_limit
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, L: SizeLimit + 'static> InternalOptions for WithOtherWriteLimit<O, L> {
+    type Limit = L;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+    fn limit(&mut self) -> &mut L {
+        &mut self.new_write_limit
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, E: ElementLimit + 'static> InternalOptions for WithOtherElementLimit<O, E> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = E;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &E {
+        &self.new_element_limit
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, D: DepthLimit + 'static> InternalOptions for WithOtherDepthLimit<O, D> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = D;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &D {
+        &self.new_depth_limit
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, I: IntEncoding + 'static> InternalOptions for WithOtherIntEncoding<O, I> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = I;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, T: TrailingBytes + 'static> InternalOptions for WithOtherTrailing<O, T> {
     type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
     type Endian = O::Endian;
     type IntEncoding = O::IntEncoding;
     type Trailing = T;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, D: DiscriminantEncoding> WithOtherDiscriminant<O, D> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherDiscriminant<O, D> {
+        WithOtherDiscriminant {
+            options,
+            _discriminant: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, D: DiscriminantEncoding + 'static> InternalOptions
+    for WithOtherDiscriminant<O, D>
+{
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = D;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, F: FloatEncoding> WithOtherFloat<O, F> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherFloat<O, F> {
+        WithOtherFloat {
+            options,
+            _float: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, F: FloatEncoding + 'static> InternalOptions for WithOtherFloat<O, F> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = F;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, H: DecodeHardening> WithOtherHardening<O, H> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherHardening<O, H> {
+        WithOtherHardening {
+            options,
+            _hardening: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, H: DecodeHardening + 'static> InternalOptions for WithOtherHardening<O, H> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = H;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, F: FlushPolicy> WithOtherFlush<O, F> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherFlush<O, F> {
+        WithOtherFlush {
+            options,
+            _flush: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, F: FlushPolicy + 'static> InternalOptions for WithOtherFlush<O, F> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = F;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, P: OptionEncoding> WithOtherPresence<O, P> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherPresence<O, P> {
+        WithOtherPresence {
+            options,
+            _presence: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, P: OptionEncoding + 'static> InternalOptions for WithOtherPresence<O, P> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = P;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, B: BoolEncoding> WithOtherBools<O, B> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherBools<O, B> {
+        WithOtherBools {
+            options,
+            _bools: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, B: BoolEncoding + 'static> InternalOptions for WithOtherBools<O, B> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = B;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, C: CharEncoding> WithOtherChars<O, C> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherChars<O, C> {
+        WithOtherChars {
+            options,
+            _chars: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, C: CharEncoding + 'static> InternalOptions for WithOtherChars<O, C> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = C;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, U: UsizeEncoding> WithOtherUsize<O, U> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherUsize<O, U> {
+        WithOtherUsize {
+            options,
+            _usize: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, U: UsizeEncoding + 'static> InternalOptions for WithOtherUsize<O, U> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = U;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, T: TypeTagEncoding> WithOtherTypeTags<O, T> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherTypeTags<O, T> {
+        WithOtherTypeTags {
+            options,
+            _tags: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, T: TypeTagEncoding + 'static> InternalOptions for WithOtherTypeTags<O, T> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = T;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, S: StructEncoding> WithOtherStructs<O, S> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherStructs<O, S> {
+        WithOtherStructs {
+            options,
+            _structs: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, S: StructEncoding + 'static> InternalOptions for WithOtherStructs<O, S> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = S;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, U: UnknownVariants> WithOtherUnknownVariants<O, U> {
+    #[inline(always)]
+    pub(crate) fn new(options: O, unknown_variants: U) -> WithOtherUnknownVariants<O, U> {
+        WithOtherUnknownVariants {
+            options,
+            new_unknown_variants: unknown_variants,
+        }
+    }
+}
+
+impl<O: Options, U: UnknownVariants + 'static> InternalOptions for WithOtherUnknownVariants<O, U> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = U;
+    type Format = O::Format;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
 
     fn limit(&mut self) -> &mut O::Limit {
         self.options.limit()
     }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &U {
+        &self.new_unknown_variants
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+impl<O: Options, F: Format> WithOtherFormat<O, F> {
+    #[inline(always)]
+    pub(crate) fn new(options: O) -> WithOtherFormat<O, F> {
+        WithOtherFormat {
+            options,
+            _format: PhantomData,
+        }
+    }
+}
+
+impl<O: Options, F: Format + 'static> InternalOptions for WithOtherFormat<O, F> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = F;
+    #[cfg(feature = "instrument")]
+    type Instrument = O::Instrument;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    #[cfg(feature = "instrument")]
+    fn instrument(&mut self) -> &mut O::Instrument {
+        self.options.instrument()
+    }
+}
+
+#[cfg(feature = "instrument")]
+impl<O: Options, I: Instrument> InternalOptions for WithOtherInstrument<O, I> {
+    type Limit = O::Limit;
+    type ReadLimit = O::ReadLimit;
+    type Endian = O::Endian;
+    type IntEncoding = O::IntEncoding;
+    type Trailing = O::Trailing;
+    type Discriminant = O::Discriminant;
+    type ElementLimit = O::ElementLimit;
+    type Float = O::Float;
+    type Hardening = O::Hardening;
+    type DepthLimit = O::DepthLimit;
+    type Flush = O::Flush;
+    type Presence = O::Presence;
+    type Bools = O::Bools;
+    type Chars = O::Chars;
+    type Usize = O::Usize;
+    type TypeTags = O::TypeTags;
+    type Structs = O::Structs;
+    type UnknownVariants = O::UnknownVariants;
+    type Format = O::Format;
+    type Instrument = I;
+
+    fn limit(&mut self) -> &mut O::Limit {
+        self.options.limit()
+    }
+
+    fn read_limit(&mut self) -> &mut O::ReadLimit {
+        self.options.read_limit()
+    }
+
+    fn element_limit(&self) -> &O::ElementLimit {
+        self.options.element_limit()
+    }
+
+    fn depth_limit(&self) -> &O::DepthLimit {
+        self.options.depth_limit()
+    }
+
+    fn unknown_variants(&self) -> &O::UnknownVariants {
+        self.options.unknown_variants()
+    }
+
+    fn instrument(&mut self) -> &mut I {
+        &mut self.new_instrument
+    }
 }
@@ -0,0 +1,93 @@
+/// A trait for encoding the `Option` fields of a struct.
+pub trait OptionEncoding {
+    /// Whether the `Option` fields of a struct are encoded as a bitmap in front of the struct.
+    const PRESENCE_BITMAP: bool;
+
+    /// Whether `None` is encoded as a zero byte, and `Some` as the value without a tag.
+    const NICHE: bool;
+}
+
+/// Every `Option` is encoded with a tag byte of `0` for `None` or `1` for `Some`, followed by the
+/// value.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct TaggedOptions;
+
+/// Every struct starts with a presence bitmap, with one bit for each of its first 64 fields. For
+/// an `Option` field, the bit is set if it is `Some`, and only the value is written. `None` takes
+/// no space at all. The bits of other fields are always zero.
+///
+/// The bitmap takes `ceil(fields / 8)` bytes, bit `i % 8` of byte `i / 8` belongs to field `i`.
+/// Fields after the 64th, and options outside of structs (like the items of a `Vec<Option<T>>` or
+/// the fields of an enum variant) are encoded with a tag byte.
+///
+/// For sparse structs with many optional fields, like telemetry frames, this is a lot smaller. A
+/// struct without any `Option` fields gets larger, so this is best used for a dedicated message
+/// type.
+///
+/// The bitmap is computed from the value before it is written, so values have to be written with
+/// [serialize](crate::serialize), [serialize_all](crate::serialize_all) or
+/// [Serializer::serialize_value](crate::Serializer::serialize_value). Serializing a struct with
+/// `value.serialize(&mut serializer)` fails with
+/// [SerializeError::PresenceUnknown](crate::SerializeError::PresenceUnknown).
+#[derive(Copy, Clone)]
+pub struct PresenceBitmap;
+
+/// `None` is encoded as a single zero byte, and `Some` as just the value, without a tag byte.
+///
+/// This saves a byte for every `Some`, for values that never start with a zero byte. The intended
+/// use is `Option<NonZeroU32>` and similar wrappers with [VarintEncoding](super::VarintEncoding),
+/// where the zero that the type cannot hold is exactly the encoding of `None`. With
+/// [FixintEncoding](super::FixintEncoding), a value like `256u32` starts with a zero byte as well.
+///
+/// Serializing a `Some` whose value starts with a zero byte or writes no bytes at all, like
+/// `Some(0u8)`, `Some("")` or `Some(())`, fails with
+/// [SerializeError::InvalidNiche](crate::SerializeError::InvalidNiche), because it would be read
+/// back as `None`. So does `Some(bool)` with [PackedBools](super::PackedBools).
+///
+/// Deserializing has to look at the next byte to tell `None` and `Some` apart, so the reader has
+/// to support [peek](crate::CoreRead::peek), like `&[u8]`, [SliceReader](crate::SliceReader) and
+/// [ChainedSlices](crate::ChainedSlices). Other readers fail with
+/// [DeserializeError::Unsupported](crate::DeserializeError::Unsupported).
+///
+/// ```
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+/// # use core::num::NonZeroU32;
+/// let options = DefaultOptions::new().with_niche_options();
+/// let values = [NonZeroU32::new(7), None];
+///
+/// let mut buffer = [0u8; 8];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&values, &mut writer, options).unwrap();
+/// assert_eq!(writer.written_buffer(), &[7, 0]);
+///
+/// let decoded: [Option<NonZeroU32>; 2] = deserialize(writer.written_buffer(), options).unwrap();
+/// assert_eq!(decoded, values);
+/// ```
+#[derive(Copy, Clone)]
+pub struct NicheOptions;
+
+impl OptionEncoding for TaggedOptions {
+    const PRESENCE_BITMAP: bool = false;
+    const NICHE: bool = false;
+}
+
+impl OptionEncoding for PresenceBitmap {
+    const PRESENCE_BITMAP: bool = true;
+    const NICHE: bool = false;
+}
+
+impl OptionEncoding for NicheOptions {
+    const PRESENCE_BITMAP: bool = false;
+    const NICHE: bool = true;
+}
+
+/// The amount of fields that are covered by the presence bitmap.
+pub(crate) const BITMAP_FIELDS: usize = 64;
+
+/// The size of the presence bitmap of a struct with `len` fields.
+pub(crate) fn bitmap_len(len: usize) -> usize {
+    len.min(BITMAP_FIELDS).div_ceil(8)
+}
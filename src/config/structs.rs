@@ -0,0 +1,82 @@
+/// A trait for encoding the fields of a struct.
+pub trait StructEncoding {
+    /// Whether every struct starts with its number of fields and the byte length of its fields.
+    const COUNTED: bool;
+}
+
+/// The fields of a struct are written one after the other, like a tuple. Decoders have to know
+/// exactly the same fields as the encoder.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct PlainStructs;
+
+/// Every struct, and the content of every enum variant, starts with its number of fields and the
+/// byte length of its fields, both encoded like a length. This lets structs gain new fields at the
+/// end without breaking the wire format between firmware revisions:
+///
+/// - A decoder that knows fewer fields than the encoder reads the fields it knows, and skips the
+///   bytes of the rest.
+/// - A decoder that knows more fields than the encoder sees the missing fields as the end of the
+///   struct, so they can be filled in with `#[serde(default)]`.
+///
+/// Fields can only be added at the end, and never removed or reordered. Tuples and tuple structs
+/// are written as before. A newtype variant has one field, and a unit variant has none. The byte
+/// length also lets an older decoder skip an enum variant it does not know, see
+/// [UnknownVariantAs](super::UnknownVariantAs).
+///
+/// With a writer that can seek, like a [BufferWriter](crate::BufferWriter), the byte length is
+/// written after the fields, over a placeholder in front of them. If the length needs more bytes
+/// than the placeholder, the fields are moved.
+///
+/// Other writers cannot go back, so the byte length is measured before the struct is written, and
+/// values have to be written with [serialize](crate::serialize),
+/// [serialize_all](crate::serialize_all) or
+/// [Serializer::serialize_value](crate::Serializer::serialize_value). Serializing a struct with
+/// `value.serialize(&mut serializer)` fails with
+/// [SerializeError::PresenceUnknown](crate::SerializeError::PresenceUnknown). Every struct is
+/// measured again for each struct it is nested in, which adds up for deeply nested structs.
+///
+/// With [TypeTags](super::TypeTags), structs are already written with their number of fields, and
+/// this has no effect.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct ReportV1 {
+///     uptime: u32,
+/// }
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct ReportV2<'a> {
+///     uptime: u32,
+///     #[serde(default)]
+///     firmware: &'a str,
+/// }
+///
+/// let options = DefaultOptions::new().with_counted_structs();
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&ReportV2 { uptime: 60, firmware: "1.2" }, &mut writer, options).unwrap();
+/// assert_eq!(writer.written_buffer(), &[2, 5, 60, 3, b'1', b'.', b'2']);
+///
+/// let old: ReportV1 = deserialize(writer.written_buffer(), options).unwrap();
+/// assert_eq!(old, ReportV1 { uptime: 60 });
+///
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&ReportV1 { uptime: 60 }, &mut writer, options).unwrap();
+/// let new: ReportV2 = deserialize(writer.written_buffer(), options).unwrap();
+/// assert_eq!(new, ReportV2 { uptime: 60, firmware: "" });
+/// ```
+#[derive(Copy, Clone)]
+pub struct CountedStructs;
+
+impl StructEncoding for PlainStructs {
+    const COUNTED: bool = false;
+}
+
+impl StructEncoding for CountedStructs {
+    const COUNTED: bool = true;
+}
@@ -1,7 +1,10 @@
+use crate::deserialize::DeserializeError;
+use crate::traits::CoreRead;
+
 /// A trait for erroring deserialization if not all bytes were read.
 pub trait TrailingBytes {
-    // /// Checks a given slice reader to determine if deserialization used all bytes in the slice.
-    // fn check_end(reader: &SliceReader) -> Result<()>;
+    /// Checks a given reader to determine if deserialization used all bytes in the reader.
+    fn check_end<'a, R: CoreRead<'a>>(reader: &R) -> Result<(), DeserializeError<'a, R>>;
 }
 
 /// A TrailingBytes config that will allow trailing bytes in slices after deserialization.
@@ -9,26 +12,25 @@ pub trait TrailingBytes {
 pub struct AllowTrailing;
 
 /// A TrailingBytes config that will cause bincode to produce an error if bytes are left over in the slice when deserialization is complete.
-
+///
+/// Readers that do not know how many bytes they have left (see [CoreRead::remaining_len]) are never
+/// rejected.
 #[derive(Copy, Clone)]
 pub struct RejectTrailing;
 
 impl TrailingBytes for AllowTrailing {
-    // #[inline(always)]
-    // fn check_end(_reader: &SliceReader) -> Result<()> {
-    //     Ok(())
-    // }
+    #[inline(always)]
+    fn check_end<'a, R: CoreRead<'a>>(_reader: &R) -> Result<(), DeserializeError<'a, R>> {
+        Ok(())
+    }
 }
 
 impl TrailingBytes for RejectTrailing {
-    // #[inline(always)]
-    // fn check_end(reader: &SliceReader) -> Result<()> {
-    //     if reader.is_finished() {
-    //         Ok(())
-    //     } else {
-    //         Err(box_new(ErrorKind::Custom(
-    //             "Slice had bytes remaining after deserialization".into(),
-    //         )))
-    //     }
-    // }
+    #[inline(always)]
+    fn check_end<'a, R: CoreRead<'a>>(reader: &R) -> Result<(), DeserializeError<'a, R>> {
+        match reader.remaining_len() {
+            Some(len) if len > 0 => Err(DeserializeError::TrailingBytes),
+            _ => Ok(()),
+        }
+    }
 }
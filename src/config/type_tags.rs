@@ -0,0 +1,136 @@
+/// A trait for writing a type tag in front of every value.
+pub trait TypeTagEncoding {
+    /// Whether every value is preceded by a [TypeTag].
+    const TAGGED: bool;
+}
+
+/// Values are written without type tags, so the format is not self-describing.
+///
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct NoTypeTags;
+
+/// Every value is preceded by a one byte type tag, which makes the format self-describing. This
+/// lets `deserialize_any` and `serde::de::IgnoredAny` work, e.g. for `#[serde(untagged)]` enums,
+/// or to skip values of an unknown type.
+///
+/// Structs and tuples are written with their number of fields, like a sequence, instead of a
+/// presence bitmap. A reader that knows fewer fields than the writer skips the fields at the end
+/// that it does not know, so a struct can grow new fields while older readers are still deployed.
+/// A reader that knows more fields sees the missing fields as the end of the struct, so they can be
+/// filled in with `#[serde(default)]`.
+///
+/// The tags are:
+///
+/// | Tag | Value | Followed by |
+/// |-----|-------|-------------|
+/// | 1 | `()`, unit struct | - |
+/// | 2 | `bool` | the bool |
+/// | 3 - 7 | `u8`, `u16`, `u32`, `u64`, `u128` | the int |
+/// | 8 - 12 | `i8`, `i16`, `i32`, `i64`, `i128` | the int |
+/// | 13, 14 | `f32`, `f64` | the float |
+/// | 15 | `char` | the char |
+/// | 16 | `&str` | the length and the bytes |
+/// | 17 | `&[u8]` | the length and the bytes |
+/// | 18 | `None` | - |
+/// | 19 | `Some` | the tagged value |
+/// | 20 | sequence, tuple, struct | the length and the tagged items |
+/// | 21 | map | the length and the tagged keys and values |
+/// | 22 | enum | the discriminant and the tagged content, `()` for a unit variant |
+///
+/// Newtype structs are written as their content. `None` and `Some` are always tagged, regardless
+/// of the [OptionEncoding](super::Options::with_niche_options).
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+/// #[derive(Serialize)]
+/// struct ReadingV2<'a> {
+///     sensor: u8,
+///     value: i32,
+///     unit: &'a str,
+/// }
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct ReadingV1 {
+///     sensor: u8,
+///     value: i32,
+/// }
+///
+/// let options = DefaultOptions::new().with_type_tags();
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// let reading = ReadingV2 { sensor: 3, value: -40, unit: "C" };
+/// serialize(&reading, &mut writer, options).unwrap();
+///
+/// let decoded: ReadingV1 = deserialize(writer.written_buffer(), options).unwrap();
+/// assert_eq!(decoded, ReadingV1 { sensor: 3, value: -40 });
+/// ```
+#[derive(Copy, Clone)]
+pub struct TypeTags;
+
+impl TypeTagEncoding for NoTypeTags {
+    const TAGGED: bool = false;
+}
+
+impl TypeTagEncoding for TypeTags {
+    const TAGGED: bool = true;
+}
+
+/// The tag that [TypeTags] writes in front of a value.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(crate) enum TypeTag {
+    Unit = 1,
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    F32,
+    F64,
+    Char,
+    Str,
+    Bytes,
+    None,
+    Some,
+    Seq,
+    Map,
+    Enum,
+}
+
+impl TypeTag {
+    pub(crate) fn from_byte(byte: u8) -> Option<TypeTag> {
+        const TAGS: [TypeTag; 22] = [
+            TypeTag::Unit,
+            TypeTag::Bool,
+            TypeTag::U8,
+            TypeTag::U16,
+            TypeTag::U32,
+            TypeTag::U64,
+            TypeTag::U128,
+            TypeTag::I8,
+            TypeTag::I16,
+            TypeTag::I32,
+            TypeTag::I64,
+            TypeTag::I128,
+            TypeTag::F32,
+            TypeTag::F64,
+            TypeTag::Char,
+            TypeTag::Str,
+            TypeTag::Bytes,
+            TypeTag::None,
+            TypeTag::Some,
+            TypeTag::Seq,
+            TypeTag::Map,
+            TypeTag::Enum,
+        ];
+        TAGS.get(usize::from(byte).wrapping_sub(1)).copied()
+    }
+}
@@ -0,0 +1,118 @@
+use super::{IntEncoding, Options};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+use core::convert::TryFrom;
+
+/// A trait for encoding the lengths of `&str`, `&[u8]`, sequences and maps.
+pub trait UsizeEncoding {
+    /// Gets the size (in bytes) that a length would be serialized to.
+    fn len_size<O: Options>(len: usize) -> usize;
+
+    /// Serializes a length.
+    fn serialize_len<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>>;
+
+    /// Deserializes a length.
+    fn deserialize_len<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<usize, DeserializeError<'de, R>>;
+}
+
+/// Lengths are encoded as a u64 with the configured integer encoding, on every target.
+///
+/// This is the default, and the portable choice: a 32-bit device and a 64-bit host read each
+/// other's data. A length that does not fit in the `usize` of the reading target fails with
+/// [DeserializeError::InvalidCast].
+#[derive(Copy, Clone)]
+pub struct U64Usize;
+
+/// Lengths are encoded as a u32 with the configured integer encoding, on every target.
+///
+/// With [FixintEncoding](super::FixintEncoding), this saves 4 bytes for every length, and a 64-bit
+/// host writes the same bytes as a 32-bit device. Serializing a length larger than `u32::MAX`
+/// fails with [SerializeError::LengthTooLarge], instead of producing data that a 32-bit peer
+/// cannot read.
+#[derive(Copy, Clone)]
+pub struct U32Usize;
+
+/// Lengths are encoded with the pointer width of the target: [U32Usize] on 16 and 32-bit targets,
+/// [U64Usize] on 64-bit targets.
+///
+/// This matches the in-memory `usize` of the target, but data written by a 64-bit host cannot be
+/// read by a 32-bit device with [FixintEncoding](super::FixintEncoding), and vice versa. Only use
+/// this when both sides have the same pointer width, and prefer [U32Usize] otherwise.
+///
+/// `usize` and `isize` values are not affected: serde passes them to the serializer as a `u64`
+/// and `i64`, so they are always encoded as such. A value that does not fit in the `usize` of the
+/// reading target fails to deserialize.
+#[cfg(not(target_pointer_width = "64"))]
+pub type NativeUsize = U32Usize;
+
+/// Lengths are encoded with the pointer width of the target: [U32Usize] on 16 and 32-bit targets,
+/// [U64Usize] on 64-bit targets.
+///
+/// This matches the in-memory `usize` of the target, but data written by a 64-bit host cannot be
+/// read by a 32-bit device with [FixintEncoding](super::FixintEncoding), and vice versa. Only use
+/// this when both sides have the same pointer width, and prefer [U32Usize] otherwise.
+///
+/// `usize` and `isize` values are not affected: serde passes them to the serializer as a `u64`
+/// and `i64`, so they are always encoded as such. A value that does not fit in the `usize` of the
+/// reading target fails to deserialize.
+#[cfg(target_pointer_width = "64")]
+pub type NativeUsize = U64Usize;
+
+impl UsizeEncoding for U64Usize {
+    #[inline(always)]
+    fn len_size<O: Options>(len: usize) -> usize {
+        O::IntEncoding::u64_size(len as u64)
+    }
+
+    #[inline(always)]
+    fn serialize_len<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>> {
+        O::IntEncoding::serialize_u64(ser, len as u64)
+    }
+
+    #[inline(always)]
+    fn deserialize_len<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<usize, DeserializeError<'de, R>> {
+        let len = O::IntEncoding::deserialize_u64(de)?;
+        usize::try_from(len).map_err(|_| DeserializeError::InvalidCast {
+            from_type: "u64",
+            to_type: "usize",
+        })
+    }
+}
+
+impl UsizeEncoding for U32Usize {
+    #[inline(always)]
+    fn len_size<O: Options>(len: usize) -> usize {
+        O::IntEncoding::u32_size(len as u32)
+    }
+
+    #[inline(always)]
+    fn serialize_len<W: CoreWrite, O: Options>(
+        ser: &mut Serializer<W, O>,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>> {
+        let len = u32::try_from(len).map_err(|_| SerializeError::LengthTooLarge(len))?;
+        O::IntEncoding::serialize_u32(ser, len)
+    }
+
+    #[inline(always)]
+    fn deserialize_len<'de, R: CoreRead<'de>, O: Options>(
+        de: &mut Deserializer<'de, R, O>,
+    ) -> Result<usize, DeserializeError<'de, R>> {
+        let len = O::IntEncoding::deserialize_u32(de)?;
+        usize::try_from(len).map_err(|_| DeserializeError::InvalidCast {
+            from_type: "u32",
+            to_type: "usize",
+        })
+    }
+}
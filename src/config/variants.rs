@@ -0,0 +1,66 @@
+/// A trait for what happens when the variant index of an enum is not a variant of the enum, e.g.
+/// because the sender has a newer version of the enum.
+pub trait UnknownVariants {
+    /// The name of the variant that unknown variants are deserialized as (if one exists)
+    fn fallback(&self) -> Option<&'static str>;
+}
+
+/// An UnknownVariants that fails with [Custom](crate::DeserializeError::Custom) on an unknown
+/// variant index.
+/// This is the default.
+#[derive(Copy, Clone)]
+pub struct RejectUnknownVariants;
+
+/// An UnknownVariants that deserializes an unknown variant index as the variant with the given
+/// name, in the enums that have a variant with that name. Enums without such a variant still
+/// reject unknown variants.
+///
+/// The content of the unknown variant is skipped, so the rest of the message is read correctly.
+/// With [TypeTags](super::TypeTags), the content is skipped like any tagged value, and with
+/// [CountedStructs](super::CountedStructs), it is skipped with the byte length in front of it.
+/// Otherwise the length of the content is not known, so the fallback is only used if nothing
+/// follows the variant index in the input, and the variant is rejected anywhere else. The fallback
+/// variant must be a unit variant.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, DefaultOptions};
+/// // Newer firmware
+/// #[derive(Serialize)]
+/// enum ModeV2 {
+///     Idle,
+///     Run,
+///     Unknown,
+///     Turbo,
+/// }
+///
+/// // Older firmware
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// enum Mode {
+///     Idle,
+///     Run,
+///     Unknown,
+/// }
+///
+/// let buffer = [3u8]; // ModeV2::Turbo
+/// let options = DefaultOptions::new().with_unknown_variant_as("Unknown");
+/// assert_eq!(deserialize::<Mode, _, _>(&buffer[..], options).unwrap(), Mode::Unknown);
+/// assert!(deserialize::<Mode, _, _>(&buffer[..], DefaultOptions::new()).is_err());
+/// ```
+#[derive(Copy, Clone)]
+pub struct UnknownVariantAs(pub &'static str);
+
+impl UnknownVariants for RejectUnknownVariants {
+    #[inline(always)]
+    fn fallback(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+impl UnknownVariants for UnknownVariantAs {
+    #[inline(always)]
+    fn fallback(&self) -> Option<&'static str> {
+        Some(self.0)
+    }
+}
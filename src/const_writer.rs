@@ -0,0 +1,317 @@
+use crate::compat::{SINGLE_BYTE_MAX, U128_BYTE, U16_BYTE, U32_BYTE, U64_BYTE};
+
+/// A serializer for plain-old-data that runs at compile time.
+///
+/// serde cannot be used in a `const` context, so this writer has a `const fn` for every primitive
+/// type instead. The fields of a struct are written one by one, in declaration order, which gives
+/// the same bytes as [serialize](crate::serialize). This way a default configuration blob can be
+/// stored in flash as a `const` array, instead of being serialized at boot.
+///
+/// Like [DefaultOptions](crate::DefaultOptions), integers are encoded as little-endian varints by
+/// default. The encoding can be changed with the builder methods that have the same name as the
+/// ones in [Options](crate::config::Options). Enum discriminants are always encoded as a `u32`
+/// with the configured int encoding.
+///
+/// [finish](ConstWriter::finish) panics if exactly `N` bytes were not written. In a `const`
+/// context that is a compile error, so a mismatch between the struct and the blob is caught
+/// early. To size the array automatically, write the fields in a `const fn` that is generic over
+/// `N`, and run it twice:
+///
+/// ```
+/// # extern crate serde_derive;
+/// # use bincode_core::{deserialize, ConstWriter, DefaultOptions};
+/// # use serde_derive::Deserialize;
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct Config<'a> {
+///     baud_rate: u32,
+///     enabled: bool,
+///     gain: f32,
+///     name: &'a str,
+///     address: [u8; 4],
+/// }
+///
+/// const fn config<const N: usize>(writer: ConstWriter<N>) -> ConstWriter<N> {
+///     writer
+///         .u32(115_200)
+///         .bool(true)
+///         .f32(1.5)
+///         .str("uart0")
+///         .bytes(&[192, 168, 0, 1])
+/// }
+///
+/// const LEN: usize = config(ConstWriter::<64>::new()).len();
+/// const CONFIG: [u8; LEN] = config(ConstWriter::new()).finish();
+///
+/// let config: Config = deserialize(&CONFIG[..], DefaultOptions::new()).unwrap();
+/// assert_eq!(config.baud_rate, 115_200);
+/// assert_eq!(config.name, "uart0");
+/// ```
+#[derive(Clone, Copy)]
+pub struct ConstWriter<const N: usize> {
+    buffer: [u8; N],
+    len: usize,
+    big_endian: bool,
+    fixint: bool,
+}
+
+impl<const N: usize> ConstWriter<N> {
+    /// Create a writer for `N` bytes, with the encoding of
+    /// [DefaultOptions](crate::DefaultOptions).
+    pub const fn new() -> Self {
+        ConstWriter {
+            buffer: [0; N],
+            len: 0,
+            big_endian: false,
+            fixint: false,
+        }
+    }
+
+    /// Sets the endianness to little-endian.
+    /// This is the default.
+    pub const fn with_little_endian(mut self) -> Self {
+        self.big_endian = false;
+        self
+    }
+
+    /// Sets the endianness to big-endian.
+    pub const fn with_big_endian(mut self) -> Self {
+        self.big_endian = true;
+        self
+    }
+
+    /// Sets the int encoding to varint.
+    /// This is the default.
+    pub const fn with_varint_encoding(mut self) -> Self {
+        self.fixint = false;
+        self
+    }
+
+    /// Sets the int encoding to be fixed.
+    pub const fn with_fixint_encoding(mut self) -> Self {
+        self.fixint = true;
+        self
+    }
+
+    /// The amount of bytes that have been written so far.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The bytes that have been written so far.
+    pub const fn written_buffer(&self) -> &[u8] {
+        self.buffer.split_at(self.len).0
+    }
+
+    /// Returns `true` if nothing has been written yet.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the written bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the amount of written bytes is not exactly `N`.
+    pub const fn finish(self) -> [u8; N] {
+        if self.len != N {
+            panic!("ConstWriter: the written length does not match the array length");
+        }
+        self.buffer
+    }
+
+    /// Write raw bytes, without a length. This matches a `[u8; N]` array field.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the bytes do not fit in the remaining space.
+    pub const fn bytes(mut self, bytes: &[u8]) -> Self {
+        if bytes.len() > N - self.len {
+            panic!("ConstWriter: the array is too small for the written bytes");
+        }
+        let mut i = 0;
+        while i < bytes.len() {
+            self.buffer[self.len + i] = bytes[i];
+            i += 1;
+        }
+        self.len += bytes.len();
+        self
+    }
+
+    /// Write a length-prefixed byte slice. This matches a `&[u8]` field.
+    pub const fn byte_slice(self, bytes: &[u8]) -> Self {
+        self.u64(bytes.len() as u64).bytes(bytes)
+    }
+
+    /// Write a length-prefixed string. This matches a `&str` field.
+    pub const fn str(self, value: &str) -> Self {
+        self.byte_slice(value.as_bytes())
+    }
+
+    /// Write a `bool`.
+    pub const fn bool(self, value: bool) -> Self {
+        self.u8(value as u8)
+    }
+
+    /// Write a `u8`.
+    pub const fn u8(self, value: u8) -> Self {
+        self.bytes(&[value])
+    }
+
+    /// Write an `i8`.
+    pub const fn i8(self, value: i8) -> Self {
+        self.u8(value as u8)
+    }
+
+    /// Write a `u16`.
+    pub const fn u16(self, value: u16) -> Self {
+        if self.fixint {
+            self.literal_u16(value)
+        } else {
+            self.varint(value as u128)
+        }
+    }
+
+    /// Write a `u32`.
+    pub const fn u32(self, value: u32) -> Self {
+        if self.fixint {
+            self.literal_u32(value)
+        } else {
+            self.varint(value as u128)
+        }
+    }
+
+    /// Write a `u64`.
+    pub const fn u64(self, value: u64) -> Self {
+        if self.fixint {
+            self.literal_u64(value)
+        } else {
+            self.varint(value as u128)
+        }
+    }
+
+    /// Write a `u128`.
+    pub const fn u128(self, value: u128) -> Self {
+        if self.fixint {
+            self.literal_u128(value)
+        } else {
+            self.varint(value)
+        }
+    }
+
+    /// Write an `i16`.
+    pub const fn i16(self, value: i16) -> Self {
+        if self.fixint {
+            self.literal_u16(value as u16)
+        } else {
+            self.varint(zigzag(value as i128))
+        }
+    }
+
+    /// Write an `i32`.
+    pub const fn i32(self, value: i32) -> Self {
+        if self.fixint {
+            self.literal_u32(value as u32)
+        } else {
+            self.varint(zigzag(value as i128))
+        }
+    }
+
+    /// Write an `i64`.
+    pub const fn i64(self, value: i64) -> Self {
+        if self.fixint {
+            self.literal_u64(value as u64)
+        } else {
+            self.varint(zigzag(value as i128))
+        }
+    }
+
+    /// Write an `i128`.
+    pub const fn i128(self, value: i128) -> Self {
+        if self.fixint {
+            self.literal_u128(value as u128)
+        } else {
+            self.varint(zigzag(value))
+        }
+    }
+
+    /// Write an `f32`.
+    pub const fn f32(self, value: f32) -> Self {
+        self.literal_u32(value.to_bits())
+    }
+
+    /// Write an `f64`.
+    pub const fn f64(self, value: f64) -> Self {
+        self.literal_u64(value.to_bits())
+    }
+
+    /// Write the tag of an `Option`. For `true`, the value has to be written next.
+    pub const fn option(self, is_some: bool) -> Self {
+        self.bool(is_some)
+    }
+
+    /// Write the variant index of an enum. The fields of the variant have to be written next.
+    pub const fn variant(self, index: u32) -> Self {
+        self.u32(index)
+    }
+
+    const fn literal_u16(self, value: u16) -> Self {
+        if self.big_endian {
+            self.bytes(&value.to_be_bytes())
+        } else {
+            self.bytes(&value.to_le_bytes())
+        }
+    }
+
+    const fn literal_u32(self, value: u32) -> Self {
+        if self.big_endian {
+            self.bytes(&value.to_be_bytes())
+        } else {
+            self.bytes(&value.to_le_bytes())
+        }
+    }
+
+    const fn literal_u64(self, value: u64) -> Self {
+        if self.big_endian {
+            self.bytes(&value.to_be_bytes())
+        } else {
+            self.bytes(&value.to_le_bytes())
+        }
+    }
+
+    const fn literal_u128(self, value: u128) -> Self {
+        if self.big_endian {
+            self.bytes(&value.to_be_bytes())
+        } else {
+            self.bytes(&value.to_le_bytes())
+        }
+    }
+
+    const fn varint(self, value: u128) -> Self {
+        if value <= SINGLE_BYTE_MAX as u128 {
+            self.u8(value as u8)
+        } else if value <= u16::MAX as u128 {
+            self.u8(U16_BYTE).literal_u16(value as u16)
+        } else if value <= u32::MAX as u128 {
+            self.u8(U32_BYTE).literal_u32(value as u32)
+        } else if value <= u64::MAX as u128 {
+            self.u8(U64_BYTE).literal_u64(value as u64)
+        } else {
+            self.u8(U128_BYTE).literal_u128(value)
+        }
+    }
+}
+
+impl<const N: usize> Default for ConstWriter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// see VarintEncoding::zigzag128_encode
+const fn zigzag(value: i128) -> u128 {
+    if value < 0 {
+        !(value as u128) * 2 + 1
+    } else {
+        (value as u128) * 2
+    }
+}
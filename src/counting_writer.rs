@@ -0,0 +1,91 @@
+use crate::traits::CoreWrite;
+
+/// An implementation of [CoreWrite] that counts the bytes that are written to it.
+///
+/// [CountingWriter::new] creates a writer that only counts, similar to
+/// [serialize_size](crate::serialize_size). [CountingWriter::wrap] counts the bytes that are
+/// forwarded to another writer. The count can be read at any point, e.g. while manually driving a
+/// [Serializer](crate::Serializer) to split a stream of values into MTU-sized packets.
+///
+/// ```
+/// # use bincode_core::{serialize, serialize_size, CountingWriter, DefaultOptions};
+/// let value = (1u8, "some text");
+/// let mut writer = CountingWriter::new();
+/// serialize(&value, &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.count(), serialize_size(&value, DefaultOptions::new()).unwrap());
+/// ```
+pub struct CountingWriter<W: CoreWrite = ()> {
+    writer: W,
+    count: usize,
+}
+
+impl CountingWriter<()> {
+    /// Create a new writer that only counts the bytes written to it.
+    pub fn new() -> Self {
+        Self::wrap(())
+    }
+}
+
+impl Default for CountingWriter<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<W: CoreWrite> CountingWriter<W> {
+    /// Create a new writer that counts the bytes that are written to `writer`.
+    pub fn wrap(writer: W) -> Self {
+        Self { writer, count: 0 }
+    }
+
+    /// The amount of bytes that were successfully written.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Set the count back to 0.
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for CountingWriter<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.writer.write(val)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_all(val)?;
+        self.count += val.len();
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite> CoreWrite for &mut CountingWriter<W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
@@ -0,0 +1,580 @@
+use crate::serialize::SerializeError;
+use crate::traits::CoreWrite;
+use serde::ser::*;
+
+/// Write `value` as human-readable text into `writer`, e.g. to print a struct to a debug UART.
+///
+/// The text looks like JSON, with the names of struct fields and enum variants written as they
+/// are:
+///
+/// | Value | Text |
+/// |-------|------|
+/// | `bool`, ints | `true`, `-300` |
+/// | floats | `1.5`, `0.333333`, `1.2e20`, `NaN`, `inf` |
+/// | `char`, `&str` | `"text"`, with `"`, `\` and control characters escaped |
+/// | `&[u8]` (with `serde_bytes`) | `0x0aff` |
+/// | `None`, `()`, unit struct | `null` |
+/// | `Some(value)`, newtype struct | the value |
+/// | sequence, tuple | `[1, 2]` |
+/// | map | `{"key": 1}` |
+/// | struct | `{id: 7, temp: -300}` |
+/// | enum | `Idle`, `Heat(40)`, `Cycle(1, 2)`, `Target {celsius: -5}` |
+///
+/// Ints and floats are formatted without `core::fmt`, which keeps the code size small. Floats
+/// are rounded to 6 decimals, or 7 significant digits for very large and very small values, so
+/// they are not exact. Only `collect_str`, e.g. for values that serialize with their `Display`
+/// implementation, uses `core::fmt`.
+///
+/// Unlike bincode, the text does not need the length of a sequence up front, so any iterator can
+/// be written with `collect_seq`. The writer is flushed after the value is written.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::{debug_dump, BufferWriter};
+/// #[derive(Serialize)]
+/// enum Mode {
+///     Idle,
+///     Heat(u16),
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Status<'a> {
+///     name: &'a str,
+///     gain: f32,
+///     modes: [Mode; 2],
+///     offset: Option<i8>,
+/// }
+///
+/// let status = Status {
+///     name: "pump",
+///     gain: 1.5,
+///     modes: [Mode::Idle, Mode::Heat(40)],
+///     offset: None,
+/// };
+/// let mut buffer = [0u8; 64];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// debug_dump(&status, &mut writer).unwrap();
+/// assert_eq!(
+///     writer.written_buffer(),
+///     b"{name: \"pump\", gain: 1.5, modes: [Idle, Heat(40)], offset: null}"
+/// );
+/// ```
+pub fn debug_dump<T: Serialize + ?Sized, W: CoreWrite>(
+    value: &T,
+    writer: W,
+) -> Result<(), SerializeError<W::Error>> {
+    let mut dumper = Dumper { writer };
+    value.serialize(&mut dumper)?;
+    dumper.writer.flush().map_err(SerializeError::Write)
+}
+
+struct Dumper<W: CoreWrite> {
+    writer: W,
+}
+
+impl<W: CoreWrite> Dumper<W> {
+    fn text(&mut self, text: &[u8]) -> Result<(), SerializeError<W::Error>> {
+        self.writer.write_all(text).map_err(SerializeError::Write)
+    }
+
+    fn u64(&mut self, mut v: u64) -> Result<(), SerializeError<W::Error>> {
+        let mut digits = [0u8; 20];
+        let mut len = 0;
+        for digit in digits.iter_mut().rev() {
+            *digit = b'0' + (v % 10) as u8;
+            v /= 10;
+            len += 1;
+            if v == 0 {
+                break;
+            }
+        }
+        self.text(digits.get(digits.len() - len..).unwrap_or(&digits))
+    }
+
+    fn u128(&mut self, mut v: u128) -> Result<(), SerializeError<W::Error>> {
+        let mut digits = [0u8; 39];
+        let mut len = 0;
+        for digit in digits.iter_mut().rev() {
+            *digit = b'0' + (v % 10) as u8;
+            v /= 10;
+            len += 1;
+            if v == 0 {
+                break;
+            }
+        }
+        self.text(digits.get(digits.len() - len..).unwrap_or(&digits))
+    }
+
+    fn i64(&mut self, v: i64) -> Result<(), SerializeError<W::Error>> {
+        if v < 0 {
+            self.text(b"-")?;
+        }
+        self.u64(v.unsigned_abs())
+    }
+
+    /// Writes the first `count` digits of `v`, with leading zeros.
+    fn fraction(&mut self, v: u64, count: u32) -> Result<(), SerializeError<W::Error>> {
+        let mut digits = [b'0'; 6];
+        let mut rest = v;
+        for digit in digits.iter_mut().rev() {
+            *digit = b'0' + (rest % 10) as u8;
+            rest /= 10;
+        }
+        self.text(digits.get(..count as usize).unwrap_or(&digits))
+    }
+
+    fn f64(&mut self, v: f64) -> Result<(), SerializeError<W::Error>> {
+        if v.is_nan() {
+            return self.text(b"NaN");
+        }
+        if v.is_sign_negative() {
+            self.text(b"-")?;
+        }
+        let v = if v < 0.0 { -v } else { v };
+        if v.is_infinite() {
+            return self.text(b"inf");
+        }
+        if v == 0.0 || (1e-4..1e15).contains(&v) {
+            let mut int = v as u64;
+            let mut fraction = ((v - int as f64) * 1e6 + 0.5) as u64;
+            if fraction >= 1_000_000 {
+                int += 1;
+                fraction -= 1_000_000;
+            }
+            self.u64(int)?;
+            self.text(b".")?;
+            return self.fraction(fraction, significant(fraction).max(1));
+        }
+        // Scale to one digit before the point, this is not exact for very large or small values
+        let mut exponent = 0i32;
+        let mut mantissa = v;
+        while mantissa >= 10.0 {
+            mantissa /= 10.0;
+            exponent += 1;
+        }
+        while mantissa < 1.0 {
+            mantissa *= 10.0;
+            exponent -= 1;
+        }
+        let mut scaled = (mantissa * 1e6 + 0.5) as u64;
+        if scaled >= 10_000_000 {
+            scaled /= 10;
+            exponent += 1;
+        }
+        self.u64(scaled / 1_000_000)?;
+        let fraction = scaled % 1_000_000;
+        if fraction != 0 {
+            self.text(b".")?;
+            self.fraction(fraction, significant(fraction))?;
+        }
+        self.text(b"e")?;
+        self.i64(exponent.into())
+    }
+
+    fn str(&mut self, v: &str) -> Result<(), SerializeError<W::Error>> {
+        self.text(b"\"")?;
+        self.escaped(v)?;
+        self.text(b"\"")
+    }
+
+    /// Writes `v` with `"`, `\` and control characters escaped.
+    fn escaped(&mut self, v: &str) -> Result<(), SerializeError<W::Error>> {
+        let mut rest = v.as_bytes();
+        while let Some(pos) = rest
+            .iter()
+            .position(|&b| b == b'"' || b == b'\\' || b < 0x20)
+        {
+            let (plain, escaped) = rest.split_at(pos);
+            self.text(plain)?;
+            let (&byte, after) = match escaped.split_first() {
+                Some(split) => split,
+                None => break,
+            };
+            match byte {
+                b'"' => self.text(b"\\\"")?,
+                b'\\' => self.text(b"\\\\")?,
+                b'\n' => self.text(b"\\n")?,
+                b'\r' => self.text(b"\\r")?,
+                b'\t' => self.text(b"\\t")?,
+                _ => self.text(&[b'\\', b'u', b'0', b'0', hex(byte >> 4), hex(byte & 0xF)])?,
+            }
+            rest = after;
+        }
+        self.text(rest)
+    }
+}
+
+/// The amount of the 6 decimals of `fraction` without the trailing zeros.
+fn significant(mut fraction: u64) -> u32 {
+    let mut count = 6;
+    while count > 0 && fraction % 10 == 0 {
+        fraction /= 10;
+        count -= 1;
+    }
+    count
+}
+
+fn hex(nibble: u8) -> u8 {
+    match nibble {
+        0..=9 => b'0' + nibble,
+        _ => b'a' + nibble - 10,
+    }
+}
+
+impl<'a, W: CoreWrite> serde::Serializer for &'a mut Dumper<W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Self::Error> {
+        self.text(if v { b"true" } else { b"false" })
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Self::Error> {
+        self.i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Self::Error> {
+        self.i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Self::Error> {
+        self.i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Self::Error> {
+        self.i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Self::Error> {
+        if v < 0 {
+            self.text(b"-")?;
+        }
+        self.u128(v.unsigned_abs())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Self::Error> {
+        self.u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Self::Error> {
+        self.u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Self::Error> {
+        self.u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Self::Error> {
+        self.u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Self::Error> {
+        self.u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Self::Error> {
+        self.f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Self::Error> {
+        self.f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Self::Error> {
+        self.str(v.encode_utf8(&mut [0u8; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Self::Error> {
+        self.str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Self::Error> {
+        self.text(b"0x")?;
+        for byte in v {
+            self.text(&[hex(byte >> 4), hex(byte & 0xF)])?;
+        }
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Self::Error> {
+        self.text(b"null")
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Self::Error> {
+        self.text(b"null")
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Self::Error> {
+        self.text(b"null")
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Self::Error> {
+        self.text(variant.as_bytes())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.text(variant.as_bytes())?;
+        self.text(b"(")?;
+        value.serialize(&mut *self)?;
+        self.text(b")")
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Compound::open(self, b"[", b"]")
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Compound::open(self, b"[", b"]")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Compound::open(self, b"[", b"]")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.text(variant.as_bytes())?;
+        Compound::open(self, b"(", b")")
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Compound::open(self, b"{", b"}")
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Compound::open(self, b"{", b"}")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.text(variant.as_bytes())?;
+        Compound::open(self, b" {", b"}")
+    }
+
+    fn collect_str<T: ?Sized + core::fmt::Display>(self, value: &T) -> Result<(), Self::Error> {
+        struct Escape<'a, W: CoreWrite> {
+            dumper: &'a mut Dumper<W>,
+            error: Option<SerializeError<W::Error>>,
+        }
+
+        impl<W: CoreWrite> core::fmt::Write for Escape<'_, W> {
+            fn write_str(&mut self, s: &str) -> core::fmt::Result {
+                self.dumper.escaped(s).map_err(|e| {
+                    self.error = Some(e);
+                    core::fmt::Error
+                })
+            }
+        }
+
+        self.text(b"\"")?;
+        let mut escape = Escape {
+            dumper: &mut *self,
+            error: None,
+        };
+        let result = core::fmt::write(&mut escape, format_args!("{}", value));
+        match escape.error {
+            Some(e) => return Err(e),
+            None if result.is_err() => return Err(SerializeError::Custom),
+            None => {}
+        }
+        self.text(b"\"")
+    }
+}
+
+/// Writes the items of a sequence, map or struct, separated by commas.
+struct Compound<'a, W: CoreWrite> {
+    dumper: &'a mut Dumper<W>,
+    first: bool,
+    close: &'static [u8],
+}
+
+impl<'a, W: CoreWrite> Compound<'a, W> {
+    fn open(
+        dumper: &'a mut Dumper<W>,
+        open: &[u8],
+        close: &'static [u8],
+    ) -> Result<Self, SerializeError<W::Error>> {
+        dumper.text(open)?;
+        Ok(Compound {
+            dumper,
+            first: true,
+            close,
+        })
+    }
+
+    fn item<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), SerializeError<W::Error>> {
+        if !core::mem::replace(&mut self.first, false) {
+            self.dumper.text(b", ")?;
+        }
+        value.serialize(&mut *self.dumper)
+    }
+
+    fn field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if !core::mem::replace(&mut self.first, false) {
+            self.dumper.text(b", ")?;
+        }
+        self.dumper.text(key.as_bytes())?;
+        self.dumper.text(b": ")?;
+        value.serialize(&mut *self.dumper)
+    }
+
+    fn close(self) -> Result<(), SerializeError<W::Error>> {
+        self.dumper.text(self.close)
+    }
+}
+
+impl<W: CoreWrite> SerializeSeq for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeTuple for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeTupleStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeTupleVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.item(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeMap for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.item(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.dumper.text(b": ")?;
+        value.serialize(&mut *self.dumper)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeStruct for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
+
+impl<W: CoreWrite> SerializeStructVariant for Compound<'_, W> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        self.close()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::config::Options;
+use crate::deserialize::deserialize_with_context;
+use crate::error::ErrorKind;
+use crate::error_context::FieldPath;
+use serde::de::DeserializeOwned;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+/// The amount of bytes in a line of the hex dump of [DumpError].
+const DUMP_LINE_LEN: usize = 16;
+
+/// Decode a value from a byte dump, e.g. in a small host-side tool that is built with the same
+/// message definitions as the device.
+///
+/// The dump is either the raw bytes, or the bytes as hex text as it is copied from a log or
+/// terminal. Hex text may separate the bytes with whitespace, `,`, `:` or `-`, prefix them with
+/// `0x`, and be wrapped in `[]`, so `01fb cdab`, `01:FB:CD:AB` and `[0x01, 0xfb, 0xcd, 0xab]` are
+/// all accepted. A dump that is not hex text in one of these forms is used as raw bytes.
+///
+/// The bytes are decoded into a temporary buffer, so the type can not borrow from the input.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::{decode_dump, DefaultOptions};
+/// #[derive(Deserialize, PartialEq, Debug)]
+/// struct Reading {
+///     id: u8,
+///     temp: i16,
+/// }
+///
+/// let reading: Reading = decode_dump(b"07 fb 57 02", DefaultOptions::new()).unwrap();
+/// assert_eq!(reading, Reading { id: 7, temp: -300 });
+///
+/// let err = decode_dump::<Reading, _>(b"07 fb 57", DefaultOptions::new()).unwrap_err();
+/// assert_eq!(err.offset, 2);
+/// assert_eq!(
+///     err.to_string(),
+///     "Unexpected end of input, 1 more bytes are needed at offset 2 while reading field 1\n\
+///      0000  07 fb 57\n\
+///      \x20           ^^\n"
+/// );
+/// ```
+pub fn decode_dump<T: DeserializeOwned, O: Options>(
+    dump: &[u8],
+    options: O,
+) -> Result<T, DumpError> {
+    let bytes = parse_hex(dump).unwrap_or_else(|| dump.to_vec());
+    deserialize_with_context(&bytes[..], options).map_err(|err| DumpError {
+        kind: err.error.kind(),
+        message: err.error.to_string(),
+        offset: err.offset,
+        path: err.path,
+        bytes: bytes.clone(),
+    })
+}
+
+/// Returns the bytes of `dump` if it is hex text, see [decode_dump].
+fn parse_hex(dump: &[u8]) -> Option<Vec<u8>> {
+    let text = core::str::from_utf8(dump).ok()?.trim();
+    let text = text
+        .strip_prefix('[')
+        .and_then(|text| text.strip_suffix(']'))
+        .unwrap_or(text);
+    let mut bytes = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '-')) {
+        if token.is_empty() {
+            continue;
+        }
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        if digits.is_empty() || digits.len() % 2 != 0 {
+            return None;
+        }
+        for pair in digits.as_bytes().chunks(2) {
+            let pair = core::str::from_utf8(pair).ok()?;
+            bytes.push(u8::from_str_radix(pair, 16).ok()?);
+        }
+    }
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// The error of [decode_dump], with the decoded bytes so the failing position can be shown.
+///
+/// The [Display](core::fmt::Display) implementation prints the error, followed by the line of
+/// the hex dump that contains the failing byte and the line before it, with the failing byte
+/// marked:
+///
+/// ```text
+/// Unknown bool value, got 7, expected 0 or 1 at offset 17 while reading field 2
+/// 0000  01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10
+/// 0010  00 07 01
+///          ^^
+/// ```
+#[derive(Debug)]
+pub struct DumpError {
+    /// The category of the error.
+    pub kind: ErrorKind,
+
+    /// The message of the error.
+    pub message: String,
+
+    /// The byte offset in the decoded bytes at which the failing read started.
+    pub offset: u64,
+
+    /// The field that was being read when the error occurred.
+    pub path: FieldPath,
+
+    /// The decoded bytes of the dump.
+    pub bytes: Vec<u8>,
+}
+
+impl DumpError {
+    fn write_line(&self, fmt: &mut core::fmt::Formatter, start: usize) -> core::fmt::Result {
+        let end = (start + DUMP_LINE_LEN).min(self.bytes.len());
+        write!(fmt, "{:04x} ", start)?;
+        for byte in self.bytes.get(start..end).unwrap_or(&[]) {
+            write!(fmt, " {:02x}", byte)?;
+        }
+        writeln!(fmt)
+    }
+}
+
+impl core::fmt::Display for DumpError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{} at offset {}", self.message, self.offset)?;
+        if self.path.depth() > 0 {
+            write!(fmt, " while reading field {}", self.path)?;
+        }
+        writeln!(fmt)?;
+        let offset = self.offset as usize;
+        let line = offset / DUMP_LINE_LEN * DUMP_LINE_LEN;
+        if line >= DUMP_LINE_LEN {
+            self.write_line(fmt, line - DUMP_LINE_LEN)?;
+        }
+        if line < self.bytes.len() {
+            self.write_line(fmt, line)?;
+        }
+        let column = 6 + (offset - line) * 3;
+        writeln!(fmt, "{:column$}^^", "", column = column)
+    }
+}
+
+impl std::error::Error for DumpError {}
@@ -0,0 +1,434 @@
+//! Delta encoding for values that are sent periodically.
+//!
+//! A sensor that sends the same telemetry struct every second often only changes a few fields
+//! between two messages. [serialize_delta] compares the previous and the current value field by
+//! field, and only writes the fields that changed, after a bitmap of the changed fields.
+//! [apply_delta] reads such a delta, and takes the unchanged fields from the previous value.
+//!
+//! The bitmap has one bit for each of the first 64 fields of the struct, bit `i % 8` of byte
+//! `i / 8` belongs to field `i`. Fields after the 64th are always written. A value that is not a
+//! struct, like an enum, is handled as a struct with a single field, so it is either written
+//! completely or not at all.
+//!
+//! Fields are compared by their encoding, so `T` does not need to implement `PartialEq`. The
+//! encoding of a field is kept in a 128 byte buffer on the stack to compare it, so a field with a
+//! longer encoding is always written. Both sides have to keep the previous value, and a lost delta
+//! has to be followed by a full value.
+//!
+//! ```
+//! # extern crate serde_derive;
+//! # use bincode_core::delta::{apply_delta, serialize_delta};
+//! # use bincode_core::{BufferWriter, DefaultOptions};
+//! # use serde_derive::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Telemetry<'a> {
+//!     uptime: u32,
+//!     temperature: i16,
+//!     battery: u8,
+//!     name: &'a str,
+//! }
+//!
+//! let prev = Telemetry { uptime: 100, temperature: 21, battery: 80, name: "probe-1" };
+//! let current = Telemetry { uptime: 101, temperature: 21, battery: 80, name: "probe-1" };
+//!
+//! let mut buffer = [0u8; 16];
+//! let mut writer = BufferWriter::new(&mut buffer);
+//! serialize_delta(&prev, &current, &mut writer, DefaultOptions::new()).unwrap();
+//! // The bitmap and the new uptime
+//! assert_eq!(writer.written_buffer(), &[0b0001, 101]);
+//!
+//! let len = writer.written_len();
+//! let mut scratch = [0u8; 16];
+//! let decoded: Telemetry = apply_delta(&prev, &buffer[..len], &mut scratch, DefaultOptions::new())
+//!     .unwrap();
+//! assert_eq!(decoded, current);
+//! ```
+
+use crate::buffer_writer::BufferWriter;
+use crate::config::{bitmap_len, Infinite, Options, TrailingBytes, WithOtherLimit, BITMAP_FIELDS};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::presence::{for_each_field, FieldVisitor, NotProbed};
+use crate::serialize::{serialize, SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+use core::marker::PhantomData;
+use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Serialize};
+
+/// Serialize the fields of `current` that are different from `prev` into `writer`.
+///
+/// The bitmap of changed fields and the changed values count towards the size limit, which is
+/// checked while writing. The writer is flushed after the delta is written, unless the options
+/// were built with [with_manual_flush](crate::config::Options::with_manual_flush).
+pub fn serialize_delta<T: Serialize + ?Sized, W: CoreWrite, O: Options>(
+    prev: &T,
+    current: &T,
+    writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    let len = for_each_field(current, &mut ());
+    let bits = changed_fields(prev, current, len, &mut options);
+
+    let mut serializer = Serializer::new(writer, options);
+    serializer.serialize_raw(&bits.to_le_bytes()[..bitmap_len(len.unwrap_or(1))])?;
+    let mut changed = ChangedFields {
+        serializer: &mut serializer,
+        bits,
+        error: None,
+    };
+    visit_fields(current, len, &mut changed);
+    if let Some(error) = changed.error {
+        return Err(error);
+    }
+    serializer.write_bools()?;
+    serializer.auto_flush()
+}
+
+/// Deserialize a delta that was written with [serialize_delta], and take the unchanged fields
+/// from `prev`.
+///
+/// The unchanged fields are encoded into `scratch` and deserialized from there, so the returned
+/// value can borrow from it. If `scratch` is too small for the unchanged fields,
+/// [DeserializeError::ScratchTooSmall] is returned.
+pub fn apply_delta<'a, T, R, O>(
+    prev: &T,
+    reader: R,
+    scratch: &'a mut [u8],
+    options: O,
+) -> Result<T, DeserializeError<'a, R>>
+where
+    T: Serialize + Deserialize<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+{
+    let len = for_each_field(prev, &mut ());
+    let mut deserializer = Deserializer::new(reader, options);
+    let bits = deserializer.deserialize_bitmap(len.unwrap_or(1))?;
+
+    let mut unchanged = UnchangedFields {
+        writer: BufferWriter::new(&mut *scratch),
+        options: deserializer.options_mut(),
+        bits,
+        error: None,
+    };
+    visit_fields(prev, len, &mut unchanged);
+    if let Some(error) = unchanged.error {
+        return Err(error);
+    }
+    let written = unchanged.writer.written_len();
+    let scratch: &'a [u8] = scratch;
+
+    let mut fields = DeltaFields {
+        deserializer: &mut deserializer,
+        unchanged: &scratch[..written],
+        bits,
+    };
+    let value = match len {
+        Some(_) => T::deserialize(&mut fields)?,
+        None => fields.field(0, PhantomData::<T>)?,
+    };
+    O::Trailing::check_end(deserializer.reader())?;
+    Ok(value)
+}
+
+/// The amount of bytes of the encoded fields of the current value that are kept to compare them
+/// with the previous value. Fields with a longer encoding are always written. This is at most 255,
+/// so the ends of the fields fit in a `u8`.
+const COMPARE_BYTES: usize = 128;
+
+/// Returns the fields of `current` that are encoded differently than in `prev`, as a bitmap. `len`
+/// is the amount of fields, or `None` if the values are not structs.
+///
+/// The fields of `current` are encoded into a buffer as long as they fit, and the fields of `prev`
+/// are compared with them while they are encoded, so every field is only encoded once. A field
+/// that cannot be encoded counts as changed, so the error is returned when it is written.
+fn changed_fields<T, O>(prev: &T, current: &T, len: Option<usize>, options: &mut O) -> u64
+where
+    T: Serialize + ?Sized,
+    O: Options,
+{
+    let fields = len.unwrap_or(1).min(BITMAP_FIELDS);
+    let mut bits = 0;
+    let mut first = 0;
+    while first < fields {
+        let mut encoded = Encoded {
+            first,
+            count: 0,
+            len: 0,
+            ends: [0; BITMAP_FIELDS],
+            bytes: [0; COMPARE_BYTES],
+        };
+        visit_fields(
+            current,
+            len,
+            &mut EncodeFields {
+                encoded: &mut encoded,
+                options: &mut *options,
+            },
+        );
+        if encoded.count == 0 {
+            // The field does not fit, or cannot be encoded
+            bits |= 1 << first;
+            first += 1;
+            continue;
+        }
+        let mut compare = CompareFields {
+            encoded: &encoded,
+            options: &mut *options,
+            // Fields that are not compared, e.g. because `prev` has fewer fields, count as changed
+            bits: (u64::MAX >> (64 - encoded.count)) << first,
+        };
+        visit_fields(prev, len, &mut compare);
+        bits |= compare.bits;
+        first += encoded.count;
+    }
+    bits
+}
+
+/// Passes the fields of `value` to `visitor`, or `value` itself as field 0 if it is not a struct.
+fn visit_fields<T: Serialize + ?Sized, V: FieldVisitor>(
+    value: &T,
+    len: Option<usize>,
+    visitor: &mut V,
+) {
+    match len {
+        Some(_) => for_each_field(value, visitor).map(drop),
+        None => visitor.visit(0, value).ok(),
+    };
+}
+
+/// The encodings of `count` fields of the current value, starting at field `first`.
+struct Encoded {
+    first: usize,
+    count: usize,
+    len: usize,
+    /// The end of the encoding of each field in `bytes`.
+    ends: [u8; BITMAP_FIELDS],
+    bytes: [u8; COMPARE_BYTES],
+}
+
+impl Encoded {
+    /// The encoding of the field at `index`, if it was encoded.
+    fn field(&self, index: usize) -> Option<&[u8]> {
+        let i = index.checked_sub(self.first).filter(|i| *i < self.count)?;
+        let start = match i {
+            0 => 0,
+            i => self.ends[i - 1] as usize,
+        };
+        Some(&self.bytes[start..self.ends[i] as usize])
+    }
+}
+
+impl CoreWrite for &mut Encoded {
+    type Error = ();
+
+    fn write(&mut self, val: u8) -> Result<(), ()> {
+        *self.bytes.get_mut(self.len).ok_or(())? = val;
+        self.len += 1;
+        Ok(())
+    }
+}
+
+/// Encodes the fields of the current value into [Encoded], until one does not fit.
+struct EncodeFields<'e, 'o, O> {
+    encoded: &'e mut Encoded,
+    options: &'o mut O,
+}
+
+impl<'e, 'o, O: Options> FieldVisitor for EncodeFields<'e, 'o, O> {
+    fn visit<F: Serialize + ?Sized>(&mut self, index: usize, value: &F) -> Result<(), NotProbed> {
+        let encoded = &mut *self.encoded;
+        if index < encoded.first {
+            return Ok(());
+        }
+        if index >= BITMAP_FIELDS {
+            return Err(NotProbed);
+        }
+        let start = encoded.len;
+        let options = WithOtherLimit::new(&mut *self.options, Infinite);
+        if serialize(value, &mut *encoded, options).is_err() {
+            encoded.len = start;
+            return Err(NotProbed);
+        }
+        encoded.ends[index - encoded.first] = encoded.len as u8;
+        encoded.count += 1;
+        Ok(())
+    }
+}
+
+/// Compares the fields of the previous value with the encoded fields of the current value, and
+/// clears the bits of the fields that are the same.
+struct CompareFields<'e, 'o, O> {
+    encoded: &'e Encoded,
+    options: &'o mut O,
+    bits: u64,
+}
+
+impl<'e, 'o, O: Options> FieldVisitor for CompareFields<'e, 'o, O> {
+    fn visit<F: Serialize + ?Sized>(&mut self, index: usize, value: &F) -> Result<(), NotProbed> {
+        if index < self.encoded.first {
+            return Ok(());
+        }
+        let expected = self.encoded.field(index).ok_or(NotProbed)?;
+        let mut matcher = Matcher { expected };
+        let options = WithOtherLimit::new(&mut *self.options, Infinite);
+        if serialize(value, &mut matcher, options).is_ok() && matcher.expected.is_empty() {
+            self.bits &= !(1 << index);
+        }
+        Ok(())
+    }
+}
+
+/// A writer that fails as soon as a byte is different from the expected bytes.
+struct Matcher<'e> {
+    /// The expected bytes that were not written yet.
+    expected: &'e [u8],
+}
+
+impl CoreWrite for &mut Matcher<'_> {
+    type Error = ();
+
+    fn write(&mut self, val: u8) -> Result<(), ()> {
+        match self.expected.split_first() {
+            Some((&byte, rest)) if byte == val => {
+                self.expected = rest;
+                Ok(())
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+/// Writes the fields that changed.
+struct ChangedFields<'s, W: CoreWrite, O: Options> {
+    serializer: &'s mut Serializer<W, O>,
+    bits: u64,
+    error: Option<SerializeError<W::Error>>,
+}
+
+impl<'s, W: CoreWrite, O: Options> FieldVisitor for ChangedFields<'s, W, O> {
+    fn visit<F: Serialize + ?Sized>(&mut self, index: usize, value: &F) -> Result<(), NotProbed> {
+        if !is_changed(self.bits, index) {
+            return Ok(());
+        }
+        self.serializer.serialize_value(value).map_err(|error| {
+            self.error = Some(error);
+            NotProbed
+        })
+    }
+}
+
+/// Writes the fields of the previous value that did not change into the scratch buffer.
+struct UnchangedFields<'w, 'o, 'a, R: CoreRead<'a>, O> {
+    writer: BufferWriter<'w>,
+    options: &'o mut O,
+    bits: u64,
+    error: Option<DeserializeError<'a, R>>,
+}
+
+impl<'w, 'o, 'a, R: CoreRead<'a>, O: Options> FieldVisitor for UnchangedFields<'w, 'o, 'a, R, O> {
+    fn visit<F: Serialize + ?Sized>(&mut self, index: usize, value: &F) -> Result<(), NotProbed> {
+        if is_changed(self.bits, index) {
+            return Ok(());
+        }
+        let options = WithOtherLimit::new(&mut *self.options, Infinite);
+        let error = match serialize(value, &mut self.writer, options) {
+            Ok(()) => return Ok(()),
+            Err(SerializeError::Write(_)) => DeserializeError::ScratchTooSmall,
+            Err(_) => DeserializeError::Custom,
+        };
+        self.error = Some(error);
+        Err(NotProbed)
+    }
+}
+
+fn is_changed(bits: u64, index: usize) -> bool {
+    index >= BITMAP_FIELDS || bits & (1 << index) != 0
+}
+
+/// Deserializes the changed fields from the delta, and the unchanged fields from the scratch
+/// buffer.
+struct DeltaFields<'a, 'd, R: CoreRead<'a> + 'a, O: Options> {
+    deserializer: &'d mut Deserializer<'a, R, O>,
+    unchanged: &'a [u8],
+    bits: u64,
+}
+
+impl<'a, 'd, R: CoreRead<'a> + 'a, O: Options> DeltaFields<'a, 'd, R, O> {
+    fn field<S: DeserializeSeed<'a>>(
+        &mut self,
+        index: usize,
+        seed: S,
+    ) -> Result<S::Value, DeserializeError<'a, R>> {
+        if is_changed(self.bits, index) {
+            return self.deserializer.deserialize_field(index, seed);
+        }
+        let options = WithOtherLimit::new(self.deserializer.options_mut(), Infinite);
+        let mut unchanged = Deserializer::new(self.unchanged, options);
+        // The scratch buffer was just written, so reading it only fails if the `Serialize` and
+        // `Deserialize` implementations of the field do not match.
+        let value = seed
+            .deserialize(&mut unchanged)
+            .map_err(|error| error.map_read(|_| DeserializeError::Custom))?;
+        self.unchanged = unchanged.into_reader();
+        Ok(value)
+    }
+}
+
+impl<'a, 'd, 'f, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
+    for &'f mut DeltaFields<'a, 'd, R, O>
+{
+    type Error = DeserializeError<'a, R>;
+
+    fn deserialize_any<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(DeserializeError::Unsupported("deserialize_any"))
+    }
+
+    fn deserialize_struct<V: Visitor<'a>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(DeltaAccess {
+            fields: self,
+            index: 0,
+            len: fields.len(),
+        })
+    }
+
+    forward_to_deserialize_any! {
+        <W: Visitor<'a>>
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier ignored_any
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+struct DeltaAccess<'f, 'a, 'd, R: CoreRead<'a> + 'a, O: Options> {
+    fields: &'f mut DeltaFields<'a, 'd, R, O>,
+    index: usize,
+    len: usize,
+}
+
+impl<'f, 'a, 'd, R: CoreRead<'a> + 'a, O: Options> SeqAccess<'a> for DeltaAccess<'f, 'a, 'd, R, O> {
+    type Error = DeserializeError<'a, R>;
+
+    fn next_element_seed<S: DeserializeSeed<'a>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Self::Error> {
+        if self.index == self.len {
+            return Ok(None);
+        }
+        let value = self.fields.field(self.index, seed)?;
+        self.index += 1;
+        Ok(Some(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.len - self.index)
+    }
+}
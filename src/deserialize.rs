@@ -1,17 +1,32 @@
 use super::*;
-use config::{BincodeByteOrder, IntEncoding, LimitError, Options, SizeLimit};
+use crate::compat::U16_BYTE;
+use config::{
+    bitmap_len, BincodeByteOrder, BoolEncoding, CharEncoding, DecodeHardening, DepthLimit,
+    DiscriminantEncoding, ElementLimit, FloatEncoding, Format, IntEncoding, LimitError,
+    OptionEncoding, Options, SizeLimit, StructEncoding, TrailingBytes, TypeTag, TypeTagEncoding,
+    UnknownVariants, UsizeEncoding, BITMAP_FIELDS,
+};
 use core::str::Utf8Error;
 use core::{marker::PhantomData, str};
-use serde::{de::*, serde_if_integer128};
+use primitive_slice::{is_big_endian, PrimitiveBytesVisitor, PrimitiveKind};
+use serde::de::*;
+// The serde trait, not `crate::Error` from `super::*`
+use serde::de::Error;
 
-// #[cfg(feature = "alloc")]
-// use alloc::{string::String, vec::Vec};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
 use std::error::Error as StdError;
 
 /// Deserialize a given object from the given [CoreRead] object.
 ///
+/// With the default configuration, [DeserializeError::TrailingBytes] is returned if the reader has
+/// bytes left after the object is read. Use
+/// [allow_trailing_bytes](config::Options::allow_trailing_bytes) to ignore them.
+///
 /// Rust will detect the first two generic arguments automatically. The third generic argument
 /// must be a valid `byteorder::ByteOrder` type. Normally this can be implemented like this:
 ///
@@ -43,12 +58,374 @@ pub fn deserialize<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
     reader: R,
     options: O,
 ) -> Result<T, DeserializeError<'a, R>> {
-    let mut deserializer = Deserializer {
-        reader,
-        options,
-        _lifetime: PhantomData,
-    };
-    T::deserialize(&mut deserializer)
+    let mut deserializer = Deserializer::new(reader, options);
+    let value = T::deserialize(&mut deserializer)?;
+    O::Trailing::check_end(&deserializer.reader)?;
+    Ok(value)
+}
+
+/// Deserialize a value from the given [CoreRead] object with a `DeserializeSeed`, instead of a
+/// `Deserialize` implementation.
+///
+/// The seed is state that the deserialization can use, e.g. an arena or a pool of objects to
+/// deserialize into, so the value does not have to be returned by value. Trailing bytes are
+/// handled like [deserialize].
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::{deserialize_seed, DefaultOptions};
+/// use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error};
+///
+/// #[derive(Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+/// struct Reading {
+///     sensor: u8,
+///     value: i16,
+/// }
+///
+/// /// A pool of readings with a fixed size, e.g. in a `static` on the device
+/// struct Pool {
+///     slots: [Reading; 4],
+///     used: usize,
+/// }
+///
+/// impl<'de, 'p> DeserializeSeed<'de> for &'p mut Pool {
+///     /// The slot that the reading is deserialized into
+///     type Value = &'p mut Reading;
+///
+///     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+///         let reading = Reading::deserialize(deserializer)?;
+///         let slot = self
+///             .slots
+///             .get_mut(self.used)
+///             .ok_or_else(|| D::Error::custom("the pool is full"))?;
+///         self.used += 1;
+///         *slot = reading;
+///         Ok(slot)
+///     }
+/// }
+///
+/// let mut pool = Pool { slots: [Reading::default(); 4], used: 0 };
+/// let buffer: [u8; 4] = [
+///     3, // sensor
+///     251, 0x2C, 0x01, // value, 150 as a zigzag varint
+/// ];
+/// let reading = deserialize_seed(&mut pool, &buffer[..], DefaultOptions::new()).unwrap();
+/// assert_eq!(*reading, Reading { sensor: 3, value: 150 });
+/// assert_eq!(pool.used, 1);
+/// ```
+pub fn deserialize_seed<'a, S, R, O>(
+    seed: S,
+    reader: R,
+    options: O,
+) -> Result<S::Value, DeserializeError<'a, R>>
+where
+    S: DeserializeSeed<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+{
+    let mut deserializer = Deserializer::new(reader, options);
+    let value = seed.deserialize(&mut deserializer)?;
+    O::Trailing::check_end(&deserializer.reader)?;
+    Ok(value)
+}
+
+/// Deserialize a given object from the given [CoreRead] object, like [deserialize]. If this fails,
+/// the error contains the byte offset and the [FieldPath] at which decoding failed.
+///
+/// ```
+/// # extern crate serde_derive;
+/// # use serde_derive::Deserialize;
+/// # use bincode_core::{deserialize_with_context, DefaultOptions, DeserializeError};
+///
+/// #[derive(Deserialize, Debug)]
+/// pub struct SomeStruct {
+///     a: u8,
+///     b: bool,
+/// }
+/// let buffer: [u8; 2] = [
+///     3, // a
+///     6, // b
+/// ];
+/// let err = deserialize_with_context::<SomeStruct, _, _>(&buffer[..], DefaultOptions::new())
+///     .unwrap_err();
+/// assert!(matches!(err.error, DeserializeError::InvalidBoolValue(6)));
+/// assert_eq!(err.offset, 1);
+/// assert_eq!(err.path.indices(), &[1]);
+/// ```
+pub fn deserialize_with_context<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
+    reader: R,
+    options: O,
+) -> Result<T, ContextError<'a, R>> {
+    let mut deserializer = Deserializer::new(reader, options);
+    let value = T::deserialize(&mut deserializer).map_err(|error| ContextError {
+        error,
+        offset: deserializer.error_offset,
+        path: deserializer.path,
+    })?;
+    O::Trailing::check_end(&deserializer.reader).map_err(|error| ContextError {
+        error,
+        offset: deserializer.bytes_read,
+        path: FieldPath::new(),
+    })?;
+    Ok(value)
+}
+
+/// Deserialize a map from the given [CoreRead] object, calling `f` for every key/value pair
+/// instead of collecting them into a map type.
+///
+/// This makes it possible to consume map-encoded payloads without a map implementation, e.g. on
+/// `no_std` targets. The length of the map is checked against the configured maximum element length
+/// before the first entry is read. Returns the amount of entries that were read.
+///
+/// ```
+/// # use bincode_core::{deserialize_map_with, DefaultOptions};
+/// let buffer: [u8; 5] = [
+///     2, // length
+///     1, 10, // 1 => 10
+///     2, 20, // 2 => 20
+/// ];
+/// let mut sum = 0;
+/// let len = deserialize_map_with(&buffer[..], DefaultOptions::new(), |key: u8, value: u8| {
+///     sum += key * value;
+/// })
+/// .unwrap();
+/// assert_eq!(len, 2);
+/// assert_eq!(sum, 50);
+/// ```
+pub fn deserialize_map_with<'a, K, V, R, O, F>(
+    reader: R,
+    options: O,
+    f: F,
+) -> Result<usize, DeserializeError<'a, R>>
+where
+    K: Deserialize<'a>,
+    V: Deserialize<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+    F: FnMut(K, V),
+{
+    struct EntryVisitor<K, V, F> {
+        f: F,
+        _entry: PhantomData<(K, V)>,
+    }
+
+    impl<'de, K: Deserialize<'de>, V: Deserialize<'de>, F: FnMut(K, V)> Visitor<'de>
+        for EntryVisitor<K, V, F>
+    {
+        type Value = usize;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            formatter.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<usize, A::Error> {
+            let mut len = 0;
+            while let Some((key, value)) = map.next_entry()? {
+                (self.f)(key, value);
+                len += 1;
+            }
+            Ok(len)
+        }
+    }
+
+    let mut deserializer = Deserializer::new(reader, options);
+    let len = serde::Deserializer::deserialize_map(
+        &mut deserializer,
+        EntryVisitor {
+            f,
+            _entry: PhantomData,
+        },
+    )?;
+    O::Trailing::check_end(&deserializer.reader)?;
+    Ok(len)
+}
+
+/// Deserialize a map from the given [CoreRead] object into a map with a fixed capacity, like a
+/// `heapless::FnvIndexMap` with the `heapless` feature.
+///
+/// The length of the map is checked against the capacity before the first entry is read, and a map
+/// that does not fit fails with [CapacityExceeded](DeserializeError::CapacityExceeded), even if
+/// some of its keys occur more than once. For those keys, the last value is kept.
+///
+/// ```
+/// # #[cfg(feature = "heapless")]
+/// # {
+/// # use bincode_core::{deserialize_fixed_map, DefaultOptions, DeserializeError};
+/// use heapless::{FnvIndexMap, LinearMap};
+///
+/// let buffer: [u8; 9] = [
+///     2, // length
+///     3, b'f', b'a', b'n', 1, // "fan" => 1
+///     1, b'x', 0, // "x" => 0
+/// ];
+/// let map: FnvIndexMap<&str, u8, 4> =
+///     deserialize_fixed_map(&buffer[..], DefaultOptions::new()).unwrap();
+/// assert_eq!(map["fan"], 1);
+///
+/// let small = deserialize_fixed_map::<LinearMap<&str, u8, 1>, _, _>(&buffer[..], DefaultOptions::new());
+/// match small {
+///     Err(DeserializeError::CapacityExceeded { len: 2, capacity: 1 }) => {}
+///     x => panic!("Expected CapacityExceeded, got {:?}", x),
+/// }
+/// # }
+/// ```
+pub fn deserialize_fixed_map<'a, M, R, O>(
+    reader: R,
+    options: O,
+) -> Result<M, DeserializeError<'a, R>>
+where
+    M: FixedMap + Default,
+    M::Key: Deserialize<'a>,
+    M::Value: Deserialize<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+{
+    struct MapVisitor<M>(M);
+
+    impl<'de, M> Visitor<'de> for MapVisitor<M>
+    where
+        M: FixedMap,
+        M::Key: Deserialize<'de>,
+        M::Value: Deserialize<'de>,
+    {
+        type Value = M;
+
+        fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(
+                formatter,
+                "a map with at most {} entries",
+                self.0.capacity()
+            )
+        }
+
+        fn visit_map<A: MapAccess<'de>>(mut self, mut map: A) -> Result<M, A::Error> {
+            while let Some((key, value)) = map.next_entry()? {
+                if self.0.insert(key, value).is_err() {
+                    return Err(A::Error::custom("the map is full"));
+                }
+            }
+            Ok(self.0)
+        }
+    }
+
+    let map = M::default();
+    let capacity = map.capacity();
+    let mut deserializer = Deserializer::new(reader, options);
+    let map = deserializer.deserialize_map_within(capacity, MapVisitor(map))?;
+    O::Trailing::check_end(&deserializer.reader)?;
+    Ok(map)
+}
+
+/// Deserialize a single value from the start of the given byte slice, returning the value together
+/// with the bytes that were not consumed.
+///
+/// This makes it possible to decode several back-to-back messages from the same buffer without an
+/// outer framing layer.
+///
+/// ```
+/// # use bincode_core::{deserialize_with_remaining, DefaultOptions};
+/// let buffer: [u8; 3] = [1, 2, 3];
+/// let options = DefaultOptions::new();
+///
+/// let (first, rest): (u8, &[u8]) = deserialize_with_remaining(&buffer[..], options).unwrap();
+/// assert_eq!(first, 1);
+/// assert_eq!(rest, &[2, 3]);
+///
+/// let (second, rest): (u16, &[u8]) = deserialize_with_remaining(rest, options).unwrap();
+/// assert_eq!(second, 2);
+/// assert_eq!(rest, &[3]);
+/// ```
+pub fn deserialize_with_remaining<'a, T: Deserialize<'a>, O: Options>(
+    bytes: &'a [u8],
+    options: O,
+) -> Result<(T, &'a [u8]), DeserializeError<'a, &'a [u8]>> {
+    let mut deserializer = Deserializer::new(bytes, options);
+    let value = T::deserialize(&mut deserializer)?;
+    Ok((value, deserializer.reader))
+}
+
+/// Deserialize a single value from the given [CoreRead] object, returning the value together with
+/// the amount of bytes that were read.
+///
+/// Like [deserialize_with_remaining], trailing bytes are never rejected. This is useful to advance
+/// the read position of e.g. a ring buffer.
+///
+/// ```
+/// # use bincode_core::{deserialize_counted, DefaultOptions};
+/// let buffer: [u8; 4] = [1, 2, 0, 3];
+/// let (value, len): ((u8, u16), u64) =
+///     deserialize_counted(&buffer[..], DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, 2));
+/// assert_eq!(len, 2);
+/// ```
+pub fn deserialize_counted<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
+    reader: R,
+    options: O,
+) -> Result<(T, u64), DeserializeError<'a, R>> {
+    let mut deserializer = Deserializer::new(reader, options);
+    let value = T::deserialize(&mut deserializer)?;
+    Ok((value, deserializer.bytes_read()))
+}
+
+/// Deserialize values of type `T` from the given [CoreRead] object until it reaches the end.
+///
+/// This reads values that were written back-to-back, e.g. by [serialize_all](crate::serialize_all).
+/// The end of the input is detected with [CoreRead::remaining_len]. Readers that have no known
+/// end, like a serial port, produce an iterator that only stops after an error.
+///
+/// Trailing bytes are never rejected, regardless of the configuration.
+///
+/// ```
+/// # use bincode_core::{deserialize_iter, DefaultOptions};
+/// let buffer: [u8; 3] = [1, 2, 3];
+/// let mut values = deserialize_iter::<u8, _, _>(&buffer[..], DefaultOptions::new());
+/// assert_eq!(values.next().unwrap().unwrap(), 1);
+/// assert_eq!(values.next().unwrap().unwrap(), 2);
+/// assert_eq!(values.next().unwrap().unwrap(), 3);
+/// assert!(values.next().is_none());
+/// ```
+pub fn deserialize_iter<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
+    reader: R,
+    options: O,
+) -> DeserializeIterator<'a, T, R, O> {
+    DeserializeIterator {
+        deserializer: Deserializer::new(reader, options),
+        failed: false,
+        _value: PhantomData,
+    }
+}
+
+/// An iterator that deserializes values until the reader reaches the end. See [deserialize_iter].
+///
+/// After an error is returned, the iterator stops.
+pub struct DeserializeIterator<'a, T, R: CoreRead<'a> + 'a, O: Options> {
+    deserializer: Deserializer<'a, R, O>,
+    failed: bool,
+    _value: PhantomData<T>,
+}
+
+impl<'a, T, R: CoreRead<'a> + 'a, O: Options> DeserializeIterator<'a, T, R, O> {
+    /// Return the inner reader.
+    pub fn into_reader(self) -> R {
+        self.deserializer.into_reader()
+    }
+}
+
+impl<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options> Iterator
+    for DeserializeIterator<'a, T, R, O>
+{
+    type Item = Result<T, DeserializeError<'a, R>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.deserializer.reader.remaining_len() == Some(0) {
+            return None;
+        }
+        // Every item starts on a new byte, see serialize_all
+        self.deserializer.bool_count = 0;
+        let result = T::deserialize(&mut self.deserializer);
+        self.failed = result.is_err();
+        Some(result)
+    }
 }
 
 /// Errors that can occur while deserializing
@@ -88,6 +465,303 @@ pub enum DeserializeError<'a, R: CoreRead<'a>> {
 
     /// Byte 255 is treated as an extension point; it should not be encoding anything. Do you have a mismatched bincode version or configuration?
     ExtensionPoint,
+
+    /// A LEB128 varint is longer than its type allows, or does not fit in its type.
+    InvalidVarint,
+
+    /// A varint is encoded with more bytes than necessary, and the configuration only accepts the
+    /// shortest encoding. See [with_canonical_varints](config::Options::with_canonical_varints).
+    NonCanonical,
+
+    /// A `&str`, `&[u8]`, sequence or map declared a length that is larger than the configured
+    /// maximum element length.
+    ElementTooLarge {
+        /// The length that was declared in the input
+        len: usize,
+
+        /// The configured maximum element length
+        max: usize,
+    },
+
+    /// The reader had bytes left over after deserialization, and the configuration rejects trailing
+    /// bytes.
+    TrailingBytes,
+
+    /// The version tag in front of a versioned message does not match the expected version. See
+    /// [deserialize_versioned](crate::versioned::deserialize_versioned).
+    VersionMismatch {
+        /// The version that was expected
+        expected: u32,
+
+        /// The version that was found in the input
+        found: u32,
+    },
+
+    /// The schema fingerprint in front of a message does not match the fingerprint of the type
+    /// that is being deserialized. See
+    /// [deserialize_with_fingerprint](crate::schema::deserialize_with_fingerprint).
+    SchemaMismatch {
+        /// The fingerprint that was expected
+        expected: u32,
+
+        /// The fingerprint that was found in the input
+        found: u32,
+    },
+
+    /// The MAC after a message does not match the MAC of the message. The message was changed, or
+    /// the MAC was set up with a different key. See
+    /// [deserialize_with_mac](crate::mac::deserialize_with_mac).
+    MacMismatch,
+
+    /// Sequences, maps, options and enums are nested deeper than allowed. See
+    /// [with_max_depth](config::Options::with_max_depth) and
+    /// [HardenedDecoding](config::HardenedDecoding).
+    DepthLimitExceeded,
+
+    /// A `&str`, `&[u8]`, sequence or map declared a length that is larger than the amount of
+    /// bytes left in the reader. See [HardenedDecoding](config::HardenedDecoding).
+    LengthExceedsInput {
+        /// The length that was declared in the input
+        len: usize,
+
+        /// The amount of bytes left in the reader
+        remaining: usize,
+    },
+
+    /// The `Deserialize` implementation rejected the input, e.g. because of an unknown enum variant
+    /// index. The message of the error is discarded, because it cannot be stored without
+    /// allocating.
+    Custom,
+
+    /// The `Deserialize` implementation requires a `serde::Deserializer` method that bincode does
+    /// not support, like `deserialize_any` without [TypeTags](config::TypeTags). This is also returned for `peek` when
+    /// [NicheOptions](config::NicheOptions) are used with a reader that cannot
+    /// [peek](CoreRead::peek).
+    Unsupported(&'static str),
+
+    /// The type tag in front of a value is unknown, or does not match the type that is being
+    /// deserialized. See [TypeTags](config::TypeTags).
+    UnexpectedTypeTag(u8),
+
+    /// The fields of a struct took more bytes than the byte length in front of the struct. See
+    /// [CountedStructs](config::CountedStructs).
+    InvalidStructLength {
+        /// The byte length that was declared in the input
+        len: usize,
+
+        /// The amount of bytes that the fields took
+        read: usize,
+    },
+
+    /// The value in a frame took more bytes than the length in front of the frame. See
+    /// [deserialize_framed](crate::deserialize_framed).
+    InvalidFrameLength {
+        /// The byte length that was declared in the input
+        len: usize,
+
+        /// The amount of bytes that the value took
+        read: usize,
+    },
+
+    /// The tag in front of a message has no registered handler. See
+    /// [Dispatcher](crate::dispatch::Dispatcher).
+    UnknownTag(u32),
+
+    /// The scratch buffer is too small for the unchanged fields of the previous value, or for the
+    /// bytes of a message. See [apply_delta](crate::delta::apply_delta) and
+    /// [PushDeserializer](crate::PushDeserializer).
+    ScratchTooSmall,
+
+    /// The reader ran out of bytes in the middle of a value. The input is not necessarily corrupt:
+    /// when more bytes arrive, deserialization can be retried. See
+    /// [bytes_needed](DeserializeError::bytes_needed).
+    ///
+    /// This is only detected for readers that know their [remaining
+    /// length](CoreRead::remaining_len). Other readers return their own error in
+    /// [Read](DeserializeError::Read).
+    UnexpectedEof {
+        /// The amount of bytes that are missing to read the current part of the value. More bytes
+        /// may be needed for the rest of the value.
+        needed: usize,
+    },
+
+    /// A map declared more entries than the fixed-capacity map that it is deserialized into can
+    /// hold. See [deserialize_fixed_map].
+    CapacityExceeded {
+        /// The amount of entries that was declared in the input
+        len: usize,
+
+        /// The capacity of the map
+        capacity: usize,
+    },
+
+    /// The reader has no byte available yet, and returns instead of waiting for one. The value can
+    /// be read by polling again when more bytes arrive. See [NbReader](crate::NbReader) and
+    /// [map_would_block](DeserializeError::map_would_block).
+    WouldBlock,
+}
+
+impl<'a, R: CoreRead<'a>> DeserializeError<'a, R> {
+    /// Returns the minimum amount of bytes that have to be added to the input before
+    /// deserialization can succeed, or `None` if the error is not caused by missing input.
+    ///
+    /// Streaming callers can use this to wait for more data and retry, instead of treating every
+    /// error as corrupt data. The input can still turn out to be corrupt once more bytes arrive.
+    ///
+    /// ```
+    /// # use bincode_core::{deserialize, DefaultOptions};
+    /// // A `&str` of 5 bytes, of which only 2 have arrived
+    /// let buffer = [5u8, b'h', b'e'];
+    /// let err = deserialize::<&str, _, _>(&buffer[..], DefaultOptions::new()).unwrap_err();
+    /// assert_eq!(err.bytes_needed(), Some(3));
+    ///
+    /// let err = deserialize::<bool, _, _>(&[2u8][..], DefaultOptions::new()).unwrap_err();
+    /// assert_eq!(err.bytes_needed(), None);
+    /// ```
+    pub fn bytes_needed(&self) -> Option<usize> {
+        match self {
+            DeserializeError::UnexpectedEof { needed } => Some(*needed),
+            DeserializeError::LengthExceedsInput { len, remaining } => Some(len - remaining),
+            _ => None,
+        }
+    }
+
+    /// The category of the error.
+    ///
+    /// ```
+    /// # use bincode_core::{deserialize, DefaultOptions, ErrorKind};
+    /// let err = deserialize::<bool, _, _>(&[2u8][..], DefaultOptions::new()).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::InvalidData);
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            DeserializeError::Read(_) => ErrorKind::Read,
+            DeserializeError::UnexpectedEof { .. }
+            | DeserializeError::LengthExceedsInput { .. }
+            | DeserializeError::WouldBlock => ErrorKind::UnexpectedEof,
+            DeserializeError::InvalidBoolValue(_)
+            | DeserializeError::InvalidCharEncoding
+            | DeserializeError::Utf8(_)
+            | DeserializeError::InvalidOptionValue(_)
+            | DeserializeError::InvalidCast { .. }
+            | DeserializeError::InvalidUtf8Encoding(_)
+            | DeserializeError::InvalidValueRange
+            | DeserializeError::ExtensionPoint
+            | DeserializeError::InvalidVarint
+            | DeserializeError::NonCanonical
+            | DeserializeError::TrailingBytes
+            | DeserializeError::UnexpectedTypeTag(_)
+            | DeserializeError::InvalidStructLength { .. }
+            | DeserializeError::InvalidFrameLength { .. } => ErrorKind::InvalidData,
+            DeserializeError::VersionMismatch { .. }
+            | DeserializeError::SchemaMismatch { .. }
+            | DeserializeError::MacMismatch => ErrorKind::Mismatch,
+            DeserializeError::LimitError(_)
+            | DeserializeError::ElementTooLarge { .. }
+            | DeserializeError::DepthLimitExceeded
+            | DeserializeError::ScratchTooSmall
+            | DeserializeError::CapacityExceeded { .. } => ErrorKind::LimitExceeded,
+            DeserializeError::Unsupported(_) | DeserializeError::UnknownTag(_) => {
+                ErrorKind::Unsupported
+            }
+            DeserializeError::Custom => ErrorKind::Custom,
+        }
+    }
+
+    /// Converts the error to an error of a different reader, with `f` for read errors.
+    pub(crate) fn map_read<'b, R2: CoreRead<'b>>(
+        self,
+        f: impl FnOnce(R::Error) -> DeserializeError<'b, R2>,
+    ) -> DeserializeError<'b, R2> {
+        match self {
+            DeserializeError::Read(e) => f(e),
+            DeserializeError::InvalidBoolValue(v) => DeserializeError::InvalidBoolValue(v),
+            DeserializeError::InvalidCharEncoding => DeserializeError::InvalidCharEncoding,
+            DeserializeError::Utf8(e) => DeserializeError::Utf8(e),
+            DeserializeError::InvalidOptionValue(v) => DeserializeError::InvalidOptionValue(v),
+            DeserializeError::LimitError(e) => DeserializeError::LimitError(e),
+            DeserializeError::InvalidCast { from_type, to_type } => {
+                DeserializeError::InvalidCast { from_type, to_type }
+            }
+            DeserializeError::InvalidUtf8Encoding(e) => DeserializeError::InvalidUtf8Encoding(e),
+            DeserializeError::InvalidValueRange => DeserializeError::InvalidValueRange,
+            DeserializeError::ExtensionPoint => DeserializeError::ExtensionPoint,
+            DeserializeError::InvalidVarint => DeserializeError::InvalidVarint,
+            DeserializeError::NonCanonical => DeserializeError::NonCanonical,
+            DeserializeError::ElementTooLarge { len, max } => {
+                DeserializeError::ElementTooLarge { len, max }
+            }
+            DeserializeError::TrailingBytes => DeserializeError::TrailingBytes,
+            DeserializeError::VersionMismatch { expected, found } => {
+                DeserializeError::VersionMismatch { expected, found }
+            }
+            DeserializeError::SchemaMismatch { expected, found } => {
+                DeserializeError::SchemaMismatch { expected, found }
+            }
+            DeserializeError::MacMismatch => DeserializeError::MacMismatch,
+            DeserializeError::DepthLimitExceeded => DeserializeError::DepthLimitExceeded,
+            DeserializeError::LengthExceedsInput { len, remaining } => {
+                DeserializeError::LengthExceedsInput { len, remaining }
+            }
+            DeserializeError::Custom => DeserializeError::Custom,
+            DeserializeError::Unsupported(method) => DeserializeError::Unsupported(method),
+            DeserializeError::UnexpectedTypeTag(tag) => DeserializeError::UnexpectedTypeTag(tag),
+            DeserializeError::InvalidStructLength { len, read } => {
+                DeserializeError::InvalidStructLength { len, read }
+            }
+            DeserializeError::InvalidFrameLength { len, read } => {
+                DeserializeError::InvalidFrameLength { len, read }
+            }
+            DeserializeError::UnknownTag(tag) => DeserializeError::UnknownTag(tag),
+            DeserializeError::ScratchTooSmall => DeserializeError::ScratchTooSmall,
+            DeserializeError::UnexpectedEof { needed } => {
+                DeserializeError::UnexpectedEof { needed }
+            }
+            DeserializeError::CapacityExceeded { len, capacity } => {
+                DeserializeError::CapacityExceeded { len, capacity }
+            }
+            DeserializeError::WouldBlock => DeserializeError::WouldBlock,
+        }
+    }
+
+    /// Converts the read errors for which `would_block` returns `true` into
+    /// [WouldBlock](DeserializeError::WouldBlock), e.g. the `WouldBlock` error of a non-blocking
+    /// reader, so polling code can tell them apart from other read errors without knowing the
+    /// reader.
+    ///
+    /// ```
+    /// # use bincode_core::{deserialize, CoreRead, DefaultOptions, DeserializeError};
+    /// # use std::collections::VecDeque;
+    /// /// A reader of a FIFO that is filled by an interrupt
+    /// struct Fifo<'a>(&'a mut VecDeque<u8>);
+    ///
+    /// #[derive(Debug)]
+    /// struct Empty;
+    ///
+    /// impl<'a> CoreRead<'a> for Fifo<'_> {
+    ///     type Error = Empty;
+    ///
+    ///     fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Empty> {
+    ///         for byte in buffer {
+    ///             *byte = self.0.pop_front().ok_or(Empty)?;
+    ///         }
+    ///         Ok(())
+    ///     }
+    ///     # fn forward_str<V: serde::de::Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Empty> { Err(Empty) }
+    ///     # fn forward_bytes<V: serde::de::Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Empty> { Err(Empty) }
+    /// }
+    ///
+    /// let mut fifo = vec![251u8].into();
+    /// let err = deserialize::<u16, _, _>(Fifo(&mut fifo), DefaultOptions::new()).unwrap_err();
+    /// let err = err.map_would_block(|_: &Empty| true);
+    /// assert!(matches!(err, DeserializeError::WouldBlock));
+    /// ```
+    pub fn map_would_block(self, would_block: impl FnOnce(&R::Error) -> bool) -> Self {
+        match self {
+            DeserializeError::Read(e) if would_block(&e) => DeserializeError::WouldBlock,
+            error => error,
+        }
+    }
 }
 
 impl<'a, R: CoreRead<'a>> From<str::Utf8Error> for DeserializeError<'a, R> {
@@ -128,114 +802,851 @@ impl<'a, R: CoreRead<'a>> core::fmt::Debug for DeserializeError<'a, R> {
                 fmt,
                 "Byte 255 is treated as an extension point; it should not be encoding anything. Do you have a mismatched bincode version or configuration?"
             ),
+            DeserializeError::InvalidVarint => write!(fmt, "Invalid LEB128 varint"),
+            DeserializeError::NonCanonical => write!(fmt, "Varint is not in its shortest form"),
+            DeserializeError::ElementTooLarge { len, max } => write!(
+                fmt,
+                "Element length {} is larger than the maximum element length {}",
+                len, max
+            ),
+            DeserializeError::TrailingBytes => {
+                write!(fmt, "Bytes remaining after deserialization")
+            }
+            DeserializeError::VersionMismatch { expected, found } => write!(
+                fmt,
+                "Expected message version {}, found {}",
+                expected, found
+            ),
+            DeserializeError::SchemaMismatch { expected, found } => write!(
+                fmt,
+                "Expected schema fingerprint {:#010x}, found {:#010x}",
+                expected, found
+            ),
+            DeserializeError::MacMismatch => write!(fmt, "Message authentication failed"),
+            DeserializeError::DepthLimitExceeded => write!(fmt, "Nesting depth limit exceeded"),
+            DeserializeError::LengthExceedsInput { len, remaining } => write!(
+                fmt,
+                "Element length {} is larger than the {} bytes left in the input",
+                len, remaining
+            ),
+            DeserializeError::Custom => write!(fmt, "Custom error thrown"),
+            DeserializeError::Unsupported(method) => write!(fmt, "{} is not supported", method),
+            DeserializeError::UnexpectedTypeTag(tag) => write!(fmt, "Unexpected type tag {}", tag),
+            DeserializeError::InvalidStructLength { len, read } => write!(
+                fmt,
+                "Struct fields took {} bytes, more than the struct length {}",
+                read, len
+            ),
+            DeserializeError::InvalidFrameLength { len, read } => write!(
+                fmt,
+                "Framed value took {} bytes, more than the frame length {}",
+                read, len
+            ),
+            DeserializeError::UnknownTag(tag) => write!(fmt, "No handler for message tag {}", tag),
+            DeserializeError::ScratchTooSmall => write!(fmt, "Scratch buffer is too small"),
+            DeserializeError::UnexpectedEof { needed } => write!(
+                fmt,
+                "Unexpected end of input, {} more bytes are needed",
+                needed
+            ),
+            DeserializeError::CapacityExceeded { len, capacity } => write!(
+                fmt,
+                "Map with {} entries does not fit in a map with capacity {}",
+                len, capacity
+            ),
+            DeserializeError::WouldBlock => write!(fmt, "No bytes are available yet"),
+        }
+    }
+}
+
+impl<'a, R: CoreRead<'a>> core::fmt::Display for DeserializeError<'a, R> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+impl<'a, R: CoreRead<'a>> Error for DeserializeError<'a, R> {
+    fn custom<T: core::fmt::Display>(_cause: T) -> Self {
+        DeserializeError::Custom
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<'a, R: CoreRead<'a>> StdError for DeserializeError<'a, R> {}
+
+#[cfg(feature = "defmt")]
+impl<'a, R: CoreRead<'a>> defmt::Format for DeserializeError<'a, R>
+where
+    R::Error: defmt::Format,
+{
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            DeserializeError::Read(e) => defmt::write!(fmt, "{}", e),
+            DeserializeError::InvalidBoolValue(v) => {
+                defmt::write!(fmt, "Unknown bool value, got {}, expected 0 or 1", v)
+            }
+            DeserializeError::InvalidCharEncoding => {
+                defmt::write!(fmt, "Invalid character encoding")
+            }
+            DeserializeError::Utf8(e) => defmt::write!(
+                fmt,
+                "Could not deserialize the value as a value UTF8 string: valid up to {}",
+                e.valid_up_to()
+            ),
+            DeserializeError::InvalidOptionValue(e) => {
+                defmt::write!(fmt, "Invalid Option value, got {}, expected 0 or 1", e)
+            }
+            DeserializeError::LimitError(e) => defmt::write!(fmt, "Limit error {}", e),
+            DeserializeError::InvalidCast { from_type, to_type } => {
+                defmt::write!(fmt, "Could not cast from {} to {}", from_type, to_type)
+            }
+            DeserializeError::InvalidUtf8Encoding(error) => defmt::write!(
+                fmt,
+                "Invalid UTF8 encoding: valid up to {}",
+                error.valid_up_to()
+            ),
+            DeserializeError::InvalidValueRange => defmt::write!(
+                fmt,
+                "Invalid value (u128 range): you may have a version or configuration disagreement?"
+            ),
+            DeserializeError::ExtensionPoint => defmt::write!(
+                fmt,
+                "Byte 255 is treated as an extension point; it should not be encoding anything. Do you have a mismatched bincode version or configuration?"
+            ),
+            DeserializeError::InvalidVarint => defmt::write!(fmt, "Invalid LEB128 varint"),
+            DeserializeError::NonCanonical => {
+                defmt::write!(fmt, "Varint is not in its shortest form")
+            }
+            DeserializeError::ElementTooLarge { len, max } => defmt::write!(
+                fmt,
+                "Element length {} is larger than the maximum element length {}",
+                len,
+                max
+            ),
+            DeserializeError::TrailingBytes => {
+                defmt::write!(fmt, "Bytes remaining after deserialization")
+            }
+            DeserializeError::VersionMismatch { expected, found } => defmt::write!(
+                fmt,
+                "Expected message version {}, found {}",
+                expected,
+                found
+            ),
+            DeserializeError::SchemaMismatch { expected, found } => defmt::write!(
+                fmt,
+                "Expected schema fingerprint {=u32:#x}, found {=u32:#x}",
+                expected,
+                found
+            ),
+            DeserializeError::MacMismatch => defmt::write!(fmt, "Message authentication failed"),
+            DeserializeError::DepthLimitExceeded => {
+                defmt::write!(fmt, "Nesting depth limit exceeded")
+            }
+            DeserializeError::LengthExceedsInput { len, remaining } => defmt::write!(
+                fmt,
+                "Element length {} is larger than the {} bytes left in the input",
+                len,
+                remaining
+            ),
+            DeserializeError::Custom => defmt::write!(fmt, "Custom error thrown"),
+            DeserializeError::Unsupported(method) => {
+                defmt::write!(fmt, "{} is not supported", method)
+            }
+            DeserializeError::UnexpectedTypeTag(tag) => {
+                defmt::write!(fmt, "Unexpected type tag {}", tag)
+            }
+            DeserializeError::InvalidStructLength { len, read } => defmt::write!(
+                fmt,
+                "Struct fields took {} bytes, more than the struct length {}",
+                read,
+                len
+            ),
+            DeserializeError::InvalidFrameLength { len, read } => defmt::write!(
+                fmt,
+                "Framed value took {} bytes, more than the frame length {}",
+                read,
+                len
+            ),
+            DeserializeError::UnknownTag(tag) => {
+                defmt::write!(fmt, "No handler for message tag {}", tag)
+            }
+            DeserializeError::ScratchTooSmall => defmt::write!(fmt, "Scratch buffer is too small"),
+            DeserializeError::UnexpectedEof { needed } => defmt::write!(
+                fmt,
+                "Unexpected end of input, {} more bytes are needed",
+                needed
+            ),
+            DeserializeError::CapacityExceeded { len, capacity } => defmt::write!(
+                fmt,
+                "Map with {} entries does not fit in a map with capacity {}",
+                len,
+                capacity
+            ),
+            DeserializeError::WouldBlock => defmt::write!(fmt, "No bytes are available yet"),
+        }
+    }
+}
+
+/// A deserializer that can be used to deserialize any `serde::Deserialize` type from a given
+/// [CoreRead] reader.
+pub struct Deserializer<'a, R: CoreRead<'a> + 'a, O: Options> {
+    reader: R,
+    options: O,
+    bytes_read: u64,
+    error_offset: u64,
+    path: FieldPath,
+    depth: usize,
+    /// The packed bools that have not been read yet.
+    bool_bits: u8,
+    /// The amount of bits left in `bool_bits`.
+    bool_count: u8,
+    /// The type tag that `deserialize_any` has read for the next value.
+    pending_tag: Option<TypeTag>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+macro_rules! impl_deserialize_literal {
+    ($name:ident : $ty:ty = $read:ident()) => {
+        #[inline]
+        pub(crate) fn $name(&mut self) -> Result<$ty, DeserializeError<'a, R>> {
+            self.read_literal_type::<$ty>()?;
+            let mut buffer = [0u8; core::mem::size_of::<$ty>()];
+            self.reader
+                .fill(&mut buffer)
+                .map_err(DeserializeError::Read)?;
+            Ok(<<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::$read(&buffer))
+        }
+    };
+}
+
+macro_rules! impl_deserialize_varint_int {
+    ($name:ident : $ty:ty = $literal:ident, $read:ident()) => {
+        /// Reads the int that follows a varint marker, along with the marker if
+        /// [deserialize_varint_marker](Deserializer::deserialize_varint_marker) only peeked at it.
+        #[inline]
+        pub(crate) fn $name(&mut self, peeked: bool) -> Result<$ty, DeserializeError<'a, R>> {
+            // The marker is only read along with the int if all of the int is there, so that a
+            // missing int is reported like any other end of the input
+            if peeked && self.reader.peek(1 + core::mem::size_of::<$ty>()).is_none() {
+                self.reader.read().map_err(DeserializeError::Read)?;
+            } else if peeked {
+                self.read_literal_type::<$ty>()?;
+                let mut buffer = [0u8; 1 + core::mem::size_of::<$ty>()];
+                self.reader
+                    .fill(&mut buffer)
+                    .map_err(DeserializeError::Read)?;
+                return Ok(
+                    <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::$read(
+                        &buffer[1..],
+                    ),
+                );
+            }
+            self.$literal()
+        }
+    };
+}
+
+impl<'a, R: CoreRead<'a> + 'a, O: Options> Deserializer<'a, R, O> {
+    /// Create a new deserializer that reads from `reader`.
+    ///
+    /// This can be used to read several values from the same reader, or to mix deserialized values
+    /// with bytes that are read directly from [reader_mut](Deserializer::reader_mut).
+    ///
+    /// ```
+    /// # use bincode_core::{CoreRead, DefaultOptions, Deserializer};
+    /// # use serde::Deserialize;
+    /// let buffer = [1u8, 0xFF, 2];
+    /// let mut deserializer = Deserializer::new(&buffer[..], DefaultOptions::new());
+    ///
+    /// assert_eq!(u8::deserialize(&mut deserializer).unwrap(), 1);
+    /// let mut marker = [0u8];
+    /// deserializer.reader_mut().fill(&mut marker).unwrap();
+    /// assert_eq!(u8::deserialize(&mut deserializer).unwrap(), 2);
+    /// assert!(deserializer.into_reader().is_empty());
+    /// ```
+    pub fn new(reader: R, options: O) -> Self {
+        Deserializer {
+            reader,
+            options,
+            bytes_read: 0,
+            error_offset: 0,
+            path: FieldPath::new(),
+            depth: 0,
+            bool_bits: 0,
+            bool_count: 0,
+            pending_tag: None,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// A reference to the inner reader.
+    pub fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// A mutable reference to the inner reader. Bytes read directly from the reader do not count
+    /// towards the size limit or [bytes_read](Deserializer::bytes_read).
+    pub fn reader_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Return the inner reader.
+    pub fn into_reader(self) -> R {
+        self.reader
+    }
+
+    pub(crate) fn options_mut(&mut self) -> &mut O {
+        &mut self.options
+    }
+
+    /// The amount of bytes that this deserializer has consumed from the reader.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// The offset at which the last read started. After an error, this is the offset of the value
+    /// that could not be decoded.
+    pub fn error_offset(&self) -> u64 {
+        self.error_offset
+    }
+
+    /// The field that is currently being read. After an error, this is the field that could not
+    /// be decoded.
+    pub fn field_path(&self) -> &FieldPath {
+        &self.path
+    }
+
+    pub(crate) fn deserialize_field<T: DeserializeSeed<'a>>(
+        &mut self,
+        index: usize,
+        seed: T,
+    ) -> Result<T::Value, DeserializeError<'a, R>> {
+        self.path.push(index as u32);
+        let value = seed.deserialize(&mut *self)?;
+        // On errors the path is kept, so it points at the field that failed
+        self.path.pop();
+        Ok(value)
+    }
+
+    /// Deserializes a struct field that is covered by a presence bitmap.
+    fn deserialize_present_field<T: DeserializeSeed<'a>>(
+        &mut self,
+        index: usize,
+        present: bool,
+        seed: T,
+    ) -> Result<T::Value, DeserializeError<'a, R>> {
+        self.path.push(index as u32);
+        let value = seed.deserialize(PresenceField {
+            deserializer: &mut *self,
+            present,
+        })?;
+        self.path.pop();
+        Ok(value)
+    }
+
+    /// Visits `len` fields. If `presence` is set, it is the presence bitmap of a struct.
+    fn visit_fields<V: Visitor<'a>>(
+        &mut self,
+        len: usize,
+        presence: Option<u64>,
+        visitor: V,
+    ) -> Result<V::Value, DeserializeError<'a, R>> {
+        struct Access<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
+            deserializer: &'b mut Deserializer<'a, R, O>,
+            index: usize,
+            len: usize,
+            hint: usize,
+            presence: Option<u64>,
+        }
+
+        impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::de::SeqAccess<'a> for Access<'a, 'b, R, O> {
+            type Error = DeserializeError<'a, R>;
+
+            fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+            where
+                T: serde::de::DeserializeSeed<'a>,
+            {
+                if self.len > 0 {
+                    self.len -= 1;
+                    let value = match self.presence {
+                        Some(bits) if self.index < BITMAP_FIELDS => {
+                            let present = bits & (1 << self.index) != 0;
+                            self.deserializer
+                                .deserialize_present_field(self.index, present, seed)?
+                        }
+                        _ => self.deserializer.deserialize_field(self.index, seed)?,
+                    };
+                    self.index += 1;
+                    Ok(Some(value))
+                } else {
+                    Ok(None)
+                }
+            }
+
+            fn size_hint(&self) -> Option<usize> {
+                Some(self.len.min(self.hint))
+            }
+        }
+
+        let hint = self.size_hint(len);
+        let mut access = Access {
+            deserializer: self,
+            index: 0,
+            len,
+            hint,
+            presence,
+        };
+        let value = visitor.visit_seq(&mut access)?;
+        if O::TypeTags::TAGGED {
+            // Skip the fields that were added by a newer version of the struct
+            while access.next_element::<IgnoredAny>()?.is_some() {}
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn deserialize_byte(&mut self) -> Result<u8, DeserializeError<'a, R>> {
+        self.read_literal_type::<u8>()?;
+        self.reader.read().map_err(DeserializeError::Read)
+    }
+
+    /// Reads the marker byte of a varint, and returns it along with whether it was only peeked at.
+    ///
+    /// If the reader can [peek](CoreRead::peek) and the marker is followed by an int of up to
+    /// `max_marker`, the marker is left in the reader, so that the marker and the int are read with
+    /// a single call to the reader by e.g.
+    /// [deserialize_varint_u16](Deserializer::deserialize_varint_u16). This halves the reader calls
+    /// per int for readers where every call has a cost.
+    pub(crate) fn deserialize_varint_marker(
+        &mut self,
+        max_marker: u8,
+    ) -> Result<(u8, bool), DeserializeError<'a, R>> {
+        self.read_literal_type::<u8>()?;
+        match self.reader.peek(1).and_then(|bytes| bytes.first()) {
+            Some(&marker) if marker >= U16_BYTE && marker <= max_marker => Ok((marker, true)),
+            _ => Ok((self.reader.read().map_err(DeserializeError::Read)?, false)),
+        }
+    }
+
+    impl_deserialize_varint_int! {
+        deserialize_varint_u16 : u16 = deserialize_literal_u16, read_u16()
+    }
+    impl_deserialize_varint_int! {
+        deserialize_varint_u32 : u32 = deserialize_literal_u32, read_u32()
+    }
+    impl_deserialize_varint_int! {
+        deserialize_varint_u64 : u64 = deserialize_literal_u64, read_u64()
+    }
+    impl_deserialize_varint_int! {
+        deserialize_varint_u128 : u128 = deserialize_literal_u128, read_u128()
+    }
+
+    /// Reads the type tag of the next value, unless `deserialize_any` has already read it.
+    fn deserialize_type_tag(&mut self) -> Result<TypeTag, DeserializeError<'a, R>> {
+        if let Some(tag) = self.pending_tag.take() {
+            return Ok(tag);
+        }
+        let byte = self.deserialize_byte()?;
+        TypeTag::from_byte(byte).ok_or(DeserializeError::UnexpectedTypeTag(byte))
+    }
+
+    /// Checks that the next value has type tag `expected`, if the options use
+    /// [TypeTags](config::TypeTags).
+    fn expect_type_tag(&mut self, expected: TypeTag) -> Result<(), DeserializeError<'a, R>> {
+        if O::TypeTags::TAGGED {
+            let tag = self.deserialize_type_tag()?;
+            if tag != expected {
+                return Err(DeserializeError::UnexpectedTypeTag(tag as u8));
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the length of a tuple or struct, which is only written with
+    /// [TypeTags](config::TypeTags). Without type tags, this is `len`.
+    fn deserialize_tuple_len(&mut self, len: usize) -> Result<usize, DeserializeError<'a, R>> {
+        if !O::TypeTags::TAGGED {
+            return Ok(len);
+        }
+        self.expect_type_tag(TypeTag::Seq)?;
+        self.deserialize_element_len()
+    }
+
+    /// Reads the fields of a struct with [CountedStructs](config::CountedStructs), and skips the
+    /// fields that the visitor does not know. If `presence_bitmap` is set, the fields start with a
+    /// presence bitmap.
+    fn visit_counted_fields<V: Visitor<'a>>(
+        &mut self,
+        presence_bitmap: bool,
+        visitor: V,
+    ) -> Result<V::Value, DeserializeError<'a, R>> {
+        self.counted(|deserializer, count| {
+            let presence = if presence_bitmap {
+                Some(deserializer.deserialize_bitmap(count)?)
+            } else {
+                None
+            };
+            deserializer.visit_fields(count, presence, visitor)
+        })
+    }
+
+    /// Reads the field count and the byte length of a struct or enum variant with
+    /// [CountedStructs](config::CountedStructs), passes the field count to `f`, and skips the bytes
+    /// that `f` does not read.
+    fn counted<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self, usize) -> Result<T, DeserializeError<'a, R>>,
+    ) -> Result<T, DeserializeError<'a, R>> {
+        let count = O::Usize::deserialize_len(self)?;
+        let len = self.deserialize_element_len()?;
+        let start = self.bytes_read;
+        let value = f(self, count)?;
+        let read = (self.bytes_read - start) as usize;
+        if read > len {
+            return Err(DeserializeError::InvalidStructLength { len, read });
+        }
+        self.skip_bytes(len - read)?;
+        // The unused bits of the last packed bools byte belong to the struct
+        self.bool_count = 0;
+        Ok(value)
+    }
+
+    /// Reads a value with a byte length in front of it, and skips the bytes of the frame that the
+    /// value does not use. See [deserialize_framed](crate::deserialize_framed).
+    pub(crate) fn deserialize_frame<T: Deserialize<'a>>(
+        &mut self,
+    ) -> Result<T, DeserializeError<'a, R>> {
+        let len = self.deserialize_element_len()?;
+        let start = self.bytes_read;
+        let value = T::deserialize(&mut *self)?;
+        let read = (self.bytes_read - start) as usize;
+        if read > len {
+            return Err(DeserializeError::InvalidFrameLength { len, read });
+        }
+        self.skip_bytes(len - read)?;
+        // The unused bits of the last packed bools byte belong to the frame
+        self.bool_count = 0;
+        Ok(value)
+    }
+
+    /// Reads the presence bitmap of a struct with `fields` fields.
+    pub(crate) fn deserialize_bitmap(
+        &mut self,
+        fields: usize,
+    ) -> Result<u64, DeserializeError<'a, R>> {
+        let mut bitmap = [0u8; 8];
+        let len = bitmap_len(fields);
+        self.read_bytes(len as u64)?;
+        self.reader
+            .fill(&mut bitmap[..len])
+            .map_err(DeserializeError::Read)?;
+        Ok(u64::from_le_bytes(bitmap))
+    }
+
+    /// Reads and discards `count` bytes.
+    fn skip_bytes(&mut self, count: usize) -> Result<(), DeserializeError<'a, R>> {
+        self.read_bytes(count as u64)?;
+        let mut scratch = [0u8; 16];
+        let mut remaining = count;
+        while remaining > 0 {
+            let len = remaining.min(scratch.len());
+            self.reader
+                .fill(&mut scratch[..len])
+                .map_err(DeserializeError::Read)?;
+            remaining -= len;
+        }
+        Ok(())
+    }
+
+    /// Reads the discriminant of an enum variant, after the type tag of the enum with
+    /// [TypeTags](config::TypeTags).
+    pub(crate) fn deserialize_variant_index(&mut self) -> Result<u32, DeserializeError<'a, R>> {
+        self.expect_type_tag(TypeTag::Enum)?;
+        O::Discriminant::deserialize_discriminant(self)
+    }
+
+    /// Reads the content of a newtype enum variant with `f`. With
+    /// [CountedStructs](config::CountedStructs), the content starts with a field count and a byte
+    /// length, and the bytes that `f` does not read are skipped.
+    pub(crate) fn newtype_variant_content<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, DeserializeError<'a, R>>,
+    ) -> Result<T, DeserializeError<'a, R>> {
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            return self.counted(|deserializer, _| f(deserializer));
+        }
+        f(self)
+    }
+
+    /// Skips the content of an unknown enum variant, so it can be replaced with the
+    /// [UnknownVariants] fallback. Returns `false` if the content cannot be skipped, because its
+    /// length is not known and there are bytes left.
+    fn skip_variant(&mut self) -> Result<bool, DeserializeError<'a, R>> {
+        if O::TypeTags::TAGGED {
+            serde::de::IgnoredAny::deserialize(&mut *self)?;
+            return Ok(true);
+        }
+        if O::Structs::COUNTED {
+            self.counted(|_, _| Ok(()))?;
+            return Ok(true);
+        }
+        Ok(self.reader.remaining_len() == Some(0))
+    }
+
+    impl_deserialize_literal! { deserialize_literal_u16 : u16 = read_u16() }
+    impl_deserialize_literal! { deserialize_literal_u32 : u32 = read_u32() }
+    impl_deserialize_literal! { deserialize_literal_u64 : u64 = read_u64() }
+
+    impl_deserialize_literal! { deserialize_literal_u128 : u128 = read_u128() }
+
+    /// Deserializes the length prefix of a `&str`, `&[u8]`, sequence or map, and checks it against
+    /// the configured maximum element length.
+    fn deserialize_element_len(&mut self) -> Result<usize, DeserializeError<'a, R>> {
+        let len = O::Usize::deserialize_len(self)?;
+        if let Some(max) = self.options.element_limit().max_element_len() {
+            if len > max {
+                return Err(DeserializeError::ElementTooLarge { len, max });
+            }
+        }
+        if O::Hardening::CHECK_LENGTHS {
+            if let Some(remaining) = self.reader.remaining_len() {
+                if len > remaining {
+                    return Err(DeserializeError::LengthExceedsInput { len, remaining });
+                }
+            }
         }
+        Ok(len)
     }
-}
 
-impl<'a, R: CoreRead<'a>> core::fmt::Display for DeserializeError<'a, R> {
-    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
-        write!(fmt, "{:?}", self)
+    /// The size hint for a sequence or map of `len` elements. A corrupt length could make the
+    /// visitor reserve a huge amount of memory, so the hint is clamped to the amount of bytes that
+    /// can still be read, from the input or within the size limit. Almost every element takes at
+    /// least one byte.
+    fn size_hint(&mut self, len: usize) -> usize {
+        let mut hint = len.min(self.reader.remaining_len().unwrap_or(usize::MAX));
+        if let Some(limit) = self.options.read_limit().limit() {
+            if hint as u64 > limit {
+                hint = limit as usize;
+            }
+        }
+        hint
     }
-}
 
-impl<'a, R: CoreRead<'a>> Error for DeserializeError<'a, R> {
-    fn custom<T: core::fmt::Display>(_cause: T) -> Self {
-        panic!("Custom error thrown: {}", _cause);
-    }
-}
+    /// Deserializes a map, after checking that its declared length fits in `capacity` entries.
+    pub(crate) fn deserialize_map_within<V: Visitor<'a>>(
+        &mut self,
+        capacity: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeserializeError<'a, R>> {
+        struct Access<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
+            deserializer: &'b mut Deserializer<'a, R, O>,
+            index: usize,
+            len: usize,
+            hint: usize,
+        }
 
-#[cfg(feature = "std")]
-impl<'a, R: CoreRead<'a>> StdError for DeserializeError<'a, R> {}
+        impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::de::MapAccess<'a> for Access<'a, 'b, R, O> {
+            type Error = DeserializeError<'a, R>;
 
-/// A deserializer that can be used to deserialize any `serde::Deserialize` type from a given
-/// [CoreRead] reader.
-pub struct Deserializer<'a, R: CoreRead<'a> + 'a, O: Options> {
-    reader: R,
-    options: O,
-    _lifetime: PhantomData<&'a ()>,
-}
+            fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+            where
+                K: serde::de::DeserializeSeed<'a>,
+            {
+                if self.len > 0 {
+                    self.len -= 1;
+                    let key = self.deserializer.deserialize_field(self.index, seed)?;
+                    Ok(Some(key))
+                } else {
+                    Ok(None)
+                }
+            }
 
-macro_rules! impl_deserialize_literal {
-    ($name:ident : $ty:ty = $read:ident()) => {
-        #[inline]
-        pub(crate) fn $name(&mut self) -> Result<$ty, DeserializeError<'a, R>> {
-            self.read_literal_type::<$ty>()?;
-            let mut buffer = [0u8; core::mem::size_of::<$ty>()];
-            self.reader
-                .fill(&mut buffer)
-                .map_err(DeserializeError::Read)?;
-            Ok(<<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::$read(&buffer))
+            fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+            where
+                V: serde::de::DeserializeSeed<'a>,
+            {
+                let value = self.deserializer.deserialize_field(self.index, seed)?;
+                self.index += 1;
+                Ok(value)
+            }
+
+            fn size_hint(&self) -> Option<usize> {
+                Some(self.len.min(self.hint))
+            }
         }
-    };
-}
 
-impl<'a, R: CoreRead<'a> + 'a, O: Options> Deserializer<'a, R, O> {
-    pub(crate) fn deserialize_byte(&mut self) -> Result<u8, DeserializeError<'a, R>> {
-        self.read_literal_type::<u8>()?;
-        let mut buf = [0u8; 1];
-        self.reader.fill(&mut buf).map_err(DeserializeError::Read)?;
-        Ok(buf[0])
+        self.expect_type_tag(TypeTag::Map)?;
+        let len = self.deserialize_element_len()?;
+        if len > capacity {
+            return Err(DeserializeError::CapacityExceeded { len, capacity });
+        }
+        let hint = self.size_hint(len);
+
+        self.nested(|de| {
+            visitor.visit_map(Access {
+                deserializer: de,
+                index: 0,
+                len,
+                hint,
+            })
+        })
     }
 
-    impl_deserialize_literal! { deserialize_literal_u16 : u16 = read_u16() }
-    impl_deserialize_literal! { deserialize_literal_u32 : u32 = read_u32() }
-    impl_deserialize_literal! { deserialize_literal_u64 : u64 = read_u64() }
+    /// Deserializes a nested sequence, map, option or enum, checking the configured maximum depth.
+    fn nested<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, DeserializeError<'a, R>>,
+    ) -> Result<T, DeserializeError<'a, R>> {
+        let max = match (
+            self.options.depth_limit().max_depth(),
+            O::Hardening::MAX_DEPTH,
+        ) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(max) = max {
+            if self.depth >= max {
+                return Err(DeserializeError::DepthLimitExceeded);
+            }
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
 
-    serde_if_integer128! {
-        impl_deserialize_literal! { deserialize_literal_u128 : u128 = read_u128() }
+    /// Reads the next bit of the current byte of packed bools, or starts a new byte.
+    fn deserialize_packed_bool(&mut self) -> Result<bool, DeserializeError<'a, R>> {
+        if self.bool_count == 0 {
+            self.bool_bits = self.deserialize_byte()?;
+            self.bool_count = 8;
+        }
+        let value = self.bool_bits & 1 != 0;
+        self.bool_bits >>= 1;
+        self.bool_count -= 1;
+        Ok(value)
     }
 
     fn read_bytes(&mut self, count: u64) -> Result<(), DeserializeError<'a, R>> {
+        if O::Format::SELF_DESCRIBING {
+            return Err(DeserializeError::Unsupported(if O::Format::CBOR {
+                "CBOR input"
+            } else {
+                "MessagePack input"
+            }));
+        }
+        // Any other value ends a run of packed bools
+        self.bool_count = 0;
+        self.error_offset = self.bytes_read;
         self.options
-            .limit()
+            .read_limit()
             .add(count)
-            .map_err(DeserializeError::LimitError)
+            .map_err(DeserializeError::LimitError)?;
+        if let Some(remaining) = self.reader.remaining_len() {
+            if count > remaining as u64 {
+                return Err(DeserializeError::UnexpectedEof {
+                    needed: (count - remaining as u64) as usize,
+                });
+            }
+        }
+        self.bytes_read += count;
+        Ok(())
     }
 
     fn read_literal_type<T>(&mut self) -> Result<(), DeserializeError<'a, R>> {
         self.read_bytes(core::mem::size_of::<T>() as u64)
     }
+}
 
-    /*
-    #[cfg(feature = "alloc")]
-    fn read_vec(&mut self) -> Result<Vec<u8>, DeserializeError<'a, R>> {
-        let len = O::IntEncoding::deserialize_len(self)?;
-        self.read_bytes(len as u64)?;
-        self.reader.read_vec(len).map_err(DeserializeError::Read)
-    }
-
-    #[cfg(feature = "alloc")]
-    fn read_string(&mut self) -> Result<String, DeserializeError<'a, R>> {
-        let vec = self.read_vec()?;
-        String::from_utf8(vec)
-            .map_err(|e| DeserializeError::InvalidUtf8Encoding(e.utf8_error()).into())
+impl<'a, R: CheckpointRead<'a> + 'a, O: Options> Deserializer<'a, R, O> {
+    /// Try to deserialize a `T`, and roll back the reader if that fails.
+    ///
+    /// On failure, the reader, [bytes_read](Deserializer::bytes_read) and the size limit are reset
+    /// to where they were before the call, so a different type can be tried on the same bytes. This
+    /// is useful to dispatch messages that do not carry a type tag.
+    ///
+    /// ```
+    /// # use bincode_core::{config::Options, DefaultOptions, Deserializer};
+    /// let buffer = [3u8, b'a', b'b', b'c'];
+    /// let options = DefaultOptions::new().with_max_element_len(2);
+    /// let mut deserializer = Deserializer::new(&buffer[..], options);
+    ///
+    /// assert!(deserializer.try_deserialize::<&str>().is_err());
+    /// assert_eq!(deserializer.bytes_read(), 0);
+    /// assert_eq!(deserializer.try_deserialize::<(u8, [u8; 3])>().unwrap(), (3, *b"abc"));
+    /// ```
+    pub fn try_deserialize<T: Deserialize<'a>>(&mut self) -> Result<T, DeserializeError<'a, R>> {
+        let mark = self.reader.mark();
+        let bytes_read = self.bytes_read;
+        let path = self.path;
+        let bools = (self.bool_bits, self.bool_count);
+        let result = T::deserialize(&mut *self);
+        if result.is_err() {
+            self.reader.reset(mark);
+            self.options
+                .read_limit()
+                .rewind(self.bytes_read - bytes_read);
+            self.bytes_read = bytes_read;
+            self.path = path;
+            (self.bool_bits, self.bool_count) = bools;
+            self.pending_tag = None;
+        }
+        result
     }
-    */
 }
 
 macro_rules! impl_deserialize_int {
-    ($name:ident = $visitor_method:ident ($dser_method:ident)) => {
+    ($name:ident = $visitor_method:ident ($dser_method:ident, $tag:ident)) => {
         #[inline]
         fn $name<V>(self, visitor: V) -> Result<V::Value, Self::Error>
         where
             V: serde::de::Visitor<'a>,
         {
+            self.expect_type_tag(TypeTag::$tag)?;
             visitor.$visitor_method(O::IntEncoding::$dser_method(self)?)
         }
     };
 }
 
-impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
-    for &'b mut Deserializer<'a, R, O>
-{
+impl<'a, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a> for &mut Deserializer<'a, R, O> {
     type Error = DeserializeError<'a, R>;
 
-    fn deserialize_any<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
-        panic!("Deserialize any not supported")
+    fn deserialize_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if !O::TypeTags::TAGGED {
+            return Err(DeserializeError::Unsupported("deserialize_any"));
+        }
+        let tag = self.deserialize_type_tag()?;
+        self.pending_tag = Some(tag);
+        match tag {
+            TypeTag::Unit => self.deserialize_unit(visitor),
+            TypeTag::Bool => self.deserialize_bool(visitor),
+            TypeTag::U8 => self.deserialize_u8(visitor),
+            TypeTag::U16 => self.deserialize_u16(visitor),
+            TypeTag::U32 => self.deserialize_u32(visitor),
+            TypeTag::U64 => self.deserialize_u64(visitor),
+            TypeTag::U128 => self.deserialize_u128(visitor),
+            TypeTag::I8 => self.deserialize_i8(visitor),
+            TypeTag::I16 => self.deserialize_i16(visitor),
+            TypeTag::I32 => self.deserialize_i32(visitor),
+            TypeTag::I64 => self.deserialize_i64(visitor),
+            TypeTag::I128 => self.deserialize_i128(visitor),
+            TypeTag::F32 => self.deserialize_f32(visitor),
+            TypeTag::F64 => self.deserialize_f64(visitor),
+            TypeTag::Char => self.deserialize_char(visitor),
+            TypeTag::Str => self.deserialize_str(visitor),
+            TypeTag::Bytes => self.deserialize_bytes(visitor),
+            TypeTag::None | TypeTag::Some => self.deserialize_option(visitor),
+            TypeTag::Seq => self.deserialize_seq(visitor),
+            TypeTag::Map => self.deserialize_map(visitor),
+            TypeTag::Enum => self.deserialize_enum("", &[], visitor),
+        }
     }
 
     fn deserialize_bool<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let value: u8 = serde::Deserialize::deserialize(self)?;
+        self.expect_type_tag(TypeTag::Bool)?;
+        if O::Bools::PACKED {
+            return visitor.visit_bool(self.deserialize_packed_bool()?);
+        }
+        let value = self.deserialize_byte()?;
         match value {
             1 => visitor.visit_bool(true),
             0 => visitor.visit_bool(false),
@@ -244,51 +1655,46 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
     }
 
     fn deserialize_i8<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::I8)?;
         visitor.visit_i8(self.deserialize_byte()? as i8)
     }
 
     fn deserialize_u8<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        visitor.visit_u8(self.deserialize_byte()? as u8)
+        self.expect_type_tag(TypeTag::U8)?;
+        visitor.visit_u8(self.deserialize_byte()?)
     }
 
-    impl_deserialize_int!(deserialize_u16 = visit_u16(deserialize_u16));
-    impl_deserialize_int!(deserialize_u32 = visit_u32(deserialize_u32));
-    impl_deserialize_int!(deserialize_u64 = visit_u64(deserialize_u64));
-    impl_deserialize_int!(deserialize_i16 = visit_i16(deserialize_i16));
-    impl_deserialize_int!(deserialize_i32 = visit_i32(deserialize_i32));
-    impl_deserialize_int!(deserialize_i64 = visit_i64(deserialize_i64));
+    impl_deserialize_int!(deserialize_u16 = visit_u16(deserialize_u16, U16));
+    impl_deserialize_int!(deserialize_u32 = visit_u32(deserialize_u32, U32));
+    impl_deserialize_int!(deserialize_u64 = visit_u64(deserialize_u64, U64));
+    impl_deserialize_int!(deserialize_i16 = visit_i16(deserialize_i16, I16));
+    impl_deserialize_int!(deserialize_i32 = visit_i32(deserialize_i32, I32));
+    impl_deserialize_int!(deserialize_i64 = visit_i64(deserialize_i64, I64));
 
-    serde_if_integer128! {
-        impl_deserialize_int!(deserialize_u128 = visit_u128(deserialize_u128));
-        impl_deserialize_int!(deserialize_i128 = visit_i128(deserialize_i128));
-    }
+    impl_deserialize_int!(deserialize_u128 = visit_u128(deserialize_u128, U128));
+    impl_deserialize_int!(deserialize_i128 = visit_i128(deserialize_i128, I128));
 
     fn deserialize_f32<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let mut buffer = [0u8; 4];
-        self.reader
-            .fill(&mut buffer)
-            .map_err(DeserializeError::Read)?;
-        let float =
-            <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::read_f32(&buffer);
-
-        visitor.visit_f32(float)
+        self.expect_type_tag(TypeTag::F32)?;
+        visitor.visit_f32(O::Float::deserialize_f32(self)?)
     }
 
     fn deserialize_f64<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let mut buffer = [0u8; 8];
-        self.reader
-            .fill(&mut buffer)
-            .map_err(DeserializeError::Read)?;
-        let float =
-            <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::read_f64(&buffer);
-
-        visitor.visit_f64(float)
+        self.expect_type_tag(TypeTag::F64)?;
+        visitor.visit_f64(O::Float::deserialize_f64(self)?)
     }
 
     fn deserialize_char<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Char)?;
+        if O::Chars::U32 {
+            let code = O::IntEncoding::deserialize_u32(self)?;
+            let res = char::from_u32(code).ok_or(DeserializeError::InvalidCharEncoding)?;
+            return visitor.visit_char(res);
+        }
         let mut buf = [0u8; 4];
 
         // Look at the first byte to see how many bytes must be read
+        self.read_bytes(1)?;
         self.reader
             .fill(&mut buf[..1])
             .map_err(DeserializeError::Read)?;
@@ -300,6 +1706,7 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
             return Err(DeserializeError::InvalidCharEncoding);
         }
 
+        self.read_bytes(width as u64 - 1)?;
         self.reader
             .fill(&mut buf[1..width])
             .map_err(DeserializeError::Read)?;
@@ -311,8 +1718,10 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         visitor.visit_char(res)
     }
 
-    fn deserialize_str<V: Visitor<'a>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-        let length = O::IntEncoding::deserialize_len(&mut self)?; // .map_err(DeserializeError::Read)?;
+    fn deserialize_str<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Str)?;
+        let length = self.deserialize_element_len()?;
+        self.read_bytes(length as u64)?;
         self.reader
             .forward_str(length, visitor)
             .map_err(DeserializeError::Read)
@@ -324,14 +1733,13 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
     }
 
     #[cfg(feature = "alloc")]
-    fn deserialize_string<V: Visitor<'a>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-        use alloc::string::String;
-        use alloc::vec;
-
-        let length = O::IntEncoding::deserialize_len(&mut self)?; // .map_err(DeserializeError::Read)?;
-        let mut buffer = vec![0; length];
-        self.reader
-            .fill(&mut buffer)
+    fn deserialize_string<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Str)?;
+        let length = self.deserialize_element_len()?;
+        self.read_bytes(length as u64)?;
+        let buffer = self
+            .reader
+            .read_owned(length)
             .map_err(DeserializeError::Read)?;
 
         visitor.visit_string(
@@ -340,8 +1748,10 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         )
     }
 
-    fn deserialize_bytes<V: Visitor<'a>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-        let length = O::IntEncoding::deserialize_len(&mut self)?; // .map_err(DeserializeError::Read)?;
+    fn deserialize_bytes<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Bytes)?;
+        let length = self.deserialize_element_len()?;
+        self.read_bytes(length as u64)?;
         self.reader
             .forward_bytes(length, visitor)
             .map_err(DeserializeError::Read)
@@ -353,30 +1763,51 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
     }
 
     #[cfg(feature = "alloc")]
-    fn deserialize_byte_buf<V: Visitor<'a>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-        use alloc::vec;
-
-        let length = O::IntEncoding::deserialize_len(&mut self)?; // .map_err(DeserializeError::Read)?;
-        let mut buffer = vec![0; length];
-        self.reader
-            .fill(&mut buffer)
+    fn deserialize_byte_buf<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Bytes)?;
+        let length = self.deserialize_element_len()?;
+        self.read_bytes(length as u64)?;
+        let buffer = self
+            .reader
+            .read_owned(length)
             .map_err(DeserializeError::Read)?;
 
         visitor.visit_byte_buf(buffer)
     }
 
     fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        let val: u8 = serde::de::Deserialize::deserialize(&mut *self)?;
+        if O::TypeTags::TAGGED {
+            return match self.deserialize_type_tag()? {
+                TypeTag::None => visitor.visit_none(),
+                TypeTag::Some => self.nested(|de| visitor.visit_some(de)),
+                tag => Err(DeserializeError::UnexpectedTypeTag(tag as u8)),
+            };
+        }
+        if O::Presence::NICHE {
+            return match self.reader.peek(1).map(|bytes| bytes[0]) {
+                Some(0) => {
+                    self.deserialize_byte()?;
+                    visitor.visit_none()
+                }
+                Some(_) => self.nested(|de| visitor.visit_some(de)),
+                None if self.reader.remaining_len() == Some(0) => {
+                    Err(DeserializeError::UnexpectedEof { needed: 1 })
+                }
+                None => Err(DeserializeError::Unsupported("peek")),
+            };
+        }
+        let val = self.deserialize_byte()?;
         if val == 0 {
             visitor.visit_none()
         } else if val == 1 {
-            visitor.visit_some(self)
+            self.nested(|de| visitor.visit_some(de))
         } else {
             Err(DeserializeError::InvalidOptionValue(val))
         }
     }
 
     fn deserialize_unit<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Unit)?;
         visitor.visit_unit()
     }
 
@@ -385,7 +1816,7 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         _name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        visitor.visit_unit()
+        self.deserialize_unit(visitor)
     }
 
     fn deserialize_newtype_struct<V: Visitor<'a>>(
@@ -396,9 +1827,10 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         visitor.visit_newtype_struct(self)
     }
 
-    fn deserialize_seq<V: Visitor<'a>>(mut self, visitor: V) -> Result<V::Value, Self::Error> {
-        let len = O::IntEncoding::deserialize_len(&mut self)?; // .map_err(DeserializeError::Read)?;
-        self.deserialize_tuple(len, visitor)
+    fn deserialize_seq<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.expect_type_tag(TypeTag::Seq)?;
+        let len = self.deserialize_element_len()?;
+        self.nested(|de| de.visit_fields(len, None, visitor))
     }
 
     fn deserialize_tuple<V: Visitor<'a>>(
@@ -406,92 +1838,48 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         len: usize,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        struct Access<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
-            deserializer: &'b mut Deserializer<'a, R, O>,
-            len: usize,
-        }
-
-        impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::de::SeqAccess<'a> for Access<'a, 'b, R, O> {
-            type Error = DeserializeError<'a, R>;
-
-            fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
-            where
-                T: serde::de::DeserializeSeed<'a>,
-            {
-                if self.len > 0 {
-                    self.len -= 1;
-                    let value =
-                        serde::de::DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
-                    Ok(Some(value))
-                } else {
-                    Ok(None)
-                }
-            }
-
-            fn size_hint(&self) -> Option<usize> {
-                Some(self.len)
-            }
-        }
-
-        let access: Access<'a, 'b, R, O> = Access {
-            deserializer: self,
-            len,
-        };
-
-        visitor.visit_seq(access)
+        let len = self.deserialize_tuple_len(len)?;
+        self.visit_fields(len, None, visitor)
     }
 
     fn deserialize_tuple_struct<V: Visitor<'a>>(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        self.deserialize_tuple(len, visitor)
-    }
-
-    fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
-        struct Access<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
-            deserializer: &'b mut Deserializer<'a, R, O>,
-            len: usize,
+        // With type tags, the bytes are written as tagged `u8` values
+        if name == crate::byte_array::BYTE_ARRAY_NAME && !O::TypeTags::TAGGED {
+            self.read_bytes(len as u64)?;
+            return self
+                .reader
+                .forward_bytes(len, visitor)
+                .map_err(DeserializeError::Read);
         }
-
-        impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::de::MapAccess<'a> for Access<'a, 'b, R, O> {
-            type Error = DeserializeError<'a, R>;
-
-            fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
-            where
-                K: serde::de::DeserializeSeed<'a>,
-            {
-                if self.len > 0 {
-                    self.len -= 1;
-                    let key =
-                        serde::de::DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
-                    Ok(Some(key))
-                } else {
-                    Ok(None)
-                }
-            }
-
-            fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
-            where
-                V: serde::de::DeserializeSeed<'a>,
-            {
-                let value = serde::de::DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
-                Ok(value)
-            }
-
-            fn size_hint(&self) -> Option<usize> {
-                Some(self.len)
+        if let Some(kind) = PrimitiveKind::from_name(name) {
+            if !kind.is_bulk::<O>() {
+                return Err(DeserializeError::Unsupported(
+                    "PrimitiveSlice with a variable size encoding",
+                ));
             }
+            let len = self.deserialize_element_len()?;
+            // A saturated length is larger than any input, so the reader fails with EOF
+            let byte_len = len.saturating_mul(kind.width());
+            self.read_bytes(byte_len as u64)?;
+            let visitor = PrimitiveBytesVisitor {
+                visitor,
+                big_endian: is_big_endian::<O>(),
+            };
+            return self
+                .reader
+                .forward_bytes(byte_len, visitor)
+                .map_err(DeserializeError::Read);
         }
+        self.deserialize_tuple(len, visitor)
+    }
 
-        let len = serde::Deserialize::deserialize(&mut *self)?;
-
-        visitor.visit_map(Access {
-            deserializer: self,
-            len,
-        })
+    fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_map_within(usize::MAX, visitor)
     }
 
     /// Hint that the `Deserialize` type is expecting a struct with a particular
@@ -502,7 +1890,17 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        self.deserialize_tuple(fields.len(), visitor)
+        if O::TypeTags::TAGGED || !O::Presence::PRESENCE_BITMAP {
+            if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+                return self.visit_counted_fields(false, visitor);
+            }
+            return self.deserialize_tuple(fields.len(), visitor);
+        }
+        if O::Structs::COUNTED {
+            return self.visit_counted_fields(true, visitor);
+        }
+        let bitmap = self.deserialize_bitmap(fields.len())?;
+        self.visit_fields(fields.len(), Some(bitmap), visitor)
     }
 
     /// Hint that the `Deserialize` type is expecting an enum value with a
@@ -510,46 +1908,33 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
     fn deserialize_enum<V: Visitor<'a>>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value, Self::Error> {
-        impl<'de, 'a, R: 'a, O> serde::de::EnumAccess<'de> for &'a mut Deserializer<'de, R, O>
-        where
-            R: CoreRead<'de>,
-            O: Options,
-        {
-            type Error = DeserializeError<'de, R>;
-            type Variant = Self;
-
-            fn variant_seed<V>(
-                self,
-                seed: V,
-            ) -> Result<(V::Value, Self::Variant), DeserializeError<'de, R>>
-            where
-                V: serde::de::DeserializeSeed<'de>,
-            {
-                let idx: u32 = O::IntEncoding::deserialize_u32(self)?;
-                let val: Result<_, DeserializeError<'de, R>> =
-                    seed.deserialize(idx.into_deserializer());
-                Ok((val?, self))
-            }
-        }
-
-        visitor.visit_enum(self)
+        self.expect_type_tag(TypeTag::Enum)?;
+        self.nested(|deserializer| {
+            visitor.visit_enum(Enum {
+                deserializer,
+                variants,
+            })
+        })
     }
 
     /// Hint that the `Deserialize` type is expecting the name of a struct
     /// field or the discriminant of an enum variant.
     fn deserialize_identifier<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
-        panic!("Deserialize_identifier not supported")
+        Err(DeserializeError::Unsupported("deserialize_identifier"))
     }
 
     /// Hint that the `Deserialize` type needs to deserialize a value whose type
     /// doesn't matter because it is ignored.
     ///
     /// Deserializers for non-self-describing formats may not support this mode.
-    fn deserialize_ignored_any<V: Visitor<'a>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
-        panic!("Deserialize_ignored_any not supported")
+    fn deserialize_ignored_any<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if !O::TypeTags::TAGGED {
+            return Err(DeserializeError::Unsupported("deserialize_ignored_any"));
+        }
+        self.deserialize_any(visitor)
     }
 
     fn is_human_readable(&self) -> bool {
@@ -557,7 +1942,73 @@ impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
     }
 }
 
-impl<'de, 'a, R, O> serde::de::VariantAccess<'de> for &'a mut Deserializer<'de, R, O>
+/// The variant of an enum, together with the names of the variants of the enum, so an unknown
+/// variant index can be replaced with the [UnknownVariants] fallback.
+struct Enum<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
+    deserializer: &'b mut Deserializer<'a, R, O>,
+    variants: &'static [&'static str],
+}
+
+impl<'de, 'a, R: 'a, O> serde::de::EnumAccess<'de> for Enum<'de, 'a, R, O>
+where
+    R: CoreRead<'de>,
+    O: Options,
+{
+    type Error = DeserializeError<'de, R>;
+    type Variant = Variant<'de, 'a, R, O>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), DeserializeError<'de, R>>
+    where
+        V: serde::de::DeserializeSeed<'de>,
+    {
+        let mut idx: u32 = O::Discriminant::deserialize_discriminant(&mut *self.deserializer)?;
+        let mut skipped = false;
+        if idx as usize >= self.variants.len() {
+            let fallback = self.deserializer.options.unknown_variants().fallback();
+            if let Some(position) =
+                fallback.and_then(|name| self.variants.iter().position(|v| *v == name))
+            {
+                if self.deserializer.skip_variant()? {
+                    idx = position as u32;
+                    skipped = true;
+                }
+            }
+        }
+        let val: Result<_, DeserializeError<'de, R>> = seed.deserialize(idx.into_deserializer());
+        Ok((
+            val?,
+            Variant {
+                deserializer: self.deserializer,
+                skipped,
+            },
+        ))
+    }
+}
+
+/// The content of an enum variant. If the variant index was unknown, the content was already
+/// skipped, and only the fallback unit variant can be read.
+struct Variant<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
+    deserializer: &'b mut Deserializer<'a, R, O>,
+    skipped: bool,
+}
+
+impl<'de, 'a, R: 'a, O> Variant<'de, 'a, R, O>
+where
+    R: CoreRead<'de>,
+    O: Options,
+{
+    /// Returns the deserializer, or an error if the content of the variant was skipped.
+    fn content(self) -> Result<&'a mut Deserializer<'de, R, O>, DeserializeError<'de, R>> {
+        if self.skipped {
+            return Err(DeserializeError::Unsupported(
+                "A fallback variant with content",
+            ));
+        }
+        Ok(self.deserializer)
+    }
+}
+
+impl<'de, 'a, R: 'a, O> serde::de::VariantAccess<'de> for Variant<'de, 'a, R, O>
 where
     R: CoreRead<'de>,
     O: Options,
@@ -565,21 +2016,32 @@ where
     type Error = DeserializeError<'de, R>;
 
     fn unit_variant(self) -> Result<(), DeserializeError<'de, R>> {
-        Ok(())
+        if self.skipped {
+            return Ok(());
+        }
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            return self.deserializer.counted(|_, _| Ok(()));
+        }
+        self.deserializer.expect_type_tag(TypeTag::Unit)
     }
 
     fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, DeserializeError<'de, R>>
     where
         T: serde::de::DeserializeSeed<'de>,
     {
-        serde::de::DeserializeSeed::deserialize(seed, self)
+        self.content()?
+            .newtype_variant_content(|deserializer| seed.deserialize(deserializer))
     }
 
     fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, DeserializeError<'de, R>>
     where
         V: serde::de::Visitor<'de>,
     {
-        serde::de::Deserializer::deserialize_tuple(self, len, visitor)
+        let deserializer = self.content()?;
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            return deserializer.visit_counted_fields(false, visitor);
+        }
+        serde::de::Deserializer::deserialize_tuple(deserializer, len, visitor)
     }
 
     fn struct_variant<V>(
@@ -590,7 +2052,83 @@ where
     where
         V: serde::de::Visitor<'de>,
     {
-        serde::de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        let deserializer = self.content()?;
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            return deserializer.visit_counted_fields(false, visitor);
+        }
+        serde::de::Deserializer::deserialize_tuple(deserializer, fields.len(), visitor)
+    }
+}
+
+/// A struct field that is covered by a presence bitmap. An `Option` is read without a tag byte,
+/// everything else is read by the inner deserializer.
+struct PresenceField<'a, 'b, R: CoreRead<'a> + 'a, O: Options> {
+    deserializer: &'b mut Deserializer<'a, R, O>,
+    present: bool,
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'a>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                self.deserializer.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'a, 'b, R: CoreRead<'a> + 'a, O: Options> serde::Deserializer<'a>
+    for PresenceField<'a, 'b, R, O>
+{
+    type Error = DeserializeError<'a, R>;
+
+    fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.present {
+            self.deserializer.nested(|de| visitor.visit_some(de))
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    forward_deserialize! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
     }
 }
 
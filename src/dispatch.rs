@@ -0,0 +1,170 @@
+//! Route messages to a handler based on a leading tag.
+//!
+//! A [Dispatcher] reads the tag in front of a message, and passes the rest of the message to the
+//! handler that was registered for that tag. Every handler is a plain function pointer that only
+//! deserializes its own message type, so the code for each message type is generated once instead
+//! of inside one large match in every receive loop.
+//!
+//! The tag is encoded like an enum discriminant, with the configured
+//! [discriminant encoding](crate::config::Options::with_u8_discriminants). A message that is
+//! written as a variant of a newtype enum on the other side, or with [serialize_tagged], can be
+//! dispatched directly.
+//!
+//! ```
+//! # use bincode_core::dispatch::{serialize_tagged, Dispatcher};
+//! # use bincode_core::{BufferWriter, DefaultOptions};
+//! # use serde::Deserialize;
+//! #[derive(Default)]
+//! struct Board {
+//!     leds: u8,
+//!     echoed: usize,
+//! }
+//!
+//! let mut dispatcher = Dispatcher::<_, _, Board, 4>::new();
+//! dispatcher
+//!     .register(0, |de, board| {
+//!         board.leds = u8::deserialize(de)?;
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! dispatcher
+//!     .register(1, |de, board| {
+//!         board.echoed += <&str>::deserialize(de)?.len();
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//!
+//! let mut buffer = [0u8; 16];
+//! let mut writer = BufferWriter::new(&mut buffer);
+//! serialize_tagged(1, "ping", &mut writer, DefaultOptions::new()).unwrap();
+//! let len = writer.written_len();
+//!
+//! let mut board = Board::default();
+//! let tag = dispatcher
+//!     .dispatch(&buffer[..len], DefaultOptions::new(), &mut board)
+//!     .unwrap();
+//! assert_eq!(tag, 1);
+//! assert_eq!(board.echoed, 4);
+//! ```
+
+use crate::config::{Options, TrailingBytes};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{serialize, SerializeError};
+use crate::traits::{CoreRead, CoreWrite};
+use serde::{Serialize, Serializer};
+
+/// A function that deserializes and handles one type of message. `C` is a context that is passed
+/// through from [Dispatcher::dispatch], e.g. the peripherals that the message acts on.
+pub type Handler<'a, R, O, C> =
+    fn(&mut Deserializer<'a, R, O>, &mut C) -> Result<(), DeserializeError<'a, R>>;
+
+type Route<'a, R, O, C> = (u32, Handler<'a, R, O, C>);
+
+/// A table of up to `N` handlers, indexed by message tag.
+pub struct Dispatcher<'a, R: CoreRead<'a> + 'a, O: Options, C, const N: usize> {
+    routes: [Option<Route<'a, R, O, C>>; N],
+}
+
+/// The [Dispatcher] has no room for another handler.
+#[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RoutesFull;
+
+impl<'a, R: CoreRead<'a> + 'a, O: Options, C, const N: usize> Dispatcher<'a, R, O, C, N> {
+    /// Create a dispatcher without any handlers.
+    pub fn new() -> Self {
+        Dispatcher { routes: [None; N] }
+    }
+
+    /// Register `handler` for messages with the given `tag`. A handler that was registered for the
+    /// same tag before is replaced.
+    ///
+    /// If all `N` slots are taken by other tags, [RoutesFull] is returned.
+    pub fn register(&mut self, tag: u32, handler: Handler<'a, R, O, C>) -> Result<(), RoutesFull> {
+        let slot = match self.routes.iter().position(|route| match route {
+            Some((t, _)) => *t == tag,
+            None => false,
+        }) {
+            Some(index) => index,
+            None => self
+                .routes
+                .iter()
+                .position(Option::is_none)
+                .ok_or(RoutesFull)?,
+        };
+        self.routes[slot] = Some((tag, handler));
+        Ok(())
+    }
+
+    /// Read a single tagged message from `reader`, and pass it to the handler that is registered
+    /// for its tag. Returns the tag of the message.
+    ///
+    /// If no handler is registered for the tag, [DeserializeError::UnknownTag] is returned before
+    /// the rest of the message is read. The configured trailing bytes behavior is checked after
+    /// the handler returns.
+    pub fn dispatch(
+        &self,
+        reader: R,
+        options: O,
+        context: &mut C,
+    ) -> Result<u32, DeserializeError<'a, R>> {
+        let mut deserializer = Deserializer::new(reader, options);
+        let tag = self.dispatch_next(&mut deserializer, context)?;
+        O::Trailing::check_end(deserializer.reader())?;
+        Ok(tag)
+    }
+
+    /// Read the next tagged message from `deserializer`, and pass it to the handler that is
+    /// registered for its tag. Returns the tag of the message.
+    ///
+    /// This can be used to handle a stream of messages from a single reader. With
+    /// [CountedStructs](crate::config::CountedStructs), the bytes of the message that the handler
+    /// does not read are skipped.
+    pub fn dispatch_next(
+        &self,
+        deserializer: &mut Deserializer<'a, R, O>,
+        context: &mut C,
+    ) -> Result<u32, DeserializeError<'a, R>> {
+        let tag = deserializer.deserialize_variant_index()?;
+        let handler = self
+            .routes
+            .iter()
+            .flatten()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, handler)| *handler)
+            .ok_or(DeserializeError::UnknownTag(tag))?;
+        deserializer.newtype_variant_content(|deserializer| handler(deserializer, context))?;
+        Ok(tag)
+    }
+}
+
+impl<'a, R: CoreRead<'a> + 'a, O: Options, C, const N: usize> Default
+    for Dispatcher<'a, R, O, C, N>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Serialize `value` into `writer`, prefixed with `tag`, so it can be read with a [Dispatcher].
+///
+/// This writes the same bytes as a newtype variant with index `tag` of an enum.
+pub fn serialize_tagged<T: Serialize + ?Sized, W: CoreWrite, O: Options>(
+    tag: u32,
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    serialize(&Tagged { tag, value }, writer, options)
+}
+
+struct Tagged<'v, T: ?Sized> {
+    tag: u32,
+    value: &'v T,
+}
+
+impl<T: Serialize + ?Sized> Serialize for Tagged<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_newtype_variant("", self.tag, "", self.value)
+    }
+}
@@ -0,0 +1,371 @@
+//! The wire format of this crate, as golden bytes for a set of values.
+//!
+//! [CORPUS] holds the encoding of every primitive and compound shape (including the zero-length
+//! and empty ones) under each [Config]. The bytes are checked by the tests of this crate, so any
+//! change to the wire format shows up as a failing test before it is released.
+//!
+//! The corpus is public so the format can be checked on the target itself, e.g. in a
+//! hardware-in-the-loop test. [verify] serializes and deserializes every value with the options
+//! of a [Config], without allocating:
+//!
+//! ```
+//! use bincode_core::encoding_spec::{verify, Config};
+//!
+//! for config in Config::ALL {
+//!     verify(config).unwrap();
+//! }
+//! ```
+//!
+//! The bytes can also be used to check another implementation of the format, e.g. the peer on the
+//! other side of a link:
+//!
+//! ```
+//! use bincode_core::encoding_spec::{Config, CORPUS};
+//!
+//! let golden = CORPUS.iter().find(|golden| golden.name == "u16_251").unwrap();
+//! assert_eq!(golden.value, "251");
+//! assert_eq!(golden.bytes(Config::Varint), &[251, 251, 0]);
+//! assert_eq!(golden.bytes(Config::FixintBigEndian), &[0, 251]);
+//! ```
+
+use crate::config::Options;
+use crate::{deserialize, serialize, BufferWriter};
+use core::marker::PhantomData;
+use core::ops::Bound;
+use core::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// A configuration that the corpus has bytes for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Config {
+    /// [DefaultOptions](crate::DefaultOptions): varint encoding and little endian.
+    Varint,
+    /// [DefaultOptions](crate::DefaultOptions) with
+    /// [with_fixint_encoding](Options::with_fixint_encoding).
+    Fixint,
+    /// [DefaultOptions](crate::DefaultOptions) with [with_big_endian](Options::with_big_endian).
+    VarintBigEndian,
+    /// [DefaultOptions](crate::DefaultOptions) with
+    /// [with_fixint_encoding](Options::with_fixint_encoding) and
+    /// [with_big_endian](Options::with_big_endian).
+    FixintBigEndian,
+}
+
+impl Config {
+    /// Every configuration in the corpus.
+    pub const ALL: [Config; 4] = [
+        Config::Varint,
+        Config::Fixint,
+        Config::VarintBigEndian,
+        Config::FixintBigEndian,
+    ];
+}
+
+/// The encoding of a single value.
+#[derive(Clone, Copy, Debug)]
+pub struct Golden {
+    /// The name of the value, e.g. `u16_251`.
+    pub name: &'static str,
+    /// The Rust type of the value, e.g. `u16`.
+    pub ty: &'static str,
+    /// The value as a Rust expression, e.g. `251`.
+    pub value: &'static str,
+    /// The bytes with [Config::Varint].
+    pub varint: &'static [u8],
+    /// The bytes with [Config::Fixint].
+    pub fixint: &'static [u8],
+    /// The bytes with [Config::VarintBigEndian].
+    pub varint_be: &'static [u8],
+    /// The bytes with [Config::FixintBigEndian].
+    pub fixint_be: &'static [u8],
+}
+
+impl Golden {
+    /// The bytes of the value with `config`.
+    pub fn bytes(&self, config: Config) -> &'static [u8] {
+        match config {
+            Config::Varint => self.varint,
+            Config::Fixint => self.fixint,
+            Config::VarintBigEndian => self.varint_be,
+            Config::FixintBigEndian => self.fixint_be,
+        }
+    }
+}
+
+/// A value of the corpus that does not match its golden bytes. See [verify].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mismatch {
+    /// Serializing the value failed, or did not produce the golden bytes.
+    Serialize {
+        /// The [name](Golden::name) of the value
+        name: &'static str,
+        /// The configuration that was used
+        config: Config,
+    },
+    /// Deserializing the golden bytes failed, or did not produce the value.
+    Deserialize {
+        /// The [name](Golden::name) of the value
+        name: &'static str,
+        /// The configuration that was used
+        config: Config,
+    },
+}
+
+/// Evaluates `$body` with `$options` set to the options of a [Config].
+macro_rules! with_config {
+    ($config:expr, |$options:ident| $body:expr) => {{
+        let options = $crate::DefaultOptions::new();
+        match $config {
+            Config::Varint => {
+                let $options = options;
+                $body
+            }
+            Config::Fixint => {
+                let $options = options.with_fixint_encoding();
+                $body
+            }
+            Config::VarintBigEndian => {
+                let $options = options.with_big_endian();
+                $body
+            }
+            Config::FixintBigEndian => {
+                let $options = options.with_fixint_encoding().with_big_endian();
+                $body
+            }
+        }
+    }};
+}
+#[cfg(feature = "arbitrary")]
+pub(crate) use with_config;
+
+/// The largest encoding in the corpus.
+const MAX_LEN: usize = 32;
+
+macro_rules! corpus {
+    ($($name:ident: $ty:ty = $value:expr,
+        varint: $varint:expr,
+        fixint: $fixint:expr,
+        varint_be: $varint_be:expr,
+        fixint_be: $fixint_be:expr;)*) => {
+        /// The golden bytes of every value, in the order they are checked by [verify].
+        pub const CORPUS: &[Golden] = &[$(
+            Golden {
+                name: stringify!($name),
+                ty: stringify!($ty),
+                value: stringify!($value),
+                varint: &$varint,
+                fixint: &$fixint,
+                varint_be: &$varint_be,
+                fixint_be: &$fixint_be,
+            },
+        )*];
+
+        fn verify_with<O: Options + Copy>(config: Config, options: O) -> Result<(), Mismatch> {
+            let mut golden = CORPUS.iter();
+            $(
+                let bytes = golden.next().map_or(&[][..], |golden| golden.bytes(config));
+                let value: $ty = $value;
+                check(stringify!($name), config, &value, bytes, options)?;
+            )*
+            Ok(())
+        }
+    };
+}
+
+corpus! {
+    unit: () = (),
+        varint: [],
+        fixint: [],
+        varint_be: [],
+        fixint_be: [];
+    unit_struct: PhantomData<u8> = PhantomData,
+        varint: [],
+        fixint: [],
+        varint_be: [],
+        fixint_be: [];
+    bool_false: bool = false,
+        varint: [0],
+        fixint: [0],
+        varint_be: [0],
+        fixint_be: [0];
+    bool_true: bool = true,
+        varint: [1],
+        fixint: [1],
+        varint_be: [1],
+        fixint_be: [1];
+    u8_max: u8 = u8::MAX,
+        varint: [255],
+        fixint: [255],
+        varint_be: [255],
+        fixint_be: [255];
+    u16_250: u16 = 250,
+        varint: [250],
+        fixint: [250, 0],
+        varint_be: [250],
+        fixint_be: [0, 250];
+    u16_251: u16 = 251,
+        varint: [251, 251, 0],
+        fixint: [251, 0],
+        varint_be: [251, 0, 251],
+        fixint_be: [0, 251];
+    u16_max: u16 = u16::MAX,
+        varint: [251, 255, 255],
+        fixint: [255, 255],
+        varint_be: [251, 255, 255],
+        fixint_be: [255, 255];
+    u32_65536: u32 = 65536,
+        varint: [252, 0, 0, 1, 0],
+        fixint: [0, 0, 1, 0],
+        varint_be: [252, 0, 1, 0, 0],
+        fixint_be: [0, 1, 0, 0];
+    u64_max: u64 = u64::MAX,
+        varint: [253, 255, 255, 255, 255, 255, 255, 255, 255],
+        fixint: [255, 255, 255, 255, 255, 255, 255, 255],
+        varint_be: [253, 255, 255, 255, 255, 255, 255, 255, 255],
+        fixint_be: [255, 255, 255, 255, 255, 255, 255, 255];
+    u128_large: u128 = 1 << 64,
+        varint: [254, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+        fixint: [0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0],
+        varint_be: [254, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0];
+    i8_min: i8 = i8::MIN,
+        varint: [128],
+        fixint: [128],
+        varint_be: [128],
+        fixint_be: [128];
+    i16_negative: i16 = -300,
+        varint: [251, 87, 2],
+        fixint: [212, 254],
+        varint_be: [251, 2, 87],
+        fixint_be: [254, 212];
+    i32_minus_one: i32 = -1,
+        varint: [1],
+        fixint: [255, 255, 255, 255],
+        varint_be: [1],
+        fixint_be: [255, 255, 255, 255];
+    i64_min: i64 = i64::MIN,
+        varint: [253, 255, 255, 255, 255, 255, 255, 255, 255],
+        fixint: [0, 0, 0, 0, 0, 0, 0, 128],
+        varint_be: [253, 255, 255, 255, 255, 255, 255, 255, 255],
+        fixint_be: [128, 0, 0, 0, 0, 0, 0, 0];
+    i128_minus_one: i128 = -1,
+        varint: [1],
+        fixint: [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255],
+        varint_be: [1],
+        fixint_be: [255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255];
+    f32_value: f32 = 1.5,
+        varint: [0, 0, 192, 63],
+        fixint: [0, 0, 192, 63],
+        varint_be: [63, 192, 0, 0],
+        fixint_be: [63, 192, 0, 0];
+    f64_value: f64 = -0.25,
+        varint: [0, 0, 0, 0, 0, 0, 208, 191],
+        fixint: [0, 0, 0, 0, 0, 0, 208, 191],
+        varint_be: [191, 208, 0, 0, 0, 0, 0, 0],
+        fixint_be: [191, 208, 0, 0, 0, 0, 0, 0];
+    char_ascii: char = 'a',
+        varint: [97],
+        fixint: [97],
+        varint_be: [97],
+        fixint_be: [97];
+    char_multibyte: char = 'é',
+        varint: [195, 169],
+        fixint: [195, 169],
+        varint_be: [195, 169],
+        fixint_be: [195, 169];
+    str_empty: &str = "",
+        varint: [0],
+        fixint: [0, 0, 0, 0, 0, 0, 0, 0],
+        varint_be: [0],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 0];
+    str_value: &str = "abc",
+        varint: [3, 97, 98, 99],
+        fixint: [3, 0, 0, 0, 0, 0, 0, 0, 97, 98, 99],
+        varint_be: [3, 97, 98, 99],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 3, 97, 98, 99];
+    bytes_empty: &[u8] = &[],
+        varint: [0],
+        fixint: [0, 0, 0, 0, 0, 0, 0, 0],
+        varint_be: [0],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 0];
+    bytes_value: &[u8] = &[1, 2, 3],
+        varint: [3, 1, 2, 3],
+        fixint: [3, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3],
+        varint_be: [3, 1, 2, 3],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 3, 1, 2, 3];
+    option_none: Option<u8> = None,
+        varint: [0],
+        fixint: [0],
+        varint_be: [0],
+        fixint_be: [0];
+    option_some: Option<u16> = Some(300),
+        varint: [1, 251, 44, 1],
+        fixint: [1, 44, 1],
+        varint_be: [1, 251, 1, 44],
+        fixint_be: [1, 1, 44];
+    tuple: (u8, u16, bool) = (1, 300, true),
+        varint: [1, 251, 44, 1, 1],
+        fixint: [1, 44, 1, 1],
+        varint_be: [1, 251, 1, 44, 1],
+        fixint_be: [1, 1, 44, 1];
+    array: [u16; 3] = [1, 300, u16::MAX],
+        varint: [1, 251, 44, 1, 251, 255, 255],
+        fixint: [1, 0, 44, 1, 255, 255],
+        varint_be: [1, 251, 1, 44, 251, 255, 255],
+        fixint_be: [0, 1, 1, 44, 255, 255];
+    array_empty: [u8; 0] = [],
+        varint: [],
+        fixint: [],
+        varint_be: [],
+        fixint_be: [];
+    struct_duration: Duration = Duration::new(1, 500),
+        varint: [1, 251, 244, 1],
+        fixint: [1, 0, 0, 0, 0, 0, 0, 0, 244, 1, 0, 0],
+        varint_be: [1, 251, 1, 244],
+        fixint_be: [0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 1, 244];
+    unit_variant: Bound<u8> = Bound::Unbounded,
+        varint: [0],
+        fixint: [0, 0, 0, 0],
+        varint_be: [0],
+        fixint_be: [0, 0, 0, 0];
+    newtype_variant: Result<u8, u16> = Err(300),
+        varint: [1, 251, 44, 1],
+        fixint: [1, 0, 0, 0, 44, 1],
+        varint_be: [1, 251, 1, 44],
+        fixint_be: [0, 0, 0, 1, 1, 44];
+    nested: Option<(&str, Option<u8>)> = Some(("hi", None)),
+        varint: [1, 2, 104, 105, 0],
+        fixint: [1, 2, 0, 0, 0, 0, 0, 0, 0, 104, 105, 0],
+        varint_be: [1, 2, 104, 105, 0],
+        fixint_be: [1, 0, 0, 0, 0, 0, 0, 0, 2, 104, 105, 0];
+}
+
+/// Serializes and deserializes every value of the corpus with the options of `config`, and
+/// returns the first value that does not match its golden bytes.
+pub fn verify(config: Config) -> Result<(), Mismatch> {
+    with_config!(config, |options| verify_with(config, options))
+}
+
+fn check<T, O>(
+    name: &'static str,
+    config: Config,
+    value: &T,
+    bytes: &'static [u8],
+    options: O,
+) -> Result<(), Mismatch>
+where
+    T: Serialize + Deserialize<'static> + PartialEq,
+    O: Options + Copy,
+{
+    let mut buffer = [0u8; MAX_LEN];
+    let mut writer = BufferWriter::new(&mut buffer);
+    if serialize(value, &mut writer, options).is_err() || writer.written_buffer() != bytes {
+        return Err(Mismatch::Serialize { name, config });
+    }
+    match deserialize::<T, _, _>(bytes, options) {
+        Ok(decoded) if decoded == *value => Ok(()),
+        _ => Err(Mismatch::Deserialize { name, config }),
+    }
+}
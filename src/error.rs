@@ -0,0 +1,164 @@
+use crate::deserialize::DeserializeError;
+use crate::error_context::ContextError;
+use crate::serialize::SerializeError;
+use crate::traits::CoreRead;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// The category of a [DeserializeError], [SerializeError] or [Error].
+///
+/// The categories are stable, so application code can e.g. retry on [UnexpectedEof] and drop the
+/// message on [InvalidData], without matching every variant of the errors. New errors are added
+/// to one of the existing categories when possible.
+///
+/// [UnexpectedEof]: ErrorKind::UnexpectedEof
+/// [InvalidData]: ErrorKind::InvalidData
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The reader returned an error.
+    Read,
+
+    /// The writer returned an error.
+    Write,
+
+    /// The input ended in the middle of a value. The value can still be read when more bytes
+    /// arrive, see [bytes_needed](DeserializeError::bytes_needed).
+    UnexpectedEof,
+
+    /// The input is not a valid encoding of the type, e.g. because it is corrupt, or because it
+    /// was written with a different configuration.
+    InvalidData,
+
+    /// The input is valid, but for a different version, schema or key, e.g. a
+    /// [VersionMismatch](DeserializeError::VersionMismatch) or a
+    /// [MacMismatch](DeserializeError::MacMismatch).
+    Mismatch,
+
+    /// A configured limit, or the capacity of a buffer or map, is too small for the value.
+    LimitExceeded,
+
+    /// The value cannot be serialized with the configuration, e.g. an enum variant index that
+    /// does not fit in the discriminant encoding.
+    InvalidValue,
+
+    /// The `Serialize` or `Deserialize` implementation needs something that is not supported with
+    /// the configuration or reader.
+    Unsupported,
+
+    /// The `Serialize` or `Deserialize` implementation returned its own error.
+    Custom,
+}
+
+/// An error of deserializing or serializing, that is only generic over the errors of the reader
+/// and the writer.
+///
+/// Application code that both reads and writes messages can return this error, and convert the
+/// [DeserializeError], [ContextError] and [SerializeError] of the different calls with `?`. Apart
+/// from the read and write errors, only the [kind](Error::kind) of the error is kept.
+///
+/// ```
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+/// use bincode_core::{BufferWriterError, Error, ErrorKind, SliceReadError};
+///
+/// /// Reads a `u32` from `request`, and writes its double to `response`.
+/// fn handle(
+///     request: &[u8],
+///     response: &mut [u8],
+/// ) -> Result<(), Error<SliceReadError, BufferWriterError>> {
+///     let value: u32 = deserialize(request, DefaultOptions::new())?;
+///     serialize(&(value * 2), BufferWriter::new(response), DefaultOptions::new())?;
+///     Ok(())
+/// }
+///
+/// let mut response = [0u8; 4];
+/// assert!(handle(&[21], &mut response).is_ok());
+/// assert_eq!(handle(&[252, 1], &mut response).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+/// assert_eq!(handle(&[251, 0, 1], &mut [0u8; 1]).unwrap_err().kind(), ErrorKind::Write);
+/// ```
+pub enum Error<RE, WE> {
+    /// The reader returned an error.
+    Read(RE),
+
+    /// The writer returned an error.
+    Write(WE),
+
+    /// Deserializing failed for another reason.
+    Deserialize(ErrorKind),
+
+    /// Serializing failed for another reason.
+    Serialize(ErrorKind),
+}
+
+impl<RE, WE> Error<RE, WE> {
+    /// The category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::Read(_) => ErrorKind::Read,
+            Error::Write(_) => ErrorKind::Write,
+            Error::Deserialize(kind) | Error::Serialize(kind) => *kind,
+        }
+    }
+}
+
+impl<'a, R: CoreRead<'a>, WE> From<DeserializeError<'a, R>> for Error<R::Error, WE> {
+    fn from(error: DeserializeError<'a, R>) -> Self {
+        let kind = error.kind();
+        match error {
+            DeserializeError::Read(e) => Error::Read(e),
+            _ => Error::Deserialize(kind),
+        }
+    }
+}
+
+impl<'a, R: CoreRead<'a>, WE> From<ContextError<'a, R>> for Error<R::Error, WE> {
+    fn from(error: ContextError<'a, R>) -> Self {
+        error.error.into()
+    }
+}
+
+impl<RE, WE> From<SerializeError<WE>> for Error<RE, WE> {
+    fn from(error: SerializeError<WE>) -> Self {
+        let kind = error.kind();
+        match error {
+            SerializeError::Write(e) => Error::Write(e),
+            _ => Error::Serialize(kind),
+        }
+    }
+}
+
+impl<RE: core::fmt::Debug, WE: core::fmt::Debug> core::fmt::Debug for Error<RE, WE> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Error::Read(e) => write!(fmt, "Read error {:?}", e),
+            Error::Write(e) => write!(fmt, "Write error {:?}", e),
+            Error::Deserialize(kind) => write!(fmt, "Deserialize error {:?}", kind),
+            Error::Serialize(kind) => write!(fmt, "Serialize error {:?}", kind),
+        }
+    }
+}
+
+impl<RE: core::fmt::Debug, WE: core::fmt::Debug> core::fmt::Display for Error<RE, WE> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<RE: core::fmt::Debug, WE: core::fmt::Debug> StdError for Error<RE, WE> {}
+
+#[cfg(feature = "defmt")]
+impl<RE: defmt::Format, WE: defmt::Format> defmt::Format for Error<RE, WE> {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            Error::Read(e) => defmt::write!(fmt, "Read error {}", e),
+            Error::Write(e) => defmt::write!(fmt, "Write error {}", e),
+            Error::Deserialize(kind) => defmt::write!(fmt, "Deserialize error {}", kind),
+            Error::Serialize(kind) => defmt::write!(fmt, "Serialize error {}", kind),
+        }
+    }
+}
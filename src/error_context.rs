@@ -0,0 +1,139 @@
+use crate::deserialize::DeserializeError;
+use crate::traits::CoreRead;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// The maximum amount of nested field indices that a [FieldPath] stores.
+pub const MAX_FIELD_PATH_DEPTH: usize = 8;
+
+/// The position of a value inside nested structs, tuples, sequences and maps.
+///
+/// Every level is the index of the field, element or map entry that was being read. Only the
+/// outermost [MAX_FIELD_PATH_DEPTH] levels are stored; deeper levels are counted in
+/// [depth](FieldPath::depth) but not remembered.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct FieldPath {
+    indices: [u32; MAX_FIELD_PATH_DEPTH],
+    depth: usize,
+}
+
+impl FieldPath {
+    /// Create an empty path, pointing at the top level value.
+    pub const fn new() -> Self {
+        FieldPath {
+            indices: [0; MAX_FIELD_PATH_DEPTH],
+            depth: 0,
+        }
+    }
+
+    /// The amount of nested levels, including the levels that were not stored.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The stored field indices, from the outermost to the innermost level.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices[..self.depth.min(MAX_FIELD_PATH_DEPTH)]
+    }
+
+    /// Returns `true` if the path is deeper than [MAX_FIELD_PATH_DEPTH], and the innermost levels
+    /// are missing from [indices](FieldPath::indices).
+    pub fn is_truncated(&self) -> bool {
+        self.depth > MAX_FIELD_PATH_DEPTH
+    }
+
+    pub(crate) fn push(&mut self, index: u32) {
+        if let Some(slot) = self.indices.get_mut(self.depth) {
+            *slot = index;
+        }
+        self.depth += 1;
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.depth -= 1;
+    }
+}
+
+impl core::fmt::Debug for FieldPath {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{}", self)
+    }
+}
+
+impl core::fmt::Display for FieldPath {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        for (i, index) in self.indices().iter().enumerate() {
+            if i > 0 {
+                write!(fmt, ".")?;
+            }
+            write!(fmt, "{}", index)?;
+        }
+        if self.is_truncated() {
+            write!(fmt, "...")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for FieldPath {
+    fn format(&self, fmt: defmt::Formatter) {
+        for (i, index) in self.indices().iter().enumerate() {
+            if i > 0 {
+                defmt::write!(fmt, ".");
+            }
+            defmt::write!(fmt, "{}", index);
+        }
+        if self.is_truncated() {
+            defmt::write!(fmt, "...");
+        }
+    }
+}
+
+/// A [DeserializeError] together with the position in the input at which it occurred. This is
+/// returned by [deserialize_with_context](crate::deserialize_with_context).
+pub struct ContextError<'a, R: CoreRead<'a>> {
+    /// The error that occurred.
+    pub error: DeserializeError<'a, R>,
+
+    /// The byte offset from the start of the value at which the failing read started.
+    pub offset: u64,
+
+    /// The field that was being read when the error occurred.
+    pub path: FieldPath,
+}
+
+impl<'a, R: CoreRead<'a>> core::fmt::Debug for ContextError<'a, R> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?} at offset {}", self.error, self.offset)?;
+        if self.path.depth() > 0 {
+            write!(fmt, " while reading field {}", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, R: CoreRead<'a>> core::fmt::Display for ContextError<'a, R> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<'a, R: CoreRead<'a>> StdError for ContextError<'a, R> {}
+
+#[cfg(feature = "defmt")]
+impl<'a, R: CoreRead<'a>> defmt::Format for ContextError<'a, R>
+where
+    R::Error: defmt::Format,
+{
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "{} at offset {}", self.error, self.offset);
+        if self.path.depth() > 0 {
+            defmt::write!(fmt, " while reading field {}", self.path);
+        }
+    }
+}
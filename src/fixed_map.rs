@@ -0,0 +1,60 @@
+/// A map with a fixed capacity, that [deserialize_fixed_map](crate::deserialize_fixed_map) fills
+/// without allocating.
+///
+/// With the `heapless` feature, this is implemented for [heapless::IndexMap] (which includes
+/// [heapless::FnvIndexMap]) and [heapless::LinearMap]. Both also implement `Serialize` and
+/// `Deserialize` with that feature, so they can be used as struct fields. A field that gets more
+/// entries than it can hold fails with [Custom](crate::DeserializeError::Custom) instead of
+/// [CapacityExceeded](crate::DeserializeError::CapacityExceeded), because serde discards the
+/// reason.
+pub trait FixedMap {
+    /// The type of the keys.
+    type Key;
+
+    /// The type of the values.
+    type Value;
+
+    /// The maximum amount of entries in the map.
+    fn capacity(&self) -> usize;
+
+    /// Inserts an entry, returning the previous value of `key`. Returns the entry if the map is
+    /// full.
+    #[allow(clippy::type_complexity)]
+    fn insert(
+        &mut self,
+        key: Self::Key,
+        value: Self::Value,
+    ) -> Result<Option<Self::Value>, (Self::Key, Self::Value)>;
+}
+
+#[cfg(feature = "heapless")]
+impl<K, V, S, const N: usize> FixedMap for ::heapless::IndexMap<K, V, S, N>
+where
+    K: Eq + core::hash::Hash,
+    S: core::hash::BuildHasher,
+{
+    type Key = K;
+    type Value = V;
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        ::heapless::IndexMap::insert(self, key, value)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<K: Eq, V, const N: usize> FixedMap for ::heapless::LinearMap<K, V, N> {
+    type Key = K;
+    type Value = V;
+
+    fn capacity(&self) -> usize {
+        N
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        ::heapless::LinearMap::insert(self, key, value)
+    }
+}
@@ -0,0 +1,134 @@
+//! Fixed-point numbers that are encoded as their raw integer.
+//!
+//! Many microcontrollers have no FPU, so sensor values and control loops use fixed-point numbers
+//! instead of floats. [Fixed] stores such a number as an integer with `FRAC` fractional bits, and
+//! is (de)serialized as that integer. This means the active integer encoding of the options applies,
+//! so with varint encoding a small `Q16_16` only takes a few bytes.
+//!
+//! ```
+//! # extern crate serde_derive;
+//! # use serde_derive::{Deserialize, Serialize};
+//! # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+//! # use bincode_core::config::Options;
+//! use bincode_core::fixed_point::Q16_16;
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Setpoint {
+//!     temperature: Q16_16,
+//! }
+//!
+//! // 21.5 degrees
+//! let setpoint = Setpoint { temperature: Q16_16::from_int(21) + Q16_16::from_bits(0x8000) };
+//! let mut buffer = [0u8; 4];
+//! let options = DefaultOptions::new().with_fixint_encoding();
+//! serialize(&setpoint, BufferWriter::new(&mut buffer), options).unwrap();
+//! assert_eq!(buffer, [0x00, 0x80, 0x15, 0x00]);
+//!
+//! let decoded: Setpoint = deserialize(&buffer[..], options).unwrap();
+//! assert_eq!(decoded.temperature.to_int(), 21);
+//! ```
+
+use core::ops::{Add, Neg, Sub};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A fixed-point number, stored as the integer `I` with `FRAC` fractional bits.
+///
+/// The value of the number is `bits / 2^FRAC`. `FRAC` has to be smaller than the amount of bits in
+/// `I`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<I, const FRAC: u32>(I);
+
+/// A signed fixed-point number with 16 integer bits and 16 fractional bits.
+pub type Q16_16 = Fixed<i32, 16>;
+/// A signed fixed-point number with 24 integer bits and 8 fractional bits.
+pub type Q24_8 = Fixed<i32, 8>;
+/// A signed fixed-point number with 8 integer bits and 8 fractional bits.
+pub type Q8_8 = Fixed<i16, 8>;
+/// A signed fixed-point number in the range `[-1, 1)` with 15 fractional bits.
+pub type Q15 = Fixed<i16, 15>;
+/// A signed fixed-point number in the range `[-1, 1)` with 31 fractional bits.
+pub type Q31 = Fixed<i32, 31>;
+/// An unsigned fixed-point number with 16 integer bits and 16 fractional bits.
+pub type UQ16_16 = Fixed<u32, 16>;
+/// An unsigned fixed-point number with 8 integer bits and 8 fractional bits.
+pub type UQ8_8 = Fixed<u16, 8>;
+
+impl<I, const FRAC: u32> Fixed<I, FRAC> {
+    /// The amount of fractional bits.
+    pub const FRAC_BITS: u32 = FRAC;
+
+    /// Create a number from its raw representation.
+    pub const fn from_bits(bits: I) -> Self {
+        Fixed(bits)
+    }
+
+    /// Return the raw representation of the number.
+    pub fn to_bits(self) -> I {
+        self.0
+    }
+}
+
+macro_rules! impl_fixed {
+    ($($ty:ty),*) => {
+        $(
+            impl<const FRAC: u32> Fixed<$ty, FRAC> {
+                /// Create a number from an integer. The integer has to fit in the integer bits of
+                /// the number.
+                pub const fn from_int(value: $ty) -> Self {
+                    Fixed(value << FRAC)
+                }
+
+                /// Return the integer part of the number, rounded towards negative infinity.
+                pub const fn to_int(self) -> $ty {
+                    self.0 >> FRAC
+                }
+            }
+
+            impl<const FRAC: u32> Add for Fixed<$ty, FRAC> {
+                type Output = Self;
+
+                fn add(self, other: Self) -> Self {
+                    Fixed(self.0 + other.0)
+                }
+            }
+
+            impl<const FRAC: u32> Sub for Fixed<$ty, FRAC> {
+                type Output = Self;
+
+                fn sub(self, other: Self) -> Self {
+                    Fixed(self.0 - other.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+macro_rules! impl_neg {
+    ($($ty:ty),*) => {
+        $(
+            impl<const FRAC: u32> Neg for Fixed<$ty, FRAC> {
+                type Output = Self;
+
+                fn neg(self) -> Self {
+                    Fixed(-self.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_neg!(i8, i16, i32, i64);
+
+impl<I: Serialize, const FRAC: u32> Serialize for Fixed<I, FRAC> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, I: Deserialize<'de>, const FRAC: u32> Deserialize<'de> for Fixed<I, FRAC> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        I::deserialize(deserializer).map(Fixed)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::traits::{CheckpointRead, CoreRead, SliceReadError};
+
+/// An implementation of [CoreRead] for memory-mapped flash, like a `&'static [u8]` that points at
+/// a flash page with the device configuration.
+///
+/// Values are deserialized in place: `&str` and `&[u8]` fields borrow from the flash, so nothing
+/// is copied into RAM. A value can start at any offset, because ints and floats are never read
+/// from the flash directly. [fill](CoreRead::fill) copies them into a buffer one byte at a time,
+/// so there are no unaligned multi-byte loads from the flash, which fault on a Cortex-M0, and are
+/// not supported by every memory-mapped flash controller. Owned `String` and `Vec<u8>` values are
+/// copied with `fill` as well.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, DefaultOptions, FlashSlice};
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Config<'a> {
+///     name: &'a str,
+///     interval_ms: u32,
+/// }
+///
+/// // On a device, this is e.g. `#[link_section = ".config"]`, or a slice of the flash page
+/// static CONFIG: [u8; 10] = [0xFF, 4, b'n', b'o', b'd', b'e', 0xFB, 0xE8, 0x03, 0xFF];
+///
+/// let flash = FlashSlice::at(&CONFIG, 1).unwrap();
+/// assert!(!flash.is_erased());
+/// let options = DefaultOptions::new().allow_trailing_bytes();
+/// let config: Config<'static> = deserialize(flash, options).unwrap();
+/// assert_eq!(config, Config { name: "node", interval_ms: 1000 });
+/// assert_eq!(config.name.as_ptr(), CONFIG[2..].as_ptr());
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FlashSlice<'a> {
+    memory: &'a [u8],
+    position: usize,
+}
+
+impl<'a> FlashSlice<'a> {
+    /// The value of a byte of erased NOR flash.
+    pub const ERASED_BYTE: u8 = 0xFF;
+
+    /// Rounds `offset` up to the next multiple of `align`, e.g. to the program granularity of the
+    /// flash controller when records are appended to a page. `align` must be a power of two.
+    ///
+    /// ```
+    /// # use bincode_core::FlashSlice;
+    /// assert_eq!(FlashSlice::align_up(0, 8), 0);
+    /// assert_eq!(FlashSlice::align_up(13, 8), 16);
+    /// assert_eq!(FlashSlice::align_up(16, 8), 16);
+    /// ```
+    pub const fn align_up(offset: usize, align: usize) -> usize {
+        let mask = align.wrapping_sub(1);
+        offset.wrapping_add(mask) & !mask
+    }
+
+    /// Create a new reader that starts at the beginning of `memory`.
+    pub const fn new(memory: &'a [u8]) -> Self {
+        Self {
+            memory,
+            position: 0,
+        }
+    }
+
+    /// Create a new reader that starts at `offset` bytes into `memory`. The offset does not have
+    /// to be aligned. Returns `None` if `offset` is past the end of `memory`.
+    pub fn at(memory: &'a [u8], offset: usize) -> Option<Self> {
+        if offset <= memory.len() {
+            Some(Self {
+                memory,
+                position: offset,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The offset from the start of the memory that is read next.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The bytes that are not read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.memory.get(self.position..).unwrap_or(&[])
+    }
+
+    /// Whether the bytes that are not read yet are all [ERASED_BYTE](FlashSlice::ERASED_BYTE),
+    /// e.g. because nothing was written to this part of the page since it was erased.
+    pub fn is_erased(&self) -> bool {
+        self.remaining()
+            .iter()
+            .all(|&byte| byte == Self::ERASED_BYTE)
+    }
+
+    /// Return the complete memory, including the bytes that were already read.
+    pub fn into_inner(self) -> &'a [u8] {
+        self.memory
+    }
+
+    /// Reads from the remaining bytes with the `&[u8]` reader, and advances past the bytes it read.
+    fn read_with<T>(
+        &mut self,
+        f: impl FnOnce(&mut &'a [u8]) -> Result<T, SliceReadError>,
+    ) -> Result<T, SliceReadError> {
+        let mut remaining = self.remaining();
+        let result = f(&mut remaining);
+        self.position = self.memory.len() - remaining.len();
+        result
+    }
+}
+
+impl<'a> CoreRead<'a> for FlashSlice<'a> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        let bytes = self.read_with(|slice| slice.read_range(buffer.len()))?;
+        for (target, &byte) in buffer.iter_mut().zip(bytes) {
+            *target = byte;
+        }
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.read_with(|slice| slice.read())
+    }
+
+    fn read_range(&mut self, len: usize) -> Result<&'a [u8], Self::Error> {
+        self.read_with(|slice| slice.read_range(len))
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.read_with(|slice| slice.forward_str(len, visitor))
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.read_with(|slice| slice.forward_bytes(len, visitor))
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.remaining().len())
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.remaining().get(..len)
+    }
+}
+
+impl<'a> CheckpointRead<'a> for FlashSlice<'a> {
+    type Mark = usize;
+
+    fn mark(&self) -> Self::Mark {
+        self.position
+    }
+
+    fn reset(&mut self, mark: Self::Mark) {
+        self.position = mark;
+    }
+}
@@ -0,0 +1,167 @@
+//! The items of [Cbor](crate::config::Cbor), in the preferred serialization of
+//! [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949.html): ints, lengths and floats are written
+//! in their shortest form, and only definite lengths are used.
+
+use super::Header;
+use crate::config::Options;
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::CoreWrite;
+use core::convert::TryFrom;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1 << 5;
+const BYTES: u8 = 2 << 5;
+const TEXT: u8 = 3 << 5;
+const ARRAY: u8 = 4 << 5;
+const MAP: u8 = 5 << 5;
+const FALSE: u8 = 0xF4;
+const TRUE: u8 = 0xF5;
+const NULL: u8 = 0xF6;
+const FLOAT16: u8 = 0xF9;
+const FLOAT32: u8 = 0xFA;
+const FLOAT64: u8 = 0xFB;
+const POSITIVE_BIGNUM: u8 = 0xC2;
+const NEGATIVE_BIGNUM: u8 = 0xC3;
+/// The `f16` NaN that deterministic encoders write for every NaN.
+const NAN16: u16 = 0x7E00;
+
+/// Returns the bits of the `f16` with exactly the value of `v`, if there is one.
+fn to_f16(v: f32) -> Option<u16> {
+    let bits = v.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xFF) as i32;
+    let mantissa = bits & 0x7F_FFFF;
+    if exponent == 0xFF {
+        return Some(if mantissa == 0 { sign | 0x7C00 } else { NAN16 });
+    }
+    if exponent == 0 {
+        // Zero, or an f32 subnormal which is too small for an f16
+        return if mantissa == 0 { Some(sign) } else { None };
+    }
+    let exponent = exponent - 127;
+    if (-14..=15).contains(&exponent) {
+        if mantissa & 0x1FFF != 0 {
+            return None;
+        }
+        return Some(sign | (((exponent + 15) as u16) << 10) | (mantissa >> 13) as u16);
+    }
+    if (-24..-14).contains(&exponent) {
+        // An f16 subnormal, which is the significand in units of 2^-24
+        let significand = mantissa | 0x80_0000;
+        let shift = -(exponent + 1);
+        if significand & ((1 << shift) - 1) != 0 {
+            return None;
+        }
+        return Some(sign | (significand >> shift) as u16);
+    }
+    None
+}
+
+impl<W: CoreWrite, O: Options> Serializer<W, O> {
+    pub(crate) fn cbor_nil(&mut self) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(NULL)
+    }
+
+    pub(crate) fn cbor_bool(&mut self, v: bool) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(if v { TRUE } else { FALSE })
+    }
+
+    pub(crate) fn cbor_uint(&mut self, v: u64) -> Result<(), SerializeError<W::Error>> {
+        self.cbor_head(UNSIGNED, v)
+    }
+
+    pub(crate) fn cbor_int(&mut self, v: i64) -> Result<(), SerializeError<W::Error>> {
+        if v >= 0 {
+            self.cbor_head(UNSIGNED, v as u64)
+        } else {
+            // A negative int is written as -1 - v
+            self.cbor_head(NEGATIVE, !v as u64)
+        }
+    }
+
+    /// Writes `v` as an int if it fits in 64 bits, and as a bignum otherwise.
+    pub(crate) fn cbor_u128(&mut self, v: u128) -> Result<(), SerializeError<W::Error>> {
+        match u64::try_from(v) {
+            Ok(v) => self.cbor_head(UNSIGNED, v),
+            Err(_) => self.cbor_bignum(POSITIVE_BIGNUM, v),
+        }
+    }
+
+    /// Writes `v` as an int if it fits in 64 bits and a sign, and as a bignum otherwise.
+    pub(crate) fn cbor_i128(&mut self, v: i128) -> Result<(), SerializeError<W::Error>> {
+        if v >= 0 {
+            return self.cbor_u128(v as u128);
+        }
+        let v = !v as u128;
+        match u64::try_from(v) {
+            Ok(v) => self.cbor_head(NEGATIVE, v),
+            Err(_) => self.cbor_bignum(NEGATIVE_BIGNUM, v),
+        }
+    }
+
+    /// Writes `v` as the shortest float that has exactly the same value.
+    pub(crate) fn cbor_f32(&mut self, v: f32) -> Result<(), SerializeError<W::Error>> {
+        match to_f16(v) {
+            Some(half) => self.cbor_marked(FLOAT16, half.to_be_bytes()),
+            None => self.cbor_marked(FLOAT32, v.to_be_bytes()),
+        }
+    }
+
+    /// Writes `v` as the shortest float that has exactly the same value.
+    pub(crate) fn cbor_f64(&mut self, v: f64) -> Result<(), SerializeError<W::Error>> {
+        let single = v as f32;
+        if v.is_nan() || single as f64 == v {
+            self.cbor_f32(single)
+        } else {
+            self.cbor_marked(FLOAT64, v.to_be_bytes())
+        }
+    }
+
+    pub(crate) fn cbor_header(
+        &mut self,
+        header: Header,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>> {
+        let major = match header {
+            Header::Str => TEXT,
+            Header::Bin => BYTES,
+            Header::Array => ARRAY,
+            Header::Map => MAP,
+        };
+        self.cbor_head(major, len as u64)
+    }
+
+    /// Writes a bignum tag, followed by the big endian bytes of `v` without leading zeros.
+    fn cbor_bignum(&mut self, tag: u8, v: u128) -> Result<(), SerializeError<W::Error>> {
+        let bytes = v.to_be_bytes();
+        let start = (v.leading_zeros() / 8) as usize;
+        let bytes = bytes.get(start..).unwrap_or(&bytes);
+        self.serialize_byte(tag)?;
+        self.format_bytes(bytes)
+    }
+
+    /// Writes the initial byte of an item with major type `major`, and `v` as the shortest
+    /// argument.
+    fn cbor_head(&mut self, major: u8, v: u64) -> Result<(), SerializeError<W::Error>> {
+        if v < 24 {
+            self.serialize_byte(major | v as u8)
+        } else if let Ok(v) = u8::try_from(v) {
+            self.serialize_raw(&[major | 24, v])
+        } else if let Ok(v) = u16::try_from(v) {
+            self.cbor_marked(major | 25, v.to_be_bytes())
+        } else if let Ok(v) = u32::try_from(v) {
+            self.cbor_marked(major | 26, v.to_be_bytes())
+        } else {
+            self.cbor_marked(major | 27, v.to_be_bytes())
+        }
+    }
+
+    fn cbor_marked<const N: usize>(
+        &mut self,
+        marker: u8,
+        bytes: [u8; N],
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(marker)?;
+        self.serialize_raw(&bytes)
+    }
+}
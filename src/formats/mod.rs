@@ -0,0 +1,119 @@
+//! The self-describing formats [MsgPack](crate::config::MsgPack) and [Cbor](crate::config::Cbor).
+//!
+//! Both formats write the serde data model the same way, as nil, bools, ints, floats, strs, bins,
+//! arrays and maps. Only the bytes of these items differ, which are written by the submodules.
+
+use crate::config::{Format, Options};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::CoreWrite;
+
+mod cbor;
+mod msgpack;
+
+/// An item that starts with its length.
+#[derive(Clone, Copy)]
+pub(crate) enum Header {
+    Str,
+    Bin,
+    Array,
+    Map,
+}
+
+impl<W: CoreWrite, O: Options> Serializer<W, O> {
+    pub(crate) fn format_nil(&mut self) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_nil()
+        } else {
+            self.msgpack_nil()
+        }
+    }
+
+    pub(crate) fn format_bool(&mut self, v: bool) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_bool(v)
+        } else {
+            self.msgpack_bool(v)
+        }
+    }
+
+    pub(crate) fn format_uint(&mut self, v: u64) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_uint(v)
+        } else {
+            self.msgpack_uint(v)
+        }
+    }
+
+    pub(crate) fn format_int(&mut self, v: i64) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_int(v)
+        } else {
+            self.msgpack_int(v)
+        }
+    }
+
+    pub(crate) fn format_u128(&mut self, v: u128) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_u128(v)
+        } else {
+            self.msgpack_int128(v.to_be_bytes())
+        }
+    }
+
+    pub(crate) fn format_i128(&mut self, v: i128) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_i128(v)
+        } else {
+            self.msgpack_int128(v.to_be_bytes())
+        }
+    }
+
+    pub(crate) fn format_f32(&mut self, v: f32) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_f32(v)
+        } else {
+            self.msgpack_f32(v)
+        }
+    }
+
+    pub(crate) fn format_f64(&mut self, v: f64) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_f64(v)
+        } else {
+            self.msgpack_f64(v)
+        }
+    }
+
+    /// Writes the header of a str, bin, array or map with `len` bytes or items.
+    pub(crate) fn format_header(
+        &mut self,
+        header: Header,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::CBOR {
+            self.cbor_header(header, len)
+        } else {
+            self.msgpack_header(header, len)
+        }
+    }
+
+    pub(crate) fn format_str(&mut self, v: &str) -> Result<(), SerializeError<W::Error>> {
+        self.format_header(Header::Str, v.len())?;
+        self.serialize_raw(v.as_bytes())
+    }
+
+    pub(crate) fn format_bytes(&mut self, v: &[u8]) -> Result<(), SerializeError<W::Error>> {
+        self.format_header(Header::Bin, v.len())?;
+        self.serialize_raw(v)
+    }
+
+    /// Starts a variant with content, as a map with a single entry from the name of the variant
+    /// to the content.
+    pub(crate) fn format_variant(
+        &mut self,
+        variant: &'static str,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.format_header(Header::Map, 1)?;
+        self.format_str(variant)
+    }
+}
@@ -0,0 +1,147 @@
+//! The items of [MsgPack](crate::config::MsgPack). See the
+//! [spec](https://github.com/msgpack/msgpack/blob/master/spec.md) for the markers.
+
+use super::Header;
+use crate::config::Options;
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::CoreWrite;
+use core::convert::TryFrom;
+
+const NIL: u8 = 0xC0;
+const FALSE: u8 = 0xC2;
+const TRUE: u8 = 0xC3;
+const BIN8: u8 = 0xC4;
+const BIN16: u8 = 0xC5;
+const BIN32: u8 = 0xC6;
+const FLOAT32: u8 = 0xCA;
+const FLOAT64: u8 = 0xCB;
+const UINT8: u8 = 0xCC;
+const UINT16: u8 = 0xCD;
+const UINT32: u8 = 0xCE;
+const UINT64: u8 = 0xCF;
+const INT8: u8 = 0xD0;
+const INT16: u8 = 0xD1;
+const INT32: u8 = 0xD2;
+const INT64: u8 = 0xD3;
+const STR8: u8 = 0xD9;
+const STR16: u8 = 0xDA;
+const STR32: u8 = 0xDB;
+const ARRAY16: u8 = 0xDC;
+const ARRAY32: u8 = 0xDD;
+const MAP16: u8 = 0xDE;
+const MAP32: u8 = 0xDF;
+
+/// The marker that holds the length in its low bits, and the largest length that fits.
+fn fixed_marker(header: Header) -> Option<(u8, usize)> {
+    match header {
+        Header::Str => Some((0xA0, 31)),
+        Header::Bin => None,
+        Header::Array => Some((0x90, 15)),
+        Header::Map => Some((0x80, 15)),
+    }
+}
+
+/// The markers of a length of 8, 16 and 32 bits. Arrays and maps have no 8 bit length.
+fn len_markers(header: Header) -> (Option<u8>, u8, u8) {
+    match header {
+        Header::Str => (Some(STR8), STR16, STR32),
+        Header::Bin => (Some(BIN8), BIN16, BIN32),
+        Header::Array => (None, ARRAY16, ARRAY32),
+        Header::Map => (None, MAP16, MAP32),
+    }
+}
+
+impl<W: CoreWrite, O: Options> Serializer<W, O> {
+    pub(crate) fn msgpack_nil(&mut self) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(NIL)
+    }
+
+    pub(crate) fn msgpack_bool(&mut self, v: bool) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(if v { TRUE } else { FALSE })
+    }
+
+    /// Writes `v` as the smallest int that fits it.
+    pub(crate) fn msgpack_uint(&mut self, v: u64) -> Result<(), SerializeError<W::Error>> {
+        if v < 0x80 {
+            self.serialize_byte(v as u8)
+        } else if let Ok(v) = u8::try_from(v) {
+            self.serialize_raw(&[UINT8, v])
+        } else if let Ok(v) = u16::try_from(v) {
+            self.msgpack_marked(UINT16, v.to_be_bytes())
+        } else if let Ok(v) = u32::try_from(v) {
+            self.msgpack_marked(UINT32, v.to_be_bytes())
+        } else {
+            self.msgpack_marked(UINT64, v.to_be_bytes())
+        }
+    }
+
+    /// Writes `v` as the smallest int that fits it. Positive values are written as unsigned ints,
+    /// like other MessagePack encoders do.
+    pub(crate) fn msgpack_int(&mut self, v: i64) -> Result<(), SerializeError<W::Error>> {
+        if v >= 0 {
+            self.msgpack_uint(v as u64)
+        } else if v >= -32 {
+            self.serialize_byte(v as u8)
+        } else if let Ok(v) = i8::try_from(v) {
+            self.serialize_raw(&[INT8, v as u8])
+        } else if let Ok(v) = i16::try_from(v) {
+            self.msgpack_marked(INT16, v.to_be_bytes())
+        } else if let Ok(v) = i32::try_from(v) {
+            self.msgpack_marked(INT32, v.to_be_bytes())
+        } else {
+            self.msgpack_marked(INT64, v.to_be_bytes())
+        }
+    }
+
+    /// Writes a 128 bit int as a bin of its 16 big endian bytes, because MessagePack ints have at
+    /// most 64 bits.
+    pub(crate) fn msgpack_int128(
+        &mut self,
+        bytes: [u8; 16],
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.format_bytes(&bytes)
+    }
+
+    pub(crate) fn msgpack_f32(&mut self, v: f32) -> Result<(), SerializeError<W::Error>> {
+        self.msgpack_marked(FLOAT32, v.to_be_bytes())
+    }
+
+    pub(crate) fn msgpack_f64(&mut self, v: f64) -> Result<(), SerializeError<W::Error>> {
+        self.msgpack_marked(FLOAT64, v.to_be_bytes())
+    }
+
+    /// Writes the marker and the length of a str, bin, array or map with `len` bytes or items.
+    pub(crate) fn msgpack_header(
+        &mut self,
+        header: Header,
+        len: usize,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if let Some((marker, max)) = fixed_marker(header) {
+            if len <= max {
+                return self.serialize_byte(marker | len as u8);
+            }
+        }
+        let (len8, len16, len32) = len_markers(header);
+        match (len8, u8::try_from(len)) {
+            (Some(marker), Ok(len)) => self.serialize_raw(&[marker, len]),
+            _ => {
+                if let Ok(len) = u16::try_from(len) {
+                    self.msgpack_marked(len16, len.to_be_bytes())
+                } else if let Ok(len) = u32::try_from(len) {
+                    self.msgpack_marked(len32, len.to_be_bytes())
+                } else {
+                    Err(SerializeError::LengthTooLarge(len))
+                }
+            }
+        }
+    }
+
+    fn msgpack_marked<const N: usize>(
+        &mut self,
+        marker: u8,
+        bytes: [u8; N],
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_byte(marker)?;
+        self.serialize_raw(&bytes)
+    }
+}
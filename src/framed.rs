@@ -0,0 +1,80 @@
+use crate::backpatch::{Prefix, Reserved};
+use crate::config::{FlushPolicy, Infinite, Options, SizeLimit, TrailingBytes, WithOtherLimit};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWriteSeek};
+use core::convert::TryFrom;
+use serde::{Deserialize, Serialize};
+
+/// Serialize `value` into `writer`, prefixed with the amount of bytes that the value takes.
+///
+/// The length is encoded like the length of a `&[u8]`, so a frame can be skipped, or read as a
+/// `&[u8]`, without knowing the type of the value. Unlike serializing the value into a scratch
+/// buffer first, or measuring it with [serialize_size](crate::serialize_size), the value is written
+/// once: a placeholder length is written before it, and overwritten when the value is written. If
+/// the length needs a different amount of bytes than the placeholder (e.g. with varint encoding),
+/// the value is moved with [CoreWriteSeek::move_tail]. With a size limit, the placeholder fits
+/// the largest length within the limit, so the value is only moved towards the start.
+///
+/// The size limit is checked while writing, and includes the length. The writer is flushed after
+/// the length is written, unless the options were built with
+/// [with_manual_flush](Options::with_manual_flush).
+///
+/// ```
+/// # use bincode_core::{deserialize, deserialize_framed, serialize_framed, BufferWriter, DefaultOptions};
+/// let mut buffer = [0u8; 8];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize_framed(&(1u8, "ab"), &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.written_buffer(), &[4, 1, 2, b'a', b'b']);
+///
+/// let frame: &[u8] = deserialize(writer.written_buffer(), DefaultOptions::new()).unwrap();
+/// assert_eq!(frame, &[1, 2, b'a', b'b']);
+/// let value: (u8, &str) = deserialize_framed(writer.written_buffer(), DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, "ab"));
+/// ```
+pub fn serialize_framed<T, W, O>(
+    value: &T,
+    mut writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>>
+where
+    T: Serialize + ?Sized,
+    W: CoreWriteSeek,
+    O: Options,
+{
+    let max_len = options
+        .limit()
+        .limit()
+        .map_or(0, |limit| usize::try_from(limit).unwrap_or(usize::MAX));
+    let placeholder = Prefix::len(WithOtherLimit::new(&mut options, Infinite), max_len)?;
+    let reserved = Reserved::write(&mut writer, &placeholder)?;
+
+    let mut serializer = Serializer::new(writer, &mut options);
+    serializer.serialize_value(value)?;
+    serializer.write_bools()?;
+    let mut writer = serializer.into_writer();
+
+    let len = writer.position() - reserved.end();
+    reserved.patch(&mut writer, &Prefix::len(&mut options, len)?)?;
+    if O::Flush::AUTO_FLUSH {
+        writer.flush().map_err(SerializeError::Write)?;
+    }
+    Ok(())
+}
+
+/// Deserialize a value that was written with [serialize_framed].
+///
+/// Bytes at the end of the frame that the value does not use are skipped, so fields that were
+/// appended to the value by a newer version of the sender are ignored. If the value takes more
+/// bytes than the frame, [DeserializeError::InvalidFrameLength] is returned.
+pub fn deserialize_framed<'a, T, R, O>(reader: R, options: O) -> Result<T, DeserializeError<'a, R>>
+where
+    T: Deserialize<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+{
+    let mut deserializer = Deserializer::new(reader, options);
+    let value = deserializer.deserialize_frame()?;
+    O::Trailing::check_end(deserializer.reader())?;
+    Ok(value)
+}
@@ -0,0 +1,255 @@
+use crate::traits::{CoreRead, CoreWrite};
+use core::fmt;
+use serde::de::{self, Visitor};
+
+/// A hash (or XOF, or checksum) that is fed every byte that passes through a [HashingWriter] or
+/// [HashingReader].
+///
+/// The bytes are passed in the order of the stream, in slices of any length. Finalizing the hash is
+/// left to the caller, after the hasher is returned with e.g. [HashingWriter::into_parts].
+///
+/// This is implemented for closures, so a hash can be used with e.g.
+/// `|data: &[u8]| hasher.update(data)`.
+pub trait StreamHasher {
+    /// Add the next `data.len()` bytes of the stream to the hash.
+    fn update(&mut self, data: &[u8]);
+}
+
+impl<F: FnMut(&[u8])> StreamHasher for F {
+    fn update(&mut self, data: &[u8]) {
+        self(data)
+    }
+}
+
+/// An implementation of [CoreWrite] that feeds all bytes that are written to the inner writer to a
+/// [StreamHasher], so a content hash of a value is computed while it is serialized.
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions, HashingWriter};
+/// let mut buffer = [0u8; 8];
+/// let mut sum = 0u8;
+/// let checksum = |data: &[u8]| data.iter().for_each(|byte| sum = sum.wrapping_add(*byte));
+/// let mut writer = HashingWriter::new(BufferWriter::new(&mut buffer), checksum);
+/// serialize(&(1u8, 2u8), &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.into_inner().written_buffer(), &[1, 2]);
+/// assert_eq!(sum, 3);
+/// ```
+pub struct HashingWriter<W: CoreWrite, H: StreamHasher> {
+    writer: W,
+    hasher: H,
+}
+
+impl<W: CoreWrite, H: StreamHasher> HashingWriter<W, H> {
+    /// Create a new writer that feeds all bytes that are written to `writer` to `hasher`.
+    pub fn new(writer: W, hasher: H) -> Self {
+        Self { writer, hasher }
+    }
+
+    /// A reference to the hasher, e.g. to finalize a copy of the hash so far.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Return the inner writer and the hasher.
+    pub fn into_parts(self) -> (W, H) {
+        (self.writer, self.hasher)
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: CoreWrite, H: StreamHasher> CoreWrite for HashingWriter<W, H> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.hasher.update(&[val]);
+        self.writer.write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.hasher.update(val);
+        self.writer.write_all(val)
+    }
+}
+
+impl<W: CoreWrite, H: StreamHasher> CoreWrite for &mut HashingWriter<W, H> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// An implementation of [CoreRead] that feeds all bytes that are read from the inner reader to a
+/// [StreamHasher], including the borrowed `&str` and `&[u8]` values. This can be used to verify
+/// the content hash of a value, e.g. of a firmware update manifest, while it is deserialized.
+///
+/// ```
+/// # use bincode_core::{DefaultOptions, Deserializer, HashingReader};
+/// # use serde::Deserialize;
+/// let mut sum = 0u8;
+/// let checksum = |data: &[u8]| data.iter().for_each(|byte| sum = sum.wrapping_add(*byte));
+/// let reader = HashingReader::new(&[2, b'a', b'b'][..], checksum);
+/// let mut deserializer = Deserializer::new(reader, DefaultOptions::new());
+/// let value = <&str>::deserialize(&mut deserializer).unwrap();
+/// drop(deserializer);
+/// assert_eq!(value, "ab");
+/// assert_eq!(sum, 2 + b'a' + b'b');
+/// ```
+pub struct HashingReader<R, H: StreamHasher> {
+    reader: R,
+    hasher: H,
+}
+
+impl<R, H: StreamHasher> HashingReader<R, H> {
+    /// Create a new reader that feeds all bytes that are read from `reader` to `hasher`.
+    pub fn new(reader: R, hasher: H) -> Self {
+        Self { reader, hasher }
+    }
+
+    /// A reference to the hasher, e.g. to finalize a copy of the hash so far.
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Return the inner reader and the hasher.
+    pub fn into_parts(self) -> (R, H) {
+        (self.reader, self.hasher)
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'a, R: CoreRead<'a>, H: StreamHasher> CoreRead<'a> for HashingReader<R, H> {
+    type Error = R::Error;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.reader.fill(buffer)?;
+        self.hasher.update(buffer);
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        let visitor = HashingVisitor {
+            visitor,
+            hasher: &mut self.hasher,
+        };
+        self.reader.forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        let visitor = HashingVisitor {
+            visitor,
+            hasher: &mut self.hasher,
+        };
+        self.reader.forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        self.reader.remaining_len()
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.reader.peek(len)
+    }
+}
+
+impl<'a, R: CoreRead<'a>, H: StreamHasher> CoreRead<'a> for &mut HashingReader<R, H> {
+    type Error = R::Error;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        (**self).peek(len)
+    }
+}
+
+/// Adds the `&str` or `&[u8]` that a reader forwards to the hash, before passing it on.
+struct HashingVisitor<'h, V, H> {
+    visitor: V,
+    hasher: &'h mut H,
+}
+
+impl<'a, V: Visitor<'a>, H: StreamHasher> Visitor<'a> for HashingVisitor<'_, V, H> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'a str) -> Result<V::Value, E> {
+        self.hasher.update(v.as_bytes());
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<V::Value, E> {
+        self.hasher.update(v.as_bytes());
+        self.visitor.visit_str(v)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_string<E: de::Error>(self, v: alloc::string::String) -> Result<V::Value, E> {
+        self.hasher.update(v.as_bytes());
+        self.visitor.visit_string(v)
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'a [u8]) -> Result<V::Value, E> {
+        self.hasher.update(v);
+        self.visitor.visit_borrowed_bytes(v)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<V::Value, E> {
+        self.hasher.update(v);
+        self.visitor.visit_bytes(v)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn visit_byte_buf<E: de::Error>(self, v: alloc::vec::Vec<u8>) -> Result<V::Value, E> {
+        self.hasher.update(&v);
+        self.visitor.visit_byte_buf(v)
+    }
+}
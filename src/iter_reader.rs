@@ -0,0 +1,157 @@
+use crate::config::Options;
+use crate::deserialize::{deserialize, DeserializeError};
+use crate::traits::{CoreRead, SliceReadError};
+use core::str;
+use serde::Deserialize;
+
+/// An implementation of [CoreRead] that reads from an iterator of bytes.
+///
+/// This allows deserializing straight from sources that hand out one byte at a time, like a
+/// `heapless::spsc::Consumer` that is drained with `core::iter::from_fn(|| consumer.dequeue())`,
+/// without collecting the bytes into a slice first.
+///
+/// An iterator has no persistent storage, so `&str` and `&[u8]` values are copied into a
+/// caller-provided scratch buffer, and borrowed from there. The scratch buffer is not reused during
+/// a single deserialization, so it has to be large enough to hold all borrowed slices in the
+/// deserialized value. If the value has no borrowed data, the scratch buffer can be empty.
+///
+/// Running out of bytes is an [EndOfSlice](SliceReadError::EndOfSlice) error. When the iterator
+/// reports an exact [size_hint](Iterator::size_hint), it is used as the
+/// [remaining length](CoreRead::remaining_len), so trailing bytes are detected.
+///
+/// ```
+/// # use bincode_core::{deserialize, DefaultOptions, IterReader};
+/// let bytes = [3, b'a', b'b', b'c', 5];
+/// let mut scratch = [0u8; 8];
+/// let reader = IterReader::new(bytes.iter().copied(), &mut scratch);
+/// let value: (&str, u8) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, ("abc", 5));
+/// ```
+pub struct IterReader<'buf, I> {
+    iter: I,
+    scratch: &'buf mut [u8],
+}
+
+impl<'buf, I: Iterator<Item = u8>> IterReader<'buf, I> {
+    /// Create a new reader that reads from `iter`, and stores `&str` and `&[u8]` values in
+    /// `scratch`.
+    pub fn new(iter: I, scratch: &'buf mut [u8]) -> Self {
+        Self { iter, scratch }
+    }
+
+    /// Return the inner iterator, with the bytes that are not read yet.
+    pub fn into_inner(self) -> I {
+        self.iter
+    }
+
+    fn read_scratch(&mut self, len: usize) -> Result<&'buf [u8], SliceReadError> {
+        if len > self.scratch.len() {
+            return Err(SliceReadError::ScratchTooSmall);
+        }
+        let (result, rest) = core::mem::take(&mut self.scratch).split_at_mut(len);
+        self.scratch = rest;
+        self.fill(result)?;
+        Ok(result)
+    }
+}
+
+impl<'buf, I: Iterator<Item = u8>> CoreRead<'buf> for IterReader<'buf, I> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buffer {
+            *byte = self.read()?;
+        }
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.iter.next().ok_or(SliceReadError::EndOfSlice)
+    }
+
+    fn read_range(&mut self, len: usize) -> Result<&'buf [u8], Self::Error> {
+        self.read_scratch(len)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        let bytes = self.read_scratch(len)?;
+        let string = str::from_utf8(bytes).map_err(|_| SliceReadError::InvalidUtf8)?;
+        visitor.visit_borrowed_str(string)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        let bytes = self.read_scratch(len)?;
+        visitor.visit_borrowed_bytes(bytes)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        }
+    }
+}
+
+impl<'buf, I: Iterator<Item = u8>> CoreRead<'buf> for &mut IterReader<'buf, I> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        (**self).read()
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'buf>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+}
+
+/// Deserialize a value from an iterator of bytes, storing `&str` and `&[u8]` values in `scratch`.
+///
+/// This is a shorthand for [deserialize] with an [IterReader]. To deserialize multiple values from
+/// the same iterator, pass `iter.by_ref()` and allow trailing bytes with
+/// [allow_trailing_bytes](crate::config::Options::allow_trailing_bytes).
+///
+/// ```
+/// # use bincode_core::{deserialize_from_iter, DefaultOptions};
+/// let bytes = [1, 2, b'h', b'i'];
+/// let mut scratch = [0u8; 8];
+/// let value: (u8, &str) =
+///     deserialize_from_iter(bytes.iter().copied(), &mut scratch, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, "hi"));
+/// ```
+pub fn deserialize_from_iter<'buf, T, I, O>(
+    iter: I,
+    scratch: &'buf mut [u8],
+    options: O,
+) -> Result<T, DeserializeError<'buf, IterReader<'buf, I::IntoIter>>>
+where
+    T: Deserialize<'buf>,
+    I: IntoIterator<Item = u8>,
+    I::IntoIter: 'buf,
+    O: Options,
+{
+    deserialize(IterReader::new(iter.into_iter(), scratch), options)
+}
@@ -0,0 +1,57 @@
+use serde::ser::{Error, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+/// Serializes the items of an iterator as a sequence, for iterators that do not know their length
+/// up front.
+///
+/// Bincode writes the length of a sequence before its elements, so `serialize_seq` needs a length,
+/// and returns [SequenceMustHaveLength](crate::SerializeError::SequenceMustHaveLength) without it.
+/// Iterator adapters like `filter` cannot report their exact length. `IterSeq` clones the iterator
+/// and counts the items first, then serializes a sequence of that length. The encoding is the same
+/// as a slice of the items.
+///
+/// The iterator is cloned and run twice every time the value is serialized, so it has to yield the
+/// same items every time. If the second run yields a different amount of items, serialization fails
+/// with a custom error.
+///
+/// ```
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions, IterSeq};
+/// let readings = [3u8, 250, 7, 255, 12];
+/// let valid = IterSeq(readings.iter().filter(|r| **r < 250));
+///
+/// let mut buffer = [0u8; 8];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&valid, &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.written_buffer(), &[3, 3, 7, 12]);
+///
+/// // A sequence of bytes is encoded like a `&[u8]`
+/// let decoded: &[u8] = deserialize(writer.written_buffer(), DefaultOptions::new()).unwrap();
+/// assert_eq!(decoded, &[3, 7, 12]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IterSeq<I>(pub I);
+
+impl<I> Serialize for IterSeq<I>
+where
+    I: Iterator + Clone,
+    I::Item: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = self.0.clone().count();
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        let mut count = 0;
+        for item in self.0.clone() {
+            count += 1;
+            if count > len {
+                break;
+            }
+            seq.serialize_element(&item)?;
+        }
+        if count != len {
+            return Err(S::Error::custom(
+                "IterSeq: the iterator yielded a different amount of items",
+            ));
+        }
+        seq.end()
+    }
+}
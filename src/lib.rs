@@ -10,29 +10,169 @@
 //! like `Vec` and `String`.
 //!
 //! This crate is an alternative (but mostly similar) for bincode that works on microcontrollers.
-//! It does this by not supporting types like `Vec` and `String`.
+//! It does not need an allocator, and reads from and writes to the [CoreRead] and [CoreWrite]
+//! traits instead.
 //!
 //! Types like `&str` and `&[u8]` are supported. This is possible because `CoreRead` has a
 //! requirement that the data being read, has to be persisted somewhere. Usually this is done by a
 //! fixed-size backing array. The `&str` and `&[u8]` then simply point to a position in that
 //! buffer.
+//!
+//! With the `alloc` feature, owned types like `Vec` and `String` are supported as well. Their
+//! bytes are read into a new allocation with `CoreRead::read_owned`, so they can be read from any
+//! reader, including readers that cannot lend out borrowed slices.
+//!
+//! # Encoding of `core` types
+//!
+//! The following `core` types use serde's implementations. Their encodings are stable, and are
+//! covered by tests. "int" means an integer in the active integer encoding, and "variant" an enum
+//! variant index in the active discriminant encoding.
+//!
+//! | Type | Encoding |
+//! |------|----------|
+//! | `Duration` | seconds as `u64` int, then nanoseconds as `u32` int |
+//! | `NonZero*`, `Wrapping<T>` | the inner integer; deserializing a zero `NonZero*` is an error |
+//! | `Range<T>`, `RangeInclusive<T>` | start, then end |
+//! | `Ipv4Addr`, `Ipv6Addr` | the 4 or 16 octets, without a length |
+//! | `IpAddr` | variant (0 for V4, 1 for V6), then the address |
+//! | `SocketAddrV4`, `SocketAddrV6` | the address, then the port as `u16` int |
+//! | `SocketAddr` | variant (0 for V4, 1 for V6), then the socket address |
+//!
+//! The flow info and scope id of a `SocketAddrV6` are not encoded, and are 0 after deserializing.
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+pub mod chunker;
+pub mod compat;
 /// Contains helper structs to customize the way your structs are (de)serialized.
 pub mod config;
+pub mod delta;
+pub mod dispatch;
+pub mod encoding_spec;
+pub mod fixed_point;
+pub mod mac;
+pub mod schema;
+#[cfg(feature = "storage")]
+pub mod storage;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod versioned;
 
+mod armor;
+mod backpatch;
 mod buffer_writer;
+mod buffered_reader;
+mod byte_array;
+mod byte_order;
+mod chained_slices;
+mod chained_writer;
+mod chunk_writer;
+#[cfg(feature = "compression")]
+mod compress;
+mod const_writer;
+mod counting_writer;
+mod debug_dump;
+#[cfg(feature = "std")]
+mod decode_dump;
 mod deserialize;
+mod error;
+mod error_context;
+mod fixed_map;
+mod flash_slice;
+mod formats;
+mod framed;
+mod hashing;
+mod iter_reader;
+mod iter_seq;
+mod pod;
+#[cfg(any(feature = "bytemuck", feature = "zerocopy"))]
+mod pod_interop;
+mod presence;
+mod primitive_slice;
+mod push_deserializer;
+#[cfg(feature = "rtt")]
+mod rtt_writer;
+mod samples;
 mod serialize;
+#[cfg(feature = "critical-section")]
+mod shared_writer;
 mod size_checker;
+mod slice_reader;
+mod tee_writer;
 mod traits;
+mod transform;
 
-pub use self::buffer_writer::{BufferWriter, BufferWriterError};
+pub use self::armor::{ArmorReadError, Base64Reader, Base64Writer, HexReader, HexWriter};
+pub use self::buffer_writer::{BufferWriter, BufferWriterError, FrameBufferWriter, FrameIterator};
+pub use self::buffered_reader::{BufferedReadError, BufferedReader};
+pub use self::byte_array::ByteArray;
+pub use self::byte_order::{Be, Le};
+pub use self::chained_slices::ChainedSlices;
+pub use self::chained_writer::ChainedWriter;
+pub use self::chunk_writer::ChunkWriter;
+#[cfg(feature = "compression")]
+pub use self::compress::{CompressedReadError, CompressedReader, CompressedWriter};
 pub use self::config::DefaultOptions;
-pub use self::deserialize::{deserialize, DeserializeError};
-pub use self::serialize::{serialize, serialize_size, SerializeError};
-pub use self::traits::{CoreRead, CoreWrite};
+pub use self::const_writer::ConstWriter;
+pub use self::counting_writer::CountingWriter;
+pub use self::debug_dump::debug_dump;
+#[cfg(feature = "std")]
+pub use self::decode_dump::{decode_dump, DumpError};
+pub use self::deserialize::{
+    deserialize, deserialize_counted, deserialize_fixed_map, deserialize_iter,
+    deserialize_map_with, deserialize_seed, deserialize_with_context, deserialize_with_remaining,
+    DeserializeError, DeserializeIterator, Deserializer,
+};
+pub use self::error::{Error, ErrorKind};
+pub use self::error_context::{ContextError, FieldPath, MAX_FIELD_PATH_DEPTH};
+pub use self::fixed_map::FixedMap;
+pub use self::flash_slice::FlashSlice;
+pub use self::framed::{deserialize_framed, serialize_framed};
+pub use self::hashing::{HashingReader, HashingWriter, StreamHasher};
+pub use self::iter_reader::{deserialize_from_iter, IterReader};
+pub use self::iter_seq::IterSeq;
+pub use self::pod::{deserialize_pod_ref, serialize_pod, Pod, PodRef};
+#[cfg(feature = "bytemuck")]
+pub use self::pod_interop::{deserialize_bytemuck_ref, serialize_bytemuck};
+#[cfg(feature = "zerocopy")]
+pub use self::pod_interop::{deserialize_zerocopy_ref, serialize_zerocopy};
+pub use self::primitive_slice::{Primitive, PrimitiveSlice};
+pub use self::push_deserializer::{PushDeserializer, PushError};
+#[cfg(feature = "rtt")]
+pub use self::rtt_writer::{RttChannel, RttWriteError, RttWriter};
+pub use self::samples::serialize_samples;
+pub use self::serialize::{
+    serialize, serialize_all, serialize_size, SerializeError, SerializeErrorFor, Serializer,
+};
+#[cfg(feature = "critical-section")]
+pub use self::shared_writer::{SharedWriteError, SharedWriter};
+pub use self::slice_reader::SliceReader;
+pub use self::tee_writer::{TeeError, TeeWriter};
+#[cfg(feature = "heapless")]
+pub use self::traits::SpscReadError;
+#[cfg(feature = "smoltcp")]
+pub use self::traits::{serialize_udp, TcpSocketError, UdpSendError};
+#[cfg(feature = "bbqueue")]
+pub use self::traits::{BbqReadError, GrantReader};
+pub use self::traits::{CheckpointRead, CoreRead, CoreWrite, CoreWriteSeek, SliceReadError};
+#[cfg(feature = "embedded-io")]
+pub use self::traits::{EmbeddedIo, EmbeddedIoError};
+#[cfg(feature = "embedded-hal")]
+pub use self::traits::{
+    HalReadError, HalWriteError, I2cAdapter, NbReader, SerialWriter, SpiAdapter, TimeoutReader,
+};
+#[cfg(feature = "std")]
+pub use self::traits::{IoReadError, IoReader, IoWriter};
+pub use self::transform::{StreamTransform, TransformReadError, TransformReader, TransformWriter};
+/// Re-export of [serde_bytes](https://docs.rs/serde_bytes), available with the `serde-bytes`
+/// feature.
+///
+/// Serde serializes `&[u8]` and `[u8; N]` one element at a time. Fields that are annotated with
+/// `#[serde(with = "bincode_core::serde_bytes")]`, and the [Bytes](serde_bytes::Bytes) and
+/// [ByteArray](serde_bytes::ByteArray) wrappers, are serialized with a length followed by a single
+/// [CoreWrite::write_all] call, and deserialized with a single [CoreRead::forward_bytes] call.
+#[cfg(feature = "serde-bytes")]
+pub use serde_bytes;
@@ -0,0 +1,168 @@
+//! Authenticate messages with a MAC trailer.
+//!
+//! [serialize_with_mac] passes every serialized byte through a [MacProvider], like HMAC or CMAC,
+//! and writes the resulting tag after the value. [deserialize_with_mac] computes the MAC over the
+//! bytes it reads, and only returns the value if the tag that follows it matches. The bytes are
+//! streamed through the MAC while they are (de)serialized, so no second buffer for the message is
+//! needed.
+//!
+//! The MAC only authenticates the message, it does not encrypt it. Combine it with a
+//! [TransformWriter](crate::TransformWriter) to encrypt the message as well.
+//!
+//! ```
+//! # use bincode_core::mac::{deserialize_with_mac, serialize_with_mac, MacProvider};
+//! # use bincode_core::{BufferWriter, DefaultOptions, DeserializeError};
+//! /// A keyed checksum, standing in for a real MAC in this example.
+//! struct KeyedSum(u8);
+//!
+//! impl MacProvider for KeyedSum {
+//!     type Tag = [u8; 1];
+//!     fn update(&mut self, data: &[u8]) {
+//!         data.iter().for_each(|byte| self.0 = self.0.wrapping_add(*byte));
+//!     }
+//!     fn finalize(self) -> [u8; 1] {
+//!         [self.0]
+//!     }
+//! }
+//!
+//! let mut buffer = [0u8; 8];
+//! let mut writer = BufferWriter::new(&mut buffer);
+//! serialize_with_mac(&(1u8, 2u8), &mut writer, DefaultOptions::new(), KeyedSum(7)).unwrap();
+//! assert_eq!(writer.written_buffer(), &[1, 2, 10]);
+//!
+//! let bytes = writer.written_buffer();
+//! let value: (u8, u8) = deserialize_with_mac(bytes, DefaultOptions::new(), KeyedSum(7)).unwrap();
+//! assert_eq!(value, (1, 2));
+//!
+//! match deserialize_with_mac::<(u8, u8), _, _, _>(bytes, DefaultOptions::new(), KeyedSum(8)) {
+//!     Err(DeserializeError::MacMismatch) => {}
+//!     _ => panic!(),
+//! }
+//! ```
+//!
+//! MAC crates can be used by implementing [MacProvider] for a wrapper type, e.g. for HMAC-SHA256
+//! with the `hmac` and `sha2` crates:
+//!
+//! ```ignore
+//! struct HmacSha256(hmac::Hmac<sha2::Sha256>);
+//!
+//! impl MacProvider for HmacSha256 {
+//!     type Tag = [u8; 32];
+//!     fn update(&mut self, data: &[u8]) {
+//!         hmac::Mac::update(&mut self.0, data)
+//!     }
+//!     fn finalize(self) -> [u8; 32] {
+//!         hmac::Mac::finalize(self.0).into_bytes().into()
+//!     }
+//! }
+//! ```
+
+use crate::config::{FlushPolicy, Options, TrailingBytes};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::hashing::{HashingReader, HashingWriter, StreamHasher};
+use crate::serialize::{serialize, SerializeError};
+use crate::traits::{CoreRead, CoreWrite};
+use serde::{Deserialize, Serialize};
+
+/// A message authentication code that is computed over a stream of bytes.
+pub trait MacProvider {
+    /// The computed tag, e.g. `[u8; 32]` for HMAC-SHA256. It is written after the message.
+    type Tag: AsRef<[u8]>;
+
+    /// Add the next bytes of the message to the MAC.
+    fn update(&mut self, data: &[u8]);
+
+    /// Compute the tag over all bytes that were added.
+    fn finalize(self) -> Self::Tag;
+}
+
+/// Serialize `value` into `writer`, followed by the tag that `mac` computes over the serialized
+/// bytes.
+///
+/// The tag does not count towards the size limit. The writer is flushed after the tag is written,
+/// unless the options were built with [with_manual_flush](Options::with_manual_flush).
+pub fn serialize_with_mac<T, W, O, M>(
+    value: &T,
+    writer: W,
+    options: O,
+    mac: M,
+) -> Result<(), SerializeError<W::Error>>
+where
+    T: Serialize + ?Sized,
+    W: CoreWrite,
+    O: Options,
+    M: MacProvider,
+{
+    let auto_flush = O::Flush::AUTO_FLUSH;
+    let mut tee = HashingWriter::new(writer, Mac(mac));
+    serialize(value, &mut tee, options.with_manual_flush()).map_err(|e| e.map_write(|e| e))?;
+    let (mut writer, Mac(mac)) = tee.into_parts();
+    writer
+        .write_all(mac.finalize().as_ref())
+        .map_err(SerializeError::Write)?;
+    if auto_flush {
+        writer.flush().map_err(SerializeError::Write)?;
+    }
+    Ok(())
+}
+
+/// Deserialize a value that was written with [serialize_with_mac].
+///
+/// `mac` has to be set up with the same key as the one that was used to serialize the value. If the
+/// tag after the value does not match, [DeserializeError::MacMismatch] is returned and the value is
+/// dropped. The tags are compared in constant time.
+pub fn deserialize_with_mac<'a, T, R, O, M>(
+    reader: R,
+    options: O,
+    mac: M,
+) -> Result<T, DeserializeError<'a, R>>
+where
+    T: Deserialize<'a>,
+    R: CoreRead<'a> + 'a,
+    O: Options,
+    M: MacProvider + 'a,
+{
+    let mut deserializer = Deserializer::new(HashingReader::new(reader, Mac(mac)), options);
+    let value =
+        T::deserialize(&mut deserializer).map_err(|e| e.map_read(DeserializeError::Read))?;
+    let (mut reader, Mac(mac)) = deserializer.into_reader().into_parts();
+    verify(&mut reader, mac.finalize().as_ref())?;
+    O::Trailing::check_end(&reader)?;
+    Ok(value)
+}
+
+/// Reads the tag from `reader`, and compares it with `expected` without returning early.
+fn verify<'a, R: CoreRead<'a> + 'a>(
+    reader: &mut R,
+    expected: &[u8],
+) -> Result<(), DeserializeError<'a, R>> {
+    if let Some(remaining) = reader.remaining_len() {
+        if remaining < expected.len() {
+            return Err(DeserializeError::UnexpectedEof {
+                needed: expected.len() - remaining,
+            });
+        }
+    }
+    let mut received = [0u8; 16];
+    let mut difference = 0;
+    for expected in expected.chunks(received.len()) {
+        let received = &mut received[..expected.len()];
+        reader.fill(received).map_err(DeserializeError::Read)?;
+        for (a, b) in expected.iter().zip(received.iter()) {
+            difference |= a ^ b;
+        }
+    }
+    if difference != 0 {
+        return Err(DeserializeError::MacMismatch);
+    }
+    Ok(())
+}
+
+/// Passes the bytes of a [HashingWriter] or [HashingReader] through the MAC.
+struct Mac<M>(M);
+
+impl<M: MacProvider> StreamHasher for Mac<M> {
+    fn update(&mut self, data: &[u8]) {
+        self.0.update(data)
+    }
+}
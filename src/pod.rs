@@ -0,0 +1,219 @@
+use crate::config::{
+    FlushPolicy, Format, OptionEncoding, Options, SizeLimit, StructEncoding, TrailingBytes,
+    TypeTagEncoding,
+};
+use crate::deserialize::{deserialize, DeserializeError};
+use crate::primitive_slice::{is_big_endian, PrimitiveKind};
+use crate::serialize::{serialize, SerializeError};
+use crate::traits::CoreWrite;
+use core::mem::{align_of, size_of};
+use core::ops::Deref;
+use serde::{Deserialize, Serialize};
+
+/// A plain-old-data type, whose in-memory representation is written and read as it is by
+/// [serialize_pod] and [deserialize_pod_ref].
+///
+/// This is implemented for the integers, `f32`, `f64` and arrays of `Pod` types. Implement it for
+/// your own structs to use the fast path for them.
+///
+/// # Safety
+///
+/// A type can only implement `Pod` if:
+///
+/// - it is a struct with `#[repr(C)]` or `#[repr(transparent)]`,
+/// - every field is `Pod`,
+/// - it has no padding, so the size of the struct is the sum of the sizes of its fields. Order the
+///   fields from the largest to the smallest alignment, or add explicit fields for the padding.
+///
+/// This means that every bit pattern is a valid value, and that no field is a `bool`, `char`,
+/// enum, reference, pointer or `UnsafeCell`.
+///
+/// For the fast path and the serde path to produce the same bytes, the `Serialize` and
+/// `Deserialize` implementations must write the fields in declaration order, e.g. with
+/// `#[derive]` and without attributes like `#[serde(skip)]`. This is not needed for safety.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($ty:ty),*) => {
+        $(
+            // SAFETY: primitives have no padding, and every bit pattern is a valid value
+            unsafe impl Pod for $ty {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+// SAFETY: arrays have the layout of their elements, without padding in between
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// A value that is read by [deserialize_pod_ref], either borrowed from the input or copied out of
+/// it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PodRef<'a, T> {
+    /// The value is borrowed from the input, because it is aligned and in the native byte order.
+    Borrowed(&'a T),
+
+    /// The value was copied, because the input is not aligned for `T`, or it was read with serde.
+    Owned(T),
+}
+
+impl<T> PodRef<'_, T> {
+    /// Returns `true` if the value is borrowed from the input.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, PodRef::Borrowed(_))
+    }
+}
+
+impl<T: Copy> PodRef<'_, T> {
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T {
+        **self
+    }
+}
+
+impl<T> Deref for PodRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match self {
+            PodRef::Borrowed(value) => value,
+            PodRef::Owned(value) => value,
+        }
+    }
+}
+
+/// Returns `true` if `O` writes a `Pod` value as its in-memory representation: every int and
+/// float with its width in the native byte order, and structs without headers.
+fn is_native_layout<O: Options>() -> bool {
+    !O::Structs::COUNTED
+        && !O::Presence::PRESENCE_BITMAP
+        && !O::Format::SELF_DESCRIBING
+        && !O::TypeTags::TAGGED
+        && is_big_endian::<O>() == cfg!(target_endian = "big")
+        && PrimitiveKind::ALL.iter().all(|kind| kind.is_bulk::<O>())
+}
+
+fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    // SAFETY: `T` has no padding, so all its bytes are initialized
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+/// Serialize a [Pod] value by writing its in-memory representation with a single
+/// [write_all](CoreWrite::write_all) call, bypassing serde.
+///
+/// This fast path is taken when the options write every int and float with its width in the
+/// native byte order, e.g. `DefaultOptions::new().with_fixint_encoding()` on a little endian
+/// target. Otherwise, the value is written with [serialize], so the output is always the same
+/// as that of [serialize]. The size limit and the flush policy are honored on both paths, but
+/// `Instrument` callbacks are only made on the serde path.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize_pod_ref, serialize, serialize_pod, BufferWriter, DefaultOptions};
+/// use bincode_core::Pod;
+///
+/// #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+/// #[repr(C)]
+/// struct Sample {
+///     timestamp: u32,
+///     channels: [u16; 4],
+/// }
+///
+/// // SAFETY: `Sample` is `repr(C)`, its fields are `Pod`, and it has no padding
+/// unsafe impl Pod for Sample {}
+///
+/// let sample = Sample { timestamp: 1000, channels: [1, 2, 3, 4] };
+/// let options = DefaultOptions::new().with_fixint_encoding().with_native_endian();
+///
+/// let mut buffer = [0u8; 12];
+/// serialize_pod(&sample, BufferWriter::new(&mut buffer), options).unwrap();
+///
+/// let mut expected = [0u8; 12];
+/// serialize(&sample, BufferWriter::new(&mut expected), options).unwrap();
+/// assert_eq!(buffer, expected);
+///
+/// let decoded = deserialize_pod_ref::<Sample, _>(&buffer[..], options).unwrap();
+/// assert_eq!(*decoded, sample);
+/// ```
+pub fn serialize_pod<T: Pod + Serialize, W: CoreWrite, O: Options>(
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    serialize_native(value, bytes_of(value), writer, options)
+}
+
+/// Writes `bytes`, the in-memory representation of `value`, if `O` has the native layout, and
+/// serializes `value` otherwise.
+pub(crate) fn serialize_native<T: Serialize, W: CoreWrite, O: Options>(
+    value: &T,
+    bytes: &[u8],
+    mut writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    if !is_native_layout::<O>() {
+        return serialize(value, writer, options);
+    }
+    options
+        .limit()
+        .add(bytes.len() as u64)
+        .map_err(|_| SerializeError::LimitReached)?;
+    writer.write_all(bytes).map_err(SerializeError::Write)?;
+    if O::Flush::AUTO_FLUSH {
+        writer.flush().map_err(SerializeError::Write)?;
+    }
+    Ok(())
+}
+
+/// Deserialize a [Pod] value from `bytes`, borrowing it from the input when possible.
+///
+/// When the options write every int and float with its width in the native byte order (see
+/// [serialize_pod]), serde is bypassed: the value is borrowed if `bytes` is aligned for `T`, and
+/// copied otherwise. With other options, the value is read with [deserialize]. Either way, the
+/// size limit and the trailing bytes are checked like [deserialize] does.
+///
+/// To make sure that the value can be borrowed, read into a buffer with the alignment of `T`,
+/// e.g. a field of a `#[repr(C, align(4))]` struct, and pass the slice that starts at its start.
+pub fn deserialize_pod_ref<'a, T: Pod + Deserialize<'a>, O: Options>(
+    bytes: &'a [u8],
+    options: O,
+) -> Result<PodRef<'a, T>, DeserializeError<'a, &'a [u8]>> {
+    deserialize_native(bytes, options, |value| {
+        let ptr = value.as_ptr() as *const T;
+        if (ptr as usize) % align_of::<T>() == 0 {
+            // SAFETY: the bytes are aligned and large enough for `T`, every bit pattern is a valid
+            // `T`, and `T` has no interior mutability, so it can share the bytes with the input
+            Ok(PodRef::Borrowed(unsafe { &*ptr }))
+        } else {
+            // SAFETY: the bytes are large enough for `T`, and every bit pattern is a valid `T`
+            Ok(PodRef::Owned(unsafe { ptr.read_unaligned() }))
+        }
+    })
+}
+
+/// Passes the first `size_of::<T>()` bytes of `bytes` to `cast` if `O` has the native layout,
+/// after checking the size limit and the trailing bytes. Deserializes a `T` otherwise.
+pub(crate) fn deserialize_native<'a, T: Deserialize<'a>, O: Options>(
+    bytes: &'a [u8],
+    mut options: O,
+    cast: impl FnOnce(&'a [u8]) -> Result<PodRef<'a, T>, DeserializeError<'a, &'a [u8]>>,
+) -> Result<PodRef<'a, T>, DeserializeError<'a, &'a [u8]>> {
+    if !is_native_layout::<O>() {
+        return deserialize(bytes, options).map(PodRef::Owned);
+    }
+    let size = size_of::<T>();
+    options
+        .read_limit()
+        .add(size as u64)
+        .map_err(DeserializeError::LimitError)?;
+    if bytes.len() < size {
+        return Err(DeserializeError::UnexpectedEof {
+            needed: size - bytes.len(),
+        });
+    }
+    let (value, rest) = bytes.split_at(size);
+    O::Trailing::check_end(&rest)?;
+    cast(value)
+}
@@ -0,0 +1,76 @@
+//! The [Pod](crate::Pod) fast path for types that implement the marker traits of `bytemuck` or
+//! `zerocopy`, so drivers that already use them can adopt this crate without implementing `Pod`.
+
+use crate::config::Options;
+use crate::deserialize::DeserializeError;
+use crate::pod::{deserialize_native, serialize_native, PodRef};
+use crate::serialize::SerializeError;
+use crate::traits::{CoreWrite, SliceReadError};
+use serde::{Deserialize, Serialize};
+
+/// Serialize a `bytemuck::Pod` value like [serialize_pod](crate::serialize_pod) does, by writing
+/// its bytes when the options write every int and float with its width in the native byte order,
+/// and with serde otherwise.
+#[cfg(feature = "bytemuck")]
+pub fn serialize_bytemuck<T: bytemuck::Pod + Serialize, W: CoreWrite, O: Options>(
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    serialize_native(value, bytemuck::bytes_of(value), writer, options)
+}
+
+/// Deserialize a `bytemuck::Pod` value like [deserialize_pod_ref](crate::deserialize_pod_ref)
+/// does, borrowing it from `bytes` when they are aligned for `T`.
+#[cfg(feature = "bytemuck")]
+pub fn deserialize_bytemuck_ref<'a, T: bytemuck::Pod + Deserialize<'a>, O: Options>(
+    bytes: &'a [u8],
+    options: O,
+) -> Result<PodRef<'a, T>, DeserializeError<'a, &'a [u8]>> {
+    deserialize_native(bytes, options, |value| {
+        if let Ok(value) = bytemuck::try_from_bytes(value) {
+            return Ok(PodRef::Borrowed(value));
+        }
+        bytemuck::try_pod_read_unaligned(value)
+            .map(PodRef::Owned)
+            // Not returned, the bytes always have the size of `T`
+            .map_err(|_| DeserializeError::Read(SliceReadError::EndOfSlice))
+    })
+}
+
+/// Serialize a value that implements `zerocopy::IntoBytes` (called `AsBytes` before zerocopy 0.8)
+/// like [serialize_pod](crate::serialize_pod) does, by writing its bytes when the options write
+/// every int and float with its width in the native byte order, and with serde otherwise.
+#[cfg(feature = "zerocopy")]
+pub fn serialize_zerocopy<T, W: CoreWrite, O: Options>(
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>>
+where
+    T: zerocopy::IntoBytes + zerocopy::Immutable + Serialize,
+{
+    serialize_native(value, value.as_bytes(), writer, options)
+}
+
+/// Deserialize a value that implements `zerocopy::FromBytes` like
+/// [deserialize_pod_ref](crate::deserialize_pod_ref) does, borrowing it from `bytes` when they are
+/// aligned for `T`.
+#[cfg(feature = "zerocopy")]
+pub fn deserialize_zerocopy_ref<'a, T, O: Options>(
+    bytes: &'a [u8],
+    options: O,
+) -> Result<PodRef<'a, T>, DeserializeError<'a, &'a [u8]>>
+where
+    T: zerocopy::FromBytes + zerocopy::KnownLayout + zerocopy::Immutable + Deserialize<'a>,
+{
+    deserialize_native(bytes, options, |value| {
+        if let Ok(value) = T::ref_from_bytes(value) {
+            return Ok(PodRef::Borrowed(value));
+        }
+        T::read_from_bytes(value)
+            .map(PodRef::Owned)
+            // Not returned, the bytes always have the size of `T`
+            .map_err(|_| DeserializeError::Read(SliceReadError::EndOfSlice))
+    })
+}
@@ -0,0 +1,402 @@
+//! Probes that look at the fields of a struct before it is written, for the
+//! [PresenceBitmap](crate::config::PresenceBitmap) option encoding and the
+//! [delta](crate::delta) encoding.
+
+use crate::config::BITMAP_FIELDS;
+use serde::ser::{self, Impossible, Serialize};
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// The `Option` fields of a struct.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct Presence {
+    /// The fields that are an `Option`.
+    pub mask: u64,
+    /// The fields that are `Some`.
+    pub bits: u64,
+}
+
+impl Presence {
+    pub fn is_option(&self, index: usize) -> bool {
+        index < BITMAP_FIELDS && self.mask & (1 << index) != 0
+    }
+}
+
+/// Returns the `Option` fields of `value`, or `None` if `value` is not a struct.
+pub(crate) fn probe_struct<T: Serialize + ?Sized>(value: &T) -> Option<Presence> {
+    let mut presence = Presence::default();
+    for_each_field(value, &mut presence)?;
+    Some(presence)
+}
+
+impl FieldVisitor for Presence {
+    fn visit<T: Serialize + ?Sized>(&mut self, index: usize, value: &T) -> Result<(), NotProbed> {
+        if index < BITMAP_FIELDS {
+            if let Some(is_some) = probe_option(value) {
+                self.mask |= 1 << index;
+                self.bits |= (is_some as u64) << index;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Receives the fields of a struct from [for_each_field].
+pub(crate) trait FieldVisitor {
+    /// Visits the field at `index`. Returning an error stops [for_each_field].
+    fn visit<T: Serialize + ?Sized>(&mut self, index: usize, value: &T) -> Result<(), NotProbed>;
+}
+
+/// Passes every field of `value` to `visitor`, and returns the amount of fields. Returns `None` if
+/// `value` is not a struct, or if the visitor returned an error.
+pub(crate) fn for_each_field<T: Serialize + ?Sized, V: FieldVisitor>(
+    value: &T,
+    visitor: &mut V,
+) -> Option<usize> {
+    value.serialize(StructProbe { visitor }).ok()
+}
+
+/// A visitor that ignores every field, to count the fields of a struct.
+impl FieldVisitor for () {
+    fn visit<T: Serialize + ?Sized>(&mut self, _index: usize, _value: &T) -> Result<(), NotProbed> {
+        Ok(())
+    }
+}
+
+/// Returns `Some(true)` if `value` is `Some`, `Some(false)` if it is `None`, or `None` if `value`
+/// is not an `Option`.
+pub(crate) fn probe_option<T: Serialize + ?Sized>(value: &T) -> Option<bool> {
+    value.serialize(OptionProbe).ok()
+}
+
+/// Returns whether `value` is a struct or an enum variant with fields, which are written with their
+/// byte length with [CountedStructs](crate::config::CountedStructs).
+pub(crate) fn probe_counted<T: Serialize + ?Sized>(value: &T) -> bool {
+    value.serialize(CountedProbe).is_ok()
+}
+
+/// The probed value does not have the expected type.
+#[derive(Debug)]
+pub(crate) struct NotProbed;
+
+impl core::fmt::Display for NotProbed {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+impl ser::Error for NotProbed {
+    fn custom<T: core::fmt::Display>(_msg: T) -> Self {
+        NotProbed
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for NotProbed {}
+
+macro_rules! reject {
+    ($($method:ident($($arg:ty),*)),* $(,)?) => {
+        $(
+            fn $method(self, $(_: $arg),*) -> Result<Self::Ok, NotProbed> {
+                Err(NotProbed)
+            }
+        )*
+    };
+}
+
+macro_rules! reject_compound {
+    () => {
+        type SerializeSeq = Impossible<Self::Ok, NotProbed>;
+        type SerializeTuple = Impossible<Self::Ok, NotProbed>;
+        type SerializeTupleStruct = Impossible<Self::Ok, NotProbed>;
+        type SerializeMap = Impossible<Self::Ok, NotProbed>;
+
+        reject! {
+            serialize_bool(bool),
+            serialize_i8(i8),
+            serialize_i16(i16),
+            serialize_i32(i32),
+            serialize_i64(i64),
+            serialize_i128(i128),
+            serialize_u8(u8),
+            serialize_u16(u16),
+            serialize_u32(u32),
+            serialize_u64(u64),
+            serialize_u128(u128),
+            serialize_f32(f32),
+            serialize_f64(f64),
+            serialize_char(char),
+            serialize_str(&str),
+            serialize_bytes(&[u8]),
+            serialize_unit(),
+            serialize_unit_struct(&'static str),
+            serialize_unit_variant(&'static str, u32, &'static str),
+        }
+
+        fn serialize_newtype_struct<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _value: &T,
+        ) -> Result<Self::Ok, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_tuple_struct(
+            self,
+            _name: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleStruct, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn collect_str<T: ?Sized + core::fmt::Display>(
+            self,
+            _value: &T,
+        ) -> Result<Self::Ok, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn is_human_readable(&self) -> bool {
+            false
+        }
+    };
+}
+
+macro_rules! reject_variants {
+    () => {
+        type SerializeTupleVariant = Impossible<Self::Ok, NotProbed>;
+        type SerializeStructVariant = Impossible<Self::Ok, NotProbed>;
+
+        fn serialize_newtype_variant<T: ?Sized + Serialize>(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _value: &T,
+        ) -> Result<Self::Ok, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_tuple_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeTupleVariant, NotProbed> {
+            Err(NotProbed)
+        }
+
+        fn serialize_struct_variant(
+            self,
+            _name: &'static str,
+            _variant_index: u32,
+            _variant: &'static str,
+            _len: usize,
+        ) -> Result<Self::SerializeStructVariant, NotProbed> {
+            Err(NotProbed)
+        }
+    };
+}
+
+struct OptionProbe;
+
+impl ser::Serializer for OptionProbe {
+    type Ok = bool;
+    type Error = NotProbed;
+    type SerializeStruct = Impossible<bool, NotProbed>;
+
+    reject_compound!();
+    reject_variants!();
+
+    fn serialize_none(self) -> Result<bool, NotProbed> {
+        Ok(false)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<bool, NotProbed> {
+        Ok(true)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, NotProbed> {
+        Err(NotProbed)
+    }
+}
+
+struct StructProbe<'v, V> {
+    visitor: &'v mut V,
+}
+
+struct FieldProbe<'v, V> {
+    visitor: &'v mut V,
+    index: usize,
+}
+
+impl<'v, V: FieldVisitor> ser::Serializer for StructProbe<'v, V> {
+    type Ok = usize;
+    type Error = NotProbed;
+    type SerializeStruct = FieldProbe<'v, V>;
+
+    reject_compound!();
+    reject_variants!();
+
+    reject! {
+        serialize_none(),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<usize, NotProbed> {
+        Err(NotProbed)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<FieldProbe<'v, V>, NotProbed> {
+        Ok(FieldProbe {
+            visitor: self.visitor,
+            index: 0,
+        })
+    }
+}
+
+impl<'v, V: FieldVisitor> ser::SerializeStruct for FieldProbe<'v, V> {
+    type Ok = usize;
+    type Error = NotProbed;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        value: &T,
+    ) -> Result<(), NotProbed> {
+        self.visitor.visit(self.index, value)?;
+        self.index += 1;
+        Ok(())
+    }
+
+    fn end(self) -> Result<usize, NotProbed> {
+        Ok(self.index)
+    }
+}
+
+struct CountedProbe;
+
+impl ser::Serializer for CountedProbe {
+    type Ok = ();
+    type Error = NotProbed;
+    type SerializeStruct = SkipFields;
+    type SerializeTupleVariant = SkipFields;
+    type SerializeStructVariant = SkipFields;
+
+    reject_compound!();
+
+    reject! {
+        serialize_none(),
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<(), NotProbed> {
+        Err(NotProbed)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<SkipFields, NotProbed> {
+        Ok(SkipFields)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<(), NotProbed> {
+        Ok(())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<SkipFields, NotProbed> {
+        Ok(SkipFields)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<SkipFields, NotProbed> {
+        Ok(SkipFields)
+    }
+}
+
+/// Ignores the fields of a struct or enum variant.
+struct SkipFields;
+
+impl ser::SerializeStruct for SkipFields {
+    type Ok = ();
+    type Error = NotProbed;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        _value: &T,
+    ) -> Result<(), NotProbed> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), NotProbed> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeTupleVariant for SkipFields {
+    type Ok = ();
+    type Error = NotProbed;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, _value: &T) -> Result<(), NotProbed> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), NotProbed> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for SkipFields {
+    type Ok = ();
+    type Error = NotProbed;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        _key: &'static str,
+        _value: &T,
+    ) -> Result<(), NotProbed> {
+        Ok(())
+    }
+
+    fn end(self) -> Result<(), NotProbed> {
+        Ok(())
+    }
+}
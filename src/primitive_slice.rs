@@ -0,0 +1,430 @@
+use crate::config::{
+    BincodeByteOrder, FloatEncoding, Format, IntEncoding, Options, TypeTagEncoding,
+};
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::{DeserializeSeed, Error, SeqAccess, Visitor};
+use serde::ser::SerializeTupleStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The size of the stack buffer that the elements are converted into, and the largest chunk that
+/// is written at once.
+pub(crate) const CHUNK_BYTES: usize = 64;
+
+mod private {
+    use super::PrimitiveKind;
+    use byteorder::ByteOrder;
+
+    pub trait Sealed: Sized {
+        const KIND: PrimitiveKind;
+
+        fn read<B: ByteOrder>(bytes: &[u8]) -> Self;
+
+        fn read_into<B: ByteOrder>(bytes: &[u8], dst: &mut [Self]);
+
+        fn write_into<B: ByteOrder>(src: &[Self], bytes: &mut [u8]);
+    }
+}
+
+/// A fixed-width integer or float that can be stored in a [PrimitiveSlice].
+///
+/// This is implemented for `u16`, `u32`, `u64`, `u128`, their signed counterparts, `f32` and
+/// `f64`.
+pub trait Primitive: private::Sealed + Copy + PartialEq + fmt::Debug + Serialize + 'static {}
+
+macro_rules! impl_primitive {
+    ($($ty:ident: $kind:ident, $read:ident, $read_into:ident, $write_into:ident;)*) => {
+        /// The element type of a [PrimitiveSlice], which the serializer and deserializer recognize
+        /// by the name of the tuple struct.
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub enum PrimitiveKind {
+            $($kind,)*
+        }
+
+        impl PrimitiveKind {
+            pub(crate) const ALL: &'static [PrimitiveKind] = &[$(PrimitiveKind::$kind,)*];
+
+            pub(crate) fn name(self) -> &'static str {
+                match self {
+                    $(PrimitiveKind::$kind => concat!("$bincode_core::PrimitiveSlice<", stringify!($ty), ">"),)*
+                }
+            }
+
+            pub(crate) fn width(self) -> usize {
+                match self {
+                    $(PrimitiveKind::$kind => core::mem::size_of::<$ty>(),)*
+                }
+            }
+
+            /// Passes the elements of `bytes`, which are little endian, to `sink` one at a time.
+            pub(crate) fn for_each<S: ElementSink>(
+                self,
+                bytes: &[u8],
+                sink: &mut S,
+            ) -> Result<(), S::Error> {
+                match self {
+                    $(PrimitiveKind::$kind => {
+                        for element in bytes.chunks_exact(self.width()) {
+                            sink.element(&LittleEndian::$read(element))?;
+                        }
+                    })*
+                }
+                Ok(())
+            }
+        }
+
+        $(
+            impl private::Sealed for $ty {
+                const KIND: PrimitiveKind = PrimitiveKind::$kind;
+
+                fn read<B: ByteOrder>(bytes: &[u8]) -> Self {
+                    B::$read(bytes)
+                }
+
+                fn read_into<B: ByteOrder>(bytes: &[u8], dst: &mut [Self]) {
+                    B::$read_into(bytes, dst)
+                }
+
+                fn write_into<B: ByteOrder>(src: &[Self], bytes: &mut [u8]) {
+                    B::$write_into(src, bytes)
+                }
+            }
+
+            impl Primitive for $ty {}
+        )*
+    };
+}
+
+impl_primitive! {
+    u16: U16, read_u16, read_u16_into, write_u16_into;
+    u32: U32, read_u32, read_u32_into, write_u32_into;
+    u64: U64, read_u64, read_u64_into, write_u64_into;
+    u128: U128, read_u128, read_u128_into, write_u128_into;
+    i16: I16, read_i16, read_i16_into, write_i16_into;
+    i32: I32, read_i32, read_i32_into, write_i32_into;
+    i64: I64, read_i64, read_i64_into, write_i64_into;
+    i128: I128, read_i128, read_i128_into, write_i128_into;
+    f32: F32, read_f32, read_f32_into, write_f32_into;
+    f64: F64, read_f64, read_f64_into, write_f64_into;
+}
+
+impl PrimitiveKind {
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        if !name.starts_with("$bincode_core::PrimitiveSlice<") {
+            return None;
+        }
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    /// Returns `true` if `O` writes every element with exactly its width, so a chunk of elements
+    /// can be written and read as it is, apart from the byte order.
+    pub(crate) fn is_bulk<O: Options>(self) -> bool {
+        if O::Format::SELF_DESCRIBING || O::TypeTags::TAGGED {
+            return false;
+        }
+        let size = match self {
+            PrimitiveKind::U16 => O::IntEncoding::u16_size(0),
+            PrimitiveKind::U32 => O::IntEncoding::u32_size(0),
+            PrimitiveKind::U64 => O::IntEncoding::u64_size(0),
+            PrimitiveKind::U128 => O::IntEncoding::u128_size(0),
+            PrimitiveKind::I16 => O::IntEncoding::i16_size(0),
+            PrimitiveKind::I32 => O::IntEncoding::i32_size(0),
+            PrimitiveKind::I64 => O::IntEncoding::i64_size(0),
+            PrimitiveKind::I128 => O::IntEncoding::i128_size(0),
+            PrimitiveKind::F32 => O::Float::f32_size::<O>(0.0),
+            PrimitiveKind::F64 => O::Float::f64_size::<O>(0.0),
+        };
+        size == self.width()
+    }
+}
+
+/// Returns `true` if `O` writes ints and floats in big endian byte order.
+pub(crate) fn is_big_endian<O: Options>() -> bool {
+    <<O::Endian as BincodeByteOrder>::Endian as ByteOrder>::read_u16(&[0, 1]) == 1
+}
+
+/// Reverses the bytes of every element of `width` bytes, to convert between little and big endian.
+pub(crate) fn swap_bytes(bytes: &mut [u8], width: usize) {
+    for element in bytes.chunks_exact_mut(width) {
+        element.reverse();
+    }
+}
+
+/// Receives the elements of a chunk of a [PrimitiveSlice] one at a time, for encodings that do
+/// not write them with their width.
+pub(crate) trait ElementSink {
+    type Error;
+
+    fn element<T: Serialize>(&mut self, value: &T) -> Result<(), Self::Error>;
+}
+
+/// A slice of fixed-width integers or floats, like ADC samples, that is written and read in bulk
+/// instead of one element at a time.
+///
+/// The encoding is the same as `&[T]`: the length, followed by the elements. With an int encoding
+/// that writes every element with its width, like
+/// [with_fixint_encoding](crate::config::Options::with_fixint_encoding), the elements are
+/// converted to the configured byte order in chunks, and written with one
+/// [write_all](crate::CoreWrite::write_all) call per chunk. With other encodings, e.g. varint, the
+/// elements are written one at a time as usual.
+///
+/// A deserialized `PrimitiveSlice` borrows the bytes from the reader, and converts the elements
+/// when they are accessed, so the bytes do not have to be aligned for `T`. Use
+/// [copy_to_slice](PrimitiveSlice::copy_to_slice) to convert all elements at once. Deserializing
+/// needs an encoding that writes every element with its width, and a reader that can borrow, e.g.
+/// a slice or a [BufferedReader](crate::BufferedReader).
+///
+/// Other serde formats see a tuple struct of byte strings: the length as a little endian `u64`,
+/// followed by chunks of the little endian elements. A `PrimitiveSlice` can only be deserialized
+/// by bincode_core.
+///
+/// ```
+/// # #[macro_use] extern crate serde_derive;
+/// # use bincode_core::config::Options;
+/// # use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions, PrimitiveSlice};
+/// #[derive(Serialize, Deserialize)]
+/// struct Frame<'a> {
+///     channel: u8,
+///     #[serde(borrow)]
+///     samples: PrimitiveSlice<'a, u16>,
+/// }
+///
+/// let samples = [0x0102u16, 0x0304, 0x0506];
+/// let frame = Frame { channel: 2, samples: PrimitiveSlice::new(&samples) };
+/// let options = DefaultOptions::new().with_fixint_encoding().with_big_endian();
+///
+/// let mut buffer = [0u8; 32];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize(&frame, &mut writer, options).unwrap();
+/// assert_eq!(&writer.written_buffer()[9..], &[1, 2, 3, 4, 5, 6]);
+///
+/// let decoded: Frame = deserialize(writer.written_buffer(), options).unwrap();
+/// let mut copy = [0u16; 3];
+/// decoded.samples.copy_to_slice(&mut copy);
+/// assert_eq!(copy, samples);
+/// assert_eq!(decoded.samples.get(1), Some(0x0304));
+/// ```
+#[derive(Clone, Copy)]
+pub struct PrimitiveSlice<'a, T> {
+    repr: Repr<'a, T>,
+}
+
+#[derive(Clone, Copy)]
+enum Repr<'a, T> {
+    Slice(&'a [T]),
+    Bytes { bytes: &'a [u8], big_endian: bool },
+}
+
+impl<'a, T: Primitive> PrimitiveSlice<'a, T> {
+    /// Create a `PrimitiveSlice` that serializes the elements of `slice`.
+    pub fn new(slice: &'a [T]) -> Self {
+        PrimitiveSlice {
+            repr: Repr::Slice(slice),
+        }
+    }
+
+    /// The amount of elements.
+    pub fn len(&self) -> usize {
+        match self.repr {
+            Repr::Slice(slice) => slice.len(),
+            Repr::Bytes { bytes, .. } => bytes.len() / T::KIND.width(),
+        }
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the element at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<T> {
+        match self.repr {
+            Repr::Slice(slice) => slice.get(index).copied(),
+            Repr::Bytes { bytes, big_endian } => {
+                let width = T::KIND.width();
+                let bytes = bytes.get(index * width..(index + 1) * width)?;
+                Some(if big_endian {
+                    T::read::<BigEndian>(bytes)
+                } else {
+                    T::read::<LittleEndian>(bytes)
+                })
+            }
+        }
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        (0..self.len()).filter_map(move |index| self.get(index))
+    }
+
+    /// Copies all elements into `dst`, converting their byte order in bulk.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `dst` is not [len](PrimitiveSlice::len).
+    pub fn copy_to_slice(&self, dst: &mut [T]) {
+        assert_eq!(
+            dst.len(),
+            self.len(),
+            "destination and source slices have different lengths"
+        );
+        match self.repr {
+            Repr::Slice(slice) => dst.copy_from_slice(slice),
+            Repr::Bytes {
+                bytes,
+                big_endian: true,
+            } => T::read_into::<BigEndian>(bytes, dst),
+            Repr::Bytes { bytes, .. } => T::read_into::<LittleEndian>(bytes, dst),
+        }
+    }
+
+    /// Copies all elements into a `Vec`.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> alloc::vec::Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<'a, T: Primitive> From<&'a [T]> for PrimitiveSlice<'a, T> {
+    fn from(slice: &'a [T]) -> Self {
+        PrimitiveSlice::new(slice)
+    }
+}
+
+impl<T: Primitive> PartialEq for PrimitiveSlice<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Primitive> fmt::Debug for PrimitiveSlice<'_, T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Serializes a chunk of a [PrimitiveSlice] as a byte string.
+struct Chunk<'a>(&'a [u8]);
+
+impl Serialize for Chunk<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl<T: Primitive> Serialize for PrimitiveSlice<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let width = T::KIND.width();
+        let chunk_len = CHUNK_BYTES / width;
+        let len = self.len();
+        let mut tuple =
+            serializer.serialize_tuple_struct(T::KIND.name(), 1 + len.div_ceil(chunk_len))?;
+        tuple.serialize_field(&Chunk(&(len as u64).to_le_bytes()))?;
+        let mut buffer = [0u8; CHUNK_BYTES];
+        match self.repr {
+            Repr::Slice(slice) => {
+                for elements in slice.chunks(chunk_len) {
+                    let buffer = &mut buffer[..elements.len() * width];
+                    T::write_into::<LittleEndian>(elements, buffer);
+                    tuple.serialize_field(&Chunk(buffer))?;
+                }
+            }
+            Repr::Bytes { bytes, big_endian } => {
+                for chunk in bytes.chunks(chunk_len * width) {
+                    let buffer = &mut buffer[..chunk.len()];
+                    buffer.copy_from_slice(chunk);
+                    if big_endian {
+                        swap_bytes(buffer, width);
+                    }
+                    tuple.serialize_field(&Chunk(buffer))?;
+                }
+            }
+        }
+        tuple.end()
+    }
+}
+
+impl<'de: 'a, 'a, T: Primitive> Deserialize<'de> for PrimitiveSlice<'a, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_tuple_struct(T::KIND.name(), 2, PrimitiveSliceVisitor(PhantomData))
+    }
+}
+
+struct PrimitiveSliceVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Primitive> Visitor<'de> for PrimitiveSliceVisitor<T> {
+    type Value = PrimitiveSlice<'de, T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "the borrowed bytes of a slice of {:?}", T::KIND)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let big_endian = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let bytes: &'de [u8] = seq
+            .next_element()?
+            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        if bytes.len() % T::KIND.width() != 0 {
+            return Err(A::Error::invalid_length(bytes.len(), &self));
+        }
+        Ok(PrimitiveSlice {
+            repr: Repr::Bytes { bytes, big_endian },
+        })
+    }
+}
+
+/// Passes the borrowed bytes of a [PrimitiveSlice] from the reader to its visitor, together with
+/// the byte order that the deserializer is configured with.
+pub(crate) struct PrimitiveBytesVisitor<V> {
+    pub visitor: V,
+    pub big_endian: bool,
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for PrimitiveBytesVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_borrowed_bytes<E: Error>(self, bytes: &'de [u8]) -> Result<Self::Value, E> {
+        self.visitor.visit_seq(PrimitiveBytes {
+            big_endian: Some(self.big_endian),
+            bytes: Some(bytes),
+            error: PhantomData,
+        })
+    }
+}
+
+/// The byte order and the bytes of a [PrimitiveSlice], as a sequence of two elements.
+struct PrimitiveBytes<'de, E> {
+    big_endian: Option<bool>,
+    bytes: Option<&'de [u8]>,
+    error: PhantomData<E>,
+}
+
+impl<'de, E: Error> SeqAccess<'de> for PrimitiveBytes<'de, E> {
+    type Error = E;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, E> {
+        use serde::de::value::{BoolDeserializer, BorrowedBytesDeserializer};
+
+        if let Some(big_endian) = self.big_endian.take() {
+            return seed
+                .deserialize(BoolDeserializer::new(big_endian))
+                .map(Some);
+        }
+        match self.bytes.take() {
+            Some(bytes) => seed
+                .deserialize(BorrowedBytesDeserializer::new(bytes))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::config::Options;
+use crate::deserialize::{deserialize_with_remaining, DeserializeError};
+use core::marker::PhantomData;
+use core::task::Poll;
+use serde::de::DeserializeOwned;
+
+/// The error that is returned by a [PushDeserializer].
+///
+/// The bytes are read from the scratch buffer of the deserializer, so read errors are
+/// [SliceReadError](crate::SliceReadError)s. Running out of bytes is not an error, the deserializer waits for more bytes
+/// instead.
+pub type PushError = DeserializeError<'static, &'static [u8]>;
+
+/// A deserializer that is fed bytes as they arrive, instead of reading them from a blocking
+/// [CoreRead](crate::CoreRead).
+///
+/// The bytes are collected in a caller-provided scratch buffer. Every call to [push](Self::push)
+/// tries to decode a value from the bytes received so far, and returns [Poll::Pending] until the
+/// value is complete. This allows e.g. an interrupt-driven UART driver to feed the deserializer
+/// from its RX interrupt, without blocking.
+///
+/// Bytes after a complete value are kept for the next value. If a single `push` contains more than
+/// one value, the next one is returned by [poll](Self::poll), or by the next `push`.
+///
+/// The partial state of a decode is not kept between pushes: every attempt decodes the value from
+/// its first byte. To limit the work, no attempt is made until the bytes that the previous attempt
+/// ran out of (see [bytes_needed](DeserializeError::bytes_needed)) have arrived. A value that
+/// arrives one byte at a time is therefore decoded again for about every field or length it
+/// contains, which costs up to `fields * size` work in the interrupt that completes them. Pushing
+/// larger chunks, e.g. from a DMA idle-line interrupt, or calling [poll](Self::poll) outside of the
+/// interrupt, keeps that work out of the interrupt.
+///
+/// The size limit of the options applies to every value separately. Trailing bytes are never
+/// rejected, regardless of the configuration.
+///
+/// ```
+/// # use bincode_core::{DefaultOptions, PushDeserializer};
+/// # use core::task::Poll;
+/// let mut scratch = [0u8; 16];
+/// let mut deserializer = PushDeserializer::<(u8, u32), _>::new(&mut scratch, DefaultOptions::new());
+/// assert!(deserializer.push(&[7, 251]).is_pending());
+/// assert!(deserializer.push(&[0xE8]).is_pending());
+/// match deserializer.push(&[0x03]) {
+///     Poll::Ready(Ok(value)) => assert_eq!(value, (7, 1000)),
+///     _ => panic!(),
+/// }
+/// ```
+pub struct PushDeserializer<'buf, T, O: Options> {
+    scratch: &'buf mut [u8],
+    len: usize,
+    /// The amount of buffered bytes that the previous attempt to decode a value needed.
+    needed: usize,
+    options: O,
+    _value: PhantomData<fn() -> T>,
+}
+
+impl<'buf, T: DeserializeOwned, O: Options + Clone> PushDeserializer<'buf, T, O> {
+    /// Create a new deserializer that can hold up to `scratch.len()` bytes of a value.
+    pub fn new(scratch: &'buf mut [u8], options: O) -> Self {
+        Self {
+            scratch,
+            len: 0,
+            needed: 0,
+            options,
+            _value: PhantomData,
+        }
+    }
+
+    /// The amount of bytes that were pushed, but are not part of a decoded value yet.
+    pub fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    /// Discard all bytes that are not part of a decoded value yet, e.g. to resynchronize after
+    /// a line error.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.needed = 0;
+    }
+
+    /// Add received bytes, and try to decode a value.
+    ///
+    /// If the bytes do not fit in the scratch buffer, or the bytes are not a valid value, an error
+    /// is returned and all buffered bytes are discarded.
+    pub fn push(&mut self, bytes: &[u8]) -> Poll<Result<T, PushError>> {
+        let end = self.len + bytes.len();
+        if end > self.scratch.len() {
+            self.reset();
+            return Poll::Ready(Err(DeserializeError::ScratchTooSmall));
+        }
+        self.scratch[self.len..end].copy_from_slice(bytes);
+        self.len = end;
+        self.poll()
+    }
+
+    /// Try to decode a value from the bytes that were pushed before. This returns [Poll::Pending]
+    /// without decoding if the bytes that the previous attempt needed have not arrived yet.
+    pub fn poll(&mut self) -> Poll<Result<T, PushError>> {
+        if self.len < self.needed {
+            return Poll::Pending;
+        }
+        self.needed = 0;
+        let result = deserialize_with_remaining(&self.scratch[..self.len], self.options.clone())
+            .map(|(value, rest): (T, &[u8])| (value, rest.len()));
+        match result {
+            Ok((value, remaining)) => {
+                self.scratch.copy_within(self.len - remaining..self.len, 0);
+                self.len = remaining;
+                Poll::Ready(Ok(value))
+            }
+            Err(e) => match e.bytes_needed() {
+                Some(needed) if self.len + needed <= self.scratch.len() => {
+                    self.needed = self.len + needed;
+                    Poll::Pending
+                }
+                Some(_) => {
+                    self.len = 0;
+                    Poll::Ready(Err(DeserializeError::ScratchTooSmall))
+                }
+                None => {
+                    self.len = 0;
+                    Poll::Ready(Err(e.map_read(DeserializeError::Read)))
+                }
+            },
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use crate::traits::CoreWrite;
+use rtt_target::UpChannel;
+
+/// An RTT up-channel that a [RttWriter] writes to.
+///
+/// This is implemented for `rtt_target::UpChannel`, and for closures with the signature of
+/// `UpChannel::write`, e.g. to write to a channel of a different RTT implementation.
+pub trait RttChannel {
+    /// Write as many bytes of `bytes` as fit in the channel buffer, and return how many were
+    /// written.
+    fn write_up(&mut self, bytes: &[u8]) -> usize;
+}
+
+impl RttChannel for UpChannel {
+    fn write_up(&mut self, bytes: &[u8]) -> usize {
+        self.write(bytes)
+    }
+}
+
+impl<F: FnMut(&[u8]) -> usize> RttChannel for F {
+    fn write_up(&mut self, bytes: &[u8]) -> usize {
+        self(bytes)
+    }
+}
+
+/// An implementation of [CoreWrite] that streams the serialized bytes to the host over an RTT
+/// up-channel of the debug probe.
+///
+/// The host tool reads the channel and decodes the values with this crate, e.g. with a
+/// [BufferedReader](crate::BufferedReader) around the received bytes.
+///
+/// ```no_run
+/// # use bincode_core::{serialize, DefaultOptions, RttWriter};
+/// # use rtt_target::{rtt_init, ChannelMode};
+/// let channels = rtt_init! { up: { 0: { size: 1024, name: "Telemetry" } } };
+/// let mut channel = channels.up.0;
+/// channel.set_mode(ChannelMode::BlockIfFull);
+/// let mut writer = RttWriter::new(channel);
+/// serialize(&(1u8, 300u16), &mut writer, DefaultOptions::new()).unwrap();
+/// ```
+///
+/// When the channel is full and does not accept any bytes, [RttWriteError::ChannelFull] is
+/// returned and the value is only partially written. The host cannot find the start of the next
+/// value after that, so channels that carry serialized values should use the `BlockIfFull` mode,
+/// or frame the values so the host can resynchronize.
+///
+/// ```
+/// # use bincode_core::{serialize, DefaultOptions, RttWriter};
+/// // A fake channel with room for 4 bytes
+/// let mut channel = Vec::new();
+/// let mut writer = RttWriter::new(|bytes: &[u8]| {
+///     let len = bytes.len().min(4 - channel.len());
+///     channel.extend_from_slice(&bytes[..len]);
+///     len
+/// });
+/// serialize(&(1u8, 300u16), &mut writer, DefaultOptions::new()).unwrap();
+/// assert!(serialize(&(1u8, 300u16), &mut writer, DefaultOptions::new()).is_err());
+/// ```
+pub struct RttWriter<C> {
+    channel: C,
+}
+
+impl<C: RttChannel> RttWriter<C> {
+    /// Create a new writer that writes to the given RTT up-channel.
+    pub fn new(channel: C) -> Self {
+        Self { channel }
+    }
+
+    /// Return the channel.
+    pub fn into_inner(self) -> C {
+        self.channel
+    }
+}
+
+/// An error that is thrown when writing to a [RttWriter].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RttWriteError {
+    /// The channel buffer is full, and the host did not read it.
+    ChannelFull,
+}
+
+impl<C: RttChannel> CoreWrite for RttWriter<C> {
+    type Error = RttWriteError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn write_all(&mut self, mut val: &[u8]) -> Result<(), Self::Error> {
+        while !val.is_empty() {
+            match self.channel.write_up(val) {
+                0 => return Err(RttWriteError::ChannelFull),
+                written => val = val.get(written..).unwrap_or_default(),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<C: RttChannel> CoreWrite for &mut RttWriter<C> {
+    type Error = RttWriteError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
@@ -0,0 +1,92 @@
+use crate::backpatch::{Prefix, Reserved};
+use crate::config::{FlushPolicy, Infinite, Options, WithOtherLimit};
+use crate::serialize::{SerializeError, Serializer};
+use crate::traits::CoreWriteSeek;
+use serde::ser::{SerializeSeq, Serializer as _};
+use serde::Serialize;
+
+/// Serialize the items of an iterator as a sequence, without knowing the amount of items up front,
+/// e.g. for samples that are read from a sensor while they are written.
+///
+/// The encoding is the same as a slice of the items, so the sequence can be deserialized as e.g. a
+/// `Vec` or a `heapless::Vec`.
+///
+/// Up to `N` items are buffered on the stack first. If the iterator ends within those, the length
+/// is written before the items as usual. Otherwise the length is written with the width of the
+/// upper bound of the iterator's `size_hint` (or `N + 1`) before the items, and overwritten with
+/// the real length when the iterator ends. If the real length needs a different amount of bytes
+/// (e.g. with varint encoding), the items are moved with [CoreWriteSeek::move_tail].
+///
+/// The size limit is checked while writing. The writer is flushed after the length is written,
+/// unless the options were built with [with_manual_flush](Options::with_manual_flush).
+///
+/// ```
+/// # use bincode_core::{deserialize, serialize_samples, BufferWriter, DefaultOptions};
+/// let mut sensor = 0u16;
+/// let samples = core::iter::from_fn(|| {
+///     sensor += 100;
+///     Some(sensor).filter(|s| *s <= 500)
+/// });
+///
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize_samples::<4, _, _, _>(samples, &mut writer, DefaultOptions::new()).unwrap();
+///
+/// let decoded: [u16; 5] = deserialize(&writer.written_buffer()[1..], DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.written_buffer()[0], 5);
+/// assert_eq!(decoded, [100, 200, 300, 400, 500]);
+/// ```
+pub fn serialize_samples<const N: usize, I, W, O>(
+    samples: I,
+    mut writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>>
+where
+    I: IntoIterator,
+    I::Item: Serialize,
+    W: CoreWriteSeek,
+    O: Options,
+{
+    let mut samples = samples.into_iter().peekable();
+    let head: [Option<I::Item>; N] = core::array::from_fn(|_| samples.next());
+
+    if samples.peek().is_none() {
+        // The length is known, so the sequence is written like a slice
+        let len = head.iter().flatten().count();
+        let mut serializer = Serializer::new(writer, options);
+        let mut seq = (&mut serializer).serialize_seq(Some(len))?;
+        for sample in head.iter().flatten() {
+            seq.serialize_element(sample)?;
+        }
+        seq.end()?;
+        serializer.write_bools()?;
+        return serializer.auto_flush();
+    }
+
+    let reserved_len = samples
+        .size_hint()
+        .1
+        .and_then(|upper| upper.checked_add(N))
+        .unwrap_or(N + 1);
+    let placeholder = Prefix::seq(WithOtherLimit::new(&mut options, Infinite), reserved_len)?;
+    let reserved = Reserved::write(&mut writer, &placeholder)?;
+
+    let mut serializer = Serializer::new(writer, &mut options);
+    let mut len = 0;
+    for sample in head.iter().flatten() {
+        serializer.serialize_value(sample)?;
+        len += 1;
+    }
+    for sample in samples {
+        serializer.serialize_value(&sample)?;
+        len += 1;
+    }
+    serializer.write_bools()?;
+    let mut writer = serializer.into_writer();
+
+    reserved.patch(&mut writer, &Prefix::seq(&mut options, len)?)?;
+    if O::Flush::AUTO_FLUSH {
+        writer.flush().map_err(SerializeError::Write)?;
+    }
+    Ok(())
+}
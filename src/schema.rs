@@ -0,0 +1,730 @@
+//! Describe the layout of a serialized value.
+//!
+//! [write_schema] walks a value with a tracing serializer, and writes a compact descriptor of its
+//! layout: the order, names and types of all fields, and how many bytes each of them takes with
+//! the given options. The descriptor can be sent to a host tool, which can then decode and display
+//! messages without sharing the Rust type definitions with the firmware.
+//!
+//! serde only describes the layout of a type while serializing a value, so a sample value is
+//! needed. Sequences and maps are described by their first element, and the content of an `Option`
+//! is only known if the sample is `Some`.
+//!
+//! ### Descriptor format
+//!
+//! The descriptor is encoded with [DefaultOptions], so strings are length-prefixed and integers
+//! are varints. It consists of a single node:
+//!
+//! ```text
+//! node  := kind: u8, size: u64, body
+//! field := name: str, node
+//! ```
+//!
+//! `kind` is one of the [Kind] values and `size` is the amount of bytes that the value takes with
+//! the options that were passed to [write_schema]. The body depends on the kind:
+//!
+//! | Kind                                | Body                                             |
+//! |-------------------------------------|--------------------------------------------------|
+//! | Primitives, `Str`, `Bytes`, `Unit`  | -                                                |
+//! | `None`                              | -                                                |
+//! | `Some`                              | node                                             |
+//! | `UnitStruct`                        | name: str                                        |
+//! | `NewtypeStruct`                     | name: str, node                                  |
+//! | `Seq`                               | len: u64, node of the first element if len > 0   |
+//! | `Map`                               | len: u64, key and value node if len > 0          |
+//! | `Tuple`                             | len: u64, len nodes                              |
+//! | `TupleStruct`                       | name: str, len: u64, len nodes                   |
+//! | `Struct`                            | name: str, len: u64, len fields                  |
+//! | `UnitVariant`                       | enum: str, index: u32, variant: str              |
+//! | `NewtypeVariant`                    | enum: str, index: u32, variant: str, node        |
+//! | `TupleVariant`                      | enum: str, index: u32, variant: str, len, nodes  |
+//! | `StructVariant`                     | enum: str, index: u32, variant: str, len, fields |
+//!
+//! ```
+//! # extern crate serde_derive;
+//! # use bincode_core::schema::{write_schema, Kind};
+//! # use bincode_core::{BufferWriter, DefaultOptions};
+//! # use serde_derive::Serialize;
+//! #[derive(Serialize)]
+//! struct Reading {
+//!     sensor: u8,
+//!     value: f32,
+//! }
+//!
+//! let mut buffer = [0u8; 64];
+//! let mut writer = BufferWriter::new(&mut buffer);
+//! let sample = Reading { sensor: 1, value: 0.0 };
+//! write_schema(&sample, &mut writer, DefaultOptions::new()).unwrap();
+//!
+//! let mut expected = vec![Kind::Struct as u8, 5, 7];
+//! expected.extend_from_slice(b"Reading");
+//! expected.extend_from_slice(&[2, 6]);
+//! expected.extend_from_slice(b"sensor");
+//! expected.extend_from_slice(&[Kind::U8 as u8, 1, 5]);
+//! expected.extend_from_slice(b"value");
+//! expected.extend_from_slice(&[Kind::F32 as u8, 4]);
+//! assert_eq!(writer.written_buffer(), &expected[..]);
+//! ```
+//!
+//! ### Fingerprints
+//!
+//! [fingerprint] hashes the parts of the layout that do not depend on the sample value into a
+//! `u32`. [serialize_with_fingerprint] puts it in front of a message, so the receiver can reject
+//! a message that was written with a different version of the type, instead of decoding garbage.
+
+use crate::config::{DefaultOptions, Infinite, Options, TrailingBytes, WithOtherLimit};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{serialize_size, serialize_with_header, SerializeError, Serializer};
+use crate::traits::{CoreRead, CoreWrite};
+use serde::ser::{self, Serialize, Serializer as _};
+use serde::Deserialize;
+
+/// The kind of a node in a schema descriptor. Every kind is named after the type in the serde data
+/// model that it describes.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[allow(missing_docs)]
+pub enum Kind {
+    Bool = 0,
+    U8 = 1,
+    U16 = 2,
+    U32 = 3,
+    U64 = 4,
+    U128 = 5,
+    I8 = 6,
+    I16 = 7,
+    I32 = 8,
+    I64 = 9,
+    I128 = 10,
+    F32 = 11,
+    F64 = 12,
+    Char = 13,
+    Str = 14,
+    Bytes = 15,
+    None = 16,
+    Some = 17,
+    Unit = 18,
+    UnitStruct = 19,
+    UnitVariant = 20,
+    NewtypeStruct = 21,
+    NewtypeVariant = 22,
+    Seq = 23,
+    Tuple = 24,
+    TupleStruct = 25,
+    TupleVariant = 26,
+    Map = 27,
+    Struct = 28,
+    StructVariant = 29,
+}
+
+impl Kind {
+    /// The kind with the given tag, or `None` if the tag is unknown.
+    pub fn from_u8(tag: u8) -> Option<Kind> {
+        const KINDS: [Kind; 30] = [
+            Kind::Bool,
+            Kind::U8,
+            Kind::U16,
+            Kind::U32,
+            Kind::U64,
+            Kind::U128,
+            Kind::I8,
+            Kind::I16,
+            Kind::I32,
+            Kind::I64,
+            Kind::I128,
+            Kind::F32,
+            Kind::F64,
+            Kind::Char,
+            Kind::Str,
+            Kind::Bytes,
+            Kind::None,
+            Kind::Some,
+            Kind::Unit,
+            Kind::UnitStruct,
+            Kind::UnitVariant,
+            Kind::NewtypeStruct,
+            Kind::NewtypeVariant,
+            Kind::Seq,
+            Kind::Tuple,
+            Kind::TupleStruct,
+            Kind::TupleVariant,
+            Kind::Map,
+            Kind::Struct,
+            Kind::StructVariant,
+        ];
+        KINDS.get(tag as usize).copied()
+    }
+}
+
+/// Write the layout descriptor of `value` into `writer`. See the [module documentation](self)
+/// for the format.
+///
+/// The sizes in the descriptor are measured with `options`. The size limit of the options is
+/// ignored.
+pub fn write_schema<T: Serialize + ?Sized, W: CoreWrite, O: Options>(
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    let mut tracer = SchemaSerializer {
+        out: Serializer::new(writer, DefaultOptions::new()),
+        options,
+        size: 0,
+        fingerprint: false,
+    };
+    tracer.node(value)?;
+    tracer
+        .out
+        .into_writer()
+        .flush()
+        .map_err(SerializeError::Write)
+}
+
+/// Compute the schema fingerprint of the type of `sample`: a 32-bit FNV-1a hash of the parts of
+/// its layout that are the same for every value of the type.
+///
+/// The fingerprint covers the names and order of struct fields, the names of structs and enums,
+/// the primitive types, and the length of tuples and arrays. Like [write_schema], it is taken from
+/// a sample value, so the content of options, sequences, maps and enum variants cannot be covered:
+/// these only add their kind (and the name of the enum) to the hash. Changing the type of a field
+/// inside an `Option` or an enum variant does not change the fingerprint.
+///
+/// ```
+/// # extern crate serde_derive;
+/// # use bincode_core::schema::fingerprint;
+/// # use serde_derive::Serialize;
+/// #[derive(Serialize)]
+/// struct ReadingV1 {
+///     sensor: u8,
+///     value: f32,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct ReadingV2 {
+///     sensor: u8,
+///     value: f64,
+/// }
+///
+/// let v1 = fingerprint(&ReadingV1 { sensor: 1, value: 0.5 }).unwrap();
+/// assert_eq!(v1, fingerprint(&ReadingV1 { sensor: 2, value: 3.0 }).unwrap());
+/// assert_ne!(v1, fingerprint(&ReadingV2 { sensor: 1, value: 0.5 }).unwrap());
+/// ```
+pub fn fingerprint<T: Serialize + ?Sized>(sample: &T) -> Result<u32, SerializeError<()>> {
+    hash(sample)
+}
+
+fn hash<T: Serialize + ?Sized, E>(sample: &T) -> Result<u32, SerializeError<E>> {
+    let mut tracer = SchemaSerializer {
+        out: Serializer::new(Fnv1a(0x811c_9dc5), DefaultOptions::new()),
+        options: DefaultOptions::new(),
+        size: 0,
+        fingerprint: true,
+    };
+    tracer
+        .node(sample)
+        .map_err(|e| e.map_write(|e| match e {}))?;
+    Ok(tracer.out.into_writer().0)
+}
+
+/// Serialize `value` into `writer`, prefixed with the [fingerprint] of its type.
+///
+/// The fingerprint is written as a `[u8; 4]` of its little endian bytes, in front of the value. It
+/// counts towards the size limit.
+pub fn serialize_with_fingerprint<T: Serialize + ?Sized, W: CoreWrite, O: Options>(
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    let fingerprint = hash(value)?;
+    serialize_with_header(&fingerprint.to_le_bytes(), value, writer, options)
+}
+
+/// Deserialize a value that was written with [serialize_with_fingerprint].
+///
+/// `expected` is the [fingerprint] of `T`, taken from any value of `T` when the receiver starts. If
+/// the fingerprint in the input is different, the sender uses a different version of the type, and
+/// [DeserializeError::SchemaMismatch] is returned before any of the value is read.
+///
+/// ```
+/// # extern crate serde_derive;
+/// # use bincode_core::schema::{deserialize_with_fingerprint, fingerprint, serialize_with_fingerprint};
+/// # use bincode_core::{BufferWriter, DefaultOptions, DeserializeError};
+/// # use serde_derive::{Deserialize, Serialize};
+/// #[derive(Serialize, Deserialize, Default)]
+/// struct Reading {
+///     sensor: u8,
+///     value: f32,
+/// }
+///
+/// let mut buffer = [0u8; 16];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize_with_fingerprint(&(1u8, 0.5f32), &mut writer, DefaultOptions::new()).unwrap();
+///
+/// // The sender used a tuple instead of a `Reading`
+/// let expected = fingerprint(&Reading::default()).unwrap();
+/// let bytes = writer.written_buffer();
+/// match deserialize_with_fingerprint::<Reading, _, _>(expected, bytes, DefaultOptions::new()) {
+///     Err(DeserializeError::SchemaMismatch { .. }) => {}
+///     _ => panic!(),
+/// }
+/// ```
+pub fn deserialize_with_fingerprint<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
+    expected: u32,
+    reader: R,
+    options: O,
+) -> Result<T, DeserializeError<'a, R>> {
+    let mut deserializer = Deserializer::new(reader, options);
+    let found = u32::from_le_bytes(<[u8; 4]>::deserialize(&mut deserializer)?);
+    if found != expected {
+        return Err(DeserializeError::SchemaMismatch { expected, found });
+    }
+    let value = T::deserialize(&mut deserializer)?;
+    O::Trailing::check_end(deserializer.reader())?;
+    Ok(value)
+}
+
+/// A [CoreWrite] that computes the 32-bit FNV-1a hash of the bytes written to it.
+struct Fnv1a(u32);
+
+impl CoreWrite for Fnv1a {
+    type Error = core::convert::Infallible;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.0 = (self.0 ^ u32::from(val)).wrapping_mul(0x0100_0193);
+        Ok(())
+    }
+}
+
+struct SchemaSerializer<W: CoreWrite, O: Options> {
+    out: Serializer<W, DefaultOptions>,
+    options: O,
+    /// The size of the value that is about to be described.
+    size: usize,
+    /// Only describe the parts of the layout that are the same for every value of the type. See
+    /// [fingerprint].
+    fingerprint: bool,
+}
+
+impl<W: CoreWrite, O: Options> SchemaSerializer<W, O> {
+    fn node<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerializeError<W::Error>> {
+        if self.fingerprint {
+            return value.serialize(self);
+        }
+        let options = WithOtherLimit::new(&mut self.options, Infinite);
+        self.size = serialize_size(value, options).map_err(|e| match e {
+            SerializeError::Write(()) | SerializeError::LimitReached => {
+                SerializeError::LimitReached
+            }
+            SerializeError::SequenceMustHaveLength => SerializeError::SequenceMustHaveLength,
+            SerializeError::LengthTooLarge(len) => SerializeError::LengthTooLarge(len),
+            SerializeError::InvalidDiscriminant(idx) => SerializeError::InvalidDiscriminant(idx),
+            SerializeError::PresenceUnknown => SerializeError::PresenceUnknown,
+            SerializeError::InvalidNiche => SerializeError::InvalidNiche,
+            SerializeError::Custom => SerializeError::Custom,
+        })?;
+        value.serialize(self)
+    }
+
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.out.serialize_str(name)?;
+        self.node(value)
+    }
+
+    fn kind(&mut self, kind: Kind) -> Result<(), SerializeError<W::Error>> {
+        self.out.serialize_u8(kind as u8)?;
+        if self.fingerprint {
+            return Ok(());
+        }
+        self.out.serialize_u64(self.size as u64)
+    }
+
+    fn named(&mut self, kind: Kind, name: &str) -> Result<(), SerializeError<W::Error>> {
+        self.kind(kind)?;
+        self.out.serialize_str(name)
+    }
+
+    /// Describes an enum variant. Returns which of its children should be described.
+    fn variant(
+        &mut self,
+        kind: Kind,
+        name: &str,
+        index: u32,
+        variant: &str,
+    ) -> Result<Children, SerializeError<W::Error>> {
+        if self.fingerprint {
+            self.named(Kind::UnitVariant, name)?;
+            return Ok(Children::None);
+        }
+        self.named(kind, name)?;
+        self.out.serialize_u32(index)?;
+        self.out.serialize_str(variant)?;
+        Ok(Children::All)
+    }
+
+    /// Describes a sequence or map. Returns which of its children should be described.
+    fn collection(
+        &mut self,
+        kind: Kind,
+        len: Option<usize>,
+    ) -> Result<Children, SerializeError<W::Error>> {
+        let len = len.ok_or(SerializeError::SequenceMustHaveLength)?;
+        self.kind(kind)?;
+        if self.fingerprint {
+            return Ok(Children::None);
+        }
+        self.len(len)?;
+        Ok(Children::First)
+    }
+
+    fn len(&mut self, len: usize) -> Result<(), SerializeError<W::Error>> {
+        self.out.serialize_u64(len as u64)
+    }
+}
+
+/// The children of a compound value that are described.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Children {
+    All,
+    First,
+    None,
+}
+
+/// Describes the children of a compound value. Sequences and maps only describe their first
+/// element, tuples and structs describe every field.
+struct SchemaCompound<'a, W: CoreWrite, O: Options> {
+    tracer: &'a mut SchemaSerializer<W, O>,
+    children: Children,
+    count: usize,
+}
+
+impl<'a, W: CoreWrite, O: Options> SchemaCompound<'a, W, O> {
+    fn new(tracer: &'a mut SchemaSerializer<W, O>, children: Children) -> Self {
+        SchemaCompound {
+            tracer,
+            children,
+            count: 0,
+        }
+    }
+
+    /// Whether the current child should be described.
+    fn describe(&self) -> bool {
+        match self.children {
+            Children::All => true,
+            Children::First => self.count == 1,
+            Children::None => false,
+        }
+    }
+
+    fn element<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.count += 1;
+        if !self.describe() {
+            return Ok(());
+        }
+        self.tracer.node(value)
+    }
+
+    fn field<T: Serialize + ?Sized>(
+        &mut self,
+        name: &str,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.count += 1;
+        if !self.describe() {
+            return Ok(());
+        }
+        self.tracer.field(name, value)
+    }
+}
+
+macro_rules! impl_trace_primitive {
+    ($($method:ident($ty:ty) = $kind:ident),* $(,)?) => {
+        $(
+            fn $method(self, _: $ty) -> Result<(), SerializeError<W::Error>> {
+                self.kind(Kind::$kind)
+            }
+        )*
+    };
+}
+
+impl<'a, W: CoreWrite, O: Options> ser::Serializer for &'a mut SchemaSerializer<W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+    type SerializeSeq = SchemaCompound<'a, W, O>;
+    type SerializeTuple = SchemaCompound<'a, W, O>;
+    type SerializeTupleStruct = SchemaCompound<'a, W, O>;
+    type SerializeTupleVariant = SchemaCompound<'a, W, O>;
+    type SerializeMap = SchemaCompound<'a, W, O>;
+    type SerializeStruct = SchemaCompound<'a, W, O>;
+    type SerializeStructVariant = SchemaCompound<'a, W, O>;
+
+    impl_trace_primitive! {
+        serialize_bool(bool) = Bool,
+        serialize_u8(u8) = U8,
+        serialize_u16(u16) = U16,
+        serialize_u32(u32) = U32,
+        serialize_u64(u64) = U64,
+        serialize_u128(u128) = U128,
+        serialize_i8(i8) = I8,
+        serialize_i16(i16) = I16,
+        serialize_i32(i32) = I32,
+        serialize_i64(i64) = I64,
+        serialize_i128(i128) = I128,
+        serialize_f32(f32) = F32,
+        serialize_f64(f64) = F64,
+        serialize_char(char) = Char,
+        serialize_str(&str) = Str,
+        serialize_bytes(&[u8]) = Bytes,
+    }
+
+    fn serialize_none(self) -> Result<(), SerializeError<W::Error>> {
+        if self.fingerprint {
+            return self.kind(Kind::Some);
+        }
+        self.kind(Kind::None)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(
+        self,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.kind(Kind::Some)?;
+        if self.fingerprint {
+            return Ok(());
+        }
+        self.node(value)
+    }
+
+    fn serialize_unit(self) -> Result<(), SerializeError<W::Error>> {
+        self.kind(Kind::Unit)
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), SerializeError<W::Error>> {
+        self.named(Kind::UnitStruct, name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.variant(Kind::UnitVariant, name, variant_index, variant)?;
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        self.named(Kind::NewtypeStruct, name)?;
+        self.node(value)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        match self.variant(Kind::NewtypeVariant, name, variant_index, variant)? {
+            Children::None => Ok(()),
+            _ => self.node(value),
+        }
+    }
+
+    fn serialize_seq(
+        self,
+        len: Option<usize>,
+    ) -> Result<Self::SerializeSeq, SerializeError<W::Error>> {
+        let children = self.collection(Kind::Seq, len)?;
+        Ok(SchemaCompound::new(self, children))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, SerializeError<W::Error>> {
+        self.kind(Kind::Tuple)?;
+        self.len(len)?;
+        Ok(SchemaCompound::new(self, Children::All))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerializeError<W::Error>> {
+        self.named(Kind::TupleStruct, name)?;
+        self.len(len)?;
+        Ok(SchemaCompound::new(self, Children::All))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerializeError<W::Error>> {
+        let children = self.variant(Kind::TupleVariant, name, variant_index, variant)?;
+        if children != Children::None {
+            self.len(len)?;
+        }
+        Ok(SchemaCompound::new(self, children))
+    }
+
+    fn serialize_map(
+        self,
+        len: Option<usize>,
+    ) -> Result<Self::SerializeMap, SerializeError<W::Error>> {
+        let children = self.collection(Kind::Map, len)?;
+        Ok(SchemaCompound::new(self, children))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, SerializeError<W::Error>> {
+        self.named(Kind::Struct, name)?;
+        self.len(len)?;
+        Ok(SchemaCompound::new(self, Children::All))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerializeError<W::Error>> {
+        let children = self.variant(Kind::StructVariant, name, variant_index, variant)?;
+        if children != Children::None {
+            self.len(len)?;
+        }
+        Ok(SchemaCompound::new(self, children))
+    }
+
+    fn collect_str<T: ?Sized + core::fmt::Display>(
+        self,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.kind(Kind::Str)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeSeq for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeTuple for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeTupleStruct for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeTupleVariant for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeMap for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.element(key)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        if !self.describe() {
+            return Ok(());
+        }
+        self.tracer.node(value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeStruct for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, O: Options> ser::SerializeStructVariant for SchemaCompound<'_, W, O> {
+    type Ok = ();
+    type Error = SerializeError<W::Error>;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
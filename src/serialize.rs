@@ -1,8 +1,23 @@
 use super::*;
-use config::{BincodeByteOrder, IntEncoding, Options};
-use serde::{ser::*, serde_if_integer128};
-
-#[cfg(feature = "std")]
+use backpatch::{Prefix, Reserved};
+use config::{
+    bitmap_len, BincodeByteOrder, BoolEncoding, Bounded, CharEncoding, DiscriminantEncoding,
+    FloatEncoding, FlushPolicy, Format, Infinite, IntEncoding, OptionEncoding, Options, SizeLimit,
+    StructEncoding, TypeTag, TypeTagEncoding, UsizeEncoding, WithOtherLimit,
+};
+use core::convert::TryFrom;
+use formats::Header;
+use presence::{probe_counted, probe_struct, Presence};
+use primitive_slice::{ElementSink, PrimitiveKind, CHUNK_BYTES};
+use serde::ser::*;
+use size_checker::struct_body_len;
+
+#[cfg(feature = "instrument")]
+use config::Instrument;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
 use std::error::Error as StdError;
 
 /// Serialize a given `T` type into a given `CoreWrite` writer with the given `B` byte order.
@@ -16,19 +31,104 @@ use std::error::Error as StdError;
 /// - BigEndian
 /// - LittleEndian
 /// - NetworkEndian.
+///
+/// If the value would take more bytes than allowed by the size limit,
+/// [SerializeError::LimitReached] is returned and no bytes are written to the writer.
+///
+/// The writer is flushed after the value is written, unless the options were built with
+/// [with_manual_flush](config::Options::with_manual_flush).
 pub fn serialize<T: serde::Serialize + ?Sized, W: CoreWrite, O: Options>(
     value: &T,
     writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    if let Some(limit) = options.limit().limit() {
+        // Measure the value first so nothing is written when the limit would be exceeded.
+        let measure_options = WithOtherLimit::new(&mut options, Bounded(limit));
+        if let Err(SerializeError::LimitReached) = serialize_size(value, measure_options) {
+            return Err(SerializeError::LimitReached);
+        }
+    }
+    let mut serializer = Serializer::new(writer, options);
+    serializer.serialize_value(value)?;
+    serializer.write_bools()?;
+    serializer.auto_flush()
+}
+
+/// Serialize every item of `values` back-to-back into the given writer, without a length prefix.
+///
+/// The items can be read back with [deserialize_iter](crate::deserialize_iter). The size limit
+/// applies to all items combined, and is checked while writing. The writer is flushed after the
+/// last item is written, unless the options were built with
+/// [with_manual_flush](config::Options::with_manual_flush).
+///
+/// ```
+/// # use bincode_core::{serialize_all, BufferWriter, DefaultOptions};
+/// let mut buffer = [0u8; 8];
+/// let mut writer = BufferWriter::new(&mut buffer);
+/// serialize_all([1u8, 2, 3], &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.written_buffer(), &[1, 2, 3]);
+/// ```
+pub fn serialize_all<I, W: CoreWrite, O: Options>(
+    values: I,
+    writer: W,
     options: O,
-) -> Result<(), SerializeError<W>> {
-    let mut serializer = Serializer::<W, O> {
-        writer,
-        _options: options,
-    };
-    value.serialize(&mut serializer)
+) -> Result<(), SerializeError<W::Error>>
+where
+    I: IntoIterator,
+    I::Item: serde::Serialize,
+{
+    let mut serializer = Serializer::new(writer, options);
+    for value in values {
+        serializer.serialize_value(&value)?;
+        serializer.write_bools()?;
+    }
+    serializer.auto_flush()
+}
+
+/// Serialize `header` and then `value` into the given writer, as two separate values, like two
+/// calls to [serialize]. Unlike a `(header, value)` tuple, this does not add the framing of a tuple
+/// with [TypeTags](config::TypeTags) or a self-describing format, so the header can be read on its
+/// own. The size limit applies to both values combined, and is checked before anything is written.
+pub(crate) fn serialize_with_header<H, T, W, O>(
+    header: &H,
+    value: &T,
+    writer: W,
+    mut options: O,
+) -> Result<(), SerializeError<W::Error>>
+where
+    H: serde::Serialize + ?Sized,
+    T: serde::Serialize + ?Sized,
+    W: CoreWrite,
+    O: Options,
+{
+    if let Some(limit) = options.limit().limit() {
+        let measure_options = WithOtherLimit::new(&mut options, Bounded(limit));
+        match serialize_size(header, measure_options) {
+            Ok(len) => {
+                let rest = limit.saturating_sub(len as u64);
+                let measure_options = WithOtherLimit::new(&mut options, Bounded(rest));
+                if let Err(SerializeError::LimitReached) = serialize_size(value, measure_options) {
+                    return Err(SerializeError::LimitReached);
+                }
+            }
+            Err(SerializeError::LimitReached) => return Err(SerializeError::LimitReached),
+            Err(_) => {}
+        }
+    }
+    let mut serializer = Serializer::new(writer, options);
+    serializer.serialize_value(header)?;
+    serializer.write_bools()?;
+    serializer.serialize_value(value)?;
+    serializer.write_bools()?;
+    serializer.auto_flush()
 }
 
 /// Return the size that serializing a given `T` type would need to be stored. This is an optimized version of getting the length of the writer after it's done writing.
+///
+/// If the configured size limit is exceeded, [SerializeError::LimitReached] is returned as soon as
+/// the measured size goes over the limit. This can be used to check if a message fits in a
+/// transport before serializing it.
 /// ```
 /// # use bincode_core::*;
 /// let mut buffer = [0u8; 1000];
@@ -49,81 +149,568 @@ pub fn serialize_size<T: serde::Serialize + ?Sized, O: Options>(
     value: &T,
     options: O,
 ) -> Result<usize, SerializeError<()>> {
-    let mut size_checker = crate::size_checker::SizeChecker { options, total: 0 };
-    value.serialize(&mut size_checker)?;
+    if O::Format::SELF_DESCRIBING {
+        let mut serializer = Serializer::new(CountingWriter::new(), options);
+        serializer.serialize_value(value)?;
+        return Ok(serializer.writer().count());
+    }
+    let mut size_checker = crate::size_checker::SizeChecker::new(options);
+    size_checker.serialize_value(value)?;
     Ok(size_checker.total)
 }
 
-/// Any error that can be thrown while serializing a type
-pub enum SerializeError<W: CoreWrite + ?Sized> {
+/// Any error that can be thrown while serializing a type.
+///
+/// `E` is the error of the writer, so errors of writers with the same error type can be stored
+/// and handled together. [SerializeErrorFor] names the error for a writer type.
+pub enum SerializeError<E> {
     /// Generic write error. See the inner `CoreWrite::Error` for more info
-    Write(W::Error),
+    Write(E),
 
-    /// A sequence (e.g. `&str` or `&[u8]`) was requested to serialize, but it has no length.
+    /// A sequence (e.g. `&str` or `&[u8]`) was requested to serialize, but it has no length. Wrap
+    /// iterators that do not know their length in an [IterSeq](crate::IterSeq).
     SequenceMustHaveLength,
+
+    /// An enum variant index does not fit in the configured discriminant encoding.
+    InvalidDiscriminant(u32),
+
+    /// The configured size limit was reached while serializing.
+    LimitReached,
+
+    /// A length does not fit in a u32, and lengths are encoded with
+    /// [U32Usize](config::U32Usize).
+    LengthTooLarge(usize),
+
+    /// A struct was serialized with [PresenceBitmap](config::PresenceBitmap) or
+    /// [CountedStructs](config::CountedStructs), without going through
+    /// [Serializer::serialize_value].
+    PresenceUnknown,
+
+    /// An `Option` was serialized with [NicheOptions](config::NicheOptions), but its value starts
+    /// with a zero byte or is empty, so it would be read back as `None`.
+    InvalidNiche,
+
+    /// The `Serialize` implementation returned an error, or a `Display` implementation failed in
+    /// `collect_str`. The message of the error is discarded, because it cannot be stored without
+    /// allocating.
+    Custom,
+}
+
+/// The [SerializeError] of serializing into the writer `W`.
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, BufferWriterError, DefaultOptions};
+/// # use bincode_core::{SerializeError, SerializeErrorFor};
+/// fn encode(value: u32, buffer: &mut [u8]) -> Result<(), SerializeErrorFor<BufferWriter<'_>>> {
+///     serialize(&value, BufferWriter::new(buffer), DefaultOptions::new())
+/// }
+///
+/// let err: SerializeError<BufferWriterError> = encode(1000, &mut [0u8; 1]).unwrap_err();
+/// assert!(matches!(err, SerializeError::Write(BufferWriterError::BufferTooSmall)));
+/// ```
+pub type SerializeErrorFor<W> = SerializeError<<W as CoreWrite>::Error>;
+
+impl<E> SerializeError<E> {
+    /// The category of the error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            SerializeError::Write(_) => ErrorKind::Write,
+            SerializeError::InvalidDiscriminant(_) | SerializeError::InvalidNiche => {
+                ErrorKind::InvalidValue
+            }
+            SerializeError::LimitReached | SerializeError::LengthTooLarge(_) => {
+                ErrorKind::LimitExceeded
+            }
+            SerializeError::SequenceMustHaveLength | SerializeError::PresenceUnknown => {
+                ErrorKind::Unsupported
+            }
+            SerializeError::Custom => ErrorKind::Custom,
+        }
+    }
+
+    /// Converts the error to an error of a different writer, with `f` for write errors.
+    pub(crate) fn map_write<E2>(self, f: impl FnOnce(E) -> E2) -> SerializeError<E2> {
+        match self {
+            SerializeError::Write(e) => SerializeError::Write(f(e)),
+            SerializeError::SequenceMustHaveLength => SerializeError::SequenceMustHaveLength,
+            SerializeError::InvalidDiscriminant(idx) => SerializeError::InvalidDiscriminant(idx),
+            SerializeError::LimitReached => SerializeError::LimitReached,
+            SerializeError::LengthTooLarge(len) => SerializeError::LengthTooLarge(len),
+            SerializeError::PresenceUnknown => SerializeError::PresenceUnknown,
+            SerializeError::InvalidNiche => SerializeError::InvalidNiche,
+            SerializeError::Custom => SerializeError::Custom,
+        }
+    }
 }
 
-impl<W: CoreWrite> core::fmt::Debug for SerializeError<W> {
+impl<E: core::fmt::Debug> core::fmt::Debug for SerializeError<E> {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             SerializeError::Write(w) => write!(fmt, "Write error {:?}", w),
             SerializeError::SequenceMustHaveLength => write!(fmt, "Sequence does not have length"),
+            SerializeError::InvalidDiscriminant(idx) => write!(
+                fmt,
+                "Variant index {} does not fit in the configured discriminant encoding",
+                idx
+            ),
+            SerializeError::LimitReached => write!(fmt, "Limit reached"),
+            SerializeError::LengthTooLarge(len) => {
+                write!(fmt, "Length {} does not fit in the configured usize", len)
+            }
+            SerializeError::PresenceUnknown => {
+                write!(
+                    fmt,
+                    "Struct presence bitmap or length is unknown, use serialize_value"
+                )
+            }
+            SerializeError::InvalidNiche => {
+                write!(fmt, "Option value starts with a zero byte or is empty")
+            }
+            SerializeError::Custom => write!(fmt, "Custom error thrown"),
         }
     }
 }
 
-impl<W: CoreWrite> core::fmt::Display for SerializeError<W> {
+impl<E: core::fmt::Debug> core::fmt::Display for SerializeError<E> {
     fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
         core::fmt::Debug::fmt(self, fmt)
     }
 }
 
-impl<W: CoreWrite> serde::ser::Error for SerializeError<W> {
+impl<E: core::fmt::Debug> serde::ser::Error for SerializeError<E> {
     fn custom<T: core::fmt::Display>(_cause: T) -> Self {
-        // Custom errors not supported
-        panic!("Custom error: {}", _cause);
+        SerializeError::Custom
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl<E: core::fmt::Debug> StdError for SerializeError<E> {}
+
+/// Returns the length of the formatted `value`, without storing it.
+pub(crate) fn str_len<T, E>(value: &T) -> Result<usize, SerializeError<E>>
+where
+    T: core::fmt::Display + ?Sized,
+{
+    struct Len(usize);
+
+    impl core::fmt::Write for Len {
+        fn write_str(&mut self, s: &str) -> core::fmt::Result {
+            self.0 += s.len();
+            Ok(())
+        }
     }
+
+    let mut len = Len(0);
+    core::fmt::write(&mut len, format_args!("{}", value)).map_err(|_| SerializeError::Custom)?;
+    Ok(len.0)
+}
+
+/// Writes formatted text as the bytes of a string, after its length was written.
+struct StrWriter<'a, W: CoreWrite, O: Options> {
+    ser: &'a mut Serializer<W, O>,
+    /// The amount of bytes that were counted by [str_len], but are not written yet.
+    remaining: usize,
+    error: Option<SerializeError<W::Error>>,
 }
 
-#[cfg(feature = "std")]
-impl<W: CoreWrite> StdError for SerializeError<W> {}
+impl<W: CoreWrite, O: Options> core::fmt::Write for StrWriter<'_, W, O> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        // A `Display` implementation that writes something else the second time would corrupt
+        // the output
+        if s.len() > self.remaining {
+            return Err(core::fmt::Error);
+        }
+        self.remaining -= s.len();
+        self.ser.serialize_raw(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            core::fmt::Error
+        })
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for SerializeError<E> {
+    fn format(&self, fmt: defmt::Formatter) {
+        match self {
+            SerializeError::Write(w) => defmt::write!(fmt, "Write error {}", w),
+            SerializeError::SequenceMustHaveLength => {
+                defmt::write!(fmt, "Sequence does not have length")
+            }
+            SerializeError::InvalidDiscriminant(idx) => defmt::write!(
+                fmt,
+                "Variant index {} does not fit in the configured discriminant encoding",
+                idx
+            ),
+            SerializeError::LimitReached => defmt::write!(fmt, "Limit reached"),
+            SerializeError::LengthTooLarge(len) => {
+                defmt::write!(fmt, "Length {} does not fit in the configured usize", len)
+            }
+            SerializeError::PresenceUnknown => {
+                defmt::write!(
+                    fmt,
+                    "Struct presence bitmap or length is unknown, use serialize_value"
+                )
+            }
+            SerializeError::InvalidNiche => {
+                defmt::write!(fmt, "Option value starts with a zero byte or is empty")
+            }
+            SerializeError::Custom => defmt::write!(fmt, "Custom error thrown"),
+        }
+    }
+}
 
 /// A serializer that can serialize any value that implements `serde::Serialize` into a given
 /// [CoreWrite] writer.
 pub struct Serializer<W: CoreWrite, O: Options> {
     writer: W,
-    _options: O,
+    options: O,
+    /// The `Option` fields of the struct that is about to be serialized.
+    presence: Option<Presence>,
+    /// The next `Option` is a struct field that is covered by the presence bitmap.
+    skip_option_tag: bool,
+    /// The byte length of the fields of the struct or enum variant that is about to be serialized,
+    /// with
+    /// [CountedStructs](config::CountedStructs) and a writer that cannot seek.
+    struct_len: Option<usize>,
+    /// The next byte is the first byte of a niche encoded `Option`, which must not be zero.
+    niche: bool,
+    /// The packed bools that have not been written yet.
+    bool_bits: u8,
+    /// The amount of bits in `bool_bits`.
+    bool_count: u8,
+    /// The element type of the [PrimitiveSlice](crate::PrimitiveSlice) whose fields are being
+    /// serialized, and whether its length was written.
+    primitive: Option<(PrimitiveKind, bool)>,
 }
 
 macro_rules! impl_serialize_literal {
     ($ser_method:ident($ty:ty) = $write:ident()) => {
-        pub(crate) fn $ser_method(&mut self, v: $ty) -> Result<(), SerializeError<W>> {
+        pub(crate) fn $ser_method(&mut self, v: $ty) -> Result<(), SerializeError<W::Error>> {
             const LEN: usize = core::mem::size_of::<$ty>();
 
             let mut buf = [0u8; LEN];
             <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::$write(&mut buf, v);
-            self.writer.write_all(&buf).map_err(SerializeError::Write)
+            self.serialize_raw(&buf)
         }
     };
 }
 
 impl<W: CoreWrite, O: Options> Serializer<W, O> {
-    pub(crate) fn serialize_byte(&mut self, v: u8) -> Result<(), SerializeError<W>> {
-        self.writer.write(v).map_err(SerializeError::Write)
+    /// Create a new serializer that writes to `writer`.
+    ///
+    /// This can be used to write several values to the same writer, or to mix serialized values
+    /// with bytes that are written directly to [writer_mut](Serializer::writer_mut). Unlike
+    /// [serialize], the size limit is only checked while writing, and the writer is only flushed by
+    /// [finish](Serializer::finish).
+    ///
+    /// ```
+    /// # use bincode_core::{BufferWriter, CoreWrite, DefaultOptions, Serializer};
+    /// # use serde::Serialize;
+    /// let mut buffer = [0u8; 8];
+    /// let mut serializer = Serializer::new(BufferWriter::new(&mut buffer), DefaultOptions::new());
+    ///
+    /// 1u8.serialize(&mut serializer).unwrap();
+    /// serializer.writer_mut().write(0xFF).unwrap();
+    /// 2u8.serialize(&mut serializer).unwrap();
+    ///
+    /// assert_eq!(serializer.writer().written_buffer(), &[1, 0xFF, 2]);
+    /// ```
+    pub fn new(writer: W, options: O) -> Self {
+        Serializer {
+            writer,
+            options,
+            presence: None,
+            skip_option_tag: false,
+            struct_len: None,
+            niche: false,
+            bool_bits: 0,
+            bool_count: 0,
+            primitive: None,
+        }
+    }
+
+    /// A reference to the inner writer.
+    pub fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    /// A mutable reference to the inner writer. Bytes written directly to the writer do not count
+    /// towards the size limit.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Return the inner writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Serialize a single value.
+    ///
+    /// This is the same as `value.serialize(&mut serializer)`, except that it also works with
+    /// [with_presence_bitmap](config::Options::with_presence_bitmap), which needs to look at the
+    /// fields of a struct before it is written.
+    pub fn serialize_value<T: Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<W::Error>> {
+        if O::Format::SELF_DESCRIBING {
+            return value.serialize(self);
+        }
+        if O::Presence::PRESENCE_BITMAP {
+            self.presence = probe_struct(value);
+        }
+        if O::Structs::COUNTED
+            && !O::TypeTags::TAGGED
+            && self.writer.as_seek().is_none()
+            && probe_counted(value)
+        {
+            self.struct_len = struct_body_len(value, &mut self.options);
+        }
+        value.serialize(self)
+    }
+
+    /// Writes the type tag of the next value, if the options use [TypeTags](config::TypeTags).
+    fn serialize_type_tag(&mut self, tag: TypeTag) -> Result<(), SerializeError<W::Error>> {
+        if O::TypeTags::TAGGED {
+            self.serialize_byte(tag as u8)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the type tag and the discriminant of an enum variant.
+    fn serialize_variant(&mut self, variant_index: u32) -> Result<(), SerializeError<W::Error>> {
+        self.serialize_type_tag(TypeTag::Enum)?;
+        O::Discriminant::serialize_discriminant(self, variant_index)
+    }
+
+    /// Writes the type tag and the length of a tuple or struct, which are only written with
+    /// [TypeTags](config::TypeTags).
+    fn serialize_tuple_len(&mut self, len: usize) -> Result<(), SerializeError<W::Error>> {
+        if O::TypeTags::TAGGED {
+            self.serialize_byte(TypeTag::Seq as u8)?;
+            O::Usize::serialize_len(self, len)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the field count and the byte length of a struct or enum variant, with
+    /// [CountedStructs](config::CountedStructs). If the writer can seek, the byte length is a
+    /// placeholder that [end_struct](Serializer::end_struct) overwrites.
+    fn serialize_struct_header(
+        &mut self,
+        len: usize,
+    ) -> Result<Option<Reserved>, SerializeError<W::Error>> {
+        if !O::Structs::COUNTED || O::TypeTags::TAGGED {
+            return Ok(None);
+        }
+        let body = self.struct_len.take();
+        if body.is_none() && self.writer.as_seek().is_none() {
+            return Err(SerializeError::PresenceUnknown);
+        }
+        O::Usize::serialize_len(self, len)?;
+        if let Some(body) = body {
+            O::Usize::serialize_len(self, body)?;
+            return Ok(None);
+        }
+        let max_len = self
+            .options
+            .limit()
+            .limit()
+            .map_or(0, |limit| usize::try_from(limit).unwrap_or(usize::MAX));
+        let placeholder = Prefix::len(WithOtherLimit::new(&mut self.options, Infinite), max_len)?;
+        let writer = self
+            .writer
+            .as_seek()
+            .ok_or(SerializeError::PresenceUnknown)?;
+        Reserved::write(writer, &placeholder).map(Some)
+    }
+
+    /// Ends a struct or enum variant. With [CountedStructs](config::CountedStructs), the pending
+    /// bools are part of the struct, and the placeholder of [serialize_struct_header](Serializer::serialize_struct_header)
+    /// is overwritten with the byte length of the fields.
+    fn end_struct(&mut self, reserved: Option<Reserved>) -> Result<(), SerializeError<W::Error>> {
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            self.write_bools()?;
+            if let Some(reserved) = reserved {
+                let writer = self
+                    .writer
+                    .as_seek()
+                    .ok_or(SerializeError::PresenceUnknown)?;
+                let len = writer.position() - reserved.end();
+                reserved.patch(writer, &Prefix::len(&mut self.options, len)?)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the tag of an `Option`, unless it is covered by a presence bitmap.
+    fn serialize_option_tag(&mut self, tag: u8) -> Result<(), SerializeError<W::Error>> {
+        if core::mem::take(&mut self.skip_option_tag) {
+            return Ok(());
+        }
+        self.serialize_byte(tag)
+    }
+
+    /// Flush the inner writer and return it.
+    ///
+    /// This flushes regardless of the flush policy in the options, so the tail of the last value
+    /// is not left in a buffered writer.
+    ///
+    /// ```
+    /// # use bincode_core::{BufferWriter, ChunkWriter, DefaultOptions, Serializer};
+    /// # use serde::Serialize;
+    /// let mut buffer = [0u8; 8];
+    /// let mut chunk = [0u8; 4];
+    /// let writer = ChunkWriter::new(BufferWriter::new(&mut buffer), &mut chunk);
+    /// let mut serializer = Serializer::new(writer, DefaultOptions::new());
+    ///
+    /// (1u8, 2u8).serialize(&mut serializer).unwrap();
+    /// assert_eq!(serializer.writer().buffered_len(), 2);
+    ///
+    /// let writer = serializer.finish().unwrap();
+    /// assert_eq!(writer.buffered_len(), 0);
+    /// assert_eq!(writer.into_inner().written_buffer(), &[1, 2]);
+    /// ```
+    pub fn finish(mut self) -> Result<W, SerializeError<W::Error>> {
+        self.write_bools()?;
+        self.writer.flush().map_err(SerializeError::Write)?;
+        Ok(self.writer)
+    }
+
+    pub(crate) fn auto_flush(&mut self) -> Result<(), SerializeError<W::Error>> {
+        if O::Flush::AUTO_FLUSH {
+            self.writer.flush().map_err(SerializeError::Write)?;
+        }
+        Ok(())
+    }
+
+    /// Rejects the first byte of a niche encoded `Option` if it is zero, which reads as `None`.
+    fn check_niche(&mut self, first: u8) -> Result<(), SerializeError<W::Error>> {
+        if core::mem::take(&mut self.niche) && first == 0 {
+            return Err(SerializeError::InvalidNiche);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn serialize_byte(&mut self, v: u8) -> Result<(), SerializeError<W::Error>> {
+        self.check_niche(v)?;
+        self.write_bools()?;
+        self.write_bytes(1)?;
+        self.writer.write(v).map_err(SerializeError::Write)?;
+        #[cfg(feature = "instrument")]
+        self.options.instrument().on_bytes_written(1);
+        Ok(())
+    }
+
+    pub(crate) fn serialize_raw(&mut self, v: &[u8]) -> Result<(), SerializeError<W::Error>> {
+        if let Some(&first) = v.first() {
+            self.check_niche(first)?;
+        }
+        self.write_bools()?;
+        self.write_bytes(v.len() as u64)?;
+        self.writer.write_all(v).map_err(SerializeError::Write)?;
+        #[cfg(feature = "instrument")]
+        self.options.instrument().on_bytes_written(v.len());
+        Ok(())
+    }
+
+    /// Adds a bool to the pending byte of packed bools.
+    fn serialize_packed_bool(&mut self, v: bool) -> Result<(), SerializeError<W::Error>> {
+        if self.niche {
+            return Err(SerializeError::InvalidNiche);
+        }
+        if self.bool_count == 0 {
+            // The byte counts towards the limit as soon as it is started
+            self.write_bytes(1)?;
+        }
+        self.bool_bits |= (v as u8) << self.bool_count;
+        self.bool_count += 1;
+        if self.bool_count == 8 {
+            self.write_bools()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the pending byte of packed bools, if any.
+    pub(crate) fn write_bools(&mut self) -> Result<(), SerializeError<W::Error>> {
+        if self.bool_count == 0 {
+            return Ok(());
+        }
+        let bits = core::mem::take(&mut self.bool_bits);
+        self.bool_count = 0;
+        self.writer.write(bits).map_err(SerializeError::Write)?;
+        #[cfg(feature = "instrument")]
+        self.options.instrument().on_bytes_written(1);
+        Ok(())
+    }
+
+    /// Writes a field of a [PrimitiveSlice](crate::PrimitiveSlice). The first field is the length
+    /// of the slice, which is written like the length of a sequence. The other fields are chunks
+    /// of little endian elements, which are converted to the configured byte order in bulk if
+    /// every element is written with its width.
+    fn serialize_primitive_field(
+        &mut self,
+        kind: PrimitiveKind,
+        len_written: bool,
+        v: &[u8],
+    ) -> Result<(), SerializeError<W::Error>> {
+        if !len_written {
+            let len = <[u8; 8]>::try_from(v).map_err(|_| SerializeError::Custom)?;
+            let len =
+                usize::try_from(u64::from_le_bytes(len)).map_err(|_| SerializeError::Custom)?;
+            self.primitive = Some((kind, true));
+            serde::Serializer::serialize_seq(&mut *self, Some(len))?;
+            return Ok(());
+        }
+        if v.len() % kind.width() != 0 {
+            return Err(SerializeError::Custom);
+        }
+        if !kind.is_bulk::<O>() {
+            return kind.for_each(v, self);
+        }
+        if !primitive_slice::is_big_endian::<O>() {
+            return self.serialize_raw(v);
+        }
+        let mut buffer = [0u8; CHUNK_BYTES];
+        for chunk in v.chunks(CHUNK_BYTES) {
+            let buffer = &mut buffer[..chunk.len()];
+            buffer.copy_from_slice(chunk);
+            primitive_slice::swap_bytes(buffer, kind.width());
+            self.serialize_raw(buffer)?;
+        }
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, count: u64) -> Result<(), SerializeError<W::Error>> {
+        self.options
+            .limit()
+            .add(count)
+            .map_err(|_| SerializeError::LimitReached)
     }
 
     impl_serialize_literal! {serialize_literal_u16(u16) = write_u16()}
     impl_serialize_literal! {serialize_literal_u32(u32) = write_u32()}
     impl_serialize_literal! {serialize_literal_u64(u64) = write_u64()}
 
-    serde_if_integer128! {
-        impl_serialize_literal!{serialize_literal_u128(u128) = write_u128()}
+    impl_serialize_literal! {serialize_literal_u128(u128) = write_u128()}
+}
+
+impl<W: CoreWrite, O: Options> ElementSink for Serializer<W, O> {
+    type Error = SerializeError<W::Error>;
+
+    fn element<T: Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.serialize_value(value)
     }
 }
 
 macro_rules! impl_serialize_int {
-    ($ser_method:ident($ty:ty) = $ser_int:ident()) => {
+    ($ser_method:ident($ty:ty) = $ser_int:ident($tag:ident), $format:ident($format_v:expr)) => {
         fn $ser_method(self, v: $ty) -> Result<Self::Ok, Self::Error> {
+            if O::Format::SELF_DESCRIBING {
+                return self.$format($format_v(v));
+            }
+            self.serialize_type_tag(TypeTag::$tag)?;
             O::IntEncoding::$ser_int(self, v)
         }
     };
@@ -131,7 +718,7 @@ macro_rules! impl_serialize_int {
 
 impl<'a, W: CoreWrite, O: Options> serde::Serializer for &'a mut Serializer<W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
     type SerializeSeq = Compound<'a, W, O>;
     type SerializeTuple = Compound<'a, W, O>;
     type SerializeTupleStruct = Compound<'a, W, O>;
@@ -141,171 +728,321 @@ impl<'a, W: CoreWrite, O: Options> serde::Serializer for &'a mut Serializer<W, O
     type SerializeStructVariant = Compound<'a, W, O>;
 
     fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            return self.format_bool(v);
+        }
+        self.serialize_type_tag(TypeTag::Bool)?;
+        if O::Bools::PACKED {
+            return self.serialize_packed_bool(v);
+        }
         self.serialize_byte(v as u8)
     }
 
     fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            return self.format_int(v.into());
+        }
+        self.serialize_type_tag(TypeTag::I8)?;
         self.serialize_byte(v as u8)
     }
 
-    impl_serialize_int! {serialize_u16(u16) = serialize_u16()}
-    impl_serialize_int! {serialize_u32(u32) = serialize_u32()}
-    impl_serialize_int! {serialize_u64(u64) = serialize_u64()}
+    impl_serialize_int! {serialize_u16(u16) = serialize_u16(U16), format_uint(u64::from)}
+    impl_serialize_int! {serialize_u32(u32) = serialize_u32(U32), format_uint(u64::from)}
+    impl_serialize_int! {serialize_u64(u64) = serialize_u64(U64), format_uint(u64::from)}
 
     fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            return self.format_uint(v.into());
+        }
+        self.serialize_type_tag(TypeTag::U8)?;
         self.serialize_byte(v)
     }
 
-    impl_serialize_int! {serialize_i16(i16) = serialize_i16()}
-    impl_serialize_int! {serialize_i32(i32) = serialize_i32()}
-    impl_serialize_int! {serialize_i64(i64) = serialize_i64()}
+    impl_serialize_int! {serialize_i16(i16) = serialize_i16(I16), format_int(i64::from)}
+    impl_serialize_int! {serialize_i32(i32) = serialize_i32(I32), format_int(i64::from)}
+    impl_serialize_int! {serialize_i64(i64) = serialize_i64(I64), format_int(i64::from)}
 
-    serde_if_integer128! {
-        impl_serialize_int!{serialize_u128(u128) = serialize_u128()}
-        impl_serialize_int!{serialize_i128(i128) = serialize_i128()}
-    }
+    impl_serialize_int! {serialize_u128(u128) = serialize_u128(U128), format_u128(u128::from)}
+    impl_serialize_int! {serialize_i128(i128) = serialize_i128(I128), format_i128(i128::from)}
 
     fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
-        let mut buf = [0u8; 4];
-        <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::write_f32(&mut buf, v);
-        self.writer.write_all(&buf).map_err(SerializeError::Write)
+        if O::Format::SELF_DESCRIBING {
+            return self.format_f32(v);
+        }
+        self.serialize_type_tag(TypeTag::F32)?;
+        O::Float::serialize_f32(self, v)
     }
 
     fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
-        let mut buf = [0u8; 8];
-        <<O::Endian as BincodeByteOrder>::Endian as byteorder::ByteOrder>::write_f64(&mut buf, v);
-        self.writer.write_all(&buf).map_err(SerializeError::Write)
+        if O::Format::SELF_DESCRIBING {
+            return self.format_f64(v);
+        }
+        self.serialize_type_tag(TypeTag::F64)?;
+        O::Float::serialize_f64(self, v)
     }
 
     fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
-        self.writer
-            .write_all(encode_utf8(v).as_slice())
-            .map_err(SerializeError::Write)
+        if O::Format::SELF_DESCRIBING {
+            let utf8 = encode_utf8(v);
+            self.format_header(Header::Str, utf8.as_slice().len())?;
+            return self.serialize_raw(utf8.as_slice());
+        }
+        self.serialize_type_tag(TypeTag::Char)?;
+        if O::Chars::U32 {
+            return O::IntEncoding::serialize_u32(self, v as u32);
+        }
+        self.serialize_raw(encode_utf8(v).as_slice())
     }
 
-    fn serialize_str(mut self, v: &str) -> Result<Self::Ok, Self::Error> {
-        O::IntEncoding::serialize_len(&mut self, v.len())?;
-        self.writer
-            .write_all(v.as_bytes())
-            .map_err(SerializeError::Write)
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            return self.format_str(v);
+        }
+        self.serialize_type_tag(TypeTag::Str)?;
+        O::Usize::serialize_len(self, v.len())?;
+        self.serialize_raw(v.as_bytes())
     }
 
-    fn serialize_bytes(mut self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
-        O::IntEncoding::serialize_len(&mut self, v.len())?;
-        self.writer.write_all(v).map_err(SerializeError::Write)
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        if let Some((kind, len_written)) = self.primitive {
+            return self.serialize_primitive_field(kind, len_written, v);
+        }
+        if O::Format::SELF_DESCRIBING {
+            return self.format_bytes(v);
+        }
+        self.serialize_type_tag(TypeTag::Bytes)?;
+        O::Usize::serialize_len(self, v.len())?;
+        self.serialize_raw(v)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        self.writer.write(0).map_err(SerializeError::Write)
+        if O::Format::SELF_DESCRIBING {
+            return self.format_nil();
+        }
+        if O::TypeTags::TAGGED {
+            return self.serialize_byte(TypeTag::None as u8);
+        }
+        self.serialize_option_tag(0)
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
-    where
-        T: Serialize,
-    {
-        self.writer.write(1).map_err(SerializeError::Write)?;
-        value.serialize(self)
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            return self.serialize_value(value);
+        }
+        if O::TypeTags::TAGGED {
+            self.serialize_byte(TypeTag::Some as u8)?;
+            return self.serialize_value(value);
+        }
+        if !O::Presence::NICHE {
+            self.serialize_option_tag(1)?;
+            return self.serialize_value(value);
+        }
+        self.niche = true;
+        self.serialize_value(value)?;
+        if self.niche {
+            // Nothing was written
+            return Err(SerializeError::InvalidNiche);
+        }
+        Ok(())
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        if O::Format::SELF_DESCRIBING {
+            return self.format_nil();
+        }
+        self.serialize_type_tag(TypeTag::Unit)
     }
 
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        if O::Format::SELF_DESCRIBING {
+            return self.format_nil();
+        }
+        self.serialize_type_tag(TypeTag::Unit)
     }
 
     fn serialize_unit_variant(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        O::IntEncoding::serialize_u32(&mut self, variant_index)
+        if O::Format::SELF_DESCRIBING {
+            return self.format_str(variant);
+        }
+        self.serialize_variant(variant_index)?;
+        if O::Structs::COUNTED && !O::TypeTags::TAGGED {
+            // A unit variant has no fields to measure
+            self.struct_len = Some(0);
+        }
+        let reserved = self.serialize_struct_header(0)?;
+        self.serialize_type_tag(TypeTag::Unit)?;
+        self.end_struct(reserved)
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
         self,
         _name: &'static str,
         value: &T,
-    ) -> Result<Self::Ok, Self::Error>
-    where
-        T: Serialize,
-    {
-        value.serialize(self)
+    ) -> Result<Self::Ok, Self::Error> {
+        self.serialize_value(value)
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
-        mut self,
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
-    ) -> Result<Self::Ok, Self::Error>
-    where
-        T: Serialize,
-    {
-        O::IntEncoding::serialize_u32(&mut self, variant_index)?;
-        value.serialize(self)
+    ) -> Result<Self::Ok, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            self.format_variant(variant)?;
+            return self.serialize_value(value);
+        }
+        self.serialize_variant(variant_index)?;
+        let reserved = self.serialize_struct_header(1)?;
+        self.serialize_value(value)?;
+        self.end_struct(reserved)
     }
 
-    fn serialize_seq(mut self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        O::IntEncoding::serialize_len(&mut self, len.expect("Sequence has no elements"))?;
-        Ok(Compound { ser: self })
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let len = len.ok_or(SerializeError::SequenceMustHaveLength)?;
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Array, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_type_tag(TypeTag::Seq)?;
+        O::Usize::serialize_len(self, len)?;
+        Ok(Compound::new(self))
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        Ok(Compound { ser: self })
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Array, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_tuple_len(len)?;
+        Ok(Compound::new(self))
     }
 
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
-        _len: usize,
+        name: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        Ok(Compound { ser: self })
+        if let Some(kind) = PrimitiveKind::from_name(name) {
+            self.primitive = Some((kind, false));
+            return Ok(Compound::new(self));
+        }
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Array, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_tuple_len(len)?;
+        Ok(Compound::new(self))
     }
 
     fn serialize_tuple_variant(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        O::IntEncoding::serialize_u32(&mut self, variant_index)?;
-        Ok(Compound { ser: self })
+        if O::Format::SELF_DESCRIBING {
+            self.format_variant(variant)?;
+            self.format_header(Header::Array, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_variant(variant_index)?;
+        let reserved = self.serialize_struct_header(len)?;
+        self.serialize_tuple_len(len)?;
+        Ok(Compound::reserved(self, reserved))
     }
 
-    fn serialize_map(mut self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        O::IntEncoding::serialize_len(&mut self, len.expect("Sequence has no elements"))?;
-        Ok(Compound { ser: self })
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let len = len.ok_or(SerializeError::SequenceMustHaveLength)?;
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Map, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_type_tag(TypeTag::Map)?;
+        O::Usize::serialize_len(self, len)?;
+        Ok(Compound::new(self))
     }
 
     fn serialize_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
-        Ok(Compound { ser: self })
+        #[cfg(feature = "instrument")]
+        self.options.instrument().on_struct_start(_name);
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Map, len)?;
+            return Ok(Compound::new(self));
+        }
+        let reserved = self.serialize_struct_header(len)?;
+        if O::TypeTags::TAGGED || !O::Presence::PRESENCE_BITMAP {
+            self.serialize_tuple_len(len)?;
+            return Ok(Compound::reserved(self, reserved));
+        }
+        let presence = self
+            .presence
+            .take()
+            .ok_or(SerializeError::PresenceUnknown)?;
+        let bitmap = presence.bits.to_le_bytes();
+        self.serialize_raw(&bitmap[..bitmap_len(len)])?;
+        Ok(Compound {
+            ser: self,
+            presence,
+            index: 0,
+            reserved,
+        })
     }
 
     fn serialize_struct_variant(
-        mut self,
+        self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        O::IntEncoding::serialize_u32(&mut self, variant_index)?;
-        Ok(Compound { ser: self })
+        #[cfg(feature = "instrument")]
+        self.options.instrument().on_struct_start(variant);
+        if O::Format::SELF_DESCRIBING {
+            self.format_variant(variant)?;
+            self.format_header(Header::Map, len)?;
+            return Ok(Compound::new(self));
+        }
+        self.serialize_variant(variant_index)?;
+        let reserved = self.serialize_struct_header(len)?;
+        self.serialize_tuple_len(len)?;
+        Ok(Compound::reserved(self, reserved))
     }
 
-    fn collect_str<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
-    where
-        T: core::fmt::Display,
-    {
-        panic!("Unimplemented: Serialize::collect_str")
+    fn collect_str<T: ?Sized + core::fmt::Display>(
+        self,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        // The length is written first, so the value is formatted twice
+        let len = str_len(value)?;
+        if O::Format::SELF_DESCRIBING {
+            self.format_header(Header::Str, len)?;
+        } else {
+            self.serialize_type_tag(TypeTag::Str)?;
+            O::Usize::serialize_len(self, len)?;
+        }
+        let mut writer = StrWriter {
+            ser: self,
+            remaining: len,
+            error: None,
+        };
+        let result = core::fmt::write(&mut writer, format_args!("{}", value));
+        match writer.error {
+            Some(e) => Err(e),
+            None if result.is_err() || writer.remaining != 0 => Err(SerializeError::Custom),
+            None => Ok(()),
+        }
     }
 
     fn is_human_readable(&self) -> bool {
@@ -316,18 +1053,38 @@ impl<'a, W: CoreWrite, O: Options> serde::Serializer for &'a mut Serializer<W, O
 /// Internal struct needed for serialization.
 pub struct Compound<'a, W: CoreWrite, O: Options> {
     ser: &'a mut Serializer<W, O>,
+    presence: Presence,
+    index: usize,
+    /// The placeholder for the byte length of a struct, see
+    /// [serialize_struct_header](Serializer::serialize_struct_header).
+    reserved: Option<Reserved>,
+}
+
+impl<'a, W: CoreWrite, O: Options> Compound<'a, W, O> {
+    fn new(ser: &'a mut Serializer<W, O>) -> Self {
+        Self::reserved(ser, None)
+    }
+
+    fn reserved(ser: &'a mut Serializer<W, O>, reserved: Option<Reserved>) -> Self {
+        Compound {
+            ser,
+            presence: Presence::default(),
+            index: 0,
+            reserved,
+        }
+    }
 }
 
 impl<'a, W: CoreWrite, O: Options> SerializeSeq for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_element<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -338,14 +1095,14 @@ impl<'a, W: CoreWrite, O: Options> SerializeSeq for Compound<'a, W, O> {
 
 impl<'a, W: CoreWrite, O: Options> SerializeTuple for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_element<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -356,58 +1113,59 @@ impl<'a, W: CoreWrite, O: Options> SerializeTuple for Compound<'a, W, O> {
 
 impl<'a, W: CoreWrite, O: Options> SerializeTupleStruct for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), Self::Error> {
+        self.ser.primitive = None;
         Ok(())
     }
 }
 
 impl<'a, W: CoreWrite, O: Options> SerializeTupleVariant for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), Self::Error> {
-        Ok(())
+        self.ser.end_struct(self.reserved)
     }
 }
 
 impl<'a, W: CoreWrite, O: Options> SerializeMap for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_key<K: ?Sized>(&mut self, value: &K) -> Result<(), Self::Error>
-    where
-        K: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_key<K: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &K,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
-    fn serialize_value<V: ?Sized>(&mut self, value: &V) -> Result<(), Self::Error>
-    where
-        V: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_value<V: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &V,
+    ) -> Result<(), Self::Error> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -418,40 +1176,59 @@ impl<'a, W: CoreWrite, O: Options> SerializeMap for Compound<'a, W, O> {
 
 impl<'a, W: CoreWrite, O: Options> SerializeStruct for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
         &mut self,
-        _key: &'static str,
+        key: &'static str,
         value: &T,
-    ) -> Result<(), Self::Error>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    ) -> Result<(), Self::Error> {
+        if O::Format::SELF_DESCRIBING {
+            self.ser.format_str(key)?;
+        }
+        self.ser.skip_option_tag = self.presence.is_option(self.index);
+        self.index += 1;
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_field_start(key);
+        self.ser.serialize_value(value)?;
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_field_end(key);
+        Ok(())
     }
 
     #[inline]
     fn end(self) -> Result<(), Self::Error> {
-        Ok(())
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_struct_end();
+        self.ser.end_struct(self.reserved)
     }
 }
 
 impl<'a, W: CoreWrite, O: Options> SerializeStructVariant for Compound<'a, W, O> {
     type Ok = ();
-    type Error = SerializeError<W>;
+    type Error = SerializeError<W::Error>;
 
     fn serialize_field<T: ?Sized + Serialize>(
         &mut self,
-        _key: &'static str,
+        key: &'static str,
         value: &T,
     ) -> Result<(), Self::Error> {
-        value.serialize(&mut *self.ser)
+        if O::Format::SELF_DESCRIBING {
+            self.ser.format_str(key)?;
+        }
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_field_start(key);
+        self.ser.serialize_value(value)?;
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_field_end(key);
+        Ok(())
     }
 
     fn end(self) -> Result<(), Self::Error> {
-        Ok(())
+        #[cfg(feature = "instrument")]
+        self.ser.options.instrument().on_struct_end();
+        self.ser.end_struct(self.reserved)
     }
 }
 
@@ -0,0 +1,142 @@
+use crate::config::Options;
+use crate::serialize::{serialize, SerializeError};
+use crate::traits::{CoreWrite, CoreWriteSeek};
+use core::cell::RefCell;
+use critical_section::Mutex;
+
+/// A [CoreWrite] that can be shared between tasks and interrupts of different priorities, e.g. in
+/// a `static`, by guarding the inner writer with a `critical-section`.
+///
+/// [serialize](SharedWriter::serialize) writes a whole value inside a single critical section,
+/// so values that are serialized from different priorities into one TX queue are never
+/// interleaved. Use [with](SharedWriter::with) to access the queue, e.g. to move the next bytes to
+/// the UART from its TX interrupt.
+///
+/// `&SharedWriter` also implements [CoreWrite] itself, with a critical section per write. That
+/// keeps the critical sections short, but the bytes of values that are written concurrently can
+/// end up interleaved, so only use it when there is a single writing task.
+///
+/// # Latency
+///
+/// Depending on the `critical-section` implementation, a critical section disables interrupts or
+/// takes a lock, so higher priority tasks are delayed for as long as it lasts:
+///
+/// - [serialize](SharedWriter::serialize) holds it for the time it takes to serialize the value
+///   and write all its bytes. This grows with the size of the value, and includes the time the
+///   inner writer takes, so the inner writer should only copy the bytes into a queue and not wait
+///   for the hardware. To keep it short for large values, serialize them into a local buffer
+///   first and write the buffer with `with(|w| w.write_all(..))`, which is a single copy.
+/// - Every call to the [CoreWrite] methods of `&SharedWriter` and every call of
+///   [with](SharedWriter::with) holds it for the duration of that one call.
+///
+/// ```
+/// # use bincode_core::{BufferWriter, DefaultOptions, SharedWriter};
+/// let mut queue = [0u8; 16];
+/// let shared = SharedWriter::new(BufferWriter::new(&mut queue));
+///
+/// // From any task or interrupt
+/// shared.serialize(&(1u8, 300u16), DefaultOptions::new()).unwrap();
+///
+/// // From the TX interrupt
+/// let pending = shared.with(|queue| queue.written_len()).unwrap();
+/// assert_eq!(pending, 4);
+/// ```
+pub struct SharedWriter<W> {
+    inner: Mutex<RefCell<W>>,
+}
+
+impl<W> SharedWriter<W> {
+    /// Create a new shared writer around `writer`. This is a `const fn`, so it can initialize a
+    /// `static`.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(writer)),
+        }
+    }
+
+    /// Call `f` with the inner writer inside a critical section.
+    ///
+    /// Returns `None` if the writer is already in use, which only happens when this is called
+    /// from within `f` or while serializing, e.g. by a `Serialize` implementation.
+    pub fn with<T>(&self, f: impl FnOnce(&mut W) -> T) -> Option<T> {
+        critical_section::with(|cs| {
+            let mut writer = self.inner.borrow(cs).try_borrow_mut().ok()?;
+            Some(f(&mut writer))
+        })
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner.into_inner().into_inner()
+    }
+}
+
+impl<W: CoreWrite> SharedWriter<W> {
+    /// Serialize a value into the inner writer inside a single critical section, so it is not
+    /// interleaved with values that are serialized from other priorities. See
+    /// [Latency](SharedWriter#latency).
+    pub fn serialize<T: serde::Serialize + ?Sized, O: Options>(
+        &self,
+        value: &T,
+        options: O,
+    ) -> Result<(), SerializeError<SharedWriteError<W::Error>>> {
+        self.with(|writer| serialize(value, ByRef(writer), options))
+            .ok_or(SerializeError::Write(SharedWriteError::Busy))?
+            .map_err(|e| e.map_write(SharedWriteError::Write))
+    }
+}
+
+/// An error that is thrown when writing to a [SharedWriter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SharedWriteError<E> {
+    /// The inner writer returned an error.
+    Write(E),
+    /// The writer is already in use by the same task, see [with](SharedWriter::with).
+    Busy,
+}
+
+impl<W: CoreWrite> CoreWrite for &SharedWriter<W> {
+    type Error = SharedWriteError<W::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.with(|writer| writer.write(val))
+            .ok_or(SharedWriteError::Busy)?
+            .map_err(SharedWriteError::Write)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.with(|writer| writer.flush())
+            .ok_or(SharedWriteError::Busy)?
+            .map_err(SharedWriteError::Write)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.with(|writer| writer.write_all(val))
+            .ok_or(SharedWriteError::Busy)?
+            .map_err(SharedWriteError::Write)
+    }
+}
+
+/// Forwards to a borrowed writer, as not every [CoreWrite] is also implemented for `&mut`.
+struct ByRef<'w, W>(&'w mut W);
+
+impl<W: CoreWrite> CoreWrite for ByRef<'_, W> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.0.write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.0.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.0.write_all(val)
+    }
+
+    fn as_seek(&mut self) -> Option<&mut dyn CoreWriteSeek<Error = Self::Error>> {
+        self.0.as_seek()
+    }
+}
@@ -1,11 +1,24 @@
-use crate::config::IntEncoding;
+use crate::config::{
+    bitmap_len, BoolEncoding, CharEncoding, DiscriminantEncoding, FloatEncoding, Infinite,
+    IntEncoding, OptionEncoding, SizeLimit, StructEncoding, TypeTagEncoding, UsizeEncoding,
+    WithOtherLimit,
+};
+use crate::presence::{probe_struct, Presence};
+use crate::primitive_slice::{ElementSink, PrimitiveKind};
 use crate::{config::Options, serialize::SerializeError, traits::CoreWrite};
-use core::mem::size_of;
-use serde::serde_if_integer128;
 
 pub(crate) struct SizeChecker<O: Options> {
     pub options: O,
     pub total: usize,
+    presence: Option<Presence>,
+    skip_option_tag: bool,
+    bool_count: u8,
+    /// The byte length of the fields of the last struct or enum variant that ended, with
+    /// [CountedStructs](crate::config::CountedStructs).
+    struct_body: Option<usize>,
+    /// The element type of the [PrimitiveSlice](crate::PrimitiveSlice) that is being serialized,
+    /// and whether its length was added.
+    primitive: Option<(PrimitiveKind, bool)>,
 }
 
 impl<O: Options> CoreWrite for SizeChecker<O> {
@@ -17,18 +30,136 @@ impl<O: Options> CoreWrite for SizeChecker<O> {
 }
 
 impl<O: Options> SizeChecker<O> {
+    pub fn new(options: O) -> Self {
+        SizeChecker {
+            options,
+            total: 0,
+            presence: None,
+            skip_option_tag: false,
+            bool_count: 0,
+            struct_body: None,
+            primitive: None,
+        }
+    }
+
+    // see Serializer::serialize_value
+    pub fn serialize_value<T: serde::Serialize + ?Sized>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<()>> {
+        if O::Presence::PRESENCE_BITMAP {
+            self.presence = probe_struct(value);
+        }
+        value.serialize(self)
+    }
+
+    // see Serializer::serialize_struct_header
+    fn start_struct(&mut self, len: usize) -> Option<(usize, usize)> {
+        if !O::Structs::COUNTED || O::TypeTags::TAGGED {
+            return None;
+        }
+        // The header is added when the struct ends, but it ends a run of packed bools here
+        self.bool_count = 0;
+        Some((len, self.total))
+    }
+
+    fn end_struct(&mut self, counted: Option<(usize, usize)>) -> Result<(), SerializeError<()>> {
+        if let Some((len, start)) = counted {
+            let body = self.total - start;
+            let header = O::Usize::len_size::<O>(len) + O::Usize::len_size::<O>(body);
+            self.options
+                .limit()
+                .add(header as u64)
+                .map_err(|_| SerializeError::LimitReached)?;
+            self.total += header;
+            // The pending bools of the struct are written when it ends
+            self.bool_count = 0;
+            self.struct_body = Some(body);
+        }
+        Ok(())
+    }
+
+    // see Serializer::serialize_type_tag
+    fn add_type_tag(&mut self) -> Result<(), SerializeError<()>> {
+        if O::TypeTags::TAGGED {
+            self.add_raw(1)?;
+        }
+        Ok(())
+    }
+
+    // see Serializer::serialize_tuple_len
+    fn add_tuple_len(&mut self, len: usize) -> Result<(), SerializeError<()>> {
+        if O::TypeTags::TAGGED {
+            self.add_raw(1)?;
+            self.add_len(len)?;
+        }
+        Ok(())
+    }
+
+    fn add_option_tag(&mut self) -> Result<(), SerializeError<()>> {
+        if O::TypeTags::TAGGED {
+            return self.add_raw(1);
+        }
+        if core::mem::take(&mut self.skip_option_tag) {
+            return Ok(());
+        }
+        self.add_raw(1)
+    }
+
+    // see Serializer::serialize_primitive_field
+    fn add_primitive_field(
+        &mut self,
+        kind: PrimitiveKind,
+        len_written: bool,
+        v: &[u8],
+    ) -> Result<(), SerializeError<()>> {
+        if !len_written {
+            use core::convert::TryFrom;
+
+            let len = <[u8; 8]>::try_from(v).map_err(|_| SerializeError::Custom)?;
+            let len =
+                usize::try_from(u64::from_le_bytes(len)).map_err(|_| SerializeError::Custom)?;
+            self.primitive = Some((kind, true));
+            self.add_type_tag()?;
+            return self.add_len(len);
+        }
+        if v.len() % kind.width() != 0 {
+            return Err(SerializeError::Custom);
+        }
+        if !kind.is_bulk::<O>() {
+            return kind.for_each(v, self);
+        }
+        self.add_raw(v.len())
+    }
+
     fn add_raw(&mut self, len: usize) -> Result<(), SerializeError<()>> {
+        self.options
+            .limit()
+            .add(len as u64)
+            .map_err(|_| SerializeError::LimitReached)?;
         self.total += len;
+        self.bool_count = 0;
+        Ok(())
+    }
+
+    // see Serializer::serialize_packed_bool
+    fn add_packed_bool(&mut self) -> Result<(), SerializeError<()>> {
+        if self.bool_count == 0 {
+            self.add_raw(1)?;
+        }
+        self.bool_count = (self.bool_count + 1) % 8;
         Ok(())
     }
 
+    // see Serializer::serialize_variant
     fn add_discriminant(&mut self, idx: u32) -> Result<(), SerializeError<()>> {
-        let bytes = O::IntEncoding::u32_size(idx);
+        self.add_type_tag()?;
+        let bytes = O::Discriminant::discriminant_size::<O>(idx);
         self.add_raw(bytes)
     }
 
     fn add_len(&mut self, len: usize) -> Result<(), SerializeError<()>> {
-        let bytes = O::IntEncoding::len_size(len);
+        let bytes = O::Usize::len_size::<O>(len);
         self.add_raw(bytes)
     }
 }
@@ -36,11 +167,20 @@ impl<O: Options> SizeChecker<O> {
 macro_rules! impl_size_int {
     ($ser_method:ident($ty:ty) = $size_method:ident()) => {
         fn $ser_method(self, v: $ty) -> Result<(), SerializeError<()>> {
+            self.add_type_tag()?;
             self.add_raw(O::IntEncoding::$size_method(v))
         }
     };
 }
 
+impl<O: Options> ElementSink for SizeChecker<O> {
+    type Error = SerializeError<()>;
+
+    fn element<T: serde::Serialize>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.serialize_value(value)
+    }
+}
+
 impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
     type Ok = ();
     type Error = SerializeError<()>;
@@ -53,21 +193,27 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
     type SerializeStructVariant = Compound<'a, O>;
 
     fn serialize_unit(self) -> Result<(), SerializeError<()>> {
-        Ok(())
+        self.add_type_tag()
     }
 
     fn serialize_unit_struct(self, _: &'static str) -> Result<(), SerializeError<()>> {
-        Ok(())
+        self.add_type_tag()
     }
 
     fn serialize_bool(self, _: bool) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
+        if O::Bools::PACKED {
+            return self.add_packed_bool();
+        }
         self.add_raw(1)
     }
 
     fn serialize_u8(self, _: u8) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
         self.add_raw(1)
     }
     fn serialize_i8(self, _: i8) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
         self.add_raw(1)
     }
 
@@ -78,62 +224,77 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
     impl_size_int! {serialize_i32(i32) = i32_size()}
     impl_size_int! {serialize_i64(i64) = i64_size()}
 
-    serde_if_integer128! {
-        impl_size_int!{serialize_u128(u128) = u128_size()}
-        impl_size_int!{serialize_i128(i128) = i128_size()}
-    }
+    impl_size_int! {serialize_u128(u128) = u128_size()}
+    impl_size_int! {serialize_i128(i128) = i128_size()}
 
-    fn serialize_f32(self, _: f32) -> Result<(), SerializeError<()>> {
-        self.add_raw(size_of::<f32>())
+    fn serialize_f32(self, v: f32) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
+        self.add_raw(O::Float::f32_size::<O>(v))
     }
 
-    fn serialize_f64(self, _: f64) -> Result<(), SerializeError<()>> {
-        self.add_raw(size_of::<f64>())
+    fn serialize_f64(self, v: f64) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
+        self.add_raw(O::Float::f64_size::<O>(v))
     }
 
     fn serialize_str(self, v: &str) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
         self.add_len(v.len())?;
         self.add_raw(v.len())
     }
 
     fn serialize_char(self, c: char) -> Result<(), SerializeError<()>> {
+        self.add_type_tag()?;
+        if O::Chars::U32 {
+            return self.add_raw(O::IntEncoding::u32_size(c as u32));
+        }
         self.add_raw(encode_utf8(c).as_slice().len())
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<(), SerializeError<()>> {
+        if let Some((kind, len_written)) = self.primitive {
+            return self.add_primitive_field(kind, len_written, v);
+        }
+        self.add_type_tag()?;
         self.add_len(v.len())?;
         self.add_raw(v.len())
     }
 
     fn serialize_none(self) -> Result<(), SerializeError<()>> {
-        self.add_raw(1)
+        self.add_option_tag()
     }
 
-    fn serialize_some<T: ?Sized>(self, v: &T) -> Result<(), SerializeError<()>>
-    where
-        T: serde::Serialize,
-    {
-        self.add_raw(1)?;
-        v.serialize(self)
+    fn serialize_some<T: ?Sized + serde::Serialize>(self, v: &T) -> Result<(), SerializeError<()>> {
+        if O::TypeTags::TAGGED || !O::Presence::NICHE {
+            self.add_option_tag()?;
+        }
+        self.serialize_value(v)
     }
 
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, SerializeError<()>> {
         let len = len.ok_or(SerializeError::SequenceMustHaveLength)?;
 
+        self.add_type_tag()?;
         self.add_len(len)?;
-        Ok(Compound { ser: self })
+        Ok(Compound::new(self))
     }
 
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerializeError<()>> {
-        Ok(Compound { ser: self })
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, SerializeError<()>> {
+        self.add_tuple_len(len)?;
+        Ok(Compound::new(self))
     }
 
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
-        _len: usize,
+        name: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct, SerializeError<()>> {
-        Ok(Compound { ser: self })
+        if let Some(kind) = PrimitiveKind::from_name(name) {
+            self.primitive = Some((kind, false));
+            return Ok(Compound::new(self));
+        }
+        self.add_tuple_len(len)?;
+        Ok(Compound::new(self))
     }
 
     fn serialize_tuple_variant(
@@ -141,25 +302,49 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant, SerializeError<()>> {
-        self.add_raw(O::IntEncoding::u32_size(variant_index))?;
-        Ok(Compound { ser: self })
+        self.add_discriminant(variant_index)?;
+        let counted = self.start_struct(len);
+        self.add_tuple_len(len)?;
+        Ok(Compound {
+            counted,
+            ..Compound::new(self)
+        })
     }
 
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, SerializeError<()>> {
         let len = len.ok_or(SerializeError::SequenceMustHaveLength)?;
 
+        self.add_type_tag()?;
         self.add_len(len)?;
-        Ok(Compound { ser: self })
+        Ok(Compound::new(self))
     }
 
     fn serialize_struct(
         self,
         _name: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStruct, SerializeError<()>> {
-        Ok(Compound { ser: self })
+        let counted = self.start_struct(len);
+        if O::TypeTags::TAGGED || !O::Presence::PRESENCE_BITMAP {
+            self.add_tuple_len(len)?;
+            return Ok(Compound {
+                counted,
+                ..Compound::new(self)
+            });
+        }
+        let presence = self
+            .presence
+            .take()
+            .ok_or(SerializeError::PresenceUnknown)?;
+        self.add_raw(bitmap_len(len))?;
+        Ok(Compound {
+            ser: self,
+            presence,
+            index: 0,
+            counted,
+        })
     }
 
     fn serialize_struct_variant(
@@ -167,10 +352,15 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
         _name: &'static str,
         variant_index: u32,
         _variant: &'static str,
-        _len: usize,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant, SerializeError<()>> {
         self.add_discriminant(variant_index)?;
-        Ok(Compound { ser: self })
+        let counted = self.start_struct(len);
+        self.add_tuple_len(len)?;
+        Ok(Compound {
+            counted,
+            ..Compound::new(self)
+        })
     }
 
     fn serialize_newtype_struct<V: serde::Serialize + ?Sized>(
@@ -178,7 +368,7 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
         _name: &'static str,
         v: &V,
     ) -> Result<(), SerializeError<()>> {
-        v.serialize(self)
+        self.serialize_value(v)
     }
 
     fn serialize_unit_variant(
@@ -187,7 +377,10 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
         variant_index: u32,
         _variant: &'static str,
     ) -> Result<(), SerializeError<()>> {
-        self.add_discriminant(variant_index)
+        self.add_discriminant(variant_index)?;
+        let counted = self.start_struct(0);
+        self.add_type_tag()?;
+        self.end_struct(counted)
     }
 
     fn serialize_newtype_variant<V: serde::Serialize + ?Sized>(
@@ -198,23 +391,43 @@ impl<'a, O: Options> serde::Serializer for &'a mut SizeChecker<O> {
         value: &V,
     ) -> Result<(), SerializeError<()>> {
         self.add_discriminant(variant_index)?;
-        value.serialize(self)
+        let counted = self.start_struct(1);
+        self.serialize_value(value)?;
+        self.end_struct(counted)
     }
 
     fn is_human_readable(&self) -> bool {
         false
     }
 
-    fn collect_str<T: ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error>
-    where
-        T: core::fmt::Display,
-    {
-        todo!()
+    fn collect_str<T: ?Sized + core::fmt::Display>(
+        self,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error> {
+        let len = crate::serialize::str_len(value)?;
+        self.add_type_tag()?;
+        self.add_len(len)?;
+        self.add_raw(len)
     }
 }
 
 pub(crate) struct Compound<'a, S: Options + 'a> {
     ser: &'a mut SizeChecker<S>,
+    presence: Presence,
+    index: usize,
+    /// The field count and the start of a counted struct.
+    counted: Option<(usize, usize)>,
+}
+
+impl<'a, O: Options> Compound<'a, O> {
+    fn new(ser: &'a mut SizeChecker<O>) -> Self {
+        Compound {
+            ser,
+            presence: Presence::default(),
+            index: 0,
+            counted: None,
+        }
+    }
 }
 
 impl<'a, O: Options> serde::ser::SerializeSeq for Compound<'a, O> {
@@ -222,11 +435,11 @@ impl<'a, O: Options> serde::ser::SerializeSeq for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_element<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -240,11 +453,11 @@ impl<'a, O: Options> serde::ser::SerializeTuple for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_element<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -258,15 +471,16 @@ impl<'a, O: Options> serde::ser::SerializeTupleStruct for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), SerializeError<()>> {
+        self.ser.primitive = None;
         Ok(())
     }
 }
@@ -276,16 +490,16 @@ impl<'a, O: Options> serde::ser::SerializeTupleVariant for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &T,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), SerializeError<()>> {
-        Ok(())
+        self.ser.end_struct(self.counted)
     }
 }
 
@@ -294,19 +508,19 @@ impl<'a, O: Options + 'a> serde::ser::SerializeMap for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_key<K: ?Sized>(&mut self, value: &K) -> Result<(), SerializeError<()>>
-    where
-        K: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_key<K: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &K,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
-    fn serialize_value<V: ?Sized>(&mut self, value: &V) -> Result<(), SerializeError<()>>
-    where
-        V: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    fn serialize_value<V: ?Sized + serde::ser::Serialize>(
+        &mut self,
+        value: &V,
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
@@ -320,20 +534,19 @@ impl<'a, O: Options> serde::ser::SerializeStruct for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
         &mut self,
         _key: &'static str,
         value: &T,
-    ) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.skip_option_tag = self.presence.is_option(self.index);
+        self.index += 1;
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), SerializeError<()>> {
-        Ok(())
+        self.ser.end_struct(self.counted)
     }
 }
 
@@ -342,20 +555,17 @@ impl<'a, O: Options> serde::ser::SerializeStructVariant for Compound<'a, O> {
     type Error = SerializeError<()>;
 
     #[inline]
-    fn serialize_field<T: ?Sized>(
+    fn serialize_field<T: ?Sized + serde::ser::Serialize>(
         &mut self,
         _key: &'static str,
         value: &T,
-    ) -> Result<(), SerializeError<()>>
-    where
-        T: serde::ser::Serialize,
-    {
-        value.serialize(&mut *self.ser)
+    ) -> Result<(), SerializeError<()>> {
+        self.ser.serialize_value(value)
     }
 
     #[inline]
     fn end(self) -> Result<(), SerializeError<()>> {
-        Ok(())
+        self.ser.end_struct(self.counted)
     }
 }
 
@@ -402,3 +612,15 @@ impl EncodeUtf8 {
         &self.buf[self.pos..]
     }
 }
+
+/// Returns the byte length of the fields of the struct or enum variant in `value` that ends last,
+/// with [CountedStructs](crate::config::CountedStructs). If `value` is a struct or enum variant, this
+/// is `value` itself. Returns `None` if `value` contains neither.
+pub(crate) fn struct_body_len<T: serde::Serialize + ?Sized, O: Options>(
+    value: &T,
+    options: &mut O,
+) -> Option<usize> {
+    let mut checker = SizeChecker::new(WithOtherLimit::new(options, Infinite));
+    checker.serialize_value(value).ok()?;
+    checker.struct_body
+}
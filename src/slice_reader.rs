@@ -0,0 +1,167 @@
+use crate::traits::{CheckpointRead, CoreRead, SliceReadError};
+
+/// An implementation of [CoreRead] that reads from a slice, and keeps track of its position.
+///
+/// This reads exactly like a plain `&[u8]`, but the position can be inspected and changed. That
+/// allows resuming parsing of a buffer later, or going back to a known offset, e.g. to re-read a
+/// message header after inspecting it.
+///
+/// ```
+/// # use bincode_core::{DefaultOptions, Deserializer, SliceReader};
+/// # use serde::Deserialize;
+/// let buffer = [1u8, 2, 251, 0x00, 0x01];
+/// let mut deserializer = Deserializer::new(SliceReader::new(&buffer), DefaultOptions::new());
+/// assert_eq!(u8::deserialize(&mut deserializer).unwrap(), 1);
+/// assert_eq!(deserializer.reader().position(), 1);
+///
+/// let mut reader = deserializer.into_reader();
+/// reader.set_position(2);
+/// assert_eq!(reader.remaining(), &[251, 0x00, 0x01]);
+/// let mut deserializer = Deserializer::new(reader, DefaultOptions::new());
+/// assert_eq!(u16::deserialize(&mut deserializer).unwrap(), 256);
+/// assert_eq!(deserializer.reader().position(), 5);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SliceReader<'a> {
+    slice: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Create a new reader that starts at the beginning of `slice`.
+    pub fn new(slice: &'a [u8]) -> Self {
+        Self { slice, position: 0 }
+    }
+
+    /// The amount of bytes that were read from the start of the slice.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Continue reading at `position` bytes from the start of the slice. Bytes before the position
+    /// can be read again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is larger than the length of the slice.
+    pub fn set_position(&mut self, position: usize) {
+        assert!(
+            position <= self.slice.len(),
+            "position {} is past the end of the slice ({} bytes)",
+            position,
+            self.slice.len()
+        );
+        self.position = position;
+    }
+
+    /// The bytes that are not read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.slice[self.position..]
+    }
+
+    /// Return the complete slice, including the bytes that were already read.
+    pub fn into_inner(self) -> &'a [u8] {
+        self.slice
+    }
+
+    /// Reads from the remaining bytes with the `&[u8]` reader, and advances past the bytes it read.
+    fn read_with<T>(
+        &mut self,
+        f: impl FnOnce(&mut &'a [u8]) -> Result<T, SliceReadError>,
+    ) -> Result<T, SliceReadError> {
+        let mut remaining = self.remaining();
+        let result = f(&mut remaining);
+        self.position = self.slice.len() - remaining.len();
+        result
+    }
+}
+
+impl<'a> CoreRead<'a> for SliceReader<'a> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.read_with(|slice| slice.fill(buffer))
+    }
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.read_with(|slice| slice.read())
+    }
+
+    fn read_range(&mut self, len: usize) -> Result<&'a [u8], Self::Error> {
+        self.read_with(|slice| slice.read_range(len))
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.read_with(|slice| slice.forward_str(len, visitor))
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.read_with(|slice| slice.forward_bytes(len, visitor))
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.slice.len() - self.position)
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.remaining().get(..len)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_owned(&mut self, len: usize) -> Result<alloc::vec::Vec<u8>, Self::Error> {
+        self.read_with(|slice| slice.read_owned(len))
+    }
+}
+
+impl<'a> CheckpointRead<'a> for SliceReader<'a> {
+    type Mark = usize;
+
+    fn mark(&self) -> Self::Mark {
+        self.position
+    }
+
+    fn reset(&mut self, mark: Self::Mark) {
+        self.position = mark;
+    }
+}
+
+impl<'a> CoreRead<'a> for &mut SliceReader<'a> {
+    type Error = SliceReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        (**self).peek(len)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_owned(&mut self, len: usize) -> Result<alloc::vec::Vec<u8>, Self::Error> {
+        (**self).read_owned(len)
+    }
+}
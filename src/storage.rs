@@ -0,0 +1,409 @@
+//! Wear-leveled settings storage in flash or EEPROM.
+//!
+//! [Settings] persists a `Serialize` value, like the configuration of a device, as a record in a
+//! [Storage]. Every [save](Settings::save) appends a new record after the previous one, and
+//! [load_latest](Settings::load_latest) returns the newest record that is intact. The records go
+//! around all pages of the storage, so the pages are erased evenly, and a page is only erased when
+//! the log moves on to it. The newest record is never in the page that is erased, so the settings
+//! survive a power loss at any point of a save, as long as the storage has at least 2 pages.
+//!
+//! Every record is:
+//!
+//! | Bytes | Content |
+//! |-------|---------|
+//! | 2 | the length of the payload, `u16` little endian |
+//! | 4 | the CRC-32 of the sequence number and the payload, `u32` little endian |
+//! | 4 | the sequence number, which counts the saves, `u32` little endian |
+//! | length | the serialized value |
+//!
+//! and is padded with [ERASED_BYTE](FlashSlice::ERASED_BYTE) to the
+//! [write size](Storage::write_size) of the storage.
+//!
+//! ```
+//! # #[macro_use] extern crate serde_derive;
+//! # use bincode_core::DefaultOptions;
+//! # use bincode_core::storage::{Settings, Storage};
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     interval_ms: u32,
+//!     enabled: bool,
+//! }
+//!
+//! /// Two pages of 64 bytes in RAM, standing in for a flash driver.
+//! struct Ram([u8; 128]);
+//!
+//! impl Storage for Ram {
+//!     type Error = ();
+//!     fn page_size(&self) -> usize {
+//!         64
+//!     }
+//!     fn page_count(&self) -> usize {
+//!         2
+//!     }
+//!     fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), ()> {
+//!         buffer.copy_from_slice(&self.0[offset..offset + buffer.len()]);
+//!         Ok(())
+//!     }
+//!     fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), ()> {
+//!         self.0[offset..offset + data.len()].copy_from_slice(data);
+//!         Ok(())
+//!     }
+//!     fn erase_page(&mut self, page: usize) -> Result<(), ()> {
+//!         self.0[page * 64..(page + 1) * 64].fill(0xFF);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let options = DefaultOptions::new();
+//! let mut settings = Settings::new(Ram([0xFF; 128])).unwrap();
+//! let mut buffer = [0u8; 32];
+//! assert_eq!(settings.load_latest::<Config, _>(&mut buffer, options).unwrap(), None);
+//!
+//! for interval_ms in 1..=20 {
+//!     let config = Config { interval_ms, enabled: true };
+//!     settings.save(&config, &mut buffer, options).unwrap();
+//! }
+//!
+//! // After a reset
+//! let mut settings = Settings::new(settings.into_inner()).unwrap();
+//! let config: Option<Config> = settings.load_latest(&mut buffer, options).unwrap();
+//! assert_eq!(config, Some(Config { interval_ms: 20, enabled: true }));
+//! ```
+
+use crate::buffer_writer::BufferWriter;
+use crate::config::Options;
+use crate::deserialize::{deserialize, DeserializeError};
+use crate::flash_slice::FlashSlice;
+use crate::serialize::{serialize, serialize_size, SerializeError};
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// The length of the header in front of the payload of every record.
+pub const HEADER_LEN: usize = 10;
+
+/// The largest payload of a record.
+pub const MAX_PAYLOAD_LEN: usize = u16::MAX as usize - 1;
+
+/// A flash or EEPROM region that is divided into pages of the same size. Offsets are counted from
+/// the start of the region.
+///
+/// For an EEPROM that can overwrite single bytes, [erase_page](Storage::erase_page) fills the page
+/// with [ERASED_BYTE](FlashSlice::ERASED_BYTE).
+pub trait Storage {
+    /// The error of the flash driver.
+    type Error: fmt::Debug;
+
+    /// The size of a page, the smallest region that can be erased.
+    fn page_size(&self) -> usize;
+
+    /// The amount of pages. With a single page, the settings are lost if the power fails while the
+    /// page is erased.
+    fn page_count(&self) -> usize;
+
+    /// The smallest amount of bytes that can be written, e.g. 8 for flash that is programmed in
+    /// double words. Records start at multiples of it.
+    ///
+    /// The default is 1.
+    fn write_size(&self) -> usize {
+        1
+    }
+
+    /// Fills `buffer` with the bytes at `offset`.
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Writes `data` at `offset`. Both are multiples of the [write size](Storage::write_size), and
+    /// the bytes are erased.
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Sets all bytes of page `page` to [ERASED_BYTE](FlashSlice::ERASED_BYTE).
+    fn erase_page(&mut self, page: usize) -> Result<(), Self::Error>;
+}
+
+/// An error that is thrown by [Settings].
+#[derive(Debug)]
+pub enum StorageError<'a, E> {
+    /// The storage returned an error.
+    Storage(E),
+    /// The record does not fit in the buffer, in a page, or in [MAX_PAYLOAD_LEN].
+    TooLarge {
+        /// The length of the record, with its header and padding.
+        len: usize,
+        /// The largest record that fits.
+        max: usize,
+    },
+    /// The value could not be serialized.
+    Serialize(SerializeError<()>),
+    /// The newest record could not be deserialized, e.g. because the type changed.
+    Deserialize(DeserializeError<'a, &'a [u8]>),
+}
+
+/// The location of an intact record.
+#[derive(Clone, Copy)]
+struct Record {
+    offset: usize,
+    len: usize,
+    sequence: u32,
+}
+
+/// Settings that are stored as a log of records in a [Storage]. See the [module](self)
+/// documentation.
+pub struct Settings<S: Storage> {
+    storage: S,
+    latest: Option<Record>,
+    /// Where the next record is written
+    head: usize,
+    /// Whether the page of `head` has to be erased before the next record is written
+    erase_head: bool,
+    /// The sequence number of the next record
+    sequence: u32,
+}
+
+impl<S: Storage> Settings<S> {
+    /// Scans `storage` for the newest intact record.
+    ///
+    /// Records with a wrong CRC, e.g. because the power failed while they were written, are
+    /// skipped. If the storage has no intact record, the first page is erased by the first
+    /// [save](Settings::save).
+    pub fn new(mut storage: S) -> Result<Self, StorageError<'static, S::Error>> {
+        let page_size = storage.page_size();
+        let mut latest: Option<Record> = None;
+        let mut head = 0;
+        for page in 0..storage.page_count() {
+            let start = page * page_size;
+            let (newest, end) = scan_page(&mut storage, start, start + page_size)?;
+            if let Some(record) = newest {
+                if latest.is_none_or(|latest| record.sequence > latest.sequence) {
+                    latest = Some(record);
+                    head = end;
+                }
+            }
+        }
+        Ok(Self {
+            storage,
+            latest,
+            head,
+            erase_head: latest.is_none(),
+            sequence: latest.map_or(0, |record| record.sequence.wrapping_add(1)),
+        })
+    }
+
+    /// Deserializes the newest intact record into `buffer`, or returns `None` if nothing was saved
+    /// yet. `&str` and `&[u8]` fields borrow from `buffer`.
+    pub fn load_latest<'a, T, O>(
+        &mut self,
+        buffer: &'a mut [u8],
+        options: O,
+    ) -> Result<Option<T>, StorageError<'a, S::Error>>
+    where
+        T: Deserialize<'a>,
+        O: Options,
+    {
+        let record = match self.latest {
+            Some(record) => record,
+            None => return Ok(None),
+        };
+        let max = buffer.len();
+        let payload = buffer.get_mut(..record.len).ok_or(StorageError::TooLarge {
+            len: record.len,
+            max,
+        })?;
+        self.storage
+            .read(record.offset + HEADER_LEN, payload)
+            .map_err(StorageError::Storage)?;
+        deserialize(&*payload, options)
+            .map(Some)
+            .map_err(StorageError::Deserialize)
+    }
+
+    /// Appends `value` as the newest record. `buffer` has to fit the serialized value with its
+    /// header and padding.
+    ///
+    /// If the record does not fit in the rest of the current page, the next page is erased and
+    /// the record is written at its start.
+    pub fn save<T, O>(
+        &mut self,
+        value: &T,
+        buffer: &mut [u8],
+        options: O,
+    ) -> Result<(), StorageError<'static, S::Error>>
+    where
+        T: Serialize + ?Sized,
+        O: Options,
+    {
+        let page_size = self.storage.page_size();
+        let write_size = self.storage.write_size();
+        let max = buffer.len().min(page_size);
+
+        let mut options = options;
+        let len = {
+            let payload = buffer.get_mut(HEADER_LEN..).unwrap_or(&mut []);
+            let mut writer = BufferWriter::new(payload);
+            match serialize(value, &mut writer, &mut options) {
+                Ok(()) => writer.written_len(),
+                Err(SerializeError::Write(_)) => {
+                    let len = serialize_size(value, &mut options).unwrap_or(usize::MAX);
+                    return Err(StorageError::TooLarge {
+                        len: align_up(HEADER_LEN.saturating_add(len), write_size),
+                        max,
+                    });
+                }
+                Err(e) => return Err(StorageError::Serialize(e.map_write(|_| ()))),
+            }
+        };
+        let padded = align_up(HEADER_LEN + len, write_size);
+        if padded > max || len > MAX_PAYLOAD_LEN {
+            return Err(StorageError::TooLarge { len: padded, max });
+        }
+
+        // A failed write can still leave an intact record, so its sequence number is not reused
+        let sequence = self.sequence;
+        self.sequence = sequence.wrapping_add(1);
+        let mut crc = Crc32::new();
+        crc.update(&sequence.to_le_bytes());
+        crc.update(&buffer[HEADER_LEN..HEADER_LEN + len]);
+        buffer[..2].copy_from_slice(&(len as u16).to_le_bytes());
+        buffer[2..6].copy_from_slice(&crc.finish().to_le_bytes());
+        buffer[6..10].copy_from_slice(&sequence.to_le_bytes());
+        buffer[HEADER_LEN + len..padded].fill(FlashSlice::ERASED_BYTE);
+
+        // A head at the end of a page is not in the next page, which is not erased yet
+        let page = self.head.saturating_sub(1) / page_size;
+        if self.head + padded > (page + 1) * page_size {
+            let next = (page + 1) % self.storage.page_count();
+            self.head = next * page_size;
+            self.erase_head = true;
+        }
+        if self.erase_head {
+            self.storage
+                .erase_page(self.head / page_size)
+                .map_err(StorageError::Storage)?;
+            self.erase_head = false;
+        }
+        if let Err(e) = self.storage.write(self.head, &buffer[..padded]) {
+            // The header may be broken, which hides the rest of the page, see `scan_page`. If the
+            // newest record is in this page, the next record goes to the next page. Otherwise,
+            // this page only has the failed record, and is erased again.
+            let start = self.head / page_size * page_size;
+            let newest_in_page = self
+                .latest
+                .is_some_and(|record| record.offset / page_size * page_size == start);
+            if newest_in_page {
+                self.head = start + page_size;
+            } else {
+                self.head = start;
+                self.erase_head = true;
+            }
+            return Err(StorageError::Storage(e));
+        }
+        self.latest = Some(Record {
+            offset: self.head,
+            len,
+            sequence,
+        });
+        self.head += padded;
+        Ok(())
+    }
+
+    /// Returns a mutable reference to the storage. Writing to it can break the records.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Return the storage.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+}
+
+/// Scans the records of the page from `start` to `end`. Returns the newest intact record in the
+/// page, and the offset after the last record in the page.
+fn scan_page<S: Storage>(
+    storage: &mut S,
+    start: usize,
+    end: usize,
+) -> Result<(Option<Record>, usize), StorageError<'static, S::Error>> {
+    let write_size = storage.write_size();
+    let mut newest: Option<Record> = None;
+    let mut offset = start;
+    while offset + HEADER_LEN <= end {
+        let mut header = [0u8; HEADER_LEN];
+        storage
+            .read(offset, &mut header)
+            .map_err(StorageError::Storage)?;
+        if header.iter().all(|&byte| byte == FlashSlice::ERASED_BYTE) {
+            // The end of the records
+            return Ok((newest, offset));
+        }
+        let len = usize::from(u16::from_le_bytes([header[0], header[1]]));
+        if len > MAX_PAYLOAD_LEN || offset + HEADER_LEN + len > end {
+            // A broken header, e.g. because the power failed while it was written. Nothing after
+            // it can be trusted, so the next record goes to the next page.
+            return Ok((newest, end));
+        }
+        let crc = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        let sequence = u32::from_le_bytes([header[6], header[7], header[8], header[9]]);
+        if payload_crc(storage, offset + HEADER_LEN, len, sequence)? == crc
+            && newest.is_none_or(|newest| sequence > newest.sequence)
+        {
+            newest = Some(Record {
+                offset,
+                len,
+                sequence,
+            });
+        }
+        offset = align_up(offset + HEADER_LEN + len, write_size);
+    }
+    Ok((newest, end))
+}
+
+/// The CRC of a record, computed over its sequence number and the payload at `offset`.
+fn payload_crc<S: Storage>(
+    storage: &mut S,
+    offset: usize,
+    len: usize,
+    sequence: u32,
+) -> Result<u32, StorageError<'static, S::Error>> {
+    let mut crc = Crc32::new();
+    crc.update(&sequence.to_le_bytes());
+    let mut chunk = [0u8; 32];
+    let mut read = 0;
+    while read < len {
+        let size = (len - read).min(chunk.len());
+        storage
+            .read(offset + read, &mut chunk[..size])
+            .map_err(StorageError::Storage)?;
+        crc.update(&chunk[..size]);
+        read += size;
+    }
+    Ok(crc.finish())
+}
+
+fn align_up(offset: usize, align: usize) -> usize {
+    match offset % align.max(1) {
+        0 => offset,
+        rest => offset + align - rest,
+    }
+}
+
+/// CRC-32 as used by Ethernet and zip, computed bit by bit to keep the code small.
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(!0)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u32::from(byte);
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xEDB8_8320 & mask);
+            }
+        }
+    }
+
+    fn finish(self) -> u32 {
+        !self.0
+    }
+}
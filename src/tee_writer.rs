@@ -0,0 +1,101 @@
+use crate::traits::CoreWrite;
+
+/// An implementation of [CoreWrite] that writes every byte to two writers, e.g. to a radio and to
+/// a log in flash.
+///
+/// Every write and [flush](CoreWrite::flush) is forwarded to both writers, also when the first
+/// writer returns an error, so a failing writer does not cause data to be lost in the other one.
+/// The errors of both writers are returned in a [TeeError].
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions, TeeWriter};
+/// let mut radio = [0u8; 8];
+/// let mut log = [0u8; 8];
+/// let mut writer = TeeWriter::new(BufferWriter::new(&mut radio), BufferWriter::new(&mut log));
+/// serialize(&(1u8, "ab"), &mut writer, DefaultOptions::new()).unwrap();
+///
+/// let (radio, log) = writer.into_inner();
+/// assert_eq!(radio.written_buffer(), &[1, 2, b'a', b'b']);
+/// assert_eq!(log.written_buffer(), &[1, 2, b'a', b'b']);
+/// ```
+pub struct TeeWriter<A: CoreWrite, B: CoreWrite> {
+    first: A,
+    second: B,
+}
+
+impl<A: CoreWrite, B: CoreWrite> TeeWriter<A, B> {
+    /// Create a new writer that writes to both `first` and `second`.
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// A reference to the first writer.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// A reference to the second writer.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Return both inner writers.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+/// An error that is thrown when writing to a [TeeWriter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TeeError<A, B> {
+    /// The first writer returned an error. The bytes were written to the second writer.
+    First(A),
+    /// The second writer returned an error. The bytes were written to the first writer.
+    Second(B),
+    /// Both writers returned an error.
+    Both(A, B),
+}
+
+impl<A, B> TeeError<A, B> {
+    fn combine(first: Result<(), A>, second: Result<(), B>) -> Result<(), Self> {
+        match (first, second) {
+            (Ok(()), Ok(())) => Ok(()),
+            (Err(a), Ok(())) => Err(TeeError::First(a)),
+            (Ok(()), Err(b)) => Err(TeeError::Second(b)),
+            (Err(a), Err(b)) => Err(TeeError::Both(a, b)),
+        }
+    }
+}
+
+impl<A: CoreWrite, B: CoreWrite> CoreWrite for TeeWriter<A, B> {
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        TeeError::combine(self.first.write(val), self.second.write(val))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        TeeError::combine(self.first.flush(), self.second.flush())
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        TeeError::combine(self.first.write_all(val), self.second.write_all(val))
+    }
+}
+
+impl<A: CoreWrite, B: CoreWrite> CoreWrite for &mut TeeWriter<A, B> {
+    type Error = TeeError<A::Error, B::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
@@ -0,0 +1,226 @@
+//! Self-checks for (de)serialization, to run in tests on the target device.
+//!
+//! The size that [serialize_size] measures, the bytes that [serialize] writes and the bytes that
+//! [deserialize_with_remaining] reads all go through different code paths. A custom `Serialize`
+//! implementation, or options that differ between the two sides, can make them disagree. This
+//! module checks that they agree for a given value, without an allocator.
+//!
+//! ```
+//! # extern crate serde_derive;
+//! # use serde_derive::{Deserialize, Serialize};
+//! # use bincode_core::testing::verify_roundtrip;
+//! # use bincode_core::DefaultOptions;
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Reading {
+//!     sensor: u16,
+//!     value: f32,
+//! }
+//!
+//! let mut buffer = [0u8; 16];
+//! let reading = Reading { sensor: 300, value: 21.5 };
+//! let report = verify_roundtrip(&reading, &mut buffer, DefaultOptions::new()).unwrap();
+//! assert!(report.is_ok(), "{:?}", report);
+//! assert_eq!(report.written_len, 7);
+//! ```
+//!
+//! With the `arbitrary` feature, [fuzz_roundtrip] and [fuzz_decode] run the same checks on values
+//! and bytes from a fuzzer, e.g. in a `cargo fuzz` target:
+//!
+//! ```ignore
+//! #![no_main]
+//! libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+//!     bincode_core::testing::fuzz_roundtrip::<my_protocol::Message>(data);
+//! });
+//! ```
+
+use crate::buffer_writer::BufferWriter;
+use crate::config::Options;
+use crate::deserialize::{deserialize_with_remaining, DeserializeError};
+use crate::serialize::{serialize, serialize_size, SerializeError};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "arbitrary")]
+use crate::encoding_spec::{with_config, Config};
+#[cfg(feature = "arbitrary")]
+use alloc::vec::Vec;
+#[cfg(feature = "arbitrary")]
+use arbitrary::{Arbitrary, Unstructured};
+#[cfg(feature = "arbitrary")]
+use core::fmt::Debug;
+
+/// The result of [verify_roundtrip].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RoundtripReport {
+    /// The size that [serialize_size] returned.
+    pub measured_len: usize,
+    /// The amount of bytes that [serialize] wrote.
+    pub written_len: usize,
+    /// The amount of bytes that were read to deserialize the value again.
+    pub read_len: usize,
+    /// Whether the deserialized value is equal to the original value.
+    pub equal: bool,
+}
+
+impl RoundtripReport {
+    /// Returns `true` if all lengths are the same, and the deserialized value is equal to the
+    /// original value.
+    pub fn is_ok(&self) -> bool {
+        self.measured_len == self.written_len && self.read_len == self.written_len && self.equal
+    }
+}
+
+/// An error that is thrown by [verify_roundtrip] when one of the steps fails.
+#[derive(Debug)]
+pub enum RoundtripError<'a> {
+    /// [serialize_size] returned an error.
+    Measure(SerializeError<()>),
+    /// The value does not fit in the buffer.
+    BufferTooSmall {
+        /// The length of the buffer
+        len: usize,
+    },
+    /// [serialize] returned an error.
+    Serialize(SerializeError<()>),
+    /// The serialized bytes could not be deserialized.
+    Deserialize(DeserializeError<'a, &'a [u8]>),
+}
+
+/// Serialize `value` into `buffer`, measure it with [serialize_size] and deserialize it again, and
+/// report whether all steps agree.
+///
+/// A mismatch is not an error, it is reported in the returned [RoundtripReport]. An error is only
+/// returned if one of the steps fails completely.
+pub fn verify_roundtrip<'a, T, O>(
+    value: &T,
+    buffer: &'a mut [u8],
+    options: O,
+) -> Result<RoundtripReport, RoundtripError<'a>>
+where
+    T: Serialize + Deserialize<'a> + PartialEq,
+    O: Options + Clone,
+{
+    let measured_len = serialize_size(value, options.clone()).map_err(RoundtripError::Measure)?;
+
+    let len = buffer.len();
+    let mut writer = BufferWriter::new(&mut *buffer);
+    serialize(value, &mut writer, options.clone()).map_err(|e| match e {
+        SerializeError::Write(_) => RoundtripError::BufferTooSmall { len },
+        e => RoundtripError::Serialize(e.map_write(|_| ())),
+    })?;
+    let written_len = writer.written_len();
+
+    let buffer: &'a [u8] = buffer;
+    let (decoded, rest): (T, _) = deserialize_with_remaining(&buffer[..written_len], options)
+        .map_err(RoundtripError::Deserialize)?;
+
+    Ok(RoundtripReport {
+        measured_len,
+        written_len,
+        read_len: written_len - rest.len(),
+        equal: decoded == *value,
+    })
+}
+
+/// Build a `T` from the fuzzer input `data`, and check that it survives a round trip with
+/// [verify_roundtrip], with one of the [encoding_spec](crate::encoding_spec) configurations that
+/// is also picked from `data`.
+///
+/// Values that cannot be serialized (e.g. because a `Serialize` implementation returns an error)
+/// are skipped.
+///
+/// # Panics
+///
+/// Panics with a description of the failed step when the round trip does not match, so the
+/// fuzzer records the input.
+#[cfg(feature = "arbitrary")]
+pub fn fuzz_roundtrip<'a, T>(data: &'a [u8])
+where
+    T: Arbitrary<'a> + Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+{
+    let mut data = Unstructured::new(data);
+    let (config, value) = match (data.choose(&Config::ALL), T::arbitrary(&mut data)) {
+        (Ok(config), Ok(value)) => (*config, value),
+        _ => return,
+    };
+    with_config!(config, |options| roundtrip(&value, config, options))
+}
+
+#[cfg(feature = "arbitrary")]
+fn roundtrip<T, O>(value: &T, config: Config, options: O)
+where
+    T: Serialize + for<'de> Deserialize<'de> + PartialEq + Debug,
+    O: Options + Clone,
+{
+    let len = match serialize_size(value, options.clone()) {
+        Ok(len) => len,
+        Err(_) => return,
+    };
+    let mut buffer = alloc::vec![0u8; len];
+    match verify_roundtrip(value, &mut buffer, options) {
+        Ok(report) if report.is_ok() => {}
+        Ok(report) => panic!("{:?} with {:?}: {:?}", value, config, report),
+        Err(e) => panic!("{:?} with {:?}: {:?}", value, config, e),
+    }
+}
+
+/// Deserialize a `T` from the fuzzer input `data`, with one of the
+/// [encoding_spec](crate::encoding_spec) configurations that is picked from the first byte.
+///
+/// Most inputs fail to deserialize, which is fine. A value that does deserialize is serialized
+/// again, and the bytes are deserialized and serialized once more, which has to give the same
+/// bytes. The bytes are compared instead of the values, so `T` does not need `PartialEq`, and a
+/// NaN float does not fail the check.
+///
+/// # Panics
+///
+/// Panics when a deserialized value cannot be serialized, or its bytes change between round
+/// trips, so the fuzzer records the input.
+#[cfg(feature = "arbitrary")]
+pub fn fuzz_decode<T>(data: &[u8])
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+{
+    let mut data = Unstructured::new(data);
+    let config = match data.choose(&Config::ALL) {
+        Ok(config) => *config,
+        Err(_) => return,
+    };
+    let bytes = data.take_rest();
+    with_config!(config, |options| decode::<T, _>(bytes, config, options))
+}
+
+#[cfg(feature = "arbitrary")]
+fn decode<T, O>(bytes: &[u8], config: Config, options: O)
+where
+    T: Serialize + for<'de> Deserialize<'de>,
+    O: Options + Copy,
+{
+    let value: T = match crate::deserialize(bytes, options) {
+        Ok(value) => value,
+        Err(_) => return,
+    };
+    let first = encode(&value, config, options);
+    let again: T = match crate::deserialize(&first[..], options) {
+        Ok(value) => value,
+        Err(e) => panic!("{:?}: {:?} does not deserialize: {:?}", config, first, e),
+    };
+    let second = encode(&again, config, options);
+    assert_eq!(
+        first, second,
+        "{:?}: the bytes changed between round trips",
+        config
+    );
+}
+
+#[cfg(feature = "arbitrary")]
+fn encode<T: Serialize, O: Options + Copy>(value: &T, config: Config, options: O) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    if let Err(e) = crate::serialize(value, &mut bytes, options) {
+        panic!(
+            "{:?}: a deserialized value does not serialize: {:?}",
+            config, e
+        );
+    }
+    bytes
+}
@@ -1,6 +1,8 @@
 use core::str;
 
-#[cfg(feature = "std")]
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
 use std::error::Error as StdError;
 
 /// A target that can be read from. This is similar to `std::io::Read`, but the std trait is not
@@ -15,6 +17,15 @@ use std::error::Error as StdError;
 /// The easiest way to implement this would be by reading data into a fixed-size array and reading
 /// from there.
 ///
+/// # Borrowing
+///
+/// [forward_str](CoreRead::forward_str), [forward_bytes](CoreRead::forward_bytes) and
+/// [read_range](CoreRead::read_range) hand out slices with the lifetime `'a`. Those slices MUST
+/// stay valid and unchanged for all of `'a`, even after more bytes are read, so they cannot point
+/// into a buffer that the reader reuses. Readers without such storage, like a serial port, return
+/// an error from these methods instead. Wrap those in a [BufferedReader](crate::BufferedReader) to
+/// read borrowed values into a scratch buffer.
+///
 /// This trait does not support async reading yet. Reads are expected to be blocking.
 pub trait CoreRead<'a> {
     /// The error that this reader can encounter
@@ -25,6 +36,27 @@ pub trait CoreRead<'a> {
     /// buffer an error MUST be returned.
     fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error>;
 
+    /// Reads a single byte from the reader.
+    ///
+    /// The default implementation calls [fill](CoreRead::fill) with a buffer of one byte.
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        let mut buffer = [0u8];
+        self.fill(&mut buffer)?;
+        Ok(buffer[0])
+    }
+
+    /// Reads the next `len` bytes as a slice that borrows from the reader. The slice MUST be
+    /// persistent, see [Borrowing](CoreRead#borrowing).
+    ///
+    /// The default implementation calls [forward_bytes](CoreRead::forward_bytes), and returns an
+    /// error if the bytes are not passed on as borrowed bytes.
+    fn read_range(&mut self, len: usize) -> Result<&'a [u8], Self::Error>
+    where
+        Self::Error: serde::de::Error,
+    {
+        self.forward_bytes(len, BorrowedBytes)
+    }
+
     /// Forward a string slice from the reader on to the given visitor.
     ///
     /// If allocations are not available on the system, the bytes forwarded MUST be a reference to a
@@ -44,42 +76,82 @@ pub trait CoreRead<'a> {
     fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'a>;
+
+    /// Returns the amount of bytes that are left in the reader, if that is known. This is used to
+    /// detect the end of a stream of values (see [deserialize_iter](crate::deserialize_iter)), and
+    /// to reject trailing bytes (see [Options::reject_trailing_bytes](crate::config::Options::reject_trailing_bytes)).
+    ///
+    /// The default implementation returns `None`, for readers like a serial port that have no
+    /// known end.
+    fn remaining_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns the next `len` bytes without consuming them, if the reader can look ahead. This can
+    /// be used to inspect a message header before deciding how to decode the message.
+    ///
+    /// The default implementation returns `None`, for readers that cannot look ahead.
+    /// Implementations also return `None` when fewer than `len` bytes are left.
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        let _ = len;
+        None
+    }
+
+    /// Read `len` bytes into a newly allocated `Vec`. This is used to deserialize owned `String`
+    /// and `Vec<u8>` values, and works for readers that cannot borrow.
+    ///
+    /// The default implementation allocates a zeroed buffer and calls [fill](CoreRead::fill).
+    #[cfg(feature = "alloc")]
+    fn read_owned(&mut self, len: usize) -> Result<alloc::vec::Vec<u8>, Self::Error> {
+        let mut buffer = alloc::vec![0; len];
+        self.fill(&mut buffer)?;
+        Ok(buffer)
+    }
 }
 
 impl<'a> CoreRead<'a> for &'a [u8] {
     type Error = SliceReadError;
 
+    #[inline]
     fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        if buffer.len() > self.len() {
-            return Err(SliceReadError::EndOfSlice);
-        }
-        buffer.copy_from_slice(&self[..buffer.len()]);
-        *self = &self[buffer.len()..];
+        let (head, rest) = split_slice(self, buffer.len())?;
+        buffer.copy_from_slice(head);
+        *self = rest;
         Ok(())
     }
 
+    #[inline]
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        let (first, rest) = self.split_first().ok_or(SliceReadError::EndOfSlice)?;
+        *self = rest;
+        Ok(*first)
+    }
+
+    #[inline]
+    fn read_range(&mut self, len: usize) -> Result<&'a [u8], Self::Error> {
+        let (result, rest) = split_slice(self, len)?;
+        *self = rest;
+        Ok(result)
+    }
+
+    #[inline]
     fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'a>,
     {
-        if len > self.len() {
-            return Err(SliceReadError::EndOfSlice);
-        }
-        let result = &self[..len];
-        *self = &self[len..];
+        let (result, rest) = split_slice(self, len)?;
+        *self = rest;
 
         visitor.visit_borrowed_bytes(result)
     }
 
+    #[inline]
     fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'a>,
     {
-        if len > self.len() {
-            return Err(SliceReadError::EndOfSlice);
-        }
-        let result = &self[..len];
-        *self = &self[len..];
+        let (result, rest) = split_slice(self, len)?;
+        *self = rest;
 
         let string = match str::from_utf8(result) {
             Ok(s) => s,
@@ -88,19 +160,102 @@ impl<'a> CoreRead<'a> for &'a [u8] {
 
         visitor.visit_borrowed_str(string)
     }
+
+    #[inline]
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    #[inline]
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.get(..len)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn read_owned(&mut self, len: usize) -> Result<alloc::vec::Vec<u8>, Self::Error> {
+        let (result, rest) = split_slice(self, len)?;
+        *self = rest;
+        Ok(result.to_vec())
+    }
+}
+
+/// Splits the first `len` bytes off `slice`. Unlike indexing or `split_at`, this has a single
+/// bounds check and no panic path, so the reads of `&[u8]` stay small in hot loops.
+#[inline(always)]
+fn split_slice(slice: &[u8], len: usize) -> Result<(&[u8], &[u8]), SliceReadError> {
+    match (slice.get(..len), slice.get(len..)) {
+        (Some(head), Some(rest)) => Ok((head, rest)),
+        _ => Err(SliceReadError::EndOfSlice),
+    }
+}
+
+/// A visitor that only accepts borrowed bytes, for the default implementation of
+/// [CoreRead::read_range].
+struct BorrowedBytes;
+
+impl<'a> serde::de::Visitor<'a> for BorrowedBytes {
+    type Value = &'a [u8];
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        formatter.write_str("borrowed bytes")
+    }
+
+    fn visit_borrowed_bytes<E: serde::de::Error>(self, v: &'a [u8]) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+}
+
+/// A [CoreRead] reader that can save its position, and go back to it later.
+///
+/// This is used by [Deserializer::try_deserialize](crate::Deserializer::try_deserialize) to roll
+/// back the reader when a value fails to decode, so a different type can be tried on the same
+/// bytes.
+///
+/// This trait is implemented for `&[u8]`.
+pub trait CheckpointRead<'a>: CoreRead<'a> {
+    /// A saved position of the reader.
+    type Mark: Clone;
+
+    /// Save the current position of the reader.
+    fn mark(&self) -> Self::Mark;
+
+    /// Go back to a position that was saved with [mark](CheckpointRead::mark). Any bytes that were
+    /// read since then will be read again.
+    fn reset(&mut self, mark: Self::Mark);
+}
+
+impl<'a> CheckpointRead<'a> for &'a [u8] {
+    type Mark = &'a [u8];
+
+    fn mark(&self) -> Self::Mark {
+        self
+    }
+
+    fn reset(&mut self, mark: Self::Mark) {
+        *self = mark;
+    }
 }
 
-/// An error that is thrown when reading from a slice.
+/// An error that is thrown when reading from a slice, from a
+/// [ChainedSlices](crate::ChainedSlices), or from an [IterReader](crate::IterReader).
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SliceReadError {
     /// Tried reading more bytes than the slice contains.
     EndOfSlice,
+    /// The bytes read from the slice are not valid UTF8.
     InvalidUtf8,
+    /// The visitor rejected the borrowed `&str` or `&[u8]`.
+    Custom,
+    /// A `&str` or `&[u8]` is split over multiple segments of a
+    /// [ChainedSlices](crate::ChainedSlices), or read from an [IterReader](crate::IterReader), and
+    /// does not fit in the remaining scratch buffer.
+    ScratchTooSmall,
 }
 
 impl serde::de::Error for SliceReadError {
     fn custom<T: core::fmt::Display>(_cause: T) -> Self {
-        panic!("Custom error thrown: {}", _cause);
+        SliceReadError::Custom
     }
 }
 
@@ -110,5 +265,5 @@ impl core::fmt::Display for SliceReadError {
     }
 }
 
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "core-error"))]
 impl StdError for SliceReadError {}
@@ -17,18 +17,53 @@ pub trait CoreWrite {
         Ok(())
     }
 
-    /// Helper function to write multiple bytes to a writer. The default implementation calls
-    /// [write] with each byte in the slice.
+    /// Write multiple bytes to the writer. The default implementation calls [write] with each byte
+    /// in the slice.
+    ///
+    /// The serializer writes every value (and every `&str` or `&[u8]`) with a single call to this
+    /// function, so writers that can transfer a chunk of bytes at once (e.g. with DMA) should
+    /// override it. See [ChunkWriter](crate::ChunkWriter) to combine small writes into larger
+    /// chunks.
     fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
         for byte in val {
             self.write(*byte)?;
         }
         Ok(())
     }
+
+    /// Returns this writer as a [CoreWriteSeek], if it is one. With
+    /// [CountedStructs](crate::config::CountedStructs), the serializer uses this to write the byte
+    /// length of a struct after its fields, instead of measuring every struct before it is written.
+    ///
+    /// The default implementation returns `None`. Writers that implement [CoreWriteSeek] should
+    /// return `Some(self)`.
+    fn as_seek(&mut self) -> Option<&mut dyn CoreWriteSeek<Error = Self::Error>> {
+        None
+    }
+}
+
+/// A [CoreWrite] that can go back and change bytes that were already written, like a buffer in
+/// RAM. This is used by [serialize_samples](crate::serialize_samples) to write the length of a
+/// sequence after its elements.
+///
+/// Positions are the amount of bytes that were written before them, starting at 0 when the writer
+/// was created.
+pub trait CoreWriteSeek: CoreWrite {
+    /// The position of the next byte that is written.
+    fn position(&self) -> usize;
+
+    /// Replace the bytes at `position..position + val.len()`, which must have been written
+    /// already.
+    fn write_at(&mut self, position: usize, val: &[u8]) -> Result<(), Self::Error>;
+
+    /// Move the bytes from `from` up to the current [position](CoreWriteSeek::position), so they
+    /// start at `to`. The position moves by the same amount, so this grows (or shrinks) the space
+    /// before the moved bytes.
+    fn move_tail(&mut self, from: usize, to: usize) -> Result<(), Self::Error>;
 }
 
-// Added because there are situations where you want to be able to return a `SerializeError<()>`.
-// SerializeError<W> requires W: CoreWrite, because some variants require W::Error.
+// A writer that discards everything. Its error is `()`, like the error in `SerializeError<()>` of
+// functions that do not write, e.g. `serialize_size`.
 impl CoreWrite for () {
     type Error = ();
     fn write(&mut self, _val: u8) -> Result<(), ()> {
@@ -37,12 +72,17 @@ impl CoreWrite for () {
 }
 
 #[cfg(feature = "alloc")]
-impl<'a> CoreWrite for &'a mut alloc::vec::Vec<u8> {
+impl CoreWrite for &mut alloc::vec::Vec<u8> {
     type Error = ();
     fn write(&mut self, val: u8) -> Result<(), ()> {
         self.push(val);
         Ok(())
     }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), ()> {
+        self.extend_from_slice(val);
+        Ok(())
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -52,4 +92,9 @@ impl CoreWrite for alloc::vec::Vec<u8> {
         self.push(val);
         Ok(())
     }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), ()> {
+        self.extend_from_slice(val);
+        Ok(())
+    }
 }
@@ -0,0 +1,208 @@
+use super::CoreRead;
+use bbqueue::{Consumer, GrantR};
+use core::str;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// An error that is thrown when reading from a `bbqueue::Consumer` or a [GrantReader].
+///
+/// Like [SpscReadError](crate::SpscReadError), this is meant for an interrupt handler that commits
+/// the received bytes, and a main loop that deserializes them. Reads never block, so a value
+/// should only be read when it is complete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BbqReadError {
+    /// The queue or grant does not contain enough bytes. Nothing is released by the failed read.
+    NotEnoughData,
+    /// Another read grant of the consumer is still held.
+    GrantInProgress,
+    /// Tried to read a `&str` or `&[u8]` that wraps around the end of the queue, so it is not in
+    /// one piece. Read values that borrow from a [GrantReader].
+    CannotBorrow,
+    /// The bytes read for a `&str` are not valid UTF8.
+    InvalidUtf8,
+    /// The visitor rejected the `&str` or `&[u8]`. This is returned when a `&'a str` or `&'a [u8]`
+    /// is read from a `bbqueue::Consumer`, because the bytes are released after they are visited.
+    Custom,
+}
+
+impl From<bbqueue::Error> for BbqReadError {
+    fn from(error: bbqueue::Error) -> Self {
+        match error {
+            bbqueue::Error::GrantInProgress => BbqReadError::GrantInProgress,
+            _ => BbqReadError::NotEnoughData,
+        }
+    }
+}
+
+impl serde::de::Error for BbqReadError {
+    fn custom<T: core::fmt::Display>(_cause: T) -> Self {
+        BbqReadError::Custom
+    }
+}
+
+impl core::fmt::Display for BbqReadError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for BbqReadError {}
+
+/// Reads `len` bytes from the front of the queue with `read`, and releases them if it succeeds.
+fn with_grant<const N: usize, T>(
+    consumer: &mut Consumer<'_, N>,
+    len: usize,
+    read: impl FnOnce(&[u8], &[u8]) -> Result<T, BbqReadError>,
+) -> Result<T, BbqReadError> {
+    let grant = match consumer.split_read() {
+        Ok(grant) => grant,
+        // An empty queue has no grant, but a read of 0 bytes still succeeds
+        Err(bbqueue::Error::InsufficientSize) if len == 0 => return read(&[], &[]),
+        Err(e) => return Err(e.into()),
+    };
+    // Dropping the grant without releasing it leaves the bytes in the queue
+    if grant.combined_len() < len {
+        return Err(BbqReadError::NotEnoughData);
+    }
+    let (first, second) = grant.bufs();
+    let value = read(first, second)?;
+    grant.release(len);
+    Ok(value)
+}
+
+impl<'a, const N: usize> CoreRead<'a> for &mut Consumer<'_, N> {
+    type Error = BbqReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        with_grant(self, buffer.len(), |first, second| {
+            let split = buffer.len().min(first.len());
+            let (head, tail) = buffer.split_at_mut(split);
+            head.copy_from_slice(&first[..split]);
+            tail.copy_from_slice(&second[..tail.len()]);
+            Ok(())
+        })
+    }
+
+    /// Passes the `&str` on to `visitor.visit_str` if it is in one piece in the queue. The bytes
+    /// are released afterwards, so this works for owned strings like `heapless::String`, but not
+    /// for `&'a str`.
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        with_grant(self, len, |first, _| {
+            let bytes = first.get(..len).ok_or(BbqReadError::CannotBorrow)?;
+            let string = str::from_utf8(bytes).map_err(|_| BbqReadError::InvalidUtf8)?;
+            visitor.visit_str(string)
+        })
+    }
+
+    /// Passes the `&[u8]` on to `visitor.visit_bytes` if it is in one piece in the queue. The
+    /// bytes are released afterwards, so this does not work for `&'a [u8]`.
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        with_grant(self, len, |first, _| {
+            let bytes = first.get(..len).ok_or(BbqReadError::CannotBorrow)?;
+            visitor.visit_bytes(bytes)
+        })
+    }
+}
+
+/// A [CoreRead] reader over the bytes of a `bbqueue` read grant, which can borrow `&str` and
+/// `&[u8]` values from the queue.
+///
+/// The reader borrows the grant, so the borrowed values have to be dropped before the grant is
+/// released. Release [read_len](GrantReader::read_len) bytes to remove the values that were read
+/// from the queue.
+///
+/// ```
+/// # use bincode_core::{DefaultOptions, Deserializer, GrantReader};
+/// # use serde::Deserialize;
+/// # let buffer: bbqueue::BBBuffer<16> = bbqueue::BBBuffer::new();
+/// # let (mut producer, mut consumer) = buffer.try_split().unwrap();
+/// # let mut write = producer.grant_exact(4).unwrap();
+/// # write.copy_from_slice(&[3, b'a', b'b', b'c']);
+/// # write.commit(4);
+/// let grant = consumer.read().unwrap();
+/// let mut deserializer = Deserializer::new(GrantReader::new(&grant), DefaultOptions::new());
+/// let name = <&str>::deserialize(&mut deserializer).unwrap();
+/// assert_eq!(name, "abc");
+/// let read = deserializer.reader().read_len();
+/// grant.release(read);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GrantReader<'g> {
+    remaining: &'g [u8],
+    len: usize,
+}
+
+impl<'g> GrantReader<'g> {
+    /// Create a reader over the bytes of `grant`.
+    pub fn new<const N: usize>(grant: &'g GrantR<'_, N>) -> Self {
+        let bytes = grant.buf();
+        GrantReader {
+            remaining: bytes,
+            len: bytes.len(),
+        }
+    }
+
+    /// The amount of bytes that were read from the grant.
+    pub fn read_len(&self) -> usize {
+        self.len - self.remaining.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'g [u8], BbqReadError> {
+        let remaining = self.remaining;
+        match (remaining.get(..len), remaining.get(len..)) {
+            (Some(head), Some(rest)) => {
+                self.remaining = rest;
+                Ok(head)
+            }
+            _ => Err(BbqReadError::NotEnoughData),
+        }
+    }
+}
+
+impl<'g> CoreRead<'g> for GrantReader<'g> {
+    type Error = BbqReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        buffer.copy_from_slice(self.take(buffer.len())?);
+        Ok(())
+    }
+
+    fn read_range(&mut self, len: usize) -> Result<&'g [u8], Self::Error> {
+        self.take(len)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'g>,
+    {
+        let bytes = self.take(len)?;
+        let string = str::from_utf8(bytes).map_err(|_| BbqReadError::InvalidUtf8)?;
+        visitor.visit_borrowed_str(string)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'g>,
+    {
+        visitor.visit_borrowed_bytes(self.take(len)?)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.remaining.len())
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        self.remaining.get(..len)
+    }
+}
@@ -0,0 +1,690 @@
+use super::{CoreRead, CoreWrite};
+use crate::config::Options;
+use crate::deserialize::{deserialize, DeserializeError};
+use embedded_hal::blocking::{i2c, spi};
+use embedded_hal::serial;
+use embedded_hal::timer::CountDown;
+
+/// Adapter that allows (de)serializing directly over a blocking `embedded_hal` SPI bus.
+///
+/// Reading is done with [spi::Transfer]: the buffer is filled with `0x00` dummy bytes and replaced
+/// by the bytes clocked in from the device. Writing is done with [spi::Write].
+///
+/// Chip select and any command bytes (e.g. the `READ` opcode and address of a SPI EEPROM) have to
+/// be sent before (de)serializing, this adapter only moves the payload.
+pub struct SpiAdapter<SPI> {
+    spi: SPI,
+}
+
+impl<SPI> SpiAdapter<SPI> {
+    /// Create a new adapter for the given SPI bus.
+    pub fn new(spi: SPI) -> Self {
+        Self { spi }
+    }
+
+    /// Release the SPI bus.
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+}
+
+/// Adapter that allows (de)serializing directly from a device on a blocking `embedded_hal` I2C
+/// bus.
+///
+/// Every read or write is a separate I2C transaction to `address`. Devices like I2C EEPROM and FRAM
+/// continue at their internal address counter on every transaction, so the memory address has to
+/// be set before (de)serializing.
+pub struct I2cAdapter<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C> I2cAdapter<I2C> {
+    /// Create a new adapter for the device at `address` on the given I2C bus.
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Self { i2c, address }
+    }
+
+    /// Release the I2C bus.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+/// Adapter that allows deserializing from a non-blocking `embedded_hal` serial port, giving up when
+/// no byte is received in time.
+///
+/// A UART that drops a byte would otherwise leave the deserializer waiting for it forever. The
+/// timer is restarted with `timeout` for every byte, so a message of any length can be read as
+/// long as its bytes keep coming in. When the timer expires before the next byte is received,
+/// [HalReadError::Timeout] is returned.
+pub struct TimeoutReader<R, C: CountDown> {
+    serial: R,
+    timer: C,
+    timeout: C::Time,
+}
+
+impl<R, C: CountDown> TimeoutReader<R, C> {
+    /// Create a new adapter that reads from `serial`, and waits at most `timeout` for every byte.
+    pub fn new(serial: R, timer: C, timeout: impl Into<C::Time>) -> Self {
+        Self {
+            serial,
+            timer,
+            timeout: timeout.into(),
+        }
+    }
+
+    /// Release the serial port and the timer.
+    pub fn release(self) -> (R, C) {
+        (self.serial, self.timer)
+    }
+}
+
+/// Adapter that allows polling deserialization from a non-blocking `embedded_hal` serial port,
+/// e.g. from a task of a cooperative scheduler.
+///
+/// Instead of waiting for the next byte, [poll](NbReader::poll) returns
+/// [DeserializeError::WouldBlock] when the port has no byte available. The bytes that were received
+/// so far are kept in the caller-provided `buffer`, and the next `poll` decodes the value again
+/// from the start, reading the kept bytes before it asks the port for new ones. The buffer has to
+/// hold the largest message; if it is full, [DeserializeError::ScratchTooSmall] is returned.
+///
+/// Like the other adapters, `NbReader` cannot read strings and byte slices.
+///
+/// ```
+/// # use bincode_core::{DefaultOptions, DeserializeError, NbReader};
+/// # use embedded_hal::serial;
+/// # struct Uart(&'static [u8]);
+/// # impl serial::Read<u8> for Uart {
+/// #     type Error = ();
+/// #     fn read(&mut self) -> nb::Result<u8, ()> {
+/// #         let (&byte, rest) = self.0.split_first().ok_or(nb::Error::WouldBlock)?;
+/// #         self.0 = rest;
+/// #         Ok(byte)
+/// #     }
+/// # }
+/// let mut buffer = [0u8; 16];
+/// let mut reader = NbReader::new(Uart(&[7, 251]), &mut buffer);
+///
+/// // The port has only received part of the value
+/// let result = reader.poll::<(u8, u16), _>(DefaultOptions::new());
+/// assert!(matches!(result, Err(DeserializeError::WouldBlock)));
+/// assert_eq!(reader.buffered_len(), 2);
+///
+/// reader.get_mut().0 = &[0xE8, 0x03];
+/// let value = reader.poll::<(u8, u16), _>(DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (7, 1000));
+/// ```
+pub struct NbReader<'buf, R> {
+    serial: R,
+    buffer: &'buf mut [u8],
+    /// The amount of bytes of the current value that were received
+    len: usize,
+    /// The read position of the current attempt to decode the value
+    pos: usize,
+}
+
+impl<'buf, R: serial::Read<u8>> NbReader<'buf, R> {
+    /// Create a new adapter that reads from `serial`, and keeps the bytes of a value that is not
+    /// complete yet in `buffer`.
+    pub fn new(serial: R, buffer: &'buf mut [u8]) -> Self {
+        Self {
+            serial,
+            buffer,
+            len: 0,
+            pos: 0,
+        }
+    }
+
+    /// The amount of bytes that were received, but are not part of a decoded value yet.
+    pub fn buffered_len(&self) -> usize {
+        self.len
+    }
+
+    /// Discard all bytes that are not part of a decoded value yet, e.g. to resynchronize after
+    /// a line error.
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+
+    /// Get a mutable reference to the serial port.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.serial
+    }
+
+    /// Release the serial port and the buffer.
+    pub fn release(self) -> (R, &'buf mut [u8]) {
+        (self.serial, self.buffer)
+    }
+
+    /// Try to decode a value from the bytes received so far, and the bytes that the port has
+    /// available.
+    ///
+    /// Returns [DeserializeError::WouldBlock] if the port runs out of bytes before the value is
+    /// complete. Any other error discards the received bytes.
+    pub fn poll<T, O: Options>(&mut self, options: O) -> Result<T, DeserializeError<'static, Self>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.pos = 0;
+        let result = deserialize(&mut *self, options)
+            .map_err(|e| e.map_read(DeserializeError::Read))
+            .map_err(|e| e.map_would_block(|e| matches!(e, HalReadError::WouldBlock)));
+        match result {
+            Err(DeserializeError::WouldBlock) => {}
+            Err(DeserializeError::Read(HalReadError::BufferFull)) => {
+                self.len = 0;
+                return Err(DeserializeError::ScratchTooSmall);
+            }
+            Err(_) => self.len = 0,
+            Ok(_) => {
+                self.buffer.copy_within(self.pos..self.len, 0);
+                self.len -= self.pos;
+            }
+        }
+        result
+    }
+}
+
+/// An error that is thrown when reading from a [SpiAdapter], [I2cAdapter] or [TimeoutReader].
+///
+/// The bus error does not have to implement `Debug`, so HALs with any error type can be used. The
+/// `Debug` output only contains the type of the bus error, match on [Bus](HalReadError::Bus) to
+/// inspect it.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HalReadError<E> {
+    /// The underlying bus returned an error.
+    Bus(E),
+    /// Tried to deserialize a `&str` or `&[u8]`. The adapters read straight from the bus and have no
+    /// buffer to borrow the data from. Wrap the adapter in a [BufferedReader](crate::BufferedReader)
+    /// to deserialize borrowed data.
+    CannotBorrow,
+    /// No byte was received before the timer of a [TimeoutReader] expired.
+    Timeout,
+    /// The serial port of an [NbReader] has no byte available yet.
+    /// [poll](NbReader::poll) returns this as [DeserializeError::WouldBlock].
+    WouldBlock,
+    /// The buffer of an [NbReader] is full. [poll](NbReader::poll) returns this as
+    /// [DeserializeError::ScratchTooSmall].
+    BufferFull,
+}
+
+impl<E> core::fmt::Debug for HalReadError<E> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            HalReadError::Bus(_) => write!(fmt, "Bus({})", core::any::type_name::<E>()),
+            HalReadError::CannotBorrow => write!(fmt, "CannotBorrow"),
+            HalReadError::Timeout => write!(fmt, "Timeout"),
+            HalReadError::WouldBlock => write!(fmt, "WouldBlock"),
+            HalReadError::BufferFull => write!(fmt, "BufferFull"),
+        }
+    }
+}
+
+impl<'a, SPI> CoreRead<'a> for SpiAdapter<SPI>
+where
+    SPI: spi::Transfer<u8>,
+{
+    type Error = HalReadError<SPI::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        buffer.fill(0);
+        self.spi.transfer(buffer).map_err(HalReadError::Bus)?;
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+}
+
+impl<'a, SPI> CoreRead<'a> for &mut SpiAdapter<SPI>
+where
+    SPI: spi::Transfer<u8>,
+{
+    type Error = HalReadError<SPI::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
+
+impl<SPI> CoreWrite for SpiAdapter<SPI>
+where
+    SPI: spi::Write<u8>,
+{
+    type Error = HalWriteError<SPI::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.spi.write(&[val]).map_err(HalWriteError::Bus)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.spi.write(val).map_err(HalWriteError::Bus)
+    }
+}
+
+impl<SPI> CoreWrite for &mut SpiAdapter<SPI>
+where
+    SPI: spi::Write<u8>,
+{
+    type Error = HalWriteError<SPI::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+impl<'a, I2C> CoreRead<'a> for I2cAdapter<I2C>
+where
+    I2C: i2c::Read,
+{
+    type Error = HalReadError<I2C::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c
+            .read(self.address, buffer)
+            .map_err(HalReadError::Bus)
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+}
+
+impl<'a, I2C> CoreRead<'a> for &mut I2cAdapter<I2C>
+where
+    I2C: i2c::Read,
+{
+    type Error = HalReadError<I2C::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
+
+impl<I2C> CoreWrite for I2cAdapter<I2C>
+where
+    I2C: i2c::Write,
+{
+    type Error = HalWriteError<I2C::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.i2c
+            .write(self.address, &[val])
+            .map_err(HalWriteError::Bus)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.i2c
+            .write(self.address, val)
+            .map_err(HalWriteError::Bus)
+    }
+}
+
+impl<I2C> CoreWrite for &mut I2cAdapter<I2C>
+where
+    I2C: i2c::Write,
+{
+    type Error = HalWriteError<I2C::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+impl<'a, R, C> CoreRead<'a> for TimeoutReader<R, C>
+where
+    R: serial::Read<u8>,
+    C: CountDown,
+    C::Time: Clone,
+{
+    type Error = HalReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buffer {
+            self.timer.start(self.timeout.clone());
+            *byte = loop {
+                match self.serial.read() {
+                    Ok(byte) => break byte,
+                    Err(nb::Error::Other(e)) => return Err(HalReadError::Bus(e)),
+                    Err(nb::Error::WouldBlock) => {
+                        if self.timer.wait().is_ok() {
+                            return Err(HalReadError::Timeout);
+                        }
+                    }
+                }
+            };
+        }
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+}
+
+impl<'a, R, C> CoreRead<'a> for &mut TimeoutReader<R, C>
+where
+    R: serial::Read<u8>,
+    C: CountDown,
+    C::Time: Clone,
+{
+    type Error = HalReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
+
+impl<'a, R: serial::Read<u8>> CoreRead<'a> for NbReader<'_, R> {
+    type Error = HalReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        for byte in buffer {
+            if self.pos == self.len {
+                let slot = self
+                    .buffer
+                    .get_mut(self.len)
+                    .ok_or(HalReadError::BufferFull)?;
+                *slot = match self.serial.read() {
+                    Ok(byte) => byte,
+                    Err(nb::Error::Other(e)) => return Err(HalReadError::Bus(e)),
+                    Err(nb::Error::WouldBlock) => return Err(HalReadError::WouldBlock),
+                };
+                self.len += 1;
+            }
+            *byte = self.buffer[self.pos];
+            self.pos += 1;
+        }
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(HalReadError::CannotBorrow)
+    }
+}
+
+impl<'a, R: serial::Read<u8>> CoreRead<'a> for &mut NbReader<'_, R> {
+    type Error = HalReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
+
+/// Adapter that allows serializing to a non-blocking `embedded_hal` serial port with flow control,
+/// like a USB CDC-ACM `usbd_serial::SerialPort`.
+///
+/// A USB serial port only accepts bytes while its endpoint buffer has room, and the buffer is only
+/// emptied when the USB device is polled. Bytes that the port does not accept are stored in the
+/// caller-provided `ring` buffer instead of waiting, and sent when the port has room again. Call
+/// [poll](SerialWriter::poll) after every `usb_dev.poll(..)` to keep sending them.
+///
+/// [CoreWrite::flush], which [serialize](crate::serialize) calls at the end of every value, sends
+/// as much of the ring as the port accepts and flushes the port, but does not wait for the host to
+/// read the rest; waiting in the same loop that polls the USB device would never finish. Use
+/// [poll](SerialWriter::poll) or [pending](SerialWriter::pending) to find out when everything is
+/// sent.
+///
+/// When the ring is full, writing returns [HalWriteError::BufferFull] and the value is only
+/// partially written. Make the ring large enough for the values that can be written between two
+/// polls, or check [available](SerialWriter::available) against
+/// [serialize_size](crate::serialize_size) before serializing.
+pub struct SerialWriter<'a, W> {
+    serial: W,
+    ring: &'a mut [u8],
+    start: usize,
+    len: usize,
+}
+
+/// An error that is thrown when writing to a [SpiAdapter], [I2cAdapter] or [SerialWriter].
+///
+/// Like [HalReadError], the `Debug` output only contains the type of the bus error.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HalWriteError<E> {
+    /// The underlying bus or serial port returned an error.
+    Bus(E),
+    /// The serial port is busy and the ring buffer is full.
+    BufferFull,
+}
+
+impl<E> core::fmt::Debug for HalWriteError<E> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            HalWriteError::Bus(_) => write!(fmt, "Bus({})", core::any::type_name::<E>()),
+            HalWriteError::BufferFull => write!(fmt, "BufferFull"),
+        }
+    }
+}
+
+impl<'a, W, E> SerialWriter<'a, W>
+where
+    W: serial::Write<u8, Error = E>,
+{
+    /// Create a new adapter that writes to `serial`, and stores the bytes that the port does not
+    /// accept yet in `ring`.
+    pub fn new(serial: W, ring: &'a mut [u8]) -> Self {
+        Self {
+            serial,
+            ring,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// The amount of bytes in the ring buffer that are not sent yet.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    /// The amount of bytes that can be written before the ring buffer is full, assuming the port
+    /// accepts none of them.
+    pub fn available(&self) -> usize {
+        self.ring.len() - self.len
+    }
+
+    /// Send the bytes in the ring buffer and flush the port. Returns `WouldBlock` until all bytes
+    /// are sent.
+    pub fn poll(&mut self) -> nb::Result<(), HalWriteError<E>> {
+        self.drain().map_err(HalWriteError::Bus)?;
+        if self.len > 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.serial.flush().map_err(|e| e.map(HalWriteError::Bus))
+    }
+
+    /// Return a mutable reference to the serial port, e.g. to pass it to `usb_dev.poll(..)` or to
+    /// read from it.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.serial
+    }
+
+    /// Release the serial port and the ring buffer. Bytes that are not sent yet are dropped.
+    pub fn release(self) -> (W, &'a mut [u8]) {
+        (self.serial, self.ring)
+    }
+
+    /// Send bytes from the ring buffer until it is empty or the port is busy.
+    fn drain(&mut self) -> Result<(), E> {
+        while self.len > 0 {
+            let byte = match self.ring.get(self.start) {
+                Some(byte) => *byte,
+                None => break,
+            };
+            match self.serial.write(byte) {
+                Ok(()) => {}
+                Err(nb::Error::WouldBlock) => return Ok(()),
+                Err(nb::Error::Other(e)) => return Err(e),
+            }
+            self.start = (self.start + 1) % self.ring.len();
+            self.len -= 1;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, W> CoreWrite for SerialWriter<'a, W>
+where
+    W: serial::Write<u8>,
+{
+    type Error = HalWriteError<W::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.drain().map_err(HalWriteError::Bus)?;
+        if self.len == 0 {
+            match self.serial.write(val) {
+                Ok(()) => return Ok(()),
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(e)) => return Err(HalWriteError::Bus(e)),
+            }
+        }
+        if self.len == self.ring.len() {
+            return Err(HalWriteError::BufferFull);
+        }
+        let end = (self.start + self.len) % self.ring.len();
+        if let Some(slot) = self.ring.get_mut(end) {
+            *slot = val;
+            self.len += 1;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        match self.poll() {
+            Ok(()) | Err(nb::Error::WouldBlock) => Ok(()),
+            Err(nb::Error::Other(e)) => Err(e),
+        }
+    }
+}
+
+impl<'a, W> CoreWrite for &mut SerialWriter<'a, W>
+where
+    W: serial::Write<u8>,
+{
+    type Error = HalWriteError<W::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+}
@@ -0,0 +1,144 @@
+use super::{CoreRead, CoreWrite};
+use embedded_io::{Read, ReadExactError, Write};
+
+/// Adapter that allows (de)serializing over any blocking [embedded_io::Read] or
+/// [embedded_io::Write], like a UART, a USB serial port or a TCP socket of a HAL or network stack
+/// that implements the `embedded-io` traits.
+///
+/// Since `embedded-io` 0.5 the blocking traits live in the crate root (they were in the `blocking`
+/// module before), and the async traits in `embedded-io-async`.
+///
+/// The adapter reads straight from the device and has no buffer to borrow `&str` and `&[u8]` from.
+/// Wrap it in a [BufferedReader](crate::BufferedReader) to deserialize borrowed data.
+///
+/// ```
+/// # use bincode_core::{deserialize, serialize, DefaultOptions, EmbeddedIo};
+/// let mut buffer = [0u8; 8];
+/// let mut writer = EmbeddedIo::new(&mut buffer[..]);
+/// serialize(&(1u8, 300u16), &mut writer, DefaultOptions::new()).unwrap();
+///
+/// let reader = EmbeddedIo::new(&buffer[..]);
+/// let value: (u8, u16) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, 300));
+/// ```
+pub struct EmbeddedIo<T> {
+    io: T,
+}
+
+impl<T> EmbeddedIo<T> {
+    /// Create a new adapter for the given reader or writer.
+    pub fn new(io: T) -> Self {
+        Self { io }
+    }
+
+    /// Release the reader or writer.
+    pub fn release(self) -> T {
+        self.io
+    }
+}
+
+/// An error that is thrown when (de)serializing with an [EmbeddedIo] adapter.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EmbeddedIoError<E> {
+    /// The underlying reader or writer returned an error.
+    Io(E),
+    /// The reader reached the end of its input.
+    UnexpectedEof,
+    /// The writer did not accept any more bytes.
+    WriteZero,
+    /// Tried to deserialize a `&str` or `&[u8]`. Wrap the adapter in a
+    /// [BufferedReader](crate::BufferedReader) to deserialize borrowed data.
+    CannotBorrow,
+}
+
+impl<'a, T: Read> CoreRead<'a> for EmbeddedIo<T> {
+    type Error = EmbeddedIoError<T::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.io.read_exact(buffer).map_err(|e| match e {
+            ReadExactError::UnexpectedEof => EmbeddedIoError::UnexpectedEof,
+            ReadExactError::Other(e) => EmbeddedIoError::Io(e),
+        })
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(EmbeddedIoError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(EmbeddedIoError::CannotBorrow)
+    }
+}
+
+impl<'a, T: Read> CoreRead<'a> for &mut EmbeddedIo<T> {
+    type Error = EmbeddedIoError<T::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
+
+impl<T: Write> CoreWrite for EmbeddedIo<T> {
+    type Error = EmbeddedIoError<T::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.io.flush().map_err(EmbeddedIoError::Io)
+    }
+
+    fn write_all(&mut self, mut val: &[u8]) -> Result<(), Self::Error> {
+        // `embedded_io::Write::write_all` panics when the writer is full, return an error instead
+        while !val.is_empty() {
+            match self.io.write(val) {
+                Ok(0) => return Err(EmbeddedIoError::WriteZero),
+                Ok(n) => val = val.get(n..).unwrap_or_default(),
+                Err(e) => return Err(EmbeddedIoError::Io(e)),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Write> CoreWrite for &mut EmbeddedIo<T> {
+    type Error = EmbeddedIoError<T::Error>;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
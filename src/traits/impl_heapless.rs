@@ -0,0 +1,66 @@
+use super::CoreRead;
+use heapless::spsc::Consumer;
+
+#[cfg(feature = "core-error")]
+use core::error::Error as StdError;
+#[cfg(all(feature = "std", not(feature = "core-error")))]
+use std::error::Error as StdError;
+
+/// An error that is thrown when reading from a `heapless::spsc::Consumer`.
+///
+/// This is meant for the common framing pattern where an interrupt handler enqueues the received
+/// bytes, and the main loop deserializes them. Reads never block, so a value should only be read
+/// when the complete value is in the queue, e.g. after the framing layer saw its delimiter or
+/// length prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SpscReadError {
+    /// The queue does not contain enough bytes. Nothing is dequeued by the failed read.
+    NotEnoughData,
+    /// Tried to deserialize a `&str` or `&[u8]`. The bytes of the queue are dequeued as they are
+    /// read, so they cannot be borrowed. Wrap the consumer in a
+    /// [BufferedReader](crate::BufferedReader) to deserialize borrowed data.
+    CannotBorrow,
+}
+
+impl core::fmt::Display for SpscReadError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "core-error"))]
+impl StdError for SpscReadError {}
+
+impl<'a, const N: usize> CoreRead<'a> for &mut Consumer<'_, u8, N> {
+    type Error = SpscReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        // Only dequeue complete reads, so a short queue never loses the start of a value
+        if self.len() < buffer.len() {
+            return Err(SpscReadError::NotEnoughData);
+        }
+        for byte in buffer {
+            *byte = self.read()?;
+        }
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<u8, Self::Error> {
+        self.dequeue().ok_or(SpscReadError::NotEnoughData)
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(SpscReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(SpscReadError::CannotBorrow)
+    }
+}
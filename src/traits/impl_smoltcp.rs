@@ -0,0 +1,112 @@
+use super::{CoreRead, CoreWrite};
+use crate::config::{Bounded, Infinite, Options, SizeLimit, WithOtherLimit};
+use crate::{serialize, serialize_size, BufferWriter, SerializeError};
+use smoltcp::socket::{tcp, udp};
+
+/// An error that is thrown when reading from or writing to a smoltcp TCP socket.
+///
+/// Reads and writes on a socket never block. A value should only be written when
+/// [serialize_size](crate::serialize_size) fits in the free space of the send buffer, and only be
+/// read when the complete value is in the receive buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TcpSocketError {
+    /// The socket is not in a state that allows sending or receiving.
+    InvalidState,
+    /// The remote end closed the connection.
+    Finished,
+    /// The send buffer does not have enough free space for the bytes that are written. Nothing is
+    /// written to the socket.
+    BufferFull,
+    /// The receive buffer does not contain enough bytes. Nothing is read from the socket.
+    NotEnoughData,
+    /// Tried to deserialize a `&str` or `&[u8]`. Wrap the socket in a
+    /// [BufferedReader](crate::BufferedReader) to deserialize borrowed data.
+    CannotBorrow,
+}
+
+impl CoreWrite for &mut tcp::Socket<'_> {
+    type Error = TcpSocketError;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.write_all(&[val])
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        if !self.may_send() {
+            return Err(TcpSocketError::InvalidState);
+        }
+        // Only enqueue complete writes, so a full buffer never leaves half a value in the stream
+        if self.send_capacity() - self.send_queue() < val.len() {
+            return Err(TcpSocketError::BufferFull);
+        }
+        self.send_slice(val)
+            .map_err(|_| TcpSocketError::InvalidState)?;
+        Ok(())
+    }
+}
+
+impl<'a> CoreRead<'a> for &mut tcp::Socket<'_> {
+    type Error = TcpSocketError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        if self.recv_queue() < buffer.len() {
+            return Err(TcpSocketError::NotEnoughData);
+        }
+        match self.recv_slice(buffer) {
+            Ok(_) => Ok(()),
+            Err(tcp::RecvError::Finished) => Err(TcpSocketError::Finished),
+            Err(tcp::RecvError::InvalidState) => Err(TcpSocketError::InvalidState),
+        }
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(TcpSocketError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(TcpSocketError::CannotBorrow)
+    }
+}
+
+/// An error that is thrown by [serialize_udp].
+#[derive(Debug)]
+pub enum UdpSendError {
+    /// The socket is not bound, or the remote endpoint is unspecified.
+    Unaddressable,
+    /// The send buffer does not have enough free space for the datagram.
+    BufferFull,
+    /// The value could not be serialized.
+    Serialize(SerializeError<()>),
+}
+
+/// Serialize `value` as a single UDP datagram, directly into the send buffer of `socket`.
+///
+/// The size of the value is measured first, so the datagram can be allocated in the send buffer
+/// without an intermediate copy. Received datagrams can be deserialized directly from the payload
+/// returned by `udp::Socket::recv`.
+pub fn serialize_udp<T: serde::Serialize + ?Sized, O: Options>(
+    value: &T,
+    socket: &mut udp::Socket<'_>,
+    meta: impl Into<udp::UdpMetadata>,
+    mut options: O,
+) -> Result<(), UdpSendError> {
+    let size = match options.limit().limit() {
+        Some(limit) => serialize_size(value, WithOtherLimit::new(&mut options, Bounded(limit))),
+        None => serialize_size(value, WithOtherLimit::new(&mut options, Infinite)),
+    }
+    .map_err(UdpSendError::Serialize)?;
+
+    let payload = socket.send(size, meta).map_err(|e| match e {
+        udp::SendError::Unaddressable => UdpSendError::Unaddressable,
+        udp::SendError::BufferFull => UdpSendError::BufferFull,
+    })?;
+    serialize(value, BufferWriter::new(payload), options)
+        .map_err(|e| UdpSendError::Serialize(e.map_write(|_| ())))
+}
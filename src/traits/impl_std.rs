@@ -0,0 +1,195 @@
+use super::{CoreRead, CoreWrite};
+use std::error::Error as StdError;
+use std::io::{self, BufRead, Read, Write};
+use std::vec::Vec;
+
+/// Adapter that allows serializing into any [std::io::Write], like a `File`, a `TcpStream` or a
+/// `Vec<u8>`.
+///
+/// This is meant for host-side code, like unit tests and desktop tools that talk to a device, so
+/// they can use the same codec code as the firmware.
+///
+/// ```
+/// # use bincode_core::{serialize, DefaultOptions, IoWriter};
+/// let mut bytes = Vec::new();
+/// serialize(&(1u8, "abc"), IoWriter::new(&mut bytes), DefaultOptions::new()).unwrap();
+/// assert_eq!(bytes, [1, 3, b'a', b'b', b'c']);
+/// ```
+pub struct IoWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> IoWriter<W> {
+    /// Create a new adapter that writes to `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> CoreWrite for IoWriter<W> {
+    type Error = io::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        self.writer.write_all(&[val])
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        self.writer.write_all(val)
+    }
+}
+
+impl<W: Write> CoreWrite for &mut IoWriter<W> {
+    type Error = io::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// Adapter that allows deserializing from any [std::io::BufRead], like a `BufReader<File>` or a
+/// `&[u8]`.
+///
+/// Like [IoWriter], this is meant for host-side code. A `BufRead` reuses its buffer, so `&str` and
+/// `&[u8]` cannot be borrowed from it; owned values like `String` work, and borrowed values can be
+/// read by wrapping this reader in a [BufferedReader](crate::BufferedReader).
+///
+/// ```
+/// # use bincode_core::{deserialize, BufferedReader, DefaultOptions, IoReader};
+/// # use std::io::BufReader;
+/// let bytes = [1u8, 3, b'a', b'b', b'c'];
+/// let reader = IoReader::new(BufReader::new(&bytes[..]));
+/// let mut scratch = [0u8; 8];
+/// let reader = BufferedReader::new(reader, &mut scratch);
+/// let value: (u8, &str) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, "abc"));
+/// ```
+pub struct IoReader<R> {
+    reader: R,
+}
+
+impl<R: BufRead> IoReader<R> {
+    /// Create a new adapter that reads from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Passes the next `len` bytes to `f`. The bytes are taken from the buffer of the reader if
+    /// possible, and copied otherwise.
+    fn with_bytes<T>(
+        &mut self,
+        len: usize,
+        f: impl FnOnce(&[u8]) -> Result<T, IoReadError>,
+    ) -> Result<T, IoReadError> {
+        let buffered = self.reader.fill_buf().map_err(IoReadError::Io)?;
+        if let Some(bytes) = buffered.get(..len) {
+            let result = f(bytes);
+            self.reader.consume(len);
+            return result;
+        }
+        // Grow the buffer while reading, a corrupt length should not allocate a huge buffer
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)
+            .map_err(IoReadError::Io)?;
+        if bytes.len() != len {
+            return Err(IoReadError::Io(io::ErrorKind::UnexpectedEof.into()));
+        }
+        f(&bytes)
+    }
+}
+
+/// An error that is thrown when reading from an [IoReader].
+#[derive(Debug)]
+pub enum IoReadError {
+    /// The inner reader returned an error. Running out of bytes is an
+    /// [UnexpectedEof](io::ErrorKind::UnexpectedEof) error.
+    Io(io::Error),
+    /// The bytes read for a `&str` are not valid UTF8.
+    InvalidUtf8,
+    /// The visitor rejected the `&str` or `&[u8]`, e.g. because it can only borrow the value.
+    Custom,
+}
+
+impl serde::de::Error for IoReadError {
+    fn custom<T: core::fmt::Display>(_cause: T) -> Self {
+        IoReadError::Custom
+    }
+}
+
+impl core::fmt::Display for IoReadError {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(fmt, "{:?}", self)
+    }
+}
+
+impl StdError for IoReadError {}
+
+impl<'a, R: BufRead> CoreRead<'a> for IoReader<R> {
+    type Error = IoReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.reader.read_exact(buffer).map_err(IoReadError::Io)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.with_bytes(len, |bytes| {
+            let string = core::str::from_utf8(bytes).map_err(|_| IoReadError::InvalidUtf8)?;
+            visitor.visit_str(string)
+        })
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        self.with_bytes(len, |bytes| visitor.visit_bytes(bytes))
+    }
+}
+
+impl<'a, R: BufRead> CoreRead<'a> for &mut IoReader<R> {
+    type Error = IoReadError;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+}
@@ -1,5 +1,31 @@
 mod core_read;
 mod core_write;
+#[cfg(feature = "bbqueue")]
+mod impl_bbqueue;
+#[cfg(feature = "embedded-hal")]
+mod impl_embedded_hal;
+#[cfg(feature = "embedded-io")]
+mod impl_embedded_io;
+#[cfg(feature = "heapless")]
+mod impl_heapless;
+#[cfg(feature = "smoltcp")]
+mod impl_smoltcp;
+#[cfg(feature = "std")]
+mod impl_std;
 
-pub use self::core_read::{CoreRead, SliceReadError};
-pub use self::core_write::CoreWrite;
+pub use self::core_read::{CheckpointRead, CoreRead, SliceReadError};
+pub use self::core_write::{CoreWrite, CoreWriteSeek};
+#[cfg(feature = "bbqueue")]
+pub use self::impl_bbqueue::{BbqReadError, GrantReader};
+#[cfg(feature = "embedded-hal")]
+pub use self::impl_embedded_hal::{
+    HalReadError, HalWriteError, I2cAdapter, NbReader, SerialWriter, SpiAdapter, TimeoutReader,
+};
+#[cfg(feature = "embedded-io")]
+pub use self::impl_embedded_io::{EmbeddedIo, EmbeddedIoError};
+#[cfg(feature = "heapless")]
+pub use self::impl_heapless::SpscReadError;
+#[cfg(feature = "smoltcp")]
+pub use self::impl_smoltcp::{serialize_udp, TcpSocketError, UdpSendError};
+#[cfg(feature = "std")]
+pub use self::impl_std::{IoReadError, IoReader, IoWriter};
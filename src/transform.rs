@@ -0,0 +1,200 @@
+use crate::traits::{CoreRead, CoreWrite};
+
+/// A streaming transform that is applied to bytes as they pass through a [TransformWriter] or
+/// [TransformReader].
+///
+/// The transform is applied in place, in the order of the stream, and may be called with slices of
+/// any length. This fits stream ciphers (like an XOR keystream or AES-CTR), where the output only
+/// depends on the position in the stream.
+///
+/// This is implemented for closures, so a cipher can be used with e.g.
+/// `|data: &mut [u8]| cipher.apply_keystream(data)`.
+pub trait StreamTransform {
+    /// Transform the next `data.len()` bytes of the stream in place.
+    fn apply(&mut self, data: &mut [u8]);
+}
+
+impl<F: FnMut(&mut [u8])> StreamTransform for F {
+    fn apply(&mut self, data: &mut [u8]) {
+        self(data)
+    }
+}
+
+/// The size of the stack buffer that [TransformWriter] copies bytes into before transforming them.
+const CHUNK_LEN: usize = 32;
+
+/// An implementation of [CoreWrite] that transforms all bytes before they are written to the inner
+/// writer, without a second buffer for the serialized value.
+///
+/// ```
+/// # use bincode_core::{serialize, BufferWriter, DefaultOptions, TransformWriter};
+/// let mut buffer = [0u8; 8];
+/// let xor = |data: &mut [u8]| data.iter_mut().for_each(|byte| *byte ^= 0x5A);
+/// let mut writer = TransformWriter::new(BufferWriter::new(&mut buffer), xor);
+/// serialize(&(1u8, 2u8), &mut writer, DefaultOptions::new()).unwrap();
+/// assert_eq!(writer.into_inner().written_buffer(), &[0x5B, 0x58]);
+/// ```
+pub struct TransformWriter<W: CoreWrite, F: StreamTransform> {
+    writer: W,
+    transform: F,
+}
+
+impl<W: CoreWrite, F: StreamTransform> TransformWriter<W, F> {
+    /// Create a new writer that applies `transform` to all bytes that are written to `writer`.
+    pub fn new(writer: W, transform: F) -> Self {
+        Self { writer, transform }
+    }
+
+    /// Return the inner writer and the transform.
+    pub fn into_parts(self) -> (W, F) {
+        (self.writer, self.transform)
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: CoreWrite, F: StreamTransform> CoreWrite for TransformWriter<W, F> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        let mut buffer = [val];
+        self.transform.apply(&mut buffer);
+        self.writer.write(buffer[0])
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.writer.flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        let mut buffer = [0u8; CHUNK_LEN];
+        for chunk in val.chunks(CHUNK_LEN) {
+            let buffer = &mut buffer[..chunk.len()];
+            buffer.copy_from_slice(chunk);
+            self.transform.apply(buffer);
+            self.writer.write_all(buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: CoreWrite, F: StreamTransform> CoreWrite for &mut TransformWriter<W, F> {
+    type Error = W::Error;
+
+    fn write(&mut self, val: u8) -> Result<(), Self::Error> {
+        (**self).write(val)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), Self::Error> {
+        (**self).write_all(val)
+    }
+}
+
+/// An error that is thrown when reading from a [TransformReader].
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TransformReadError<E> {
+    /// The inner reader returned an error.
+    Read(E),
+    /// Tried to deserialize a `&str` or `&[u8]`. The bytes of the inner reader cannot be
+    /// transformed in place, wrap the `TransformReader` in a [BufferedReader](crate::BufferedReader)
+    /// to deserialize borrowed data.
+    CannotBorrow,
+}
+
+/// An implementation of [CoreRead] that transforms all bytes after they are read from the inner
+/// reader.
+///
+/// Borrowed `&str` and `&[u8]` values cannot be transformed in place. To deserialize those, wrap
+/// this reader in a [BufferedReader](crate::BufferedReader).
+///
+/// ```
+/// # use bincode_core::{deserialize, DefaultOptions, TransformReader};
+/// let buffer = [0x5B, 0x58];
+/// let xor = |data: &mut [u8]| data.iter_mut().for_each(|byte| *byte ^= 0x5A);
+/// let reader = TransformReader::new(&buffer[..], xor);
+/// let value: (u8, u8) = deserialize(reader, DefaultOptions::new()).unwrap();
+/// assert_eq!(value, (1, 2));
+/// ```
+pub struct TransformReader<R, F: StreamTransform> {
+    reader: R,
+    transform: F,
+}
+
+impl<R, F: StreamTransform> TransformReader<R, F> {
+    /// Create a new reader that applies `transform` to all bytes that are read from `reader`.
+    pub fn new(reader: R, transform: F) -> Self {
+        Self { reader, transform }
+    }
+
+    /// Return the inner reader and the transform.
+    pub fn into_parts(self) -> (R, F) {
+        (self.reader, self.transform)
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<'a, R: CoreRead<'a>, F: StreamTransform> CoreRead<'a> for TransformReader<R, F> {
+    type Error = TransformReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.reader.fill(buffer).map_err(TransformReadError::Read)?;
+        self.transform.apply(buffer);
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(TransformReadError::CannotBorrow)
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(TransformReadError::CannotBorrow)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        self.reader.remaining_len()
+    }
+}
+
+impl<'a, R: CoreRead<'a>, F: StreamTransform> CoreRead<'a> for &mut TransformReader<R, F> {
+    type Error = TransformReadError<R::Error>;
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).fill(buffer)
+    }
+
+    fn forward_str<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_str(len, visitor)
+    }
+
+    fn forward_bytes<V>(&mut self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        (**self).forward_bytes(len, visitor)
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+}
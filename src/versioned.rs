@@ -0,0 +1,63 @@
+//! Helpers to put a version tag in front of a message.
+//!
+//! Firmware and host software are often updated separately. By prefixing every message with the
+//! version of its schema, a receiver can reject a message it does not understand instead of
+//! decoding it into garbage.
+//!
+//! ```
+//! # use bincode_core::versioned::{deserialize_versioned, serialize_versioned};
+//! # use bincode_core::{BufferWriter, DefaultOptions, DeserializeError};
+//! let mut buffer = [0u8; 16];
+//! let mut writer = BufferWriter::new(&mut buffer);
+//! serialize_versioned(2, &(1u8, 2u8), &mut writer, DefaultOptions::new()).unwrap();
+//! let len = writer.written_len();
+//!
+//! let value: (u8, u8) = deserialize_versioned(2, &buffer[..len], DefaultOptions::new()).unwrap();
+//! assert_eq!(value, (1, 2));
+//!
+//! match deserialize_versioned::<(u8, u8), _, _>(3, &buffer[..len], DefaultOptions::new()) {
+//!     Err(DeserializeError::VersionMismatch { expected: 3, found: 2 }) => {}
+//!     _ => panic!(),
+//! }
+//! ```
+
+use crate::config::{Options, TrailingBytes};
+use crate::deserialize::{DeserializeError, Deserializer};
+use crate::serialize::{serialize_with_header, SerializeError};
+use crate::traits::{CoreRead, CoreWrite};
+use serde::{Deserialize, Serialize};
+
+/// Serialize `value` into `writer`, prefixed with the version `tag`.
+///
+/// The tag is encoded as a `u32` with the configured integer encoding, and counts towards the size
+/// limit.
+pub fn serialize_versioned<T: Serialize + ?Sized, W: CoreWrite, O: Options>(
+    tag: u32,
+    value: &T,
+    writer: W,
+    options: O,
+) -> Result<(), SerializeError<W::Error>> {
+    serialize_with_header(&tag, value, writer, options)
+}
+
+/// Deserialize a value that was written with [serialize_versioned].
+///
+/// If the version tag in the input is not `tag`, [DeserializeError::VersionMismatch] is returned
+/// before any of the value is read.
+pub fn deserialize_versioned<'a, T: Deserialize<'a>, R: CoreRead<'a> + 'a, O: Options>(
+    tag: u32,
+    reader: R,
+    options: O,
+) -> Result<T, DeserializeError<'a, R>> {
+    let mut deserializer = Deserializer::new(reader, options);
+    let found = u32::deserialize(&mut deserializer)?;
+    if found != tag {
+        return Err(DeserializeError::VersionMismatch {
+            expected: tag,
+            found,
+        });
+    }
+    let value = T::deserialize(&mut deserializer)?;
+    O::Trailing::check_end(deserializer.reader())?;
+    Ok(value)
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "alloc")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{deserialize, serialize, BufferWriter, CoreRead, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Owned {
+    name: String,
+    #[serde(with = "serde_bytes_buf")]
+    payload: Vec<u8>,
+}
+
+/// Deserializes a `Vec<u8>` through `deserialize_byte_buf` instead of as a sequence.
+mod serde_bytes_buf {
+    use serde::de::{Deserializer, Visitor};
+    use serde::Serializer;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BufVisitor;
+        impl<'de> Visitor<'de> for BufVisitor {
+            type Value = Vec<u8>;
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a byte buffer")
+            }
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(v)
+            }
+        }
+        deserializer.deserialize_byte_buf(BufVisitor)
+    }
+}
+
+/// A reader that can only copy bytes out, like a serial port.
+struct Stream<'a> {
+    data: &'a [u8],
+}
+
+impl<'a, 'b> CoreRead<'b> for Stream<'a> {
+    type Error = ();
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        if buffer.len() > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[..buffer.len()]);
+        self.data = &self.data[buffer.len()..];
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'b>,
+    {
+        Err(())
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'b>,
+    {
+        Err(())
+    }
+}
+
+fn sample() -> (Owned, [u8; 100], usize) {
+    let value = Owned {
+        name: "owned".to_string(),
+        payload: vec![1, 2, 3],
+    };
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    (value, buffer, len)
+}
+
+#[test]
+fn owned_from_slice() {
+    let (value, buffer, len) = sample();
+    let deserialized: Owned = deserialize(&buffer[..len], DefaultOptions::new()).unwrap();
+    assert_eq!(value, deserialized);
+}
+
+#[test]
+fn owned_from_stream() {
+    let (value, buffer, len) = sample();
+    let stream = Stream {
+        data: &buffer[..len],
+    };
+    let deserialized: Owned = deserialize(stream, DefaultOptions::new()).unwrap();
+    assert_eq!(value, deserialized);
+}
@@ -0,0 +1,149 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, ArmorReadError, Base64Reader, Base64Writer,
+    BufferWriter, BufferedReader, CoreRead, CoreWrite, DefaultOptions, DeserializeError, HexReader,
+    HexWriter,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message<'a> {
+    id: u32,
+    name: &'a str,
+    payload: Vec<u8>,
+}
+
+fn message() -> Message<'static> {
+    Message {
+        id: 70000,
+        name: "modem",
+        payload: (0..=255).collect(),
+    }
+}
+
+/// The test vectors of RFC 4648.
+const BASE64_VECTORS: [(&str, &str); 7] = [
+    ("", ""),
+    ("f", "Zg=="),
+    ("fo", "Zm8="),
+    ("foo", "Zm9v"),
+    ("foob", "Zm9vYg=="),
+    ("fooba", "Zm9vYmE="),
+    ("foobar", "Zm9vYmFy"),
+];
+
+#[test]
+fn hex_round_trip() {
+    let mut buffer = [0u8; 1024];
+    let mut writer = HexWriter::new(BufferWriter::new(&mut buffer));
+    serialize(&message(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+    assert!(buffer[..len].iter().all(u8::is_ascii_hexdigit));
+
+    let mut scratch = [0u8; 300];
+    let reader = BufferedReader::new(HexReader::new(&buffer[..len]), &mut scratch);
+    let decoded: Message = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, message());
+}
+
+#[test]
+fn hex_reads_uppercase_and_rejects_other_characters() {
+    let mut reader = HexReader::new(&b"0aFf"[..]);
+    let mut bytes = [0u8; 2];
+    reader.fill(&mut bytes).unwrap();
+    assert_eq!(bytes, [0x0A, 0xFF]);
+
+    let mut reader = HexReader::new(&b"0g"[..]);
+    assert!(matches!(
+        reader.read(),
+        Err(ArmorReadError::InvalidCharacter(b'g'))
+    ));
+}
+
+#[test]
+fn base64_matches_rfc_4648() {
+    for (plain, encoded) in BASE64_VECTORS.iter() {
+        let mut buffer = [0u8; 16];
+        let mut writer = Base64Writer::new(BufferWriter::new(&mut buffer));
+        writer.write_all(plain.as_bytes()).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.into_inner().written_buffer(), encoded.as_bytes());
+
+        // Byte by byte gives the same output
+        let mut buffer = [0u8; 16];
+        let mut writer = Base64Writer::new(BufferWriter::new(&mut buffer));
+        for byte in plain.bytes() {
+            writer.write(byte).unwrap();
+        }
+        writer.flush().unwrap();
+        assert_eq!(writer.into_inner().written_buffer(), encoded.as_bytes());
+
+        let mut reader = Base64Reader::new(encoded.as_bytes());
+        assert_eq!(reader.remaining_len(), Some(plain.len()));
+        let mut decoded = [0u8; 6];
+        reader.fill(&mut decoded[..plain.len()]).unwrap();
+        assert_eq!(&decoded[..plain.len()], plain.as_bytes());
+        assert_eq!(reader.remaining_len(), Some(0));
+    }
+}
+
+#[test]
+fn base64_round_trip() {
+    let mut buffer = [0u8; 1024];
+    let mut writer = Base64Writer::new(BufferWriter::new(&mut buffer));
+    serialize(&message(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+    assert_eq!(len % 4, 0);
+
+    let mut scratch = [0u8; 300];
+    let reader = BufferedReader::new(Base64Reader::new(&buffer[..len]), &mut scratch);
+    let decoded: Message = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, message());
+}
+
+#[test]
+fn base64_messages_are_padded_and_read_as_one_stream() {
+    let mut buffer = [0u8; 64];
+    let mut writer = Base64Writer::new(BufferWriter::new(&mut buffer));
+    for value in [1u16, 300, 70].iter() {
+        serialize(value, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    let writer = writer.into_inner();
+    assert_eq!(writer.written_buffer(), b"AQ==+ywBRg==");
+
+    let values = deserialize_iter::<u16, _, _>(
+        Base64Reader::new(writer.written_buffer()),
+        DefaultOptions::new(),
+    );
+    assert_eq!(values.collect::<Result<Vec<_>, _>>().unwrap(), [1, 300, 70]);
+}
+
+#[test]
+fn base64_rejects_invalid_input() {
+    for input in [&b"Zm9*"[..], b"=Zg=", b"Zm=v"].iter() {
+        let result = deserialize::<u8, _, _>(Base64Reader::new(*input), DefaultOptions::new());
+        assert!(
+            matches!(
+                result,
+                Err(DeserializeError::Read(ArmorReadError::InvalidCharacter(_)))
+            ),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn borrowed_values_need_buffered_reader() {
+    let mut buffer = [0u8; 32];
+    let mut writer = HexWriter::new(BufferWriter::new(&mut buffer));
+    serialize("text", &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+
+    let result = deserialize::<&str, _, _>(HexReader::new(&buffer[..len]), DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(ArmorReadError::CannotBorrow))
+    ));
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "bbqueue")]
+
+use bbqueue::{BBBuffer, Consumer, Producer};
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BbqReadError, BufferWriter, DefaultOptions, DeserializeError,
+    Deserializer, GrantReader,
+};
+use serde::Deserialize;
+
+fn commit<const N: usize>(producer: &mut Producer<'_, N>, bytes: &[u8]) {
+    let mut grant = producer.grant_exact(bytes.len()).unwrap();
+    grant.copy_from_slice(bytes);
+    grant.commit(bytes.len());
+}
+
+/// Moves the start of the queue `len` bytes forward.
+fn skip<const N: usize>(
+    producer: &mut Producer<'_, N>,
+    consumer: &mut Consumer<'_, N>,
+    len: usize,
+) {
+    commit(producer, &[0; N][..len]);
+    consumer.read().unwrap().release(len);
+}
+
+#[test]
+fn consumer() {
+    let queue: BBBuffer<16> = BBBuffer::new();
+    let (mut producer, mut consumer) = queue.try_split().unwrap();
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&(300u16, "abc"), &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+
+    // Only the first half arrived, the failed read leaves it in the queue
+    let options = DefaultOptions::new().with_fixint_encoding();
+    commit(&mut producer, &bytes[..2]);
+    let result = deserialize::<u32, _, _>(&mut consumer, options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(BbqReadError::NotEnoughData))
+    ));
+    assert_eq!(consumer.read().unwrap().len(), 2);
+
+    // Owned strings are visited in the queue, before their bytes are released
+    commit(&mut producer, &bytes[2..]);
+    let value: (u16, String) = deserialize(&mut consumer, DefaultOptions::new()).unwrap();
+    assert_eq!(value, (300, "abc".to_string()));
+    assert!(consumer.read().is_err());
+
+    // A `&str` cannot outlive the released bytes
+    commit(&mut producer, bytes);
+    let result = deserialize::<(u16, &str), _, _>(&mut consumer, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(BbqReadError::Custom))
+    ));
+
+    // A read fails while the consumer holds a grant
+    let grant = consumer.read().unwrap();
+    let result = deserialize::<u16, _, _>(&mut consumer, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(BbqReadError::GrantInProgress))
+    ));
+    grant.release(0);
+}
+
+#[test]
+fn wrapping_grants() {
+    let queue: BBBuffer<8> = BBBuffer::new();
+    let (mut producer, mut consumer) = queue.try_split().unwrap();
+    skip(&mut producer, &mut consumer, 6);
+
+    // The second grant does not fit after the first one, and starts at the front of the buffer
+    let options = DefaultOptions::new().with_fixint_encoding();
+    commit(&mut producer, &[1, 2]);
+    commit(&mut producer, &[3, 4]);
+    let value: u32 = deserialize(&mut consumer, options).unwrap();
+    assert_eq!(value, 0x0403_0201);
+
+    // A string over both grants is not in one piece
+    skip(&mut producer, &mut consumer, 4);
+    commit(&mut producer, &[3, b'a']);
+    commit(&mut producer, b"bc");
+    let result = deserialize::<&str, _, _>(&mut consumer, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(BbqReadError::CannotBorrow))
+    ));
+}
+
+#[test]
+fn grant_reader() {
+    let queue: BBBuffer<16> = BBBuffer::new();
+    let (mut producer, mut consumer) = queue.try_split().unwrap();
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&("abc", &[1u8, 2][..]), &mut writer, DefaultOptions::new()).unwrap();
+    serialize(&7u8, &mut writer, DefaultOptions::new()).unwrap();
+    commit(&mut producer, writer.written_buffer());
+
+    let grant = consumer.read().unwrap();
+    let mut deserializer = Deserializer::new(GrantReader::new(&grant), DefaultOptions::new());
+    let value = <(&str, &[u8])>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(value, ("abc", &[1u8, 2][..]));
+    assert_eq!(deserializer.reader().read_len(), 7);
+    let reader = deserializer.into_reader();
+    let result = deserialize::<u16, _, _>(reader, DefaultOptions::new().with_fixint_encoding());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::UnexpectedEof { needed: 1 })
+    ));
+    let read = reader.read_len();
+    grant.release(read);
+
+    // Only the bytes that were read are released
+    let grant = consumer.read().unwrap();
+    let reader = GrantReader::new(&grant);
+    assert_eq!(
+        deserialize::<u8, _, _>(reader, DefaultOptions::new()).unwrap(),
+        7
+    );
+}
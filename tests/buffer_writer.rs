@@ -0,0 +1,92 @@
+use bincode_core::{
+    serialize, BufferWriter, BufferWriterError, DefaultOptions, FrameBufferWriter, SerializeError,
+};
+
+#[test]
+fn into_written() {
+    let mut buffer = [0u8; 8];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&(1u8, 300u16), &mut writer, DefaultOptions::new()).unwrap();
+
+    let (buffer, len) = writer.into_written();
+    assert_eq!(len, 4);
+    assert_eq!(&buffer[..len], &[1, 251, 44, 1]);
+    assert_eq!(buffer.len(), 8);
+}
+
+#[test]
+fn split_written() {
+    let mut buffer = [0u8; 6];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&"abc", &mut writer, DefaultOptions::new()).unwrap();
+
+    let (message, rest) = writer.split_written();
+    assert_eq!(message, &[3, b'a', b'b', b'c']);
+    assert_eq!(rest.len(), 2);
+
+    let mut next = BufferWriter::new(rest);
+    assert!(matches!(
+        serialize(&"abc", &mut next, DefaultOptions::new()),
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall))
+    ));
+    assert_eq!(message, &[3, b'a', b'b', b'c']);
+
+    // The writer keeps writing after the split
+    serialize(&7u8, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(writer.written_buffer(), &[3, b'a', b'b', b'c', 7]);
+}
+
+#[test]
+fn frames() {
+    let mut buffer = [0u8; 16];
+    let mut ends = [0usize; 3];
+    let mut writer = FrameBufferWriter::new(&mut buffer, &mut ends);
+    serialize(&300u16, &mut writer, DefaultOptions::new()).unwrap();
+    writer.mark_frame().unwrap();
+    writer.mark_frame().unwrap();
+    serialize(&"hi", &mut writer, DefaultOptions::new()).unwrap();
+    writer.mark_frame().unwrap();
+    serialize(&5u8, &mut writer, DefaultOptions::new()).unwrap();
+
+    assert_eq!(writer.frame_count(), 3);
+    assert_eq!(writer.written_len(), 7);
+    assert_eq!(writer.pending(), &[5]);
+    let frames: Vec<&[u8]> = writer.frames().collect();
+    assert_eq!(frames, [&[251, 44, 1][..], &[], &[2, b'h', b'i']]);
+    assert_eq!(writer.frames().len(), 3);
+
+    assert_eq!(writer.mark_frame(), Err(BufferWriterError::TooManyFrames));
+
+    writer.clear();
+    assert_eq!(writer.frame_count(), 0);
+    assert_eq!(writer.frames().next(), None);
+    serialize(&6u8, &mut writer, DefaultOptions::new()).unwrap();
+    writer.mark_frame().unwrap();
+    assert_eq!(writer.frames().collect::<Vec<_>>(), [&[6][..]]);
+}
+
+#[test]
+fn errors_of_writers_with_the_same_error() {
+    let mut buffer = [0u8; 2];
+    let mut ends = [0usize; 1];
+    let errors: [SerializeError<BufferWriterError>; 2] = [
+        serialize(
+            &"abc",
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new(),
+        )
+        .unwrap_err(),
+        serialize(
+            &"abc",
+            FrameBufferWriter::new(&mut buffer, &mut ends),
+            DefaultOptions::new(),
+        )
+        .unwrap_err(),
+    ];
+    for error in errors {
+        assert!(matches!(
+            error,
+            SerializeError::Write(BufferWriterError::BufferTooSmall)
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferWriter, BufferedReadError, BufferedReader, CoreRead,
+    DefaultOptions, DeserializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Message<'a> {
+    id: u32,
+    name: &'a str,
+    payload: &'a [u8],
+}
+
+/// A reader that can only copy bytes out, like a serial port.
+struct Stream<'a> {
+    data: &'a [u8],
+}
+
+impl<'a, 'b> CoreRead<'b> for &mut Stream<'a> {
+    type Error = ();
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        if buffer.len() > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[..buffer.len()]);
+        self.data = &self.data[buffer.len()..];
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'b>,
+    {
+        Err(())
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'b>,
+    {
+        Err(())
+    }
+}
+
+fn serialized(message: &Message, buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(message, &mut writer, DefaultOptions::new()).unwrap();
+    writer.written_len()
+}
+
+#[test]
+fn borrowed_fields_from_stream() {
+    let message = Message {
+        id: 5,
+        name: "sensor",
+        payload: &[1, 2, 3],
+    };
+    let mut buffer = [0u8; 100];
+    let len = serialized(&message, &mut buffer);
+
+    let mut scratch = [0u8; 16];
+    let mut stream = Stream {
+        data: &buffer[..len],
+    };
+    let reader = BufferedReader::new(&mut stream, &mut scratch);
+    let deserialized: Message = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(message, deserialized);
+    assert!(stream.data.is_empty());
+}
+
+#[test]
+fn scratch_too_small() {
+    let message = Message {
+        id: 5,
+        name: "sensor",
+        payload: &[1, 2, 3],
+    };
+    let mut buffer = [0u8; 100];
+    let len = serialized(&message, &mut buffer);
+
+    let mut scratch = [0u8; 8];
+    let mut stream = Stream {
+        data: &buffer[..len],
+    };
+    let reader = BufferedReader::new(&mut stream, &mut scratch);
+    match deserialize::<Message, _, _>(reader, DefaultOptions::new().with_fixint_encoding()) {
+        Err(DeserializeError::Read(BufferedReadError::ScratchTooSmall)) => {}
+        x => panic!("Expected ScratchTooSmall, got {:?}", x),
+    }
+}
+
+#[test]
+fn default_read_and_read_range() {
+    let data = [7u8, 1, 2, 3, 4];
+    let mut stream = Stream { data: &data };
+    let mut reader = &mut stream;
+    assert_eq!(reader.read(), Ok(7));
+
+    // Borrowed ranges of a stream are read into the scratch buffer
+    let mut scratch = [0u8; 4];
+    let mut reader = BufferedReader::new(&mut stream, &mut scratch);
+    assert_eq!(reader.read_range(3).unwrap(), &[1, 2, 3]);
+    assert!(matches!(
+        reader.read_range(2),
+        Err(BufferedReadError::ScratchTooSmall)
+    ));
+}
@@ -0,0 +1,63 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, ByteArray, DefaultOptions,
+    DeserializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Page {
+    index: u16,
+    data: ByteArray<4096>,
+}
+
+#[test]
+fn same_encoding_as_array() {
+    let array = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    let mut buffer_a = [0u8; 16];
+    let mut writer_a = BufferWriter::new(&mut buffer_a);
+    serialize(&array, &mut writer_a, DefaultOptions::new()).unwrap();
+
+    let mut buffer_b = [0u8; 16];
+    let mut writer_b = BufferWriter::new(&mut buffer_b);
+    serialize(&ByteArray(array), &mut writer_b, DefaultOptions::new()).unwrap();
+
+    assert_eq!(writer_a.written_buffer(), writer_b.written_buffer());
+    assert_eq!(
+        8,
+        serialize_size(&ByteArray(array), DefaultOptions::new()).unwrap()
+    );
+
+    let deserialized: ByteArray<8> = deserialize(&buffer_a[..8], DefaultOptions::new()).unwrap();
+    assert_eq!(array, *deserialized);
+}
+
+#[test]
+fn large_array_in_struct() {
+    let mut data = [0u8; 4096];
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let page = Page {
+        index: 3,
+        data: ByteArray(data),
+    };
+    let mut buffer = vec![0u8; 5000];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&page, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(4097, writer.written_len());
+
+    let deserialized: Page = deserialize(&buffer[..4097], DefaultOptions::new()).unwrap();
+    assert_eq!(page, deserialized);
+}
+
+#[test]
+fn array_counts_towards_limit() {
+    let buffer = [0u8; 16];
+    match deserialize::<ByteArray<16>, _, _>(&buffer[..], DefaultOptions::new().with_limit(8)) {
+        Err(DeserializeError::LimitError(_)) => {}
+        x => panic!("Expected LimitError, got {:?}", x),
+    }
+}
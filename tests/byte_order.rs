@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, Be, BufferWriter, CoreRead, DefaultOptions, Le,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Registers {
+    status: u8,
+    count: Be<u32>,
+    offset: Le<i16>,
+    scale: Be<f32>,
+}
+
+fn registers() -> Registers {
+    Registers {
+        status: 7,
+        count: Be(0x0102_0304),
+        offset: Le(-2),
+        scale: Be(1.5),
+    }
+}
+
+#[test]
+fn ignores_global_endian_and_int_encoding() {
+    let expected = [7, 1, 2, 3, 4, 0xFE, 0xFF, 0x3F, 0xC0, 0, 0];
+    let mut buffer = [0u8; 16];
+
+    let options = DefaultOptions::new();
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&registers(), &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), &expected);
+    assert_eq!(
+        serialize_size(&registers(), options).unwrap(),
+        expected.len()
+    );
+
+    let options = DefaultOptions::new()
+        .with_big_endian()
+        .with_fixint_encoding();
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&registers(), &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), &expected);
+
+    let decoded: Registers = deserialize(&expected[..], options).unwrap();
+    assert_eq!(decoded, registers());
+}
+
+#[test]
+fn all_types_roundtrip() {
+    fn roundtrip<T>(value: T, expected: &[u8])
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + PartialEq + core::fmt::Debug,
+    {
+        let mut buffer = [0u8; 16];
+        let mut writer = BufferWriter::new(&mut buffer);
+        serialize(&value, &mut writer, DefaultOptions::new()).unwrap();
+        assert_eq!(writer.written_buffer(), expected);
+        let decoded: T = deserialize(expected, DefaultOptions::new()).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    roundtrip(Be(0x0102u16), &[1, 2]);
+    roundtrip(Le(0x0102u16), &[2, 1]);
+    roundtrip(Be(-1i64), &[0xFF; 8]);
+    roundtrip(Be(1u128), &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+    roundtrip(Le(1i128), &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    roundtrip(Le(2.0f64), &[0, 0, 0, 0, 0, 0, 0, 0x40]);
+}
+
+/// A reader that can only copy bytes out, like a serial port.
+struct Stream<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CoreRead<'a> for Stream<'_> {
+    type Error = ();
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        if buffer.len() > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[..buffer.len()]);
+        self.data = &self.data[buffer.len()..];
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+}
+
+#[test]
+fn reads_without_borrowing() {
+    let bytes = [7, 1, 2, 3, 4, 0xFE, 0xFF, 0x3F, 0xC0, 0, 0];
+    let reader = Stream { data: &bytes };
+    let decoded: Registers = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, registers());
+}
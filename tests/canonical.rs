@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions, DeserializeError};
+use serde::Deserialize;
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Payment<'a> {
+    amount: u64,
+    fee: i32,
+    memo: &'a str,
+}
+
+fn options() -> impl Options {
+    DefaultOptions::new().with_canonical_varints()
+}
+
+fn assert_non_canonical<'a, T: Deserialize<'a> + Debug>(bytes: &'a [u8]) {
+    match deserialize::<T, _, _>(bytes, options()) {
+        Err(DeserializeError::NonCanonical) => {}
+        x => panic!("Expected NonCanonical for {:?}, got {:?}", bytes, x),
+    }
+    // The default varint encoding accepts every form
+    deserialize::<T, _, _>(bytes, DefaultOptions::new()).unwrap();
+}
+
+#[test]
+fn same_bytes_as_varint() {
+    let payment = Payment {
+        amount: 70_000,
+        fee: -300,
+        memo: "rent",
+    };
+    let mut canonical = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut canonical);
+    serialize(&payment, &mut writer, options()).unwrap();
+    let canonical = writer.written_buffer();
+
+    let mut varint = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut varint);
+    serialize(&payment, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(canonical, writer.written_buffer());
+
+    let decoded: Payment = deserialize(canonical, options()).unwrap();
+    assert_eq!(decoded, payment);
+}
+
+#[test]
+fn shortest_forms_are_accepted() {
+    for (value, bytes) in [
+        (250u64, &[250][..]),
+        (251, &[251, 251, 0]),
+        (65_535, &[251, 255, 255]),
+        (65_536, &[252, 0, 0, 1, 0]),
+        (u32::MAX as u64 + 1, &[253, 0, 0, 0, 0, 1, 0, 0, 0]),
+    ] {
+        assert_eq!(deserialize::<u64, _, _>(bytes, options()).unwrap(), value);
+    }
+    assert_eq!(
+        deserialize::<u128, _, _>(
+            &[254, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0][..],
+            options()
+        )
+        .unwrap(),
+        u64::MAX as u128 + 1
+    );
+}
+
+#[test]
+fn longer_forms_are_rejected() {
+    assert_non_canonical::<u16>(&[251, 5, 0]);
+    assert_non_canonical::<u32>(&[251, 250, 0]);
+    assert_non_canonical::<u32>(&[252, 255, 255, 0, 0]);
+    assert_non_canonical::<u64>(&[253, 255, 255, 255, 255, 0, 0, 0, 0]);
+    assert_non_canonical::<u128>(&[
+        254, 255, 255, 255, 255, 255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]);
+    // -1 is zigzag encoded as 1
+    assert_non_canonical::<i32>(&[251, 1, 0]);
+    assert_non_canonical::<i128>(&[252, 1, 0, 0, 0]);
+    // Lengths are varints as well
+    assert_non_canonical::<&str>(&[251, 1, 0, b'a']);
+}
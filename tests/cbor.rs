@@ -0,0 +1,190 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+};
+
+#[derive(Serialize)]
+enum Mode {
+    Idle,
+    Heat(u16),
+    Cycle(u8, u8),
+    Target { celsius: i16 },
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Status<'a> {
+    on: bool,
+    name: &'a str,
+    letter: char,
+    gain: f32,
+    ratio: f64,
+    offset: Option<i64>,
+    missing: Option<u8>,
+    history: [u16; 3],
+    pair: (u8, f64),
+    unit: Unit,
+    modes: (Mode, Mode, Mode, Mode),
+}
+
+fn status() -> Status<'static> {
+    Status {
+        on: true,
+        name: "pump",
+        letter: 'é',
+        gain: 1.5,
+        ratio: 0.1,
+        offset: Some(-70000),
+        missing: None,
+        history: [1, 300, 35000],
+        pair: (255, -0.25),
+        unit: Unit,
+        modes: (
+            Mode::Idle,
+            Mode::Heat(40),
+            Mode::Cycle(1, 2),
+            Mode::Target { celsius: -5 },
+        ),
+    }
+}
+
+fn cbor<T: serde::Serialize + ?Sized>(value: &T) -> Vec<u8> {
+    let mut buffer = [0u8; 512];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, DefaultOptions::new().with_cbor_format()).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+/// Checks that `value` is written the same as `serde_cbor` writes it.
+fn check<T: serde::Serialize>(value: &T) {
+    assert_eq!(cbor(value), serde_cbor::to_vec(value).unwrap());
+}
+
+#[test]
+fn ints() {
+    assert_eq!(cbor(&0u8), [0x00]);
+    assert_eq!(cbor(&23u64), [0x17]);
+    assert_eq!(cbor(&24u8), [0x18, 24]);
+    assert_eq!(cbor(&256u16), [0x19, 0x01, 0x00]);
+    assert_eq!(cbor(&65536u32), [0x1A, 0x00, 0x01, 0x00, 0x00]);
+    assert_eq!(cbor(&-1i8), [0x20]);
+    assert_eq!(cbor(&-25i32), [0x38, 24]);
+    assert_eq!(cbor(&-300i16), [0x39, 0x01, 0x2B]);
+
+    for value in [
+        0,
+        1,
+        23,
+        24,
+        255,
+        256,
+        65535,
+        65536,
+        u32::MAX as u64,
+        u64::MAX,
+    ] {
+        check(&value);
+    }
+    for value in [
+        0,
+        -1,
+        -24,
+        -25,
+        -256,
+        -257,
+        i32::MIN as i64,
+        i64::MIN,
+        i64::MAX,
+    ] {
+        check(&value);
+    }
+}
+
+#[test]
+fn wide_ints_use_bignums_only_when_needed() {
+    assert_eq!(cbor(&(u64::MAX as u128)), cbor(&u64::MAX));
+    assert_eq!(
+        cbor(&(-(u64::MAX as i128) - 1)),
+        [0x3B, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+    assert_eq!(
+        cbor(&(u64::MAX as u128 + 1)),
+        [0xC2, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        cbor(&(-(u64::MAX as i128) - 2)),
+        [0xC3, 0x49, 1, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(cbor(&u128::MAX)[..2], [0xC2, 0x50]);
+    assert_eq!(cbor(&i128::MIN)[..3], [0xC3, 0x50, 0x7F]);
+
+    for value in [0, 1, u64::MAX as u128] {
+        check(&value);
+    }
+    for value in [-1, 24, i64::MIN as i128, -(u64::MAX as i128) - 1] {
+        check(&value);
+    }
+}
+
+#[test]
+fn floats_use_the_shortest_exact_form() {
+    assert_eq!(cbor(&0.0f64), [0xF9, 0x00, 0x00]);
+    assert_eq!(cbor(&-0.0f32), [0xF9, 0x80, 0x00]);
+    assert_eq!(cbor(&1.5f64), [0xF9, 0x3E, 0x00]);
+    assert_eq!(cbor(&65504.0f32), [0xF9, 0x7B, 0xFF]);
+    assert_eq!(cbor(&5.960464477539063e-8f64), [0xF9, 0x00, 0x01]);
+    assert_eq!(cbor(&100000.0f64), [0xFA, 0x47, 0xC3, 0x50, 0x00]);
+    assert_eq!(
+        cbor(&1.1f64),
+        [0xFB, 0x3F, 0xF1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9A]
+    );
+    assert_eq!(cbor(&f32::INFINITY), [0xF9, 0x7C, 0x00]);
+    assert_eq!(cbor(&f64::NEG_INFINITY), [0xF9, 0xFC, 0x00]);
+    assert_eq!(cbor(&f64::NAN), [0xF9, 0x7E, 0x00]);
+    assert_eq!(cbor(&-f32::NAN), [0xF9, 0x7E, 0x00]);
+
+    for value in [
+        0.1f32,
+        1.0e-7,
+        3.0e-5,
+        6.1035156e-5,
+        f32::MIN_POSITIVE,
+        f32::MAX,
+    ] {
+        check(&value);
+    }
+    for value in [
+        0.1f64,
+        1.0e-300,
+        2.0f64.powi(-24),
+        2.0f64.powi(-25),
+        f64::MAX,
+    ] {
+        check(&value);
+    }
+}
+
+#[test]
+fn values_match_serde_cbor() {
+    check(&status());
+    check(&"x".repeat(300));
+    check(&[7u32; 30]);
+    check(&(Mode::Idle, Some(Mode::Heat(1)), None::<Mode>));
+}
+
+#[test]
+fn size_matches_and_deserializing_is_unsupported() {
+    let options = DefaultOptions::new().with_cbor_format();
+    let bytes = cbor(&status());
+    assert_eq!(serialize_size(&status(), options).unwrap(), bytes.len());
+
+    match deserialize::<u8, _, _>(&cbor(&5u8)[..], options) {
+        Err(DeserializeError::Unsupported(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
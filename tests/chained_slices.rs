@@ -0,0 +1,107 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, BufferWriter, ChainedSlices, CoreRead,
+    DefaultOptions, DeserializeError, SliceReadError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Packet<'a> {
+    seq: u32,
+    payload: &'a str,
+    checksum: u16,
+}
+
+fn packet() -> Packet<'static> {
+    Packet {
+        seq: 0x0102_0304,
+        payload: "hello",
+        checksum: 0xBEEF,
+    }
+}
+
+fn encode(packet: &Packet) -> Vec<u8> {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(packet, &mut writer, DefaultOptions::new()).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn every_split_point() {
+    let bytes = encode(&packet());
+    for first in 0..=bytes.len() {
+        for second in first..=bytes.len() {
+            let segments = [&bytes[..first], &bytes[first..second], &bytes[second..]];
+            let mut scratch = [0u8; 8];
+            let reader = ChainedSlices::new(&segments, &mut scratch);
+            let decoded: Packet = deserialize(reader, DefaultOptions::new()).unwrap();
+            assert_eq!(decoded, packet());
+        }
+    }
+}
+
+#[test]
+fn borrows_from_segments_when_possible() {
+    let bytes = encode(&packet());
+    // The split falls inside `seq`, the payload is in the second segment
+    let segments = [&bytes[..2], &bytes[2..]];
+    let reader = ChainedSlices::new(&segments, &mut []);
+    let decoded: Packet = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, packet());
+    assert!(segments[1]
+        .as_ptr_range()
+        .contains(&decoded.payload.as_ptr()));
+
+    // The split falls inside the payload
+    let segments = [&bytes[..8], &bytes[8..]];
+    let reader = ChainedSlices::new(&segments, &mut []);
+    match deserialize::<Packet, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::Read(SliceReadError::ScratchTooSmall)) => {}
+        x => panic!("Expected ScratchTooSmall, got {:?}", x),
+    }
+}
+
+#[test]
+fn end_of_input() {
+    let bytes = encode(&packet());
+    let segments = [&bytes[..4], &bytes[4..bytes.len() - 1]];
+    let mut scratch = [0u8; 8];
+    let reader = ChainedSlices::new(&segments, &mut scratch);
+    match deserialize::<Packet, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::UnexpectedEof { needed: 1 }) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+
+    let segments = [&bytes[..4], &[][..], &bytes[4..], &[7][..]];
+    let mut scratch = [0u8; 8];
+    let reader = ChainedSlices::new(&segments, &mut scratch);
+    assert_eq!(reader.remaining_len(), Some(bytes.len() + 1));
+    let options = DefaultOptions::new().reject_trailing_bytes();
+    match deserialize::<Packet, _, _>(reader, options) {
+        Err(DeserializeError::TrailingBytes) => {}
+        x => panic!("Expected TrailingBytes, got {:?}", x),
+    }
+}
+
+#[test]
+fn stream_of_values() {
+    let values: [u16; 4] = [1, 300, 70, 65535];
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    for value in &values {
+        serialize(value, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    let bytes = writer.written_buffer();
+    let segments = [&bytes[..2], &bytes[2..3], &bytes[3..]];
+    let reader = ChainedSlices::new(&segments, &mut []);
+    assert_eq!(reader.peek(2), Some(&bytes[..2]));
+    assert_eq!(reader.peek(3), None);
+
+    let decoded = deserialize_iter::<u16, _, _>(reader, DefaultOptions::new())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(decoded, values);
+}
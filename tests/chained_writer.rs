@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferWriterError, ChainedWriter, DefaultOptions, SerializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Packet<'a> {
+    seq: u32,
+    payload: &'a str,
+}
+
+#[test]
+fn spills_over_segments() {
+    let packet = Packet {
+        seq: 0x0102_0304,
+        payload: "hello",
+    };
+    let mut a = [0u8; 3];
+    let mut b = [0u8; 0];
+    let mut c = [0u8; 4];
+    let mut d = [0u8; 12];
+    let mut segments = [&mut a[..], &mut b[..], &mut c[..], &mut d[..]];
+    let mut writer = ChainedWriter::new(&mut segments);
+    serialize(
+        &packet,
+        &mut writer,
+        DefaultOptions::new().with_fixint_encoding(),
+    )
+    .unwrap();
+    assert_eq!(4 + 8 + 5, writer.written_len());
+
+    let mut joined = Vec::new();
+    joined.extend_from_slice(&a);
+    joined.extend_from_slice(&c);
+    joined.extend_from_slice(&d);
+    let deserialized: Packet =
+        deserialize(&joined[..17], DefaultOptions::new().with_fixint_encoding()).unwrap();
+    assert_eq!(packet, deserialized);
+}
+
+#[test]
+fn too_small() {
+    let mut a = [0u8; 2];
+    let mut b = [0u8; 2];
+    let mut segments = [&mut a[..], &mut b[..]];
+    let mut writer = ChainedWriter::new(&mut segments);
+    match serialize("abcd", &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+    // Only the length was written
+    assert_eq!(1, writer.written_len());
+}
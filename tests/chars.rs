@@ -0,0 +1,63 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+};
+
+const CHARS: [char; 6] = ['a', '\0', 'é', '€', '😀', char::MAX];
+
+fn round_trip(options: impl Options + Copy, sizes: [usize; 6]) {
+    for (c, size) in CHARS.iter().zip(sizes) {
+        let mut buffer = [0u8; 8];
+        let mut writer = BufferWriter::new(&mut buffer);
+        serialize(c, &mut writer, options).unwrap();
+        assert_eq!(writer.written_len(), size, "{:?}", c);
+        assert_eq!(serialize_size(c, options).unwrap(), size);
+
+        let decoded: char = deserialize(writer.written_buffer(), options).unwrap();
+        assert_eq!(decoded, *c);
+    }
+
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&(CHARS, "€"), &mut writer, options).unwrap();
+    let decoded: ([char; 6], &str) = deserialize(writer.written_buffer(), options).unwrap();
+    assert_eq!(decoded, (CHARS, "€"));
+}
+
+#[test]
+fn utf8() {
+    round_trip(DefaultOptions::new(), [1, 1, 2, 3, 4, 4]);
+    round_trip(DefaultOptions::new().with_utf8_chars(), [1, 1, 2, 3, 4, 4]);
+}
+
+#[test]
+fn u32_varint() {
+    round_trip(DefaultOptions::new().with_u32_chars(), [1, 1, 1, 3, 5, 5]);
+}
+
+#[test]
+fn u32_fixint() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_big_endian()
+        .with_u32_chars();
+    round_trip(options, [4; 6]);
+
+    let mut buffer = [0u8; 4];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&'😀', &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), &[0, 1, 0xF6, 0x00]);
+}
+
+#[test]
+fn invalid_code_points() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_u32_chars();
+    for code in [0xD800u32, 0xDFFF, 0x11_0000, u32::MAX] {
+        assert!(matches!(
+            deserialize::<char, _, _>(&code.to_le_bytes()[..], options),
+            Err(DeserializeError::InvalidCharEncoding)
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, BufferWriter, CheckpointRead, CoreRead, DefaultOptions, DeserializeError,
+    Deserializer, TransformReader,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SetLed {
+    led: u8,
+    on: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Echo<'a> {
+    text: &'a str,
+}
+
+#[derive(PartialEq, Debug)]
+enum Request<'a> {
+    SetLed(SetLed),
+    Echo(Echo<'a>),
+}
+
+fn dispatch<'a>(
+    deserializer: &mut Deserializer<'a, &'a [u8], impl Options>,
+) -> Option<Request<'a>> {
+    if let Ok(request) = deserializer.try_deserialize() {
+        return Some(Request::SetLed(request));
+    }
+    deserializer.try_deserialize().ok().map(Request::Echo)
+}
+
+#[test]
+fn dispatch_tries_each_message_type() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&Echo { text: "ping" }, &mut writer, DefaultOptions::new()).unwrap();
+    serialize(
+        &SetLed { led: 2, on: true },
+        &mut writer,
+        DefaultOptions::new(),
+    )
+    .unwrap();
+    let bytes = writer.written_buffer();
+
+    let mut deserializer = Deserializer::new(bytes, DefaultOptions::new());
+    // "ping" starts with a length of 4, which is not a valid bool
+    assert_eq!(
+        dispatch(&mut deserializer),
+        Some(Request::Echo(Echo { text: "ping" }))
+    );
+    assert_eq!(deserializer.bytes_read(), 5);
+    assert_eq!(
+        dispatch(&mut deserializer),
+        Some(Request::SetLed(SetLed { led: 2, on: true }))
+    );
+    assert!(deserializer.reader().is_empty());
+}
+
+#[test]
+fn failed_attempts_are_rolled_back() {
+    let buffer = [1u8, 5, 0xFF];
+    let mut deserializer = Deserializer::new(&buffer[..], DefaultOptions::new().with_limit(3));
+
+    match deserializer.try_deserialize::<(u8, u8, bool)>() {
+        Err(DeserializeError::InvalidBoolValue(0xFF)) => {}
+        x => panic!("Expected InvalidBoolValue, got {:?}", x),
+    }
+    assert_eq!(deserializer.bytes_read(), 0);
+    assert_eq!(deserializer.reader().len(), 3);
+
+    // The whole size limit is available again
+    let value: (u8, u8, u8) = deserializer.try_deserialize().unwrap();
+    assert_eq!(value, (1, 5, 0xFF));
+    assert_eq!(deserializer.bytes_read(), 3);
+    assert!(matches!(
+        deserializer.try_deserialize::<u8>(),
+        Err(DeserializeError::LimitError(_))
+    ));
+}
+
+#[test]
+fn slice_mark_and_reset() {
+    let buffer = [1u8, 2, 3];
+    let mut reader = &buffer[..];
+    let mark = reader.mark();
+
+    let mut bytes = [0u8; 2];
+    reader.fill(&mut bytes).unwrap();
+    assert_eq!(reader, &[3]);
+
+    reader.reset(mark);
+    assert_eq!(reader, &[1, 2, 3]);
+}
+
+#[test]
+fn peek_does_not_consume() {
+    let buffer = [1u8, 2, 3];
+    let reader = &buffer[..];
+    assert_eq!(reader.peek(2), Some(&[1, 2][..]));
+    assert_eq!(reader.peek(3), Some(&buffer[..]));
+    assert_eq!(reader.peek(4), None);
+    assert_eq!(reader.len(), 3);
+
+    // Readers that cannot look ahead return None
+    let reader = TransformReader::new(&buffer[..], |_: &mut [u8]| {});
+    assert_eq!(reader.peek(1), None);
+}
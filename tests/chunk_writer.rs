@@ -0,0 +1,75 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, BufferWriter, BufferWriterError, ChunkWriter, CoreWrite, DefaultOptions,
+    SerializeError,
+};
+
+/// A writer that records the size of every call to `write_all`.
+#[derive(Default)]
+struct Recorder {
+    data: Vec<u8>,
+    chunks: Vec<usize>,
+    flushed: bool,
+}
+
+impl CoreWrite for &mut Recorder {
+    type Error = ();
+
+    fn write(&mut self, val: u8) -> Result<(), ()> {
+        self.write_all(&[val])
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), ()> {
+        self.data.extend_from_slice(val);
+        self.chunks.push(val.len());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ()> {
+        self.flushed = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn int_sequence_is_written_in_chunks() {
+    let values: [u32; 20] = core::array::from_fn(|i| i as u32);
+    let mut recorder = Recorder::default();
+    let mut buffer = [0u8; 32];
+    let writer = ChunkWriter::new(&mut recorder, &mut buffer);
+    serialize(
+        &values,
+        writer,
+        DefaultOptions::new().with_fixint_encoding(),
+    )
+    .unwrap();
+
+    assert_eq!(vec![32, 32, 16], recorder.chunks);
+    assert!(recorder.flushed);
+    assert_eq!(&[0, 0, 0, 0, 1, 0, 0, 0], &recorder.data[..8]);
+}
+
+#[test]
+fn large_slices_bypass_the_buffer() {
+    let text = "x".repeat(100);
+    let value: (u8, &str) = (1, &text);
+    let mut recorder = Recorder::default();
+    let mut buffer = [0u8; 16];
+    let writer = ChunkWriter::new(&mut recorder, &mut buffer);
+    serialize(&value, writer, DefaultOptions::new()).unwrap();
+
+    // The u8 and the length are buffered, the string is written directly
+    assert_eq!(vec![2, 100], recorder.chunks);
+}
+
+#[test]
+fn buffer_writer_bulk_write() {
+    let mut buffer = [0u8; 4];
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize("hello", &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+    // The length fits, the string does not
+    assert_eq!(1, writer.written_len());
+}
@@ -0,0 +1,189 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::chunker::{
+    serialize_chunked, ChunkError, FragmentHeader, ReassembleError, Reassembler, HEADER_LEN,
+};
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize_size, DefaultOptions, SerializeError};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Telemetry {
+    device: [u8; 8],
+    samples: [u16; 10],
+    battery: u8,
+}
+
+fn telemetry() -> Telemetry {
+    Telemetry {
+        device: *b"lora-042",
+        samples: [1, 2, 3, 500, 600, 700, 65535, 0, 9, 10],
+        battery: 87,
+    }
+}
+
+/// Serializes `value` into a list of packets with the given MTU.
+fn packets<T: serde::Serialize>(value: &T, message_id: u8, mtu: usize) -> Vec<Vec<u8>> {
+    let mut packet = vec![0u8; mtu];
+    let mut packets = Vec::new();
+    serialize_chunked(value, message_id, &mut packet, DefaultOptions::new(), |p| {
+        assert!(p.len() <= mtu);
+        packets.push(p.to_vec());
+        Ok::<(), ()>(())
+    })
+    .unwrap();
+    packets
+}
+
+#[test]
+fn round_trip() {
+    let size = serialize_size(&telemetry(), DefaultOptions::new()).unwrap();
+    let packets = packets(&telemetry(), 9, 20);
+    assert_eq!(packets.len(), size.div_ceil(17));
+
+    let mut buffer = [0u8; 64];
+    let mut reassembler = Reassembler::new(&mut buffer);
+    let (last, rest) = packets.split_last().unwrap();
+    for packet in rest {
+        assert_eq!(reassembler.push(packet), Ok(None));
+        assert!(reassembler.is_partial());
+    }
+    let message = reassembler.push(last).unwrap().unwrap();
+    assert_eq!(message.len(), size);
+    let decoded: Telemetry = deserialize(message, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, telemetry());
+    assert!(!reassembler.is_partial());
+}
+
+#[test]
+fn headers() {
+    let sent = packets(&[7u8; 10], 3, 8);
+    let headers: Vec<_> = sent
+        .iter()
+        .map(|p| FragmentHeader::parse(p).unwrap().0)
+        .collect();
+    assert_eq!(
+        headers,
+        [0, 1].map(|index| FragmentHeader {
+            message_id: 3,
+            index,
+            total: 2,
+        })
+    );
+    assert_eq!(sent[0], [3, 0, 2, 7, 7, 7, 7, 7]);
+    assert_eq!(sent[1], [3, 1, 2, 7, 7, 7, 7, 7]);
+
+    // A value without any bytes is a single empty fragment
+    assert_eq!(packets(&(), 4, 8), [vec![4, 0, 1]]);
+}
+
+#[test]
+fn manual_flush_sends_last_fragment() {
+    let value = &[5u8; 10][..];
+    let mut packet = [0u8; 8];
+    let mut sent = Vec::new();
+    let options = DefaultOptions::new().with_manual_flush();
+    serialize_chunked(value, 1, &mut packet, options, |p| {
+        sent.push(p.to_vec());
+        Ok::<(), ()>(())
+    })
+    .unwrap();
+    assert_eq!(sent.len(), 3);
+
+    let mut buffer = [0u8; 16];
+    let mut reassembler = Reassembler::new(&mut buffer);
+    assert_eq!(reassembler.push(&sent[0]), Ok(None));
+    assert_eq!(reassembler.push(&sent[1]), Ok(None));
+    let message = reassembler.push(&sent[2]).unwrap().unwrap();
+    let decoded: &[u8] = deserialize(message, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn lost_fragment_discards_the_message() {
+    let first = packets(&telemetry(), 1, 8);
+    let second = packets(&telemetry(), 2, 8);
+    let mut buffer = [0u8; 64];
+    let mut reassembler = Reassembler::new(&mut buffer);
+
+    reassembler.push(&first[0]).unwrap();
+    assert_eq!(
+        reassembler.push(&first[2]),
+        Err(ReassembleError::UnexpectedFragment {
+            header: FragmentHeader::parse(&first[2]).unwrap().0,
+        })
+    );
+    assert!(!reassembler.is_partial());
+    assert!(matches!(
+        reassembler.push(&first[3]),
+        Err(ReassembleError::UnexpectedFragment { .. })
+    ));
+
+    let mut message = None;
+    for packet in &second {
+        message = reassembler.push(packet).unwrap().map(<[u8]>::to_vec);
+    }
+    let decoded: Telemetry = deserialize(&message.unwrap()[..], DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, telemetry());
+}
+
+#[test]
+fn reassembler_errors() {
+    let packets = packets(&telemetry(), 1, 12);
+    let mut buffer = [0u8; 16];
+    let mut reassembler = Reassembler::new(&mut buffer);
+    assert_eq!(
+        reassembler.push(&[1, 0]),
+        Err(ReassembleError::InvalidHeader)
+    );
+    assert_eq!(
+        reassembler.push(&[1, 2, 2, 0]),
+        Err(ReassembleError::InvalidHeader)
+    );
+
+    reassembler.push(&packets[0]).unwrap();
+    assert_eq!(
+        reassembler.push(&packets[1]),
+        Err(ReassembleError::MessageTooLarge)
+    );
+}
+
+#[test]
+fn serialize_errors() {
+    let send = |_: &[u8]| Ok::<(), ()>(());
+    let mut packet = [0u8; HEADER_LEN];
+    assert!(matches!(
+        serialize_chunked(&1u8, 0, &mut packet, DefaultOptions::new(), send),
+        Err(ChunkError::PacketTooSmall)
+    ));
+
+    let mut packet = [0u8; HEADER_LEN + 1];
+    assert!(matches!(
+        serialize_chunked(&[0u8; 32], 0, &mut packet, DefaultOptions::new(), send),
+        Ok(())
+    ));
+    let value = vec![0u8; 300];
+    assert!(matches!(
+        serialize_chunked(&value, 0, &mut packet, DefaultOptions::new(), send),
+        Err(ChunkError::TooManyFragments)
+    ));
+
+    let options = DefaultOptions::new().with_limit(4);
+    assert!(matches!(
+        serialize_chunked(&telemetry(), 0, &mut packet, options, send),
+        Err(ChunkError::Serialize(SerializeError::LimitReached))
+    ));
+
+    let mut packet = [0u8; 8];
+    let mut sent = 0;
+    let result = serialize_chunked(&telemetry(), 0, &mut packet, DefaultOptions::new(), |_| {
+        sent += 1;
+        if sent == 2 {
+            Err("link down")
+        } else {
+            Ok(())
+        }
+    });
+    assert!(matches!(result, Err(ChunkError::Send("link down"))));
+    assert_eq!(sent, 2);
+}
@@ -0,0 +1,253 @@
+#![cfg(feature = "compat-tests")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::compat::{self, SINGLE_BYTE_MAX, U128_BYTE, U16_BYTE, U32_BYTE, U64_BYTE};
+use bincode_core::config::{self, Options};
+use bincode_core::{deserialize, serialize, serialize_size, DefaultOptions};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Unit;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Newtype(u32);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct TupleStruct(i8, u64, Option<i16>);
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Message {
+    Ping,
+    Text(String),
+    Reading { sensor: u8, value: f32 },
+    Pair(i64, u128),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Nested {
+    id: u64,
+    tags: Vec<String>,
+    history: Vec<Message>,
+    lookup: BTreeMap<u16, Option<Newtype>>,
+    shape: TupleStruct,
+    marker: Unit,
+}
+
+/// Checks that bincode_core writes the same bytes as upstream bincode, reports the same size, and
+/// decodes the upstream bytes back to the original value.
+fn check<T, O, C>(value: &T, options: impl Fn() -> O, upstream: C)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+    O: Options,
+    C: bincode::config::Config,
+{
+    let expected = bincode::serde::encode_to_vec(value, upstream).unwrap();
+
+    let mut written = Vec::new();
+    serialize(value, &mut written, options()).unwrap();
+    assert_eq!(written, expected, "encoding of {:?}", value);
+    assert_eq!(serialize_size(value, options()).unwrap(), expected.len());
+
+    let decoded: T = deserialize(&expected[..], options()).unwrap();
+    assert_eq!(&decoded, value);
+}
+
+/// Runs `check` for a value with every preset, in little and big endian.
+fn check_all<T>(value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    check(&value, config::legacy, bincode::config::legacy());
+    check(&value, config::standard, bincode::config::standard());
+    check(
+        &value,
+        || config::legacy().with_big_endian(),
+        bincode::config::legacy().with_big_endian(),
+    );
+    check(
+        &value,
+        || config::standard().with_big_endian(),
+        bincode::config::standard().with_big_endian(),
+    );
+}
+
+#[test]
+fn varint_markers_match_upstream() {
+    let options = || DefaultOptions::new().allow_trailing_bytes();
+    let cases: [(u64, u8); 5] = [
+        (SINGLE_BYTE_MAX as u64, SINGLE_BYTE_MAX),
+        (SINGLE_BYTE_MAX as u64 + 1, U16_BYTE),
+        (u16::MAX as u64 + 1, U32_BYTE),
+        (u32::MAX as u64 + 1, U64_BYTE),
+        (u64::MAX, U64_BYTE),
+    ];
+    for (value, marker) in cases.iter() {
+        let expected = bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap();
+        assert_eq!(expected[0], *marker);
+        check(value, options, bincode::config::standard());
+    }
+
+    let value = u64::MAX as u128 + 1;
+    let expected = bincode::serde::encode_to_vec(value, bincode::config::standard()).unwrap();
+    assert_eq!(expected[0], U128_BYTE);
+    check(&value, options, bincode::config::standard());
+}
+
+#[test]
+fn unsigned_boundaries() {
+    for value in [0u16, 1, 250, 251, 252, 255, 256, u16::MAX] {
+        check_all(value);
+    }
+    for value in [
+        0u32,
+        250,
+        251,
+        u16::MAX as u32,
+        u16::MAX as u32 + 1,
+        u32::MAX,
+    ] {
+        check_all(value);
+    }
+    for value in [
+        0u64,
+        250,
+        251,
+        u32::MAX as u64,
+        u32::MAX as u64 + 1,
+        u64::MAX,
+    ] {
+        check_all(value);
+    }
+    for value in [
+        0u128,
+        250,
+        251,
+        u64::MAX as u128,
+        u64::MAX as u128 + 1,
+        u128::MAX,
+    ] {
+        check_all(value);
+    }
+    check_all(usize::MAX);
+    check_all(u8::MAX);
+}
+
+#[test]
+fn signed_boundaries() {
+    for value in [0i16, -1, 1, -125, 125, -126, 126, i16::MIN, i16::MAX] {
+        check_all(value);
+    }
+    for value in [0i32, -32768, 32767, -32769, 32768, i32::MIN, i32::MAX] {
+        check_all(value);
+    }
+    for value in [
+        0i64,
+        i32::MIN as i64 - 1,
+        i32::MAX as i64 + 1,
+        i64::MIN,
+        i64::MAX,
+    ] {
+        check_all(value);
+    }
+    for value in [
+        0i128,
+        i64::MIN as i128 - 1,
+        i64::MAX as i128 + 1,
+        i128::MIN,
+        i128::MAX,
+    ] {
+        check_all(value);
+    }
+    check_all(isize::MIN);
+    check_all(i8::MIN);
+}
+
+#[test]
+fn floats_bools_and_chars() {
+    for value in [
+        0.0f32,
+        -0.0,
+        1.5,
+        f32::MAX,
+        f32::MIN_POSITIVE,
+        f32::INFINITY,
+    ] {
+        check_all(value);
+    }
+    for value in [0.0f64, -0.0, 1e300, f64::MIN, f64::NEG_INFINITY] {
+        check_all(value);
+    }
+    check_all(true);
+    check_all(false);
+    for value in ['a', 'ß', '€', '🦀', '\0', char::MAX] {
+        check_all(value);
+    }
+}
+
+#[test]
+fn strings_and_sequences() {
+    check_all(String::new());
+    check_all(String::from("hello"));
+    check_all("x".repeat(300));
+    check_all(Vec::<u8>::new());
+    check_all((0..300u16).collect::<Vec<_>>());
+    check_all([1u8, 2, 3, 4]);
+    check_all((1u8, -2i32, 3u64));
+}
+
+#[test]
+fn options_and_units() {
+    check_all(Option::<u32>::None);
+    check_all(Some(Some(7u32)));
+    check_all(Some(Option::<u32>::None));
+    check_all(());
+    check_all(Unit);
+    check_all(Newtype(70_000));
+    check_all(TupleStruct(-1, u64::MAX, Some(-300)));
+}
+
+#[test]
+fn enums_and_nested_types() {
+    check_all(Message::Ping);
+    check_all(Message::Text(String::from("status ok")));
+    check_all(Message::Reading {
+        sensor: 3,
+        value: -12.5,
+    });
+    check_all(Message::Pair(i64::MIN, u128::MAX));
+
+    let mut lookup = BTreeMap::new();
+    lookup.insert(1, None);
+    lookup.insert(300, Some(Newtype(5)));
+    lookup.insert(u16::MAX, Some(Newtype(u32::MAX)));
+    check_all(Nested {
+        id: 1 << 40,
+        tags: vec![String::from("a"), String::from("bcd")],
+        history: vec![
+            Message::Ping,
+            Message::Pair(-1, 1),
+            Message::Text(String::new()),
+        ],
+        lookup,
+        shape: TupleStruct(5, 6, None),
+        marker: Unit,
+    });
+}
+
+#[test]
+fn compat_aliases_are_the_presets() {
+    let legacy = || -> compat::Legacy { config::legacy() };
+    let standard = || -> compat::Standard { config::standard() };
+    check(&70_000u32, legacy, bincode::config::legacy());
+    check(&70_000u32, standard, bincode::config::standard());
+    check(
+        &70_000u32,
+        compat::DefaultOptions::new,
+        bincode::config::standard(),
+    );
+}
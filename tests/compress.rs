@@ -0,0 +1,135 @@
+#![cfg(feature = "compression")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, serialize_size, BufferWriter, CompressedReadError,
+    CompressedReader, CompressedWriter, CoreRead, DefaultOptions, DeserializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Reading {
+    device: u16,
+    sensor: [u8; 8],
+    value: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Sample {
+    sensor: [u8; 16],
+    value: u32,
+}
+
+fn samples() -> Vec<Sample> {
+    (0..16)
+        .map(|i| Sample {
+            sensor: *b"temperature-east",
+            value: 20 + i % 3,
+        })
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    let mut buffer = [0u8; 512];
+    let mut window = [0u8; 256];
+    let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+    serialize(&samples(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+
+    let mut window = [0u8; 256];
+    let reader = CompressedReader::new(&buffer[..len], &mut window);
+    let decoded: Vec<Sample> = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, samples());
+}
+
+#[test]
+fn repeated_strings_compress() {
+    let uncompressed = serialize_size(&samples(), DefaultOptions::new()).unwrap();
+    let mut buffer = [0u8; 512];
+    let mut window = [0u8; 256];
+    let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+    serialize(&samples(), &mut writer, DefaultOptions::new()).unwrap();
+    let compressed = writer.into_inner().written_len();
+    assert!(
+        compressed * 3 < uncompressed,
+        "{} bytes compressed to {}",
+        uncompressed,
+        compressed
+    );
+}
+
+#[test]
+fn every_message_is_flushed() {
+    let readings = [
+        Reading {
+            device: 17,
+            sensor: *b"humidity",
+            value: 40,
+        },
+        Reading {
+            device: 17,
+            sensor: *b"humidity",
+            value: 41,
+        },
+        Reading {
+            device: 17,
+            sensor: *b"pressure",
+            value: -3,
+        },
+    ];
+    let mut buffer = [0u8; 128];
+    let mut window = [0u8; 64];
+    let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+    let mut ends = Vec::new();
+    for reading in &readings {
+        serialize(reading, &mut writer, DefaultOptions::new()).unwrap();
+        ends.push(writer.writer().written_len());
+    }
+    // The second message mostly refers back to the first one
+    assert!(ends[1] - ends[0] < ends[0] / 2);
+
+    // The first message can be decoded before the next one is written
+    let mut window = [0u8; 64];
+    let reader = CompressedReader::new(&buffer[..ends[0]], &mut window);
+    let first: Reading = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(first, readings[0]);
+
+    let mut window = [0u8; 64];
+    let reader = CompressedReader::new(&buffer[..ends[2]], &mut window);
+    let decoded = deserialize_iter::<Reading, _, _>(reader, DefaultOptions::new())
+        .take(3)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(decoded, readings);
+}
+
+#[test]
+fn borrowing_needs_a_buffered_reader() {
+    let mut buffer = [0u8; 32];
+    let mut window = [0u8; 16];
+    let mut writer = CompressedWriter::new(BufferWriter::new(&mut buffer), &mut window);
+    serialize(&"abc", &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+
+    let mut window = [0u8; 16];
+    let reader = CompressedReader::new(&buffer[..len], &mut window);
+    match deserialize::<&str, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::Read(CompressedReadError::CannotBorrow)) => {}
+        other => panic!("expected CannotBorrow, got {:?}", other),
+    }
+}
+
+#[test]
+fn reference_before_the_start_is_rejected() {
+    // A back-reference with a distance of 1 as the first token
+    let input = [0b0000_0000, 0b0000_0000];
+    let mut window = [0u8; 256];
+    let mut reader = CompressedReader::new(&input[..], &mut window);
+    let mut byte = [0u8];
+    match reader.fill(&mut byte) {
+        Err(CompressedReadError::InvalidReference) => {}
+        other => panic!("expected InvalidReference, got {:?}", other),
+    }
+}
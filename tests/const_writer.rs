@@ -0,0 +1,137 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, BufferWriter, ConstWriter, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Mode {
+    Off,
+    Fixed(u16),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Config<'a> {
+    id: u8,
+    offset: i8,
+    port: u16,
+    baud_rate: u32,
+    serial: u64,
+    calibration: i16,
+    drift: i32,
+    epoch: i64,
+    key: u128,
+    delta: i128,
+    enabled: bool,
+    gain: f32,
+    scale: f64,
+    name: &'a str,
+    blob: &'a [u8],
+    address: [u8; 4],
+    timeout: Option<u32>,
+    retries: Option<u32>,
+    mode: Mode,
+}
+
+const DEFAULT: Config<'static> = Config {
+    id: 7,
+    offset: -3,
+    port: 8080,
+    baud_rate: 115_200,
+    serial: u64::MAX - 1,
+    calibration: -300,
+    drift: i32::MIN,
+    epoch: 1 << 40,
+    key: u128::MAX,
+    delta: -1,
+    enabled: true,
+    gain: 1.5,
+    scale: -0.001,
+    name: "sensor",
+    blob: &[1, 2, 3],
+    address: [10, 0, 0, 1],
+    timeout: Some(500),
+    retries: None,
+    mode: Mode::Fixed(1000),
+};
+
+const fn write_default<const N: usize>(writer: ConstWriter<N>) -> ConstWriter<N> {
+    writer
+        .u8(DEFAULT.id)
+        .i8(DEFAULT.offset)
+        .u16(DEFAULT.port)
+        .u32(DEFAULT.baud_rate)
+        .u64(DEFAULT.serial)
+        .i16(DEFAULT.calibration)
+        .i32(DEFAULT.drift)
+        .i64(DEFAULT.epoch)
+        .u128(DEFAULT.key)
+        .i128(DEFAULT.delta)
+        .bool(DEFAULT.enabled)
+        .f32(DEFAULT.gain)
+        .f64(DEFAULT.scale)
+        .str(DEFAULT.name)
+        .byte_slice(DEFAULT.blob)
+        .bytes(&DEFAULT.address)
+        .option(true)
+        .u32(500)
+        .option(false)
+        .variant(1)
+        .u16(1000)
+}
+
+const VARINT_LEN: usize = write_default(ConstWriter::<128>::new()).len();
+const VARINT: [u8; VARINT_LEN] = write_default(ConstWriter::new()).finish();
+
+const FIXINT_LEN: usize = write_default(ConstWriter::<128>::new().with_fixint_encoding()).len();
+const FIXINT_BIG_ENDIAN: [u8; FIXINT_LEN] =
+    write_default(ConstWriter::new().with_fixint_encoding().with_big_endian()).finish();
+
+fn check<O: Options>(expected: &[u8], options: impl Fn() -> O) {
+    let mut buffer = [0u8; 128];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&DEFAULT, &mut writer, options()).unwrap();
+    assert_eq!(writer.written_buffer(), expected);
+
+    let config: Config = deserialize(expected, options()).unwrap();
+    assert_eq!(config, DEFAULT);
+}
+
+#[test]
+fn matches_the_runtime_serializer() {
+    check(&VARINT, DefaultOptions::new);
+}
+
+#[test]
+fn matches_the_runtime_serializer_with_fixint_big_endian() {
+    check(&FIXINT_BIG_ENDIAN, || {
+        DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_big_endian()
+    });
+}
+
+#[test]
+fn varint_boundaries() {
+    for value in [0u64, 250, 251, 65535, 65536, u32::MAX as u64, u64::MAX] {
+        let bytes = ConstWriter::<9>::new().u64(value);
+        let mut buffer = [0u8; 9];
+        let mut writer = BufferWriter::new(&mut buffer);
+        serialize(&value, &mut writer, DefaultOptions::new()).unwrap();
+        assert_eq!(writer.written_buffer(), bytes.written_buffer());
+    }
+    const SMALL: [u8; 4] = ConstWriter::new().i16(-1).u16(251).finish();
+    assert_eq!(SMALL, [1, 251, 251, 0]);
+}
+
+#[test]
+#[should_panic(expected = "does not match the array length")]
+fn finish_checks_the_length() {
+    ConstWriter::<4>::new().u16(1).finish();
+}
+
+#[test]
+#[should_panic(expected = "too small")]
+fn writes_past_the_end_panic() {
+    let _ = ConstWriter::<2>::new().with_fixint_encoding().u32(1);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "core-error")]
+
+use bincode_core::config::LimitError;
+use bincode_core::{
+    deserialize, serialize, BufferWriter, BufferWriterError, DefaultOptions, SliceReadError,
+};
+use core::error::Error;
+
+fn message(error: &dyn Error) -> String {
+    error.to_string()
+}
+
+#[test]
+fn errors_are_core_errors() {
+    let err = deserialize::<bool, _, _>(&[2u8][..], DefaultOptions::new()).unwrap_err();
+    assert!(message(&err).contains("bool"));
+
+    let err = serialize(
+        &"abc",
+        BufferWriter::new(&mut [0u8; 2]),
+        DefaultOptions::new(),
+    )
+    .unwrap_err();
+    assert_eq!(message(&err), "Write error BufferTooSmall");
+
+    assert_eq!(
+        message(&BufferWriterError::TooManyFrames),
+        "No room to record another frame"
+    );
+    assert_eq!(message(&LimitError::LimitReached), "Limit reached");
+    assert!(!message(&SliceReadError::Custom).is_empty());
+}
+
+#[test]
+fn question_mark_into_boxed_error() {
+    fn parse(bytes: &[u8]) -> Result<u8, Box<dyn Error + '_>> {
+        Ok(deserialize(bytes, DefaultOptions::new())?)
+    }
+    assert_eq!(parse(&[7]).unwrap(), 7);
+    assert!(parse(&[]).is_err());
+}
@@ -0,0 +1,148 @@
+//! Pins the encoding of `core` types, so a change in this crate or in serde that changes the
+//! documented layouts is caught.
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+use core::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use core::num::{NonZeroI16, NonZeroU32, NonZeroU8, Wrapping};
+use core::ops::{Range, RangeInclusive};
+use core::time::Duration;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+
+fn check<T, O>(value: T, options: O, expected: &[u8])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+    O: Options + Copy,
+{
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), expected, "{:?}", value);
+    assert_eq!(serialize_size(&value, options).unwrap(), expected.len());
+    let decoded: T = deserialize(expected, options).unwrap();
+    assert_eq!(decoded, value);
+}
+
+fn varint() -> impl Options + Copy {
+    DefaultOptions::new()
+}
+
+fn fixint() -> impl Options + Copy {
+    DefaultOptions::new().with_fixint_encoding()
+}
+
+#[test]
+fn duration() {
+    let value = Duration::new(3, 500_000_000);
+    check(value, varint(), &[3, 252, 0x00, 0x65, 0xCD, 0x1D]);
+    check(
+        value,
+        fixint(),
+        &[3, 0, 0, 0, 0, 0, 0, 0, 0x00, 0x65, 0xCD, 0x1D],
+    );
+    check(
+        Duration::from_millis(5),
+        varint(),
+        &[0, 252, 0x40, 0x4B, 0x4C, 0x00],
+    );
+    check(Duration::ZERO, varint(), &[0, 0]);
+}
+
+#[test]
+fn invalid_duration() {
+    // `u64::MAX` seconds plus more than a second of nanoseconds overflows
+    let bytes = [
+        253, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 252, 0x00, 0xCA, 0x9A, 0x3B,
+    ];
+    assert!(deserialize::<Duration, _, _>(&bytes[..], varint()).is_err());
+}
+
+#[test]
+fn non_zero() {
+    check(NonZeroU8::new(7).unwrap(), varint(), &[7]);
+    check(NonZeroU32::new(300).unwrap(), varint(), &[251, 0x2C, 0x01]);
+    check(NonZeroU32::new(300).unwrap(), fixint(), &[0x2C, 0x01, 0, 0]);
+    check(NonZeroI16::new(-1).unwrap(), varint(), &[1]);
+    check(NonZeroI16::new(-1).unwrap(), fixint(), &[0xFF, 0xFF]);
+    assert!(deserialize::<NonZeroU32, _, _>(&[0][..], varint()).is_err());
+}
+
+#[test]
+fn wrapping() {
+    check(Wrapping(300u16), varint(), &[251, 0x2C, 0x01]);
+    check(Wrapping(300u16), fixint(), &[0x2C, 0x01]);
+    check(Wrapping(-2i32), varint(), &[3]);
+}
+
+#[test]
+fn ranges() {
+    check(
+        Range {
+            start: 1u32,
+            end: 300,
+        },
+        varint(),
+        &[1, 251, 0x2C, 0x01],
+    );
+    check(
+        Range {
+            start: 1u32,
+            end: 300,
+        },
+        fixint(),
+        &[1, 0, 0, 0, 0x2C, 0x01, 0, 0],
+    );
+    check(RangeInclusive::new(1u8, 255), varint(), &[1, 255]);
+    check(RangeInclusive::new(1u16, 2), fixint(), &[1, 0, 2, 0]);
+}
+
+#[test]
+fn ip_addresses() {
+    let v4 = Ipv4Addr::new(192, 168, 1, 2);
+    let v6 = Ipv6Addr::new(0xFE80, 0, 0, 0, 0, 0, 0, 1);
+    let v6_bytes = [0xFE, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+
+    check(v4, varint(), &[192, 168, 1, 2]);
+    check(v4, fixint(), &[192, 168, 1, 2]);
+    check(v6, varint(), &v6_bytes);
+
+    check(IpAddr::V4(v4), varint(), &[0, 192, 168, 1, 2]);
+    check(IpAddr::V4(v4), fixint(), &[0, 0, 0, 0, 192, 168, 1, 2]);
+    let mut expected = vec![1];
+    expected.extend_from_slice(&v6_bytes);
+    check(IpAddr::V6(v6), varint(), &expected);
+}
+
+#[test]
+fn socket_addresses() {
+    let v4 = SocketAddrV4::new(Ipv4Addr::new(10, 0, 0, 1), 8080);
+    check(v4, varint(), &[10, 0, 0, 1, 251, 0x90, 0x1F]);
+    check(v4, fixint(), &[10, 0, 0, 1, 0x90, 0x1F]);
+    check(
+        SocketAddr::V4(v4),
+        varint(),
+        &[0, 10, 0, 0, 1, 251, 0x90, 0x1F],
+    );
+    check(
+        SocketAddr::V4(v4),
+        fixint(),
+        &[0, 0, 0, 0, 10, 0, 0, 1, 0x90, 0x1F],
+    );
+
+    let v6 = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 53, 0, 0);
+    let mut expected = vec![1];
+    expected.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+    expected.push(53);
+    check(SocketAddr::V6(v6), varint(), &expected);
+
+    // The flow info and scope id are not encoded
+    let with_scope = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 53, 7, 3);
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&with_scope, &mut writer, varint()).unwrap();
+    assert_eq!(writer.written_buffer(), &expected[1..]);
+    let decoded: SocketAddrV6 = deserialize(&expected[1..], varint()).unwrap();
+    assert_eq!(decoded, v6);
+}
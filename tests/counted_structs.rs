@@ -0,0 +1,270 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, BufferWriterError, CoreWrite,
+    DefaultOptions, DeserializeError, SerializeError, Serializer,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SensorV1 {
+    id: u8,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SensorV2 {
+    id: u8,
+    #[serde(default)]
+    calibration: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct FrameV1 {
+    seq: u16,
+    sensor: SensorV1,
+    ok: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct FrameV2<'a> {
+    seq: u16,
+    sensor: SensorV2,
+    ok: bool,
+    #[serde(default)]
+    note: Option<&'a str>,
+    #[serde(default)]
+    samples: [u32; 2],
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum CommandV1 {
+    Reset,
+    Set { id: u8 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum CommandV2 {
+    Reset,
+    Set {
+        id: u8,
+        #[serde(default)]
+        value: u64,
+    },
+}
+
+fn convert<'a, T, U, O>(
+    value: &T,
+    buffer: &'a mut [u8],
+    options: O,
+) -> Result<U, DeserializeError<'a, &'a [u8]>>
+where
+    T: Serialize,
+    U: Deserialize<'a> + Debug,
+    O: Options + Copy,
+{
+    let mut writer = BufferWriter::new(buffer);
+    serialize(value, &mut writer, options).unwrap();
+    let len = writer.written_len();
+    assert_eq!(serialize_size(value, options).unwrap(), len);
+    deserialize(&buffer[..len], options)
+}
+
+fn options() -> impl Options + Copy {
+    DefaultOptions::new().with_counted_structs()
+}
+
+#[test]
+fn older_reader() {
+    let frame = FrameV2 {
+        seq: 300,
+        sensor: SensorV2 {
+            id: 4,
+            calibration: -70_000,
+        },
+        ok: true,
+        note: Some("drift"),
+        samples: [1, 1 << 20],
+    };
+    let mut buffer = [0u8; 64];
+    let decoded: FrameV1 = convert(&frame, &mut buffer, options()).unwrap();
+    assert_eq!(
+        decoded,
+        FrameV1 {
+            seq: 300,
+            sensor: SensorV1 { id: 4 },
+            ok: true,
+        }
+    );
+}
+
+#[test]
+fn newer_reader() {
+    let frame = FrameV1 {
+        seq: 300,
+        sensor: SensorV1 { id: 4 },
+        ok: false,
+    };
+    let mut buffer = [0u8; 64];
+    let decoded: FrameV2 = convert(&frame, &mut buffer, options()).unwrap();
+    assert_eq!(
+        decoded,
+        FrameV2 {
+            seq: 300,
+            sensor: SensorV2 {
+                id: 4,
+                calibration: 0
+            },
+            ok: false,
+            note: None,
+            samples: [0, 0],
+        }
+    );
+}
+
+#[test]
+fn struct_variants() {
+    let mut buffer = [0u8; 32];
+    let commands = [CommandV2::Set { id: 1, value: 9 }, CommandV2::Reset];
+    let decoded: [CommandV1; 2] = convert(&commands, &mut buffer, options()).unwrap();
+    assert_eq!(decoded, [CommandV1::Set { id: 1 }, CommandV1::Reset]);
+
+    let decoded: CommandV2 = convert(&CommandV1::Set { id: 1 }, &mut buffer, options()).unwrap();
+    assert_eq!(decoded, CommandV2::Set { id: 1, value: 0 });
+}
+
+#[test]
+fn packed_bools_stay_in_their_struct() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Flags {
+        a: bool,
+        b: bool,
+    }
+
+    let options = options().with_packed_bools();
+    let value = (
+        Flags { a: true, b: false },
+        true,
+        Flags { a: false, b: true },
+    );
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options).unwrap();
+    // Every struct has its own byte of bools, the bool between them gets one as well
+    assert_eq!(writer.written_buffer(), &[2, 1, 0b01, 0b1, 2, 1, 0b10]);
+    assert_eq!(serialize_size(&value, options).unwrap(), 7);
+
+    let decoded: (Flags, bool, Flags) = deserialize(writer.written_buffer(), options).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn presence_bitmap() {
+    let options = options().with_presence_bitmap();
+    let frame = FrameV2 {
+        seq: 1,
+        sensor: SensorV2 {
+            id: 2,
+            calibration: 3,
+        },
+        ok: true,
+        note: None,
+        samples: [4, 5],
+    };
+    let mut buffer = [0u8; 64];
+    let decoded: FrameV2 = convert(&frame, &mut buffer, options).unwrap();
+    assert_eq!(decoded, frame);
+    let decoded: FrameV1 = convert(&frame, &mut buffer, options).unwrap();
+    assert_eq!(decoded.sensor, SensorV1 { id: 2 });
+}
+
+#[test]
+fn fields_longer_than_struct() {
+    match deserialize::<SensorV1, _, _>(&[1u8, 0, 5][..], options()) {
+        Err(DeserializeError::InvalidStructLength { len: 0, read: 1 }) => {}
+        x => panic!("Expected InvalidStructLength, got {:?}", x),
+    }
+}
+
+/// A writer that cannot seek, so the length of every struct is measured before it is written.
+struct Unseekable<'a>(BufferWriter<'a>);
+
+impl CoreWrite for &mut Unseekable<'_> {
+    type Error = BufferWriterError;
+
+    fn write(&mut self, val: u8) -> Result<(), BufferWriterError> {
+        self.0.write(val)
+    }
+}
+
+#[test]
+fn requires_serialize_value() {
+    let mut buffer = [0u8; 8];
+    let mut writer = Unseekable(BufferWriter::new(&mut buffer));
+    let mut serializer = Serializer::new(&mut writer, options());
+    assert!(matches!(
+        SensorV1 { id: 1 }.serialize(&mut serializer),
+        Err(SerializeError::PresenceUnknown)
+    ));
+    serializer.serialize_value(&SensorV1 { id: 1 }).unwrap();
+    assert_eq!(writer.0.written_buffer(), &[1, 1, 1]);
+
+    // A writer that can seek writes the length after the fields
+    let mut serializer = Serializer::new(BufferWriter::new(&mut buffer), options());
+    SensorV1 { id: 1 }.serialize(&mut serializer).unwrap();
+    assert_eq!(serializer.writer().written_buffer(), &[1, 1, 1]);
+}
+
+#[test]
+fn seekable_writer() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Inner<'a> {
+        data: &'a [u8],
+        flag: bool,
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Outer<'a> {
+        #[serde(borrow)]
+        inner: Inner<'a>,
+        command: CommandV2,
+        tail: u8,
+    }
+
+    let data = [7u8; 300];
+    let value = Outer {
+        inner: Inner {
+            data: &data,
+            flag: true,
+        },
+        command: CommandV2::Set { id: 1, value: 2 },
+        tail: 9,
+    };
+    let options = options().with_packed_bools();
+    let size = serialize_size(&value, options).unwrap();
+
+    let mut expected = [0u8; 512];
+    let mut writer = Unseekable(BufferWriter::new(&mut expected));
+    serialize(&value, &mut writer, options).unwrap();
+    let expected = writer.0.written_buffer();
+    assert_eq!(expected.len(), size);
+
+    // The lengths of more than 250 bytes do not fit in the placeholder, and move the fields
+    let mut buffer = [0u8; 512];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), expected);
+    let decoded: Outer = deserialize(writer.written_buffer(), options).unwrap();
+    assert_eq!(decoded, value);
+
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options.with_limit(size as u64)).unwrap();
+    assert_eq!(writer.written_buffer(), expected);
+    let mut writer = BufferWriter::new(&mut buffer);
+    assert!(matches!(
+        serialize(&value, &mut writer, options.with_limit(size as u64 - 1)),
+        Err(SerializeError::LimitReached)
+    ));
+}
@@ -0,0 +1,27 @@
+use bincode_core::{
+    serialize, BufferWriter, BufferWriterError, CountingWriter, DefaultOptions, SerializeError,
+};
+
+#[test]
+fn counts_forwarded_bytes() {
+    let mut buffer = [0u8; 16];
+    let mut writer = CountingWriter::wrap(BufferWriter::new(&mut buffer));
+    serialize(&(1u8, "abc"), &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(5, writer.count());
+
+    writer.reset();
+    serialize(&300u16, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(3, writer.count());
+    assert_eq!(8, writer.into_inner().written_len());
+}
+
+#[test]
+fn failed_writes_are_not_counted() {
+    let mut buffer = [0u8; 3];
+    let mut writer = CountingWriter::wrap(BufferWriter::new(&mut buffer));
+    match serialize(&(1u8, "abc"), &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+    assert_eq!(2, writer.count());
+}
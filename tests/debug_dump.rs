@@ -0,0 +1,164 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{debug_dump, BufferWriter, BufferWriterError, SerializeError};
+use serde::Serialize;
+
+#[derive(Serialize)]
+enum Mode {
+    Idle,
+    Heat(u16),
+    Cycle(u8, u8),
+    Target { celsius: i16 },
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Id(u32);
+
+#[derive(Serialize)]
+struct Status<'a> {
+    on: bool,
+    id: Id,
+    name: &'a str,
+    letter: char,
+    offset: Option<i64>,
+    missing: Option<u8>,
+    history: [u16; 3],
+    pair: (u8, f64),
+    unit: Unit,
+    modes: (Mode, Mode, Mode, Mode),
+}
+
+fn dump<T: Serialize + ?Sized>(value: &T) -> String {
+    let mut buffer = [0u8; 512];
+    let mut writer = BufferWriter::new(&mut buffer);
+    debug_dump(value, &mut writer).unwrap();
+    String::from_utf8(writer.written_buffer().to_vec()).unwrap()
+}
+
+#[test]
+fn structs_and_enums() {
+    let status = Status {
+        on: true,
+        id: Id(7),
+        name: "pump",
+        letter: 'é',
+        offset: Some(-70000),
+        missing: None,
+        history: [1, 300, 4464],
+        pair: (255, -0.25),
+        unit: Unit,
+        modes: (
+            Mode::Idle,
+            Mode::Heat(40),
+            Mode::Cycle(1, 2),
+            Mode::Target { celsius: -5 },
+        ),
+    };
+    assert_eq!(
+        dump(&status),
+        "{on: true, id: 7, name: \"pump\", letter: \"é\", offset: -70000, missing: null, \
+         history: [1, 300, 4464], pair: [255, -0.25], unit: null, \
+         modes: [Idle, Heat(40), Cycle(1, 2), Target {celsius: -5}]}"
+    );
+}
+
+#[test]
+fn ints() {
+    assert_eq!(dump(&0u8), "0");
+    assert_eq!(dump(&u64::MAX), "18446744073709551615");
+    assert_eq!(dump(&i64::MIN), "-9223372036854775808");
+    assert_eq!(dump(&u128::MAX), "340282366920938463463374607431768211455");
+    assert_eq!(dump(&i128::MIN), "-170141183460469231731687303715884105728");
+}
+
+#[test]
+fn floats() {
+    assert_eq!(dump(&0.0f64), "0.0");
+    assert_eq!(dump(&-0.0f64), "-0.0");
+    assert_eq!(dump(&2.0f32), "2.0");
+    assert_eq!(dump(&1.5f32), "1.5");
+    assert_eq!(dump(&(1.0f64 / 3.0)), "0.333333");
+    assert_eq!(dump(&0.9999999f64), "1.0");
+    assert_eq!(dump(&-123456.75f64), "-123456.75");
+    assert_eq!(dump(&1.2e20f64), "1.2e20");
+    assert_eq!(dump(&-2.5e-7f64), "-2.5e-7");
+    assert_eq!(dump(&f64::NAN), "NaN");
+    assert_eq!(dump(&f32::INFINITY), "inf");
+    assert_eq!(dump(&f64::NEG_INFINITY), "-inf");
+}
+
+#[test]
+fn strings_are_escaped() {
+    assert_eq!(dump("a \"quote\"\\"), "\"a \\\"quote\\\"\\\\\"");
+    assert_eq!(dump("line\r\n\tend\u{1}"), "\"line\\r\\n\\tend\\u0001\"");
+    assert_eq!(dump(&'"'), "\"\\\"\"");
+}
+
+#[test]
+fn bytes_are_hex() {
+    struct Bytes(&'static [u8]);
+
+    impl Serialize for Bytes {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_bytes(self.0)
+        }
+    }
+
+    assert_eq!(dump(&Bytes(&[0x0A, 0x1B, 0xFF])), "0x0a1bff");
+    assert_eq!(dump(&Bytes(&[])), "0x");
+}
+
+#[test]
+fn maps_and_unknown_lengths() {
+    struct Odd;
+
+    impl Serialize for Odd {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq((0u8..10).filter(|i| i % 2 == 1))
+        }
+    }
+
+    struct Map;
+
+    impl Serialize for Map {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_map(vec![("a", 1u8), ("b", 2)])
+        }
+    }
+
+    assert_eq!(dump(&Odd), "[1, 3, 5, 7, 9]");
+    assert_eq!(dump(&Map), "{\"a\": 1, \"b\": 2}");
+    assert_eq!(dump(&Vec::<u8>::new()), "[]");
+}
+
+#[test]
+fn collect_str_is_escaped() {
+    struct Quoted;
+
+    impl core::fmt::Display for Quoted {
+        fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+            write!(f, "say \"{}\"", 42)
+        }
+    }
+
+    impl Serialize for Quoted {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    assert_eq!(dump(&Quoted), "\"say \\\"42\\\"\"");
+}
+
+#[test]
+fn writer_errors_are_returned() {
+    let mut buffer = [0u8; 8];
+    match debug_dump("too long for the buffer", BufferWriter::new(&mut buffer)) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        other => panic!("{:?}", other),
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{decode_dump, DefaultOptions, ErrorKind};
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct Status {
+    serial: [u8; 16],
+    count: u8,
+    on: bool,
+    mode: u8,
+}
+
+const HEX: &str = "0102030405060708090a0b0c0d0e0f10 00 01 02";
+
+fn status() -> Status {
+    Status {
+        serial: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+        count: 0,
+        on: true,
+        mode: 2,
+    }
+}
+
+#[test]
+fn hex_formats() {
+    let dumps = [
+        HEX.to_string(),
+        HEX.to_uppercase(),
+        format!("  {}\n", HEX),
+        "01:02:03:04:05:06:07:08:09:0a:0b:0c:0d:0e:0f:10:00:01:02".to_string(),
+        "01-02-03-04-05-06-07-08-09-0A-0B-0C-0D-0E-0F-10-00-01-02".to_string(),
+        "[0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, \
+         0x0f, 0x10, 0x00, 0x01, 0x02]"
+            .to_string(),
+    ];
+    for dump in dumps.iter() {
+        let decoded: Status = decode_dump(dump.as_bytes(), DefaultOptions::new()).unwrap();
+        assert_eq!(decoded, status(), "{}", dump);
+    }
+}
+
+#[test]
+fn raw_bytes() {
+    let bytes = [
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 0, 1, 2,
+    ];
+    let decoded: Status = decode_dump(&bytes, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, status());
+
+    // Not hex, because of the odd amount of digits
+    let decoded: (u8, u8, u8) = decode_dump(b"123", DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, (b'1', b'2', b'3'));
+}
+
+#[test]
+fn error_shows_the_failing_byte() {
+    let err = decode_dump::<Status, _>(
+        b"0102030405060708090a0b0c0d0e0f10 00 07 01",
+        DefaultOptions::new(),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidData);
+    assert_eq!(err.offset, 17);
+    assert_eq!(err.path.indices(), [2]);
+    assert_eq!(err.bytes.len(), 19);
+    assert_eq!(
+        err.to_string(),
+        "Unknown bool value, got 7, expected 0 or 1 at offset 17 while reading field 2\n\
+         0000  01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10\n\
+         0010  00 07 01\n\
+         \x20        ^^\n"
+    );
+}
+
+#[test]
+fn error_at_the_end_of_the_input() {
+    let err = decode_dump::<Status, _>(b"0102030405060708090a0b0c0d0e0f10", DefaultOptions::new())
+        .unwrap_err();
+    assert_eq!(err.kind, ErrorKind::UnexpectedEof);
+    assert_eq!(err.offset, 16);
+    assert_eq!(
+        err.to_string(),
+        "Unexpected end of input, 1 more bytes are needed at offset 16 while reading field 1\n\
+         0000  01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10\n\
+         \x20     ^^\n"
+    );
+}
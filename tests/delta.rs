@@ -0,0 +1,189 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::delta::{apply_delta, serialize_delta};
+use bincode_core::{serialize_size, BufferWriter, DefaultOptions, DeserializeError};
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Telemetry<'a> {
+    uptime: u32,
+    temperature: i16,
+    battery: u8,
+    position: (f32, f32),
+    status: Status,
+    armed: bool,
+    alert: Option<&'a str>,
+    samples: [u16; 32],
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub enum Status {
+    Idle,
+    Moving { speed: u16 },
+}
+
+fn telemetry() -> Telemetry<'static> {
+    Telemetry {
+        uptime: 1000,
+        temperature: -5,
+        battery: 90,
+        position: (52.1, 4.3),
+        status: Status::Idle,
+        armed: false,
+        alert: None,
+        samples: [300; 32],
+    }
+}
+
+fn delta<T: Serialize + ?Sized, O: Options>(prev: &T, current: &T, options: O) -> Vec<u8> {
+    let mut buffer = [0u8; 256];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_delta(prev, current, &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn unchanged_value_is_only_a_bitmap() {
+    let prev = telemetry();
+    let written = delta(&prev, &prev, DefaultOptions::new());
+    assert_eq!(written, [0]);
+
+    let mut scratch = [0u8; 256];
+    let decoded: Telemetry =
+        apply_delta(&prev, &written[..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, prev);
+}
+
+#[test]
+fn only_changed_fields_are_written() {
+    let prev = telemetry();
+    let mut current = prev.clone();
+    current.uptime = 1001;
+    current.status = Status::Moving { speed: 12 };
+    current.alert = Some("low battery");
+
+    let written = delta(&prev, &current, DefaultOptions::new());
+    let full = serialize_size(&current, DefaultOptions::new()).unwrap();
+    assert!(written.len() * 4 < full, "{} vs {}", written.len(), full);
+    assert_eq!(written[0], 0b0101_0001);
+    assert_eq!(&written[1..4], &[251, 0xE9, 0x03]);
+
+    let mut scratch = [0u8; 256];
+    let decoded: Telemetry =
+        apply_delta(&prev, &written[..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, current);
+}
+
+#[test]
+fn changes_past_the_compare_window_are_found() {
+    let prev = telemetry();
+    let mut current = prev.clone();
+    current.samples[31] = 301;
+
+    let written = delta(&prev, &current, DefaultOptions::new());
+    assert_eq!(written[0], 0b1000_0000);
+
+    let mut scratch = [0u8; 256];
+    let decoded: Telemetry =
+        apply_delta(&prev, &written[..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, current);
+}
+
+#[test]
+fn works_with_other_encodings() {
+    let options = || {
+        DefaultOptions::new()
+            .with_packed_bools()
+            .with_presence_bitmap()
+            .with_fixint_encoding()
+    };
+    let prev = telemetry();
+    let mut current = prev.clone();
+    current.armed = true;
+    current.alert = Some("tilt");
+
+    let written = delta(&prev, &current, options());
+    let mut scratch = [0u8; 256];
+    let decoded: Telemetry = apply_delta(&prev, &written[..], &mut scratch, options()).unwrap();
+    assert_eq!(decoded, current);
+}
+
+#[test]
+fn works_with_type_tags() {
+    let options = DefaultOptions::new().with_type_tags();
+    let prev = telemetry();
+    let mut current = prev.clone();
+    current.battery = 89;
+    current.alert = Some("low battery");
+
+    let written = delta(&prev, &current, options);
+    // With their type tags, the samples are too long to compare, and are always written
+    assert_eq!(written[0], 0b1100_0100);
+    let mut scratch = [0u8; 256];
+    let decoded: Telemetry = apply_delta(&prev, &written[..], &mut scratch, options).unwrap();
+    assert_eq!(decoded, current);
+}
+
+#[test]
+fn non_structs_are_a_single_field() {
+    let prev = Status::Idle;
+    let current = Status::Moving { speed: 3 };
+    let written = delta(&prev, &current, DefaultOptions::new());
+    assert_eq!(written, [1, 1, 3]);
+    let mut scratch = [0u8; 8];
+    let decoded: Status =
+        apply_delta(&prev, &written[..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, current);
+
+    assert_eq!(delta(&7u32, &7u32, DefaultOptions::new()), [0]);
+    let decoded: u32 = apply_delta(&7u32, &[0u8][..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, 7);
+}
+
+#[test]
+fn small_scratch_is_an_error() {
+    let prev = telemetry();
+    let mut scratch = [0u8; 8];
+    match apply_delta::<Telemetry, _, _>(&prev, &[0u8][..], &mut scratch, DefaultOptions::new()) {
+        Err(DeserializeError::ScratchTooSmall) => {}
+        other => panic!("expected ScratchTooSmall, got {:?}", other),
+    }
+}
+
+#[test]
+fn long_fields_are_always_written() {
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Log<'a> {
+        first: [u16; 32],
+        line: &'a str,
+        second: [u16; 32],
+        level: u8,
+    }
+
+    let line = "x".repeat(150);
+    let prev = Log {
+        first: [300; 32],
+        line: &line,
+        second: [400; 32],
+        level: 1,
+    };
+    // The arrays do not fit in the compare buffer together, and the line does not fit at all
+    let written = delta(&prev, &prev, DefaultOptions::new());
+    assert_eq!(written[0], 0b0010);
+    assert_eq!(written.len(), 1 + 1 + line.len());
+
+    let mut current = Log {
+        second: [400; 32],
+        level: 2,
+        ..prev
+    };
+    current.second[0] = 401;
+    let written = delta(&prev, &current, DefaultOptions::new());
+    assert_eq!(written[0], 0b1110);
+    let mut scratch = [0u8; 256];
+    let decoded: Log =
+        apply_delta(&prev, &written[..], &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, current);
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "compat-tests")]
+
+//! Property tests that compare bincode_core with upstream bincode on random values, in both
+//! directions, for every int encoding and endianness.
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::{self, Options};
+use bincode_core::{deserialize, serialize, serialize_size};
+use proptest::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+enum Message {
+    Ping,
+    Text(String),
+    Reading { sensor: u8, value: f32 },
+    Pair(i64, u128),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+struct Record {
+    id: u64,
+    tags: Vec<String>,
+    history: Vec<Message>,
+    lookup: BTreeMap<u16, Option<i32>>,
+    payload: Vec<u8>,
+}
+
+fn message() -> impl Strategy<Value = Message> {
+    prop_oneof![
+        Just(Message::Ping),
+        any::<String>().prop_map(Message::Text),
+        (any::<u8>(), any::<f32>()).prop_map(|(sensor, value)| Message::Reading { sensor, value }),
+        (any::<i64>(), any::<u128>()).prop_map(|(a, b)| Message::Pair(a, b)),
+    ]
+}
+
+fn record() -> impl Strategy<Value = Record> {
+    (
+        any::<u64>(),
+        prop::collection::vec(any::<String>(), 0..4),
+        prop::collection::vec(message(), 0..4),
+        prop::collection::btree_map(any::<u16>(), any::<Option<i32>>(), 0..4),
+        prop::collection::vec(any::<u8>(), 0..300),
+    )
+        .prop_map(|(id, tags, history, lookup, payload)| Record {
+            id,
+            tags,
+            history,
+            lookup,
+            payload,
+        })
+}
+
+/// Checks one pair of matching options in both directions. The bytes are compared instead of the
+/// decoded values, so a NaN float compares equal to itself.
+fn check<T, O, C>(value: &T, options: O, upstream: C) -> Result<(), TestCaseError>
+where
+    T: Serialize + DeserializeOwned + Debug,
+    O: Options + Copy,
+    C: bincode::config::Config,
+{
+    let expected = bincode::serde::encode_to_vec(value, upstream).unwrap();
+
+    let mut written = Vec::new();
+    serialize(value, &mut written, options).unwrap();
+    prop_assert_eq!(&written, &expected, "encoding of {:?}", value);
+    prop_assert_eq!(serialize_size(value, options).unwrap(), expected.len());
+
+    // Upstream bytes, decoded by bincode_core
+    let decoded: T = deserialize(&expected[..], options).unwrap();
+    let mut again = Vec::new();
+    serialize(&decoded, &mut again, options).unwrap();
+    prop_assert_eq!(&again, &expected);
+
+    // bincode_core bytes, decoded by upstream
+    let (decoded, len): (T, usize) = bincode::serde::decode_from_slice(&written, upstream).unwrap();
+    prop_assert_eq!(len, written.len());
+    let again = bincode::serde::encode_to_vec(&decoded, upstream).unwrap();
+    prop_assert_eq!(&again, &written);
+    Ok(())
+}
+
+/// Runs `check` with fixint and varint encoding, in little and big endian.
+fn check_all<T>(value: T) -> Result<(), TestCaseError>
+where
+    T: Serialize + DeserializeOwned + Debug,
+{
+    check(&value, config::legacy(), bincode::config::legacy())?;
+    check(
+        &value,
+        config::legacy().with_big_endian(),
+        bincode::config::legacy().with_big_endian(),
+    )?;
+    check(&value, config::standard(), bincode::config::standard())?;
+    check(
+        &value,
+        config::standard().with_big_endian(),
+        bincode::config::standard().with_big_endian(),
+    )?;
+    check(
+        &value,
+        config::legacy().with_varint_encoding(),
+        bincode::config::legacy().with_variable_int_encoding(),
+    )?;
+    check(
+        &value,
+        config::standard().with_fixint_encoding(),
+        bincode::config::standard().with_fixed_int_encoding(),
+    )
+}
+
+proptest! {
+    #[test]
+    fn unsigned(value in any::<(u8, u16, u32, u64, u128, usize)>()) {
+        check_all(value)?;
+    }
+
+    #[test]
+    fn signed(value in any::<(i8, i16, i32, i64, i128, isize)>()) {
+        check_all(value)?;
+    }
+
+    #[test]
+    fn floats_bools_and_chars(value in any::<(f32, f64, bool, char)>()) {
+        check_all(value)?;
+    }
+
+    #[test]
+    fn strings_and_bytes(value in any::<(String, Vec<u8>, Option<String>)>()) {
+        check_all(value)?;
+    }
+
+    #[test]
+    fn enums(value in message()) {
+        check_all(value)?;
+    }
+
+    #[test]
+    fn nested(value in record()) {
+        check_all(value)?;
+    }
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum ComplexEnum {
+    A(u8),
+    B(u8, i8),
+    C { a: u16 },
+}
+
+macro_rules! discriminant_test {
+    ($name:ident, $options:expr, val: $val:expr, size: $size:expr) => {
+        #[test]
+        fn $name() {
+            let s: ComplexEnum = $val;
+            let mut buffer = [0u8; 100];
+            let mut writer = BufferWriter::new(&mut buffer);
+            serialize(&s, &mut writer, $options).unwrap();
+            println!("Buffer: {:?}", writer.written_buffer());
+
+            assert_eq!($size, writer.written_len());
+            assert_eq!($size, serialize_size(&s, $options).unwrap());
+
+            let deserialized: ComplexEnum = deserialize(&buffer[..$size], $options).unwrap();
+            assert_eq!(s, deserialized);
+        }
+    };
+}
+
+// Variant (4 bytes) + content (1 byte)
+discriminant_test!(fixint_int_discriminant, DefaultOptions::new().with_fixint_encoding(), val: ComplexEnum::A(1), size: 5);
+// Variant (1 byte) + content (2 bytes)
+discriminant_test!(fixint_u8_discriminant, DefaultOptions::new().with_fixint_encoding().with_u8_discriminants(), val: ComplexEnum::B(1, -1), size: 3);
+// Variant (2 bytes) + content (2 bytes)
+discriminant_test!(fixint_u16_discriminant, DefaultOptions::new().with_fixint_encoding().with_u16_discriminants(), val: ComplexEnum::C { a: 5 }, size: 4);
+// Variant (1 byte) + content (1 byte)
+discriminant_test!(varint_u16_discriminant, DefaultOptions::new().with_u16_discriminants(), val: ComplexEnum::C { a: 5 }, size: 2);
+
+#[test]
+fn u8_discriminant_is_a_single_raw_byte() {
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_big_endian()
+        .with_u8_discriminants();
+    serialize(&ComplexEnum::C { a: 0x0102 }, &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), &[2, 1, 2]);
+}
@@ -0,0 +1,235 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::dispatch::{serialize_tagged, Dispatcher, RoutesFull};
+use bincode_core::{serialize, BufferWriter, DefaultOptions, DeserializeError, Deserializer};
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct SetLed {
+    led: u8,
+    on: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Move {
+    x: i16,
+    y: i16,
+}
+
+/// The messages as the host defines them.
+#[derive(Serialize)]
+pub enum Command<'a> {
+    SetLed(SetLed),
+    Move(Move),
+    Log(&'a str),
+}
+
+#[derive(Default, Debug)]
+struct Device {
+    leds: [bool; 4],
+    position: (i16, i16),
+}
+
+type DeviceDispatcher<'a, O> = Dispatcher<'a, &'a [u8], O, Device, 2>;
+
+fn dispatcher<'a, O: Options>() -> DeviceDispatcher<'a, O> {
+    let mut dispatcher = DeviceDispatcher::new();
+    dispatcher
+        .register(0, |de, device| {
+            let msg = SetLed::deserialize(de)?;
+            device.leds[msg.led as usize] = msg.on;
+            Ok(())
+        })
+        .unwrap();
+    dispatcher
+        .register(1, |de, device| {
+            let msg = Move::deserialize(de)?;
+            device.position.0 += msg.x;
+            device.position.1 += msg.y;
+            Ok(())
+        })
+        .unwrap();
+    dispatcher
+}
+
+#[test]
+fn routes_enum_variants_to_handlers() {
+    let mut device = Device::default();
+    for (command, tag) in [
+        (Command::SetLed(SetLed { led: 2, on: true }), 0),
+        (Command::Move(Move { x: -5, y: 300 }), 1),
+    ] {
+        let mut buffer = [0u8; 16];
+        let mut writer = BufferWriter::new(&mut buffer);
+        serialize(&command, &mut writer, DefaultOptions::new()).unwrap();
+        let bytes = writer.written_buffer();
+        let found = dispatcher()
+            .dispatch(bytes, DefaultOptions::new(), &mut device)
+            .unwrap();
+        assert_eq!(found, tag);
+    }
+    assert_eq!(device.leds, [false, false, true, false]);
+    assert_eq!(device.position, (-5, 300));
+}
+
+#[test]
+fn unknown_tags_are_rejected() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&Command::Log("hi"), &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+
+    let mut device = Device::default();
+    match dispatcher().dispatch(bytes, DefaultOptions::new(), &mut device) {
+        Err(DeserializeError::UnknownTag(2)) => {}
+        x => panic!("Expected UnknownTag, got {:?}", x),
+    }
+}
+
+#[test]
+fn stream_of_tagged_messages() {
+    let options = DefaultOptions::new().with_u8_discriminants();
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_tagged(1, &Move { x: 1, y: 2 }, &mut writer, options).unwrap();
+    serialize_tagged(0, &SetLed { led: 3, on: true }, &mut writer, options).unwrap();
+    serialize_tagged(1, &Move { x: 10, y: 20 }, &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer()[0], 1);
+    let bytes = writer.written_buffer();
+
+    let dispatcher = dispatcher();
+    let mut device = Device::default();
+    let mut deserializer = Deserializer::new(bytes, options);
+    let mut tags = Vec::new();
+    while !deserializer.reader().is_empty() {
+        tags.push(
+            dispatcher
+                .dispatch_next(&mut deserializer, &mut device)
+                .unwrap(),
+        );
+    }
+    assert_eq!(tags, [1, 0, 1]);
+    assert_eq!(device.leds, [false, false, false, true]);
+    assert_eq!(device.position, (11, 22));
+}
+
+#[test]
+fn trailing_bytes_are_checked() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_tagged(
+        0,
+        &(SetLed { led: 0, on: true }, 9u8),
+        &mut writer,
+        DefaultOptions::new(),
+    )
+    .unwrap();
+    let bytes = writer.written_buffer();
+
+    let mut device = Device::default();
+    assert!(matches!(
+        dispatcher().dispatch(bytes, DefaultOptions::new(), &mut device),
+        Err(DeserializeError::TrailingBytes)
+    ));
+    let options = DefaultOptions::new().allow_trailing_bytes();
+    assert_eq!(
+        dispatcher().dispatch(bytes, options, &mut device).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn register_replaces_and_fills_up() {
+    let mut dispatcher = dispatcher::<DefaultOptions>();
+    assert_eq!(dispatcher.register(2, |_, _| Ok(())), Err(RoutesFull));
+    dispatcher
+        .register(1, |_, device| {
+            device.position = (7, 7);
+            Ok(())
+        })
+        .unwrap();
+
+    let mut device = Device::default();
+    dispatcher
+        .dispatch(&[1u8][..], DefaultOptions::new(), &mut device)
+        .unwrap();
+    assert_eq!(device.position, (7, 7));
+}
+
+#[test]
+fn counted_structs() {
+    let options = DefaultOptions::new().with_counted_structs();
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_tagged(1, &Move { x: 1, y: 2 }, &mut writer, options).unwrap();
+    // A newer message with a field that the handler does not know
+    serialize_tagged(0, &(SetLed { led: 3, on: true }, 9u8), &mut writer, options).unwrap();
+    serialize(&Command::Move(Move { x: 10, y: 20 }), &mut writer, options).unwrap();
+    let bytes = writer.written_buffer();
+
+    let dispatcher = dispatcher();
+    let mut device = Device::default();
+    let mut deserializer = Deserializer::new(bytes, options);
+    let mut tags = Vec::new();
+    while !deserializer.reader().is_empty() {
+        tags.push(
+            dispatcher
+                .dispatch_next(&mut deserializer, &mut device)
+                .unwrap(),
+        );
+    }
+    assert_eq!(tags, [1, 0, 1]);
+    assert_eq!(device.leds, [false, false, false, true]);
+    assert_eq!(device.position, (11, 22));
+
+    let mut dispatcher = Dispatcher::<_, _, u8, 1>::new();
+    dispatcher
+        .register(1, |de, value| {
+            *value = u8::deserialize(de)?;
+            Ok(())
+        })
+        .unwrap();
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_tagged(1, &7u8, &mut writer, options).unwrap();
+    let mut value = 0;
+    dispatcher
+        .dispatch(writer.written_buffer(), options, &mut value)
+        .unwrap();
+    assert_eq!(value, 7);
+}
+
+#[test]
+fn type_tags() {
+    let options = DefaultOptions::new().with_type_tags();
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_tagged(1, &Move { x: 1, y: 2 }, &mut writer, options).unwrap();
+    serialize(
+        &Command::SetLed(SetLed { led: 3, on: true }),
+        &mut writer,
+        options,
+    )
+    .unwrap();
+    let bytes = writer.written_buffer();
+
+    let dispatcher = dispatcher();
+    let mut device = Device::default();
+    let mut deserializer = Deserializer::new(bytes, options);
+    assert_eq!(
+        dispatcher
+            .dispatch_next(&mut deserializer, &mut device)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        dispatcher
+            .dispatch_next(&mut deserializer, &mut device)
+            .unwrap(),
+        0
+    );
+    assert!(deserializer.reader().is_empty());
+    assert_eq!(device.leds, [false, false, false, true]);
+    assert_eq!(device.position, (1, 2));
+}
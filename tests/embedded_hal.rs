@@ -0,0 +1,381 @@
+#![cfg(feature = "embedded-hal")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, BufferWriter, DefaultOptions, DeserializeError,
+    HalReadError, HalWriteError, I2cAdapter, NbReader, SerialWriter, SerializeError, SpiAdapter,
+    TimeoutReader,
+};
+use embedded_hal::blocking::{i2c, spi};
+use embedded_hal::{serial, timer};
+use std::collections::VecDeque;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Config {
+    a: u32,
+    b: i16,
+    c: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Named<'a> {
+    name: &'a str,
+}
+
+/// A fake memory device that records everything written to it, and returns it when read.
+#[derive(Default)]
+struct Memory {
+    data: Vec<u8>,
+    read_index: usize,
+    last_address: Option<u8>,
+}
+
+impl Memory {
+    fn read_into(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        let end = self.read_index + buffer.len();
+        if end > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[self.read_index..end]);
+        self.read_index = end;
+        Ok(())
+    }
+}
+
+impl spi::Transfer<u8> for Memory {
+    type Error = ();
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], ()> {
+        assert!(words.iter().all(|w| *w == 0));
+        self.read_into(words)?;
+        Ok(words)
+    }
+}
+
+impl spi::Write<u8> for Memory {
+    type Error = ();
+    fn write(&mut self, words: &[u8]) -> Result<(), ()> {
+        self.data.extend_from_slice(words);
+        Ok(())
+    }
+}
+
+impl i2c::Read for Memory {
+    type Error = ();
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), ()> {
+        self.last_address = Some(address);
+        self.read_into(buffer)
+    }
+}
+
+impl i2c::Write for Memory {
+    type Error = ();
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), ()> {
+        self.last_address = Some(address);
+        self.data.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+/// A fake UART that has a new byte after every `gap` polls.
+struct Uart {
+    data: VecDeque<u8>,
+    gap: u32,
+    polls: u32,
+}
+
+impl Uart {
+    fn new(data: &[u8], gap: u32) -> Self {
+        Uart {
+            data: data.iter().copied().collect(),
+            gap,
+            polls: 0,
+        }
+    }
+}
+
+impl serial::Read<u8> for Uart {
+    type Error = ();
+    fn read(&mut self) -> nb::Result<u8, ()> {
+        self.polls += 1;
+        if self.polls <= self.gap {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.polls = 0;
+        self.data.pop_front().ok_or(nb::Error::WouldBlock)
+    }
+}
+
+/// A fake USB serial port with an endpoint buffer of `room` bytes, that is emptied when the host
+/// reads it.
+struct UsbPort {
+    endpoint: Vec<u8>,
+    room: usize,
+    received: Vec<u8>,
+}
+
+impl UsbPort {
+    fn new(room: usize) -> Self {
+        UsbPort {
+            endpoint: Vec::new(),
+            room,
+            received: Vec::new(),
+        }
+    }
+
+    fn host_reads(&mut self) {
+        self.received.append(&mut self.endpoint);
+    }
+}
+
+impl serial::Write<u8> for UsbPort {
+    type Error = ();
+    fn write(&mut self, word: u8) -> nb::Result<(), ()> {
+        if self.endpoint.len() == self.room {
+            return Err(nb::Error::WouldBlock);
+        }
+        self.endpoint.push(word);
+        Ok(())
+    }
+    fn flush(&mut self) -> nb::Result<(), ()> {
+        if self.endpoint.is_empty() {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+/// A fake timer that expires after the given amount of polls.
+#[derive(Default)]
+struct Ticks {
+    remaining: u32,
+    started: u32,
+}
+
+impl timer::CountDown for Ticks {
+    type Time = u32;
+    fn start<T: Into<u32>>(&mut self, count: T) {
+        self.remaining = count.into();
+        self.started += 1;
+    }
+    fn wait(&mut self) -> nb::Result<(), void::Void> {
+        if self.remaining == 0 {
+            return Ok(());
+        }
+        self.remaining -= 1;
+        Err(nb::Error::WouldBlock)
+    }
+}
+
+#[test]
+fn spi_round_trip() {
+    let config = Config {
+        a: 70_000,
+        b: -5,
+        c: true,
+    };
+    let mut adapter = SpiAdapter::new(Memory::default());
+    serialize(&config, &mut adapter, DefaultOptions::new()).unwrap();
+
+    let deserialized: Config = deserialize(&mut adapter, DefaultOptions::new()).unwrap();
+    assert_eq!(config, deserialized);
+    let memory = adapter.release();
+    assert_eq!(memory.read_index, memory.data.len());
+}
+
+#[test]
+fn i2c_round_trip() {
+    let config = Config {
+        a: 1,
+        b: 300,
+        c: false,
+    };
+    let mut adapter = I2cAdapter::new(Memory::default(), 0x50);
+    serialize(&config, &mut adapter, DefaultOptions::new()).unwrap();
+
+    let deserialized: Config = deserialize(&mut adapter, DefaultOptions::new()).unwrap();
+    assert_eq!(config, deserialized);
+    assert_eq!(Some(0x50), adapter.release().last_address);
+}
+
+#[test]
+fn borrowed_data_is_rejected() {
+    let mut adapter = SpiAdapter::new(Memory::default());
+    serialize(&Named { name: "abc" }, &mut adapter, DefaultOptions::new()).unwrap();
+
+    match deserialize::<Named, _, _>(&mut adapter, DefaultOptions::new()) {
+        Err(DeserializeError::Read(HalReadError::CannotBorrow)) => {}
+        x => panic!("Expected CannotBorrow, got {:?}", x),
+    }
+}
+
+/// A bus with an error type that does not implement `Debug`.
+struct Unplugged;
+
+struct Nack;
+
+impl spi::Transfer<u8> for Unplugged {
+    type Error = Nack;
+    fn transfer<'w>(&mut self, _words: &'w mut [u8]) -> Result<&'w [u8], Nack> {
+        Err(Nack)
+    }
+}
+
+#[test]
+fn bus_error_without_debug() {
+    let err =
+        deserialize::<u32, _, _>(SpiAdapter::new(Unplugged), DefaultOptions::new()).unwrap_err();
+    assert!(matches!(
+        err,
+        DeserializeError::Read(HalReadError::Bus(Nack))
+    ));
+    assert!(format!("{:?}", err).ends_with("Bus(embedded_hal::Nack)"));
+}
+
+#[test]
+fn timeout_reader_waits_for_every_byte() {
+    let config = Config {
+        a: 70_000,
+        b: -5,
+        c: true,
+    };
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&config, &mut writer, DefaultOptions::new()).unwrap();
+    let data = writer.written_buffer().to_vec();
+
+    let mut reader = TimeoutReader::new(Uart::new(&data, 5), Ticks::default(), 10u32);
+    let deserialized: Config = deserialize(&mut reader, DefaultOptions::new()).unwrap();
+    assert_eq!(config, deserialized);
+    let (uart, timer) = reader.release();
+    assert!(uart.data.is_empty());
+    assert_eq!(timer.started as usize, data.len());
+}
+
+#[test]
+fn timeout_reader_gives_up_on_a_dropped_byte() {
+    let config = Config {
+        a: 70_000,
+        b: -5,
+        c: true,
+    };
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&config, &mut writer, DefaultOptions::new()).unwrap();
+    let mut data = writer.written_buffer().to_vec();
+    data.pop();
+
+    let reader = TimeoutReader::new(Uart::new(&data, 5), Ticks::default(), 10u32);
+    match deserialize::<Config, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::Read(HalReadError::Timeout)) => {}
+        x => panic!("Expected Timeout, got {:?}", x),
+    }
+
+    let reader = TimeoutReader::new(Uart::new(&data, 20), Ticks::default(), 10u32);
+    match deserialize::<Config, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::Read(HalReadError::Timeout)) => {}
+        x => panic!("Expected Timeout, got {:?}", x),
+    }
+}
+
+#[test]
+fn serial_writer_buffers_while_the_port_is_busy() {
+    let configs = [
+        Config {
+            a: 70_000,
+            b: -5,
+            c: true,
+        },
+        Config {
+            a: 1,
+            b: 2,
+            c: false,
+        },
+    ];
+    let mut ring = [0u8; 16];
+    let mut writer = SerialWriter::new(UsbPort::new(4), &mut ring);
+    for config in &configs {
+        serialize(config, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    assert_eq!(writer.pending(), 6);
+    assert!(matches!(writer.poll(), Err(nb::Error::WouldBlock)));
+
+    while let Err(nb::Error::WouldBlock) = writer.poll() {
+        writer.get_mut().host_reads();
+    }
+    let mut port = writer.release().0;
+    port.host_reads();
+    let received: Vec<Config> = deserialize_iter(&port.received[..], DefaultOptions::new())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(received, configs);
+}
+
+#[test]
+fn serial_writer_full_ring() {
+    let mut ring = [0u8; 2];
+    let mut writer = SerialWriter::new(UsbPort::new(4), &mut ring);
+    assert_eq!(writer.available(), 2);
+    match serialize(&[1u8; 8], &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(HalWriteError::BufferFull)) => {}
+        x => panic!("Expected BufferFull, got {:?}", x),
+    }
+    assert_eq!(writer.pending(), 2);
+    assert_eq!(writer.available(), 0);
+}
+
+#[test]
+fn nb_reader_polls_until_the_value_is_complete() {
+    let configs = [
+        Config {
+            a: 70000,
+            b: -3,
+            c: true,
+        },
+        Config {
+            a: 1,
+            b: 2,
+            c: false,
+        },
+    ];
+    let mut data = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut data);
+    for config in &configs {
+        serialize(config, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    let len = writer.written_len();
+
+    let mut buffer = [0u8; 16];
+    let mut reader = NbReader::new(Uart::new(&data[..len], 2), &mut buffer);
+    let mut received = Vec::new();
+    let mut would_block = 0;
+    while received.len() < configs.len() {
+        match reader.poll::<Config, _>(DefaultOptions::new()) {
+            Ok(config) => received.push(config),
+            Err(DeserializeError::WouldBlock) => would_block += 1,
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+    assert_eq!(received, configs);
+    assert!(would_block > len);
+    assert_eq!(reader.buffered_len(), 0);
+}
+
+#[test]
+fn nb_reader_errors() {
+    let mut buffer = [0u8; 2];
+    let mut reader = NbReader::new(Uart::new(&[1, 2, 3, 4, 5], 0), &mut buffer);
+    let result = reader.poll::<(u8, u8, u8), _>(DefaultOptions::new());
+    assert!(matches!(result, Err(DeserializeError::ScratchTooSmall)));
+    assert_eq!(reader.buffered_len(), 0);
+
+    // An invalid bool discards the received bytes
+    let mut buffer = [0u8; 4];
+    let mut reader = NbReader::new(Uart::new(&[2, 1], 0), &mut buffer);
+    let result = reader.poll::<bool, _>(DefaultOptions::new());
+    assert!(matches!(result, Err(DeserializeError::InvalidBoolValue(2))));
+    assert_eq!(reader.buffered_len(), 0);
+    assert!(reader.poll::<bool, _>(DefaultOptions::new()).unwrap());
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "embedded-io")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferedReader, DefaultOptions, DeserializeError, EmbeddedIo,
+    EmbeddedIoError, SerializeError,
+};
+use embedded_io::{ErrorType, Write};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Named<'a> {
+    id: u16,
+    name: &'a str,
+}
+
+/// A fake UART that accepts at most `chunk` bytes per write, until `capacity` bytes are written.
+struct Uart {
+    data: Vec<u8>,
+    chunk: usize,
+    capacity: usize,
+}
+
+impl ErrorType for Uart {
+    type Error = core::convert::Infallible;
+}
+
+impl Write for Uart {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        let len = buf
+            .len()
+            .min(self.chunk)
+            .min(self.capacity - self.data.len());
+        self.data.extend_from_slice(&buf[..len]);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[test]
+fn roundtrip() {
+    let value = Named {
+        id: 1000,
+        name: "pump",
+    };
+    let mut uart = Uart {
+        data: Vec::new(),
+        chunk: 3,
+        capacity: 64,
+    };
+    serialize(&value, EmbeddedIo::new(&mut uart), DefaultOptions::new()).unwrap();
+    assert_eq!(uart.data, [251, 232, 3, 4, b'p', b'u', b'm', b'p']);
+
+    let mut scratch = [0u8; 8];
+    let reader = BufferedReader::new(EmbeddedIo::new(&uart.data[..]), &mut scratch);
+    let decoded: Named = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn borrowed_data_needs_a_buffer() {
+    let bytes = [1u8, b'a'];
+    assert!(matches!(
+        deserialize::<&str, _, _>(EmbeddedIo::new(&bytes[..]), DefaultOptions::new()),
+        Err(DeserializeError::Read(EmbeddedIoError::CannotBorrow))
+    ));
+}
+
+#[test]
+fn end_of_input() {
+    let bytes = [1u8, 2, 3];
+    assert!(matches!(
+        deserialize::<u32, _, _>(
+            EmbeddedIo::new(&bytes[..]),
+            DefaultOptions::new().with_fixint_encoding()
+        ),
+        Err(DeserializeError::Read(EmbeddedIoError::UnexpectedEof))
+    ));
+}
+
+#[test]
+fn full_writer_is_an_error() {
+    let mut uart = Uart {
+        data: Vec::new(),
+        chunk: 3,
+        capacity: 4,
+    };
+    assert!(matches!(
+        serialize(&[7u8; 8], EmbeddedIo::new(&mut uart), DefaultOptions::new()),
+        Err(SerializeError::Write(EmbeddedIoError::WriteZero))
+    ));
+    assert_eq!(uart.data, [7; 4]);
+
+    let mut buffer = [0u8; 2];
+    assert!(matches!(
+        serialize(
+            &300u32,
+            EmbeddedIo::new(&mut buffer[..]),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Write(EmbeddedIoError::Io(
+            embedded_io::SliceWriteError::Full
+        )))
+    ));
+}
@@ -0,0 +1,119 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize_with_context, serialize, BufferWriter, DefaultOptions, DeserializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Inner {
+    id: u16,
+    enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Outer {
+    header: u32,
+    inner: [Inner; 3],
+    trailer: Option<u8>,
+}
+
+fn outer() -> Outer {
+    Outer {
+        header: 0xDEAD_BEEF,
+        inner: [
+            Inner {
+                id: 1,
+                enabled: true,
+            },
+            Inner {
+                id: 2,
+                enabled: false,
+            },
+            Inner {
+                id: 3,
+                enabled: true,
+            },
+        ],
+        trailer: Some(4),
+    }
+}
+
+fn options() -> impl Options {
+    DefaultOptions::new().with_fixint_encoding()
+}
+
+fn serialized(buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(&outer(), &mut writer, options()).unwrap();
+    writer.written_len()
+}
+
+#[test]
+fn round_trip() {
+    let mut buffer = [0u8; 32];
+    let len = serialized(&mut buffer);
+    let value: Outer = deserialize_with_context(&buffer[..len], options()).unwrap();
+    assert_eq!(value, outer());
+}
+
+#[test]
+fn invalid_value_reports_offset_and_path() {
+    let mut buffer = [0u8; 32];
+    let len = serialized(&mut buffer);
+    // header (4) + inner[0] (3) + inner[1].id (2)
+    buffer[9] = 7;
+
+    let err = deserialize_with_context::<Outer, _, _>(&buffer[..len], options()).unwrap_err();
+    assert!(matches!(err.error, DeserializeError::InvalidBoolValue(7)));
+    assert_eq!(err.offset, 9);
+    assert_eq!(err.path.indices(), &[1, 1, 1]);
+    assert!(!err.path.is_truncated());
+    assert_eq!(
+        format!("{}", err),
+        "Unknown bool value, got 7, expected 0 or 1 at offset 9 while reading field 1.1.1"
+    );
+}
+
+#[test]
+fn truncated_input_reports_offset_and_path() {
+    let mut buffer = [0u8; 32];
+    let len = serialized(&mut buffer);
+
+    let err = deserialize_with_context::<Outer, _, _>(&buffer[..len - 1], options()).unwrap_err();
+    assert!(matches!(
+        err.error,
+        DeserializeError::UnexpectedEof { needed: 1 }
+    ));
+    assert_eq!(err.offset, len as u64 - 1);
+    assert_eq!(err.path.indices(), &[2]);
+}
+
+#[test]
+fn trailing_bytes_report_end_offset() {
+    let mut buffer = [0u8; 32];
+    let len = serialized(&mut buffer);
+
+    let err = deserialize_with_context::<Outer, _, _>(&buffer[..len + 1], options()).unwrap_err();
+    assert!(matches!(err.error, DeserializeError::TrailingBytes));
+    assert_eq!(err.offset, len as u64);
+    assert_eq!(err.path.depth(), 0);
+    assert_eq!(
+        format!("{}", err),
+        format!("Bytes remaining after deserialization at offset {}", len)
+    );
+}
+
+#[test]
+fn deep_paths_are_truncated() {
+    type Deep = ((((((((((bool,),),),),),),),),),);
+    let buffer = [2u8];
+
+    let err = deserialize_with_context::<Deep, _, _>(&buffer[..], options()).unwrap_err();
+    assert!(matches!(err.error, DeserializeError::InvalidBoolValue(2)));
+    assert_eq!(err.path.depth(), 10);
+    assert_eq!(err.path.indices(), &[0; 8]);
+    assert!(err.path.is_truncated());
+    assert_eq!(format!("{}", err.path), "0.0.0.0.0.0.0.0...");
+}
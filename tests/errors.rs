@@ -0,0 +1,102 @@
+use bincode_core::config::Options;
+use bincode_core::versioned::{deserialize_versioned, serialize_versioned};
+use bincode_core::{
+    deserialize, deserialize_with_context, serialize, serialize_size, BufferWriter,
+    BufferWriterError, CoreRead, DefaultOptions, Error, ErrorKind, SliceReadError,
+};
+use serde::de::Visitor;
+
+type AppError = Error<SliceReadError, BufferWriterError>;
+
+fn read<'a, T: serde::Deserialize<'a>>(bytes: &'a [u8]) -> Result<T, AppError> {
+    Ok(deserialize(bytes, DefaultOptions::new())?)
+}
+
+#[test]
+fn deserialize_kinds() {
+    assert_eq!(
+        read::<u32>(&[252, 1]).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        read::<&str>(&[5, b'a']).unwrap_err().kind(),
+        ErrorKind::UnexpectedEof
+    );
+    assert_eq!(
+        read::<Option<u8>>(&[2]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+    assert_eq!(
+        read::<u8>(&[1, 2]).unwrap_err().kind(),
+        ErrorKind::InvalidData
+    );
+
+    let options = DefaultOptions::new().with_limit(2);
+    let err = deserialize::<[u8; 4], _, _>(&[1u8, 2, 3, 4][..], options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+
+    let mut buffer = [0u8; 8];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_versioned(2, &1u8, &mut writer, DefaultOptions::new()).unwrap();
+    let err = deserialize_versioned::<u8, _, _>(3, writer.written_buffer(), DefaultOptions::new())
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Mismatch);
+}
+
+/// A reader that is disconnected.
+struct Unplugged;
+
+impl<'a> CoreRead<'a> for Unplugged {
+    type Error = &'static str;
+
+    fn fill(&mut self, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+        Err("unplugged")
+    }
+
+    fn forward_str<V: Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Self::Error> {
+        Err("unplugged")
+    }
+
+    fn forward_bytes<V: Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Self::Error> {
+        Err("unplugged")
+    }
+}
+
+#[test]
+fn read_and_write_errors_keep_their_payload() {
+    let err: Error<&str, ()> = deserialize::<u8, _, _>(Unplugged, DefaultOptions::new())
+        .unwrap_err()
+        .into();
+    assert_eq!(err.kind(), ErrorKind::Read);
+    assert!(matches!(err, Error::Read("unplugged")));
+
+    let result: Result<(), AppError> = serialize(
+        &"abc",
+        BufferWriter::new(&mut [0u8; 2]),
+        DefaultOptions::new(),
+    )
+    .map_err(AppError::from);
+    match result {
+        Err(Error::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected a write error, got {:?}", x),
+    }
+}
+
+#[test]
+fn serialize_kinds() {
+    let options = DefaultOptions::new().with_limit(2);
+    let err = serialize_size(&[1u8, 2, 3, 4], options).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+    let err: Error<(), ()> = err.into();
+    assert_eq!(err.kind(), ErrorKind::LimitExceeded);
+    assert!(matches!(err, Error::Serialize(ErrorKind::LimitExceeded)));
+}
+
+#[test]
+fn context_error() {
+    let err = deserialize_with_context::<(u8, bool), _, _>(&[1u8, 7][..], DefaultOptions::new())
+        .unwrap_err();
+    assert_eq!(err.error.kind(), ErrorKind::InvalidData);
+    let err: AppError = err.into();
+    assert!(matches!(err, Error::Deserialize(ErrorKind::InvalidData)));
+}
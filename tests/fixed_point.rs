@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::fixed_point::{Fixed, Q15, Q16_16, Q8_8, UQ16_16};
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Reading {
+    gain: Q15,
+    offset: Q8_8,
+    value: Q16_16,
+}
+
+fn reading() -> Reading {
+    Reading {
+        gain: Q15::from_bits(0x4000),
+        offset: -Q8_8::from_int(2),
+        value: Q16_16::from_int(3),
+    }
+}
+
+#[test]
+fn uses_the_active_int_encoding() {
+    let mut buffer = [0u8; 16];
+
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&reading(), &mut writer, options).unwrap();
+    assert_eq!(
+        writer.written_buffer(),
+        &[0x00, 0x40, 0x00, 0xFE, 0x00, 0x00, 0x03, 0x00]
+    );
+
+    // Varint encoding zigzag-encodes the signed integers
+    let options = DefaultOptions::new();
+    assert_eq!(serialize_size(&reading(), options).unwrap(), 3 + 3 + 5);
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&reading(), &mut writer, options).unwrap();
+    let len = writer.written_len();
+    let decoded: Reading = deserialize(&buffer[..len], options).unwrap();
+    assert_eq!(decoded, reading());
+}
+
+#[test]
+fn integer_conversions() {
+    assert_eq!(Q16_16::from_int(5).to_bits(), 5 << 16);
+    assert_eq!(Q16_16::from_int(5).to_int(), 5);
+    // Rounds towards negative infinity
+    let value = Q16_16::from_int(-2) + Q16_16::from_bits(0x8000);
+    assert_eq!(value.to_int(), -2);
+    assert_eq!((-value).to_int(), 1);
+    assert_eq!((Q16_16::from_int(1) - value).to_int(), 2);
+    assert_eq!(UQ16_16::from_int(7).to_int(), 7);
+    assert_eq!(Fixed::<u8, 4>::FRAC_BITS, 4);
+}
@@ -0,0 +1,106 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferWriter, CheckpointRead, CoreRead, DefaultOptions,
+    DeserializeError, Deserializer, FlashSlice, SliceReadError,
+};
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Calibration<'a> {
+    serial: &'a [u8],
+    label: &'a str,
+    offset: i32,
+    gain: f64,
+    counter: u64,
+}
+
+fn calibration() -> Calibration<'static> {
+    Calibration {
+        serial: &[0xA5, 0x5A, 0x01],
+        label: "probe",
+        offset: -123_456,
+        gain: 0.997,
+        counter: u64::MAX - 1,
+    }
+}
+
+/// A flash page with the calibration written at `offset`.
+fn page(offset: usize) -> Vec<u8> {
+    let mut page = vec![FlashSlice::ERASED_BYTE; 64];
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let mut writer = BufferWriter::new(&mut page[offset..]);
+    serialize(&calibration(), &mut writer, options).unwrap();
+    page
+}
+
+#[test]
+fn any_alignment() {
+    for offset in 0..8 {
+        let page = page(offset);
+        let flash = FlashSlice::at(&page, offset).unwrap();
+        let options = DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes();
+        let decoded: Calibration = deserialize(flash, options).unwrap();
+        assert_eq!(decoded, calibration());
+    }
+}
+
+#[test]
+fn borrows_from_flash() {
+    let page = page(3);
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let mut deserializer = Deserializer::new(FlashSlice::at(&page, 3).unwrap(), options);
+    let decoded = Calibration::deserialize(&mut deserializer).unwrap();
+    let range = page.as_ptr_range();
+    assert!(range.contains(&decoded.serial.as_ptr()));
+    assert!(range.contains(&decoded.label.as_ptr()));
+
+    let reader = deserializer.into_reader();
+    assert_eq!(reader.position(), 3 + 8 + 3 + 8 + 5 + 4 + 8 + 8);
+    assert!(reader.is_erased());
+    assert_eq!(reader.into_inner().len(), page.len());
+}
+
+#[test]
+fn erased_and_out_of_range() {
+    let page = [0u8, 0xFF, 0xFF];
+    assert!(!FlashSlice::new(&page).is_erased());
+    assert!(FlashSlice::at(&page, 1).unwrap().is_erased());
+    assert!(FlashSlice::at(&page, 3).unwrap().is_erased());
+    assert!(FlashSlice::at(&page, 4).is_none());
+
+    let mut flash = FlashSlice::at(&page, 2).unwrap();
+    let mut buffer = [0u8; 2];
+    assert!(matches!(
+        flash.fill(&mut buffer),
+        Err(SliceReadError::EndOfSlice)
+    ));
+    assert_eq!(flash.position(), 2);
+    match deserialize::<u16, _, _>(flash, DefaultOptions::new().with_fixint_encoding()) {
+        Err(DeserializeError::UnexpectedEof { needed: 1 }) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+}
+
+#[test]
+fn checkpoint() {
+    let page = [251u8, 0x34, 0x12, 7];
+    let mut deserializer = Deserializer::new(FlashSlice::new(&page), DefaultOptions::new());
+    assert!(deserializer.try_deserialize::<bool>().is_err());
+    assert_eq!(deserializer.reader().mark(), 0);
+    assert_eq!(u16::deserialize(&mut deserializer).unwrap(), 0x1234);
+    assert_eq!(deserializer.reader().peek(1), Some(&[7][..]));
+}
+
+#[test]
+fn align_up() {
+    assert_eq!(FlashSlice::align_up(0, 4), 0);
+    assert_eq!(FlashSlice::align_up(1, 4), 4);
+    assert_eq!(FlashSlice::align_up(4, 4), 4);
+    assert_eq!(FlashSlice::align_up(5, 1), 5);
+    assert_eq!(FlashSlice::align_up(2047, 2048), 2048);
+}
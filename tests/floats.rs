@@ -0,0 +1,103 @@
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+
+// Quiet and signaling NaNs with a payload, and negative zero
+const F32_BITS: [u32; 4] = [0x7FC0_1234, 0x7F80_0001, 0xFFA0_0042, 0x8000_0000];
+const F64_BITS: [u64; 4] = [
+    0x7FF8_0000_DEAD_BEEF,
+    0x7FF0_0000_0000_0001,
+    0xFFF4_0000_0000_0042,
+    0x8000_0000_0000_0000,
+];
+
+macro_rules! float_bits_test {
+    ($name:ident, $options:expr, $float:ty, $bits:expr) => {
+        #[test]
+        fn $name() {
+            for bits in $bits.iter() {
+                let value = <$float>::from_bits(*bits);
+                let mut buffer = [0u8; 16];
+                let mut writer = BufferWriter::new(&mut buffer);
+                serialize(&value, &mut writer, $options).unwrap();
+                let len = writer.written_len();
+                assert_eq!(len, serialize_size(&value, $options).unwrap());
+
+                let deserialized: $float = deserialize(&buffer[..len], $options).unwrap();
+                assert_eq!(*bits, deserialized.to_bits());
+            }
+        }
+    };
+}
+
+float_bits_test!(
+    f32_as_bits_varint,
+    DefaultOptions::new().with_float_as_bits(),
+    f32,
+    F32_BITS
+);
+float_bits_test!(
+    f64_as_bits_varint,
+    DefaultOptions::new().with_float_as_bits(),
+    f64,
+    F64_BITS
+);
+float_bits_test!(
+    f32_as_bits_fixint,
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_float_as_bits(),
+    f32,
+    F32_BITS
+);
+float_bits_test!(
+    f64_as_bits_fixint,
+    DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_float_as_bits(),
+    f64,
+    F64_BITS
+);
+float_bits_test!(f32_ieee, DefaultOptions::new(), f32, F32_BITS);
+float_bits_test!(
+    f64_ieee,
+    DefaultOptions::new().with_big_endian(),
+    f64,
+    F64_BITS
+);
+
+#[test]
+fn float_as_bits_uses_int_encoding() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(
+        &-0.0f32,
+        &mut writer,
+        DefaultOptions::new().with_float_as_bits(),
+    )
+    .unwrap();
+    // 0x8000_0000 is larger than u16::MAX, so varint writes a u32 marker and 4 bytes
+    assert_eq!(writer.written_buffer(), &[252, 0, 0, 0, 0x80]);
+
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(
+        &0.0f64,
+        &mut writer,
+        DefaultOptions::new().with_float_as_bits(),
+    )
+    .unwrap();
+    assert_eq!(writer.written_buffer(), &[0]);
+}
+
+#[test]
+fn float_as_bits_with_fixint_matches_ieee() {
+    let value = f64::from_bits(F64_BITS[1]);
+    let mut ieee = [0u8; 8];
+    serialize(&value, BufferWriter::new(&mut ieee), DefaultOptions::new()).unwrap();
+    let mut bits = [0u8; 8];
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_float_as_bits();
+    serialize(&value, BufferWriter::new(&mut bits), options).unwrap();
+    assert_eq!(ieee, bits);
+}
@@ -0,0 +1,114 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, serialize_all, ChunkWriter, CoreWrite, DefaultOptions, SerializeError, Serializer,
+};
+use serde::Serialize;
+
+/// A writer that counts how often it is flushed, and can fail on flush.
+#[derive(Default)]
+struct Fifo {
+    data: Vec<u8>,
+    flushes: usize,
+    fail_flush: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum FifoError {
+    Flush,
+}
+
+impl CoreWrite for &mut Fifo {
+    type Error = FifoError;
+
+    fn write(&mut self, val: u8) -> Result<(), FifoError> {
+        self.data.push(val);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), FifoError> {
+        if self.fail_flush {
+            return Err(FifoError::Flush);
+        }
+        self.flushes += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn serialize_flushes_by_default() {
+    let mut fifo = Fifo::default();
+    serialize(&(1u8, 2u16), &mut fifo, DefaultOptions::new()).unwrap();
+    assert_eq!(fifo.data, [1, 2]);
+    assert_eq!(fifo.flushes, 1);
+
+    serialize_all(
+        [3u8, 4, 5],
+        &mut fifo,
+        DefaultOptions::new().with_auto_flush(),
+    )
+    .unwrap();
+    assert_eq!(fifo.data, [1, 2, 3, 4, 5]);
+    assert_eq!(fifo.flushes, 2);
+}
+
+#[test]
+fn manual_flush_leaves_the_tail_buffered() {
+    let mut fifo = Fifo::default();
+    let mut buffer = [0u8; 16];
+    let mut writer = ChunkWriter::new(&mut fifo, &mut buffer);
+    let options = DefaultOptions::new().with_manual_flush();
+
+    serialize(&1u8, &mut writer, options).unwrap();
+    serialize_all([2u8, 3], &mut writer, options).unwrap();
+    assert_eq!(writer.buffered_len(), 3);
+
+    writer.flush().unwrap();
+    assert_eq!(fifo.data, [1, 2, 3]);
+    assert_eq!(fifo.flushes, 1);
+}
+
+#[test]
+fn finish_flushes_the_serializer() {
+    let mut fifo = Fifo::default();
+    let mut serializer = Serializer::new(&mut fifo, DefaultOptions::new().with_manual_flush());
+    7u8.serialize(&mut serializer).unwrap();
+    "hi".serialize(&mut serializer).unwrap();
+    serializer.finish().unwrap();
+    assert_eq!(fifo.data, [7, 2, b'h', b'i']);
+    assert_eq!(fifo.flushes, 1);
+}
+
+#[test]
+fn flush_errors_are_returned() {
+    let mut fifo = Fifo {
+        fail_flush: true,
+        ..Fifo::default()
+    };
+    match serialize(&1u8, &mut fifo, DefaultOptions::new()) {
+        Err(SerializeError::Write(FifoError::Flush)) => {}
+        x => panic!("Expected a flush error, got {:?}", x),
+    }
+    // The value itself was written
+    assert_eq!(fifo.data, [1]);
+
+    serialize(&2u8, &mut fifo, DefaultOptions::new().with_manual_flush()).unwrap();
+
+    let serializer = Serializer::new(&mut fifo, DefaultOptions::new());
+    assert!(matches!(
+        serializer.finish(),
+        Err(SerializeError::Write(FifoError::Flush))
+    ));
+}
+
+#[test]
+fn failed_serialization_is_not_flushed() {
+    let mut fifo = Fifo::default();
+    let result = serialize(&[1u8, 2, 3], &mut fifo, DefaultOptions::new().with_limit(2));
+    assert!(matches!(result, Err(SerializeError::LimitReached)));
+    assert_eq!(fifo.flushes, 0);
+
+    let result = serialize_all([1u8, 2, 3], &mut fifo, DefaultOptions::new().with_limit(2));
+    assert!(matches!(result, Err(SerializeError::LimitReached)));
+    assert_eq!(fifo.data, [1, 2]);
+    assert_eq!(fifo.flushes, 0);
+}
@@ -0,0 +1,122 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_framed, serialize, serialize_framed, BufferWriter, BufferWriterError,
+    DefaultOptions, DeserializeError, SerializeError,
+};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Reading {
+    id: u8,
+    value: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct ReadingV2 {
+    id: u8,
+    value: u16,
+    unit: u8,
+}
+
+/// The bytes of `value` as a `&[u8]`, which a frame is the same as.
+fn as_bytes<T: serde::Serialize + ?Sized, O: Options + Copy>(value: &T, options: O) -> Vec<u8> {
+    let mut payload = [0u8; 1024];
+    let mut writer = BufferWriter::new(&mut payload);
+    serialize(value, &mut writer, options).unwrap();
+    let payload = writer.written_buffer();
+
+    let mut buffer = [0u8; 1024];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(payload, &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+fn framed<T: serde::Serialize + ?Sized, O: Options>(value: &T, options: O) -> Vec<u8> {
+    let mut buffer = [0u8; 1024];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_framed(value, &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn frame_is_a_byte_slice() {
+    let reading = Reading { id: 1, value: 300 };
+    let options = DefaultOptions::new();
+    assert_eq!(framed(&reading, options), as_bytes(&reading, options));
+
+    // The length needs 3 bytes with varint encoding
+    let long = [7u8; 300];
+    assert_eq!(framed(&long[..], options), as_bytes(&long[..], options));
+
+    let options = DefaultOptions::new().with_fixint_encoding();
+    assert_eq!(framed(&long[..], options), as_bytes(&long[..], options));
+
+    // The placeholder for the limit takes 3 bytes, and the value is moved back
+    let options = DefaultOptions::new().with_limit(1000);
+    assert_eq!(framed(&reading, options), as_bytes(&reading, options));
+}
+
+#[test]
+fn roundtrip() {
+    let reading = Reading { id: 1, value: 300 };
+    let bytes = framed(&reading, DefaultOptions::new());
+    let decoded: Reading = deserialize_framed(&bytes[..], DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, reading);
+}
+
+#[test]
+fn appended_fields_are_skipped() {
+    let reading = ReadingV2 {
+        id: 1,
+        value: 300,
+        unit: 4,
+    };
+    let bytes = framed(&reading, DefaultOptions::new());
+    let decoded: Reading = deserialize_framed(&bytes[..], DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, Reading { id: 1, value: 300 });
+
+    // A value that takes more bytes than the frame
+    let bytes = framed(&Reading { id: 1, value: 300 }, DefaultOptions::new());
+    let mut longer = bytes.clone();
+    longer.push(4);
+    match deserialize_framed::<ReadingV2, _, _>(&longer[..], DefaultOptions::new()) {
+        Err(DeserializeError::InvalidFrameLength { len: 4, read: 5 }) => {}
+        x => panic!("Expected InvalidFrameLength, got {:?}", x),
+    }
+}
+
+#[test]
+fn frames_can_be_skipped() {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_framed(
+        &Reading { id: 1, value: 300 },
+        &mut writer,
+        DefaultOptions::new(),
+    )
+    .unwrap();
+    serialize_framed(&2u8, &mut writer, DefaultOptions::new()).unwrap();
+    let options = DefaultOptions::new().allow_trailing_bytes();
+    let (_, second): (&[u8], &[u8]) = deserialize(writer.written_buffer(), options).unwrap();
+    assert_eq!(second, &[2]);
+}
+
+#[test]
+fn errors() {
+    let mut buffer = [0u8; 3];
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize_framed(&[7u8; 8][..], &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new().with_limit(4);
+    match serialize_framed(&[7u8; 3][..], &mut writer, options) {
+        Err(SerializeError::LimitReached) => {}
+        x => panic!("Expected LimitReached, got {:?}", x),
+    }
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use bincode_core::testing::{fuzz_decode, fuzz_roundtrip};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+type Message = (u8, String, Vec<u16>, Option<i64>, bool, char, [u32; 2]);
+
+/// Deterministic inputs of different lengths, standing in for a fuzzer.
+fn inputs() -> impl Iterator<Item = Vec<u8>> {
+    let mut state = 0x1234_5678u32;
+    (0..200).map(move |len| {
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn roundtrip_arbitrary_values() {
+    for input in inputs() {
+        fuzz_roundtrip::<Message>(&input);
+        fuzz_roundtrip::<Vec<Option<(i16, String)>>>(&input);
+    }
+}
+
+#[test]
+fn decode_arbitrary_bytes() {
+    for input in inputs() {
+        fuzz_decode::<Message>(&input);
+        fuzz_decode::<Vec<String>>(&input);
+        fuzz_decode::<(f32, f64)>(&input);
+    }
+    // A NaN is not equal to itself, but has the same bytes every time
+    fuzz_decode::<f32>(&[0, 0, 0, 192, 127]);
+}
+
+/// A type that writes a `u16`, but only reads a `u8`.
+#[derive(Debug, PartialEq)]
+struct Lossy(u8);
+
+impl<'a> Arbitrary<'a> for Lossy {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Lossy(u.arbitrary()?))
+    }
+}
+
+impl Serialize for Lossy {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (u16::from(self.0) + 300).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Lossy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Lossy)
+    }
+}
+
+#[test]
+#[should_panic]
+fn roundtrip_mismatch_panics() {
+    fuzz_roundtrip::<Lossy>(&[0, 1]);
+}
@@ -0,0 +1,69 @@
+use bincode_core::encoding_spec::{verify, Config, CORPUS};
+use bincode_core::{deserialize, CoreRead, DefaultOptions};
+use core::marker::PhantomData;
+use serde::de::Visitor;
+use std::collections::HashSet;
+
+#[test]
+fn corpus_matches_every_config() {
+    for config in Config::ALL {
+        assert_eq!(verify(config), Ok(()), "{:?}", config);
+    }
+}
+
+#[test]
+fn corpus_names_are_unique() {
+    let names: HashSet<_> = CORPUS.iter().map(|golden| golden.name).collect();
+    assert_eq!(names.len(), CORPUS.len());
+}
+
+#[test]
+fn endianness_only_changes_multi_byte_integers() {
+    for golden in CORPUS {
+        for (little, big) in [
+            (Config::Varint, Config::VarintBigEndian),
+            (Config::Fixint, Config::FixintBigEndian),
+        ] {
+            assert_eq!(
+                golden.bytes(little).len(),
+                golden.bytes(big).len(),
+                "{}",
+                golden.name
+            );
+        }
+    }
+}
+
+/// A reader that fails every read, to check that zero-length values read nothing.
+struct Unreadable;
+
+impl<'a> CoreRead<'a> for Unreadable {
+    type Error = &'static str;
+
+    fn fill(&mut self, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+        Err("read")
+    }
+
+    fn forward_str<V: Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Self::Error> {
+        Err("read")
+    }
+
+    fn forward_bytes<V: Visitor<'a>>(&mut self, _: usize, _: V) -> Result<V::Value, Self::Error> {
+        Err("read")
+    }
+}
+
+#[test]
+fn zero_length_values_do_not_read() {
+    let options = DefaultOptions::new();
+    deserialize::<(), _, _>(Unreadable, options).unwrap();
+    deserialize::<PhantomData<u64>, _, _>(Unreadable, options).unwrap();
+    deserialize::<[u32; 0], _, _>(Unreadable, options).unwrap();
+    deserialize::<((), [u8; 0]), _, _>(Unreadable, options).unwrap();
+
+    // Empty strings and slices only read their length
+    let empty: &str = deserialize(&[0u8][..], options).unwrap();
+    assert_eq!(empty, "");
+    let empty: &[u8] = deserialize(&[0u8][..], options).unwrap();
+    assert!(empty.is_empty());
+}
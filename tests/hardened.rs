@@ -0,0 +1,241 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, DefaultOptions, DeserializeError};
+use core::marker::PhantomData;
+use serde::de::{IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+/// A recursive value that counts how deep its options are nested.
+#[derive(Debug)]
+struct Depth(usize);
+
+impl<'de> Deserialize<'de> for Depth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let inner: Option<Depth> = Option::deserialize(deserializer)?;
+        Ok(Depth(inner.map_or(0, |depth| depth.0 + 1)))
+    }
+}
+
+/// A recursive value that counts how deep its enums are nested.
+#[derive(Debug)]
+struct EnumDepth(usize);
+
+#[derive(Deserialize)]
+enum Node {
+    Leaf,
+    Branch(EnumDepth),
+}
+
+impl<'de> Deserialize<'de> for EnumDepth {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Node::deserialize(deserializer)? {
+            Node::Leaf => Ok(EnumDepth(0)),
+            Node::Branch(depth) => Ok(EnumDepth(depth.0 + 1)),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub enum Command<'a> {
+    Ping,
+    SetName(&'a str),
+    Move { x: i16, y: i16 },
+    Batch([Option<u8>; 2]),
+}
+
+#[derive(Deserialize, Debug)]
+pub struct Packet<'a> {
+    pub id: u16,
+    #[serde(borrow)]
+    pub commands: (Command<'a>, Command<'a>),
+    pub payload: &'a [u8],
+}
+
+/// A sequence that counts its elements, without storing them.
+struct Seq<T>(usize, PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Seq<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Counter<T>(PhantomData<T>);
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Counter<T> {
+            type Value = Seq<T>;
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("a sequence")
+            }
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Seq<T>, A::Error> {
+                let mut len = 0;
+                while seq.next_element::<T>()?.is_some() {
+                    len += 1;
+                }
+                Ok(Seq(len, PhantomData))
+            }
+        }
+        deserializer.deserialize_seq(Counter(PhantomData))
+    }
+}
+
+/// A map with `u8` keys that counts its entries, without storing them.
+struct Map<T>(usize, PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Map<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Counter<T>(PhantomData<T>);
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for Counter<T> {
+            type Value = Map<T>;
+            fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                f.write_str("a map")
+            }
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Map<T>, A::Error> {
+                let mut len = 0;
+                while map.next_entry::<u8, T>()?.is_some() {
+                    len += 1;
+                }
+                Ok(Map(len, PhantomData))
+            }
+        }
+        deserializer.deserialize_map(Counter(PhantomData))
+    }
+}
+
+fn nested(depth: usize, buffer: &mut [u8]) -> &[u8] {
+    buffer[..depth].fill(1);
+    buffer[depth] = 0;
+    &buffer[..=depth]
+}
+
+#[test]
+fn depth_is_unlimited_by_default() {
+    let mut buffer = [0u8; 101];
+    let depth: Depth = deserialize(nested(100, &mut buffer), DefaultOptions::new()).unwrap();
+    assert_eq!(depth.0, 100);
+}
+
+#[test]
+fn hardened_depth_is_limited() {
+    let options = DefaultOptions::new().with_hardened_decoding();
+    let mut buffer = [0u8; 101];
+    let depth: Depth = deserialize(nested(32, &mut buffer), options).unwrap();
+    assert_eq!(depth.0, 32);
+
+    let result = deserialize::<Depth, _, _>(nested(33, &mut buffer), options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+}
+
+#[test]
+fn max_depth_limits_options() {
+    let options = DefaultOptions::new().with_max_depth(4);
+    let mut buffer = [0u8; 101];
+    let depth: Depth = deserialize(nested(4, &mut buffer), options).unwrap();
+    assert_eq!(depth.0, 4);
+
+    let result = deserialize::<Depth, _, _>(nested(5, &mut buffer), options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+}
+
+#[test]
+fn max_depth_limits_enums() {
+    let options = DefaultOptions::new().with_max_depth(4);
+    // Three branches and a leaf
+    let buffer = [1, 1, 1, 0];
+    let depth: EnumDepth = deserialize(&buffer[..], options).unwrap();
+    assert_eq!(depth.0, 3);
+
+    let buffer = [1, 1, 1, 1, 0];
+    let result = deserialize::<EnumDepth, _, _>(&buffer[..], options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+}
+
+#[test]
+fn max_depth_limits_seqs_and_maps() {
+    let options = DefaultOptions::new().with_max_depth(1);
+    let buffer = [2, 5, 6];
+    let value: Seq<u8> = deserialize(&buffer[..], options).unwrap();
+    assert_eq!(value.0, 2);
+
+    let buffer = [1, 1, 5];
+    let result = deserialize::<Seq<Seq<u8>>, _, _>(&buffer[..], options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+
+    let buffer = [1, 3, 4];
+    let value: Map<u8> = deserialize(&buffer[..], options).unwrap();
+    assert_eq!(value.0, 1);
+
+    let buffer = [1, 1, 1, 2];
+    let result = deserialize::<Map<Seq<u8>>, _, _>(&buffer[..], options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+}
+
+#[test]
+fn hardened_depth_does_not_raise_max_depth() {
+    let options = DefaultOptions::new()
+        .with_hardened_decoding()
+        .with_max_depth(40);
+    let mut buffer = [0u8; 101];
+    let result = deserialize::<Depth, _, _>(nested(33, &mut buffer), options);
+    assert!(matches!(result, Err(DeserializeError::DepthLimitExceeded)));
+}
+
+#[test]
+fn hardened_length_must_fit_in_input() {
+    // A `&str` that claims to be 200 bytes long
+    let buffer = [200, b'a', b'b', b'c'];
+
+    let result = deserialize::<&str, _, _>(&buffer[..], DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::UnexpectedEof { needed: 197 })
+    ));
+
+    let options = DefaultOptions::new().with_hardened_decoding();
+    let result = deserialize::<&str, _, _>(&buffer[..], options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::LengthExceedsInput {
+            len: 200,
+            remaining: 3
+        })
+    ));
+}
+
+#[test]
+fn invalid_variant_is_an_error() {
+    let buffer = [7];
+    let result = deserialize::<Command, _, _>(&buffer[..], DefaultOptions::new());
+    assert!(matches!(result, Err(DeserializeError::Custom)));
+}
+
+#[test]
+fn unsupported_methods_are_an_error() {
+    let buffer = [0];
+    let result = deserialize::<IgnoredAny, _, _>(&buffer[..], DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Unsupported("deserialize_ignored_any"))
+    ));
+}
+
+#[test]
+fn arbitrary_input_does_not_panic() {
+    let options = DefaultOptions::new().with_hardened_decoding();
+    let mut state = 0x2545_F491u32;
+    let mut buffer = [0u8; 24];
+    for _ in 0..10_000 {
+        for byte in buffer.iter_mut() {
+            // xorshift32
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            // Keep most bytes small, so lengths and variant indexes are often valid
+            *byte = if state & 0x100 == 0 {
+                (state % 4) as u8
+            } else {
+                state as u8
+            };
+        }
+        let len = (state as usize >> 16) % buffer.len();
+        let _ = deserialize::<Packet, _, _>(&buffer[..len], options);
+        let _ = deserialize::<Depth, _, _>(&buffer[..len], options);
+    }
+}
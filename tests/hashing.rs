@@ -0,0 +1,80 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, serialize, BufferWriter, DefaultOptions, Deserializer, HashingReader,
+    HashingWriter, StreamHasher,
+};
+use serde::Deserialize;
+
+/// 32-bit FNV-1a, standing in for a real hash.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Fnv(u32);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0x811c_9dc5)
+    }
+}
+
+impl StreamHasher for Fnv {
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 = (self.0 ^ u32::from(*byte)).wrapping_mul(0x0100_0193);
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Manifest<'a> {
+    version: u16,
+    name: &'a str,
+    digest: [u8; 3],
+}
+
+const MANIFEST: Manifest = Manifest {
+    version: 3,
+    name: "firmware",
+    digest: [0xAA, 0xBB, 0xCC],
+};
+
+fn hash_of(bytes: &[u8]) -> Fnv {
+    let mut hash = Fnv::default();
+    hash.update(bytes);
+    hash
+}
+
+#[test]
+fn writer_hashes_the_written_bytes() {
+    let mut buffer = [0u8; 32];
+    let mut writer = HashingWriter::new(BufferWriter::new(&mut buffer), Fnv::default());
+    serialize(&MANIFEST, &mut writer, DefaultOptions::new()).unwrap();
+    let (writer, hash) = writer.into_parts();
+    assert_eq!(hash, hash_of(writer.written_buffer()));
+}
+
+#[test]
+fn reader_hashes_the_read_bytes() {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&MANIFEST, &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+
+    let mut deserializer = Deserializer::new(
+        HashingReader::new(bytes, Fnv::default()),
+        DefaultOptions::new(),
+    );
+    let manifest = Manifest::deserialize(&mut deserializer).unwrap();
+    let (_, hash) = deserializer.into_reader().into_parts();
+    assert_eq!(manifest, MANIFEST);
+    assert_eq!(hash, hash_of(bytes));
+}
+
+#[test]
+fn closures_are_hashers() {
+    let mut seen = Vec::new();
+    let reader = HashingReader::new(&[1u8, 2][..], |data: &[u8]| seen.extend_from_slice(data));
+    let value: (u8, u8) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(value, (1, 2));
+    assert_eq!(seen, [1, 2]);
+}
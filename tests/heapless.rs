@@ -0,0 +1,191 @@
+#![cfg(feature = "heapless")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_fixed_map, serialize, BufferWriter, BufferedReadError, BufferedReader,
+    DefaultOptions, DeserializeError, SpscReadError,
+};
+use heapless::spsc::Queue;
+use heapless::{FnvIndexMap, LinearMap};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Config<'a> {
+    #[serde(borrow)]
+    limits: LinearMap<&'a str, u16, 4>,
+    enabled: bool,
+}
+
+fn encode_pairs<'b>(pairs: &[(&str, u32)], buffer: &'b mut [u8]) -> &'b [u8] {
+    let mut map: LinearMap<&str, u32, 8> = LinearMap::new();
+    for &(key, value) in pairs {
+        map.insert(key, value).unwrap();
+    }
+    let mut writer = BufferWriter::new(buffer);
+    serialize(&map, &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    &buffer[..len]
+}
+
+#[test]
+fn fnv_index_map() {
+    let mut buffer = [0u8; 64];
+    let bytes = encode_pairs(&[("rate", 100), ("gain", 3), ("mode", 70_000)], &mut buffer);
+
+    let map: FnvIndexMap<&str, u32, 4> =
+        deserialize_fixed_map(bytes, DefaultOptions::new()).unwrap();
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["rate"], 100);
+    assert_eq!(map["gain"], 3);
+    assert_eq!(map["mode"], 70_000);
+    // Insertion order is kept
+    let keys: Vec<_> = map.keys().copied().collect();
+    assert_eq!(keys, ["rate", "gain", "mode"]);
+
+    let map: LinearMap<&str, u32, 3> = deserialize_fixed_map(bytes, DefaultOptions::new()).unwrap();
+    assert_eq!(map.get("mode"), Some(&70_000));
+}
+
+#[test]
+fn capacity_exceeded() {
+    let mut buffer = [0u8; 64];
+    let bytes = encode_pairs(&[("a", 1), ("b", 2), ("c", 3)], &mut buffer);
+
+    match deserialize_fixed_map::<FnvIndexMap<&str, u32, 2>, _, _>(bytes, DefaultOptions::new()) {
+        Err(DeserializeError::CapacityExceeded {
+            len: 3,
+            capacity: 2,
+        }) => {}
+        x => panic!("Expected CapacityExceeded, got {:?}", x),
+    }
+    match deserialize_fixed_map::<LinearMap<&str, u32, 1>, _, _>(bytes, DefaultOptions::new()) {
+        Err(DeserializeError::CapacityExceeded {
+            len: 3,
+            capacity: 1,
+        }) => {}
+        x => panic!("Expected CapacityExceeded, got {:?}", x),
+    }
+
+    // The length is checked before the entries, which are not even in the input here
+    let bytes = [200u8];
+    match deserialize_fixed_map::<LinearMap<u8, u8, 4>, _, _>(&bytes[..], DefaultOptions::new()) {
+        Err(DeserializeError::CapacityExceeded {
+            len: 200,
+            capacity: 4,
+        }) => {}
+        x => panic!("Expected CapacityExceeded, got {:?}", x),
+    }
+}
+
+#[test]
+fn duplicate_keys() {
+    // The last value is kept
+    let bytes = [2u8, 1, 10, 1, 20];
+    let map: LinearMap<u8, u8, 2> =
+        deserialize_fixed_map(&bytes[..], DefaultOptions::new()).unwrap();
+    assert_eq!(map.len(), 1);
+    assert_eq!(map.get(&1), Some(&20));
+
+    // The declared length counts, even if the keys would fit
+    match deserialize_fixed_map::<LinearMap<u8, u8, 1>, _, _>(&bytes[..], DefaultOptions::new()) {
+        Err(DeserializeError::CapacityExceeded {
+            len: 2,
+            capacity: 1,
+        }) => {}
+        x => panic!("Expected CapacityExceeded, got {:?}", x),
+    }
+}
+
+#[test]
+fn trailing_bytes() {
+    let bytes = [1u8, 1, 10, 0];
+    match deserialize_fixed_map::<LinearMap<u8, u8, 2>, _, _>(
+        &bytes[..],
+        DefaultOptions::new().reject_trailing_bytes(),
+    ) {
+        Err(DeserializeError::TrailingBytes) => {}
+        x => panic!("Expected TrailingBytes, got {:?}", x),
+    }
+}
+
+#[test]
+fn struct_field() {
+    let mut limits = LinearMap::new();
+    limits.insert("pump", 1200).unwrap();
+    limits.insert("fan", 300).unwrap();
+    let config = Config {
+        limits,
+        enabled: true,
+    };
+
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&config, &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+    let decoded: Config = deserialize(bytes, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, config);
+
+    // A field with too many entries fails with the error of heapless' `Deserialize`
+    let mut too_many = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut too_many);
+    let map: LinearMap<&str, u16, 5> = [("a", 1), ("b", 2), ("c", 3), ("d", 4), ("e", 5)]
+        .iter()
+        .copied()
+        .collect();
+    serialize(&(map, true), &mut writer, DefaultOptions::new()).unwrap();
+    let result = deserialize::<Config, _, _>(writer.written_buffer(), DefaultOptions::new());
+    match result {
+        Err(DeserializeError::Custom) => {}
+        x => panic!("Expected Custom, got {:?}", x),
+    }
+}
+
+#[test]
+fn spsc_consumer() {
+    let mut queue: Queue<u8, 16> = Queue::new();
+    let (mut producer, mut consumer) = queue.split();
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&(300u16, "abc"), &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+
+    // Only the first half arrived, the failed read leaves it in the queue
+    let options = DefaultOptions::new().with_fixint_encoding();
+    for &byte in &bytes[..2] {
+        producer.enqueue(byte).unwrap();
+    }
+    let result = deserialize::<u32, _, _>(&mut consumer, options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(SpscReadError::NotEnoughData))
+    ));
+    assert_eq!(SpscReadError::NotEnoughData.to_string(), "NotEnoughData");
+    assert_eq!(consumer.len(), 2);
+
+    for &byte in &bytes[2..] {
+        producer.enqueue(byte).unwrap();
+    }
+    let mut scratch = [0u8; 8];
+    let reader = BufferedReader::new(&mut consumer, &mut scratch);
+    let value: (u16, &str) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(value, (300, "abc"));
+    assert_eq!(consumer.len(), 0);
+
+    for &byte in bytes {
+        producer.enqueue(byte).unwrap();
+    }
+    let result = deserialize::<(u16, &str), _, _>(&mut consumer, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(SpscReadError::CannotBorrow))
+    ));
+    let mut scratch = [0u8; 2];
+    let reader = BufferedReader::new(&mut consumer, &mut scratch);
+    let result = deserialize::<&str, _, _>(reader, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(BufferedReadError::ScratchTooSmall))
+    ));
+}
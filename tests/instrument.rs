@@ -0,0 +1,139 @@
+#![cfg(feature = "instrument")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::{Instrument, Options};
+use bincode_core::{serialize, serialize_size, BufferWriter, DefaultOptions};
+
+#[derive(Serialize)]
+struct Position {
+    x: i16,
+    y: i16,
+}
+
+#[derive(Serialize)]
+struct Robot<'a> {
+    id: u8,
+    position: Position,
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+enum Command {
+    Move { speed: u8 },
+}
+
+#[derive(Debug, PartialEq)]
+enum Event {
+    StructStart(&'static str),
+    StructEnd,
+    FieldStart(&'static str),
+    FieldEnd(&'static str),
+    Bytes(usize),
+}
+
+#[derive(Default)]
+struct Recorder {
+    events: Vec<Event>,
+}
+
+impl Instrument for Recorder {
+    fn on_struct_start(&mut self, name: &'static str) {
+        self.events.push(Event::StructStart(name));
+    }
+
+    fn on_struct_end(&mut self) {
+        self.events.push(Event::StructEnd);
+    }
+
+    fn on_field_start(&mut self, name: &'static str) {
+        self.events.push(Event::FieldStart(name));
+    }
+
+    fn on_field_end(&mut self, name: &'static str) {
+        self.events.push(Event::FieldEnd(name));
+    }
+
+    fn on_bytes_written(&mut self, n: usize) {
+        self.events.push(Event::Bytes(n));
+    }
+}
+
+#[test]
+fn nested_struct() {
+    let robot = Robot {
+        id: 7,
+        position: Position { x: 1, y: -1 },
+        name: "r2",
+    };
+    let mut recorder = Recorder::default();
+    let mut buffer = [0u8; 16];
+    let options = DefaultOptions::new().with_instrument(&mut recorder);
+    serialize(&robot, BufferWriter::new(&mut buffer), options).unwrap();
+
+    use Event::*;
+    assert_eq!(
+        recorder.events,
+        [
+            StructStart("Robot"),
+            FieldStart("id"),
+            Bytes(1),
+            FieldEnd("id"),
+            FieldStart("position"),
+            StructStart("Position"),
+            FieldStart("x"),
+            Bytes(1),
+            FieldEnd("x"),
+            FieldStart("y"),
+            Bytes(1),
+            FieldEnd("y"),
+            StructEnd,
+            FieldEnd("position"),
+            FieldStart("name"),
+            Bytes(1),
+            Bytes(2),
+            FieldEnd("name"),
+            StructEnd,
+        ]
+    );
+}
+
+#[test]
+fn struct_variant() {
+    let mut recorder = Recorder::default();
+    let mut buffer = [0u8; 4];
+    let options = DefaultOptions::new().with_instrument(&mut recorder);
+    serialize(
+        &Command::Move { speed: 3 },
+        BufferWriter::new(&mut buffer),
+        options,
+    )
+    .unwrap();
+
+    use Event::*;
+    assert_eq!(
+        recorder.events,
+        [
+            StructStart("Move"),
+            Bytes(1),
+            FieldStart("speed"),
+            Bytes(1),
+            FieldEnd("speed"),
+            StructEnd,
+        ]
+    );
+}
+
+#[test]
+fn size_checks_are_not_recorded() {
+    let mut recorder = Recorder::default();
+    let options = DefaultOptions::new()
+        .with_limit(16)
+        .with_instrument(&mut recorder);
+    assert_eq!(
+        serialize_size(&Position { x: 1, y: 2 }, options).unwrap(),
+        2
+    );
+    assert!(recorder.events.is_empty());
+}
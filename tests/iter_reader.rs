@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_from_iter, serialize, BufferWriter, DefaultOptions, DeserializeError,
+    IterReader, SliceReadError,
+};
+use std::collections::VecDeque;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Message<'a> {
+    id: u32,
+    name: &'a str,
+    payload: &'a [u8],
+}
+
+const MESSAGE: Message = Message {
+    id: 5,
+    name: "sensor",
+    payload: &[1, 2, 3],
+};
+
+fn serialized(message: &Message) -> Vec<u8> {
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(message, &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    buffer[..len].to_vec()
+}
+
+#[test]
+fn borrowed_fields_from_queue() {
+    // Drained like a `heapless::spsc::Consumer`, without a known length
+    let mut queue: VecDeque<u8> = serialized(&MESSAGE).into();
+    let mut scratch = [0u8; 16];
+    let iter = core::iter::from_fn(|| queue.pop_front());
+    let deserialized: Message =
+        deserialize_from_iter(iter, &mut scratch, DefaultOptions::new()).unwrap();
+    assert_eq!(deserialized, MESSAGE);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn multiple_values() {
+    let mut bytes = serialized(&MESSAGE);
+    bytes.extend(serialized(&Message { id: 6, ..MESSAGE }));
+    let options = DefaultOptions::new().allow_trailing_bytes();
+    let mut iter = bytes.into_iter();
+
+    let mut scratch = [0u8; 16];
+    let first: Message = deserialize_from_iter(iter.by_ref(), &mut scratch, options).unwrap();
+    assert_eq!(first, MESSAGE);
+    let mut scratch = [0u8; 16];
+    let reader = IterReader::new(iter.by_ref(), &mut scratch);
+    let second: Message = deserialize(reader, options).unwrap();
+    assert_eq!(second.id, 6);
+    assert_eq!(iter.len(), 0);
+}
+
+#[test]
+fn errors() {
+    let bytes = serialized(&MESSAGE);
+
+    let mut scratch = [0u8; 8];
+    let result = deserialize_from_iter::<Message, _, _>(
+        bytes.iter().copied(),
+        &mut scratch,
+        DefaultOptions::new(),
+    );
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(SliceReadError::ScratchTooSmall))
+    ));
+
+    let mut scratch = [0u8; 16];
+    let result = deserialize_from_iter::<Message, _, _>(
+        bytes[..bytes.len() - 1].iter().copied(),
+        &mut scratch,
+        DefaultOptions::new(),
+    );
+    assert!(result.is_err());
+
+    // The exact size hint of a slice iterator detects trailing bytes
+    let mut scratch = [0u8; 16];
+    let result = deserialize_from_iter::<Message, _, _>(
+        bytes.iter().chain(&[0]).copied(),
+        &mut scratch,
+        DefaultOptions::new(),
+    );
+    assert!(matches!(result, Err(DeserializeError::TrailingBytes)));
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, IterSeq, SerializeError,
+};
+use core::cell::Cell;
+
+#[derive(Serialize)]
+#[serde(bound(serialize = "I: Iterator + Clone, I::Item: serde::Serialize"))]
+struct Report<I> {
+    id: u16,
+    samples: IterSeq<I>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+struct DecodedReport {
+    id: u16,
+    samples: [u32; 3],
+}
+
+#[test]
+fn same_encoding_as_a_slice() {
+    let samples = [10u32, 70_000, 3, 500];
+    let filtered = IterSeq(samples.iter().copied().filter(|s| *s != 3));
+    let options = DefaultOptions::new().with_fixint_encoding();
+
+    let mut expected = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut expected);
+    serialize(&[10u32, 70_000, 500][..], &mut writer, options).unwrap();
+    let expected = writer.written_buffer();
+
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&filtered, &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), expected);
+    assert_eq!(serialize_size(&filtered, options).unwrap(), expected.len());
+}
+
+#[test]
+fn nested_in_a_struct() {
+    let samples = [1u32, 2, 3, 4, 5];
+    let report = Report {
+        id: 7,
+        samples: IterSeq(samples.iter().filter(|s| *s % 2 == 1)),
+    };
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&report, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(writer.written_buffer(), &[7, 3, 1, 3, 5]);
+
+    // The length is followed by the elements, skip it to read the elements as an array
+    let bytes = writer.written_buffer();
+    let decoded: DecodedReport = deserialize(
+        &[bytes[0], bytes[2], bytes[3], bytes[4]][..],
+        DefaultOptions::new(),
+    )
+    .unwrap();
+    assert_eq!(
+        decoded,
+        DecodedReport {
+            id: 7,
+            samples: [1, 3, 5]
+        }
+    );
+}
+
+#[test]
+fn iterator_that_changes_between_runs() {
+    let runs = Cell::new(0);
+    // Yields one more item on every run
+    let iter = (0..4u8).filter(|i| {
+        if *i == 0 {
+            runs.set(runs.get() + 1);
+        }
+        *i < runs.get()
+    });
+    let mut buffer = [0u8; 8];
+    assert!(matches!(
+        serialize(
+            &IterSeq(iter),
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Custom)
+    ));
+}
@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Sample {
+    len: u32,
+    delta: i16,
+    offset: i64,
+}
+
+fn options() -> impl Options {
+    DefaultOptions::new().with_leb128_encoding()
+}
+
+/// Checks that `value` serializes to the given LEB128 bytes, and that it round-trips.
+fn check<'a, T: Serialize + Deserialize<'a> + PartialEq + Debug>(value: T, expected: &'a [u8]) {
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options()).unwrap();
+    assert_eq!(expected, writer.written_buffer());
+    assert_eq!(expected.len(), serialize_size(&value, options()).unwrap());
+
+    let deserialized: T = deserialize(expected, options()).unwrap();
+    assert_eq!(value, deserialized);
+}
+
+#[test]
+fn leb128_unsigned() {
+    check(0u16, &[0x00]);
+    check(624_485u32, &[0xE5, 0x8E, 0x26]);
+    check(u32::MAX, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    check(
+        u64::MAX,
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+    );
+}
+
+#[test]
+fn leb128_signed() {
+    check(0i16, &[0x00]);
+    check(-1i16, &[0x7F]);
+    check(63i16, &[0x3F]);
+    check(64i16, &[0xC0, 0x00]);
+    check(-64i32, &[0x40]);
+    check(-65i32, &[0xBF, 0x7F]);
+    check(-123_456i32, &[0xC0, 0xBB, 0x78]);
+    check(
+        i64::MIN,
+        &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F],
+    );
+    check(
+        i64::MAX,
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00],
+    );
+    check(-1i128, &[0x7F]);
+    check(i128::MAX, &{
+        let mut bytes = [0xFF; 19];
+        bytes[18] = 0x01;
+        bytes
+    });
+}
+
+#[test]
+fn leb128_struct() {
+    check(
+        Sample {
+            len: 300,
+            delta: -2,
+            offset: 64,
+        },
+        &[0xAC, 0x02, 0x7E, 0xC0, 0x00],
+    );
+}
+
+#[test]
+fn leb128_rejects_invalid_varints() {
+    // Last byte of an i64 has bits set that do not match the sign
+    assert!(deserialize::<i64, _, _>(
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01][..],
+        options()
+    )
+    .is_err());
+    // Too many continuation bytes for an i64
+    assert!(deserialize::<i64, _, _>(&[0x80; 11][..], options()).is_err());
+    // Valid i64, but does not fit in an i16
+    assert!(deserialize::<i16, _, _>(&[0x80, 0x80, 0x04][..], options()).is_err());
+}
@@ -0,0 +1,258 @@
+use bincode_core::config::{
+    Bounded, LimitError, Options, SizeLimit, WithOtherLimit, WithOtherReadLimit,
+};
+use bincode_core::{
+    deserialize, deserialize_with_remaining, serialize, BufferWriter, DefaultOptions,
+    DeserializeError, SerializeError,
+};
+
+fn write_str(value: &str, buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(value, &mut writer, DefaultOptions::new()).unwrap();
+    writer.written_len()
+}
+
+#[test]
+fn max_element_len_accepts_short_elements() {
+    let mut buffer = [0u8; 100];
+    let len = write_str("test", &mut buffer);
+
+    let options = DefaultOptions::new().with_max_element_len(4);
+    let value: &str = deserialize(&buffer[..len], options).unwrap();
+    assert_eq!(value, "test");
+}
+
+#[test]
+fn max_element_len_rejects_long_str() {
+    let mut buffer = [0u8; 100];
+    let len = write_str("too long", &mut buffer);
+
+    let options = DefaultOptions::new().with_max_element_len(4);
+    let result: Result<&str, _> = deserialize(&buffer[..len], options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::ElementTooLarge { len: 8, max: 4 })
+    ));
+}
+
+#[test]
+fn max_element_len_rejects_corrupt_length() {
+    // Varint u64 marker followed by a huge length and no content
+    let buffer = [253u8, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0];
+
+    let options = DefaultOptions::new().with_max_element_len(16);
+    let result: Result<&[u8], _> = deserialize(&buffer[..], options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::ElementTooLarge {
+            len: 0xFFFF_FFFF,
+            max: 16
+        })
+    ));
+}
+
+#[test]
+fn limit_rejects_str_length_before_reading() {
+    let mut buffer = [0u8; 100];
+    let len = write_str("some longer string", &mut buffer);
+
+    let options = DefaultOptions::new().with_limit(8);
+    let result: Result<&str, _> = deserialize(&buffer[..len], options);
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+}
+
+#[test]
+fn serialized_size_honors_limit() {
+    let value = (1u8, "test");
+
+    // u8 (1 byte) + len (1 byte) + content (4 bytes)
+    let options = DefaultOptions::new().with_limit(6);
+    assert_eq!(options.serialized_size(&value).unwrap(), 6);
+
+    let options = DefaultOptions::new().with_limit(5);
+    assert!(matches!(
+        options.serialized_size(&value),
+        Err(SerializeError::LimitReached)
+    ));
+}
+
+#[test]
+fn serialize_honors_limit_without_writing() {
+    let value = (1u8, "test");
+    let mut buffer = [0u8; 100];
+
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new().with_limit(5);
+    assert!(matches!(
+        options.serialize_into(&mut writer, &value),
+        Err(SerializeError::LimitReached)
+    ));
+    assert_eq!(writer.written_len(), 0);
+
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new().with_limit(6);
+    options.serialize_into(&mut writer, &value).unwrap();
+    assert_eq!(writer.written_len(), 6);
+}
+
+/// Records the size hint of a sequence, without reading its elements.
+#[derive(Debug, PartialEq)]
+struct SeqHint(Option<usize>);
+
+/// Records the size hint of a map, without reading its entries.
+#[derive(Debug, PartialEq)]
+struct MapHint(Option<usize>);
+
+struct HintVisitor;
+
+impl<'de> serde::de::Visitor<'de> for HintVisitor {
+    type Value = Option<usize>;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("a sequence or map")
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        Ok(seq.size_hint())
+    }
+
+    fn visit_map<A: serde::de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        Ok(map.size_hint())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SeqHint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(HintVisitor).map(SeqHint)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MapHint {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(HintVisitor).map(MapHint)
+    }
+}
+
+#[test]
+fn size_hint_is_clamped_to_remaining_input() {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&[1u8, 2, 3][..], &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    let (hint, _): (SeqHint, _) =
+        deserialize_with_remaining(&buffer[..len], DefaultOptions::new()).unwrap();
+    assert_eq!(hint, SeqHint(Some(3)));
+
+    // A varint length of 1_000_000, followed by 3 bytes
+    let buffer = [252, 0x40, 0x42, 0x0F, 0x00, 1, 2, 3];
+    let (hint, _): (SeqHint, _) =
+        deserialize_with_remaining(&buffer[..], DefaultOptions::new()).unwrap();
+    assert_eq!(hint, SeqHint(Some(3)));
+    let (hint, _): (MapHint, _) =
+        deserialize_with_remaining(&buffer[..], DefaultOptions::new()).unwrap();
+    assert_eq!(hint, MapHint(Some(3)));
+}
+
+#[test]
+fn size_hint_is_clamped_to_limit() {
+    let mut buffer = [0u8; 32];
+    buffer[..5].copy_from_slice(&[252, 0x40, 0x42, 0x0F, 0x00]);
+
+    // The length itself takes 5 bytes of the limit
+    let options = DefaultOptions::new().with_limit(10);
+    let (hint, _): (SeqHint, _) = deserialize_with_remaining(&buffer[..], options).unwrap();
+    assert_eq!(hint, SeqHint(Some(5)));
+    let (hint, _): (MapHint, _) = deserialize_with_remaining(&buffer[..], options).unwrap();
+    assert_eq!(hint, MapHint(Some(5)));
+}
+
+/// Counts the bytes that were read, and allows reading `max` bytes.
+struct CountingLimit {
+    used: u64,
+    max: u64,
+}
+
+impl SizeLimit for CountingLimit {
+    fn add(&mut self, n: u64) -> Result<(), LimitError> {
+        if self.used + n > self.max {
+            return Err(LimitError::LimitReached);
+        }
+        self.used += n;
+        Ok(())
+    }
+
+    fn rewind(&mut self, n: u64) {
+        self.used -= n;
+    }
+
+    fn limit(&self) -> Option<u64> {
+        Some(self.max - self.used)
+    }
+}
+
+#[test]
+fn custom_size_limit() {
+    let mut buffer = [0u8; 100];
+    let len = write_str("test", &mut buffer);
+
+    let limit = CountingLimit { used: 0, max: 5 };
+    let options = WithOtherLimit::new(DefaultOptions::new(), limit);
+    let value: &str = deserialize(&buffer[..len], options).unwrap();
+    assert_eq!(value, "test");
+
+    let limit = CountingLimit { used: 0, max: 4 };
+    let options = WithOtherLimit::new(DefaultOptions::new(), limit);
+    let result: Result<&str, _> = deserialize(&buffer[..len], options);
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+
+    // A limit behind a trait object
+    let limit: &'static mut dyn SizeLimit = Box::leak(Box::new(Bounded(4)));
+    let options = WithOtherLimit::new(DefaultOptions::new(), limit);
+    let result = serialize("test", BufferWriter::new(&mut buffer), options);
+    assert!(matches!(result, Err(SerializeError::LimitReached)));
+}
+
+#[test]
+fn separate_read_and_write_limits() {
+    let value = (1u8, "test");
+    let mut buffer = [0u8; 100];
+    let options = DefaultOptions::new().with_read_limit(5).with_write_limit(6);
+
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options).unwrap();
+    let len = writer.written_len();
+    assert_eq!(len, 6);
+    let result: Result<(u8, &str), _> = deserialize(&buffer[..len], options);
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+
+    let options = DefaultOptions::new().with_read_limit(6).with_write_limit(5);
+    let mut writer = BufferWriter::new(&mut buffer);
+    assert!(matches!(
+        serialize(&value, &mut writer, options),
+        Err(SerializeError::LimitReached)
+    ));
+    assert_eq!(writer.written_len(), 0);
+    let decoded: (u8, &str) = deserialize(&buffer[..len], options).unwrap();
+    assert_eq!(decoded, value);
+
+    // `with_limit` sets both
+    let options = DefaultOptions::new().with_read_limit(100).with_limit(5);
+    let result: Result<(u8, &str), _> = deserialize(&buffer[..len], options);
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+}
+
+#[test]
+fn custom_read_limit() {
+    let mut buffer = [0u8; 100];
+    let len = write_str("test", &mut buffer);
+
+    let limit = CountingLimit { used: 0, max: 4 };
+    let options = WithOtherReadLimit::new(DefaultOptions::new(), limit);
+    let result: Result<&str, _> = deserialize(&buffer[..len], options);
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+
+    // The limit does not apply to serialization
+    let limit = CountingLimit { used: 0, max: 4 };
+    let options = WithOtherReadLimit::new(DefaultOptions::new(), limit);
+    serialize("test", BufferWriter::new(&mut buffer), options).unwrap();
+}
@@ -0,0 +1,118 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::mac::{deserialize_with_mac, serialize_with_mac, MacProvider};
+use bincode_core::{BufferWriter, DefaultOptions, DeserializeError};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Command<'a> {
+    id: u32,
+    name: &'a str,
+    payload: &'a [u8],
+}
+
+/// A keyed FNV-1a hash, standing in for a real MAC.
+struct KeyedFnv {
+    hash: u64,
+}
+
+impl KeyedFnv {
+    fn new(key: u64) -> Self {
+        KeyedFnv {
+            hash: 0xcbf2_9ce4_8422_2325 ^ key,
+        }
+    }
+}
+
+impl MacProvider for KeyedFnv {
+    type Tag = [u8; 8];
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.hash = (self.hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finalize(self) -> [u8; 8] {
+        self.hash.to_le_bytes()
+    }
+}
+
+fn command() -> Command<'static> {
+    Command {
+        id: 70_000,
+        name: "unlock",
+        payload: &[1, 2, 3],
+    }
+}
+
+fn write(command: &Command, key: u64) -> Vec<u8> {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_with_mac(
+        command,
+        &mut writer,
+        DefaultOptions::new(),
+        KeyedFnv::new(key),
+    )
+    .unwrap();
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn round_trip() {
+    let bytes = write(&command(), 42);
+    let mut mac = KeyedFnv::new(42);
+    mac.update(&bytes[..bytes.len() - 8]);
+    assert_eq!(bytes[bytes.len() - 8..], mac.finalize());
+
+    let decoded: Command =
+        deserialize_with_mac(&bytes[..], DefaultOptions::new(), KeyedFnv::new(42)).unwrap();
+    assert_eq!(decoded, command());
+}
+
+#[test]
+fn every_byte_is_authenticated() {
+    let bytes = write(&command(), 42);
+    assert_eq!(bytes.len(), 24);
+    // The id, the bytes of the borrowed `&str` and `&[u8]`, and the tag itself. Changing the varint
+    // tag or the lengths makes the message invalid before the tag is read.
+    for i in (1..5).chain(6..12).chain(13..24) {
+        let mut tampered = bytes.clone();
+        tampered[i] ^= 0x01;
+        match deserialize_with_mac::<Command, _, _, _>(
+            &tampered[..],
+            DefaultOptions::new(),
+            KeyedFnv::new(42),
+        ) {
+            Err(DeserializeError::MacMismatch) => {}
+            x => panic!("Expected MacMismatch for byte {}, got {:?}", i, x),
+        }
+    }
+}
+
+#[test]
+fn wrong_key() {
+    let bytes = write(&command(), 42);
+    match deserialize_with_mac::<Command, _, _, _>(
+        &bytes[..],
+        DefaultOptions::new(),
+        KeyedFnv::new(43),
+    ) {
+        Err(DeserializeError::MacMismatch) => {}
+        x => panic!("Expected MacMismatch, got {:?}", x),
+    }
+}
+
+#[test]
+fn missing_tag() {
+    let bytes = write(&command(), 42);
+    match deserialize_with_mac::<Command, _, _, _>(
+        &bytes[..bytes.len() - 3],
+        DefaultOptions::new(),
+        KeyedFnv::new(42),
+    ) {
+        Err(DeserializeError::UnexpectedEof { needed: 3 }) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+}
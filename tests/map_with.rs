@@ -0,0 +1,84 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize_map_with, serialize, BufferWriter, DefaultOptions, DeserializeError,
+};
+use serde::{Serialize, Serializer};
+
+/// Key-value telemetry, serialized as a map.
+struct Telemetry<'a>(&'a [(&'a str, i32)]);
+
+impl Serialize for Telemetry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(key, value)| (key, value)))
+    }
+}
+
+const ENTRIES: [(&str, i32); 3] = [("temperature", 215), ("humidity", 40), ("pressure", -3)];
+
+fn write_telemetry(buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(&Telemetry(&ENTRIES), &mut writer, DefaultOptions::new()).unwrap();
+    writer.written_len()
+}
+
+#[test]
+fn entries_are_passed_in_order() {
+    let mut buffer = [0u8; 64];
+    let len = write_telemetry(&mut buffer);
+
+    let mut index = 0;
+    let count = deserialize_map_with(
+        &buffer[..len],
+        DefaultOptions::new(),
+        |key: &str, value: i32| {
+            assert_eq!((key, value), ENTRIES[index]);
+            index += 1;
+        },
+    )
+    .unwrap();
+    assert_eq!(count, 3);
+    assert_eq!(index, 3);
+}
+
+#[test]
+fn empty_map() {
+    let buffer = [0u8];
+    let count = deserialize_map_with(&buffer[..], DefaultOptions::new(), |_: u8, _: u8| {
+        panic!("no entries expected")
+    })
+    .unwrap();
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn map_length_is_checked_before_entries() {
+    let mut buffer = [0u8; 64];
+    let len = write_telemetry(&mut buffer);
+
+    let options = DefaultOptions::new().with_max_element_len(2);
+    let result = deserialize_map_with(&buffer[..len], options, |_: &str, _: i32| {
+        panic!("no entries expected")
+    });
+    assert!(matches!(
+        result,
+        Err(DeserializeError::ElementTooLarge { len: 3, max: 2 })
+    ));
+}
+
+#[test]
+fn truncated_map_stops_with_an_error() {
+    let mut buffer = [0u8; 64];
+    let len = write_telemetry(&mut buffer);
+
+    let mut count = 0;
+    let result = deserialize_map_with(
+        &buffer[..len - 1],
+        DefaultOptions::new(),
+        |_: &str, _: i32| count += 1,
+    );
+    assert!(matches!(
+        result,
+        Err(DeserializeError::UnexpectedEof { needed: 1 })
+    ));
+    assert_eq!(count, 2);
+}
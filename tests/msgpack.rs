@@ -0,0 +1,207 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+    SerializeError,
+};
+use rmpv::Value;
+use serde::Serialize;
+
+#[derive(Serialize)]
+enum Mode {
+    Idle,
+    Heat(u16),
+    Cycle(u8, u8),
+    Target { celsius: i16 },
+}
+
+#[derive(Serialize)]
+struct Unit;
+
+#[derive(Serialize)]
+struct Status<'a> {
+    on: bool,
+    name: &'a str,
+    letter: char,
+    gain: f32,
+    offset: Option<i64>,
+    missing: Option<u8>,
+    history: [u16; 3],
+    pair: (u8, f64),
+    unit: Unit,
+    modes: (Mode, Mode, Mode, Mode),
+}
+
+fn status() -> Status<'static> {
+    Status {
+        on: true,
+        name: "pump",
+        letter: 'é',
+        gain: 1.5,
+        offset: Some(-70000),
+        missing: None,
+        history: [1, 300, 4464],
+        pair: (255, -0.25),
+        unit: Unit,
+        modes: (
+            Mode::Idle,
+            Mode::Heat(40),
+            Mode::Cycle(1, 2),
+            Mode::Target { celsius: -5 },
+        ),
+    }
+}
+
+fn encode<T: Serialize + ?Sized, O: Options>(value: &T, options: O) -> Vec<u8> {
+    let mut buffer = [0u8; 512];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+fn msgpack<T: Serialize + ?Sized>(value: &T) -> Vec<u8> {
+    encode(value, DefaultOptions::new().with_msgpack_format())
+}
+
+fn map(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (Value::from(key), value))
+            .collect(),
+    )
+}
+
+#[test]
+fn ints_use_the_smallest_encoding() {
+    assert_eq!(msgpack(&0u8), [0x00]);
+    assert_eq!(msgpack(&127u64), [0x7F]);
+    assert_eq!(msgpack(&128u8), [0xCC, 0x80]);
+    assert_eq!(msgpack(&256u16), [0xCD, 0x01, 0x00]);
+    assert_eq!(msgpack(&65536u32), [0xCE, 0x00, 0x01, 0x00, 0x00]);
+    assert_eq!(
+        msgpack(&u64::MAX),
+        [0xCF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+    );
+
+    assert_eq!(msgpack(&5i32), [0x05]);
+    assert_eq!(msgpack(&-1i8), [0xFF]);
+    assert_eq!(msgpack(&-32i64), [0xE0]);
+    assert_eq!(msgpack(&-33i8), [0xD0, 0xDF]);
+    assert_eq!(msgpack(&-129i16), [0xD1, 0xFF, 0x7F]);
+    assert_eq!(msgpack(&-32769i32), [0xD2, 0xFF, 0xFF, 0x7F, 0xFF]);
+    assert_eq!(
+        msgpack(&i64::MIN),
+        [0xD3, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]
+    );
+
+    let mut expected = vec![0xC4, 16];
+    expected.extend_from_slice(&1u128.to_be_bytes());
+    assert_eq!(msgpack(&1u128), expected);
+    let mut expected = vec![0xC4, 16];
+    expected.extend_from_slice(&(-2i128).to_be_bytes());
+    assert_eq!(msgpack(&-2i128), expected);
+}
+
+#[test]
+fn lengths_use_the_smallest_header() {
+    let text = "x".repeat(300);
+    assert_eq!(msgpack(&text[..31])[0], 0xBF);
+    assert_eq!(msgpack(&text[..32])[..2], [0xD9, 32]);
+    assert_eq!(msgpack(&text[..])[..3], [0xDA, 0x01, 0x2C]);
+
+    let items = [0u8; 16];
+    assert_eq!(msgpack(&items[..15])[0], 0x9F);
+    assert_eq!(msgpack(&items[..])[..3], [0xDC, 0x00, 0x10]);
+}
+
+#[test]
+fn values_decode_with_a_msgpack_library() {
+    let bytes = msgpack(&status());
+    let value = rmpv::decode::read_value(&mut &bytes[..]).unwrap();
+    let expected = map(vec![
+        ("on", Value::from(true)),
+        ("name", Value::from("pump")),
+        ("letter", Value::from("é")),
+        ("gain", Value::F32(1.5)),
+        ("offset", Value::from(-70000)),
+        ("missing", Value::Nil),
+        (
+            "history",
+            Value::Array(vec![1.into(), 300.into(), 4464.into()]),
+        ),
+        ("pair", Value::Array(vec![255.into(), Value::F64(-0.25)])),
+        ("unit", Value::Nil),
+        (
+            "modes",
+            Value::Array(vec![
+                Value::from("Idle"),
+                map(vec![("Heat", 40.into())]),
+                map(vec![("Cycle", Value::Array(vec![1.into(), 2.into()]))]),
+                map(vec![("Target", map(vec![("celsius", Value::from(-5))]))]),
+            ]),
+        ),
+    ]);
+    assert_eq!(value, expected);
+}
+
+#[test]
+fn other_encoding_options_are_ignored() {
+    let expected = msgpack(&status());
+    let options = DefaultOptions::new().with_msgpack_format();
+    assert_eq!(encode(&status(), options.with_fixint_encoding()), expected);
+    assert_eq!(encode(&status(), options.with_big_endian()), expected);
+    assert_eq!(encode(&status(), options.with_type_tags()), expected);
+    assert_eq!(encode(&status(), options.with_packed_bools()), expected);
+    assert_eq!(encode(&status(), options.with_counted_structs()), expected);
+    assert_eq!(encode(&status(), options.with_presence_bitmap()), expected);
+    assert_eq!(encode(&status(), options.with_niche_options()), expected);
+    assert_eq!(encode(&status(), options.with_u32_chars()), expected);
+    assert_ne!(encode(&status(), options.with_bincode_format()), expected);
+}
+
+#[test]
+fn size_and_limit() {
+    let options = DefaultOptions::new().with_msgpack_format();
+    let len = msgpack(&status()).len();
+    assert_eq!(serialize_size(&status(), options).unwrap(), len);
+
+    let mut buffer = [0u8; 256];
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize(&status(), &mut writer, options.with_limit(len as u64 - 1)) {
+        Err(SerializeError::LimitReached) => {}
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(writer.written_len(), 0);
+    serialize(&status(), &mut writer, options.with_limit(len as u64)).unwrap();
+    assert_eq!(writer.written_len(), len);
+}
+
+#[test]
+fn sequences_must_have_a_length() {
+    struct Odd;
+
+    impl Serialize for Odd {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq((0u8..10).filter(|i| i % 2 == 1))
+        }
+    }
+
+    let options = DefaultOptions::new().with_msgpack_format();
+    match serialize(&Odd, BufferWriter::new(&mut [0u8; 16]), options) {
+        Err(SerializeError::SequenceMustHaveLength) => {}
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn deserializing_is_unsupported() {
+    let bytes = msgpack(&5u8);
+    let options = DefaultOptions::new().with_msgpack_format();
+    match deserialize::<u8, _, _>(&bytes[..], options) {
+        Err(DeserializeError::Unsupported(_)) => {}
+        other => panic!("{:?}", other),
+    }
+}
@@ -0,0 +1,140 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, CoreRead, DefaultOptions,
+    DeserializeError, SerializeError,
+};
+use core::num::{NonZeroI16, NonZeroU32, NonZeroU8};
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Packet {
+    seq: u16,
+    node: Option<NonZeroU8>,
+    offset: Option<NonZeroI16>,
+    session: Option<NonZeroU32>,
+}
+
+fn options() -> impl Options {
+    DefaultOptions::new().with_niche_options()
+}
+
+fn write<T: Serialize>(value: &T, options: impl Options, buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(value, &mut writer, options).unwrap();
+    writer.written_len()
+}
+
+#[test]
+fn no_tag_bytes() {
+    let packet = Packet {
+        seq: 9,
+        node: NonZeroU8::new(3),
+        offset: NonZeroI16::new(-1),
+        session: None,
+    };
+    let mut buffer = [0u8; 16];
+    let len = write(&packet, options(), &mut buffer);
+    // -1 is zigzag encoded as 1, `None` is a single zero
+    assert_eq!(&buffer[..len], &[9, 3, 1, 0]);
+    assert_eq!(serialize_size(&packet, options()).unwrap(), len);
+
+    let decoded: Packet = deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(decoded, packet);
+
+    let mut tagged = [0u8; 16];
+    let tagged_len = write(&packet, DefaultOptions::new(), &mut tagged);
+    assert_eq!(tagged_len, len + 2);
+}
+
+#[test]
+fn large_values() {
+    let packet = Packet {
+        seq: 1,
+        node: None,
+        offset: NonZeroI16::new(i16::MIN),
+        session: NonZeroU32::new(u32::MAX),
+    };
+    let mut buffer = [0u8; 16];
+    let len = write(&packet, options(), &mut buffer);
+    let decoded: Packet = deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(decoded, packet);
+}
+
+#[test]
+fn zero_values_are_rejected() {
+    fn assert_invalid<T: Serialize>(value: &T, options: impl Options) {
+        let mut buffer = [0u8; 16];
+        let mut writer = BufferWriter::new(&mut buffer);
+        match serialize(value, &mut writer, options) {
+            Err(SerializeError::InvalidNiche) => {}
+            x => panic!("Expected InvalidNiche, got {:?}", x),
+        }
+    }
+    assert_invalid(&Some(0u32), options());
+    assert_invalid(&Some(""), options());
+    assert_invalid(&Some(()), options());
+    assert_invalid(&Some(false), options());
+    assert_invalid(&Some(None::<u8>), options());
+    assert_invalid(&Some(true), options().with_packed_bools());
+    // 256 starts with a zero byte with fixint encoding
+    assert_invalid(&Some(256u32), options().with_fixint_encoding());
+
+    let mut buffer = [0u8; 16];
+    let len = write(
+        &(Some(true), Some("a"), Some(256u32)),
+        options(),
+        &mut buffer,
+    );
+    let decoded: (Option<bool>, Option<&str>, Option<u32>) =
+        deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(decoded, (Some(true), Some("a"), Some(256)));
+}
+
+/// A reader that cannot peek, like a serial port.
+struct Stream<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> CoreRead<'a> for Stream<'_> {
+    type Error = ();
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        if buffer.len() > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[..buffer.len()]);
+        self.data = &self.data[buffer.len()..];
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+}
+
+#[test]
+fn reader_must_peek() {
+    let stream = Stream { data: &[5] };
+    match deserialize::<Option<NonZeroU8>, _, _>(stream, options()) {
+        Err(DeserializeError::Unsupported("peek")) => {}
+        x => panic!("Expected Unsupported, got {:?}", x),
+    }
+
+    match deserialize::<Option<NonZeroU8>, _, _>(&[][..], options()) {
+        Err(DeserializeError::UnexpectedEof { needed: 1 }) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+}
@@ -0,0 +1,181 @@
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, BufferWriterError, CoreWrite,
+    DefaultOptions, SerializeError,
+};
+use core::cell::Cell;
+use core::fmt;
+use serde::ser::{Error, SerializeSeq};
+use serde::{Serialize, Serializer};
+
+/// A sequence that does not know its length up front.
+struct Unsized;
+
+impl Serialize for Unsized {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(None)?;
+        seq.serialize_element(&1u8)?;
+        seq.end()
+    }
+}
+
+#[test]
+fn sequence_without_length() {
+    let mut buffer = [0u8; 8];
+    assert!(matches!(
+        serialize(
+            &Unsized,
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::SequenceMustHaveLength)
+    ));
+    assert!(matches!(
+        serialize_size(&Unsized, DefaultOptions::new()),
+        Err(SerializeError::SequenceMustHaveLength)
+    ));
+}
+
+struct Rejected;
+
+impl Serialize for Rejected {
+    fn serialize<S: Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+        Err(S::Error::custom("not serializable"))
+    }
+}
+
+#[test]
+fn custom_error() {
+    let mut buffer = [0u8; 8];
+    assert!(matches!(
+        serialize(
+            &Rejected,
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Custom)
+    ));
+    assert!(matches!(
+        serialize_size(&Rejected, DefaultOptions::new()),
+        Err(SerializeError::Custom)
+    ));
+}
+
+/// A value that is serialized with `collect_str`.
+struct Version {
+    major: u8,
+    minor: u8,
+    /// Makes every call to `fmt` print one more character than the last call
+    grow: Option<Cell<usize>>,
+    fail: bool,
+}
+
+impl Version {
+    fn new(major: u8, minor: u8) -> Self {
+        Version {
+            major,
+            minor,
+            grow: None,
+            fail: false,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.fail {
+            return Err(fmt::Error);
+        }
+        write!(f, "v{}.{}", self.major, self.minor)?;
+        if let Some(grow) = &self.grow {
+            grow.set(grow.get() + 1);
+            for _ in 0..grow.get() {
+                f.write_str("+")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[test]
+fn collect_str() {
+    let version = Version::new(1, 12);
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&version, &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(writer.written_buffer(), b"\x05v1.12");
+    assert_eq!(serialize_size(&version, DefaultOptions::new()).unwrap(), 6);
+    let decoded: &str = deserialize(writer.written_buffer(), DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, "v1.12");
+
+    // Does not fit in the buffer
+    assert!(matches!(
+        serialize(
+            &version,
+            BufferWriter::new(&mut buffer[..4]),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall))
+    ));
+}
+
+#[test]
+fn collect_str_errors() {
+    let mut buffer = [0u8; 16];
+
+    let failing = Version {
+        fail: true,
+        ..Version::new(1, 0)
+    };
+    assert!(matches!(
+        serialize(
+            &failing,
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Custom)
+    ));
+    assert!(matches!(
+        serialize_size(&failing, DefaultOptions::new()),
+        Err(SerializeError::Custom)
+    ));
+
+    // The text changes between measuring and writing it
+    let growing = Version {
+        grow: Some(Cell::new(0)),
+        ..Version::new(1, 0)
+    };
+    assert!(matches!(
+        serialize(
+            &growing,
+            BufferWriter::new(&mut buffer),
+            DefaultOptions::new()
+        ),
+        Err(SerializeError::Custom)
+    ));
+}
+
+#[test]
+fn full_buffer_writer_returns_an_error() {
+    let mut buffer = [0u8; 2];
+    let mut writer = BufferWriter::new(&mut buffer);
+    writer.write(1).unwrap();
+    writer.write_all(&[2]).unwrap();
+    assert_eq!(writer.write(3), Err(BufferWriterError::BufferTooSmall));
+    assert_eq!(
+        writer.write_all(&[3]),
+        Err(BufferWriterError::BufferTooSmall)
+    );
+    assert_eq!(writer.written_len(), 2);
+
+    let mut by_ref = &mut writer;
+    assert_eq!(
+        CoreWrite::write(&mut by_ref, 3),
+        Err(BufferWriterError::BufferTooSmall)
+    );
+}
@@ -0,0 +1,120 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, serialize_all, serialize_size, BufferWriter,
+    DefaultOptions, SerializeError, Serializer,
+};
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct Flags {
+    armed: bool,
+    gps_fix: bool,
+    low_battery: bool,
+    channel: u8,
+    tx_enabled: bool,
+    rx_enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Status {
+    flags: Flags,
+    heater: bool,
+    alarms: [bool; 10],
+}
+
+fn packed() -> impl Options + Copy {
+    DefaultOptions::new().with_packed_bools()
+}
+
+fn round_trip<T>(value: &T) -> Vec<u8>
+where
+    T: Serialize + for<'a> serde::Deserialize<'a> + PartialEq + core::fmt::Debug,
+{
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, packed()).unwrap();
+    let written = writer.written_buffer().to_vec();
+    assert_eq!(serialize_size(value, packed()).unwrap(), written.len());
+
+    let decoded: T = deserialize(&written[..], packed()).unwrap();
+    assert_eq!(&decoded, value);
+    written
+}
+
+#[test]
+fn consecutive_bools_share_a_byte() {
+    let flags = Flags {
+        armed: true,
+        gps_fix: false,
+        low_battery: true,
+        channel: 7,
+        tx_enabled: false,
+        rx_enabled: true,
+    };
+    assert_eq!(round_trip(&flags), [0b101, 7, 0b10]);
+    assert_eq!(serialize_size(&flags, DefaultOptions::new()).unwrap(), 6);
+}
+
+#[test]
+fn bools_are_packed_across_structs_and_arrays() {
+    let mut alarms = [false; 10];
+    alarms[0] = true;
+    alarms[9] = true;
+    let status = Status {
+        flags: Flags {
+            armed: true,
+            gps_fix: true,
+            low_battery: false,
+            channel: 0xFF,
+            tx_enabled: true,
+            rx_enabled: false,
+        },
+        heater: true,
+        alarms,
+    };
+    // tx_enabled, rx_enabled, heater and the first 5 alarms, then the last 5 alarms
+    assert_eq!(round_trip(&status), [0b011, 0xFF, 0b0000_1101, 0b1_0000]);
+}
+
+#[test]
+fn full_bytes_are_written_right_away() {
+    let mut buffer = [0u8; 8];
+    let mut serializer = Serializer::new(BufferWriter::new(&mut buffer), packed());
+    serializer.serialize_value(&[true; 8]).unwrap();
+    assert_eq!(serializer.writer().written_buffer(), &[0xFF]);
+    serializer.serialize_value(&true).unwrap();
+    assert_eq!(serializer.writer().written_len(), 1);
+
+    let writer = serializer.finish().unwrap();
+    assert_eq!(writer.written_buffer(), &[0xFF, 1]);
+}
+
+#[test]
+fn serialize_all_starts_every_item_on_a_new_byte() {
+    let items = [[true, false, true], [false, false, true]];
+    let mut buffer = [0u8; 8];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_all(items, &mut writer, packed()).unwrap();
+    assert_eq!(writer.written_buffer(), &[0b101, 0b100]);
+
+    let values = deserialize_iter::<[bool; 3], _, _>(&[0b101, 0b100][..], packed())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(values, items);
+}
+
+#[test]
+fn limit_counts_the_packed_byte() {
+    let options = packed().with_limit(2);
+    let value = ([true; 9], ());
+    assert_eq!(serialize_size(&value, options).unwrap(), 2);
+
+    let mut buffer = [0u8; 8];
+    match serialize(&[true; 17], BufferWriter::new(&mut buffer), options) {
+        Err(SerializeError::LimitReached) => {}
+        other => panic!("expected LimitReached, got {:?}", other),
+    }
+}
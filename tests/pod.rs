@@ -0,0 +1,121 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_pod_ref, serialize, serialize_pod, BufferWriter, DefaultOptions,
+    DeserializeError, Pod, SerializeError,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[repr(C)]
+struct Sample {
+    timestamp: u32,
+    gain: f32,
+    channels: [i16; 4],
+}
+
+// SAFETY: `Sample` is `repr(C)`, its fields are `Pod`, and it has no padding
+unsafe impl Pod for Sample {}
+
+/// A buffer that is aligned for `Sample`.
+#[repr(C, align(4))]
+struct Aligned([u8; 20]);
+
+const SAMPLE: Sample = Sample {
+    timestamp: 0x0102_0304,
+    gain: -1.5,
+    channels: [1, -2, 300, i16::MIN],
+};
+
+fn write<O: Options + Copy>(options: O) -> (Vec<u8>, Vec<u8>) {
+    let mut pod = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut pod);
+    serialize_pod(&SAMPLE, &mut writer, options).unwrap();
+    let pod = writer.written_buffer().to_vec();
+    let mut serde = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut serde);
+    serialize(&SAMPLE, &mut writer, options).unwrap();
+    (pod, writer.written_buffer().to_vec())
+}
+
+#[test]
+fn same_output_as_serialize() {
+    let fixint = DefaultOptions::new().with_fixint_encoding();
+    let (pod, serde) = write(fixint.with_little_endian());
+    assert_eq!(pod, serde);
+    assert_eq!(pod.len(), 16);
+    let (pod, serde) = write(fixint.with_big_endian());
+    assert_eq!(pod, serde);
+    assert_eq!(pod[..4], [1, 2, 3, 4]);
+
+    // Varint falls back to serde
+    let (pod, serde) = write(DefaultOptions::new());
+    assert_eq!(pod, serde);
+    assert_ne!(pod.len(), 16);
+}
+
+#[test]
+fn borrowed_when_aligned() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_native_endian();
+    let mut buffer = Aligned([0u8; 20]);
+    serialize_pod(&SAMPLE, BufferWriter::new(&mut buffer.0), options).unwrap();
+
+    let value = deserialize_pod_ref::<Sample, _>(&buffer.0[..16], options).unwrap();
+    assert!(value.is_borrowed());
+    assert_eq!(*value, SAMPLE);
+
+    // Moved by one byte, so not aligned for `u32`
+    buffer.0.copy_within(..16, 1);
+    let value = deserialize_pod_ref::<Sample, _>(&buffer.0[1..17], options).unwrap();
+    assert!(!value.is_borrowed());
+    assert_eq!(value.get(), SAMPLE);
+}
+
+fn read_back<O: Options + Copy>(options: O, native: bool) {
+    let mut buffer = Aligned([0u8; 20]);
+    serialize_pod(&SAMPLE, BufferWriter::new(&mut buffer.0), options).unwrap();
+    let value = deserialize_pod_ref::<Sample, _>(&buffer.0[..16], options).unwrap();
+    assert_eq!(value.is_borrowed(), native);
+    assert_eq!(*value, SAMPLE);
+
+    let value: Sample = deserialize(&buffer.0[..16], options).unwrap();
+    assert_eq!(value, SAMPLE);
+}
+
+#[test]
+fn foreign_byte_order_uses_serde() {
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let little = cfg!(target_endian = "little");
+    read_back(options.with_little_endian(), little);
+    read_back(options.with_big_endian(), !little);
+}
+
+#[test]
+fn limits_and_input_length() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_native_endian();
+    let mut buffer = [0u8; 20];
+    let result = serialize_pod(
+        &SAMPLE,
+        BufferWriter::new(&mut buffer),
+        options.with_limit(15),
+    );
+    assert!(matches!(result, Err(SerializeError::LimitReached)));
+    serialize_pod(&SAMPLE, BufferWriter::new(&mut buffer), options).unwrap();
+
+    let result = deserialize_pod_ref::<Sample, _>(&buffer[..15], options);
+    assert!(matches!(
+        result,
+        Err(DeserializeError::UnexpectedEof { needed: 1 })
+    ));
+    let result = deserialize_pod_ref::<Sample, _>(&buffer[..16], options.with_limit(15));
+    assert!(matches!(result, Err(DeserializeError::LimitError(_))));
+    let result = deserialize_pod_ref::<Sample, _>(&buffer[..17], options);
+    assert!(matches!(result, Err(DeserializeError::TrailingBytes)));
+    let result = deserialize_pod_ref::<Sample, _>(&buffer[..17], options.allow_trailing_bytes());
+    assert_eq!(result.unwrap().get(), SAMPLE);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "zerocopy")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize_zerocopy_ref, serialize, serialize_zerocopy, BufferWriter, DefaultOptions,
+};
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    FromBytes,
+    IntoBytes,
+    KnownLayout,
+    Immutable,
+    Clone,
+    Copy,
+    PartialEq,
+    Debug,
+)]
+#[repr(C)]
+struct Register {
+    address: u32,
+    value: u16,
+    mask: u16,
+}
+
+/// A buffer that is aligned for `Register`.
+#[repr(C, align(4))]
+struct Aligned([u8; 12]);
+
+const REGISTER: Register = Register {
+    address: 0x4000_1000,
+    value: 0x00ff,
+    mask: 0xf0f0,
+};
+
+fn check<O: Options + Copy>(options: O, native: bool) {
+    let mut buffer = Aligned([0u8; 12]);
+    let mut writer = BufferWriter::new(&mut buffer.0);
+    serialize_zerocopy(&REGISTER, &mut writer, options).unwrap();
+    let len = writer.written_len();
+
+    let mut expected = [0u8; 12];
+    let mut writer = BufferWriter::new(&mut expected);
+    serialize(&REGISTER, &mut writer, options).unwrap();
+    assert_eq!(&buffer.0[..len], writer.written_buffer());
+
+    let value = deserialize_zerocopy_ref::<Register, _>(&buffer.0[..len], options).unwrap();
+    assert_eq!(value.is_borrowed(), native);
+    assert_eq!(*value, REGISTER);
+}
+
+#[test]
+fn same_output_as_serialize() {
+    let fixint = DefaultOptions::new().with_fixint_encoding();
+    check(fixint.with_native_endian(), true);
+    let little = cfg!(target_endian = "little");
+    check(fixint.with_little_endian(), little);
+    check(fixint.with_big_endian(), !little);
+    check(DefaultOptions::new(), false);
+}
+
+#[test]
+fn unaligned_input_is_copied() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_native_endian();
+    let mut buffer = Aligned([0u8; 12]);
+    serialize_zerocopy(&REGISTER, BufferWriter::new(&mut buffer.0[1..]), options).unwrap();
+    let value = deserialize_zerocopy_ref::<Register, _>(&buffer.0[1..9], options).unwrap();
+    assert!(!value.is_borrowed());
+    assert_eq!(value.get(), REGISTER);
+}
@@ -0,0 +1,114 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, serialize_size, BufferWriter, DefaultOptions};
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Reading {
+    id: u16,
+    offset: i32,
+    values: [u64; 2],
+    label: &'static str,
+    flag: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Command {
+    Ping,
+    Set(u32),
+    Move { x: i16, y: i16 },
+}
+
+fn options() -> impl Options {
+    DefaultOptions::new().with_postcard_encoding()
+}
+
+/// Checks that `value` serializes to the bytes postcard produces for it, and that it round-trips.
+fn check<'a, T: Serialize + Deserialize<'a> + PartialEq + Debug>(value: T, expected: &'a [u8]) {
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options()).unwrap();
+    assert_eq!(expected, writer.written_buffer());
+    assert_eq!(expected.len(), serialize_size(&value, options()).unwrap());
+
+    let deserialized: T = deserialize(expected, options()).unwrap();
+    assert_eq!(value, deserialized);
+}
+
+#[test]
+fn postcard_unsigned() {
+    check(0u16, &[0x00]);
+    check(127u16, &[0x7F]);
+    check(128u16, &[0x80, 0x01]);
+    check(300u16, &[0xAC, 0x02]);
+    check(u16::MAX, &[0xFF, 0xFF, 0x03]);
+    check(u32::MAX, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    check(
+        u64::MAX,
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+    );
+    check(
+        u128::MAX,
+        &[
+            0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            0xFF, 0xFF, 0xFF, 0xFF, 0x03,
+        ],
+    );
+}
+
+#[test]
+fn postcard_signed() {
+    check(0i16, &[0x00]);
+    check(-1i16, &[0x01]);
+    check(1i16, &[0x02]);
+    check(-64i32, &[0x7F]);
+    check(64i32, &[0x80, 0x01]);
+    check(i32::MIN, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+    check(
+        i64::MAX,
+        &[0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+    );
+    check(-1i128, &[0x01]);
+}
+
+#[test]
+fn postcard_struct() {
+    check(
+        Reading {
+            id: 300,
+            offset: -2,
+            values: [1, 128],
+            label: "hi",
+            flag: Some(true),
+        },
+        &[
+            0xAC, 0x02, 0x03, 0x01, 0x80, 0x01, 0x02, b'h', b'i', 0x01, 0x01,
+        ],
+    );
+}
+
+#[test]
+fn postcard_enum() {
+    check(Command::Ping, &[0x00]);
+    check(Command::Set(200), &[0x01, 0xC8, 0x01]);
+    check(Command::Move { x: -1, y: 1 }, &[0x02, 0x01, 0x02]);
+}
+
+#[test]
+fn postcard_rejects_invalid_varints() {
+    // Too many continuation bytes for a u32
+    assert!(
+        deserialize::<u32, _, _>(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01][..], options()).is_err()
+    );
+    // Fits in the maximum length of a u64, but overflows it
+    assert!(deserialize::<u64, _, _>(
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02][..],
+        options()
+    )
+    .is_err());
+    // Valid u64, but does not fit in a u16
+    assert!(deserialize::<u16, _, _>(&[0x80, 0x80, 0x04][..], options()).is_err());
+}
@@ -0,0 +1,181 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, SerializeError,
+    Serializer,
+};
+use serde::Serialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct Telemetry {
+    timestamp: u32,
+    battery: Option<u8>,
+    temperature: Option<i16>,
+    humidity: Option<u8>,
+    pressure: Option<u32>,
+    altitude: Option<i32>,
+    latitude: Option<f32>,
+    longitude: Option<f32>,
+    speed: Option<u16>,
+    heading: Option<u16>,
+    rssi: Option<i8>,
+    snr: Option<i8>,
+    error_code: Option<u16>,
+    uptime: Option<u32>,
+    firmware: Option<u16>,
+    counter_a: Option<u32>,
+    counter_b: Option<u32>,
+    counter_c: Option<u32>,
+    flags: Option<u8>,
+    mode: Option<u8>,
+    label: Option<Label>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Label {
+    id: u8,
+    extra: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Mixed {
+    values: Vec<Option<u8>>,
+    nested: Option<Option<u8>>,
+    kind: Kind,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Kind {
+    Empty,
+    Named { a: Option<u8>, b: Option<u8> },
+}
+
+fn sparse() -> Telemetry {
+    Telemetry {
+        timestamp: 1000,
+        temperature: Some(-40),
+        rssi: Some(-90),
+        label: Some(Label { id: 7, extra: None }),
+        ..Telemetry::default()
+    }
+}
+
+fn round_trip<T>(value: &T) -> Vec<u8>
+where
+    T: Serialize + for<'a> serde::Deserialize<'a> + PartialEq + core::fmt::Debug,
+{
+    let options = || DefaultOptions::new().with_presence_bitmap();
+    let mut buffer = [0u8; 256];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, options()).unwrap();
+    let written = writer.written_buffer().to_vec();
+    assert_eq!(serialize_size(value, options()).unwrap(), written.len());
+
+    let decoded: T = deserialize(&written[..], options()).unwrap();
+    assert_eq!(&decoded, value);
+    written
+}
+
+#[test]
+fn sparse_struct_is_smaller() {
+    let value = sparse();
+    let tagged = serialize_size(&value, DefaultOptions::new()).unwrap();
+    let written = round_trip(&value);
+    assert_eq!(tagged, 27);
+    assert_eq!(written.len(), 10);
+}
+
+#[test]
+fn bitmap_layout() {
+    let value = sparse();
+    let written = round_trip(&value);
+    // 21 fields take 3 bytes, temperature is field 2, rssi is field 10 and label is field 20
+    assert_eq!(&written[..3], &[0b0000_0100, 0b0000_0100, 0b0001_0000]);
+    // timestamp as a varint, -40 and -90 zigzag encoded, and the label with an empty bitmap
+    assert_eq!(&written[3..], &[251, 0xE8, 0x03, 79, 0xA6, 0, 7]);
+}
+
+#[test]
+fn options_outside_of_structs_keep_their_tag() {
+    let value = Mixed {
+        values: vec![None, Some(3), None],
+        nested: Some(None),
+        kind: Kind::Named {
+            a: None,
+            b: Some(2),
+        },
+    };
+    let written = round_trip(&value);
+    assert_eq!(
+        written,
+        [
+            // bitmap, only `nested` is an `Option`
+            0b0000_0010,
+            // values
+            3,
+            0,
+            1,
+            3,
+            0,
+            // the inner option of nested
+            0,
+            // kind
+            1,
+            0,
+            1,
+            2,
+        ]
+    );
+}
+
+#[test]
+fn all_fields_present() {
+    let value = Telemetry {
+        battery: Some(80),
+        temperature: Some(21),
+        humidity: Some(40),
+        pressure: Some(101_325),
+        altitude: Some(-12),
+        latitude: Some(52.0),
+        longitude: Some(4.5),
+        speed: Some(3),
+        heading: Some(270),
+        rssi: Some(-60),
+        snr: Some(9),
+        error_code: Some(0),
+        uptime: Some(86_400),
+        firmware: Some(0x0102),
+        counter_a: Some(1),
+        counter_b: Some(2),
+        counter_c: Some(3),
+        flags: Some(0xFF),
+        mode: Some(1),
+        label: Some(Label {
+            id: 1,
+            extra: Some(2),
+        }),
+        ..Telemetry::default()
+    };
+    let tagged = serialize_size(&value, DefaultOptions::new()).unwrap();
+    let written = round_trip(&value);
+    // 20 tag bytes and the tag of `extra` are replaced by 3 + 1 bitmap bytes
+    assert_eq!(written.len(), tagged - 21 + 4);
+}
+
+#[test]
+fn direct_serialize_needs_serialize_value() {
+    let mut buffer = [0u8; 64];
+    let options = DefaultOptions::new().with_presence_bitmap();
+    let mut serializer = Serializer::new(BufferWriter::new(&mut buffer), options);
+    match sparse().serialize(&mut serializer) {
+        Err(SerializeError::PresenceUnknown) => {}
+        other => panic!("expected PresenceUnknown, got {:?}", other),
+    }
+
+    serializer.serialize_value(&sparse()).unwrap();
+    let written = serializer.into_writer().written_buffer().to_vec();
+    let decoded: Telemetry = deserialize(&written[..], options).unwrap();
+    assert_eq!(decoded, sparse());
+}
@@ -0,0 +1,101 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::{self, Options};
+use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub enum Kind {
+    Empty,
+    Pair(u8, i16),
+    Named { value: u64 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Everything<'a> {
+    a: u8,
+    b: u16,
+    c: u32,
+    d: u64,
+    e: i32,
+    f: i64,
+    g: f32,
+    h: f64,
+    i: bool,
+    j: char,
+    name: &'a str,
+    maybe: Option<u16>,
+    nothing: Option<u16>,
+    kinds: (Kind, Kind, Kind),
+    bytes: [u8; 3],
+    large: u128,
+}
+
+fn everything() -> Everything<'static> {
+    Everything {
+        a: 200,
+        b: 60_000,
+        c: 70_000,
+        d: u64::MAX - 3,
+        e: -1_000_000,
+        f: i64::MIN + 5,
+        g: 1.5,
+        h: -0.25,
+        i: true,
+        j: 'ß',
+        name: "sensor",
+        maybe: Some(300),
+        nothing: None,
+        kinds: (
+            Kind::Empty,
+            Kind::Pair(7, -300),
+            Kind::Named { value: 1 << 40 },
+        ),
+        bytes: [1, 2, 3],
+        large: u128::MAX / 3,
+    }
+}
+
+fn check<O: Options, C: bincode::config::Config>(options: impl Fn() -> O, upstream: C) {
+    let value = everything();
+    let expected = bincode::serde::encode_to_vec(&value, upstream).unwrap();
+
+    let mut buffer = [0u8; 256];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&value, &mut writer, options()).unwrap();
+    assert_eq!(writer.written_buffer(), &expected[..]);
+
+    let decoded: Everything = deserialize(&expected[..], options()).unwrap();
+    assert_eq!(decoded, value);
+}
+
+#[test]
+fn legacy_matches_bincode() {
+    check(config::legacy, bincode::config::legacy());
+}
+
+#[test]
+fn standard_matches_bincode() {
+    check(config::standard, bincode::config::standard());
+}
+
+#[test]
+fn network_endian_matches_bincode() {
+    check(
+        || config::legacy().with_network_endian(),
+        bincode::config::legacy().with_big_endian(),
+    );
+    check(
+        || DefaultOptions::new().with_network_endian(),
+        bincode::config::standard().with_big_endian(),
+    );
+}
+
+#[test]
+fn presets_allow_trailing_bytes() {
+    let buffer = [1u8, 0, 0, 0, 0xFF];
+    let value: u32 = deserialize(&buffer[..], config::legacy()).unwrap();
+    assert_eq!(value, 1);
+    let value: u8 = deserialize(&buffer[..], config::standard()).unwrap();
+    assert_eq!(value, 1);
+}
@@ -0,0 +1,135 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+    PrimitiveSlice,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Frame<'a> {
+    channel: u8,
+    #[serde(borrow)]
+    samples: PrimitiveSlice<'a, u16>,
+    end: bool,
+}
+
+#[derive(Serialize)]
+struct PlainFrame<'a> {
+    channel: u8,
+    samples: &'a [u16],
+    end: bool,
+}
+
+/// More samples than fit in one chunk.
+fn samples() -> Vec<u16> {
+    (0..100).map(|i| i * 661).collect()
+}
+
+fn to_vec<T: serde::Serialize, O: Options + Copy>(value: &T, options: O) -> Vec<u8> {
+    let mut buffer = vec![0u8; 1024];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, options).unwrap();
+    let len = writer.written_len();
+    assert_eq!(serialize_size(value, options).unwrap(), len);
+    buffer.truncate(len);
+    buffer
+}
+
+fn check_same_as_slice<O: Options + Copy>(options: O) {
+    let samples = samples();
+    let frame = Frame {
+        channel: 3,
+        samples: PrimitiveSlice::new(&samples),
+        end: true,
+    };
+    let plain = PlainFrame {
+        channel: 3,
+        samples: &samples,
+        end: true,
+    };
+    assert_eq!(to_vec(&frame, options), to_vec(&plain, options));
+}
+
+#[test]
+fn same_encoding_as_slice() {
+    check_same_as_slice(DefaultOptions::new());
+    check_same_as_slice(DefaultOptions::new().with_fixint_encoding());
+    check_same_as_slice(
+        DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_big_endian(),
+    );
+    check_same_as_slice(DefaultOptions::new().with_big_endian());
+}
+
+#[test]
+fn round_trip() {
+    let samples = samples();
+    let frame = Frame {
+        channel: 3,
+        samples: PrimitiveSlice::new(&samples),
+        end: true,
+    };
+    let little = DefaultOptions::new().with_fixint_encoding();
+    let big = little.with_big_endian();
+
+    let bytes = to_vec(&frame, little);
+    let decoded: Frame = deserialize(&bytes[..], little).unwrap();
+    assert_eq!(decoded, frame);
+    assert_eq!(decoded.samples.iter().collect::<Vec<_>>(), samples);
+
+    let bytes = to_vec(&frame, big);
+    let decoded: Frame = deserialize(&bytes[..], big).unwrap();
+    assert_eq!(decoded, frame);
+    let mut copy = vec![0u16; samples.len()];
+    decoded.samples.copy_to_slice(&mut copy);
+    assert_eq!(copy, samples);
+
+    // A deserialized slice is written the same way again
+    assert_eq!(to_vec(&decoded, big), bytes);
+    assert_eq!(to_vec(&decoded, little), to_vec(&frame, little));
+}
+
+#[test]
+fn wide_and_float_elements() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_big_endian();
+
+    let values = [1.5f32, -0.25, f32::MAX];
+    let bytes = to_vec(&PrimitiveSlice::new(&values), options);
+    assert_eq!(bytes, to_vec(&&values[..], options));
+    let decoded: PrimitiveSlice<f32> = deserialize(&bytes[..], options).unwrap();
+    assert_eq!(decoded.get(2), Some(f32::MAX));
+
+    let values = [u128::MAX, 7];
+    let bytes = to_vec(&PrimitiveSlice::new(&values), options);
+    assert_eq!(bytes, to_vec(&&values[..], options));
+    let decoded: PrimitiveSlice<u128> = deserialize(&bytes[..], options).unwrap();
+    assert_eq!(decoded.get(0), Some(u128::MAX));
+    assert_eq!(decoded.get(2), None);
+
+    let values: [i32; 0] = [];
+    let bytes = to_vec(&PrimitiveSlice::new(&values), options);
+    let decoded: PrimitiveSlice<i32> = deserialize(&bytes[..], options).unwrap();
+    assert!(decoded.is_empty());
+}
+
+#[test]
+fn varint_can_not_be_borrowed() {
+    let samples = [1u16, 1000];
+    let bytes = to_vec(&PrimitiveSlice::new(&samples), DefaultOptions::new());
+    let result = deserialize::<PrimitiveSlice<u16>, _, _>(&bytes[..], DefaultOptions::new());
+    assert!(matches!(result, Err(DeserializeError::Unsupported(_))));
+}
+
+#[test]
+fn truncated_input() {
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let samples = [1u32, 2, 3];
+    let bytes = to_vec(&PrimitiveSlice::new(&samples), options);
+    let result = deserialize::<PrimitiveSlice<u32>, _, _>(&bytes[..bytes.len() - 1], options);
+    assert!(result.is_err());
+}
@@ -0,0 +1,184 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, BufferWriter, DefaultOptions, DeserializeError, PushDeserializer, PushError,
+    SliceReadError,
+};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::task::Poll;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Frame {
+    id: u16,
+    payload: [u8; 6],
+    checksum: u32,
+}
+
+fn frames() -> Vec<Frame> {
+    (0..3)
+        .map(|i| Frame {
+            id: 1000 + i,
+            payload: [i as u8; 6],
+            checksum: 0xDEAD_BEEF - i as u32,
+        })
+        .collect()
+}
+
+fn encode(frames: &[Frame]) -> Vec<u8> {
+    let mut buffer = [0u8; 128];
+    let mut writer = BufferWriter::new(&mut buffer);
+    for frame in frames {
+        serialize(frame, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn byte_by_byte() {
+    let bytes = encode(&frames());
+    let mut scratch = [0u8; 16];
+    let mut deserializer = PushDeserializer::<Frame, _>::new(&mut scratch, DefaultOptions::new());
+
+    let mut decoded = Vec::new();
+    for byte in bytes {
+        match ready(deserializer.push(&[byte])) {
+            Some(frame) => decoded.push(frame),
+            None => assert!(deserializer.buffered_len() > 0),
+        }
+    }
+    assert_eq!(decoded, frames());
+    assert_eq!(deserializer.buffered_len(), 0);
+}
+
+#[test]
+fn several_values_in_one_push() {
+    let bytes = encode(&frames());
+    let mut scratch = [0u8; 64];
+    let mut deserializer = PushDeserializer::<Frame, _>::new(&mut scratch, DefaultOptions::new());
+
+    // The first two frames and a part of the third
+    let split = bytes.len() - 4;
+    assert_eq!(
+        ready(deserializer.push(&bytes[..split])),
+        Some(frames()[0].clone())
+    );
+    assert_eq!(ready(deserializer.poll()), Some(frames()[1].clone()));
+    assert_eq!(ready(deserializer.poll()), None);
+    assert_eq!(
+        ready(deserializer.push(&bytes[split..])),
+        Some(frames()[2].clone())
+    );
+    assert_eq!(ready(deserializer.poll()), None);
+}
+
+fn ready(poll: Poll<Result<Frame, PushError>>) -> Option<Frame> {
+    match poll {
+        Poll::Ready(frame) => Some(frame.unwrap()),
+        Poll::Pending => None,
+    }
+}
+
+#[test]
+fn value_larger_than_scratch() {
+    let bytes = encode(&frames());
+    let mut scratch = [0u8; 8];
+    let mut deserializer = PushDeserializer::<Frame, _>::new(&mut scratch, DefaultOptions::new());
+
+    let result = bytes[..8]
+        .iter()
+        .map(|byte| deserializer.push(&[*byte]))
+        .find(Poll::is_ready);
+    match result {
+        Some(Poll::Ready(Err(DeserializeError::ScratchTooSmall))) => {}
+        x => panic!("Expected ScratchTooSmall, got {:?}", x),
+    }
+    assert_eq!(deserializer.buffered_len(), 0);
+
+    match deserializer.push(&bytes[..9]) {
+        Poll::Ready(Err(DeserializeError::ScratchTooSmall)) => {}
+        x => panic!("Expected ScratchTooSmall, got {:?}", x),
+    }
+}
+
+#[test]
+fn invalid_input_is_discarded() {
+    let mut scratch = [0u8; 16];
+    let mut deserializer =
+        PushDeserializer::<(bool, u8), _>::new(&mut scratch, DefaultOptions::new());
+    match deserializer.push(&[2]) {
+        Poll::Ready(Err(DeserializeError::InvalidBoolValue(2))) => {}
+        x => panic!("Expected InvalidBoolValue, got {:?}", x),
+    }
+    assert_eq!(deserializer.buffered_len(), 0);
+
+    assert!(deserializer.push(&[1]).is_pending());
+    deserializer.reset();
+    match deserializer.push(&[0, 5]) {
+        Poll::Ready(Ok(value)) => assert_eq!(value, (false, 5)),
+        x => panic!("Expected a value, got {:?}", x),
+    }
+
+    // Strings are decoded from the scratch buffer
+    let mut scratch = [0u8; 16];
+    let mut deserializer = PushDeserializer::<String, _>::new(&mut scratch, DefaultOptions::new());
+    assert!(deserializer.push(&[2, 0xC3]).is_pending());
+    match deserializer.push(&[0x28]) {
+        // With the alloc feature, the string is validated by the deserializer instead
+        Poll::Ready(Err(DeserializeError::Read(SliceReadError::InvalidUtf8)))
+        | Poll::Ready(Err(DeserializeError::InvalidUtf8Encoding(_))) => {}
+        x => panic!("Expected an UTF8 error, got {:?}", x),
+    }
+}
+
+#[test]
+fn limit_applies_to_every_value() {
+    let options = DefaultOptions::new().with_limit(4);
+    let mut scratch = [0u8; 16];
+    let mut deserializer = PushDeserializer::<(u8, u32), _>::new(&mut scratch, options);
+    match deserializer.push(&[1, 251, 0, 1]) {
+        Poll::Ready(Ok(value)) => assert_eq!(value, (1, 256)),
+        x => panic!("Expected a value, got {:?}", x),
+    }
+    match deserializer.push(&[1, 252, 0, 0]) {
+        Poll::Ready(Err(DeserializeError::LimitError(_))) => {}
+        x => panic!("Expected LimitError, got {:?}", x),
+    }
+    assert!(deserializer.push(&[2, 251]).is_pending());
+    assert!(deserializer.push(&[0]).is_pending());
+    match deserializer.push(&[2]) {
+        Poll::Ready(Ok(value)) => assert_eq!(value, (2, 512)),
+        x => panic!("Expected a value, got {:?}", x),
+    }
+}
+
+static DECODES: AtomicUsize = AtomicUsize::new(0);
+
+/// A `u64` that counts how often it is decoded.
+#[derive(Debug, PartialEq)]
+struct Counted(u64);
+
+impl<'de> serde::Deserialize<'de> for Counted {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DECODES.fetch_add(1, Ordering::Relaxed);
+        u64::deserialize(deserializer).map(Counted)
+    }
+}
+
+#[test]
+fn waits_for_the_needed_bytes() {
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let mut scratch = [0u8; 16];
+    let mut deserializer = PushDeserializer::<Counted, _>::new(&mut scratch, options);
+    let bytes = 0x0102_0304_0506_0708u64.to_le_bytes();
+    for byte in &bytes[..7] {
+        assert!(deserializer.push(&[*byte]).is_pending());
+    }
+    // The first byte showed that 7 more are needed, so only the last byte decodes again
+    match deserializer.push(&bytes[7..]) {
+        Poll::Ready(Ok(value)) => assert_eq!(value, Counted(0x0102_0304_0506_0708)),
+        x => panic!("Expected a value, got {:?}", x),
+    }
+    assert_eq!(DECODES.load(Ordering::Relaxed), 2);
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "rtt")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, serialize, DefaultOptions, RttWriteError, RttWriter, SerializeError,
+};
+use rtt_target::{rtt_init, ChannelMode};
+use std::cell::RefCell;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Telemetry<'a> {
+    uptime: u32,
+    temperature: i16,
+    state: &'a str,
+}
+
+/// A fake up-channel that accepts at most 3 bytes per write, like a channel in `NoBlockTrim` mode
+/// that the host reads in between.
+fn channel<'a>(received: &'a RefCell<Vec<u8>>, capacity: usize) -> impl FnMut(&[u8]) -> usize + 'a {
+    move |bytes| {
+        let mut received = received.borrow_mut();
+        let len = bytes.len().min(3).min(capacity - received.len());
+        received.extend_from_slice(&bytes[..len]);
+        len
+    }
+}
+
+#[test]
+fn streams_values_to_the_host() {
+    let received = RefCell::new(Vec::new());
+    let mut writer = RttWriter::new(channel(&received, 64));
+    let telemetry = Telemetry {
+        uptime: 86_400,
+        temperature: -12,
+        state: "charging",
+    };
+    serialize(&telemetry, &mut writer, DefaultOptions::new()).unwrap();
+
+    drop(writer);
+    let received = received.into_inner();
+    let decoded: Telemetry = deserialize(&received[..], DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, telemetry);
+}
+
+#[test]
+fn full_channel() {
+    let received = RefCell::new(Vec::new());
+    let mut writer = RttWriter::new(channel(&received, 4));
+    assert!(matches!(
+        serialize(&"telemetry", &mut writer, DefaultOptions::new()),
+        Err(SerializeError::Write(RttWriteError::ChannelFull))
+    ));
+    assert_eq!(*received.borrow(), [9, b't', b'e', b'l']);
+}
+
+#[test]
+fn up_channel() {
+    // The control block is a global symbol, so it can only be created once in this test binary
+    let channels = rtt_init! { up: { 0: { size: 16, mode: ChannelMode::NoBlockTrim } } };
+    let mut writer = RttWriter::new(channels.up.0);
+    serialize(&"telemetry", &mut writer, DefaultOptions::new()).unwrap();
+
+    // The host does not read the channel, so the second value does not fit
+    assert!(matches!(
+        serialize(&"telemetry", &mut writer, DefaultOptions::new()),
+        Err(SerializeError::Write(RttWriteError::ChannelFull))
+    ));
+    assert!(!writer.into_inner().is_empty());
+}
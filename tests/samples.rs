@@ -0,0 +1,102 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, serialize_samples, BufferWriter, BufferWriterError, DefaultOptions, SerializeError,
+};
+
+/// An iterator that does not know its length, like samples read from a sensor.
+fn sensor(count: u16) -> impl Iterator<Item = u16> {
+    let mut next = 0;
+    core::iter::from_fn(move || {
+        next += 1;
+        Some(next * 7).filter(|_| next <= count)
+    })
+}
+
+fn as_slice<O: Options + Copy>(count: u16, options: O) -> Vec<u8> {
+    let samples: Vec<u16> = sensor(count).collect();
+    let mut buffer = [0u8; 2048];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&samples[..], &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+fn as_samples<const N: usize, O: Options>(
+    samples: impl Iterator<Item = u16>,
+    options: O,
+) -> Vec<u8> {
+    let mut buffer = [0u8; 2048];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_samples::<N, _, _, _>(samples, &mut writer, options).unwrap();
+    writer.written_buffer().to_vec()
+}
+
+#[test]
+fn samples_within_the_scratch_buffer() {
+    let options = DefaultOptions::new();
+    assert_eq!(as_samples::<8, _>(sensor(0), options), as_slice(0, options));
+    assert_eq!(as_samples::<8, _>(sensor(5), options), as_slice(5, options));
+    assert_eq!(as_samples::<8, _>(sensor(8), options), as_slice(8, options));
+}
+
+#[test]
+fn length_is_patched() {
+    let options = DefaultOptions::new();
+    assert_eq!(as_samples::<4, _>(sensor(9), options), as_slice(9, options));
+
+    // The length needs 3 bytes, instead of the single byte that was reserved
+    assert_eq!(
+        as_samples::<4, _>(sensor(300), options),
+        as_slice(300, options)
+    );
+
+    // The reserved length is larger than the real one
+    let filtered = (1..=300u16).map(|s| s * 7).filter(|s| s % 2 == 0);
+    let expected: Vec<u16> = filtered.clone().collect();
+    let mut buffer = [0u8; 2048];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&expected[..], &mut writer, options).unwrap();
+    assert_eq!(
+        as_samples::<4, _>(filtered, options),
+        writer.written_buffer()
+    );
+}
+
+#[test]
+fn other_encodings() {
+    let options = DefaultOptions::new().with_fixint_encoding();
+    assert_eq!(
+        as_samples::<4, _>(sensor(300), options),
+        as_slice(300, options)
+    );
+
+    let options = DefaultOptions::new().with_type_tags();
+    assert_eq!(
+        as_samples::<4, _>(sensor(300), options),
+        as_slice(300, options)
+    );
+}
+
+#[test]
+fn writer_too_small() {
+    let mut buffer = [0u8; 8];
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize_samples::<2, _, _, _>(sensor(300), &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(BufferWriterError::BufferTooSmall)) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+}
+
+#[test]
+fn limit_includes_the_length() {
+    let options = DefaultOptions::new().with_limit(4);
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_samples::<1, _, _, _>([1u8, 2, 3], &mut writer, options).unwrap();
+    assert_eq!(writer.written_buffer(), &[3, 1, 2, 3]);
+
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize_samples::<1, _, _, _>([1u8, 2, 3, 4], &mut writer, options) {
+        Err(SerializeError::LimitReached) => {}
+        x => panic!("Expected LimitReached, got {:?}", x),
+    }
+}
@@ -0,0 +1,409 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::schema::{
+    deserialize_with_fingerprint, fingerprint, serialize_with_fingerprint, write_schema, Kind,
+};
+use bincode_core::{serialize_size, BufferWriter, DefaultOptions, DeserializeError, Deserializer};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+pub struct Marker;
+
+#[derive(Serialize)]
+pub struct Id(u16);
+
+#[derive(Serialize)]
+pub struct Point(i32, i32);
+
+#[derive(Serialize)]
+pub enum Status {
+    Idle,
+    Error(u8),
+    Moving(Point, u8),
+    Charging { level: u8 },
+}
+
+#[derive(Serialize)]
+pub struct Telemetry<'a> {
+    id: Id,
+    name: &'a str,
+    history: Vec<Status>,
+    limits: BTreeMap<u8, f64>,
+    target: Option<Point>,
+    fault: Option<u32>,
+    marker: Marker,
+    raw: (bool, char, ()),
+}
+
+fn sample() -> Telemetry<'static> {
+    let mut limits = BTreeMap::new();
+    limits.insert(1, 10.0);
+    limits.insert(2, 20.0);
+    Telemetry {
+        id: Id(300),
+        name: "rover",
+        history: vec![
+            Status::Moving(Point(-1, 2), 3),
+            Status::Idle,
+            Status::Error(1),
+        ],
+        limits,
+        target: Some(Point(5, 6)),
+        fault: None,
+        marker: Marker,
+        raw: (true, 'x', ()),
+    }
+}
+
+/// A node of the descriptor, as a host tool would decode it.
+#[derive(Debug, PartialEq)]
+enum Node {
+    Leaf(Kind, u64),
+    Named(Kind, u64, String, Vec<(String, Node)>),
+    Variant(Kind, u64, String, u32, String, Vec<(String, Node)>),
+}
+
+type Reader<'a> = Deserializer<'a, &'a [u8], DefaultOptions>;
+
+fn read<'a, T: Deserialize<'a>>(de: &mut Reader<'a>) -> T {
+    T::deserialize(de).unwrap()
+}
+
+fn children(de: &mut Reader, count: usize, named: bool) -> Vec<(String, Node)> {
+    (0..count)
+        .map(|_| {
+            let name = if named {
+                read::<&str>(de).to_string()
+            } else {
+                String::new()
+            };
+            (name, parse(de))
+        })
+        .collect()
+}
+
+fn parse(de: &mut Reader) -> Node {
+    let kind = Kind::from_u8(read(de)).unwrap();
+    let size: u64 = read(de);
+    match kind {
+        Kind::Some => Node::Named(kind, size, String::new(), children(de, 1, false)),
+        Kind::UnitStruct => Node::Named(kind, size, read::<&str>(de).to_string(), Vec::new()),
+        Kind::NewtypeStruct => {
+            let name = read::<&str>(de).to_string();
+            Node::Named(kind, size, name, children(de, 1, false))
+        }
+        Kind::Seq => {
+            let len: u64 = read(de);
+            let count = len.min(1) as usize;
+            Node::Named(kind, size, len.to_string(), children(de, count, false))
+        }
+        Kind::Map => {
+            let len: u64 = read(de);
+            let count = len.min(1) as usize * 2;
+            Node::Named(kind, size, len.to_string(), children(de, count, false))
+        }
+        Kind::Tuple => {
+            let len: u64 = read(de);
+            Node::Named(kind, size, String::new(), children(de, len as usize, false))
+        }
+        Kind::TupleStruct | Kind::Struct => {
+            let name = read::<&str>(de).to_string();
+            let len: u64 = read(de);
+            let fields = children(de, len as usize, kind == Kind::Struct);
+            Node::Named(kind, size, name, fields)
+        }
+        Kind::UnitVariant | Kind::NewtypeVariant | Kind::TupleVariant | Kind::StructVariant => {
+            let name = read::<&str>(de).to_string();
+            let index: u32 = read(de);
+            let variant = read::<&str>(de).to_string();
+            let fields = match kind {
+                Kind::UnitVariant => Vec::new(),
+                Kind::NewtypeVariant => children(de, 1, false),
+                _ => {
+                    let len: u64 = read(de);
+                    children(de, len as usize, kind == Kind::StructVariant)
+                }
+            };
+            Node::Variant(kind, size, name, index, variant, fields)
+        }
+        _ => Node::Leaf(kind, size),
+    }
+}
+
+fn schema<T: Serialize, O: Options>(value: &T, options: O) -> Node {
+    let mut buffer = [0u8; 512];
+    let mut writer = BufferWriter::new(&mut buffer);
+    write_schema(value, &mut writer, options).unwrap();
+    let mut de = Deserializer::new(writer.written_buffer(), DefaultOptions::new());
+    let node = parse(&mut de);
+    assert!(de.reader().is_empty());
+    node
+}
+
+fn named(kind: Kind, size: u64, name: &str, fields: Vec<(&str, Node)>) -> Node {
+    let fields = fields
+        .into_iter()
+        .map(|(n, f)| (n.to_string(), f))
+        .collect();
+    Node::Named(kind, size, name.to_string(), fields)
+}
+
+fn unnamed(nodes: Vec<Node>) -> Vec<(&'static str, Node)> {
+    nodes.into_iter().map(|n| ("", n)).collect()
+}
+
+#[test]
+fn describes_every_field() {
+    let value = sample();
+    let total = serialize_size(&value, DefaultOptions::new()).unwrap() as u64;
+    let point = |size| {
+        named(
+            Kind::TupleStruct,
+            size,
+            "Point",
+            unnamed(vec![Node::Leaf(Kind::I32, 1), Node::Leaf(Kind::I32, 1)]),
+        )
+    };
+    let expected = named(
+        Kind::Struct,
+        total,
+        "Telemetry",
+        vec![
+            (
+                "id",
+                named(
+                    Kind::NewtypeStruct,
+                    3,
+                    "Id",
+                    unnamed(vec![Node::Leaf(Kind::U16, 3)]),
+                ),
+            ),
+            ("name", Node::Leaf(Kind::Str, 6)),
+            (
+                "history",
+                named(
+                    Kind::Seq,
+                    8,
+                    "3",
+                    unnamed(vec![Node::Variant(
+                        Kind::TupleVariant,
+                        4,
+                        "Status".to_string(),
+                        2,
+                        "Moving".to_string(),
+                        vec![
+                            (String::new(), point(2)),
+                            (String::new(), Node::Leaf(Kind::U8, 1)),
+                        ],
+                    )]),
+                ),
+            ),
+            (
+                "limits",
+                named(
+                    Kind::Map,
+                    19,
+                    "2",
+                    unnamed(vec![Node::Leaf(Kind::U8, 1), Node::Leaf(Kind::F64, 8)]),
+                ),
+            ),
+            ("target", named(Kind::Some, 3, "", unnamed(vec![point(2)]))),
+            ("fault", Node::Leaf(Kind::None, 1)),
+            ("marker", named(Kind::UnitStruct, 0, "Marker", vec![])),
+            (
+                "raw",
+                named(
+                    Kind::Tuple,
+                    2,
+                    "",
+                    unnamed(vec![
+                        Node::Leaf(Kind::Bool, 1),
+                        Node::Leaf(Kind::Char, 1),
+                        Node::Leaf(Kind::Unit, 0),
+                    ]),
+                ),
+            ),
+        ],
+    );
+    assert_eq!(schema(&value, DefaultOptions::new()), expected);
+}
+
+#[test]
+fn sizes_follow_the_options() {
+    let options = || DefaultOptions::new().with_fixint_encoding();
+    let value = Status::Charging { level: 80 };
+    assert_eq!(
+        schema(&value, options()),
+        Node::Variant(
+            Kind::StructVariant,
+            5,
+            "Status".to_string(),
+            3,
+            "Charging".to_string(),
+            vec![("level".to_string(), Node::Leaf(Kind::U8, 1))],
+        )
+    );
+    assert_eq!(
+        schema(&Status::Idle, options().with_u8_discriminants()),
+        Node::Variant(
+            Kind::UnitVariant,
+            1,
+            "Status".to_string(),
+            0,
+            "Idle".to_string(),
+            vec![],
+        )
+    );
+    assert_eq!(
+        schema(&Id(1), options().with_limit(1)),
+        named(
+            Kind::NewtypeStruct,
+            2,
+            "Id",
+            unnamed(vec![Node::Leaf(Kind::U16, 2)])
+        )
+    );
+}
+
+#[test]
+fn empty_sequences_have_no_element_node() {
+    let value: Vec<u32> = Vec::new();
+    assert_eq!(
+        schema(&value, DefaultOptions::new()),
+        named(Kind::Seq, 1, "0", vec![])
+    );
+}
+
+#[test]
+fn kind_tags_round_trip() {
+    for tag in 0..=u8::MAX {
+        match Kind::from_u8(tag) {
+            Some(kind) => assert_eq!(kind as u8, tag),
+            None => assert!(tag >= 30),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Reading {
+    sensor: u8,
+    value: f32,
+}
+
+#[derive(Serialize)]
+pub struct RenamedField {
+    sensor: u8,
+    reading: f32,
+}
+
+#[derive(Serialize)]
+pub struct ReorderedFields {
+    value: f32,
+    sensor: u8,
+}
+
+#[derive(Serialize)]
+pub struct WiderField {
+    sensor: u16,
+    value: f32,
+}
+
+#[test]
+fn fingerprint_is_the_same_for_every_value() {
+    let other = Telemetry {
+        id: Id(1),
+        name: "a much longer name",
+        history: Vec::new(),
+        limits: BTreeMap::new(),
+        target: None,
+        fault: Some(5),
+        marker: Marker,
+        raw: (false, 'y', ()),
+    };
+    assert_eq!(
+        fingerprint(&sample()).unwrap(),
+        fingerprint(&other).unwrap()
+    );
+    assert_eq!(
+        fingerprint(&Status::Idle).unwrap(),
+        fingerprint(&Status::Charging { level: 1 }).unwrap()
+    );
+    // The hash of a single `Kind::U8` tag, fingerprints must not change between releases
+    assert_eq!(fingerprint(&0u8).unwrap(), 0x040c_5b8c);
+}
+
+#[test]
+fn fingerprint_changes_with_the_layout() {
+    let reading = fingerprint(&Reading {
+        sensor: 1,
+        value: 2.0,
+    })
+    .unwrap();
+    let others = [
+        fingerprint(&RenamedField {
+            sensor: 1,
+            reading: 2.0,
+        }),
+        fingerprint(&ReorderedFields {
+            value: 2.0,
+            sensor: 1,
+        }),
+        fingerprint(&WiderField {
+            sensor: 1,
+            value: 2.0,
+        }),
+        fingerprint(&(1u8, 2.0f32)),
+    ];
+    for other in others {
+        assert_ne!(other.unwrap(), reading);
+    }
+}
+
+#[test]
+fn fingerprint_header() {
+    let value = Reading {
+        sensor: 3,
+        value: 1.5,
+    };
+    let expected = fingerprint(&value).unwrap();
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_with_fingerprint(&value, &mut writer, DefaultOptions::new()).unwrap();
+    let bytes = writer.written_buffer();
+    assert_eq!(bytes[..4], expected.to_le_bytes());
+    assert_eq!(
+        bytes.len(),
+        4 + serialize_size(&value, DefaultOptions::new()).unwrap()
+    );
+
+    let decoded: Reading =
+        deserialize_with_fingerprint(expected, bytes, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, value);
+
+    match deserialize_with_fingerprint::<Reading, _, _>(!expected, bytes, DefaultOptions::new()) {
+        Err(DeserializeError::SchemaMismatch {
+            expected: e,
+            found: f,
+        }) if e == !expected && f == expected => {}
+        x => panic!("Expected SchemaMismatch, got {:?}", x),
+    }
+}
+
+#[test]
+fn fingerprint_header_with_type_tags() {
+    let value = Reading {
+        sensor: 3,
+        value: 1.5,
+    };
+    let expected = fingerprint(&value).unwrap();
+    let options = DefaultOptions::new().with_type_tags();
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_with_fingerprint(&value, &mut writer, options).unwrap();
+    let decoded: Reading =
+        deserialize_with_fingerprint(expected, writer.written_buffer(), options).unwrap();
+    assert_eq!(decoded, value);
+}
@@ -0,0 +1,66 @@
+use bincode_core::config::Options;
+use bincode_core::{deserialize_seed, DefaultOptions, DeserializeError};
+use serde::de::{DeserializeSeed, Deserializer, Error, SeqAccess, Visitor};
+use std::fmt;
+
+/// Deserializes a sequence of names into the unused slots of a pool, and returns the slots it used.
+struct Names<'p, 'de> {
+    pool: &'p mut [&'de str],
+}
+
+impl<'p, 'de> DeserializeSeed<'de> for Names<'p, 'de> {
+    type Value = &'p [&'de str];
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'p, 'de> Visitor<'de> for Names<'p, 'de> {
+    type Value = &'p [&'de str];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of names")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut used = 0;
+        while let Some(name) = seq.next_element()? {
+            let slot = self
+                .pool
+                .get_mut(used)
+                .ok_or_else(|| A::Error::custom("the pool is full"))?;
+            *slot = name;
+            used += 1;
+        }
+        Ok(&self.pool[..used])
+    }
+}
+
+#[test]
+fn fills_pool() {
+    let bytes = [2u8, 3, b'f', b'a', b'n', 4, b'p', b'u', b'm', b'p'];
+    let mut pool = [""; 4];
+    let names = deserialize_seed(Names { pool: &mut pool }, &bytes[..], DefaultOptions::new());
+    assert_eq!(names.unwrap(), ["fan", "pump"]);
+    // The names borrow from the input
+    assert_eq!(pool[0].as_ptr(), bytes[2..].as_ptr());
+}
+
+#[test]
+fn errors() {
+    let bytes = [2u8, 1, b'a', 1, b'b'];
+    let mut pool = [""; 1];
+    match deserialize_seed(Names { pool: &mut pool }, &bytes[..], DefaultOptions::new()) {
+        Err(DeserializeError::Custom) => {}
+        x => panic!("Expected Custom, got {:?}", x),
+    }
+
+    let bytes = [1u8, 1, b'a', 0];
+    let mut pool = [""; 2];
+    let options = DefaultOptions::new().reject_trailing_bytes();
+    match deserialize_seed(Names { pool: &mut pool }, &bytes[..], options) {
+        Err(DeserializeError::TrailingBytes) => {}
+        x => panic!("Expected TrailingBytes, got {:?}", x),
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "serde-bytes")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::serde_bytes::{ByteArray, Bytes};
+use bincode_core::{deserialize, serialize, serialize_size, CoreWrite, DefaultOptions};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Frame<'a> {
+    id: u8,
+    #[serde(with = "bincode_core::serde_bytes")]
+    payload: &'a [u8],
+    #[serde(with = "bincode_core::serde_bytes")]
+    checksum: [u8; 4],
+}
+
+/// A writer that records the written bytes, and how often `write` and `write_all` are called.
+#[derive(Default)]
+struct Recorder {
+    data: Vec<u8>,
+    writes: usize,
+    bulk_writes: usize,
+}
+
+impl CoreWrite for &mut Recorder {
+    type Error = ();
+
+    fn write(&mut self, val: u8) -> Result<(), ()> {
+        self.writes += 1;
+        self.data.push(val);
+        Ok(())
+    }
+
+    fn write_all(&mut self, val: &[u8]) -> Result<(), ()> {
+        self.bulk_writes += 1;
+        self.data.extend_from_slice(val);
+        Ok(())
+    }
+}
+
+#[test]
+fn bytes_are_written_at_once() {
+    let payload = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    let mut recorder = Recorder::default();
+    serialize(Bytes::new(&payload), &mut recorder, DefaultOptions::new()).unwrap();
+
+    assert_eq!(recorder.data, [8, 1, 2, 3, 4, 5, 6, 7, 8]);
+    // The length, and the bytes
+    assert_eq!(recorder.writes, 1);
+    assert_eq!(recorder.bulk_writes, 1);
+    assert_eq!(
+        serialize_size(Bytes::new(&payload), DefaultOptions::new()).unwrap(),
+        recorder.data.len()
+    );
+}
+
+#[test]
+fn plain_slices_are_written_per_element() {
+    let payload = [1u8, 2, 3, 4, 5, 6, 7, 8];
+    let mut recorder = Recorder::default();
+    serialize(&payload[..], &mut recorder, DefaultOptions::new()).unwrap();
+
+    // The encoding is the same as with `Bytes`
+    assert_eq!(recorder.data, [8, 1, 2, 3, 4, 5, 6, 7, 8]);
+    assert_eq!(recorder.writes, 9);
+}
+
+#[test]
+fn byte_array_round_trip() {
+    let value = ByteArray::new([0xDE, 0xAD, 0xBE, 0xEF]);
+    let mut recorder = Recorder::default();
+    serialize(&value, &mut recorder, DefaultOptions::new()).unwrap();
+
+    assert_eq!(recorder.data, [4, 0xDE, 0xAD, 0xBE, 0xEF]);
+    assert_eq!(recorder.writes, 1);
+    assert_eq!(recorder.bulk_writes, 1);
+    assert_eq!(
+        serialize_size(&value, DefaultOptions::new()).unwrap(),
+        recorder.data.len()
+    );
+
+    let deserialized: ByteArray<4> =
+        deserialize(&recorder.data[..], DefaultOptions::new()).unwrap();
+    assert_eq!(deserialized, value);
+}
+
+#[test]
+fn annotated_fields_round_trip() {
+    let frame = Frame {
+        id: 7,
+        payload: b"hello",
+        checksum: [1, 2, 3, 4],
+    };
+    let mut recorder = Recorder::default();
+    serialize(&frame, &mut recorder, DefaultOptions::new()).unwrap();
+
+    assert_eq!(
+        recorder.data,
+        [7, 5, b'h', b'e', b'l', b'l', b'o', 4, 1, 2, 3, 4]
+    );
+    assert_eq!(
+        serialize_size(&frame, DefaultOptions::new()).unwrap(),
+        recorder.data.len()
+    );
+
+    let deserialized: Frame = deserialize(&recorder.data[..], DefaultOptions::new()).unwrap();
+    assert_eq!(deserialized, frame);
+}
@@ -0,0 +1,81 @@
+#![cfg(all(feature = "critical-section", feature = "alloc"))]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferWriter, DefaultOptions, SerializeError, SharedWriteError,
+    SharedWriter,
+};
+use std::thread;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Message {
+    priority: u8,
+    sequence: u32,
+    payload: [u16; 8],
+}
+
+static QUEUE: SharedWriter<Vec<u8>> = SharedWriter::new(Vec::new());
+
+#[test]
+fn messages_are_not_interleaved() {
+    let options = DefaultOptions::new().with_fixint_encoding();
+    let tasks: Vec<_> = (0..4u8)
+        .map(|priority| {
+            thread::spawn(move || {
+                for sequence in 0..100 {
+                    let message = Message {
+                        priority,
+                        sequence,
+                        payload: [priority as u16; 8],
+                    };
+                    QUEUE.serialize(&message, options).unwrap();
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.join().unwrap();
+    }
+
+    let bytes = QUEUE.with(core::mem::take).unwrap();
+    assert_eq!(bytes.len(), 4 * 100 * 21);
+    let mut next = [0u32; 4];
+    for chunk in bytes.chunks(21) {
+        let message: Message = deserialize(chunk, options).unwrap();
+        let priority = message.priority as usize;
+        assert_eq!(message.sequence, next[priority]);
+        assert_eq!(message.payload, [message.priority as u16; 8]);
+        next[priority] += 1;
+    }
+    assert_eq!(next, [100; 4]);
+}
+
+#[test]
+fn write_through_reference() {
+    let mut buffer = [0u8; 8];
+    let shared = SharedWriter::new(BufferWriter::new(&mut buffer));
+    serialize(&(1u8, 2u16), &shared, DefaultOptions::new()).unwrap();
+    let result = serialize(&[0u8; 8], &shared, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(SerializeError::Write(SharedWriteError::Write(_)))
+    ));
+    assert_eq!(shared.with(|writer| writer.written_len()), Some(8));
+}
+
+#[test]
+fn busy_when_reentered() {
+    let shared = SharedWriter::new(Vec::new());
+    let inner = shared.with(|_| shared.serialize(&1u8, DefaultOptions::new()));
+    assert!(matches!(
+        inner,
+        Some(Err(SerializeError::Write(SharedWriteError::Busy)))
+    ));
+    assert!(shared.with(|_| shared.with(|_| ())).unwrap().is_none());
+
+    shared.serialize(&1u8, DefaultOptions::new()).unwrap();
+    assert_eq!(shared.into_inner(), [1]);
+}
@@ -4,8 +4,12 @@ extern crate serde_derive;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+use bincode_core::config::Options;
 use bincode_core::BufferWriter;
-use bincode_core::{deserialize, serialize, DefaultOptions};
+use bincode_core::{
+    deserialize, deserialize_counted, deserialize_with_remaining, serialize, DefaultOptions,
+    DeserializeError,
+};
 use std::marker::PhantomData;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
@@ -80,7 +84,7 @@ fn simple_struct() {
     // [u8; 3]      3 (fixed array so no length)
     assert_eq!(1 + 1 + 1 + 1 + 1 + 1 + 1 + 3, writer.written_len());
 
-    let deserialized: TestStruct = deserialize(&buffer[..], options).unwrap();
+    let deserialized: TestStruct = deserialize(&buffer[..10], options).unwrap();
     assert_eq!(s, deserialized);
 }
 
@@ -101,7 +105,7 @@ fn simple_tuple() {
     // &str         1 (len) + 4 (str content)
     assert_eq!(1 + 1 + 1 + 4 + 1 + 4, writer.written_len());
 
-    let deserialized: (u16, u32, &[u8], &str) = deserialize(&buffer[..], options).unwrap();
+    let deserialized: (u16, u32, &[u8], &str) = deserialize(&buffer[..12], options).unwrap();
     assert_eq!(s, deserialized);
 }
 
@@ -118,7 +122,7 @@ macro_rules! simple_test {
 
             assert_eq!($size, writer.written_len());
 
-            let deserialized: $prim = deserialize(&buffer[..], options).unwrap();
+            let deserialized: $prim = deserialize(&buffer[..$size], options).unwrap();
             assert_eq!(s, deserialized);
         }
     };
@@ -174,3 +178,57 @@ simple_test!(test_vec(alloc::vec::Vec<u8>), val: alloc::vec![3u8; 1], size: 2);
 
 #[cfg(feature = "alloc")]
 simple_test!(test_string(alloc::string::String), val: "Test".to_string(), size: 5);
+
+#[test]
+fn deserialize_back_to_back() {
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new();
+    serialize(&SimpleStruct { a: 1 }, &mut writer, options).unwrap();
+    serialize(&ComplexEnum::B(2, -3), &mut writer, options).unwrap();
+    serialize("tail", &mut writer, options).unwrap();
+    let written = writer.written_buffer();
+
+    let (first, rest): (SimpleStruct, _) = deserialize_with_remaining(written, options).unwrap();
+    assert_eq!(first, SimpleStruct { a: 1 });
+    let (second, rest): (ComplexEnum, _) = deserialize_with_remaining(rest, options).unwrap();
+    assert_eq!(second, ComplexEnum::B(2, -3));
+    let (third, rest): (&str, _) = deserialize_with_remaining(rest, options).unwrap();
+    assert_eq!(third, "tail");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn reject_trailing_bytes() {
+    let buffer = [1u8, 2];
+
+    let result: Result<u8, _> = deserialize(&buffer[..], DefaultOptions::new());
+    assert!(matches!(result, Err(DeserializeError::TrailingBytes)));
+
+    let value: u8 = deserialize(&buffer[..], DefaultOptions::new().allow_trailing_bytes()).unwrap();
+    assert_eq!(value, 1);
+}
+
+type Mixed<'a> = (ComplexEnum, f32, f64, char, &'a str, Option<u16>);
+
+#[test]
+fn deserialize_counted_matches_serialized_size() {
+    let s = (
+        ComplexEnum::B(1, -1),
+        1.5f32,
+        2.5f64,
+        'ß',
+        "text",
+        Some(300u16),
+    );
+
+    let mut buffer = [0u8; 100];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&s, &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+
+    let (deserialized, read): (Mixed, u64) =
+        deserialize_counted(&buffer[..], DefaultOptions::new()).unwrap();
+    assert_eq!(s, deserialized);
+    assert_eq!(len as u64, read);
+}
@@ -55,8 +55,11 @@ macro_rules! simple_test {
 
             assert_eq!($size, writer.written_len());
 
-            let deserialized: $prim =
-                deserialize(&buffer[..], DefaultOptions::new().with_fixint_encoding()).unwrap();
+            let deserialized: $prim = deserialize(
+                &buffer[..$size],
+                DefaultOptions::new().with_fixint_encoding(),
+            )
+            .unwrap();
             assert_eq!(s, deserialized);
         }
     };
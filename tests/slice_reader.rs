@@ -0,0 +1,103 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, deserialize_iter, serialize, BufferWriter, CoreRead, DefaultOptions,
+    DeserializeError, Deserializer, SliceReadError, SliceReader,
+};
+use serde::Deserialize;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Header<'a> {
+    kind: u8,
+    name: &'a str,
+}
+
+fn encode() -> ([u8; 32], usize) {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    let header = Header {
+        kind: 2,
+        name: "temp",
+    };
+    serialize(&header, &mut writer, DefaultOptions::new()).unwrap();
+    serialize(&(21u16, 1000u32), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    (buffer, len)
+}
+
+#[test]
+fn position_follows_reads() {
+    let (buffer, len) = encode();
+    let mut deserializer =
+        Deserializer::new(SliceReader::new(&buffer[..len]), DefaultOptions::new());
+    let header = Header::deserialize(&mut deserializer).unwrap();
+    assert_eq!(header.name, "temp");
+    assert_eq!(deserializer.reader().position(), 6);
+    assert_eq!(deserializer.reader().remaining_len(), Some(len - 6));
+
+    let body = <(u16, u32)>::deserialize(&mut deserializer).unwrap();
+    assert_eq!(body, (21, 1000));
+    let reader = deserializer.into_reader();
+    assert_eq!(reader.position(), len);
+    assert!(reader.remaining().is_empty());
+    assert_eq!(reader.into_inner(), &buffer[..len]);
+}
+
+#[test]
+fn resume_at_a_position() {
+    let (buffer, len) = encode();
+    let mut reader = SliceReader::new(&buffer[..len]);
+    reader.set_position(6);
+    assert_eq!(reader.peek(1), Some(&[21][..]));
+    let body: (u16, u32) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(body, (21, 1000));
+
+    // Reading the header again after the body
+    let mut reader = SliceReader::new(&buffer[..len]);
+    reader.set_position(6);
+    let values = deserialize_iter::<(u16, u32), _, _>(reader, DefaultOptions::new());
+    let mut reader = values.into_reader();
+    reader.set_position(0);
+    let header: Header = deserialize(reader, DefaultOptions::new().allow_trailing_bytes()).unwrap();
+    assert_eq!(header.kind, 2);
+}
+
+#[test]
+fn failed_reads_keep_the_position() {
+    let (buffer, len) = encode();
+    let mut reader = SliceReader::new(&buffer[..len]);
+    reader.set_position(len - 2);
+    let mut deserializer = Deserializer::new(reader, DefaultOptions::new());
+    match deserializer.try_deserialize::<[u8; 3]>() {
+        Err(DeserializeError::UnexpectedEof { needed: 1 }) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+    assert_eq!(deserializer.reader().position(), len - 2);
+}
+
+#[test]
+#[should_panic]
+fn position_past_the_end() {
+    let buffer = [0u8; 4];
+    SliceReader::new(&buffer).set_position(5);
+}
+
+#[test]
+fn read_and_read_range() {
+    let buffer = [1u8, 2, 3, 4];
+    let mut slice = &buffer[..];
+    assert_eq!(slice.read().unwrap(), 1);
+    assert_eq!(slice.read_range(2).unwrap(), &buffer[1..3]);
+    assert!(matches!(
+        slice.read_range(2),
+        Err(SliceReadError::EndOfSlice)
+    ));
+
+    let mut reader = SliceReader::new(&buffer);
+    assert_eq!(reader.read_range(3).unwrap(), &buffer[..3]);
+    assert_eq!(reader.read().unwrap(), 4);
+    assert_eq!(reader.position(), 4);
+    assert!(matches!(reader.read(), Err(SliceReadError::EndOfSlice)));
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "smoltcp")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    serialize, serialize_size, serialize_udp, DefaultOptions, SerializeError, TcpSocketError,
+    UdpSendError,
+};
+use smoltcp::socket::{tcp, udp};
+use smoltcp::wire::{IpAddress, IpEndpoint, Ipv4Address};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Reading {
+    sensor: u8,
+    value: i32,
+}
+
+fn udp_socket(payload_capacity: usize) -> udp::Socket<'static> {
+    let rx = udp::PacketBuffer::new(vec![udp::PacketMetadata::EMPTY; 4], vec![0; 64]);
+    let tx = udp::PacketBuffer::new(
+        vec![udp::PacketMetadata::EMPTY; 4],
+        vec![0; payload_capacity],
+    );
+    udp::Socket::new(rx, tx)
+}
+
+fn remote() -> IpEndpoint {
+    IpEndpoint::new(IpAddress::Ipv4(Ipv4Address::new(10, 0, 0, 2)), 4000)
+}
+
+#[test]
+fn udp_datagram_is_enqueued() {
+    let mut socket = udp_socket(64);
+    socket.bind(1234).unwrap();
+
+    let reading = Reading {
+        sensor: 3,
+        value: -20,
+    };
+    serialize_udp(&reading, &mut socket, remote(), DefaultOptions::new()).unwrap();
+    let size = serialize_size(&reading, DefaultOptions::new()).unwrap();
+    assert_eq!(socket.send_queue(), size);
+}
+
+#[test]
+fn udp_unbound_socket_is_unaddressable() {
+    let mut socket = udp_socket(64);
+    let reading = Reading {
+        sensor: 3,
+        value: -20,
+    };
+    let result = serialize_udp(&reading, &mut socket, remote(), DefaultOptions::new());
+    assert!(matches!(result, Err(UdpSendError::Unaddressable)));
+}
+
+#[test]
+fn udp_datagram_too_large() {
+    let mut socket = udp_socket(1);
+    socket.bind(1234).unwrap();
+    let reading = Reading {
+        sensor: 3,
+        value: -20,
+    };
+    let result = serialize_udp(&reading, &mut socket, remote(), DefaultOptions::new());
+    assert!(matches!(result, Err(UdpSendError::BufferFull)));
+}
+
+#[test]
+fn tcp_closed_socket_cannot_send() {
+    let mut socket = tcp::Socket::new(
+        tcp::SocketBuffer::new(vec![0; 64]),
+        tcp::SocketBuffer::new(vec![0; 64]),
+    );
+    let reading = Reading {
+        sensor: 3,
+        value: -20,
+    };
+    let result = serialize(&reading, &mut socket, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(SerializeError::Write(TcpSocketError::InvalidState))
+    ));
+}
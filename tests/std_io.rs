@@ -0,0 +1,105 @@
+#![cfg(feature = "std")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, serialize, BufferedReader, DefaultOptions, DeserializeError, Deserializer,
+    IoReadError, IoReader, IoWriter,
+};
+use serde::Deserialize;
+use std::io::{BufReader, Cursor};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Reading {
+    sensor: u8,
+    value: i32,
+    unit: char,
+}
+
+#[test]
+fn roundtrip_through_std_io() {
+    let readings = [
+        Reading {
+            sensor: 1,
+            value: -40,
+            unit: 'C',
+        },
+        Reading {
+            sensor: 2,
+            value: 70_000,
+            unit: 'µ',
+        },
+    ];
+    let mut writer = IoWriter::new(Vec::new());
+    for reading in &readings {
+        serialize(reading, &mut writer, DefaultOptions::new()).unwrap();
+    }
+    let bytes = writer.into_inner();
+
+    // A small capacity makes values span multiple refills of the buffer
+    let reader = BufReader::with_capacity(2, Cursor::new(bytes));
+    let mut deserializer = Deserializer::new(IoReader::new(reader), DefaultOptions::new());
+    for reading in &readings {
+        assert_eq!(&Reading::deserialize(&mut deserializer).unwrap(), reading);
+    }
+    let mut reader = deserializer.into_reader();
+    assert!(matches!(
+        u8::deserialize(&mut Deserializer::new(&mut reader, DefaultOptions::new())),
+        Err(DeserializeError::Read(IoReadError::Io(e))) if e.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+}
+
+#[test]
+fn borrowed_values_need_a_buffered_reader() {
+    let mut bytes = Vec::new();
+    serialize(
+        &("sensor", &[1u8, 2, 3][..]),
+        IoWriter::new(&mut bytes),
+        DefaultOptions::new(),
+    )
+    .unwrap();
+
+    let reader = IoReader::new(BufReader::new(&bytes[..]));
+    assert!(matches!(
+        deserialize::<(&str, &[u8]), _, _>(reader, DefaultOptions::new()),
+        Err(DeserializeError::Read(IoReadError::Custom))
+    ));
+
+    let mut scratch = [0u8; 16];
+    let reader = BufferedReader::new(IoReader::new(BufReader::new(&bytes[..])), &mut scratch);
+    let value: (&str, &[u8]) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(value, ("sensor", &[1, 2, 3][..]));
+}
+
+#[test]
+fn invalid_utf8() {
+    let bytes = [2u8, 0xC3, 0x28];
+    let reader = IoReader::new(BufReader::new(&bytes[..]));
+    assert!(matches!(
+        deserialize::<&str, _, _>(reader, DefaultOptions::new()),
+        Err(DeserializeError::Read(IoReadError::InvalidUtf8))
+    ));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn owned_strings_across_buffer_refills() {
+    let value = (String::from("a longer label"), vec![1u8, 2, 3, 4, 5]);
+    let mut bytes = Vec::new();
+    serialize(&value, IoWriter::new(&mut bytes), DefaultOptions::new()).unwrap();
+
+    let reader = IoReader::new(BufReader::with_capacity(4, &bytes[..]));
+    let decoded: (String, Vec<u8>) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(decoded, value);
+
+    // A length that runs past the input is an EOF, without allocating that length up front
+    let reader = IoReader::new(BufReader::with_capacity(
+        4,
+        &[0xFCu8, 0, 0, 0, 0x40, b'a'][..],
+    ));
+    assert!(matches!(
+        deserialize::<String, _, _>(reader, DefaultOptions::new()),
+        Err(DeserializeError::Read(IoReadError::Io(e))) if e.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+}
@@ -0,0 +1,232 @@
+#![cfg(feature = "storage")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::storage::{Settings, Storage, StorageError, HEADER_LEN};
+use bincode_core::DefaultOptions;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Config<'a> {
+    name: &'a str,
+    interval_ms: u32,
+}
+
+/// NOR flash in RAM: writes can only clear bits, and only a whole page can be set again.
+struct Flash {
+    bytes: Vec<u8>,
+    page_size: usize,
+    write_size: usize,
+    erases: Vec<usize>,
+    /// The amount of bytes that are written before the power fails
+    power: Option<usize>,
+}
+
+impl Flash {
+    fn new(pages: usize, page_size: usize, write_size: usize) -> Self {
+        Flash {
+            bytes: vec![0xFF; pages * page_size],
+            page_size,
+            write_size,
+            erases: vec![0; pages],
+            power: None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct PowerLoss;
+
+impl Storage for Flash {
+    type Error = PowerLoss;
+
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    fn page_count(&self) -> usize {
+        self.erases.len()
+    }
+
+    fn write_size(&self) -> usize {
+        self.write_size
+    }
+
+    fn read(&mut self, offset: usize, buffer: &mut [u8]) -> Result<(), PowerLoss> {
+        buffer.copy_from_slice(&self.bytes[offset..offset + buffer.len()]);
+        Ok(())
+    }
+
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), PowerLoss> {
+        assert_eq!(offset % self.write_size, 0);
+        assert_eq!(data.len() % self.write_size, 0);
+        for (i, &byte) in data.iter().enumerate() {
+            if self.power == Some(0) {
+                return Err(PowerLoss);
+            }
+            self.power = self.power.map(|power| power - 1);
+            assert_eq!(self.bytes[offset + i], 0xFF, "write to a programmed byte");
+            self.bytes[offset + i] = byte;
+        }
+        Ok(())
+    }
+
+    fn erase_page(&mut self, page: usize) -> Result<(), PowerLoss> {
+        self.erases[page] += 1;
+        let start = page * self.page_size;
+        self.bytes[start..start + self.page_size].fill(0xFF);
+        // Without power, the page is still erased, but nothing is written after it
+        match self.power {
+            Some(0) => Err(PowerLoss),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn config(interval_ms: u32) -> Config<'static> {
+    Config {
+        name: "sensor",
+        interval_ms,
+    }
+}
+
+fn load(flash: Flash) -> (Option<u32>, Flash) {
+    let mut settings = Settings::new(flash).unwrap();
+    let mut buffer = [0u8; 64];
+    let config: Option<Config> = settings
+        .load_latest(&mut buffer, DefaultOptions::new())
+        .unwrap();
+    (
+        config.map(|config| config.interval_ms),
+        settings.into_inner(),
+    )
+}
+
+#[test]
+fn empty_storage() {
+    let (latest, flash) = load(Flash::new(2, 64, 1));
+    assert_eq!(latest, None);
+
+    // Garbage in a storage without records is erased before the first save
+    let mut flash = flash;
+    flash.bytes[3] = 0;
+    let mut settings = Settings::new(flash).unwrap();
+    let mut buffer = [0u8; 64];
+    settings
+        .save(&config(5), &mut buffer, DefaultOptions::new())
+        .unwrap();
+    let (latest, flash) = load(settings.into_inner());
+    assert_eq!(latest, Some(5));
+    assert_eq!(flash.erases, [1, 0]);
+}
+
+#[test]
+fn borrows_from_buffer() {
+    let mut settings = Settings::new(Flash::new(2, 64, 1)).unwrap();
+    let mut buffer = [0u8; 64];
+    settings
+        .save(&config(1), &mut buffer, DefaultOptions::new())
+        .unwrap();
+    let loaded: Config = settings
+        .load_latest(&mut buffer, DefaultOptions::new())
+        .unwrap()
+        .unwrap();
+    assert_eq!(loaded, config(1));
+}
+
+#[test]
+fn pages_wear_evenly() {
+    let mut settings = Settings::new(Flash::new(4, 64, 8)).unwrap();
+    let mut buffer = [0u8; 64];
+    for interval in 0..1000 {
+        settings
+            .save(&config(interval), &mut buffer, DefaultOptions::new())
+            .unwrap();
+    }
+    let (latest, flash) = load(settings.into_inner());
+    assert_eq!(latest, Some(999));
+    let min = *flash.erases.iter().min().unwrap();
+    let max = *flash.erases.iter().max().unwrap();
+    assert!(max - min <= 1, "{:?}", flash.erases);
+}
+
+#[test]
+fn power_loss() {
+    for power in 0..40 {
+        let mut settings = Settings::new(Flash::new(2, 64, 4)).unwrap();
+        let mut buffer = [0u8; 64];
+        for interval in 0..3 {
+            settings
+                .save(&config(interval), &mut buffer, DefaultOptions::new())
+                .unwrap();
+        }
+        let mut flash = settings.into_inner();
+        flash.power = Some(power);
+        let mut settings = Settings::new(flash).unwrap();
+        let result = settings.save(&config(3), &mut buffer, DefaultOptions::new());
+
+        let mut flash = settings.into_inner();
+        flash.power = None;
+        let (latest, flash) = load(flash);
+        match result {
+            Ok(()) => assert_eq!(latest, Some(3)),
+            // The record is complete if only its padding is missing
+            Err(StorageError::Storage(PowerLoss)) => {
+                assert!(latest == Some(2) || latest == Some(3))
+            }
+            Err(e) => panic!("{:?}", e),
+        }
+
+        // Saving works again after the power is back, with or without a reset
+        let mut settings = Settings::new(flash).unwrap();
+        settings
+            .save(&config(4), &mut buffer, DefaultOptions::new())
+            .unwrap();
+        assert_eq!(load(settings.into_inner()).0, Some(4));
+
+        let mut settings = Settings::new(Flash::new(2, 64, 4)).unwrap();
+        settings.storage_mut().power = Some(power);
+        let failed = settings.save(&config(5), &mut buffer, DefaultOptions::new());
+        settings.storage_mut().power = None;
+        settings
+            .save(&config(6), &mut buffer, DefaultOptions::new())
+            .unwrap();
+        assert_eq!(failed.is_ok(), power >= 20);
+        assert_eq!(load(settings.into_inner()).0, Some(6));
+
+        // A failed write in a new page must not erase the page with the newest record next
+        let mut settings = Settings::new(Flash::new(2, 64, 4)).unwrap();
+        for interval in 0..3 {
+            settings
+                .save(&config(interval), &mut buffer, DefaultOptions::new())
+                .unwrap();
+        }
+        settings.storage_mut().power = Some(power);
+        let failed = settings.save(&config(7), &mut buffer, DefaultOptions::new());
+        settings.storage_mut().power = Some(0);
+        assert!(settings
+            .save(&config(8), &mut buffer, DefaultOptions::new())
+            .is_err());
+        let mut flash = settings.into_inner();
+        flash.power = None;
+        let expected = if failed.is_ok() { 7 } else { 2 };
+        assert_eq!(load(flash).0, Some(expected));
+    }
+}
+
+#[test]
+fn too_large() {
+    let mut settings = Settings::new(Flash::new(2, 16, 4)).unwrap();
+    let mut buffer = [0u8; 64];
+    match settings.save(&config(1), &mut buffer, DefaultOptions::new()) {
+        Err(StorageError::TooLarge { len: 20, max: 16 }) => {}
+        x => panic!("Expected TooLarge, got {:?}", x),
+    }
+
+    let mut settings = Settings::new(Flash::new(2, 64, 4)).unwrap();
+    let mut buffer = [0u8; HEADER_LEN + 4];
+    match settings.save(&config(1), &mut buffer, DefaultOptions::new()) {
+        Err(StorageError::TooLarge { len: 20, max: 14 }) => {}
+        x => panic!("Expected TooLarge, got {:?}", x),
+    }
+}
@@ -0,0 +1,78 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize_iter, deserialize_with_remaining, serialize_all, BufferWriter, DefaultOptions,
+    DeserializeError,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+pub struct Sample {
+    timestamp: u32,
+    value: i16,
+}
+
+fn samples() -> [Sample; 4] {
+    core::array::from_fn(|i| Sample {
+        timestamp: 1000 * i as u32,
+        value: -(i as i16),
+    })
+}
+
+#[test]
+fn samples_round_trip() {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_all(samples(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+
+    let deserialized: Vec<Sample> = deserialize_iter(&buffer[..len], DefaultOptions::new())
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(&samples()[..], &deserialized[..]);
+}
+
+#[test]
+fn truncated_stream_stops_after_error() {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_all(samples(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+
+    let mut iter = deserialize_iter::<Sample, _, _>(&buffer[..len - 1], DefaultOptions::new());
+    for sample in &samples()[..3] {
+        assert_eq!(*sample, iter.next().unwrap().unwrap());
+    }
+    match iter.next() {
+        Some(Err(DeserializeError::UnexpectedEof { needed: 1 })) => {}
+        x => panic!("Expected UnexpectedEof, got {:?}", x),
+    }
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn retry_when_more_bytes_arrive() {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_all(samples(), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+
+    // Only wait for as many bytes as the deserializer asks for
+    let mut received = 1;
+    let value = loop {
+        match deserialize_with_remaining::<[Sample; 4], _>(
+            &buffer[..received],
+            DefaultOptions::new(),
+        ) {
+            Ok((value, _)) => break value,
+            Err(e) => received += e.bytes_needed().unwrap(),
+        }
+    };
+    assert_eq!(value, samples());
+    assert_eq!(received, len);
+
+    // A `Sample` with an invalid bool in its place is not a matter of missing bytes
+    let err = deserialize_with_remaining::<(bool, Sample), _>(&[2][..], DefaultOptions::new())
+        .unwrap_err();
+    assert_eq!(err.bytes_needed(), None);
+}
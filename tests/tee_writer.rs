@@ -0,0 +1,79 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    serialize, BufferWriter, BufferWriterError, CoreWrite, DefaultOptions, SerializeError,
+    TeeError, TeeWriter,
+};
+
+/// A writer that keeps the written bytes until it is flushed.
+#[derive(Default)]
+struct Flushing {
+    pending: Vec<u8>,
+    flushed: Vec<u8>,
+}
+
+impl CoreWrite for &mut Flushing {
+    type Error = ();
+
+    fn write(&mut self, val: u8) -> Result<(), ()> {
+        self.pending.push(val);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), ()> {
+        self.flushed.append(&mut self.pending);
+        Ok(())
+    }
+}
+
+#[test]
+fn both_writers_receive_all_bytes() {
+    let mut buffer = [0u8; 8];
+    let mut log = Flushing::default();
+    let mut writer = TeeWriter::new(BufferWriter::new(&mut buffer), &mut log);
+    serialize(&(1u8, "ab"), &mut writer, DefaultOptions::new()).unwrap();
+    assert_eq!(writer.first().written_buffer(), &[1, 2, b'a', b'b']);
+    assert_eq!(writer.second().flushed, [1, 2, b'a', b'b']);
+}
+
+#[test]
+fn flush_is_forwarded() {
+    let mut buffer = [0u8; 8];
+    let mut log = Flushing::default();
+    let options = DefaultOptions::new().with_manual_flush();
+    let mut writer = TeeWriter::new(BufferWriter::new(&mut buffer), &mut log);
+    serialize(&7u8, &mut writer, options).unwrap();
+    assert!(writer.second().flushed.is_empty());
+
+    writer.flush().unwrap();
+    assert_eq!(writer.second().flushed, [7]);
+    assert!(writer.second().pending.is_empty());
+}
+
+#[test]
+fn failing_writer_does_not_lose_bytes_in_the_other() {
+    let mut radio = [0u8; 2];
+    let mut log = [0u8; 8];
+    let mut writer = TeeWriter::new(BufferWriter::new(&mut radio), BufferWriter::new(&mut log));
+    match serialize(&(1u8, "ab"), &mut writer, DefaultOptions::new()) {
+        Err(SerializeError::Write(TeeError::First(BufferWriterError::BufferTooSmall))) => {}
+        x => panic!("Expected an error of the first writer, got {:?}", x),
+    }
+    let (radio, log) = writer.into_inner();
+    assert_eq!(radio.written_buffer(), &[1, 2]);
+    assert_eq!(log.written_buffer(), &[1, 2, b'a', b'b']);
+}
+
+#[test]
+fn both_errors_are_reported() {
+    let mut radio = [0u8; 1];
+    let mut log = [0u8; 1];
+    let mut writer = TeeWriter::new(BufferWriter::new(&mut radio), BufferWriter::new(&mut log));
+    assert_eq!(
+        writer.write_all(&[1, 2]),
+        Err(TeeError::Both(
+            BufferWriterError::BufferTooSmall,
+            BufferWriterError::BufferTooSmall
+        ))
+    );
+    assert_eq!(writer.write_all(&[1]), Ok(()),);
+}
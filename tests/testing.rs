@@ -0,0 +1,155 @@
+#![cfg(feature = "testing")]
+
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::testing::{verify_roundtrip, RoundtripError, RoundtripReport};
+use bincode_core::{DefaultOptions, DeserializeError};
+use core::cell::Cell;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Packet<'a> {
+    seq: u32,
+    payload: &'a str,
+    #[serde(skip)]
+    local: u8,
+}
+
+#[test]
+fn roundtrip_ok() {
+    let packet = Packet {
+        seq: 1000,
+        payload: "hello",
+        local: 0,
+    };
+    let mut buffer = [0u8; 32];
+    let report = verify_roundtrip(&packet, &mut buffer, DefaultOptions::new()).unwrap();
+    assert_eq!(
+        report,
+        RoundtripReport {
+            measured_len: 9,
+            written_len: 9,
+            read_len: 9,
+            equal: true,
+        }
+    );
+    assert!(report.is_ok());
+
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_big_endian();
+    let report = verify_roundtrip(&packet, &mut buffer, options).unwrap();
+    assert_eq!(report.written_len, 17);
+    assert!(report.is_ok());
+}
+
+#[test]
+fn value_not_equal() {
+    // A skipped field is not serialized, so it comes back as the default value
+    let packet = Packet {
+        seq: 1,
+        payload: "",
+        local: 3,
+    };
+    let mut buffer = [0u8; 32];
+    let report = verify_roundtrip(&packet, &mut buffer, DefaultOptions::new()).unwrap();
+    assert_eq!(report.measured_len, report.written_len);
+    assert_eq!(report.read_len, report.written_len);
+    assert!(!report.equal);
+    assert!(!report.is_ok());
+
+    let report = verify_roundtrip(&f32::NAN, &mut buffer, DefaultOptions::new()).unwrap();
+    assert!(!report.equal);
+}
+
+/// Serializes a different value every time it is serialized.
+#[derive(PartialEq, Debug)]
+struct Counter(Cell<u32>);
+
+impl Serialize for Counter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.0.get();
+        self.0.set(value + 1000);
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Counter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(|v| Counter(Cell::new(v)))
+    }
+}
+
+/// Serializes as a `u32`, but only reads a `u8` back.
+#[derive(PartialEq, Debug)]
+struct Truncated(u8);
+
+impl Serialize for Truncated {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        u32::from(self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Truncated {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Truncated)
+    }
+}
+
+#[test]
+fn length_mismatch() {
+    let mut buffer = [0u8; 32];
+    let options = DefaultOptions::new();
+
+    let report = verify_roundtrip(&Counter(Cell::new(0)), &mut buffer, options).unwrap();
+    assert_eq!(report.measured_len, 1);
+    assert_eq!(report.written_len, 3);
+    assert_eq!(report.read_len, 3);
+    assert!(!report.is_ok());
+
+    let options = options.with_fixint_encoding();
+    let report = verify_roundtrip(&Truncated(5), &mut buffer, options).unwrap();
+    assert_eq!(report.measured_len, 4);
+    assert_eq!(report.written_len, 4);
+    assert_eq!(report.read_len, 1);
+    assert!(report.equal);
+    assert!(!report.is_ok());
+}
+
+/// Serializes as a `u8`, but reads a `bool` back.
+#[derive(PartialEq, Debug)]
+struct NotABool(u8);
+
+impl Serialize for NotABool {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for NotABool {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        bool::deserialize(deserializer).map(|b| NotABool(b as u8))
+    }
+}
+
+#[test]
+fn errors() {
+    let mut buffer = [0u8; 4];
+    match verify_roundtrip(&"too long", &mut buffer, DefaultOptions::new()) {
+        Err(RoundtripError::BufferTooSmall { len: 4 }) => {}
+        x => panic!("Expected BufferTooSmall, got {:?}", x),
+    }
+
+    let options = DefaultOptions::new().with_limit(2);
+    match verify_roundtrip(&"abc", &mut buffer, options) {
+        Err(RoundtripError::Measure(_)) => {}
+        x => panic!("Expected Measure, got {:?}", x),
+    }
+
+    match verify_roundtrip(&NotABool(2), &mut buffer, DefaultOptions::new()) {
+        Err(RoundtripError::Deserialize(DeserializeError::InvalidBoolValue(2))) => {}
+        x => panic!("Expected Deserialize, got {:?}", x),
+    }
+}
@@ -0,0 +1,110 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::{
+    deserialize, serialize, BufferWriter, BufferedReader, DefaultOptions, DeserializeError,
+    StreamTransform, TransformReadError, TransformReader, TransformWriter,
+};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Message<'a> {
+    counter: u32,
+    name: &'a str,
+    values: [u16; 24],
+}
+
+/// A toy keystream, where every byte depends on its position in the stream.
+struct Keystream {
+    position: u32,
+}
+
+impl Keystream {
+    fn new() -> Self {
+        Keystream { position: 0 }
+    }
+}
+
+impl StreamTransform for Keystream {
+    fn apply(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= (self.position.wrapping_mul(31) >> 2) as u8 ^ 0xA5;
+            self.position += 1;
+        }
+    }
+}
+
+fn message() -> Message<'static> {
+    let mut values = [0u16; 24];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = i as u16 * 1000;
+    }
+    Message {
+        counter: 42,
+        name: "sensor node with a rather long descriptive name",
+        values,
+    }
+}
+
+fn write_plain(buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(&message(), &mut writer, DefaultOptions::new()).unwrap();
+    writer.written_len()
+}
+
+fn write_transformed(buffer: &mut [u8]) -> usize {
+    let mut writer = TransformWriter::new(BufferWriter::new(buffer), Keystream::new());
+    serialize(&message(), &mut writer, DefaultOptions::new()).unwrap();
+    writer.into_inner().written_len()
+}
+
+#[test]
+fn writer_applies_the_keystream_in_stream_order() {
+    let mut plain = [0u8; 128];
+    let len = write_plain(&mut plain);
+    let mut transformed = [0u8; 128];
+    assert_eq!(write_transformed(&mut transformed), len);
+    assert_ne!(plain[..len], transformed[..len]);
+
+    // Applying the keystream to the plain bytes in one go gives the same output
+    Keystream::new().apply(&mut plain[..len]);
+    assert_eq!(plain[..len], transformed[..len]);
+}
+
+#[test]
+fn round_trip_through_buffered_reader() {
+    let mut buffer = [0u8; 128];
+    let len = write_transformed(&mut buffer);
+
+    let mut scratch = [0u8; 64];
+    let reader = BufferedReader::new(
+        TransformReader::new(&buffer[..len], Keystream::new()),
+        &mut scratch,
+    );
+    let deserialized: Message = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(deserialized, message());
+}
+
+#[test]
+fn owned_values_without_buffered_reader() {
+    let mut buffer = [0u8; 16];
+    let mut writer = TransformWriter::new(BufferWriter::new(&mut buffer), Keystream::new());
+    serialize(&(1u8, 300u16, -5i32), &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.into_inner().written_len();
+
+    let reader = TransformReader::new(&buffer[..len], Keystream::new());
+    let value: (u8, u16, i32) = deserialize(reader, DefaultOptions::new()).unwrap();
+    assert_eq!(value, (1, 300, -5));
+}
+
+#[test]
+fn borrowed_values_need_buffered_reader() {
+    let mut buffer = [0u8; 128];
+    let len = write_transformed(&mut buffer);
+
+    let reader = TransformReader::new(&buffer[..len], Keystream::new());
+    let result = deserialize::<Message, _, _>(reader, DefaultOptions::new());
+    assert!(matches!(
+        result,
+        Err(DeserializeError::Read(TransformReadError::CannotBorrow))
+    ));
+}
@@ -0,0 +1,175 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, DeserializeError,
+};
+use serde::de::IgnoredAny;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Mode {
+    Idle,
+    Heat(u16),
+    Cycle(u8, u8),
+    Target { celsius: i16 },
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Status<'a> {
+    on: bool,
+    name: &'a str,
+    letter: char,
+    gain: f32,
+    offset: Option<i64>,
+    history: [u16; 3],
+    modes: (Mode, Mode, Mode, Mode),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct ConfigV1 {
+    id: u32,
+    mode: Mode,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct ConfigV2<'a> {
+    id: u32,
+    mode: Mode,
+    #[serde(default)]
+    label: Option<&'a str>,
+    #[serde(default)]
+    limits: (u8, Option<u16>),
+}
+
+fn options() -> impl Options + Copy {
+    DefaultOptions::new().with_type_tags()
+}
+
+fn write<T: serde::Serialize>(value: &T, buffer: &mut [u8]) -> usize {
+    let mut writer = BufferWriter::new(buffer);
+    serialize(value, &mut writer, options()).unwrap();
+    let len = writer.written_len();
+    assert_eq!(serialize_size(value, options()).unwrap(), len);
+    len
+}
+
+#[test]
+fn round_trip() {
+    let status = Status {
+        on: true,
+        name: "boiler",
+        letter: 'é',
+        gain: 1.5,
+        offset: Some(-3),
+        history: [1, 300, 7_000],
+        modes: (
+            Mode::Idle,
+            Mode::Heat(40),
+            Mode::Cycle(1, 2),
+            Mode::Target { celsius: -5 },
+        ),
+    };
+    let mut buffer = [0u8; 128];
+    let len = write(&status, &mut buffer);
+    let decoded: Status = deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(decoded, status);
+}
+
+#[test]
+fn tags_in_front_of_values() {
+    let mut buffer = [0u8; 16];
+    let len = write(&(1u8, "a", None::<u8>, Mode::Idle), &mut buffer);
+    assert_eq!(buffer[..len], [20, 4, 3, 1, 16, 1, b'a', 18, 22, 0, 1]);
+}
+
+#[test]
+fn older_readers_skip_new_fields() {
+    let config = ConfigV2 {
+        id: 9,
+        mode: Mode::Target { celsius: 21 },
+        label: Some("hall"),
+        limits: (3, Some(500)),
+    };
+    let mut buffer = [0u8; 64];
+    let len = write(&config, &mut buffer);
+    let decoded: ConfigV1 = deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(
+        decoded,
+        ConfigV1 {
+            id: 9,
+            mode: Mode::Target { celsius: 21 }
+        }
+    );
+}
+
+#[test]
+fn newer_readers_default_missing_fields() {
+    let config = ConfigV1 {
+        id: 9,
+        mode: Mode::Heat(60),
+    };
+    let mut buffer = [0u8; 64];
+    let len = write(&config, &mut buffer);
+    let decoded: ConfigV2 = deserialize(&buffer[..len], options()).unwrap();
+    assert_eq!(
+        decoded,
+        ConfigV2 {
+            id: 9,
+            mode: Mode::Heat(60),
+            label: None,
+            limits: (0, None),
+        }
+    );
+}
+
+#[test]
+fn ignored_any() {
+    let mut buffer = [0u8; 64];
+    let len = write(&(Mode::Cycle(1, 2), [Some('x'), None], 5u128), &mut buffer);
+    deserialize::<IgnoredAny, _, _>(&buffer[..len], options()).unwrap();
+}
+
+#[test]
+fn untagged_enum() {
+    #[derive(Deserialize, PartialEq, Debug)]
+    #[serde(untagged)]
+    enum Value {
+        Number(u32),
+        Text(String),
+    }
+
+    let mut buffer = [0u8; 16];
+    let len = write(&7u32, &mut buffer);
+    assert_eq!(
+        deserialize::<Value, _, _>(&buffer[..len], options()).unwrap(),
+        Value::Number(7)
+    );
+    let len = write(&"seven", &mut buffer);
+    assert_eq!(
+        deserialize::<Value, _, _>(&buffer[..len], options()).unwrap(),
+        Value::Text("seven".into())
+    );
+}
+
+#[test]
+fn mismatched_tag() {
+    let mut buffer = [0u8; 4];
+    let len = write(&7u8, &mut buffer);
+    match deserialize::<u32, _, _>(&buffer[..len], options()) {
+        Err(DeserializeError::UnexpectedTypeTag(3)) => {}
+        x => panic!("Expected UnexpectedTypeTag, got {:?}", x),
+    }
+    match deserialize::<IgnoredAny, _, _>(&[0u8][..], options()) {
+        Err(DeserializeError::UnexpectedTypeTag(0)) => {}
+        x => panic!("Expected UnexpectedTypeTag, got {:?}", x),
+    }
+}
+
+#[test]
+fn any_needs_type_tags() {
+    match deserialize::<IgnoredAny, _, _>(&[7u8][..], DefaultOptions::new()) {
+        Err(DeserializeError::Unsupported("deserialize_ignored_any")) => {}
+        x => panic!("Expected Unsupported, got {:?}", x),
+    }
+}
@@ -0,0 +1,148 @@
+use bincode_core::config::Options;
+use bincode_core::{deserialize, serialize, BufferWriter, DefaultOptions};
+use serde_derive::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum StateV2 {
+    Off,
+    On,
+    Unknown,
+    Blinking,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum State {
+    Off,
+    On,
+    Unknown,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Strict {
+    Off,
+    On,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Renamed {
+    Off,
+    On,
+    #[serde(rename = "Other")]
+    Unknown,
+}
+
+fn encode(value: &StateV2) -> ([u8; 8], usize) {
+    let mut buffer = [0u8; 8];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, DefaultOptions::new()).unwrap();
+    let len = writer.written_len();
+    (buffer, len)
+}
+
+#[test]
+fn unknown_variant_as_fallback() {
+    let options = DefaultOptions::new().with_unknown_variant_as("Unknown");
+    let (buffer, len) = encode(&StateV2::Blinking);
+    assert_eq!(
+        deserialize::<State, _, _>(&buffer[..len], options).unwrap(),
+        State::Unknown
+    );
+
+    let (buffer, len) = encode(&StateV2::On);
+    assert_eq!(
+        deserialize::<State, _, _>(&buffer[..len], options).unwrap(),
+        State::On
+    );
+}
+
+#[test]
+fn unknown_variants_are_rejected_by_default() {
+    let (buffer, len) = encode(&StateV2::Blinking);
+    assert!(deserialize::<State, _, _>(&buffer[..len], DefaultOptions::new()).is_err());
+
+    let options = DefaultOptions::new()
+        .with_unknown_variant_as("Unknown")
+        .reject_unknown_variants();
+    assert!(deserialize::<State, _, _>(&buffer[..len], options).is_err());
+}
+
+#[test]
+fn enums_without_the_fallback_still_reject() {
+    let options = DefaultOptions::new().with_unknown_variant_as("Unknown");
+    let (buffer, len) = encode(&StateV2::Blinking);
+    assert!(deserialize::<Strict, _, _>(&buffer[..len], options).is_err());
+
+    // The serialized name is matched, not the Rust name
+    assert!(deserialize::<Renamed, _, _>(&buffer[..len], options).is_err());
+    let options = DefaultOptions::new().with_unknown_variant_as("Other");
+    assert_eq!(
+        deserialize::<Renamed, _, _>(&buffer[..len], options).unwrap(),
+        Renamed::Unknown
+    );
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+enum ModeV2 {
+    A,
+    Unknown,
+    B(u16),
+    C(u16, u8),
+    D { level: u32 },
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+enum Mode {
+    A,
+    Unknown,
+}
+
+#[derive(Serialize)]
+struct NewMsg {
+    mode: ModeV2,
+    tail: u8,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct OldMsg {
+    mode: Mode,
+    tail: u8,
+}
+
+/// Serializes `mode` in the middle of a message, and deserializes it with an older enum.
+fn convert<O: Options + Copy>(mode: ModeV2, options: O) -> Option<OldMsg> {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&NewMsg { mode, tail: 5 }, &mut writer, options).unwrap();
+    let options = options.with_unknown_variant_as("Unknown");
+    deserialize(writer.written_buffer(), options).ok()
+}
+
+#[test]
+fn unknown_variant_content_is_skipped() {
+    let unknown = Some(OldMsg {
+        mode: Mode::Unknown,
+        tail: 5,
+    });
+    let tagged = DefaultOptions::new().with_type_tags();
+    let counted = DefaultOptions::new().with_counted_structs();
+    for mode in [ModeV2::B(300), ModeV2::C(300, 1), ModeV2::D { level: 7 }] {
+        assert_eq!(convert(mode, tagged), unknown);
+    }
+    for mode in [ModeV2::B(300), ModeV2::C(300, 1), ModeV2::D { level: 7 }] {
+        assert_eq!(convert(mode, counted), unknown);
+    }
+    let known = Some(OldMsg {
+        mode: Mode::A,
+        tail: 5,
+    });
+    assert_eq!(convert(ModeV2::A, tagged), known);
+    assert_eq!(convert(ModeV2::A, counted), known);
+    assert_eq!(convert(ModeV2::Unknown, counted), unknown);
+}
+
+#[test]
+fn unknown_variant_content_without_length_is_rejected() {
+    // Without type tags or counted structs, the content of the unknown variant cannot be skipped
+    assert_eq!(convert(ModeV2::B(300), DefaultOptions::new()), None);
+    assert_eq!(convert(ModeV2::C(300, 1), DefaultOptions::new()), None);
+}
@@ -0,0 +1,77 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, serialize_size, BufferWriter, DefaultOptions, SerializeError,
+};
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+
+fn write(value: &(&str, [u8; 2], usize), options: impl Options + Copy) -> Vec<u8> {
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(value, &mut writer, options).unwrap();
+    assert_eq!(
+        serialize_size(value, options).unwrap(),
+        writer.written_len()
+    );
+    let bytes = writer.written_buffer().to_vec();
+    let decoded: (&str, [u8; 2], usize) = deserialize(&bytes[..], options).unwrap();
+    assert_eq!(decoded, *value);
+    bytes
+}
+
+#[test]
+fn u32_lengths() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_u32_usize();
+    let bytes = write(&("ab", [1, 2], 7), options);
+    // The usize value is still a u64
+    assert_eq!(
+        bytes,
+        [2, 0, 0, 0, b'a', b'b', 1, 2, 7, 0, 0, 0, 0, 0, 0, 0]
+    );
+
+    let portable = DefaultOptions::new().with_fixint_encoding();
+    let bytes = write(&("ab", [1, 2], 7), portable);
+    assert_eq!(&bytes[..8], &[2, 0, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn native_lengths() {
+    let options = DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_native_usize();
+    let bytes = write(&("ab", [1, 2], 7), options);
+    let width = core::mem::size_of::<usize>().min(8);
+    assert_eq!(bytes.len(), width + 2 + 2 + 8);
+    assert_eq!(bytes[0], 2);
+}
+
+#[test]
+fn varint_lengths_are_unchanged() {
+    let value = ("ab", [1, 2], 7);
+    assert_eq!(
+        write(&value, DefaultOptions::new()),
+        write(&value, DefaultOptions::new().with_u32_usize())
+    );
+}
+
+/// A sequence that claims a length without holding the elements.
+struct Huge;
+
+impl Serialize for Huge {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_seq(Some(u32::MAX as usize + 1))?.end()
+    }
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn too_large_for_u32() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    match serialize(&Huge, &mut writer, DefaultOptions::new().with_u32_usize()) {
+        Err(SerializeError::LengthTooLarge(len)) => assert_eq!(len, 1 << 32),
+        x => panic!("Expected LengthTooLarge, got {:?}", x),
+    }
+    assert_eq!(writer.written_len(), 0);
+}
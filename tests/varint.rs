@@ -0,0 +1,114 @@
+use bincode_core::config::Options;
+use bincode_core::{
+    deserialize, serialize, BufferWriter, CoreRead, DefaultOptions, DeserializeError,
+};
+use std::cell::Cell;
+
+/// A reader that counts the calls that read bytes, like a driver where every call has a cost.
+struct Counting<'a, 'c> {
+    data: &'a [u8],
+    can_peek: bool,
+    calls: &'c Cell<usize>,
+}
+
+impl<'a> CoreRead<'a> for Counting<'a, '_> {
+    type Error = ();
+
+    fn fill(&mut self, buffer: &mut [u8]) -> Result<(), ()> {
+        self.calls.set(self.calls.get() + 1);
+        if buffer.len() > self.data.len() {
+            return Err(());
+        }
+        buffer.copy_from_slice(&self.data[..buffer.len()]);
+        self.data = &self.data[buffer.len()..];
+        Ok(())
+    }
+
+    fn forward_str<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+
+    fn forward_bytes<V>(&mut self, _len: usize, _visitor: V) -> Result<V::Value, ()>
+    where
+        V: serde::de::Visitor<'a>,
+    {
+        Err(())
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.data.len())
+    }
+
+    fn peek(&self, len: usize) -> Option<&[u8]> {
+        if self.can_peek {
+            self.data.get(..len)
+        } else {
+            None
+        }
+    }
+}
+
+const VALUES: (u8, u16, u32, u64, u128) = (7, 300, 70_000, 1 << 40, 1 << 70);
+
+fn check_calls<O: Options + Copy>(options: O) {
+    let mut buffer = [0u8; 64];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize(&VALUES, &mut writer, options).unwrap();
+
+    for &(can_peek, expected_calls) in &[(true, 5), (false, 9)] {
+        let calls = Cell::new(0);
+        let reader = Counting {
+            data: writer.written_buffer(),
+            can_peek,
+            calls: &calls,
+        };
+        let decoded: (u8, u16, u32, u64, u128) = deserialize(reader, options).unwrap();
+        assert_eq!(decoded, VALUES);
+        assert_eq!(calls.get(), expected_calls);
+    }
+}
+
+#[test]
+fn one_read_per_int_with_peek() {
+    check_calls(DefaultOptions::new());
+    check_calls(DefaultOptions::new().with_big_endian());
+    check_calls(DefaultOptions::new().with_canonical_varints());
+}
+
+#[test]
+fn truncated_int() {
+    let bytes = [252u8, 1, 2];
+    for &can_peek in &[true, false] {
+        let calls = Cell::new(0);
+        let reader = Counting {
+            data: &bytes,
+            can_peek,
+            calls: &calls,
+        };
+        match deserialize::<u32, _, _>(reader, DefaultOptions::new()) {
+            Err(DeserializeError::UnexpectedEof { needed: 2 }) => {}
+            x => panic!("Expected UnexpectedEof, got {:?}", x),
+        }
+    }
+}
+
+#[test]
+fn u128_marker_for_a_u64() {
+    let mut bytes = [0u8; 17];
+    bytes[0] = 254;
+    let calls = Cell::new(0);
+    let reader = Counting {
+        data: &bytes,
+        can_peek: true,
+        calls: &calls,
+    };
+    match deserialize::<u64, _, _>(reader, DefaultOptions::new()) {
+        Err(DeserializeError::InvalidValueRange) => {}
+        x => panic!("Expected InvalidValueRange, got {:?}", x),
+    }
+    // Only the marker is read
+    assert_eq!(calls.get(), 1);
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate serde_derive;
+
+use bincode_core::config::Options;
+use bincode_core::versioned::{deserialize_versioned, serialize_versioned};
+use bincode_core::{BufferWriter, DefaultOptions, DeserializeError, SerializeError};
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Telemetry {
+    temperature: i16,
+    humidity: u8,
+}
+
+#[test]
+fn version_tag_is_written_first() {
+    let value = Telemetry {
+        temperature: -20,
+        humidity: 40,
+    };
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    let options = DefaultOptions::new();
+    serialize_versioned(7, &value, &mut writer, options).unwrap();
+    assert_eq!(&[7, 39, 40], writer.written_buffer());
+
+    let deserialized: Telemetry = deserialize_versioned(7, &buffer[..3], options).unwrap();
+    assert_eq!(value, deserialized);
+}
+
+#[test]
+fn version_mismatch_is_rejected() {
+    let mut buffer = [0u8; 16];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_versioned(1, &5u8, &mut writer, DefaultOptions::new()).unwrap();
+
+    // Only the version tag is available, the payload is never read
+    match deserialize_versioned::<Telemetry, _, _>(2, &buffer[..1], DefaultOptions::new()) {
+        Err(DeserializeError::VersionMismatch {
+            expected: 2,
+            found: 1,
+        }) => {}
+        x => panic!("Expected VersionMismatch, got {:?}", x),
+    }
+}
+
+#[test]
+fn type_tags() {
+    let value = Telemetry {
+        temperature: -20,
+        humidity: 40,
+    };
+    let options = DefaultOptions::new().with_type_tags();
+    let mut buffer = [0u8; 32];
+    let mut writer = BufferWriter::new(&mut buffer);
+    serialize_versioned(7, &value, &mut writer, options).unwrap();
+    let len = writer.written_len();
+    let deserialized: Telemetry = deserialize_versioned(7, &buffer[..len], options).unwrap();
+    assert_eq!(value, deserialized);
+
+    // The limit applies to the tag and the value together
+    let mut writer = BufferWriter::new(&mut buffer);
+    let result = serialize_versioned(7, &value, &mut writer, options.with_limit(len as u64 - 1));
+    assert!(matches!(result, Err(SerializeError::LimitReached)));
+    assert_eq!(writer.written_len(), 0);
+}